@@ -5,6 +5,21 @@
 //! order (common typos, Levenshtein distance, git/cargo-specific, permission errors)
 //! and the first match is returned.
 //!
+//! ## Learning
+//!
+//! Whether the user ran a suggestion is fed back through
+//! [`CommandCorrector::record_outcome`]. Corrections are remembered by the
+//! words they change (`gti` -> `git`, `git psuh` -> `git push`); once one has
+//! been accepted [`AUTO_FIX_MIN_ACCEPTED`] times more than it was rejected,
+//! [`CommandCorrector::auto_fix`] applies it before the command runs, and a
+//! correction rejected more often than accepted is no longer offered. The
+//! memory and a per-command "never correct" list persist in
+//! `~/.elwood/autocorrect.json`.
+//!
+//! The user's shell aliases and functions (probed from the interactive shell,
+//! see [`definitions_probe_script`]) count as known commands: they are
+//! offered as typo matches and are never "corrected" themselves.
+//!
 //! ## Example
 //!
 //! ```
@@ -16,10 +31,14 @@
 //! }
 //! ```
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
 use std::time::Instant;
 
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
 // ─── Correction ─────────────────────────────────────────────────────────
 
 /// A suggested correction for a failed command.
@@ -33,6 +52,178 @@ pub struct Correction {
     pub confidence: f64,
     /// Human-readable explanation of the correction.
     pub explanation: String,
+    /// Name of the strategy that produced it (for acceptance tracking).
+    pub strategy: &'static str,
+}
+
+// ─── Correction memory ──────────────────────────────────────────────────
+
+/// Accepted corrections needed (beyond rejections) before one auto-fixes.
+pub const AUTO_FIX_MIN_ACCEPTED: u32 = 2;
+
+/// Rejections after which a correction that is rejected more often than
+/// accepted stops being suggested.
+const SUPPRESS_MIN_REJECTED: u32 = 2;
+
+/// Strategies whose corrections are remembered: the typo fixes, plus
+/// `learned` (which only replays those). Permission (`sudo`) and cargo
+/// fixes depend on the situation, so they are never learned.
+const REMEMBERED_STRATEGIES: &[&str] = &[
+    "common_typo",
+    "git_typo",
+    "git_did_you_mean",
+    "levenshtein",
+    "learned",
+];
+
+/// How the user responded to one remembered correction.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LearnedCorrection {
+    /// The words that replace the key (e.g. `git` for the key `gti`).
+    pub replacement: String,
+    /// Times the user ran the suggestion.
+    pub accepted: u32,
+    /// Times the user ran something else instead.
+    pub rejected: u32,
+}
+
+impl LearnedCorrection {
+    /// Whether this correction is applied without asking.
+    pub fn auto_fixes(&self) -> bool {
+        self.accepted >= self.rejected + AUTO_FIX_MIN_ACCEPTED
+    }
+
+    /// Whether the user has turned this correction down often enough to stop offering it.
+    pub fn suppressed(&self) -> bool {
+        self.rejected >= SUPPRESS_MIN_REJECTED && self.rejected > self.accepted
+    }
+}
+
+/// Corrections and opt-outs remembered across sessions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorrectionMemory {
+    /// Responses to corrections, keyed by the mistyped words (`gti`, `git psuh`).
+    pub corrections: BTreeMap<String, LearnedCorrection>,
+    /// Commands (first word) that are never corrected.
+    pub never: BTreeSet<String>,
+}
+
+impl CorrectionMemory {
+    /// Read a memory file; a missing file is an empty memory.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => {
+                serde_json::from_str(&json).with_context(|| format!("parsing {}", path.display()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("reading {}", path.display())),
+        }
+    }
+
+    /// Write the memory atomically (temp file + rename).
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("creating {}", parent.display()))?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).with_context(|| format!("writing {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("renaming to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// The remembered correction whose key starts `command`, with the key.
+    fn lookup(&self, command: &str) -> Option<(&str, &LearnedCorrection)> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        // Longest key first: `git psuh` is more specific than `git`
+        (1..=words.len().min(2)).rev().find_map(|n| {
+            let key = words[..n].join(" ");
+            self.corrections
+                .get_key_value(&key)
+                .map(|(k, v)| (k.as_str(), v))
+        })
+    }
+}
+
+/// Default location of the correction memory: `~/.elwood/autocorrect.json`.
+pub fn default_memory_path() -> PathBuf {
+    dirs_next::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".elwood")
+        .join("autocorrect.json")
+}
+
+/// The words a correction changes, as `(key, replacement)`.
+///
+/// A single-word fix keeps the words before it for context (`git psuh` ->
+/// `git push`); anything else (`sudo` prefixes, flag rewrites) is remembered
+/// as the whole command line.
+fn correction_key(original: &str, suggested: &str) -> (String, String) {
+    let from: Vec<&str> = original.split_whitespace().collect();
+    let to: Vec<&str> = suggested.split_whitespace().collect();
+    if from.len() == to.len() {
+        let mut diffs = (0..from.len()).filter(|&i| from[i] != to[i]);
+        if let (Some(i), None) = (diffs.next(), diffs.next()) {
+            if i < 2 {
+                return (from[..=i].join(" "), to[..=i].join(" "));
+            }
+        }
+    }
+    (from.join(" "), to.join(" "))
+}
+
+/// `command` with its leading `key` words replaced by `replacement`.
+fn replace_key(command: &str, key: &str, replacement: &str) -> String {
+    let rest: Vec<&str> = command
+        .split_whitespace()
+        .skip(key.split_whitespace().count())
+        .collect();
+    if rest.is_empty() {
+        replacement.to_string()
+    } else {
+        format!("{replacement} {}", rest.join(" "))
+    }
+}
+
+// ─── Shell definitions probe ────────────────────────────────────────────
+
+/// Marker echoed before the probe's output, so rc-file noise is skipped.
+const PROBE_BEGIN: &str = "__elwood_definitions_begin__";
+/// Marker echoed after the probe's output.
+const PROBE_END: &str = "__elwood_definitions_end__";
+
+/// Script that lists the alias and function names of an interactive `shell`,
+/// or `None` for shells we don't know how to ask.
+pub fn definitions_probe_script(shell: &str) -> Option<String> {
+    let name = Path::new(shell).file_name()?.to_str()?;
+    let list = match name {
+        "bash" => "compgen -a; compgen -A function",
+        "zsh" => "print -rl -- ${(k)aliases} ${(k)functions}",
+        "fish" => "functions -n; abbr --list",
+        _ => return None,
+    };
+    Some(format!("echo {PROBE_BEGIN}; {list}; echo {PROBE_END}"))
+}
+
+/// Parse the names printed between the probe markers.
+///
+/// Private helpers (leading `_`, e.g. completion functions) are skipped.
+pub fn parse_definitions_probe(lines: &[String]) -> Vec<String> {
+    let mut names: Vec<String> = lines
+        .iter()
+        .skip_while(|l| l.trim() != PROBE_BEGIN)
+        .skip(1)
+        .take_while(|l| l.trim() != PROBE_END)
+        .flat_map(|l| l.split(','))
+        .map(str::trim)
+        .filter(|n| !n.is_empty() && !n.starts_with('_') && !n.contains(char::is_whitespace))
+        .map(String::from)
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
 }
 
 // ─── Levenshtein distance ───────────────────────────────────────────────
@@ -249,6 +440,8 @@ const CACHE_TTL_SECS: u64 = 300; // 5 minutes
 
 struct KnownCommandsCache {
     commands: Vec<String>,
+    /// Aliases and functions defined by the user's shell (sorted).
+    shell_definitions: Vec<String>,
     last_refresh: Instant,
 }
 
@@ -256,6 +449,7 @@ impl KnownCommandsCache {
     fn new() -> Self {
         let mut cache = Self {
             commands: Vec::new(),
+            shell_definitions: Vec::new(),
             last_refresh: Instant::now(),
         };
         cache.refresh();
@@ -264,6 +458,7 @@ impl KnownCommandsCache {
 
     fn refresh(&mut self) {
         let mut commands: Vec<String> = SHELL_BUILTINS.iter().map(|s| (*s).to_string()).collect();
+        commands.extend(self.shell_definitions.iter().cloned());
 
         if let Ok(path_var) = std::env::var("PATH") {
             for dir in path_var.split(':') {
@@ -291,6 +486,21 @@ impl KnownCommandsCache {
         self.last_refresh.elapsed().as_secs() >= CACHE_TTL_SECS
     }
 
+    fn contains(&mut self, name: &str) -> bool {
+        if self.is_stale() {
+            self.refresh();
+        }
+        self.commands
+            .binary_search_by(|c| c.as_str().cmp(name))
+            .is_ok()
+    }
+
+    fn is_shell_definition(&self, name: &str) -> bool {
+        self.shell_definitions
+            .binary_search_by(|d| d.as_str().cmp(name))
+            .is_ok()
+    }
+
     fn find_closest(&mut self, typo: &str, max_results: usize) -> Vec<(String, usize)> {
         if self.is_stale() {
             self.refresh();
@@ -329,8 +539,15 @@ pub struct CommandCorrector {
     cache: Mutex<KnownCommandsCache>,
     /// Track acceptance rates: (accepted, total) per strategy.
     acceptance: Mutex<HashMap<String, (u32, u32)>>,
+    /// Remembered responses and the never-correct list.
+    memory: Mutex<CorrectionMemory>,
+    /// Where `memory` is saved after each change, if anywhere.
+    memory_path: Option<PathBuf>,
 }
 
+/// Strategy feedback needed before its acceptance rate scales confidence.
+const MIN_STRATEGY_FEEDBACK: u32 = 5;
+
 impl CommandCorrector {
     /// Create a new corrector with a fresh known-commands cache.
     pub fn new() -> Self {
@@ -338,9 +555,95 @@ impl CommandCorrector {
             enabled: true,
             cache: Mutex::new(KnownCommandsCache::new()),
             acceptance: Mutex::new(HashMap::new()),
+            memory: Mutex::new(CorrectionMemory::default()),
+            memory_path: None,
         }
     }
 
+    /// Load the correction memory from `path` and save back to it on every
+    /// change. Returns the load error, if any, alongside a corrector that
+    /// starts with an empty memory.
+    pub fn with_memory_path(mut self, path: PathBuf) -> (Self, Option<String>) {
+        let error = match CorrectionMemory::load(&path) {
+            Ok(memory) => {
+                self.memory = Mutex::new(memory);
+                None
+            }
+            Err(e) => Some(format!("{e:#}")),
+        };
+        self.memory_path = Some(path);
+        (self, error)
+    }
+
+    /// A snapshot of the remembered corrections and never-correct list.
+    pub fn memory(&self) -> CorrectionMemory {
+        self.memory.lock().map(|m| m.clone()).unwrap_or_default()
+    }
+
+    fn update_memory(&self, f: impl FnOnce(&mut CorrectionMemory)) -> anyhow::Result<()> {
+        let mut memory = self
+            .memory
+            .lock()
+            .map_err(|_| anyhow::anyhow!("correction memory lock poisoned"))?;
+        f(&mut memory);
+        match &self.memory_path {
+            Some(path) => memory.save(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Replace the user's shell alias and function names (see
+    /// [`parse_definitions_probe`]).
+    pub fn set_shell_definitions(&self, mut names: Vec<String>) {
+        names.sort_unstable();
+        names.dedup();
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.shell_definitions = names;
+            cache.refresh();
+        }
+    }
+
+    /// Number of known shell aliases and functions.
+    pub fn shell_definition_count(&self) -> usize {
+        self.cache
+            .lock()
+            .map(|c| c.shell_definitions.len())
+            .unwrap_or(0)
+    }
+
+    /// Never correct commands starting with `command` (its first word).
+    pub fn never_correct(&self, command: &str) -> anyhow::Result<()> {
+        let name = command.split_whitespace().next().unwrap_or("").to_string();
+        if name.is_empty() {
+            anyhow::bail!("no command given");
+        }
+        self.update_memory(|m| {
+            m.never.insert(name);
+        })
+    }
+
+    /// Remove `command` from the never-correct list. Returns whether it was listed.
+    pub fn allow_correct(&self, command: &str) -> anyhow::Result<bool> {
+        let mut removed = false;
+        self.update_memory(|m| removed = m.never.remove(command.trim()))?;
+        Ok(removed)
+    }
+
+    /// Forget every remembered correction (the never-correct list is kept).
+    pub fn forget_corrections(&self) -> anyhow::Result<()> {
+        self.update_memory(|m| m.corrections.clear())
+    }
+
+    fn is_never_corrected(&self, command: &str) -> bool {
+        let Some(name) = command.split_whitespace().next() else {
+            return false;
+        };
+        self.memory
+            .lock()
+            .map(|m| m.never.contains(name))
+            .unwrap_or(false)
+    }
+
     /// Whether auto-correction is currently enabled.
     pub fn is_enabled(&self) -> bool {
         self.enabled
@@ -351,6 +654,39 @@ impl CommandCorrector {
         self.enabled = enabled;
     }
 
+    /// Human-readable summary for `/autocorrect`.
+    pub fn format_status(&self) -> String {
+        let memory = self.memory();
+        let mut out = format!(
+            "Autocorrect: {}\nShell aliases/functions known: {}\n",
+            if self.enabled { "on" } else { "off" },
+            self.shell_definition_count()
+        );
+        if memory.corrections.is_empty() {
+            out.push_str("No learned corrections yet.\n");
+        } else {
+            out.push_str("Learned corrections:\n");
+            for (key, learned) in &memory.corrections {
+                let state = if learned.auto_fixes() {
+                    " · auto-fix"
+                } else if learned.suppressed() {
+                    " · not offered"
+                } else {
+                    ""
+                };
+                out.push_str(&format!(
+                    "  {key} -> {}  (accepted {}, rejected {}{state})\n",
+                    learned.replacement, learned.accepted, learned.rejected
+                ));
+            }
+        }
+        if !memory.never.is_empty() {
+            let never: Vec<&str> = memory.never.iter().map(String::as_str).collect();
+            out.push_str(&format!("Never corrected: {}\n", never.join(", ")));
+        }
+        out
+    }
+
     /// Force a refresh of the known-commands cache (called by `/rehash`).
     pub fn rehash(&self) {
         if let Ok(mut cache) = self.cache.lock() {
@@ -369,17 +705,126 @@ impl CommandCorrector {
         }
     }
 
+    /// Record whether the user ran a suggested correction, updating the
+    /// strategy's acceptance rate and, for typo fixes, the remembered
+    /// correction.
+    pub fn record_outcome(&self, correction: &Correction, accepted: bool) -> anyhow::Result<()> {
+        self.record_feedback(correction.strategy, accepted);
+        if !REMEMBERED_STRATEGIES.contains(&correction.strategy) {
+            return Ok(());
+        }
+        let (key, replacement) = correction_key(&correction.original, &correction.suggested);
+        self.update_memory(|m| {
+            let entry = m.corrections.entry(key).or_default();
+            if entry.replacement != replacement {
+                // A different fix for the same words starts over
+                *entry = LearnedCorrection {
+                    replacement,
+                    ..Default::default()
+                };
+            }
+            if accepted {
+                entry.accepted += 1;
+            } else {
+                entry.rejected += 1;
+            }
+        })
+    }
+
+    /// A remembered correction the user has accepted often enough to apply
+    /// to `command` before it runs.
+    ///
+    /// `sudo` rewrites are never applied, nor is a key that has since become
+    /// a real command (a newly installed `gti` runs as typed).
+    pub fn auto_fix(&self, command: &str) -> Option<Correction> {
+        if !self.enabled || self.is_never_corrected(command) {
+            return None;
+        }
+        let memory = self.memory.lock().ok()?;
+        let (key, learned) = memory.lookup(command)?;
+        if !learned.auto_fixes() {
+            return None;
+        }
+        let typed = key.split_whitespace().next()?;
+        let fixed = learned.replacement.split_whitespace().next()?;
+        if fixed == "sudo" && typed != "sudo" {
+            return None;
+        }
+        if typed != fixed && self.cache.lock().ok()?.contains(typed) {
+            return None;
+        }
+        Some(Correction {
+            original: command.to_string(),
+            suggested: replace_key(command, key, &learned.replacement),
+            confidence: 1.0,
+            explanation: format!(
+                "Learned: `{key}` -> `{}` (accepted {} times)",
+                learned.replacement, learned.accepted
+            ),
+            strategy: "learned",
+        })
+    }
+
+    /// Previously accepted correction for `command`, suggested ahead of the
+    /// built-in strategies.
+    fn try_learned(&self, command: &str) -> Option<Correction> {
+        let memory = self.memory.lock().ok()?;
+        let (key, learned) = memory.lookup(command)?;
+        if learned.accepted <= learned.rejected {
+            return None;
+        }
+        Some(Correction {
+            original: command.to_string(),
+            suggested: replace_key(command, key, &learned.replacement),
+            confidence: 0.97,
+            explanation: format!("You used `{}` for `{key}` before", learned.replacement),
+            strategy: "learned",
+        })
+    }
+
+    /// Whether the user keeps turning down this exact fix.
+    fn is_suppressed(&self, correction: &Correction) -> bool {
+        let (key, replacement) = correction_key(&correction.original, &correction.suggested);
+        self.memory
+            .lock()
+            .map(|m| {
+                m.corrections
+                    .get(&key)
+                    .is_some_and(|l| l.replacement == replacement && l.suppressed())
+            })
+            .unwrap_or(false)
+    }
+
+    /// Scale a strategy's confidence by how often its suggestions are run.
+    fn weigh_by_feedback(&self, mut correction: Correction) -> Correction {
+        let feedback = self
+            .acceptance
+            .lock()
+            .ok()
+            .and_then(|acc| acc.get(correction.strategy).copied());
+        if let Some((accepted, total)) = feedback {
+            if total >= MIN_STRATEGY_FEEDBACK {
+                let rate = f64::from(accepted) / f64::from(total);
+                correction.confidence *= 0.5 + rate / 2.0;
+            }
+        }
+        correction
+    }
+
     /// Suggest a correction for a failed command.
     ///
     /// Strategies are tried in order:
-    /// 1. Common typo map (exact match)
-    /// 2. Git subcommand corrections
-    /// 3. Git "did you mean" stderr parsing
-    /// 4. Permission error suggestions
-    /// 5. Cargo "did you mean" parsing
-    /// 6. Levenshtein distance against known commands
+    /// 1. Corrections the user accepted before
+    /// 2. Common typo map (exact match)
+    /// 3. Git subcommand corrections
+    /// 4. Git "did you mean" stderr parsing
+    /// 5. Permission error suggestions
+    /// 6. Cargo "did you mean" parsing
+    /// 7. Levenshtein distance against known commands and shell definitions
     ///
-    /// Returns the first match, or `None` if no correction is found.
+    /// Returns the first match, or `None` if no correction is found, the
+    /// command is on the never-correct list, or the user keeps rejecting
+    /// the match.
     pub fn suggest_correction(
         &self,
         command: &str,
@@ -391,10 +836,14 @@ impl CommandCorrector {
         }
 
         // Only act on actual failures
-        if exit_code == 0 {
+        if exit_code == 0 || self.is_never_corrected(command) {
             return None;
         }
 
+        if let Some(learned) = self.try_learned(command) {
+            return Some(learned);
+        }
+
         // Try strategies in priority order
         self.try_common_typo(command)
             .or_else(|| self.try_git_correction(command, stderr))
@@ -402,6 +851,8 @@ impl CommandCorrector {
             .or_else(|| self.try_permission_error(command, stderr))
             .or_else(|| self.try_cargo_correction(command, stderr))
             .or_else(|| self.try_levenshtein_correction(command, stderr))
+            .filter(|c| !self.is_suppressed(c))
+            .map(|c| self.weigh_by_feedback(c))
     }
 
     // ── Strategy 1: Common typo map ─────────────────────────────────────
@@ -410,6 +861,15 @@ impl CommandCorrector {
         let parts: Vec<&str> = command.split_whitespace().collect();
         let first_word = parts.first()?;
 
+        // A user alias or function with a typo-like name is intentional
+        if self
+            .cache
+            .lock()
+            .is_ok_and(|c| c.is_shell_definition(first_word))
+        {
+            return None;
+        }
+
         let typos = common_typos();
         if let Some(&corrected) = typos.get(*first_word) {
             let rest: String = parts[1..].join(" ");
@@ -423,6 +883,7 @@ impl CommandCorrector {
                 suggested,
                 confidence: 0.95,
                 explanation: format!("Common typo: `{first_word}` -> `{corrected}`"),
+                strategy: "common_typo",
             });
         }
 
@@ -451,6 +912,7 @@ impl CommandCorrector {
                 suggested,
                 confidence: 0.95,
                 explanation: format!("Git typo: `{sub}` -> `{corrected}`"),
+                strategy: "git_typo",
             });
         }
 
@@ -484,6 +946,7 @@ impl CommandCorrector {
                         suggested,
                         confidence: 0.9,
                         explanation: format!("Git suggested: `{trimmed}`"),
+                        strategy: "git_did_you_mean",
                     });
                 }
             }
@@ -506,6 +969,7 @@ impl CommandCorrector {
                         suggested,
                         confidence: 0.85,
                         explanation: format!("Git suggested: `{trimmed}`"),
+                        strategy: "git_did_you_mean",
                     });
                 }
             }
@@ -538,6 +1002,7 @@ impl CommandCorrector {
                 suggested: format!("{command} --user"),
                 confidence: 0.7,
                 explanation: "Permission denied — try installing for current user".to_string(),
+                strategy: "permission",
             });
         }
 
@@ -547,6 +1012,7 @@ impl CommandCorrector {
             confidence: 0.6,
             explanation: "Permission denied — may require elevated privileges (use with caution)"
                 .to_string(),
+            strategy: "permission",
         })
     }
 
@@ -580,6 +1046,7 @@ impl CommandCorrector {
                             suggested,
                             confidence: 0.85,
                             explanation: format!("Cargo suggested: `{suggested_name}`"),
+                            strategy: "cargo",
                         });
                     }
                 }
@@ -594,6 +1061,7 @@ impl CommandCorrector {
                 confidence: 0.5,
                 explanation: "No Cargo.toml found — check working directory or use --manifest-path"
                     .to_string(),
+                strategy: "cargo",
             });
         }
 
@@ -643,6 +1111,7 @@ impl CommandCorrector {
             suggested,
             confidence,
             explanation: format!("Similar command: `{best_cmd}` (edit distance {best_dist})"),
+            strategy: "levenshtein",
         })
     }
 }
@@ -1110,4 +1579,235 @@ mod tests {
         let fix = c.try_permission_error("ls foo", "No such file or directory");
         assert!(fix.is_none());
     }
+
+    // ── Learning tests ──────────────────────────────────────────────────
+
+    fn temp_memory_path(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("elwood-autocorrect-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("autocorrect.json")
+    }
+
+    #[test]
+    fn test_correction_key_single_word() {
+        assert_eq!(
+            correction_key("gti status", "git status"),
+            ("gti".to_string(), "git".to_string())
+        );
+        assert_eq!(
+            correction_key("git psuh origin main", "git push origin main"),
+            ("git psuh".to_string(), "git push".to_string())
+        );
+        // Prefixes and rewrites remember the whole line
+        assert_eq!(
+            correction_key("apt install foo", "sudo apt install foo"),
+            (
+                "apt install foo".to_string(),
+                "sudo apt install foo".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_replace_key_keeps_arguments() {
+        assert_eq!(replace_key("gti log -n 3", "gti", "git"), "git log -n 3");
+        assert_eq!(replace_key("git psuh", "git psuh", "git push"), "git push");
+    }
+
+    #[test]
+    fn test_accepted_correction_auto_fixes() {
+        let c = CommandCorrector::new();
+        let fix = c
+            .suggest_correction("gti status", "command not found: gti", 127)
+            .unwrap();
+        assert!(c.auto_fix("gti log").is_none());
+
+        c.record_outcome(&fix, true).unwrap();
+        assert!(c.auto_fix("gti log").is_none());
+        c.record_outcome(&fix, true).unwrap();
+
+        let auto = c.auto_fix("gti log").expect("learned auto-fix");
+        assert_eq!(auto.suggested, "git log");
+        assert_eq!(auto.strategy, "learned");
+        assert!(c.auto_fix("git log").is_none());
+    }
+
+    #[test]
+    fn test_learned_correction_suggested_first() {
+        let c = CommandCorrector::new();
+        let fix = Correction {
+            original: "dkcr ps".to_string(),
+            suggested: "docker ps".to_string(),
+            confidence: 0.8,
+            explanation: String::new(),
+            strategy: "levenshtein",
+        };
+        c.record_outcome(&fix, true).unwrap();
+        let learned = c.suggest_correction("dkcr images", "", 127).unwrap();
+        assert_eq!(learned.strategy, "learned");
+        assert_eq!(learned.suggested, "docker images");
+    }
+
+    #[test]
+    fn test_permission_fix_not_remembered() {
+        let c = CommandCorrector::new();
+        let fix = c
+            .try_permission_error("apt install foo", "Permission denied")
+            .unwrap();
+        c.record_outcome(&fix, true).unwrap();
+        c.record_outcome(&fix, true).unwrap();
+        assert!(c.memory().corrections.is_empty());
+        assert!(c.auto_fix("apt install foo").is_none());
+    }
+
+    #[test]
+    fn test_known_command_not_auto_fixed() {
+        let c = CommandCorrector::new();
+        let fix = Correction {
+            original: "cd src".to_string(),
+            suggested: "cdx src".to_string(),
+            confidence: 0.8,
+            explanation: String::new(),
+            strategy: "levenshtein",
+        };
+        c.record_outcome(&fix, true).unwrap();
+        c.record_outcome(&fix, true).unwrap();
+        assert!(c.memory().corrections["cd"].auto_fixes());
+        assert!(c.auto_fix("cd src").is_none());
+    }
+
+    #[test]
+    fn test_rejected_correction_suppressed() {
+        let c = CommandCorrector::new();
+        let fix = c.suggest_correction("git psuh", "", 1).unwrap();
+        c.record_outcome(&fix, false).unwrap();
+        assert!(c.suggest_correction("git psuh", "", 1).is_some());
+        c.record_outcome(&fix, false).unwrap();
+        assert!(c.suggest_correction("git psuh", "", 1).is_none());
+        assert!(c.auto_fix("git psuh").is_none());
+    }
+
+    #[test]
+    fn test_never_correct_list() {
+        let c = CommandCorrector::new();
+        c.never_correct("gti").unwrap();
+        assert!(c
+            .suggest_correction("gti status", "command not found: gti", 127)
+            .is_none());
+        assert!(c.memory().never.contains("gti"));
+
+        assert!(c.allow_correct("gti").unwrap());
+        assert!(!c.allow_correct("gti").unwrap());
+        assert!(c
+            .suggest_correction("gti status", "command not found: gti", 127)
+            .is_some());
+        assert!(c.never_correct("  ").is_err());
+    }
+
+    #[test]
+    fn test_memory_persists() {
+        let path = temp_memory_path("persist");
+        let (c, err) = CommandCorrector::new().with_memory_path(path.clone());
+        assert!(err.is_none());
+        let fix = c.suggest_correction("gti status", "", 127).unwrap();
+        c.record_outcome(&fix, true).unwrap();
+        c.record_outcome(&fix, true).unwrap();
+        c.never_correct("sl").unwrap();
+
+        let (reloaded, err) = CommandCorrector::new().with_memory_path(path.clone());
+        assert!(err.is_none());
+        assert_eq!(reloaded.memory(), c.memory());
+        assert!(reloaded.auto_fix("gti diff").is_some());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_format_status() {
+        let c = CommandCorrector::new();
+        assert!(c.format_status().contains("No learned corrections"));
+        let fix = c.suggest_correction("gti status", "", 127).unwrap();
+        c.record_outcome(&fix, true).unwrap();
+        c.record_outcome(&fix, true).unwrap();
+        c.never_correct("sl").unwrap();
+        let status = c.format_status();
+        assert!(status.contains("gti -> git"));
+        assert!(status.contains("auto-fix"));
+        assert!(status.contains("Never corrected: sl"));
+    }
+
+    #[test]
+    fn test_corrupt_memory_reports_error() {
+        let path = temp_memory_path("corrupt");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "not json").unwrap();
+        let (c, err) = CommandCorrector::new().with_memory_path(path.clone());
+        assert!(err.is_some());
+        assert_eq!(c.memory(), CorrectionMemory::default());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_strategy_feedback_scales_confidence() {
+        let c = CommandCorrector::new();
+        let before = c.suggest_correction("git psuh", "", 1).unwrap().confidence;
+        for _ in 0..MIN_STRATEGY_FEEDBACK {
+            c.record_feedback("git_typo", false);
+        }
+        let after = c.suggest_correction("git psuh", "", 1).unwrap().confidence;
+        assert!(after < before, "{after} should be below {before}");
+    }
+
+    // ── Shell definition tests ──────────────────────────────────────────
+
+    #[test]
+    fn test_probe_script_per_shell() {
+        assert!(definitions_probe_script("/bin/bash")
+            .unwrap()
+            .contains("compgen -a"));
+        assert!(definitions_probe_script("/usr/bin/zsh")
+            .unwrap()
+            .contains("aliases"));
+        assert!(definitions_probe_script("fish")
+            .unwrap()
+            .contains("functions -n"));
+        assert!(definitions_probe_script("/bin/tcsh").is_none());
+    }
+
+    #[test]
+    fn test_parse_definitions_probe() {
+        let lines: Vec<String> = [
+            "Welcome to the machine",
+            PROBE_BEGIN,
+            "ll",
+            "gst",
+            "_git_helper",
+            "mkcd, ll",
+            PROBE_END,
+            "goodbye",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        assert_eq!(parse_definitions_probe(&lines), vec!["gst", "ll", "mkcd"]);
+        assert!(parse_definitions_probe(&lines[..1]).is_empty());
+    }
+
+    #[test]
+    fn test_shell_definitions_are_typo_targets() {
+        let c = CommandCorrector::new();
+        c.set_shell_definitions(vec!["deploy-staging".to_string()]);
+        assert_eq!(c.shell_definition_count(), 1);
+        let fix = c
+            .suggest_correction("deploy-stagign", "command not found: deploy-stagign", 127)
+            .unwrap();
+        assert_eq!(fix.suggested, "deploy-staging");
+    }
+
+    #[test]
+    fn test_shell_definitions_not_corrected() {
+        let c = CommandCorrector::new();
+        c.set_shell_definitions(vec!["gti".to_string()]);
+        assert!(c.try_common_typo("gti status").is_none());
+    }
 }
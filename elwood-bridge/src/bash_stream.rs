@@ -0,0 +1,598 @@
+//! Streaming, cancellable command execution for `NativeBashTool`.
+//!
+//! The pieces here carry no mux dependency so they can be unit tested:
+//!
+//! - [`HeadTailBuffer`] keeps the first and last N lines of a stream and
+//!   records how much was dropped in between
+//! - [`run_subprocess`] runs `$SHELL -c <command>` with piped output, hands
+//!   every chunk to a callback as it arrives, and kills the process group on
+//!   cancellation or timeout
+//! - [`ScreenTail`] turns successive pane snapshots into "new lines" so a
+//!   command running in a visible pane can be streamed the same way
+//! - [`wrap_command`] / [`parse_sentinel`] append an exit-code marker to a
+//!   command typed into a shell pane, so completion doesn't rely on guessing
+//!   what the prompt looks like
+
+use std::collections::VecDeque;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Prefix of the completion marker printed after a command in a pane.
+const SENTINEL_PREFIX: &str = "__ELWOOD_DONE_";
+
+/// How often the subprocess loop checks for cancellation and timeout.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Time between SIGTERM and SIGKILL when stopping a process group.
+const KILL_GRACE: Duration = Duration::from_secs(2);
+
+/// Which stream a chunk of output came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// A piece of output produced while a command is still running.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BashChunk {
+    pub stream: OutputStream,
+    pub text: String,
+}
+
+impl BashChunk {
+    pub fn is_stderr(&self) -> bool {
+        self.stream == OutputStream::Stderr
+    }
+}
+
+// ─── Head + tail truncation ──────────────────────────────────────────────
+
+/// Line buffer that keeps the first `head` and last `tail` lines.
+///
+/// Lines that fall between the two are counted but not stored, so memory
+/// stays bounded no matter how much a build prints.
+#[derive(Debug)]
+pub struct HeadTailBuffer {
+    head_limit: usize,
+    tail_limit: usize,
+    head: Vec<String>,
+    tail: VecDeque<String>,
+    partial: String,
+    omitted_lines: usize,
+    omitted_bytes: usize,
+}
+
+impl HeadTailBuffer {
+    pub fn new(head_limit: usize, tail_limit: usize) -> Self {
+        Self {
+            head_limit,
+            tail_limit,
+            head: Vec::new(),
+            tail: VecDeque::new(),
+            partial: String::new(),
+            omitted_lines: 0,
+            omitted_bytes: 0,
+        }
+    }
+
+    /// Split `max_lines` evenly between head and tail.
+    pub fn with_max_lines(max_lines: usize) -> Self {
+        let head = max_lines / 2;
+        Self::new(head, max_lines - head)
+    }
+
+    /// Append raw output. Text after the last newline is held until the
+    /// line is completed (or [`finish`](Self::finish) is called).
+    pub fn push(&mut self, text: &str) {
+        self.partial.push_str(text);
+        while let Some(pos) = self.partial.find('\n') {
+            let mut line: String = self.partial.drain(..=pos).collect();
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+            self.push_line(line);
+        }
+    }
+
+    fn push_line(&mut self, line: String) {
+        if self.head.len() < self.head_limit {
+            self.head.push(line);
+            return;
+        }
+        self.tail.push_back(line);
+        if self.tail.len() > self.tail_limit {
+            if let Some(dropped) = self.tail.pop_front() {
+                self.omitted_lines += 1;
+                self.omitted_bytes += dropped.len() + 1;
+            }
+        }
+    }
+
+    /// Whether any lines have been dropped so far.
+    pub fn is_truncated(&self) -> bool {
+        self.omitted_lines > 0
+    }
+
+    /// Number of lines dropped from the middle.
+    pub fn omitted_lines(&self) -> usize {
+        self.omitted_lines
+    }
+
+    /// Flush any pending partial line and render the kept text, with a
+    /// marker line where output was dropped.
+    pub fn finish(mut self) -> String {
+        if !self.partial.is_empty() {
+            let line = std::mem::take(&mut self.partial);
+            self.push_line(line);
+        }
+        let mut lines: Vec<String> = self.head;
+        if self.omitted_lines > 0 {
+            lines.push(truncation_marker(self.omitted_lines, self.omitted_bytes));
+        }
+        lines.extend(self.tail);
+        lines.join("\n")
+    }
+}
+
+/// Marker line inserted where [`HeadTailBuffer`] dropped output.
+pub fn truncation_marker(lines: usize, bytes: usize) -> String {
+    let plural = if lines == 1 { "" } else { "s" };
+    format!("… [{lines} line{plural} ({bytes} bytes) truncated] …")
+}
+
+// ─── UTF-8 chunk decoding ────────────────────────────────────────────────
+
+/// Decode `bytes` as UTF-8, carrying an incomplete trailing sequence over
+/// to the next call so multi-byte characters split across reads survive.
+pub fn decode_utf8_chunk(carry: &mut Vec<u8>, bytes: &[u8]) -> String {
+    carry.extend_from_slice(bytes);
+    let keep_from = match std::str::from_utf8(carry) {
+        Ok(_) => carry.len(),
+        // An incomplete sequence at the very end: wait for more bytes.
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => carry.len(),
+    };
+    let rest = carry.split_off(keep_from);
+    let text = String::from_utf8_lossy(carry).into_owned();
+    *carry = rest;
+    text
+}
+
+// ─── Subprocess execution ────────────────────────────────────────────────
+
+/// How a streamed subprocess ended.
+#[derive(Debug)]
+pub struct SubprocessResult {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: Option<i32>,
+    pub cancelled: bool,
+    pub timed_out: bool,
+    pub truncated: bool,
+}
+
+/// Run `command` through the user's shell, streaming output as it arrives.
+///
+/// `on_chunk` is called on the caller's thread for every read from stdout
+/// or stderr. `is_cancelled` is polled every 50ms; once it returns true (or
+/// `timeout` elapses) the whole process group is sent SIGTERM, then SIGKILL
+/// if it is still alive two seconds later.
+pub fn run_subprocess(
+    command: &str,
+    cwd: Option<&Path>,
+    timeout: Duration,
+    max_lines: usize,
+    is_cancelled: &dyn Fn() -> bool,
+    on_chunk: &mut dyn FnMut(&BashChunk),
+) -> anyhow::Result<SubprocessResult> {
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
+    let mut cmd = Command::new(shell);
+    cmd.arg("-c")
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = cwd {
+        cmd.current_dir(dir);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("failed to run `{command}`: {e}"))?;
+
+    let (tx, rx) = flume::unbounded::<BashChunk>();
+    let mut readers = Vec::new();
+    if let Some(out) = child.stdout.take() {
+        readers.push(spawn_reader(out, OutputStream::Stdout, tx.clone()));
+    }
+    if let Some(err) = child.stderr.take() {
+        readers.push(spawn_reader(err, OutputStream::Stderr, tx.clone()));
+    }
+    drop(tx);
+
+    let mut stdout = HeadTailBuffer::with_max_lines(max_lines);
+    let mut stderr = HeadTailBuffer::with_max_lines(max_lines);
+    let started = Instant::now();
+    let mut cancelled = false;
+    let mut timed_out = false;
+    let mut kill_sent: Option<Instant> = None;
+
+    loop {
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(chunk) => {
+                match chunk.stream {
+                    OutputStream::Stdout => stdout.push(&chunk.text),
+                    OutputStream::Stderr => stderr.push(&chunk.text),
+                }
+                on_chunk(&chunk);
+            }
+            Err(flume::RecvTimeoutError::Timeout) => {}
+            Err(flume::RecvTimeoutError::Disconnected) => break,
+        }
+
+        match kill_sent {
+            None => {
+                if is_cancelled() {
+                    cancelled = true;
+                } else if started.elapsed() >= timeout {
+                    timed_out = true;
+                }
+                if cancelled || timed_out {
+                    signal_group(&mut child, false);
+                    kill_sent = Some(Instant::now());
+                }
+            }
+            Some(at) if at.elapsed() >= KILL_GRACE => {
+                signal_group(&mut child, true);
+                // Don't wait on grandchildren that escaped the group and
+                // still hold the pipes open.
+                break;
+            }
+            Some(_) => {}
+        }
+    }
+
+    let status = child.wait()?;
+    if kill_sent.is_none() {
+        for reader in readers {
+            let _ = reader.join();
+        }
+    }
+
+    let truncated = stdout.is_truncated() || stderr.is_truncated();
+    Ok(SubprocessResult {
+        stdout: stdout.finish(),
+        stderr: stderr.finish(),
+        exit_code: status.code(),
+        cancelled,
+        timed_out,
+        truncated,
+    })
+}
+
+fn spawn_reader<R: Read + Send + 'static>(
+    mut source: R,
+    stream: OutputStream,
+    tx: flume::Sender<BashChunk>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        let mut carry = Vec::new();
+        loop {
+            match source.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let text = decode_utf8_chunk(&mut carry, &buf[..n]);
+                    if !text.is_empty() && tx.send(BashChunk { stream, text }).is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+        if !carry.is_empty() {
+            let text = String::from_utf8_lossy(&carry).into_owned();
+            let _ = tx.send(BashChunk { stream, text });
+        }
+    })
+}
+
+/// Signal the child's process group (it was spawned as a group leader).
+fn signal_group(child: &mut std::process::Child, force: bool) {
+    #[cfg(unix)]
+    {
+        let sig = if force { libc::SIGKILL } else { libc::SIGTERM };
+        unsafe {
+            libc::kill(-(child.id() as libc::pid_t), sig);
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = force;
+        let _ = child.kill();
+    }
+}
+
+// ─── Pane streaming ──────────────────────────────────────────────────────
+
+/// Tracks the last seen pane contents and reports lines added since.
+///
+/// The cursor line (the last non-blank line) is held back until something
+/// is printed below it, so progress bars and half-written lines are only
+/// reported once they are final.
+#[derive(Debug, Default)]
+pub struct ScreenTail {
+    committed: Vec<String>,
+}
+
+impl ScreenTail {
+    /// Start tracking from `lines`; anything already on screen is ignored.
+    pub fn new(lines: &[String]) -> Self {
+        Self {
+            committed: committed_lines(lines).to_vec(),
+        }
+    }
+
+    /// Return the complete lines that appeared since the previous call.
+    pub fn advance(&mut self, lines: &[String]) -> Vec<String> {
+        let current = committed_lines(lines);
+        let new = new_lines(&self.committed, current).to_vec();
+        self.committed = current.to_vec();
+        new
+    }
+
+    /// Return the held-back cursor line, for use once the command is done.
+    pub fn flush(&self, lines: &[String]) -> Option<String> {
+        let trimmed = trim_blank_tail(lines);
+        if trimmed.len() > self.committed.len() || self.committed.is_empty() {
+            trimmed.last().cloned()
+        } else {
+            None
+        }
+    }
+}
+
+fn trim_blank_tail(lines: &[String]) -> &[String] {
+    let end = lines
+        .iter()
+        .rposition(|l| !l.trim().is_empty())
+        .map_or(0, |i| i + 1);
+    &lines[..end]
+}
+
+fn committed_lines(lines: &[String]) -> &[String] {
+    let trimmed = trim_blank_tail(lines);
+    &trimmed[..trimmed.len().saturating_sub(1)]
+}
+
+/// Lines of `current` that follow the longest overlap between the end of
+/// `previous` and the start of `current` (i.e. what scrolled into view).
+pub fn new_lines<'a>(previous: &[String], current: &'a [String]) -> &'a [String] {
+    let max = previous.len().min(current.len());
+    for k in (1..=max).rev() {
+        if previous[previous.len() - k..] == current[..k] {
+            return &current[k..];
+        }
+    }
+    current
+}
+
+// ─── Completion sentinel ─────────────────────────────────────────────────
+
+/// Generate a marker id unique to this process and invocation.
+pub fn new_nonce() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{:x}{n:x}", std::process::id())
+}
+
+/// Append a `printf` that reports `$?` once `command` finishes.
+///
+/// The typed command echoes `%d` rather than digits, so only the printed
+/// marker matches [`parse_sentinel`]. Requires a POSIX-style shell.
+pub fn wrap_command(command: &str, nonce: &str) -> String {
+    format!("{command}; printf '\\n{SENTINEL_PREFIX}{nonce}:%d__\\n' $?")
+}
+
+/// Whether `line` mentions the marker at all (echoed command or output).
+pub fn contains_sentinel(line: &str, nonce: &str) -> bool {
+    line.contains(&format!("{SENTINEL_PREFIX}{nonce}"))
+}
+
+/// Extract the exit code from a printed marker line.
+pub fn parse_sentinel(line: &str, nonce: &str) -> Option<i32> {
+    let marker = format!("{SENTINEL_PREFIX}{nonce}:");
+    let start = line.find(&marker)? + marker.len();
+    let rest = &line[start..];
+    let end = rest.find("__")?;
+    rest[..end].parse().ok()
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_head_tail_under_limit() {
+        let mut buf = HeadTailBuffer::new(2, 2);
+        buf.push("a\nb\nc");
+        assert!(!buf.is_truncated());
+        assert_eq!(buf.finish(), "a\nb\nc");
+    }
+
+    #[test]
+    fn test_head_tail_truncates_middle() {
+        let mut buf = HeadTailBuffer::new(2, 2);
+        for i in 0..10 {
+            buf.push(&format!("line{i}\n"));
+        }
+        assert!(buf.is_truncated());
+        assert_eq!(buf.omitted_lines(), 6);
+        let out = buf.finish();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[..2], ["line0", "line1"]);
+        assert_eq!(lines[3..], ["line8", "line9"]);
+        assert!(lines[2].contains("6 lines"));
+        assert!(lines[2].contains("truncated"));
+    }
+
+    #[test]
+    fn test_head_tail_partial_lines_and_crlf() {
+        let mut buf = HeadTailBuffer::with_max_lines(10);
+        buf.push("hel");
+        buf.push("lo\r\nwor");
+        buf.push("ld");
+        assert_eq!(buf.finish(), "hello\nworld");
+    }
+
+    #[test]
+    fn test_decode_utf8_split_sequence() {
+        let bytes = "héllo".as_bytes();
+        let mut carry = Vec::new();
+        // Split inside the two-byte 'é'
+        let a = decode_utf8_chunk(&mut carry, &bytes[..2]);
+        assert_eq!(a, "h");
+        let b = decode_utf8_chunk(&mut carry, &bytes[2..]);
+        assert_eq!(b, "éllo");
+        assert!(carry.is_empty());
+    }
+
+    #[test]
+    fn test_decode_utf8_invalid_bytes_are_lossy() {
+        let mut carry = Vec::new();
+        let text = decode_utf8_chunk(&mut carry, b"ok\xffok");
+        assert_eq!(text, "ok\u{fffd}ok");
+        assert!(carry.is_empty());
+    }
+
+    #[test]
+    fn test_new_lines_append_and_scroll() {
+        let prev = strings(&["a", "b"]);
+        assert_eq!(new_lines(&prev, &strings(&["a", "b", "c"])), strings(&["c"]));
+        let prev = strings(&["a", "b", "c"]);
+        assert_eq!(new_lines(&prev, &strings(&["b", "c", "d", "e"])), strings(&["d", "e"]));
+        // No overlap at all (screen cleared): everything is new
+        assert_eq!(new_lines(&prev, &strings(&["x"])), strings(&["x"]));
+        // Nothing changed
+        assert!(new_lines(&prev, &prev).is_empty());
+    }
+
+    #[test]
+    fn test_screen_tail_holds_cursor_line() {
+        let mut tail = ScreenTail::new(&strings(&["$ make", "10%", ""]));
+        // Progress line is still being rewritten
+        assert!(tail.advance(&strings(&["$ make", "50%", ""])).is_empty());
+        let new = tail.advance(&strings(&["$ make", "100%", "done", ""]));
+        assert_eq!(new, strings(&["100%"]));
+        assert_eq!(
+            tail.flush(&strings(&["$ make", "100%", "done", ""])),
+            Some("done".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sentinel_roundtrip() {
+        let nonce = new_nonce();
+        let wrapped = wrap_command("cargo build", &nonce);
+        assert!(wrapped.starts_with("cargo build; printf"));
+        // The echoed command line never parses as a result
+        assert!(contains_sentinel(&wrapped, &nonce));
+        assert_eq!(parse_sentinel(&wrapped, &nonce), None);
+
+        let printed = format!("{SENTINEL_PREFIX}{nonce}:101__");
+        assert_eq!(parse_sentinel(&printed, &nonce), Some(101));
+        assert_eq!(parse_sentinel(&printed, "other"), None);
+        assert_ne!(new_nonce(), nonce);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_subprocess_streams_both_streams() {
+        let mut chunks = Vec::new();
+        let result = run_subprocess(
+            "echo out; echo err >&2; exit 3",
+            None,
+            Duration::from_secs(10),
+            100,
+            &|| false,
+            &mut |c| chunks.push(c.clone()),
+        )
+        .unwrap();
+        assert_eq!(result.exit_code, Some(3));
+        assert_eq!(result.stdout, "out");
+        assert_eq!(result.stderr, "err");
+        assert!(!result.cancelled && !result.timed_out && !result.truncated);
+        assert!(chunks.iter().any(|c| !c.is_stderr() && c.text.contains("out")));
+        assert!(chunks.iter().any(|c| c.is_stderr() && c.text.contains("err")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_subprocess_truncates() {
+        let result = run_subprocess(
+            "seq 1 1000",
+            None,
+            Duration::from_secs(10),
+            10,
+            &|| false,
+            &mut |_| {},
+        )
+        .unwrap();
+        assert!(result.truncated);
+        let lines: Vec<&str> = result.stdout.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[0], "1");
+        assert_eq!(lines[10], "1000");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_subprocess_cancel() {
+        let started = Instant::now();
+        let cancel_at = started + Duration::from_millis(200);
+        let result = run_subprocess(
+            "echo started; sleep 30",
+            None,
+            Duration::from_secs(60),
+            100,
+            &|| Instant::now() >= cancel_at,
+            &mut |_| {},
+        )
+        .unwrap();
+        assert!(result.cancelled);
+        assert!(!result.timed_out);
+        assert_eq!(result.stdout, "started");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_subprocess_timeout() {
+        let result = run_subprocess(
+            "sleep 30",
+            None,
+            Duration::from_millis(100),
+            100,
+            &|| false,
+            &mut |_| {},
+        )
+        .unwrap();
+        assert!(result.timed_out);
+        assert!(result.exit_code.is_none());
+    }
+}
@@ -20,10 +20,16 @@
 //! `BlockManager::push_agent_block()` and `BlockManager::push_output_block()`
 //! are called explicitly as agent events arrive, giving us synthetic blocks
 //! without OSC 133 parsing.
+//!
+//! Blocks are snapshotted to disk by [`crate::block_store`] and replayed via
+//! `BlockManager::restore_block()` when the pane starts again.
 
+use std::path::PathBuf;
 use std::time::Instant;
 use wezterm_term::StableRowIndex;
 
+use crate::theme::Role;
+
 
 /// Opaque identifier for a block.
 pub type BlockId = u64;
@@ -59,6 +65,15 @@ pub struct Block {
     /// Rows that contain command output (OSC 133;C–133;D).
     pub output_zone: Option<ZoneRange>,
 
+    /// The command text, when known at the time the block was created.
+    ///
+    /// Shell blocks built from semantic zones leave this `None`; their command
+    /// is read back from the input zone instead.
+    pub command: Option<String>,
+
+    /// Working directory the command ran in (used for rerun).
+    pub cwd: Option<PathBuf>,
+
     /// Exit code from OSC 133;D (if received).
     pub exit_code: Option<i32>,
 
@@ -191,6 +206,8 @@ impl BlockManager {
                         prompt_zone: Some(range),
                         input_zone: None,
                         output_zone: None,
+                        command: None,
+                        cwd: None,
                         exit_code: None,
                         start_time: None,
                         end_time: None,
@@ -213,6 +230,8 @@ impl BlockManager {
                         prompt_zone: None,
                         input_zone: Some(range),
                         output_zone: None,
+                        command: None,
+                        cwd: None,
                         exit_code: None,
                         start_time: None,
                         end_time: None,
@@ -241,6 +260,8 @@ impl BlockManager {
                         prompt_zone: None,
                         input_zone: None,
                         output_zone: Some(range),
+                        command: None,
+                        cwd: None,
                         exit_code: None,
                         start_time: None,
                         end_time: None,
@@ -280,6 +301,8 @@ impl BlockManager {
             }),
             input_zone: None,
             output_zone: None,
+            command: None,
+            cwd: None,
             exit_code: None,
             start_time: Some(Instant::now()),
             end_time: None,
@@ -289,6 +312,61 @@ impl BlockManager {
         id
     }
 
+    /// Begin a new shell command block starting at `start_row`.
+    ///
+    /// Used when the ElwoodPane runs a command (Terminal mode or `!` prefix).
+    /// The command text and working directory are kept on the block so it
+    /// can be copied or rerun later.  Returns the new block's id.
+    pub fn push_command_block(
+        &mut self,
+        start_row: StableRowIndex,
+        command: &str,
+        cwd: Option<PathBuf>,
+    ) -> BlockId {
+        let id = self.push_agent_block(start_row);
+        if let Some(block) = self.blocks.last_mut() {
+            block.command = Some(command.to_string());
+            block.cwd = cwd;
+        }
+        id
+    }
+
+    /// Re-create a block loaded from a [`crate::block_store::BlockStore`].
+    ///
+    /// The replayed text occupies `start_row..=end_row`, with its output
+    /// starting at `output_row`.  Bookmark/collapse state, exit code and
+    /// duration are carried over.  Returns the new block's id.
+    pub fn restore_block(
+        &mut self,
+        persisted: &crate::block_store::PersistedBlock,
+        start_row: StableRowIndex,
+        output_row: StableRowIndex,
+        end_row: StableRowIndex,
+    ) -> BlockId {
+        let id = self.next_id();
+        let (start_time, end_time) = persisted.instants();
+        self.blocks.push(Block {
+            id,
+            prompt_zone: Some(ZoneRange {
+                start_y: start_row,
+                end_y: start_row,
+            }),
+            input_zone: None,
+            output_zone: Some(ZoneRange {
+                start_y: output_row,
+                end_y: end_row.max(output_row),
+            }),
+            command: persisted.command.clone(),
+            cwd: persisted.cwd.clone(),
+            exit_code: persisted.exit_code,
+            start_time,
+            end_time,
+            collapsed: persisted.collapsed,
+            bookmarked: persisted.bookmarked,
+        });
+        id
+    }
+
     /// Extend the output zone of the most recent block to `end_row`.
     ///
     /// If there is no current block, this is a no-op.
@@ -500,6 +578,9 @@ impl BlockManager {
         let mut md = String::new();
         md.push_str(&format!("## Block {}\n\n", block.id));
 
+        if let Some(ref cmd) = block.command {
+            md.push_str(&format!("```bash\n$ {cmd}\n```\n\n"));
+        }
         if let Some(exit) = block.exit_code {
             md.push_str(&format!("**Exit code:** {exit}\n"));
         }
@@ -534,17 +615,95 @@ impl BlockManager {
     }
 }
 
+// ── Block actions ──────────────────────────────────────────────────────────
+
+/// Maximum bytes of block output included when sending a block to the agent.
+const MAX_AGENT_OUTPUT_BYTES: usize = 8 * 1024;
+
+/// An action performed on the content of a single block.
+///
+/// The block itself only stores row ranges; the pane reads the command and
+/// output text from the virtual terminal and then applies the action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockAction {
+    /// Copy only the command line to the clipboard.
+    CopyCommand,
+    /// Copy only the output to the clipboard.
+    CopyOutput,
+    /// Run the command again in its original working directory.
+    Rerun,
+    /// Send the command and output to the agent as context.
+    SendToAgent,
+    /// Save the output as plain text (default path if `None`).
+    SaveOutput(Option<PathBuf>),
+    /// Export the block as a self-contained HTML snippet (default path if `None`).
+    ExportHtml(Option<PathBuf>),
+}
+
+impl BlockAction {
+    /// Short human-readable label for confirmation messages.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::CopyCommand => "Copy command",
+            Self::CopyOutput => "Copy output",
+            Self::Rerun => "Rerun",
+            Self::SendToAgent => "Send to agent",
+            Self::SaveOutput(_) => "Save output",
+            Self::ExportHtml(_) => "Export HTML",
+        }
+    }
+}
+
+/// Build the agent prompt used by [`BlockAction::SendToAgent`].
+///
+/// Long output keeps its tail, since errors and summaries usually come last.
+pub fn format_block_for_agent(block: &Block, command: Option<&str>, output: &str) -> String {
+    let mut prompt = String::from("Here is a block from my terminal for context.\n\n");
+    if let Some(cmd) = command {
+        prompt.push_str(&format!("Command: `{cmd}`\n"));
+    }
+    if let Some(ref cwd) = block.cwd {
+        prompt.push_str(&format!("Working directory: `{}`\n", cwd.display()));
+    }
+    if let Some(exit) = block.exit_code {
+        prompt.push_str(&format!("Exit code: {exit}\n"));
+    }
+    if let Some(dur) = block.duration_secs() {
+        prompt.push_str(&format!("Duration: {dur:.1}s\n"));
+    }
+
+    let trimmed = output.trim_end();
+    let body = if trimmed.len() > MAX_AGENT_OUTPUT_BYTES {
+        let mut start = trimmed.len() - MAX_AGENT_OUTPUT_BYTES;
+        while !trimmed.is_char_boundary(start) {
+            start += 1;
+        }
+        format!("[... {start} bytes truncated ...]\n{}", &trimmed[start..])
+    } else {
+        trimmed.to_string()
+    };
+    prompt.push_str(&format!("\nOutput:\n```\n{body}\n```\n"));
+    prompt
+}
+
+/// Default file name for a saved or exported block.
+pub fn default_block_filename(block: &Block, extension: &str) -> String {
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    format!("elwood_block_{}_{timestamp}.{extension}", block.id)
+}
+
 // ── Block chrome rendering helpers ─────────────────────────────────────────
 
-// TokyoNight palette subset for block chrome
+// Theme roles used by block chrome
 const RESET: &str = "\x1b[0m";
-const BORDER: (u8, u8, u8) = (59, 66, 97);    // muted blue-grey
-const SUCCESS: (u8, u8, u8) = (158, 206, 106); // green
-const ERROR: (u8, u8, u8) = (247, 118, 142);   // red
-const MUTED: (u8, u8, u8) = (86, 95, 137);     // dim grey
-const BOOKMARK: (u8, u8, u8) = (224, 175, 104); // amber
-
-fn fg_rgb(r: u8, g: u8, b: u8) -> String {
+const BORDER: Role = Role::BlockBorder;
+const SUCCESS: Role = Role::Success;
+const ERROR: Role = Role::Error;
+const MUTED: Role = Role::FgMuted;
+const BOOKMARK: Role = Role::Warning;
+
+fn fg_role(role: Role) -> String {
+    let (r, g, b) = role.rgb();
     format!("\x1b[38;2;{r};{g};{b}m")
 }
 
@@ -552,7 +711,7 @@ fn fg_rgb(r: u8, g: u8, b: u8) -> String {
 ///
 /// Shows the block number and bookmarked indicator.
 pub fn render_block_top_border(block: &Block, width: u16) -> String {
-    let border_esc = fg_rgb(BORDER.0, BORDER.1, BORDER.2);
+    let border_esc = fg_role(BORDER);
     let bookmark = if block.bookmarked { "★ " } else { "" };
     let id_label = format!("─ {}{}", bookmark, block.id);
     let fill_len = (width as usize).saturating_sub(id_label.len() + 4);
@@ -561,7 +720,7 @@ pub fn render_block_top_border(block: &Block, width: u16) -> String {
     let id_part = if block.bookmarked {
         format!(
             "{bm}{id}{border}",
-            bm = fg_rgb(BOOKMARK.0, BOOKMARK.1, BOOKMARK.2),
+            bm = fg_role(BOOKMARK),
             id = id_label,
             border = border_esc,
         )
@@ -582,26 +741,17 @@ pub fn render_block_top_border(block: &Block, width: u16) -> String {
 ///
 /// Shows exit code and duration.
 pub fn render_block_bottom_border(block: &Block, width: u16) -> String {
-    let border_esc = fg_rgb(BORDER.0, BORDER.1, BORDER.2);
+    let border_esc = fg_role(BORDER);
 
     let exit_str = match block.exit_code {
-        Some(0) => format!(
-            "{}exit 0{}",
-            fg_rgb(SUCCESS.0, SUCCESS.1, SUCCESS.2),
-            RESET,
-        ),
-        Some(n) => format!(
-            "{}exit {}{}",
-            fg_rgb(ERROR.0, ERROR.1, ERROR.2),
-            n,
-            RESET,
-        ),
+        Some(0) => format!("{}exit 0{}", fg_role(SUCCESS), RESET),
+        Some(n) => format!("{}exit {}{}", fg_role(ERROR), n, RESET),
         None => String::new(),
     };
 
     let dur_str = block
         .duration_secs()
-        .map(|d| format!(" {}· {:.1}s{}", fg_rgb(MUTED.0, MUTED.1, MUTED.2), d, RESET))
+        .map(|d| format!(" {}· {:.1}s{}", fg_role(MUTED), d, RESET))
         .unwrap_or_default();
 
     let footer = format!(" {}{} ", exit_str, dur_str);
@@ -835,6 +985,8 @@ mod tests {
             prompt_zone: Some(ZoneRange { start_y: 0, end_y: 0 }),
             input_zone: Some(ZoneRange { start_y: 1, end_y: 1 }),
             output_zone: Some(ZoneRange { start_y: 2, end_y: 9 }),
+            command: None,
+            cwd: None,
             exit_code: None,
             start_time: None,
             end_time: None,
@@ -1047,6 +1199,89 @@ mod tests {
         assert!(mgr.export_block_markdown(0).is_none());
     }
 
+    #[test]
+    fn test_export_block_markdown_includes_command() {
+        let mut mgr = BlockManager::new();
+        mgr.push_command_block(0, "cargo build", None);
+        let md = mgr.export_block_markdown(0).unwrap();
+        assert!(md.contains("$ cargo build"));
+    }
+
+    // ── Tests for command blocks and block actions ──────────────────────
+
+    #[test]
+    fn test_restore_block() {
+        let persisted = crate::block_store::PersistedBlock {
+            command: Some("cargo build".to_string()),
+            cwd: Some(PathBuf::from("/src")),
+            exit_code: Some(101),
+            started_at: Some(chrono::Utc::now() - chrono::Duration::seconds(10)),
+            finished_at: Some(chrono::Utc::now() - chrono::Duration::seconds(8)),
+            output: "error[E0308]".to_string(),
+            bookmarked: true,
+            collapsed: true,
+        };
+        let mut mgr = BlockManager::new();
+        let id = mgr.restore_block(&persisted, 3, 4, 9);
+        let block = mgr.get_block_by_id(id).unwrap();
+        assert_eq!(block.first_row(), Some(3));
+        assert_eq!(block.last_row(), Some(9));
+        assert_eq!(block.command.as_deref(), Some("cargo build"));
+        assert_eq!(block.exit_code, Some(101));
+        assert!(block.bookmarked);
+        assert!(block.collapsed);
+        let dur = block.duration_secs().unwrap();
+        assert!((dur - 2.0).abs() < 0.05, "duration was {dur}");
+        assert_eq!(mgr.bookmarked_blocks_with_index().len(), 1);
+    }
+
+    #[test]
+    fn test_push_command_block() {
+        let mut mgr = BlockManager::new();
+        let id = mgr.push_command_block(3, "ls -la", Some(PathBuf::from("/tmp")));
+        let block = mgr.get_block_by_id(id).unwrap();
+        assert_eq!(block.command.as_deref(), Some("ls -la"));
+        assert_eq!(block.cwd, Some(PathBuf::from("/tmp")));
+        assert_eq!(block.first_row(), Some(3));
+        assert!(block.start_time.is_some());
+    }
+
+    #[test]
+    fn test_format_block_for_agent() {
+        let mut mgr = BlockManager::new();
+        mgr.push_command_block(0, "cargo test", Some(PathBuf::from("/work")));
+        mgr.finish_block(Some(101));
+        let block = &mgr.blocks()[0];
+
+        let prompt = format_block_for_agent(block, Some("cargo test"), "test failed\n");
+        assert!(prompt.contains("Command: `cargo test`"));
+        assert!(prompt.contains("Working directory: `/work`"));
+        assert!(prompt.contains("Exit code: 101"));
+        assert!(prompt.contains("```\ntest failed\n```"));
+    }
+
+    #[test]
+    fn test_format_block_for_agent_keeps_tail() {
+        let mut mgr = BlockManager::new();
+        mgr.push_agent_block(0);
+        let block = &mgr.blocks()[0];
+
+        let output = format!("{}END", "x".repeat(MAX_AGENT_OUTPUT_BYTES * 2));
+        let prompt = format_block_for_agent(block, None, &output);
+        assert!(prompt.contains("bytes truncated"));
+        assert!(prompt.contains("END"));
+        assert!(prompt.len() < MAX_AGENT_OUTPUT_BYTES + 512);
+    }
+
+    #[test]
+    fn test_default_block_filename() {
+        let mut mgr = BlockManager::new();
+        mgr.push_agent_block(0);
+        let name = default_block_filename(&mgr.blocks()[0], "html");
+        assert!(name.starts_with("elwood_block_0_"));
+        assert!(name.ends_with(".html"));
+    }
+
     // ── Test select and deselect ────────────────────────────────────────
 
     #[test]
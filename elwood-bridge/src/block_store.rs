@@ -0,0 +1,616 @@
+//! Block persistence — keep Elwood blocks across restarts.
+//!
+//! `BlockManager` lives in memory and is rebuilt as the virtual terminal is
+//! written, so bookmarks, exit codes and durations would otherwise vanish when
+//! the pane is closed.  `BlockStore` snapshots each block (command, cwd, exit
+//! code, timestamps, output text, bookmark/collapse flags) to a JSON file so
+//! the pane can replay them into scrollback on startup.
+//!
+//! ## Storage
+//!
+//! Each pane writes its own session file, so two panes open in the same
+//! project never overwrite each other.  Files live in
+//! `~/.elwood/blocks/{dir}/{session}.jsonl`, where `{dir}` is a short SHA-256
+//! of the working directory and `{session}` identifies the pane's session.
+//! A new pane restores the most recently written session of its directory.
+//!
+//! The file is JSON Lines: a header, then one record per block write.
+//! Finishing a block or toggling its bookmark appends a record for that block
+//! instead of rewriting the file; on load the last record per block wins.
+//! Once the file holds twice [`MAX_PERSISTED_BLOCKS`] records it is compacted.
+//!
+//! ```text
+//! {"version":2,"cwd":"/home/me/project","created_at":"2026-03-01T12:00:00Z"}
+//! {"id":0,"command":"cargo test","exit_code":0,"bookmarked":false,...}
+//! {"id":0,"command":"cargo test","exit_code":0,"bookmarked":true,...}
+//! ```
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::block::{Block, BlockId};
+
+/// Current on-disk format version.
+const STORE_VERSION: u32 = 2;
+
+/// Maximum number of blocks kept per session (oldest are dropped first).
+pub const MAX_PERSISTED_BLOCKS: usize = 200;
+
+/// Maximum number of session files kept per working directory.
+pub const MAX_SESSIONS_PER_DIR: usize = 8;
+
+/// Maximum output bytes stored per block (the tail is kept).
+pub const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+// ─── Types ──────────────────────────────────────────────────────────────────
+
+/// A block snapshot suitable for writing to disk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistedBlock {
+    /// Shell command text (`None` for agent turns).
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Working directory the command ran in.
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+    /// Exit code, if the block finished.
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    /// Wall-clock start time.
+    #[serde(default)]
+    pub started_at: Option<DateTime<Utc>>,
+    /// Wall-clock end time.
+    #[serde(default)]
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Plain-text output as it appeared in the pane.
+    #[serde(default)]
+    pub output: String,
+    /// Whether the block was bookmarked.
+    #[serde(default)]
+    pub bookmarked: bool,
+    /// Whether the block was collapsed.
+    #[serde(default)]
+    pub collapsed: bool,
+}
+
+impl PersistedBlock {
+    /// Snapshot a live block together with its output text.
+    ///
+    /// `Instant` timestamps are converted to wall-clock time relative to now.
+    /// Output longer than [`MAX_OUTPUT_BYTES`] keeps only its tail.
+    pub fn from_block(block: &Block, output: &str) -> Self {
+        Self {
+            command: block.command.clone(),
+            cwd: block.cwd.clone(),
+            exit_code: block.exit_code,
+            started_at: block.start_time.map(instant_to_utc),
+            finished_at: block.end_time.map(instant_to_utc),
+            output: tail(output, MAX_OUTPUT_BYTES).to_string(),
+            bookmarked: block.bookmarked,
+            collapsed: block.collapsed,
+        }
+    }
+
+    /// Duration of the block in seconds, if both timestamps are known.
+    pub fn duration_secs(&self) -> Option<f64> {
+        let start = self.started_at?;
+        let end = self.finished_at?;
+        let ms = (end - start).num_milliseconds().max(0);
+        Some(ms as f64 / 1000.0)
+    }
+
+    /// Reconstruct `Instant`s that preserve this block's duration.
+    ///
+    /// The start is placed as long ago as the block really started, or at
+    /// now when that is further back than the platform's `Instant` can
+    /// reach; the end keeps the original duration from the start.
+    pub fn instants(&self) -> (Option<Instant>, Option<Instant>) {
+        let now = Instant::now();
+        let to_instant = |t: DateTime<Utc>| {
+            let ago = (Utc::now() - t).to_std().unwrap_or(Duration::ZERO);
+            now.checked_sub(ago).unwrap_or(now)
+        };
+        let start = self.started_at.map(to_instant);
+        let end = match (start, self.duration_secs()) {
+            (Some(s), Some(d)) => Some(s + Duration::from_secs_f64(d)),
+            _ => self.finished_at.map(to_instant),
+        };
+        (start, end)
+    }
+}
+
+/// First line of a session file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoreHeader {
+    version: u32,
+    #[serde(default)]
+    cwd: Option<PathBuf>,
+    #[serde(default)]
+    created_at: Option<DateTime<Utc>>,
+}
+
+/// One block write: the block's id in its session plus its snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BlockRecord {
+    id: BlockId,
+    #[serde(flatten)]
+    block: PersistedBlock,
+}
+
+// ─── Store ──────────────────────────────────────────────────────────────────
+
+/// Reads and writes the persisted blocks of one pane's session.
+#[derive(Debug, Clone)]
+pub struct BlockStore {
+    /// Directory holding every session of this working directory.
+    dir: PathBuf,
+    /// Path to this session's file.
+    path: PathBuf,
+    /// Working directory the session belongs to.
+    cwd: PathBuf,
+    /// Latest snapshot of each block written so far, oldest first.
+    blocks: Vec<(BlockId, PersistedBlock)>,
+    /// Number of block records in the file (including superseded ones).
+    records: usize,
+}
+
+impl BlockStore {
+    /// Store for session `session_id` of the directory `cwd`, under
+    /// `~/.elwood/blocks/`.
+    pub fn for_session(cwd: &Path, session_id: &str) -> Self {
+        Self::with_dir(default_store_dir(), cwd, session_id)
+    }
+
+    /// Store for session `session_id` of `cwd`, under a custom directory (for testing).
+    pub fn with_dir(dir: PathBuf, cwd: &Path, session_id: &str) -> Self {
+        let dir = dir.join(dir_key(cwd));
+        let path = dir.join(format!("{session_id}.jsonl"));
+        Self {
+            dir,
+            path,
+            cwd: cwd.to_path_buf(),
+            blocks: Vec::new(),
+            records: 0,
+        }
+    }
+
+    /// Path to this session's file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The last snapshot written for block `id`, if any.
+    pub fn get(&self, id: BlockId) -> Option<&PersistedBlock> {
+        self.blocks.iter().find(|(i, _)| *i == id).map(|(_, b)| b)
+    }
+
+    /// Load this session's blocks (empty if nothing was saved yet).
+    pub fn load(&self) -> anyhow::Result<Vec<PersistedBlock>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        read_session(&self.path)
+    }
+
+    /// Load the most recently written other session of this directory, so a
+    /// new pane can pick up where the last one left off.
+    pub fn load_previous(&self) -> anyhow::Result<Vec<PersistedBlock>> {
+        match self.sessions()?.into_iter().find(|p| *p != self.path) {
+            Some(path) => read_session(&path),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Record the current state of block `id`.
+    ///
+    /// Appends one line to the session file; unchanged blocks are not
+    /// written again.
+    pub fn upsert(&mut self, id: BlockId, block: PersistedBlock) -> anyhow::Result<()> {
+        self.upsert_all(vec![(id, block)])
+    }
+
+    /// Record several blocks with a single append.
+    pub fn upsert_all(&mut self, blocks: Vec<(BlockId, PersistedBlock)>) -> anyhow::Result<()> {
+        let mut lines = String::new();
+        for (id, block) in blocks {
+            match self.blocks.iter_mut().find(|(i, _)| *i == id) {
+                Some((_, existing)) if *existing == block => continue,
+                Some((_, existing)) => *existing = block.clone(),
+                None => self.blocks.push((id, block.clone())),
+            }
+            lines.push_str(&serde_json::to_string(&BlockRecord { id, block })?);
+            lines.push('\n');
+            self.records += 1;
+        }
+        if lines.is_empty() {
+            return Ok(());
+        }
+        if self.records >= MAX_PERSISTED_BLOCKS * 2 || !self.path.exists() {
+            return self.compact();
+        }
+        let mut file = std::fs::OpenOptions::new().append(true).open(&self.path)?;
+        file.write_all(lines.as_bytes())?;
+        Ok(())
+    }
+
+    /// Delete all but the `keep` most recently written sessions of this
+    /// directory (this session is never deleted).
+    pub fn prune_sessions(&self, keep: usize) -> anyhow::Result<()> {
+        for path in self.sessions()?.into_iter().skip(keep) {
+            if path != self.path {
+                std::fs::remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete this session's file, if any, and forget its blocks.
+    pub fn clear(&mut self) -> anyhow::Result<()> {
+        self.blocks.clear();
+        self.records = 0;
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+
+    /// Rewrite the file with only the latest snapshot of each block, keeping
+    /// at most [`MAX_PERSISTED_BLOCKS`].
+    ///
+    /// Bookmarked blocks are never dropped to make room for newer ones.
+    fn compact(&mut self) -> anyhow::Result<()> {
+        self.blocks = prune(&self.blocks, MAX_PERSISTED_BLOCKS);
+        std::fs::create_dir_all(&self.dir)?;
+        let header = StoreHeader {
+            version: STORE_VERSION,
+            cwd: Some(self.cwd.clone()),
+            created_at: Some(Utc::now()),
+        };
+        let mut content = serde_json::to_string(&header)?;
+        content.push('\n');
+        for (id, block) in &self.blocks {
+            let record = BlockRecord {
+                id: *id,
+                block: block.clone(),
+            };
+            content.push_str(&serde_json::to_string(&record)?);
+            content.push('\n');
+        }
+        // Write to a temp file and rename so a crash never leaves half a file.
+        let tmp = self.path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, content)?;
+        std::fs::rename(&tmp, &self.path)?;
+        self.records = self.blocks.len();
+        Ok(())
+    }
+
+    /// Session files of this directory, most recently written first.
+    fn sessions(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut sessions: Vec<(SystemTime, PathBuf)> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "jsonl"))
+            .map(|p| {
+                let modified = p
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .unwrap_or(UNIX_EPOCH);
+                (modified, p)
+            })
+            .collect();
+        sessions.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+        Ok(sessions.into_iter().map(|(_, p)| p).collect())
+    }
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+/// Default directory for persisted blocks.
+fn default_store_dir() -> PathBuf {
+    dirs_next::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".elwood")
+        .join("blocks")
+}
+
+/// Stable, filename-safe key for a working directory.
+fn dir_key(cwd: &Path) -> String {
+    let digest = Sha256::digest(cwd.to_string_lossy().as_bytes());
+    digest.iter().take(8).map(|b| format!("{b:02x}")).collect()
+}
+
+/// Convert a monotonic `Instant` to wall-clock time relative to now.
+fn instant_to_utc(instant: Instant) -> DateTime<Utc> {
+    let ago =
+        chrono::Duration::from_std(instant.elapsed()).unwrap_or_else(|_| chrono::Duration::zero());
+    Utc::now() - ago
+}
+
+/// The last `max_bytes` of `s`, on a char boundary.
+fn tail(s: &str, max_bytes: usize) -> &str {
+    if s.len() <= max_bytes {
+        return s;
+    }
+    let mut start = s.len() - max_bytes;
+    while !s.is_char_boundary(start) {
+        start += 1;
+    }
+    &s[start..]
+}
+
+/// Read a session file: the latest snapshot of each block, oldest first,
+/// keeping at most [`MAX_PERSISTED_BLOCKS`].
+fn read_session(path: &Path) -> anyhow::Result<Vec<PersistedBlock>> {
+    let content = std::fs::read_to_string(path)?;
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+    let Some(header) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    let header: StoreHeader = serde_json::from_str(header)?;
+    if header.version > STORE_VERSION {
+        anyhow::bail!(
+            "Unsupported block store version {} in {}",
+            header.version,
+            path.display()
+        );
+    }
+    let mut blocks: Vec<(BlockId, PersistedBlock)> = Vec::new();
+    for line in lines {
+        // A crash mid-append can leave a torn last line; skip it
+        let Ok(record) = serde_json::from_str::<BlockRecord>(line) else {
+            continue;
+        };
+        match blocks.iter_mut().find(|(id, _)| *id == record.id) {
+            Some((_, existing)) => *existing = record.block,
+            None => blocks.push((record.id, record.block)),
+        }
+    }
+    Ok(prune(&blocks, MAX_PERSISTED_BLOCKS)
+        .into_iter()
+        .map(|(_, b)| b)
+        .collect())
+}
+
+/// Keep at most `max` blocks, dropping the oldest unbookmarked ones first.
+fn prune(blocks: &[(BlockId, PersistedBlock)], max: usize) -> Vec<(BlockId, PersistedBlock)> {
+    let mut excess = blocks.len().saturating_sub(max);
+    blocks
+        .iter()
+        .filter(|(_, b)| {
+            if excess > 0 && !b.bookmarked {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::BlockManager;
+
+    fn sample(command: &str, bookmarked: bool) -> PersistedBlock {
+        PersistedBlock {
+            command: Some(command.to_string()),
+            cwd: Some(PathBuf::from("/tmp/project")),
+            exit_code: Some(0),
+            started_at: Some(Utc::now() - chrono::Duration::seconds(3)),
+            finished_at: Some(Utc::now()),
+            output: format!("output of {command}"),
+            bookmarked,
+            collapsed: false,
+        }
+    }
+
+    fn store(dir: &Path, cwd: &str, session: &str) -> BlockStore {
+        BlockStore::with_dir(dir.to_path_buf(), Path::new(cwd), session)
+    }
+
+    fn line_count(store: &BlockStore) -> usize {
+        std::fs::read_to_string(store.path())
+            .unwrap()
+            .lines()
+            .count()
+    }
+
+    #[test]
+    fn test_upsert_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = store(dir.path(), "/tmp/project", "s1");
+        let blocks = vec![sample("ls", false), sample("cargo test", true)];
+        store.upsert(0, blocks[0].clone()).unwrap();
+        store.upsert(1, blocks[1].clone()).unwrap();
+
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded, blocks);
+        assert!(store.path().exists());
+    }
+
+    #[test]
+    fn test_upsert_appends_and_last_record_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = store(dir.path(), "/tmp/project", "s1");
+        let block = sample("make", false);
+        store.upsert(0, block.clone()).unwrap();
+        assert_eq!(line_count(&store), 2);
+
+        // Unchanged blocks are not written again
+        store.upsert(0, block.clone()).unwrap();
+        assert_eq!(line_count(&store), 2);
+
+        let bookmarked = PersistedBlock {
+            bookmarked: true,
+            ..block
+        };
+        store.upsert(0, bookmarked.clone()).unwrap();
+        assert_eq!(line_count(&store), 3);
+        assert_eq!(store.load().unwrap(), vec![bookmarked.clone()]);
+        assert_eq!(store.get(0), Some(&bookmarked));
+    }
+
+    #[test]
+    fn test_compacts_superseded_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = store(dir.path(), "/tmp/project", "s1");
+        let mut block = sample("watch", false);
+        for i in 0..MAX_PERSISTED_BLOCKS * 2 {
+            block.exit_code = Some(i as i32);
+            store.upsert(0, block.clone()).unwrap();
+        }
+        assert!(line_count(&store) < MAX_PERSISTED_BLOCKS * 2);
+        assert_eq!(store.load().unwrap(), vec![block]);
+    }
+
+    #[test]
+    fn test_skips_torn_last_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = store(dir.path(), "/tmp/project", "s1");
+        store.upsert(0, sample("ls", false)).unwrap();
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(store.path())
+            .unwrap();
+        file.write_all(b"{\"id\":1,\"comm").unwrap();
+        assert_eq!(store.load().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_load_missing_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path(), "/nowhere", "s1");
+        assert!(store.load().unwrap().is_empty());
+        assert!(store.load_previous().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sessions_keyed_by_cwd_and_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut a = store(dir.path(), "/a", "s1");
+        let b = store(dir.path(), "/b", "s1");
+        let a2 = store(dir.path(), "/a", "s2");
+        assert_ne!(a.path(), b.path());
+        assert_ne!(a.path(), a2.path());
+
+        // Two panes in the same directory don't overwrite each other
+        a.upsert(0, sample("in-a", false)).unwrap();
+        assert!(b.load().unwrap().is_empty());
+        assert!(a2.load().unwrap().is_empty());
+        assert!(b.load_previous().unwrap().is_empty());
+        assert_eq!(a2.load_previous().unwrap().len(), 1);
+        assert!(a.load_previous().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_prune_sessions_keeps_current() {
+        let dir = tempfile::tempdir().unwrap();
+        for session in ["s1", "s2", "s3"] {
+            store(dir.path(), "/a", session)
+                .upsert(0, sample(session, false))
+                .unwrap();
+        }
+        let current = store(dir.path(), "/a", "s1");
+        current.prune_sessions(1).unwrap();
+        let left = std::fs::read_dir(current.path().parent().unwrap())
+            .unwrap()
+            .count();
+        assert!(left <= 2);
+        assert!(current.path().exists());
+    }
+
+    #[test]
+    fn test_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = store(dir.path(), "/tmp/project", "s1");
+        store.upsert(0, sample("ls", false)).unwrap();
+        store.clear().unwrap();
+        assert!(!store.path().exists());
+        assert!(store.load().unwrap().is_empty());
+        assert!(store.get(0).is_none());
+    }
+
+    #[test]
+    fn test_rejects_future_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path(), "/tmp/project", "s1");
+        std::fs::create_dir_all(store.path().parent().unwrap()).unwrap();
+        std::fs::write(store.path(), "{\"version\": 99}\n").unwrap();
+        assert!(store.load().is_err());
+    }
+
+    #[test]
+    fn test_prune_keeps_bookmarks() {
+        let blocks: Vec<_> = [
+            sample("old-bookmarked", true),
+            sample("old", false),
+            sample("mid", false),
+            sample("new", false),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, b)| (i as BlockId, b))
+        .collect();
+        let pruned = prune(&blocks, 2);
+        let cmds: Vec<_> = pruned
+            .iter()
+            .map(|(_, b)| b.command.clone().unwrap())
+            .collect();
+        assert_eq!(cmds, vec!["old-bookmarked", "new"]);
+    }
+
+    #[test]
+    fn test_from_block_snapshot() {
+        let mut mgr = BlockManager::new();
+        mgr.push_command_block(0, "make", Some(PathBuf::from("/src")));
+        mgr.finish_block(Some(2));
+        mgr.toggle_bookmark_at(0);
+
+        let persisted = PersistedBlock::from_block(&mgr.blocks()[0], "error: boom");
+        assert_eq!(persisted.command.as_deref(), Some("make"));
+        assert_eq!(persisted.cwd, Some(PathBuf::from("/src")));
+        assert_eq!(persisted.exit_code, Some(2));
+        assert!(persisted.bookmarked);
+        assert!(persisted.started_at.is_some());
+        assert!(persisted.finished_at.is_some());
+        assert_eq!(persisted.output, "error: boom");
+    }
+
+    #[test]
+    fn test_from_block_truncates_output_tail() {
+        let mut mgr = BlockManager::new();
+        mgr.push_agent_block(0);
+        let output = format!("{}END", "x".repeat(MAX_OUTPUT_BYTES));
+        let persisted = PersistedBlock::from_block(&mgr.blocks()[0], &output);
+        assert_eq!(persisted.output.len(), MAX_OUTPUT_BYTES);
+        assert!(persisted.output.ends_with("END"));
+    }
+
+    #[test]
+    fn test_instants_preserve_duration() {
+        let block = sample("sleep 3", false);
+        let (start, end) = block.instants();
+        let elapsed = end.unwrap().duration_since(start.unwrap()).as_secs_f64();
+        assert!((elapsed - block.duration_secs().unwrap()).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_tail_char_boundary() {
+        assert_eq!(tail("héllo", 4), "llo");
+        assert_eq!(tail("abc", 10), "abc");
+    }
+}
@@ -0,0 +1,288 @@
+//! Text charts for agent replies.
+//!
+//! A fenced code block tagged `chart`, `sparkline` or `bar` whose body is
+//! JSON is drawn as a chart instead of code (see [`crate::markdown`]):
+//!
+//! ````text
+//! ```sparkline
+//! [3, 5, 2, 8, 6]
+//! ```
+//!
+//! ```chart
+//! {"type": "bar", "title": "Build time (s)", "labels": ["debug", "release"], "values": [42, 97]}
+//! ```
+//! ````
+//!
+//! The data may be a plain array of numbers, an array of `[label, value]`
+//! pairs or `{"label", "value"}` objects, or an object with `labels` and
+//! `values` (or `data`), plus optional `type` and `title`. Charts are drawn
+//! with block characters, so they work in any terminal.
+
+use serde_json::Value;
+
+use crate::theme;
+
+/// Eighths of a cell, for sparklines.
+const SPARK: [char; 8] = [
+    '\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}', '\u{2586}', '\u{2587}', '\u{2588}',
+];
+
+/// Eighths of a cell, for horizontal bars.
+const BAR_EIGHTHS: [char; 8] = [
+    ' ', '\u{258F}', '\u{258E}', '\u{258D}', '\u{258C}', '\u{258B}', '\u{258A}', '\u{2589}',
+];
+
+/// Widest a bar may get, in cells.
+const MAX_BAR_WIDTH: usize = 40;
+
+/// Longest label shown next to a bar.
+const MAX_LABEL_WIDTH: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartKind {
+    Sparkline,
+    Bar,
+}
+
+/// Chart data parsed from a code block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    pub kind: ChartKind,
+    pub title: Option<String>,
+    /// Labels are empty for unlabeled series.
+    pub points: Vec<(String, f64)>,
+}
+
+/// Whether a code block with this language tag holds chart data.
+pub fn is_chart_lang(lang: &str) -> bool {
+    matches!(lang.trim(), "chart" | "sparkline" | "bar")
+}
+
+impl Chart {
+    /// Parse the body of a chart code block. `None` if it isn't chart data.
+    pub fn parse(lang: &str, body: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(body.trim()).ok()?;
+        let (declared, title, data) = match &value {
+            Value::Object(map) => {
+                let declared = map.get("type").and_then(Value::as_str).map(str::to_string);
+                let title = map.get("title").and_then(Value::as_str).map(str::to_string);
+                let data = match (map.get("labels"), map.get("values").or(map.get("data"))) {
+                    (Some(Value::Array(labels)), Some(Value::Array(values))) => labels
+                        .iter()
+                        .zip(values)
+                        .map(|(l, v)| Some((label_text(l), v.as_f64()?)))
+                        .collect::<Option<Vec<_>>>()?,
+                    (None, Some(data)) => points(data)?,
+                    _ => return None,
+                };
+                (declared, title, data)
+            }
+            other => (None, None, points(other)?),
+        };
+        if data.is_empty() {
+            return None;
+        }
+
+        let labeled = data.iter().any(|(label, _)| !label.is_empty());
+        let kind = match declared.as_deref().unwrap_or(lang.trim()) {
+            "sparkline" | "line" => ChartKind::Sparkline,
+            "bar" => ChartKind::Bar,
+            _ if labeled => ChartKind::Bar,
+            _ => ChartKind::Sparkline,
+        };
+        Some(Self {
+            kind,
+            title,
+            points: data,
+        })
+    }
+
+    /// Render as ANSI text, one `\r\n`-terminated line per row.
+    pub fn render(&self) -> String {
+        let t = theme::active();
+        let r = t.reset();
+        let accent = t.ansi_fg(t.accent);
+        let muted = t.ansi_fg(t.fg_muted);
+        let title = t.ansi_bold_fg(t.fg_primary);
+
+        let mut out = String::new();
+        if let Some(ref text) = self.title {
+            out.push_str(&format!("  {title}{text}{r}\r\n"));
+        }
+        let values: Vec<f64> = self.points.iter().map(|(_, v)| *v).collect();
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        match self.kind {
+            ChartKind::Sparkline => {
+                out.push_str(&format!(
+                    "  {accent}{}{r}  {muted}min {} max {}{r}\r\n",
+                    sparkline(&values),
+                    format_value(min),
+                    format_value(max),
+                ));
+            }
+            ChartKind::Bar => {
+                let label_width = self
+                    .points
+                    .iter()
+                    .map(|(label, _)| label.chars().count().min(MAX_LABEL_WIDTH))
+                    .max()
+                    .unwrap_or(0);
+                for (label, value) in &self.points {
+                    let label: String = label.chars().take(MAX_LABEL_WIDTH).collect();
+                    let pad = label_width - label.chars().count();
+                    out.push_str(&format!(
+                        "  {label}{} {accent}{}{r} {muted}{}{r}\r\n",
+                        " ".repeat(pad),
+                        bar(*value, max.max(0.0), MAX_BAR_WIDTH),
+                        format_value(*value),
+                    ));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// `[1, 2]`, `[["a", 1], ["b", 2]]` or `[{"label": "a", "value": 1}]`.
+fn points(value: &Value) -> Option<Vec<(String, f64)>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|item| match item {
+            Value::Number(n) => Some((String::new(), n.as_f64()?)),
+            Value::Array(pair) if pair.len() == 2 => {
+                Some((label_text(&pair[0]), pair[1].as_f64()?))
+            }
+            Value::Object(map) => Some((
+                map.get("label").map(label_text).unwrap_or_default(),
+                map.get("value")?.as_f64()?,
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+fn label_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// `▁▃▂█` — one cell per value, scaled between the series' min and max.
+pub fn sparkline(values: &[f64]) -> String {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+    values
+        .iter()
+        .map(|v| {
+            if range <= 0.0 || !range.is_finite() {
+                SPARK[SPARK.len() / 2]
+            } else {
+                let level = ((v - min) / range * (SPARK.len() - 1) as f64).round() as usize;
+                SPARK[level.min(SPARK.len() - 1)]
+            }
+        })
+        .collect()
+}
+
+/// A horizontal bar `value / max` of `width` cells, in eighths of a cell.
+/// Negative values draw as an empty bar.
+fn bar(value: f64, max: f64, width: usize) -> String {
+    if max <= 0.0 || value <= 0.0 {
+        return String::new();
+    }
+    let eighths = ((value / max).min(1.0) * (width * 8) as f64).round() as usize;
+    let (full, part) = (eighths / 8, eighths % 8);
+    let mut out = "\u{2588}".repeat(full);
+    if part > 0 {
+        out.push(BAR_EIGHTHS[part]);
+    }
+    out
+}
+
+/// Whole numbers without a fraction, others to two places.
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip_ansi(s: &str) -> String {
+        let re = regex::Regex::new(r"\x1b\[[0-9;]*m").unwrap();
+        re.replace_all(s, "").into_owned()
+    }
+
+    #[test]
+    fn test_parse_forms() {
+        let chart = Chart::parse("sparkline", "[3, 5, 2]").unwrap();
+        assert_eq!(chart.kind, ChartKind::Sparkline);
+        assert_eq!(chart.points.len(), 3);
+
+        let chart = Chart::parse("chart", r#"[["debug", 42], ["release", 97]]"#).unwrap();
+        assert_eq!(chart.kind, ChartKind::Bar);
+        assert_eq!(chart.points[1], ("release".to_string(), 97.0));
+
+        let chart = Chart::parse(
+            "chart",
+            r#"{"type": "sparkline", "title": "Latency", "labels": ["a", "b"], "values": [1, 2]}"#,
+        )
+        .unwrap();
+        assert_eq!(chart.kind, ChartKind::Sparkline);
+        assert_eq!(chart.title.as_deref(), Some("Latency"));
+
+        let chart = Chart::parse("bar", r#"{"data": [{"label": "x", "value": 1.5}]}"#).unwrap();
+        assert_eq!(chart.points, vec![("x".to_string(), 1.5)]);
+    }
+
+    #[test]
+    fn test_parse_rejects_non_chart_data() {
+        assert!(Chart::parse("chart", "not json").is_none());
+        assert!(Chart::parse("chart", "[]").is_none());
+        assert!(Chart::parse("chart", r#"["a", "b"]"#).is_none());
+        assert!(Chart::parse("chart", r#"{"name": "x"}"#).is_none());
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0.0, 7.0, 3.5]), "\u{2581}\u{2588}\u{2585}");
+        assert_eq!(sparkline(&[2.0, 2.0]), "\u{2585}\u{2585}");
+    }
+
+    #[test]
+    fn test_bar() {
+        assert_eq!(bar(10.0, 10.0, 4), "\u{2588}".repeat(4));
+        assert_eq!(bar(5.0, 10.0, 3), "\u{2588}\u{258C}");
+        assert_eq!(bar(-1.0, 10.0, 4), "");
+    }
+
+    #[test]
+    fn test_render_bar_chart() {
+        let chart = Chart::parse(
+            "chart",
+            r#"{"title": "Build time", "labels": ["debug", "release"], "values": [42, 84]}"#,
+        )
+        .unwrap();
+        let plain = strip_ansi(&chart.render());
+        let lines: Vec<&str> = plain.split("\r\n").collect();
+        assert_eq!(lines[0], "  Build time");
+        assert!(lines[1].starts_with("  debug   \u{2588}"));
+        assert!(lines[1].ends_with(" 42"));
+        assert!(lines[2].starts_with(&format!("  release {}", "\u{2588}".repeat(MAX_BAR_WIDTH))));
+    }
+
+    #[test]
+    fn test_render_sparkline() {
+        let plain = strip_ansi(&Chart::parse("sparkline", "[1, 2.5]").unwrap().render());
+        assert_eq!(plain, "  \u{2581}\u{2588}  min 1 max 2.50\r\n");
+    }
+}
@@ -13,7 +13,9 @@
 //! | `/model`  | Show current model info                  |
 //! | `/export` | Export session (md/html/json/share)     |
 //! | `/import` | Import a session file                   |
-//! | `/compact`| Summarize conversation history           |
+//! | `/resume` | Continue a saved session in a new pane  |
+//! | `/sessions`| Search past sessions                    |
+//! | `/compact`| Compact history to free context          |
 //! | `/plan`   | Start plan mode                          |
 //! | `/diff`   | Show git diff of working directory       |
 //! | `/git`    | Git operations (status, stage, commit)  |
@@ -42,12 +44,89 @@ pub enum VimToggleAction {
     Status,
 }
 
+/// Action for the `/quickfix` slash command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickfixAction {
+    /// Rebuild the list from all panes and open the overlay.
+    Open,
+    /// Open the next location in the editor.
+    Next,
+    /// Open the previous location in the editor.
+    Prev,
+    /// Send every error in the selected file to the agent.
+    FixFile,
+}
+
+/// Action for the `/routing` slash command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClassifierAction {
+    /// Explain how the given text (or the last routed input) is classified.
+    Explain(Option<String>),
+    /// Forget every learned weight and recorded correction.
+    Reset,
+}
+
+/// Action for the `/predictions` slash command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PredictionsAction {
+    /// Show prediction accuracy per source.
+    Stats,
+    /// Forget learned command history and accuracy stats.
+    Reset,
+}
+
+/// Action for the `/autocorrect` slash command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutocorrectAction {
+    /// Turn correction suggestions on.
+    On,
+    /// Turn correction suggestions off.
+    Off,
+    /// Show whether autocorrect is on, what it learned and the never list.
+    Status,
+    /// Never correct commands starting with this word.
+    Never(String),
+    /// Take a command off the never-correct list.
+    Allow(String),
+    /// Forget every learned correction.
+    Forget,
+}
+
+/// Action for the `/notifications` slash command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationsAction {
+    /// Open the notification history overlay.
+    Open,
+    /// Forget past notifications.
+    Clear,
+}
+
+/// Action for the `/sessions` slash command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionsAction {
+    /// Search saved sessions; an empty query lists the most recent ones.
+    Search(String),
+    /// Rebuild the search index from scratch.
+    Reindex,
+}
+
+/// Action for the `/jobs` slash command on a single job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobAction {
+    /// Follow the job's live output in a split pane.
+    Foreground(u32),
+    /// Hold the agent turn until the job finishes, then report its result.
+    Wait(u32),
+    /// Show the path of the job's log file.
+    Log(u32),
+}
+
 /// Result of executing a slash command.
 #[derive(Debug, Clone)]
 pub enum CommandResult {
     /// Display a message in the chat area (informational output).
     ChatMessage(String),
-    /// Send a request to the agent (e.g. /compact, /plan).
+    /// Send a request to the agent (e.g. /plan).
     AgentRequest(AgentRequest),
     /// Clear the chat history (scroll buffer).
     ClearChat,
@@ -65,6 +144,13 @@ pub enum CommandResult {
         /// Path to the session file.
         path: String,
     },
+    /// Continue a saved session in a fresh pane (`/resume`).
+    ResumeSession {
+        /// Path to the session file.
+        path: String,
+    },
+    /// Compact the agent's conversation history (`/compact`).
+    CompactContext,
     /// Open the interactive diff viewer.
     OpenDiffViewer { staged: bool },
     /// Open the interactive staging view (`/git stage`).
@@ -87,6 +173,16 @@ pub enum CommandResult {
     RecordPause,
     /// Resume recording (`/record resume`).
     RecordResume,
+    /// Render a recording to an animated SVG (`/record export`).
+    RecordExport {
+        source: String,
+        output: Option<String>,
+        from_marker: Option<String>,
+        to_marker: Option<String>,
+        max_idle: Option<f64>,
+    },
+    /// Replay a recording in the pane (`/replay <file>`).
+    Replay { path: String },
     /// Switch to a named model (`/model <name>`).
     SwitchModel { model_name: String },
     /// List saved plans (`/plan list`).
@@ -97,6 +193,11 @@ pub enum CommandResult {
     ListBookmarks,
     /// Export a specific block as markdown (`/export block [index]`).
     ExportBlock { index: Option<usize> },
+    /// Perform an action on a block's content (`/block <action> [index]`).
+    BlockAction {
+        action: crate::block::BlockAction,
+        index: Option<usize>,
+    },
     /// Workflow command result (display message or run steps).
     WorkflowResult(crate::workflow::WorkflowCommandResult),
     /// Open the background jobs panel (`/jobs`).
@@ -105,12 +206,18 @@ pub enum CommandResult {
     RunBackground { command: String },
     /// Kill a running background job (`/kill <id>`).
     KillJob { job_id: u32 },
+    /// Act on a single background job (`/jobs fg|wait|log <id>`).
+    Job(JobAction),
     /// Secret redaction command result (`/redact`).
     RedactCommand(String),
     /// Launch config command result (`/launch`).
     LaunchCommand(crate::launch_config::LaunchCommandResult),
     /// Autocorrect command result (`/autocorrect`).
-    AutocorrectCommand(String),
+    AutocorrectCommand(AutocorrectAction),
+    /// Notification history (`/notifications`).
+    Notifications(NotificationsAction),
+    /// Saved session search (`/sessions`).
+    Sessions(SessionsAction),
     /// Refresh known commands cache (`/rehash`).
     RehashCommands,
     /// Agent management command (`/agent`, `/agents`, `/tell`).
@@ -123,6 +230,14 @@ pub enum CommandResult {
     NotebookCommand(crate::notebook::NotebookCommand),
     /// Toggle or query vim mode (`/vim on|off|status`).
     VimToggle(VimToggleAction),
+    /// Quickfix list across panes (`/quickfix [next|prev|fix]`).
+    Quickfix(QuickfixAction),
+    /// Inspect or reset the NL-vs-shell classifier (`/routing`).
+    Classifier(ClassifierAction),
+    /// Re-send the last auto-routed input the other way (`/reroute`).
+    Reroute,
+    /// Next-command prediction stats (`/predictions [stats|reset]`).
+    Predictions(PredictionsAction),
     /// Unknown or invalid command.
     Unknown(String),
 }
@@ -155,9 +270,19 @@ pub fn get_commands() -> Vec<SlashCommand> {
             description: "Import a session file",
             usage: "/import <path>",
         },
+        SlashCommand {
+            name: "resume",
+            description: "Continue a saved session in a new pane",
+            usage: "/resume <path>",
+        },
+        SlashCommand {
+            name: "sessions",
+            description: "Search past sessions (open, resume, re-export)",
+            usage: "/sessions [search <query>|reindex]",
+        },
         SlashCommand {
             name: "compact",
-            description: "Summarize conversation history",
+            description: "Summarize old turns and drop stale context",
             usage: "/compact",
         },
         SlashCommand {
@@ -185,10 +310,20 @@ pub fn get_commands() -> Vec<SlashCommand> {
             description: "List bookmarked blocks",
             usage: "/bookmarks",
         },
+        SlashCommand {
+            name: "block",
+            description: "Copy, rerun, send, save or export a block",
+            usage: "/block <copy-cmd|copy|rerun|send|save|html> [index] [path]",
+        },
         SlashCommand {
             name: "record",
-            description: "Terminal recording (start/stop/pause/resume)",
-            usage: "/record <start [file]|stop|pause|resume>",
+            description: "Terminal recording (start/stop/pause/resume/export)",
+            usage: "/record <start [file]|stop|pause|resume|export <file> [out.svg]>",
+        },
+        SlashCommand {
+            name: "replay",
+            description: "Replay a recording (play/pause, seek, markers)",
+            usage: "/replay <file>",
         },
         SlashCommand {
             name: "workflow",
@@ -197,8 +332,8 @@ pub fn get_commands() -> Vec<SlashCommand> {
         },
         SlashCommand {
             name: "jobs",
-            description: "Show background jobs panel",
-            usage: "/jobs",
+            description: "Background jobs (list/fg/wait/log)",
+            usage: "/jobs [fg|wait|log] <id>",
         },
         SlashCommand {
             name: "bg",
@@ -222,12 +357,17 @@ pub fn get_commands() -> Vec<SlashCommand> {
         },
         SlashCommand {
             name: "autocorrect",
-            description: "Toggle command auto-correction (on/off)",
-            usage: "/autocorrect [on|off]",
+            description: "Command auto-correction (on/off, never-correct list)",
+            usage: "/autocorrect [on|off|never <cmd>|allow <cmd>|forget]",
+        },
+        SlashCommand {
+            name: "notifications",
+            description: "Past notifications and their actions",
+            usage: "/notifications [clear]",
         },
         SlashCommand {
             name: "rehash",
-            description: "Refresh known commands cache",
+            description: "Refresh known commands, aliases and functions",
             usage: "/rehash",
         },
         SlashCommand {
@@ -265,6 +405,26 @@ pub fn get_commands() -> Vec<SlashCommand> {
             description: "Toggle vim mode (on/off/status)",
             usage: "/vim [on|off]",
         },
+        SlashCommand {
+            name: "quickfix",
+            description: "Errors from all panes by file (next/prev/fix)",
+            usage: "/quickfix [next|prev|fix]",
+        },
+        SlashCommand {
+            name: "routing",
+            description: "Explain how input is routed to shell or agent",
+            usage: "/routing [text|reset]",
+        },
+        SlashCommand {
+            name: "reroute",
+            description: "Re-send the last input the other way and learn from it",
+            usage: "/reroute",
+        },
+        SlashCommand {
+            name: "predictions",
+            description: "Next-command prediction accuracy (stats/reset)",
+            usage: "/predictions [stats|reset]",
+        },
     ]
 }
 
@@ -301,17 +461,20 @@ pub fn execute_command(name: &str, args: &str, model_name: &str) -> CommandResul
         "model" => execute_model(args, model_name),
         "export" => execute_export(args),
         "import" => execute_import(args),
+        "resume" => execute_resume(args),
         "compact" => execute_compact(),
         "plan" => execute_plan(args),
         "diff" => execute_diff(args),
         "git" => execute_git(args),
         "panes" => CommandResult::ListPanes,
         "bookmarks" => CommandResult::ListBookmarks,
+        "block" => execute_block(args),
         "record" => execute_record(args),
+        "replay" => execute_replay(args),
         "workflow" | "wf" => {
             CommandResult::WorkflowResult(crate::workflow::execute_workflow_command(args))
         }
-        "jobs" => CommandResult::OpenJobsPanel,
+        "jobs" => execute_jobs(args),
         "bg" => execute_bg(args),
         "kill" => execute_kill(args),
         "redact" => {
@@ -327,6 +490,8 @@ pub fn execute_command(name: &str, args: &str, model_name: &str) -> CommandResul
             )
         }
         "autocorrect" => execute_autocorrect(args),
+        "notifications" => execute_notifications(args),
+        "sessions" => execute_sessions(args),
         "rehash" => CommandResult::RehashCommands,
         "agent" => execute_agent(args),
         "agents" => CommandResult::AgentCommand(crate::multi_agent::AgentCommand::List),
@@ -346,6 +511,10 @@ pub fn execute_command(name: &str, args: &str, model_name: &str) -> CommandResul
             }
         }
         "vim" => execute_vim(args),
+        "quickfix" | "qf" => execute_quickfix(args),
+        "routing" => execute_routing(args),
+        "reroute" => CommandResult::Reroute,
+        "predictions" => execute_predictions(args),
         _ => CommandResult::Unknown(name.to_string()),
     }
 }
@@ -366,7 +535,11 @@ fn execute_help() -> CommandResult {
     msg.push_str("  Ctrl+F       Quick-fix last error\n");
     msg.push_str("  Ctrl+Up/Down Navigate blocks\n");
     msg.push_str("  Shift+Enter  New line in input\n");
+    msg.push_str("  Ctrl+X Ctrl+E Edit input in $EDITOR\n");
+    msg.push_str("  Alt+Q        Quickfix list (Alt+] / Alt+[ next/prev)\n");
+    msg.push_str("  Alt+N        Notification history\n");
     msg.push_str("  !command     Run shell command (Agent mode)\n");
+    msg.push_str("  Ctrl+Enter   Send the other way than auto-detect would\n");
     msg.push_str("  @file        Attach file context to prompt\n");
     CommandResult::ChatMessage(msg)
 }
@@ -448,7 +621,7 @@ fn execute_import(args: &str) -> CommandResult {
     let path = args.trim();
     if path.is_empty() {
         return CommandResult::ChatMessage(
-            "Usage: /import <path>\n\nSupported formats: .json, .elwood-session".to_string(),
+            "Usage: /import <path>\n\nSupported formats: .json, .md, .elwood-session".to_string(),
         );
     }
     CommandResult::ImportSession {
@@ -456,13 +629,26 @@ fn execute_import(args: &str) -> CommandResult {
     }
 }
 
-/// `/compact` — ask the agent to summarize conversation history.
+/// `/resume <path>` — continue a saved session in a fresh pane.
+fn execute_resume(args: &str) -> CommandResult {
+    let path = args.trim();
+    if path.is_empty() {
+        return CommandResult::ChatMessage(
+            "Usage: /resume <path>\n\n\
+             Restores the conversation, tool history, model and plan of a saved\n\
+             session (.json or .md) into a new pane. Use /sessions to find one."
+                .to_string(),
+        );
+    }
+    CommandResult::ResumeSession {
+        path: path.to_string(),
+    }
+}
+
+/// `/compact` — fold old turns into a summary and drop stale attachments,
+/// pane snapshots and tool output (see [`crate::context_window`]).
 fn execute_compact() -> CommandResult {
-    CommandResult::AgentRequest(AgentRequest::SendMessage {
-        content: "Please provide a concise summary of our conversation so far, \
-                  highlighting the key decisions, changes made, and current state."
-            .to_string(),
-    })
+    CommandResult::CompactContext
 }
 
 /// `/plan [list|resume <id>|<description>]` — plan mode with subcommands.
@@ -564,12 +750,15 @@ fn execute_record(args: &str) -> CommandResult {
         "stop" => CommandResult::RecordStop,
         "pause" => CommandResult::RecordPause,
         "resume" => CommandResult::RecordResume,
+        "export" => parse_record_export(sub_args),
         "" => {
             let help = "\
 /record start [file]  Begin recording (asciinema v2 format)\n\
 /record stop          Stop and save recording\n\
 /record pause         Pause recording\n\
-/record resume        Resume recording";
+/record resume        Resume recording\n\
+/record export <file> [out.svg] [--from <marker>] [--to <marker>] [--idle <secs>]\n\
+                      Render a recording to an animated SVG";
             CommandResult::ChatMessage(help.to_string())
         }
         other => CommandResult::ChatMessage(format!(
@@ -578,6 +767,106 @@ fn execute_record(args: &str) -> CommandResult {
     }
 }
 
+/// `/record export <file> [out.svg] [--from <marker>] [--to <marker>] [--idle <secs>]`
+fn parse_record_export(args: &str) -> CommandResult {
+    let usage = || {
+        CommandResult::ChatMessage(
+            "Usage: /record export <file> [out.svg] [--from <marker>] [--to <marker>] [--idle <secs>]"
+                .to_string(),
+        )
+    };
+
+    let mut positional = Vec::new();
+    let mut from_marker = None;
+    let mut to_marker = None;
+    let mut max_idle = None;
+    let mut tokens = args.split_whitespace();
+    while let Some(token) = tokens.next() {
+        match token {
+            "--from" | "--to" | "--idle" => {
+                let Some(value) = tokens.next() else {
+                    return usage();
+                };
+                match token {
+                    "--from" => from_marker = Some(value.to_string()),
+                    "--to" => to_marker = Some(value.to_string()),
+                    _ => match value.parse::<f64>() {
+                        Ok(secs) if secs >= 0.0 => max_idle = Some(secs),
+                        _ => return usage(),
+                    },
+                }
+            }
+            other => positional.push(other.to_string()),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let Some(source) = positional.next() else {
+        return usage();
+    };
+    let output = positional.next();
+    if positional.next().is_some() {
+        return usage();
+    }
+    CommandResult::RecordExport {
+        source,
+        output,
+        from_marker,
+        to_marker,
+        max_idle,
+    }
+}
+
+/// `/block <action> [index] [path]` — act on a block's command or output.
+///
+/// Without an index the pane uses the selected block, or the last one.
+fn execute_block(args: &str) -> CommandResult {
+    use crate::block::BlockAction;
+
+    let mut parts = args.split_whitespace();
+    let action_name = parts.next().unwrap_or("");
+    let mut rest: Vec<&str> = parts.collect();
+    let index = match rest.first().and_then(|s| s.parse::<usize>().ok()) {
+        Some(i) => {
+            rest.remove(0);
+            Some(i)
+        }
+        None => None,
+    };
+    let path = if rest.is_empty() {
+        None
+    } else {
+        Some(std::path::PathBuf::from(rest.join(" ")))
+    };
+
+    let action = match action_name {
+        "copy-cmd" | "copy-command" => BlockAction::CopyCommand,
+        "copy" | "copy-output" => BlockAction::CopyOutput,
+        "rerun" | "run" => BlockAction::Rerun,
+        "send" | "agent" => BlockAction::SendToAgent,
+        "save" => BlockAction::SaveOutput(path),
+        "html" => BlockAction::ExportHtml(path),
+        "" => {
+            let help = "\
+/block copy-cmd [N]       Copy the command only\n\
+/block copy [N]           Copy the output only\n\
+/block rerun [N]          Rerun the command in its original directory\n\
+/block send [N]           Send command + output to the agent\n\
+/block save [N] [path]    Save the output to a text file\n\
+/block html [N] [path]    Export the block as an HTML snippet\n\
+\n\
+N is a block index (see /bookmarks); defaults to the selected or last block.";
+            return CommandResult::ChatMessage(help.to_string());
+        }
+        other => {
+            return CommandResult::ChatMessage(format!(
+                "Unknown block action: {other}\nType /block for available actions."
+            ))
+        }
+    };
+    CommandResult::BlockAction { action, index }
+}
+
 /// `/bg <command>` — run a command in the background.
 fn execute_bg(args: &str) -> CommandResult {
     let command = args.trim();
@@ -593,6 +882,48 @@ fn execute_bg(args: &str) -> CommandResult {
     }
 }
 
+/// `/replay <file>` — play back an asciinema recording in the pane.
+fn execute_replay(args: &str) -> CommandResult {
+    let path = args.trim();
+    if path.is_empty() {
+        return CommandResult::ChatMessage(
+            "Usage: /replay <file>\n\n\
+             Plays a .cast recording (a path, or a name in ~/.elwood/recordings/).\n\
+             Space play/pause   \u{2190}/\u{2192} seek 5s   [ / ] previous/next marker\n\
+             - / + speed        0 restart             q or Esc quit"
+                .to_string(),
+        );
+    }
+    CommandResult::Replay {
+        path: path.to_string(),
+    }
+}
+
+/// `/jobs [fg|wait|log] <id>` — list background jobs or act on one.
+fn execute_jobs(args: &str) -> CommandResult {
+    let mut parts = args.split_whitespace();
+    let sub = parts.next().unwrap_or("");
+    let job_id = parts
+        .next()
+        .and_then(|s| s.trim_start_matches('#').parse::<u32>().ok())
+        .filter(|&id| id > 0);
+
+    match (sub, job_id) {
+        ("" | "list", _) => CommandResult::OpenJobsPanel,
+        ("fg" | "foreground", Some(id)) => CommandResult::Job(JobAction::Foreground(id)),
+        ("wait", Some(id)) => CommandResult::Job(JobAction::Wait(id)),
+        ("log" | "logs", Some(id)) => CommandResult::Job(JobAction::Log(id)),
+        _ => CommandResult::ChatMessage(
+            "Usage: /jobs [fg|wait|log] <id>\n\n\
+             /jobs            List background jobs\n\
+             /jobs fg <id>    Follow a job's live output in a split pane\n\
+             /jobs wait <id>  Wait for a job, then hand its result to the agent\n\
+             /jobs log <id>   Show the path of a job's log file"
+                .to_string(),
+        ),
+    }
+}
+
 /// `/kill <job_id>` — kill a running background job.
 fn execute_kill(args: &str) -> CommandResult {
     let id_str = args.trim();
@@ -608,15 +939,79 @@ fn execute_kill(args: &str) -> CommandResult {
     }
 }
 
-/// `/autocorrect [on|off]` — toggle command auto-correction.
+/// `/autocorrect [on|off|never <cmd>|allow <cmd>|forget]` — control command
+/// auto-correction.
 fn execute_autocorrect(args: &str) -> CommandResult {
+    let (sub, rest) = args
+        .trim()
+        .split_once(char::is_whitespace)
+        .map(|(s, r)| (s, r.trim()))
+        .unwrap_or((args.trim(), ""));
+    let action = match (sub, rest) {
+        ("on", "") => AutocorrectAction::On,
+        ("off", "") => AutocorrectAction::Off,
+        ("" | "status", "") => AutocorrectAction::Status,
+        ("never", cmd) if !cmd.contains(char::is_whitespace) && !cmd.is_empty() => {
+            AutocorrectAction::Never(cmd.to_string())
+        }
+        ("allow", cmd) if !cmd.contains(char::is_whitespace) && !cmd.is_empty() => {
+            AutocorrectAction::Allow(cmd.to_string())
+        }
+        ("forget", "") => AutocorrectAction::Forget,
+        _ => {
+            return CommandResult::ChatMessage(
+                "Usage: /autocorrect [on|off|never <cmd>|allow <cmd>|forget]\n\n\
+                 Failed commands get a suggested fix (Tab accepts it). Fixes you\n\
+                 accept repeatedly are applied automatically.\n\
+                 /autocorrect              Show status and what was learned\n\
+                 /autocorrect never <cmd>  Never correct <cmd>\n\
+                 /autocorrect allow <cmd>  Correct <cmd> again\n\
+                 /autocorrect forget       Forget learned corrections"
+                    .to_string(),
+            )
+        }
+    };
+    CommandResult::AutocorrectCommand(action)
+}
+
+/// `/notifications [clear]` — browse or clear the notification history.
+fn execute_notifications(args: &str) -> CommandResult {
     match args.trim() {
-        "on" => CommandResult::AutocorrectCommand("on".to_string()),
-        "off" => CommandResult::AutocorrectCommand("off".to_string()),
-        "" => CommandResult::AutocorrectCommand("status".to_string()),
-        other => CommandResult::ChatMessage(format!(
-            "Unknown autocorrect option: {other}\nUsage: /autocorrect [on|off]"
-        )),
+        "" | "open" => CommandResult::Notifications(NotificationsAction::Open),
+        "clear" => CommandResult::Notifications(NotificationsAction::Clear),
+        _ => CommandResult::ChatMessage(
+            "Usage: /notifications [clear]\n\n\
+             Every toast is kept here after it disappears.\n\
+             /notifications        Open the history (Alt+N); Enter runs a toast's action\n\
+             /notifications clear  Forget past notifications"
+                .to_string(),
+        ),
+    }
+}
+
+/// `/sessions [search <query>|reindex]` — search exported and auto-saved sessions.
+fn execute_sessions(args: &str) -> CommandResult {
+    let (subcmd, rest) = match args.trim().split_once(char::is_whitespace) {
+        Some((cmd, rest)) => (cmd, rest.trim()),
+        None => (args.trim(), ""),
+    };
+    match subcmd {
+        "" | "recent" if rest.is_empty() => {
+            CommandResult::Sessions(SessionsAction::Search(String::new()))
+        }
+        "search" if !rest.is_empty() => {
+            CommandResult::Sessions(SessionsAction::Search(rest.to_string()))
+        }
+        "reindex" if rest.is_empty() => CommandResult::Sessions(SessionsAction::Reindex),
+        _ => CommandResult::ChatMessage(
+            "Usage: /sessions [search <query>|reindex]\n\n\
+             Searches sessions saved in ~/.elwood/sessions (exports and auto-saves).\n\
+             /sessions                 List recent sessions\n\
+             /sessions search <query>  Find sessions by prompt, reply, command, tool or file\n\
+             /sessions reindex         Rebuild the search index\n\n\
+             In the list: Enter opens the file, r resumes, e re-exports as HTML"
+                .to_string(),
+        ),
     }
 }
 
@@ -663,6 +1058,58 @@ fn execute_vim(args: &str) -> CommandResult {
     }
 }
 
+/// `/routing [text|reset]` — show the classifier's decision for some input.
+fn execute_routing(args: &str) -> CommandResult {
+    match args.trim() {
+        "" => CommandResult::Classifier(ClassifierAction::Explain(None)),
+        "reset" => CommandResult::Classifier(ClassifierAction::Reset),
+        "help" | "--help" => CommandResult::ChatMessage(
+            "Usage: /routing [text|reset]\n\n\
+             Show how input is routed between the shell and the agent.\n\
+             /routing         Explain the last auto-routed input\n\
+             /routing <text>  Explain how <text> would be routed\n\
+             /routing reset   Forget everything learned from corrections\n\n\
+             Corrections are learned from !command, Ctrl+Enter and /reroute."
+                .to_string(),
+        ),
+        text => CommandResult::Classifier(ClassifierAction::Explain(Some(text.to_string()))),
+    }
+}
+
+/// `/predictions [stats|reset]` — inspect or reset next-command predictions.
+fn execute_predictions(args: &str) -> CommandResult {
+    match args.trim() {
+        "" | "stats" => CommandResult::Predictions(PredictionsAction::Stats),
+        "reset" => CommandResult::Predictions(PredictionsAction::Reset),
+        _ => CommandResult::ChatMessage(
+            "Usage: /predictions [stats|reset]\n\n\
+             Ghost-text predictions learn from the commands you run.\n\
+             /predictions         Show how often predictions matched\n\
+             /predictions reset   Forget learned history and stats"
+                .to_string(),
+        ),
+    }
+}
+
+/// `/quickfix [next|prev|fix]` — navigate errors detected across panes.
+fn execute_quickfix(args: &str) -> CommandResult {
+    match args.trim() {
+        "" | "open" | "list" => CommandResult::Quickfix(QuickfixAction::Open),
+        "next" | "n" => CommandResult::Quickfix(QuickfixAction::Next),
+        "prev" | "p" => CommandResult::Quickfix(QuickfixAction::Prev),
+        "fix" => CommandResult::Quickfix(QuickfixAction::FixFile),
+        _ => CommandResult::ChatMessage(
+            "Usage: /quickfix [next|prev|fix]\n\n\
+             Errors with file locations from all panes, grouped by file.\n\
+             /quickfix        Open the list (Alt+Q)\n\
+             /quickfix next   Open the next error in your editor (Alt+])\n\
+             /quickfix prev   Open the previous error (Alt+[)\n\
+             /quickfix fix    Ask the agent to fix every error in the current file"
+                .to_string(),
+        ),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -785,7 +1232,7 @@ mod tests {
     #[test]
     fn test_execute_compact() {
         let result = execute_command("compact", "", "");
-        assert!(matches!(result, CommandResult::AgentRequest(_)));
+        assert!(matches!(result, CommandResult::CompactContext));
     }
 
     #[test]
@@ -1053,6 +1500,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_execute_resume() {
+        match execute_command("resume", " ~/.elwood/sessions/a.json ", "") {
+            CommandResult::ResumeSession { path } => {
+                assert_eq!(path, "~/.elwood/sessions/a.json");
+            }
+            other => panic!("expected ResumeSession, got {other:?}"),
+        }
+        match execute_command("resume", "", "") {
+            CommandResult::ChatMessage(msg) => assert!(msg.contains("Usage: /resume")),
+            other => panic!("expected ChatMessage, got {other:?}"),
+        }
+    }
+
     #[test]
     fn test_get_commands_has_import() {
         let commands = get_commands();
@@ -1101,10 +1562,264 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_execute_block_actions() {
+        use crate::block::BlockAction;
+        let cases = [
+            ("copy-cmd", BlockAction::CopyCommand),
+            ("copy", BlockAction::CopyOutput),
+            ("rerun", BlockAction::Rerun),
+            ("send", BlockAction::SendToAgent),
+            ("save", BlockAction::SaveOutput(None)),
+            ("html", BlockAction::ExportHtml(None)),
+        ];
+        for (arg, expected) in cases {
+            match execute_command("block", arg, "") {
+                CommandResult::BlockAction { action, index } => {
+                    assert_eq!(action, expected);
+                    assert_eq!(index, None);
+                }
+                other => panic!("expected BlockAction for {arg}, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_execute_block_with_index_and_path() {
+        use crate::block::BlockAction;
+        match execute_command("block", "save 2 /tmp/out.txt", "") {
+            CommandResult::BlockAction { action, index } => {
+                assert_eq!(index, Some(2));
+                assert_eq!(
+                    action,
+                    BlockAction::SaveOutput(Some(std::path::PathBuf::from("/tmp/out.txt")))
+                );
+            }
+            other => panic!("expected BlockAction, got {other:?}"),
+        }
+        match execute_command("block", "html /tmp/b.html", "") {
+            CommandResult::BlockAction { action, index } => {
+                assert_eq!(index, None);
+                assert_eq!(
+                    action,
+                    BlockAction::ExportHtml(Some(std::path::PathBuf::from("/tmp/b.html")))
+                );
+            }
+            other => panic!("expected BlockAction, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_block_help_and_unknown() {
+        match execute_command("block", "", "") {
+            CommandResult::ChatMessage(msg) => assert!(msg.contains("/block rerun")),
+            other => panic!("expected ChatMessage, got {other:?}"),
+        }
+        match execute_command("block", "frobnicate", "") {
+            CommandResult::ChatMessage(msg) => assert!(msg.contains("Unknown block action")),
+            other => panic!("expected ChatMessage, got {other:?}"),
+        }
+    }
+
     #[test]
     fn test_get_commands_has_bookmarks() {
         let commands = get_commands();
         let names: Vec<&str> = commands.iter().map(|c| c.name).collect();
         assert!(names.contains(&"bookmarks"));
     }
+
+    #[test]
+    fn test_execute_quickfix() {
+        assert!(matches!(
+            execute_command("quickfix", "", ""),
+            CommandResult::Quickfix(QuickfixAction::Open)
+        ));
+        assert!(matches!(
+            execute_command("qf", "next", ""),
+            CommandResult::Quickfix(QuickfixAction::Next)
+        ));
+        assert!(matches!(
+            execute_command("quickfix", "p", ""),
+            CommandResult::Quickfix(QuickfixAction::Prev)
+        ));
+        assert!(matches!(
+            execute_command("quickfix", "fix", ""),
+            CommandResult::Quickfix(QuickfixAction::FixFile)
+        ));
+        match execute_command("quickfix", "bogus", "") {
+            CommandResult::ChatMessage(msg) => assert!(msg.contains("Usage: /quickfix")),
+            other => panic!("expected ChatMessage, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_routing() {
+        assert!(matches!(
+            execute_command("routing", "", ""),
+            CommandResult::Classifier(ClassifierAction::Explain(None))
+        ));
+        match execute_command("routing", "make coffee", "") {
+            CommandResult::Classifier(ClassifierAction::Explain(Some(text))) => {
+                assert_eq!(text, "make coffee")
+            }
+            other => panic!("expected Explain, got {other:?}"),
+        }
+        assert!(matches!(
+            execute_command("routing", "reset", ""),
+            CommandResult::Classifier(ClassifierAction::Reset)
+        ));
+        assert!(matches!(
+            execute_command("reroute", "", ""),
+            CommandResult::Reroute
+        ));
+    }
+
+    #[test]
+    fn test_execute_predictions() {
+        assert!(matches!(
+            execute_command("predictions", "", ""),
+            CommandResult::Predictions(PredictionsAction::Stats)
+        ));
+        assert!(matches!(
+            execute_command("predictions", "reset", ""),
+            CommandResult::Predictions(PredictionsAction::Reset)
+        ));
+        match execute_command("predictions", "bogus", "") {
+            CommandResult::ChatMessage(msg) => assert!(msg.contains("Usage: /predictions")),
+            other => panic!("expected ChatMessage, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_autocorrect() {
+        assert!(matches!(
+            execute_command("autocorrect", "", ""),
+            CommandResult::AutocorrectCommand(AutocorrectAction::Status)
+        ));
+        assert!(matches!(
+            execute_command("autocorrect", "off", ""),
+            CommandResult::AutocorrectCommand(AutocorrectAction::Off)
+        ));
+        match execute_command("autocorrect", "never  sl", "") {
+            CommandResult::AutocorrectCommand(AutocorrectAction::Never(cmd)) => {
+                assert_eq!(cmd, "sl")
+            }
+            other => panic!("expected Never, got {other:?}"),
+        }
+        for bad in ["never", "never git push", "on please", "bogus"] {
+            match execute_command("autocorrect", bad, "") {
+                CommandResult::ChatMessage(msg) => assert!(msg.contains("Usage: /autocorrect")),
+                other => panic!("expected ChatMessage for {bad:?}, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_execute_sessions() {
+        assert!(matches!(
+            execute_command("sessions", "", ""),
+            CommandResult::Sessions(SessionsAction::Search(q)) if q.is_empty()
+        ));
+        assert!(matches!(
+            execute_command("sessions", "search  cargo test ", ""),
+            CommandResult::Sessions(SessionsAction::Search(q)) if q == "cargo test"
+        ));
+        assert!(matches!(
+            execute_command("sessions", "reindex", ""),
+            CommandResult::Sessions(SessionsAction::Reindex)
+        ));
+        for bad in ["search", "bogus", "reindex now"] {
+            match execute_command("sessions", bad, "") {
+                CommandResult::ChatMessage(msg) => assert!(msg.contains("Usage: /sessions")),
+                other => panic!("expected ChatMessage, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_execute_notifications() {
+        assert!(matches!(
+            execute_command("notifications", "", ""),
+            CommandResult::Notifications(NotificationsAction::Open)
+        ));
+        assert!(matches!(
+            execute_command("notifications", "clear", ""),
+            CommandResult::Notifications(NotificationsAction::Clear)
+        ));
+        match execute_command("notifications", "bogus", "") {
+            CommandResult::ChatMessage(msg) => assert!(msg.contains("Usage: /notifications")),
+            other => panic!("expected ChatMessage, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_record_export() {
+        match execute_command(
+            "record",
+            "export demo.cast out.svg --from prompt --idle 0.5",
+            "",
+        ) {
+            CommandResult::RecordExport {
+                source,
+                output,
+                from_marker,
+                to_marker,
+                max_idle,
+            } => {
+                assert_eq!(source, "demo.cast");
+                assert_eq!(output.as_deref(), Some("out.svg"));
+                assert_eq!(from_marker.as_deref(), Some("prompt"));
+                assert_eq!(to_marker, None);
+                assert_eq!(max_idle, Some(0.5));
+            }
+            other => panic!("expected RecordExport, got {other:?}"),
+        }
+        for bad in [
+            "export",
+            "export a b c",
+            "export a --to",
+            "export a --idle x",
+        ] {
+            match execute_command("record", bad, "") {
+                CommandResult::ChatMessage(msg) => assert!(msg.contains("Usage: /record export")),
+                other => panic!("expected usage for {bad:?}, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_execute_replay() {
+        match execute_command("replay", " demo.cast ", "") {
+            CommandResult::Replay { path } => assert_eq!(path, "demo.cast"),
+            other => panic!("expected Replay, got {other:?}"),
+        }
+        match execute_command("replay", "", "") {
+            CommandResult::ChatMessage(msg) => assert!(msg.contains("Usage: /replay")),
+            other => panic!("expected ChatMessage, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_jobs() {
+        assert!(matches!(
+            execute_command("jobs", "", ""),
+            CommandResult::OpenJobsPanel
+        ));
+        assert!(matches!(
+            execute_command("jobs", "fg #2", ""),
+            CommandResult::Job(JobAction::Foreground(2))
+        ));
+        assert!(matches!(
+            execute_command("jobs", "wait 3", ""),
+            CommandResult::Job(JobAction::Wait(3))
+        ));
+        assert!(matches!(
+            execute_command("jobs", "log 1", ""),
+            CommandResult::Job(JobAction::Log(1))
+        ));
+        match execute_command("jobs", "wait", "") {
+            CommandResult::ChatMessage(msg) => assert!(msg.contains("Usage: /jobs")),
+            other => panic!("expected ChatMessage, got {other:?}"),
+        }
+    }
 }
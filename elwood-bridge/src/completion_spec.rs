@@ -0,0 +1,954 @@
+//! Declarative argument completion specs for common CLIs.
+//!
+//! History and path completion know nothing about a command's grammar, so
+//! `git checkout ` or `cargo test -p ` get no useful suggestions. A spec
+//! describes a command's subcommands, options and positional arguments;
+//! argument values come from *generators* (git branches, cargo packages,
+//! make targets, docker images, ...) that are evaluated in the current
+//! directory and cached briefly. Generators that run a command do so on a
+//! background thread: completion serves their last output (nothing on first
+//! use) and starts a refresh once it is older than the cache TTL, so typing
+//! never waits on `git` or `docker`.
+//!
+//! Built-in specs ship in `completions/builtin.toml`. Users add or override
+//! specs with `~/.elwood/completions/*.toml`; a user spec with the same
+//! `name` as a built-in replaces it.
+//!
+//! ## TOML format
+//!
+//! ```toml
+//! [[spec]]
+//! name = "just"
+//! aliases = ["j"]                 # other command names using this spec
+//! args = { script = "just --summary | tr ' ' '\\n'" }
+//!
+//! [[spec.option]]
+//! name = ["-f", "--justfile"]
+//! arg = "files"                   # option takes a value from this generator
+//! description = "Use this justfile"
+//!
+//! [[spec.subcommand]]
+//! name = "deploy"
+//! option = [{ name = ["--env"], arg = { values = ["staging", "prod"] } }]
+//! ```
+//!
+//! Generators are either a built-in name (`files`, `directories`, `text`,
+//! `git-branches`, `git-remotes`, `git-tags`, `git-changed-files`,
+//! `cargo-packages`, `make-targets`, `npm-scripts`, `docker-images`,
+//! `docker-containers`), a fixed list (`{ values = [...] }`) or a shell
+//! command printing one value per line (`{ script = "..." }`). `text` marks
+//! a free-form value: nothing is suggested, but the word is consumed.
+//!
+//! Options marked `persistent = true` stay available inside subcommands.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use serde::Deserialize;
+
+use crate::completions::{Completion, CompletionSource};
+
+/// Spec definitions compiled into the binary.
+const BUILTIN_SPECS: &str = include_str!("../completions/builtin.toml");
+
+/// How long a generator command may run before it is killed.
+const GENERATOR_TIMEOUT: Duration = Duration::from_millis(750);
+
+/// How long generator output is reused for the same directory.
+const GENERATOR_TTL: Duration = Duration::from_secs(10);
+
+/// Maximum completions returned for one input.
+const MAX_COMPLETIONS: usize = 20;
+
+/// Scores place spec suggestions above filesystem and static completions but
+/// below history.
+const VALUE_SCORE: f32 = 3.0;
+const SUBCOMMAND_SCORE: f32 = 2.5;
+const OPTION_SCORE: f32 = 2.0;
+
+/// A spec definition file: `[[spec]]` tables.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpecFile {
+    #[serde(default)]
+    pub spec: Vec<CommandSpec>,
+}
+
+/// A command or subcommand.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CommandSpec {
+    /// Command name; user specs replace built-ins with the same name.
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub subcommand: Vec<CommandSpec>,
+    #[serde(default)]
+    pub option: Vec<OptionSpec>,
+    /// Generator for positional arguments.
+    #[serde(default)]
+    pub args: Option<Generator>,
+}
+
+/// A flag, optionally taking a value.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OptionSpec {
+    /// Spellings of the option, e.g. `["-p", "--package"]`.
+    pub name: Vec<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Generator for the option's value; `None` for a plain flag.
+    #[serde(default)]
+    pub arg: Option<Generator>,
+    /// Also offered inside subcommands.
+    #[serde(default)]
+    pub persistent: bool,
+}
+
+/// Where argument values come from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(untagged)]
+pub enum Generator {
+    Builtin(BuiltinGenerator),
+    Values { values: Vec<String> },
+    Script { script: String },
+}
+
+/// Generators implemented in Rust.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BuiltinGenerator {
+    /// Free-form value: consumes a word, suggests nothing.
+    Text,
+    Files,
+    Directories,
+    GitBranches,
+    GitRemotes,
+    GitTags,
+    /// Modified and untracked files (`git ls-files -m -o`).
+    GitChangedFiles,
+    /// Package names from the enclosing Cargo workspace.
+    CargoPackages,
+    MakeTargets,
+    NpmScripts,
+    DockerImages,
+    DockerContainers,
+}
+
+impl Generator {
+    /// Whether values come from an external command, which is too slow to
+    /// wait for on a keystroke.
+    fn runs_command(&self) -> bool {
+        !matches!(
+            self,
+            Generator::Values { .. }
+                | Generator::Builtin(
+                    BuiltinGenerator::Text
+                        | BuiltinGenerator::Files
+                        | BuiltinGenerator::Directories
+                        | BuiltinGenerator::CargoPackages
+                        | BuiltinGenerator::MakeTargets
+                        | BuiltinGenerator::NpmScripts
+                )
+        )
+    }
+}
+
+impl CommandSpec {
+    fn matches(&self, word: &str) -> bool {
+        self.name == word || self.aliases.iter().any(|a| a == word)
+    }
+
+    fn find_subcommand(&self, word: &str) -> Option<&CommandSpec> {
+        self.subcommand.iter().find(|s| s.matches(word))
+    }
+}
+
+impl OptionSpec {
+    fn matches(&self, word: &str) -> bool {
+        self.name.iter().any(|n| n == word)
+    }
+}
+
+/// Last output of a generator in one directory.
+#[derive(Debug, Default)]
+struct CachedValues {
+    values: Vec<String>,
+    /// When `values` were produced (`None` until the first run finishes).
+    updated: Option<Instant>,
+    /// A background run is in progress.
+    running: bool,
+}
+
+/// Generator output keyed by generator and directory.
+type GeneratorCache = HashMap<(Generator, PathBuf), CachedValues>;
+
+/// An ordered set of specs plus a short-lived cache of generator output.
+#[derive(Debug, Default)]
+pub struct SpecSet {
+    specs: Vec<CommandSpec>,
+    cache: Arc<Mutex<GeneratorCache>>,
+}
+
+impl SpecSet {
+    /// An empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The specs shipped with Elwood.
+    pub fn builtin() -> Self {
+        Self::from_toml(BUILTIN_SPECS).expect("built-in completion specs are valid")
+    }
+
+    /// Parse a TOML spec file.
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let file: SpecFile = toml::from_str(content)?;
+        Ok(Self::from_specs(file.spec))
+    }
+
+    pub fn from_specs(specs: Vec<CommandSpec>) -> Self {
+        Self {
+            specs,
+            cache: Arc::default(),
+        }
+    }
+
+    /// Load every `*.toml` file in `dir`, in file name order.
+    ///
+    /// Files that fail to load are skipped and reported in the second value.
+    pub fn load_dir(dir: &Path) -> (Self, Vec<String>) {
+        let mut set = Self::new();
+        let mut errors = Vec::new();
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return (set, errors);
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "toml"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let loaded = std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|s| Self::from_toml(&s));
+            match loaded {
+                Ok(more) => set.extend(more),
+                Err(e) => errors.push(format!("{}: {e:#}", path.display())),
+            }
+        }
+        (set, errors)
+    }
+
+    /// User specs from `~/.elwood/completions`, followed by the built-ins
+    /// they don't override.
+    pub fn load_default() -> (Self, Vec<String>) {
+        let (mut set, errors) = Self::load_dir(&specs_dir());
+        set.extend(Self::builtin());
+        (set, errors)
+    }
+
+    /// Append specs whose names aren't already present.
+    pub fn extend(&mut self, other: SpecSet) {
+        for spec in other.specs {
+            if !self.specs.iter().any(|s| s.name == spec.name) {
+                self.specs.push(spec);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.specs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.specs.is_empty()
+    }
+
+    /// The spec for a command name or alias.
+    pub fn find(&self, command: &str) -> Option<&CommandSpec> {
+        self.specs.iter().find(|s| s.matches(command))
+    }
+
+    /// Complete the last word of `input` from the spec for its command.
+    ///
+    /// Returns nothing while the command name itself is being typed; that is
+    /// left to history and the static list.
+    pub fn complete(&self, input: &str, cwd: &Path) -> Vec<Completion> {
+        let Some(split) = input.rfind(char::is_whitespace) else {
+            return Vec::new();
+        };
+        let (head, partial) = input.split_at(split + 1);
+        let words: Vec<&str> = head.split_whitespace().collect();
+        let Some((command, rest)) = words.split_first() else {
+            return Vec::new();
+        };
+        let Some(spec) = self.find(command) else {
+            return Vec::new();
+        };
+
+        // Walk the typed words to find the active subcommand and whether the
+        // word being completed is an option's value.
+        let mut chain = vec![spec];
+        let mut pending: Option<&OptionSpec> = None;
+        let mut used: HashSet<&str> = HashSet::new();
+        for word in rest {
+            if pending.take().is_some() {
+                continue;
+            }
+            if word.starts_with('-') {
+                let (name, inline_value) = match word.split_once('=') {
+                    Some((name, _)) => (name, true),
+                    None => (*word, false),
+                };
+                used.insert(name);
+                pending = find_option(&chain, name).filter(|o| o.arg.is_some() && !inline_value);
+                continue;
+            }
+            if let Some(sub) = chain.last().and_then(|node| node.find_subcommand(word)) {
+                chain.push(sub);
+            }
+        }
+        let node = *chain.last().expect("chain starts with the command");
+
+        let mut out: Vec<Completion> = Vec::new();
+        let mut push = |value: String, description: Option<&String>, score: f32| {
+            out.push(Completion {
+                text: format!("{head}{value}"),
+                source: CompletionSource::Spec,
+                score,
+                description: description.cloned(),
+            });
+        };
+
+        if let Some(option) = pending {
+            if let Some(generator) = &option.arg {
+                for value in self.values(generator, cwd, partial) {
+                    push(value, None, VALUE_SCORE);
+                }
+            }
+        } else if let Some((name, value)) =
+            partial.split_once('=').filter(|_| partial.starts_with('-'))
+        {
+            // `--option=value`
+            if let Some(generator) = find_option(&chain, name).and_then(|o| o.arg.as_ref()) {
+                for v in self.values(generator, cwd, value) {
+                    push(format!("{name}={v}"), None, VALUE_SCORE);
+                }
+            }
+        } else if partial.starts_with('-') {
+            for option in visible_options(&chain) {
+                if option.name.iter().any(|n| used.contains(n.as_str())) {
+                    continue;
+                }
+                for name in option
+                    .name
+                    .iter()
+                    .filter(|n| n.starts_with(partial) && *n != partial)
+                {
+                    push(name.clone(), option.description.as_ref(), OPTION_SCORE);
+                }
+            }
+        } else {
+            for sub in &node.subcommand {
+                if sub.name.starts_with(partial) && sub.name != partial {
+                    push(sub.name.clone(), sub.description.as_ref(), SUBCOMMAND_SCORE);
+                }
+            }
+            if let Some(generator) = &node.args {
+                for value in self.values(generator, cwd, partial) {
+                    push(value, None, VALUE_SCORE);
+                }
+            }
+        }
+
+        out.truncate(MAX_COMPLETIONS);
+        out
+    }
+
+    /// Generator values starting with `partial` (and not equal to it).
+    fn values(&self, generator: &Generator, cwd: &Path, partial: &str) -> Vec<String> {
+        let all = match generator {
+            Generator::Builtin(BuiltinGenerator::Text) => return Vec::new(),
+            Generator::Builtin(BuiltinGenerator::Files) => return path_values(cwd, partial, false),
+            Generator::Builtin(BuiltinGenerator::Directories) => {
+                return path_values(cwd, partial, true)
+            }
+            Generator::Values { values } => values.clone(),
+            _ => self.cached(generator, cwd),
+        };
+        all.into_iter()
+            .filter(|v| v.starts_with(partial) && v != partial)
+            .collect()
+    }
+
+    /// Cached generator output. Expired output of a command generator is
+    /// still returned while a background thread refreshes it.
+    fn cached(&self, generator: &Generator, cwd: &Path) -> Vec<String> {
+        let key = (generator.clone(), cwd.to_path_buf());
+        let mut cache = self.cache.lock();
+        let entry = cache.entry(key.clone()).or_default();
+        let fresh = entry.updated.is_some_and(|at| at.elapsed() < GENERATOR_TTL);
+        if fresh || entry.running {
+            return entry.values.clone();
+        }
+        if !generator.runs_command() {
+            entry.values = run_generator(generator, cwd);
+            entry.updated = Some(Instant::now());
+            return entry.values.clone();
+        }
+
+        entry.running = true;
+        let stale = entry.values.clone();
+        drop(cache);
+        let shared = Arc::clone(&self.cache);
+        let (generator, dir) = key.clone();
+        let spawned = std::thread::Builder::new()
+            .name("elwood-completion-gen".into())
+            .spawn(move || {
+                let values = run_generator(&generator, &dir);
+                let mut cache = shared.lock();
+                let entry = cache.entry((generator, dir)).or_default();
+                entry.values = values;
+                entry.updated = Some(Instant::now());
+                entry.running = false;
+            });
+        if spawned.is_err() {
+            if let Some(entry) = self.cache.lock().get_mut(&key) {
+                entry.running = false;
+            }
+        }
+        stale
+    }
+}
+
+/// Directory holding user spec files: `~/.elwood/completions`.
+pub fn specs_dir() -> PathBuf {
+    dirs_next::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".elwood")
+        .join("completions")
+}
+
+/// An option by spelling: the innermost subcommand's, then persistent ones
+/// from its parents.
+fn find_option<'a>(chain: &[&'a CommandSpec], name: &str) -> Option<&'a OptionSpec> {
+    visible_options(chain).into_iter().find(|o| o.matches(name))
+}
+
+fn visible_options<'a>(chain: &[&'a CommandSpec]) -> Vec<&'a OptionSpec> {
+    let (last, parents) = chain.split_last().expect("chain starts with the command");
+    last.option
+        .iter()
+        .chain(
+            parents
+                .iter()
+                .rev()
+                .flat_map(|p| p.option.iter().filter(|o| o.persistent)),
+        )
+        .collect()
+}
+
+fn run_generator(generator: &Generator, cwd: &Path) -> Vec<String> {
+    let mut values = match generator {
+        Generator::Builtin(builtin) => match builtin {
+            BuiltinGenerator::Text | BuiltinGenerator::Files | BuiltinGenerator::Directories => {
+                Vec::new()
+            }
+            BuiltinGenerator::GitBranches => {
+                let mut branches = command_lines(
+                    "git",
+                    &["for-each-ref", "--format=%(refname:short)", "refs/heads/"],
+                    cwd,
+                );
+                // `origin/HEAD` shortens to `origin`; keep only real branches
+                branches.extend(
+                    command_lines(
+                        "git",
+                        &["for-each-ref", "--format=%(refname:short)", "refs/remotes/"],
+                        cwd,
+                    )
+                    .into_iter()
+                    .filter(|b| b.contains('/') && !b.ends_with("/HEAD")),
+                );
+                branches
+            }
+            BuiltinGenerator::GitRemotes => command_lines("git", &["remote"], cwd),
+            BuiltinGenerator::GitTags => command_lines("git", &["tag", "--list"], cwd),
+            BuiltinGenerator::GitChangedFiles => command_lines(
+                "git",
+                &["ls-files", "--modified", "--others", "--exclude-standard"],
+                cwd,
+            ),
+            BuiltinGenerator::CargoPackages => cargo_packages(cwd),
+            BuiltinGenerator::MakeTargets => make_targets(cwd),
+            BuiltinGenerator::NpmScripts => npm_scripts(cwd),
+            BuiltinGenerator::DockerImages => command_lines(
+                "docker",
+                &["images", "--format", "{{.Repository}}:{{.Tag}}"],
+                cwd,
+            )
+            .into_iter()
+            .filter(|i| !i.contains("<none>"))
+            .collect(),
+            BuiltinGenerator::DockerContainers => {
+                command_lines("docker", &["ps", "--all", "--format", "{{.Names}}"], cwd)
+            }
+        },
+        Generator::Values { values } => values.clone(),
+        Generator::Script { script } => {
+            if cfg!(windows) {
+                command_lines("cmd", &["/C", script], cwd)
+            } else {
+                command_lines("sh", &["-c", script], cwd)
+            }
+        }
+    };
+    let mut seen = HashSet::new();
+    values.retain(|v| !v.is_empty() && seen.insert(v.clone()));
+    values
+}
+
+/// Non-empty, trimmed stdout lines of a command, or nothing if it fails or
+/// outlives [`GENERATOR_TIMEOUT`].
+fn command_lines(program: &str, args: &[&str], cwd: &Path) -> Vec<String> {
+    command_lines_with_timeout(program, args, cwd, GENERATOR_TIMEOUT)
+}
+
+/// [`command_lines`] with a caller-chosen time limit.
+pub(crate) fn command_lines_with_timeout(
+    program: &str,
+    args: &[&str],
+    cwd: &Path,
+    timeout: Duration,
+) -> Vec<String> {
+    let Ok(mut child) = Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return Vec::new();
+    };
+
+    // Drain stdout on a thread so a chatty command can't block on a full pipe
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let reader = std::thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = stdout.read_to_string(&mut buf);
+        buf
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(5)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+        }
+    };
+    // On timeout the reader is left behind: a grandchild (`sh -c`) may still
+    // hold the pipe open, and it exits on its own once the pipe closes.
+    match status {
+        Some(status) if status.success() => reader
+            .join()
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(String::from)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Complete a path word relative to `cwd`. Directories get a trailing `/`.
+fn path_values(cwd: &Path, partial: &str, dirs_only: bool) -> Vec<String> {
+    let (dir_part, prefix) = match partial.rfind('/') {
+        Some(i) => partial.split_at(i + 1),
+        None => ("", partial),
+    };
+    let dir = if let Some(rest) = dir_part.strip_prefix("~/") {
+        match dirs_next::home_dir() {
+            Some(home) => home.join(rest),
+            None => return Vec::new(),
+        }
+    } else {
+        cwd.join(dir_part)
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut values: Vec<(bool, String)> = entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            let is_dir = e.path().is_dir();
+            if dirs_only && !is_dir {
+                return None;
+            }
+            let suffix = if is_dir { "/" } else { "" };
+            Some((is_dir, format!("{dir_part}{name}{suffix}")))
+        })
+        .collect();
+    values.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    values.into_iter().map(|(_, v)| v).collect()
+}
+
+/// Package names in the Cargo workspace enclosing `cwd`, or the nearest
+/// package when there is no workspace.
+fn cargo_packages(cwd: &Path) -> Vec<String> {
+    let manifests: Vec<(PathBuf, toml::Value)> = cwd
+        .ancestors()
+        .map(|dir| dir.join("Cargo.toml"))
+        .filter_map(|path| {
+            let value = std::fs::read_to_string(&path)
+                .ok()?
+                .parse::<toml::Value>()
+                .ok()?;
+            Some((path.parent()?.to_path_buf(), value))
+        })
+        .collect();
+
+    let root = manifests
+        .iter()
+        .rev()
+        .find(|(_, m)| m.get("workspace").is_some())
+        .or_else(|| manifests.first());
+    let Some((root_dir, manifest)) = root else {
+        return Vec::new();
+    };
+
+    let package_name = |m: &toml::Value| {
+        m.get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+            .map(String::from)
+    };
+    let mut names: Vec<String> = package_name(manifest).into_iter().collect();
+
+    let members = manifest
+        .get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(|m| m.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for member in members.iter().filter_map(|m| m.as_str()) {
+        let dirs: Vec<PathBuf> = match member.strip_suffix("/*") {
+            Some(parent) => std::fs::read_dir(root_dir.join(parent))
+                .map(|rd| rd.flatten().map(|e| e.path()).collect())
+                .unwrap_or_default(),
+            None => vec![root_dir.join(member)],
+        };
+        for dir in dirs {
+            let name = std::fs::read_to_string(dir.join("Cargo.toml"))
+                .ok()
+                .and_then(|s| s.parse::<toml::Value>().ok())
+                .and_then(|m| package_name(&m));
+            names.extend(name);
+        }
+    }
+    names.sort();
+    names
+}
+
+/// Explicit targets from the Makefile in `cwd` (no pattern rules or
+/// special `.TARGETS`).
+fn make_targets(cwd: &Path) -> Vec<String> {
+    let Some(content) = ["GNUmakefile", "makefile", "Makefile"]
+        .iter()
+        .find_map(|name| std::fs::read_to_string(cwd.join(name)).ok())
+    else {
+        return Vec::new();
+    };
+
+    let mut targets = Vec::new();
+    for line in content.lines() {
+        if line.starts_with(|c: char| c.is_whitespace() || c == '#' || c == '.') {
+            continue;
+        }
+        let Some((lhs, rhs)) = line.split_once(':') else {
+            continue;
+        };
+        // `VAR := value` and `VAR = a:b` are assignments, not rules
+        if rhs.starts_with('=') || lhs.contains('=') {
+            continue;
+        }
+        targets.extend(
+            lhs.split_whitespace()
+                .filter(|t| !t.contains('%') && !t.contains('$'))
+                .map(String::from),
+        );
+    }
+    targets
+}
+
+/// Script names from `package.json` in `cwd`.
+fn npm_scripts(cwd: &Path) -> Vec<String> {
+    std::fs::read_to_string(cwd.join("package.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        .and_then(|v| {
+            v.get("scripts")?
+                .as_object()
+                .map(|o| o.keys().cloned().collect())
+        })
+        .unwrap_or_default()
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(completions: &[Completion]) -> Vec<&str> {
+        completions.iter().map(|c| c.text.as_str()).collect()
+    }
+
+    /// Complete until a background generator has produced values.
+    fn complete_when_ready(set: &SpecSet, input: &str, cwd: &Path) -> Vec<Completion> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let completions = set.complete(input, cwd);
+            if !completions.is_empty() || Instant::now() > deadline {
+                return completions;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn set(toml: &str) -> SpecSet {
+        SpecSet::from_toml(toml).unwrap()
+    }
+
+    #[test]
+    fn builtin_specs_parse() {
+        let set = SpecSet::builtin();
+        for name in ["git", "cargo", "make", "npm", "docker"] {
+            assert!(set.find(name).is_some(), "missing built-in spec for {name}");
+        }
+    }
+
+    #[test]
+    fn completes_subcommands_options_and_values() {
+        let set = set(r#"
+            [[spec]]
+            name = "tool"
+            aliases = ["t"]
+
+            [[spec.option]]
+            name = ["-C"]
+            arg = "text"
+            persistent = true
+
+            [[spec.subcommand]]
+            name = "deploy"
+            description = "Ship it"
+            option = [
+                { name = ["--env", "-e"], arg = { values = ["staging", "prod"] } },
+                { name = ["--force"] },
+            ]
+
+            [[spec.subcommand]]
+            name = "destroy"
+            args = { values = ["web", "worker"] }
+        "#);
+        let cwd = Path::new("/");
+
+        let subs = set.complete("tool de", cwd);
+        assert_eq!(texts(&subs), ["tool deploy", "tool destroy"]);
+        assert_eq!(subs[0].description.as_deref(), Some("Ship it"));
+        assert!(subs.iter().all(|c| c.source == CompletionSource::Spec));
+
+        // Aliases, positional values, and the exact word is not offered again
+        assert_eq!(
+            texts(&set.complete("t destroy w", cwd)),
+            ["t destroy web", "t destroy worker"]
+        );
+        assert!(set.complete("tool deploy", cwd).is_empty());
+
+        // Option values, separate and inline
+        assert_eq!(
+            texts(&set.complete("tool deploy --env ", cwd)),
+            ["tool deploy --env staging", "tool deploy --env prod"]
+        );
+        assert_eq!(
+            texts(&set.complete("tool deploy -e=p", cwd)),
+            ["tool deploy -e=prod"]
+        );
+
+        // Options: persistent parent options included, used ones skipped
+        let opts: Vec<String> = set
+            .complete("tool deploy --force -", cwd)
+            .into_iter()
+            .map(|c| c.text.replace("tool deploy --force ", ""))
+            .collect();
+        assert_eq!(opts, ["--env", "-e", "-C"]);
+
+        // Option values are consumed rather than treated as subcommands
+        assert_eq!(
+            texts(&set.complete("tool -C destroy de", cwd)),
+            ["tool -C destroy deploy", "tool -C destroy destroy"]
+        );
+
+        // Unknown commands and the command word itself get nothing
+        assert!(set.complete("other de", cwd).is_empty());
+        assert!(set.complete("too", cwd).is_empty());
+    }
+
+    #[test]
+    fn generators_read_project_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\", \"tools/gen\"]\n",
+        )
+        .unwrap();
+        for (path, name) in [
+            ("crates/core", "core"),
+            ("crates/cli", "cli"),
+            ("tools/gen", "gen"),
+        ] {
+            std::fs::create_dir_all(root.join(path)).unwrap();
+            std::fs::write(
+                root.join(path).join("Cargo.toml"),
+                format!("[package]\nname = \"{name}\"\n"),
+            )
+            .unwrap();
+        }
+        // Found from inside a member, too
+        assert_eq!(
+            cargo_packages(&root.join("crates/cli")),
+            ["cli", "core", "gen"]
+        );
+
+        std::fs::write(
+            root.join("Makefile"),
+            ".PHONY: build test\nVERSION := 1.0\nbuild test: deps\n\tcc -o x\n%.o: %.c\ndeploy:\n",
+        )
+        .unwrap();
+        assert_eq!(make_targets(root), ["build", "test", "deploy"]);
+
+        std::fs::write(
+            root.join("package.json"),
+            r#"{"scripts": {"dev": "vite", "lint": "eslint ."}}"#,
+        )
+        .unwrap();
+        assert_eq!(npm_scripts(root), ["dev", "lint"]);
+
+        let set = SpecSet::builtin();
+        assert_eq!(
+            texts(&set.complete("cargo test -p c", root)),
+            ["cargo test -p cli", "cargo test -p core"]
+        );
+        assert_eq!(texts(&set.complete("make d", root)), ["make deploy"]);
+    }
+
+    #[test]
+    fn path_generator_lists_directories_first() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("setup.sh"), "").unwrap();
+        std::fs::write(dir.path().join(".secret"), "").unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "").unwrap();
+
+        assert_eq!(path_values(dir.path(), "s", false), ["src/", "setup.sh"]);
+        assert_eq!(path_values(dir.path(), "s", true), ["src/"]);
+        assert_eq!(path_values(dir.path(), "src/", false), ["src/main.rs"]);
+        assert_eq!(path_values(dir.path(), ".s", false), [".secret"]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn script_generator_runs_in_cwd_and_caches() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("names"), "alpha\nbeta\n\nalpha\n").unwrap();
+        let set = set(r#"
+            [[spec]]
+            name = "tool"
+            args = { script = "cat names" }
+        "#);
+        // The first completion starts the script without waiting for it
+        assert!(set.complete("tool ", dir.path()).is_empty());
+        assert_eq!(
+            texts(&complete_when_ready(&set, "tool ", dir.path())),
+            ["tool alpha", "tool beta"]
+        );
+
+        // Served from the cache until the TTL expires
+        std::fs::write(dir.path().join("names"), "gamma\n").unwrap();
+        assert_eq!(
+            texts(&set.complete("tool ", dir.path())),
+            ["tool alpha", "tool beta"]
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn slow_generator_does_not_block_completion() {
+        let set = set(r#"
+            [[spec]]
+            name = "tool"
+            args = { script = "sleep 0.5; echo late" }
+        "#);
+        let started = Instant::now();
+        assert!(set.complete("tool ", Path::new("/")).is_empty());
+        assert!(set.complete("tool l", Path::new("/")).is_empty());
+        assert!(started.elapsed() < Duration::from_millis(250));
+        assert_eq!(
+            texts(&complete_when_ready(&set, "tool ", Path::new("/"))),
+            ["tool late"]
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn slow_generator_times_out() {
+        let started = Instant::now();
+        assert!(command_lines("sh", &["-c", "sleep 5; echo late"], Path::new("/")).is_empty());
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
+
+    #[test]
+    fn user_specs_override_builtins() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("git.toml"),
+            "[[spec]]\nname = \"git\"\nsubcommand = [{ name = \"sync\" }]\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("broken.toml"), "[[spec]]\nnmae = \"x\"\n").unwrap();
+
+        let (mut set, errors) = SpecSet::load_dir(dir.path());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("broken.toml"));
+        set.extend(SpecSet::builtin());
+
+        assert_eq!(texts(&set.complete("git s", Path::new("/"))), ["git sync"]);
+        assert!(set.find("cargo").is_some());
+    }
+}
@@ -3,14 +3,25 @@
 //! Provides Fish-style autosuggestions from multiple sources:
 //! - **History**: prefix match against command history, scored by recency
 //! - **Filesystem**: path completion when input contains `/` or starts with `.`
+//! - **Spec**: subcommands, options and argument values from declarative
+//!   command specs (see [`crate::completion_spec`])
 //! - **Static**: common command completions
 //!
-//! The top suggestion is rendered as dim "ghost text" after the cursor.
+//! The top suggestion is rendered as dim "ghost text" after the cursor; the
+//! full ranked list backs the completion dropdown (Ctrl+Space).
 
+use crate::completion_spec::SpecSet;
 use crate::semantic_bridge::SemanticBridge;
+use crate::theme;
 use std::path::Path;
 use std::time::Instant;
 
+/// Rows of completions visible in the dropdown at once.
+const MENU_MAX_ROWS: usize = 8;
+
+/// Maximum dropdown width (including borders).
+const MENU_WIDTH: usize = 64;
+
 /// A single completion suggestion.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Completion {
@@ -20,6 +31,8 @@ pub struct Completion {
     pub source: CompletionSource,
     /// Score for ranking (higher = better).
     pub score: f32,
+    /// Short explanation shown in the dropdown (spec subcommands and options).
+    pub description: Option<String>,
 }
 
 /// Source of a completion suggestion.
@@ -33,6 +46,8 @@ pub enum CompletionSource {
     Static,
     /// From project symbol index (functions, structs, etc.).
     Symbol,
+    /// From a command completion spec.
+    Spec,
 }
 
 /// A history entry with metadata for frecency scoring.
@@ -50,19 +65,31 @@ pub struct HistoryEntry {
 pub struct CompletionEngine {
     /// Command history entries.
     history: Vec<HistoryEntry>,
+    /// Command specs for argument completion.
+    specs: SpecSet,
 }
 
 impl CompletionEngine {
-    /// Create a new completion engine with no history.
+    /// Create a new completion engine with no history and the built-in specs.
     pub fn new() -> Self {
         Self {
             history: Vec::new(),
+            specs: SpecSet::builtin(),
         }
     }
 
     /// Create a completion engine pre-loaded with history.
     pub fn with_history(history: Vec<HistoryEntry>) -> Self {
-        Self { history }
+        Self {
+            history,
+            specs: SpecSet::builtin(),
+        }
+    }
+
+    /// Replace the command specs (e.g. with [`SpecSet::load_default`]).
+    pub fn with_specs(mut self, specs: SpecSet) -> Self {
+        self.specs = specs;
+        self
     }
 
     /// Add a history entry from a submitted command.
@@ -114,12 +141,15 @@ impl CompletionEngine {
             results.extend(filesystem_completions(last_token, input, cwd));
         }
 
-        // 3. Symbol completions (from tree-sitter index)
+        // 3. Spec completions (subcommands, options, argument values)
+        results.extend(self.specs.complete(input, cwd));
+
+        // 4. Symbol completions (from tree-sitter index)
         if let Some(bridge) = semantic_bridge {
             results.extend(symbol_completions(last_token, input, bridge));
         }
 
-        // 4. Static completions
+        // 5. Static completions
         results.extend(static_completions(input));
 
         // Sort by score descending
@@ -162,6 +192,7 @@ impl CompletionEngine {
                     text: entry.text.clone(),
                     source: CompletionSource::History,
                     score: score as f32 + 100.0, // Boost history above other sources
+                    description: None,
                 }
             })
             .take(10)
@@ -175,6 +206,151 @@ impl Default for CompletionEngine {
     }
 }
 
+/// Dropdown listing the ranked completions for the current input (Ctrl+Space).
+///
+/// Drawn directly above the input box; the selected entry replaces the
+/// input when accepted.
+#[derive(Debug, Clone)]
+pub struct CompletionMenu {
+    items: Vec<Completion>,
+    selected: usize,
+    /// Length of the input prefix shared by every item (up to the word
+    /// being completed), hidden in the list.
+    head_len: usize,
+}
+
+impl CompletionMenu {
+    /// A menu for `input`, or `None` when there is nothing to offer.
+    pub fn new(input: &str, items: Vec<Completion>) -> Option<Self> {
+        if items.is_empty() {
+            return None;
+        }
+        let head_len = input.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let head_len = if items.iter().all(|c| c.text.starts_with(&input[..head_len])) {
+            head_len
+        } else {
+            0
+        };
+        Some(Self {
+            items,
+            selected: 0,
+            head_len,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn selected(&self) -> &Completion {
+        &self.items[self.selected]
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.items.len();
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.checked_sub(1).unwrap_or(self.items.len() - 1);
+    }
+
+    /// Rows the menu occupies, including borders.
+    pub fn height(&self) -> u16 {
+        (self.items.len().min(MENU_MAX_ROWS) + 2) as u16
+    }
+
+    /// Render the menu so its bottom border sits on the row above `input_top`.
+    pub fn render(&self, screen_width: u16, input_top: u16) -> String {
+        let t = theme::active();
+        let r = t.reset();
+        let border = t.ansi_fg(t.accent);
+        let fg = t.ansi_fg(t.fg_primary);
+        let muted = t.ansi_fg(t.fg_muted);
+        let sel_bg = t.ansi_bg(t.bg_tertiary);
+        let bold = t.bold();
+
+        let width = menu_width(screen_width);
+        let inner = width.saturating_sub(2);
+        let left = 3;
+        let mut row = input_top.saturating_sub(self.height()).max(1) as usize;
+
+        let mut out = String::with_capacity(2048);
+        out.push_str("\x1b[s\x1b[?25l");
+
+        let title = format!(" Completions ({}) ", self.items.len());
+        let fill = "\u{2500}".repeat(inner.saturating_sub(title.chars().count() + 1));
+        out.push_str(&format!(
+            "\x1b[{row};{left}H{border}\u{256D}\u{2500}{bold}{title}{r}{border}{fill}\u{256E}{r}"
+        ));
+        row += 1;
+
+        let start = self
+            .selected
+            .saturating_sub(MENU_MAX_ROWS / 2)
+            .min(self.items.len().saturating_sub(MENU_MAX_ROWS));
+        let visible = self.items.iter().enumerate().skip(start);
+        for (i, item) in visible.take(MENU_MAX_ROWS) {
+            let selected = i == self.selected;
+            let (bg, marker) = if selected {
+                (sel_bg.as_str(), "\u{25B8}")
+            } else {
+                ("", " ")
+            };
+            let label = item.text.get(self.head_len..).unwrap_or(&item.text);
+            let tag = match item.source {
+                CompletionSource::History => "history",
+                CompletionSource::Filesystem => "path",
+                CompletionSource::Static => "common",
+                CompletionSource::Symbol => "symbol",
+                CompletionSource::Spec => "",
+            };
+            let note = item.description.as_deref().unwrap_or(tag);
+
+            let label: String = label.chars().take(inner.saturating_sub(2)).collect();
+            let label_len = label.chars().count();
+            let note_room = inner.saturating_sub(label_len + 4);
+            let note: String = if note_room >= 4 {
+                note.chars().take(note_room).collect()
+            } else {
+                String::new()
+            };
+            let pad = inner.saturating_sub(label_len + note.chars().count() + 2);
+            out.push_str(&format!(
+                "\x1b[{row};{left}H{border}\u{2502}{r}{bg}{marker}{fg}{label}{}{muted}{note}{r}{bg} {r}{border}\u{2502}{r}",
+                " ".repeat(pad),
+            ));
+            row += 1;
+        }
+
+        let bottom = "\u{2500}".repeat(inner);
+        out.push_str(&format!(
+            "\x1b[{row};{left}H{border}\u{2570}{bottom}\u{256F}{r}"
+        ));
+        out.push_str("\x1b[?25h\x1b[u");
+        out
+    }
+
+    /// Blank the rows drawn by [`render`](Self::render).
+    pub fn erase(&self, screen_width: u16, input_top: u16) -> String {
+        let width = menu_width(screen_width);
+        let top = input_top.saturating_sub(self.height()).max(1);
+        let mut out = String::from("\x1b[s");
+        for row in top..input_top {
+            out.push_str(&format!("\x1b[{row};3H{}", " ".repeat(width)));
+        }
+        out.push_str("\x1b[u");
+        out
+    }
+}
+
+fn menu_width(screen_width: u16) -> usize {
+    MENU_WIDTH.min((screen_width as usize).saturating_sub(4)).max(20)
+}
+
 /// Calculate frecency score (frequency + recency).
 ///
 /// More recent commands and frequently used commands score higher.
@@ -266,6 +442,7 @@ fn filesystem_completions(partial_path: &str, full_input: &str, cwd: &Path) -> V
                 text: full_text,
                 source: CompletionSource::Filesystem,
                 score,
+                description: None,
             }
         })
         .take(20)
@@ -303,6 +480,7 @@ fn symbol_completions(
                 text: full_text,
                 source: CompletionSource::Symbol,
                 score: sym.score * 0.8, // Slightly below history boost
+                description: None,
             }
         })
         .collect()
@@ -351,6 +529,7 @@ fn static_completions(input: &str) -> Vec<Completion> {
             text: cmd.to_string(),
             source: CompletionSource::Static,
             score: 0.5,
+            description: None,
         })
         .take(5)
         .collect()
@@ -466,6 +645,58 @@ mod tests {
         assert!(completions.iter().any(|c| c.text == "cargo build"));
     }
 
+    // ── Spec completions ─────────────────────────────────────────────
+
+    #[test]
+    fn spec_completions_feed_ghost_text() {
+        let engine = CompletionEngine::new();
+        let completions = engine.get_completions("git commit --a", Path::new("/tmp"));
+        assert!(completions
+            .iter()
+            .any(|c| c.text == "git commit --amend" && c.source == CompletionSource::Spec));
+
+        let ghost = engine.ghost_text("git stat", Path::new("/tmp"));
+        assert_eq!(ghost, Some("us".to_string()));
+    }
+
+    #[test]
+    fn history_outranks_specs() {
+        let engine = engine_with_history().with_specs(SpecSet::builtin());
+        let completions = engine.get_completions("git p", Path::new("/tmp"));
+        assert_eq!(completions[0].text, "git push origin main");
+        assert!(completions
+            .iter()
+            .any(|c| c.text == "git pull" && c.source == CompletionSource::Spec));
+    }
+
+    // ── Dropdown ─────────────────────────────────────────────────────
+
+    #[test]
+    fn menu_navigation_wraps() {
+        let engine = engine_with_history();
+        let items = engine.get_completions("cargo", Path::new("/tmp"));
+        let mut menu = CompletionMenu::new("cargo", items.clone()).unwrap();
+        assert_eq!(menu.selected().text, items[0].text);
+        menu.select_prev();
+        assert_eq!(menu.selected().text, items[items.len() - 1].text);
+        menu.select_next();
+        menu.select_next();
+        assert_eq!(menu.selected().text, items[1].text);
+        assert!(CompletionMenu::new("zzz", Vec::new()).is_none());
+    }
+
+    #[test]
+    fn menu_hides_shared_prefix() {
+        let engine = CompletionEngine::new();
+        let items = engine.get_completions("git commit --a", Path::new("/tmp"));
+        let menu = CompletionMenu::new("git commit --a", items).unwrap();
+        assert_eq!(menu.head_len, "git commit ".len());
+        let out = menu.render(80, 20);
+        assert!(out.contains("--amend"));
+        assert!(!out.contains("git commit"));
+        assert!(menu.height() >= 3);
+    }
+
     // ── Frecency scoring ─────────────────────────────────────────────
 
     #[test]
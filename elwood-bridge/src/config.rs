@@ -22,6 +22,7 @@
 //! [[models]]
 //! name = "claude-sonnet-4-6"
 //! provider = "anthropic"
+//! context_window = 200000  # optional; defaults by model family
 //! ```
 
 use crate::model_router::{ModelConfig, ModelRouter};
@@ -67,6 +68,23 @@ pub struct ElwoodConfig {
     /// MCP (Model Context Protocol) configuration.
     #[serde(default)]
     pub mcp: crate::mcp::McpConfig,
+
+    /// TUI theme: a built-in name, `auto` (derived from the WezTerm color
+    /// scheme), or a file in `~/.elwood/themes/`.
+    #[serde(default = "default_theme")]
+    pub theme: String,
+
+    /// Font metrics and timing for `/record export`.
+    #[serde(default)]
+    pub recording_export: crate::recording_export::ExportConfig,
+
+    /// Desktop notification routing for toasts.
+    #[serde(default)]
+    pub notifications: crate::notification::NotificationConfig,
+
+    /// Inline image protocol and size limits for the chat area.
+    #[serde(default)]
+    pub images: crate::inline_image::ImageConfig,
 }
 
 impl Default for ElwoodConfig {
@@ -81,6 +99,10 @@ impl Default for ElwoodConfig {
             scrollback_size: default_scrollback(),
             working_dir: None,
             mcp: crate::mcp::McpConfig::default(),
+            theme: default_theme(),
+            recording_export: crate::recording_export::ExportConfig::default(),
+            notifications: crate::notification::NotificationConfig::default(),
+            images: crate::inline_image::ImageConfig::default(),
         }
     }
 }
@@ -93,7 +115,8 @@ fn default_model() -> String {
     "gemini-2.5-pro".into()
 }
 
-fn default_config_path() -> PathBuf {
+/// `~/.elwood/elwood.toml`.
+pub fn default_config_path() -> PathBuf {
     dirs_next::home_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join(".elwood")
@@ -108,6 +131,10 @@ fn default_scrollback() -> usize {
     10_000
 }
 
+fn default_theme() -> String {
+    "tokyo_night".into()
+}
+
 impl ElwoodConfig {
     /// Load configuration from the default config file.
     pub fn load() -> Self {
@@ -147,6 +174,41 @@ mod tests {
         assert!(config.models.is_empty());
     }
 
+    #[test]
+    fn test_recording_export_setting() {
+        let config: ElwoodConfig = toml::from_str("[recording_export]\nfont_size = 18.0").unwrap();
+        assert_eq!(config.recording_export.font_size, 18.0);
+        assert_eq!(config.recording_export.max_idle, 2.0);
+    }
+
+    #[test]
+    fn test_notifications_setting() {
+        let config: ElwoodConfig =
+            toml::from_str("[notifications]\ndesktop_level = \"info\"").unwrap();
+        assert!(!config.notifications.desktop_when_pane_unfocused);
+        assert_eq!(
+            config.notifications.desktop_level,
+            crate::notification::ToastLevel::Info
+        );
+    }
+
+    #[test]
+    fn test_images_setting() {
+        let config: ElwoodConfig = toml::from_str("[images]\nprotocol = \"kitty\"").unwrap();
+        assert_eq!(
+            config.images.protocol,
+            crate::inline_image::ImageProtocol::Kitty
+        );
+        assert_eq!(config.images.max_rows, 20);
+    }
+
+    #[test]
+    fn test_theme_setting() {
+        assert_eq!(ElwoodConfig::default().theme, "tokyo_night");
+        let config: ElwoodConfig = toml::from_str("theme = \"auto\"").unwrap();
+        assert_eq!(config.theme, "auto");
+    }
+
     #[test]
     fn test_parse_legacy_config() {
         let toml_str = r#"
@@ -181,12 +243,15 @@ mod tests {
             display_name = "Claude Sonnet"
             cost_per_1k_input = 0.003
             cost_per_1k_output = 0.015
+            context_window = 500000
         "#;
         let config: ElwoodConfig = toml::from_str(toml_str).unwrap();
         assert_eq!(config.models.len(), 3);
         assert_eq!(config.models[0].name, "gemini-2.5-pro");
         assert!(config.models[0].default);
         assert_eq!(config.models[2].display_name, "Claude Sonnet");
+        assert_eq!(config.models[1].context_window, None);
+        assert_eq!(config.models[2].context_budget(), 500_000);
     }
 
     #[test]
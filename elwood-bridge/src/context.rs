@@ -15,9 +15,26 @@
 //! @src/main.rs explain this file        -> attaches src/main.rs
 //! @Cargo.toml @README.md compare these  -> attaches both files
 //! ```
+//!
+//! ## Other Reference Kinds
+//!
+//! ```text
+//! @symbol:name      -> the definition of a symbol (via the semantic bridge)
+//! @diff             -> `git diff` of the working tree
+//! @diff:staged      -> `git diff --staged`
+//! @commit:<rev>     -> `git show --stat --patch <rev>`
+//! @pane:<id>        -> screen + recent scrollback of another pane
+//! @block:<n>        -> command and output of OSC 133 block `n` (as in `/block`)
+//! @dir:<path>       -> a `.gitignore`-aware tree listing of a directory
+//! ```
+//!
+//! Every attachment, whatever its kind, is passed through the [`Redactor`]
+//! and then capped at `MAX_FILE_SIZE` bytes before it reaches the agent.
 
+use crate::redaction::Redactor;
 use crate::semantic_bridge::SemanticBridge;
 use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// Maximum file size to attach (100 KB).
 const MAX_FILE_SIZE: u64 = 100 * 1024;
@@ -25,12 +42,34 @@ const MAX_FILE_SIZE: u64 = 100 * 1024;
 /// Maximum number of search results to return.
 const MAX_SEARCH_RESULTS: usize = 20;
 
+/// Number of lines (screen + scrollback) captured by `@pane:<id>`.
+pub const PANE_CONTEXT_LINES: usize = 200;
+
+/// Maximum depth of an `@dir:` tree listing.
+const MAX_DIR_DEPTH: usize = 4;
+
+/// Maximum number of entries in an `@dir:` tree listing.
+const MAX_DIR_ENTRIES: usize = 500;
+
+/// Access to terminal state that only the pane can provide.
+///
+/// Implemented by the Elwood pane so that `@pane:` and `@block:` references
+/// can be resolved without this module depending on the mux.
+pub trait TerminalContext {
+    /// The last `max_lines` lines of pane `pane_id`, or `None` if there is no such pane.
+    fn pane_text(&self, pane_id: usize, max_lines: usize) -> Option<String>;
+
+    /// The command (if known) and output of block `index`, or `None` if out of range.
+    fn block_text(&self, index: usize) -> Option<(Option<String>, String)>;
+}
+
 /// A file attachment parsed from an `@` reference.
 #[derive(Debug, Clone)]
 pub struct ContextAttachment {
     /// Display label (e.g. `@src/main.rs`).
     pub label: String,
-    /// The resolved absolute path.
+    /// The resolved absolute path (the working directory for git references,
+    /// empty for `@pane:` and `@block:`).
     pub path: PathBuf,
     /// File contents (may be truncated for very large files).
     pub content: String,
@@ -226,10 +265,28 @@ pub fn resolve_and_build_prompt(
 /// - `@path/to/file` works as before (file attachment)
 ///
 /// When `semantic_bridge` is `None`, `@symbol:` references are ignored.
+/// Terminal references (`@pane:`, `@block:`) are ignored; use
+/// [`resolve_and_build_prompt_with_context`] to resolve those too.
 pub fn resolve_and_build_prompt_with_symbols(
     input: &str,
     cwd: &Path,
     semantic_bridge: Option<&SemanticBridge>,
+) -> (Vec<ContextAttachment>, String) {
+    resolve_and_build_prompt_with_context(input, cwd, semantic_bridge, None, &Redactor::new())
+}
+
+/// Resolve every kind of `@` reference and build the augmented prompt.
+///
+/// `terminal` supplies `@pane:` and `@block:` content; when it is `None`
+/// those references are ignored. Unresolvable references are skipped
+/// silently, as with plain file references. All attachment content is
+/// redacted and then size-limited.
+pub fn resolve_and_build_prompt_with_context(
+    input: &str,
+    cwd: &Path,
+    semantic_bridge: Option<&SemanticBridge>,
+    terminal: Option<&dyn TerminalContext>,
+    redactor: &Redactor,
 ) -> (Vec<ContextAttachment>, String) {
     let (refs, user_text) = parse_at_references(input);
     if refs.is_empty() {
@@ -240,49 +297,15 @@ pub fn resolve_and_build_prompt_with_symbols(
     let mut context_block = String::new();
 
     for reference in &refs {
-        // Check for @symbol:name pattern
-        if let Some(symbol_name) = crate::semantic_bridge::extract_symbol_name(reference) {
-            if let Some(bridge) = semantic_bridge {
-                if let Some(def) = bridge.resolve_symbol(symbol_name) {
-                    let label = format!("@symbol:{}", def.name);
-                    context_block.push_str(&format!(
-                        "<symbol name=\"{}\" kind=\"{}\" file=\"{}\" line=\"{}\">\n{}\n</symbol>\n\n",
-                        def.name,
-                        def.kind,
-                        def.file.display(),
-                        def.start_line,
-                        def.source,
-                    ));
-                    attachments.push(ContextAttachment {
-                        label,
-                        path: def.file,
-                        content: def.source,
-                    });
-                    continue;
-                }
-            }
-            // Symbol not found — skip silently
+        let Some((open_tag, close_tag, mut att)) =
+            resolve_reference(reference, cwd, semantic_bridge, terminal)
+        else {
             continue;
-        }
-
-        // Standard file resolution
-        let direct_path = cwd.join(reference);
-        let attachment = if direct_path.is_file() {
-            read_attachment(&direct_path, cwd)
-        } else {
-            search_files(reference, cwd)
-                .first()
-                .and_then(|p| read_attachment(p, cwd))
         };
-
-        if let Some(att) = attachment {
-            context_block.push_str(&format!(
-                "<file path=\"{}\">\n{}\n</file>\n\n",
-                att.label.trim_start_matches('@'),
-                att.content,
-            ));
-            attachments.push(att);
-        }
+        let redacted = redactor.redact(&att.content).redacted;
+        att.content = limit_size(&redacted, MAX_FILE_SIZE as usize);
+        context_block.push_str(&format!("{open_tag}\n{}\n{close_tag}\n\n", att.content));
+        attachments.push(att);
     }
 
     let augmented = if context_block.is_empty() {
@@ -296,6 +319,214 @@ pub fn resolve_and_build_prompt_with_symbols(
     (attachments, augmented)
 }
 
+/// Resolve a single reference (without its leading `@`) to its opening tag,
+/// closing tag and raw attachment.
+fn resolve_reference(
+    reference: &str,
+    cwd: &Path,
+    semantic_bridge: Option<&SemanticBridge>,
+    terminal: Option<&dyn TerminalContext>,
+) -> Option<(String, &'static str, ContextAttachment)> {
+    // @symbol:name
+    if let Some(symbol_name) = crate::semantic_bridge::extract_symbol_name(reference) {
+        let def = semantic_bridge?.resolve_symbol(symbol_name)?;
+        let open = format!(
+            "<symbol name=\"{}\" kind=\"{}\" file=\"{}\" line=\"{}\">",
+            def.name,
+            def.kind,
+            def.file.display(),
+            def.start_line,
+        );
+        let att = ContextAttachment {
+            label: format!("@symbol:{}", def.name),
+            path: def.file,
+            content: def.source,
+        };
+        return Some((open, "</symbol>", att));
+    }
+
+    // @diff / @diff:staged
+    if reference == "diff" || reference.starts_with("diff:") {
+        let staged = match reference.strip_prefix("diff:") {
+            None => false,
+            Some("staged" | "cached") => true,
+            Some(_) => return None,
+        };
+        let content = git_diff_text(cwd, staged).ok()?;
+        let att = ContextAttachment {
+            label: format!("@{reference}"),
+            path: cwd.to_path_buf(),
+            content,
+        };
+        return Some((format!("<diff staged=\"{staged}\">"), "</diff>", att));
+    }
+
+    // @commit:<rev>
+    if let Some(rev) = reference.strip_prefix("commit:") {
+        let content = git_show_text(cwd, rev).ok()?;
+        let att = ContextAttachment {
+            label: format!("@commit:{rev}"),
+            path: cwd.to_path_buf(),
+            content,
+        };
+        return Some((format!("<commit rev=\"{rev}\">"), "</commit>", att));
+    }
+
+    // @pane:<id>
+    if let Some(id) = reference.strip_prefix("pane:") {
+        let id: usize = id.parse().ok()?;
+        let content = terminal?.pane_text(id, PANE_CONTEXT_LINES)?;
+        let att = ContextAttachment {
+            label: format!("@pane:{id}"),
+            path: PathBuf::new(),
+            content,
+        };
+        return Some((format!("<pane id=\"{id}\">"), "</pane>", att));
+    }
+
+    // @block:<n>
+    if let Some(index) = reference.strip_prefix("block:") {
+        let index: usize = index.parse().ok()?;
+        let (command, output) = terminal?.block_text(index)?;
+        let content = match command {
+            Some(cmd) => format!("$ {cmd}\n{output}"),
+            None => output,
+        };
+        let att = ContextAttachment {
+            label: format!("@block:{index}"),
+            path: PathBuf::new(),
+            content,
+        };
+        return Some((format!("<block index=\"{index}\">"), "</block>", att));
+    }
+
+    // @dir:<path>
+    if let Some(dir) = reference.strip_prefix("dir:") {
+        let path = cwd.join(dir);
+        if !path.is_dir() {
+            return None;
+        }
+        let att = ContextAttachment {
+            label: format!("@dir:{dir}"),
+            content: dir_tree(&path),
+            path,
+        };
+        return Some((format!("<dir path=\"{dir}\">"), "</dir>", att));
+    }
+
+    // Standard file resolution
+    let direct_path = cwd.join(reference);
+    let att = if direct_path.is_file() {
+        read_attachment(&direct_path, cwd)
+    } else {
+        search_files(reference, cwd)
+            .first()
+            .and_then(|p| read_attachment(p, cwd))
+    }?;
+    let open = format!("<file path=\"{}\">", att.label.trim_start_matches('@'));
+    Some((open, "</file>", att))
+}
+
+/// Cap `text` at `max_bytes`, cutting on a line boundary where possible and
+/// noting how much was dropped.
+fn limit_size(text: &str, max_bytes: usize) -> String {
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    if let Some(nl) = text[..end].rfind('\n') {
+        end = nl;
+    }
+    format!(
+        "{}\n[... truncated {} bytes ...]",
+        &text[..end],
+        text.len() - end
+    )
+}
+
+/// Run a git command in `cwd` and return its stdout.
+fn run_git(cwd: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .map_err(|e| format!("failed to run git: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git {} failed: {}", args[0], stderr.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The working-tree (or staged) diff as plain text.
+fn git_diff_text(cwd: &Path, staged: bool) -> Result<String, String> {
+    let mut args = vec!["diff", "--no-color"];
+    if staged {
+        args.push("--staged");
+    }
+    let diff = run_git(cwd, &args)?;
+    if !diff.trim().is_empty() {
+        Ok(diff)
+    } else if staged {
+        Ok("(no staged changes)".to_string())
+    } else {
+        Ok("(no unstaged changes)".to_string())
+    }
+}
+
+/// `git show --stat --patch` for a single revision.
+fn git_show_text(cwd: &Path, rev: &str) -> Result<String, String> {
+    // A revision starting with `-` would be parsed as an option.
+    if rev.is_empty() || rev.starts_with('-') {
+        return Err(format!("invalid revision: {rev:?}"));
+    }
+    run_git(cwd, &["show", "--no-color", "--stat", "--patch", rev, "--"])
+}
+
+/// A `.gitignore`-aware, indented tree listing of `root`.
+///
+/// Directories end in `/`. The listing stops after `MAX_DIR_ENTRIES` entries
+/// or `MAX_DIR_DEPTH` levels.
+fn dir_tree(root: &Path) -> String {
+    let walker = ignore::WalkBuilder::new(root)
+        .hidden(true)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .max_depth(Some(MAX_DIR_DEPTH))
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build();
+
+    let mut out = String::new();
+    let mut count = 0;
+    for entry in walker.flatten() {
+        if entry.depth() == 0 {
+            continue;
+        }
+        if count == MAX_DIR_ENTRIES {
+            out.push_str("[... more entries omitted ...]\n");
+            break;
+        }
+        let name = entry.file_name().to_string_lossy();
+        let slash = match entry.file_type() {
+            Some(ft) if ft.is_dir() => "/",
+            _ => "",
+        };
+        let indent = "  ".repeat(entry.depth() - 1);
+        out.push_str(&format!("{indent}{name}{slash}\n"));
+        count += 1;
+    }
+    if out.is_empty() {
+        out.push_str("(empty directory)\n");
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -438,4 +669,116 @@ mod tests {
         assert!(attachments.is_empty());
         assert_eq!(prompt, "explain");
     }
+
+    struct FakeTerminal;
+
+    impl TerminalContext for FakeTerminal {
+        fn pane_text(&self, pane_id: usize, _max_lines: usize) -> Option<String> {
+            (pane_id == 3).then(|| "$ cargo build\nerror[E0308]: mismatched types".to_string())
+        }
+
+        fn block_text(&self, index: usize) -> Option<(Option<String>, String)> {
+            (index == 0).then(|| (Some("ls".to_string()), "README.md\nsrc".to_string()))
+        }
+    }
+
+    fn resolve_with_terminal(input: &str, cwd: &Path) -> (Vec<ContextAttachment>, String) {
+        resolve_and_build_prompt_with_context(
+            input,
+            cwd,
+            None,
+            Some(&FakeTerminal),
+            &Redactor::new(),
+        )
+    }
+
+    fn git(dir: &Path, args: &[&str]) -> bool {
+        Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .is_ok_and(|o| o.status.success())
+    }
+
+    #[test]
+    fn test_limit_size() {
+        assert_eq!(limit_size("short", 100), "short");
+        let text = "line one\nline two\nline three\n";
+        let limited = limit_size(text, 15);
+        assert!(limited.starts_with("line one\n[... truncated"));
+        assert!(limited.ends_with("bytes ...]"));
+        // Never splits a multi-byte character.
+        assert!(limit_size("ééééé", 3).starts_with('é'));
+    }
+
+    #[test]
+    fn test_pane_and_block_references() {
+        let dir = setup_test_dir();
+        let (attachments, prompt) = resolve_with_terminal("@pane:3 @block:0 why?", dir.path());
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(attachments[0].label, "@pane:3");
+        assert_eq!(attachments[1].label, "@block:0");
+        assert!(prompt.contains("<pane id=\"3\">\n$ cargo build"));
+        assert!(prompt.contains("<block index=\"0\">\n$ ls\nREADME.md"));
+        assert!(prompt.ends_with("why?"));
+    }
+
+    #[test]
+    fn test_terminal_references_unresolved() {
+        let dir = setup_test_dir();
+        let (attachments, prompt) =
+            resolve_with_terminal("@pane:9 @block:7 @pane:x hi", dir.path());
+        assert!(attachments.is_empty());
+        assert_eq!(prompt, "hi");
+
+        // Without a terminal provider they are ignored.
+        let (attachments, _) =
+            resolve_and_build_prompt_with_symbols("@pane:3 hi", dir.path(), None);
+        assert!(attachments.is_empty());
+    }
+
+    #[test]
+    fn test_dir_reference() {
+        let dir = setup_test_dir();
+        let (attachments, prompt) = resolve_with_terminal("@dir:. layout?", dir.path());
+        assert_eq!(attachments.len(), 1);
+        let tree = &attachments[0].content;
+        assert!(tree.contains("src/\n  lib.rs\n  main.rs\n"));
+        assert!(tree.contains("README.md\n"));
+        assert!(prompt.contains("<dir path=\".\">"));
+
+        let (attachments, _) = resolve_with_terminal("@dir:nope x", dir.path());
+        assert!(attachments.is_empty());
+    }
+
+    #[test]
+    fn test_diff_and_commit_references() {
+        let dir = setup_test_dir();
+        let base = dir.path();
+        if !git(base, &["init", "-q"])
+            || !git(base, &["add", "README.md"])
+            || !git(base, &["commit", "-q", "-m", "initial readme"])
+        {
+            return; // git unavailable
+        }
+        fs::write(base.join("README.md"), "# Test Project\nmore\n").unwrap();
+
+        let (attachments, prompt) = resolve_with_terminal("@diff review", base);
+        assert_eq!(attachments.len(), 1);
+        assert!(attachments[0].content.contains("+more"));
+        assert!(prompt.contains("<diff staged=\"false\">"));
+
+        let (attachments, _) = resolve_with_terminal("@diff:staged review", base);
+        assert_eq!(attachments[0].content, "(no staged changes)");
+
+        let (attachments, prompt) = resolve_with_terminal("@commit:HEAD explain", base);
+        assert_eq!(attachments.len(), 1);
+        assert!(attachments[0].content.contains("initial readme"));
+        assert!(prompt.contains("<commit rev=\"HEAD\">"));
+
+        let (attachments, _) = resolve_with_terminal("@commit:--help @commit:nope x", base);
+        assert!(attachments.is_empty());
+    }
 }
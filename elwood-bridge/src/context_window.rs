@@ -0,0 +1,806 @@
+//! Token-aware management of the agent's context window.
+//!
+//! The runtime mirrors the conversation it sends the model in a
+//! [`ContextWindow`]. User messages are split into the prompt itself, `@`
+//! attachments (see [`crate::context`]), sibling pane snapshots (see
+//! [`crate::observer`]) and the git and code context the runtime adds; replies
+//! and full tool output are recorded as they stream. Sizes are estimated with
+//! [`estimate_tokens`] and measured against the active model's
+//! [`context_budget`](crate::model_router::ModelConfig::context_budget).
+//!
+//! Once a turn would take usage past [`AUTO_COMPACT_AT`] of the budget, or on
+//! `/compact`, [`ContextWindow::compact`] frees space in a fixed order, oldest
+//! turns first and leaving the last [`KEEP_RECENT_TURNS`] alone:
+//!
+//! 1. stale pane snapshots and injected git/code context,
+//! 2. old tool output,
+//! 3. large attachments,
+//! 4. whole turns, folded into an extractive summary.
+//!
+//! Dropped items leave a one-line stub so the agent knows what it no longer
+//! sees. The runtime then rebuilds its message list from
+//! [`ContextWindow::messages`].
+
+use std::collections::HashMap;
+
+use crate::session_resume::{AgentMessage, Role};
+
+/// Fraction of the budget at which a new turn triggers compaction.
+pub const AUTO_COMPACT_AT: f64 = 0.8;
+
+/// Fraction of the budget that compaction aims to get back under.
+pub const COMPACT_TARGET: f64 = 0.5;
+
+/// Turns, counting the one in progress, that compaction keeps intact.
+pub const KEEP_RECENT_TURNS: usize = 2;
+
+/// Items smaller than this aren't worth replacing with a stub.
+const MIN_ELIDE_TOKENS: usize = 64;
+
+/// Longest prompt or reply excerpt kept per turn in a summary.
+const SUMMARY_EXCERPT_CHARS: usize = 160;
+
+/// Most summary lines kept; older ones are counted instead.
+const MAX_SUMMARY_LINES: usize = 200;
+
+/// First line of a summary item.
+const SUMMARY_HEADER: &str = "[Summary of earlier conversation, compacted to save context]";
+
+/// Tags wrapping injected content, as written by `context` and `observer`.
+const BLOCK_TAGS: [&str; 7] = ["file", "symbol", "diff", "commit", "dir", "pane", "block"];
+
+/// Rough token count of `text`: four ASCII characters per token, one per
+/// other character (CJK text and symbols tokenize far less densely).
+pub fn estimate_tokens(text: &str) -> usize {
+    let (ascii, other) = text.chars().fold((0usize, 0usize), |(a, o), c| {
+        if c.is_ascii() {
+            (a + 1, o)
+        } else {
+            (a, o + 1)
+        }
+    });
+    ascii.div_ceil(4) + other
+}
+
+/// What a piece of the conversation is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextKind {
+    /// Text the user typed.
+    Prompt,
+    /// An `@` reference resolved into the message.
+    Attachment,
+    /// A sibling pane's screen, added by the observer.
+    PaneSnapshot,
+    /// Git status or relevant code added by the runtime.
+    Enrichment,
+    /// Streamed reply text.
+    Reply,
+    /// A tool call and its output.
+    ToolOutput,
+    /// Summary of compacted turns.
+    Summary,
+}
+
+impl ContextKind {
+    /// Whether the kind belongs to the user's side of the conversation.
+    fn is_user_side(self) -> bool {
+        !matches!(self, Self::Reply | Self::ToolOutput)
+    }
+
+    /// Compaction drops lower ranks first; `None` for kinds that are only
+    /// ever summarized.
+    fn drop_rank(self) -> Option<u8> {
+        match self {
+            Self::PaneSnapshot | Self::Enrichment => Some(0),
+            Self::ToolOutput => Some(1),
+            Self::Attachment => Some(2),
+            Self::Prompt | Self::Reply | Self::Summary => None,
+        }
+    }
+}
+
+/// One piece of the conversation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextItem {
+    pub kind: ContextKind,
+    /// Turn the item belongs to, counting from 1.
+    pub turn: usize,
+    /// Attachment reference, tool call or enrichment name.
+    pub label: String,
+    /// Whether the content was replaced with a stub.
+    pub elided: bool,
+    text: String,
+    tokens: usize,
+}
+
+impl ContextItem {
+    fn new(kind: ContextKind, turn: usize, label: &str, text: String) -> Self {
+        Self {
+            kind,
+            turn,
+            label: label.to_string(),
+            elided: false,
+            tokens: estimate_tokens(&text),
+            text,
+        }
+    }
+
+    /// The text sent to the model.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Estimated tokens.
+    pub fn tokens(&self) -> usize {
+        self.tokens
+    }
+
+    fn append(&mut self, text: &str) {
+        self.text.push_str(text);
+        self.tokens += estimate_tokens(text);
+    }
+
+    /// Replace the content with a stub naming what was dropped.
+    fn elide(&mut self) {
+        let dropped = format!("elided to save context, ~{} tokens", self.tokens);
+        self.text = match self.kind {
+            ContextKind::ToolOutput => format!("\n[Tool: {}]\n[output {dropped}]\n", self.label),
+            ContextKind::PaneSnapshot => format!("[{} snapshot {dropped}]\n", self.label),
+            _ => format!("[{} {dropped}]\n", self.label),
+        };
+        self.tokens = estimate_tokens(&self.text);
+        self.elided = true;
+    }
+}
+
+/// What a compaction did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompactReport {
+    /// Estimated tokens before and after.
+    pub before: usize,
+    pub after: usize,
+    /// Items replaced with a stub.
+    pub elided: usize,
+    /// Turns folded into the summary.
+    pub summarized_turns: usize,
+}
+
+impl CompactReport {
+    /// Whether anything was dropped.
+    pub fn changed(&self) -> bool {
+        self.elided > 0 || self.summarized_turns > 0
+    }
+
+    /// One line for the chat area, e.g.
+    /// `Context compacted: 150.2K → 61.0K tokens (4 items elided, 9 turns summarized)`.
+    pub fn describe(&self) -> String {
+        if !self.changed() {
+            return format!(
+                "Nothing to compact: {} tokens, all in recent turns",
+                format_tokens(self.after)
+            );
+        }
+        format!(
+            "Context compacted: {} \u{2192} {} tokens ({} item{} elided, {} turn{} summarized)",
+            format_tokens(self.before),
+            format_tokens(self.after),
+            self.elided,
+            if self.elided == 1 { "" } else { "s" },
+            self.summarized_turns,
+            if self.summarized_turns == 1 { "" } else { "s" },
+        )
+    }
+}
+
+/// The conversation as the model sees it, split into droppable pieces.
+#[derive(Debug, Clone, Default)]
+pub struct ContextWindow {
+    items: Vec<ContextItem>,
+    turn: usize,
+    /// Tool call ID → index of its item, while the call runs.
+    open_tools: HashMap<String, usize>,
+}
+
+impl ContextWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the conversation.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn items(&self) -> &[ContextItem] {
+        &self.items
+    }
+
+    /// Estimated tokens of the whole conversation.
+    pub fn tokens(&self) -> usize {
+        self.items.iter().map(|item| item.tokens).sum()
+    }
+
+    /// Start a new turn; items pushed from here on belong to it.
+    pub fn begin_turn(&mut self) {
+        self.turn += 1;
+        self.open_tools.clear();
+    }
+
+    /// Add an item of `kind` to the current turn.
+    pub fn push(&mut self, kind: ContextKind, label: &str, text: &str) {
+        if !text.is_empty() {
+            self.items
+                .push(ContextItem::new(kind, self.turn, label, text.to_string()));
+        }
+    }
+
+    /// A user message, starting a new turn.
+    pub fn push_user(&mut self, content: &str) {
+        self.begin_turn();
+        self.push_prompt(content);
+    }
+
+    /// Message text for the current turn, with attachments and pane
+    /// snapshots split out.
+    pub fn push_prompt(&mut self, content: &str) {
+        for (kind, label, text) in split_message(content) {
+            self.push(kind, &label, text);
+        }
+    }
+
+    /// Streamed reply text; consecutive chunks form one item.
+    pub fn push_reply(&mut self, delta: &str) {
+        match self.items.last_mut() {
+            Some(last) if last.kind == ContextKind::Reply && last.turn == self.turn => {
+                last.append(delta)
+            }
+            _ => self.push(ContextKind::Reply, "", delta),
+        }
+    }
+
+    /// A tool call started; its output follows in [`Self::tool_finished`].
+    pub fn tool_started(&mut self, tool_id: &str, tool_name: &str, arguments: &str) {
+        let label = format!("{tool_name}({})", excerpt(arguments, 80));
+        self.push(
+            ContextKind::ToolOutput,
+            &label,
+            &format!("\n[Tool: {label}]\n"),
+        );
+        self.open_tools
+            .insert(tool_id.to_string(), self.items.len() - 1);
+    }
+
+    /// A tool call finished with `output` (or an error message).
+    pub fn tool_finished(&mut self, tool_id: &str, output: &str) {
+        if let Some(item) = self
+            .open_tools
+            .remove(tool_id)
+            .and_then(|index| self.items.get_mut(index))
+        {
+            item.append(output);
+            item.append("\n");
+        }
+    }
+
+    /// Replace the conversation with a restored one.
+    pub fn restore(&mut self, messages: &[AgentMessage]) {
+        self.clear();
+        for message in messages {
+            match message.role {
+                Role::User => self.push_user(&message.content),
+                Role::Assistant => self.push_reply(&message.content),
+            }
+        }
+    }
+
+    /// The conversation to send the model.
+    ///
+    /// Adjacent items on the same side form one message, so the roles
+    /// alternate even where a turn got no reply.
+    pub fn messages(&self) -> Vec<AgentMessage> {
+        let mut messages: Vec<AgentMessage> = Vec::new();
+        let mut last_turn = 0;
+        for item in &self.items {
+            let role = if item.kind.is_user_side() {
+                Role::User
+            } else {
+                Role::Assistant
+            };
+            match messages.last_mut() {
+                Some(last) if last.role == role => {
+                    if item.turn != last_turn {
+                        last.content.push_str("\n\n");
+                    }
+                    last.content.push_str(&item.text);
+                }
+                _ => messages.push(AgentMessage {
+                    role,
+                    content: item.text.clone(),
+                }),
+            }
+            last_turn = item.turn;
+        }
+        messages
+    }
+
+    /// Free space until usage is at most `target` tokens, leaving the last
+    /// `keep_turns` turns intact.
+    ///
+    /// Droppable items of older turns are elided first, in rank order and
+    /// oldest first; if that isn't enough, the older turns are folded into a
+    /// summary.
+    pub fn compact(&mut self, target: usize, keep_turns: usize) -> CompactReport {
+        let before = self.tokens();
+        let recent_from = (self.turn + 1).saturating_sub(keep_turns.max(1));
+        let elided = self.elide_until(target, |item| item.turn < recent_from);
+        let summarized_turns = if self.tokens() > target {
+            self.summarize_before(recent_from)
+        } else {
+            0
+        };
+        CompactReport {
+            before,
+            after: self.tokens(),
+            elided,
+            summarized_turns,
+        }
+    }
+
+    /// Last resort once older turns are compacted: elide attachments and
+    /// tool output in recent turns too, still in rank order.
+    pub fn elide_recent(&mut self, target: usize) -> usize {
+        self.elide_until(target, |_| true)
+    }
+
+    /// Elide droppable items accepted by `filter` until usage is at most
+    /// `target`. Returns how many were elided.
+    fn elide_until(&mut self, target: usize, filter: impl Fn(&ContextItem) -> bool) -> usize {
+        let mut candidates: Vec<usize> = (0..self.items.len())
+            .filter(|&i| {
+                let item = &self.items[i];
+                item.kind.drop_rank().is_some()
+                    && !item.elided
+                    && item.tokens >= MIN_ELIDE_TOKENS
+                    && filter(item)
+            })
+            .collect();
+        candidates.sort_by_key(|&i| (self.items[i].kind.drop_rank(), self.items[i].turn));
+
+        let mut total = self.tokens();
+        let mut elided = 0;
+        for i in candidates {
+            if total <= target {
+                break;
+            }
+            let before = self.items[i].tokens;
+            self.items[i].elide();
+            total = total - before + self.items[i].tokens;
+            elided += 1;
+        }
+        elided
+    }
+
+    /// Fold every turn before `recent_from` into one summary item. Returns
+    /// how many turns were folded (not counting an earlier summary).
+    fn summarize_before(&mut self, recent_from: usize) -> usize {
+        let split = self
+            .items
+            .iter()
+            .position(|item| item.turn >= recent_from)
+            .unwrap_or(self.items.len());
+        if split == 0 {
+            return 0;
+        }
+        let old: Vec<ContextItem> = self.items.drain(..split).collect();
+        self.open_tools.clear();
+
+        let mut lines: Vec<String> = Vec::new();
+        let mut turns = 0;
+        let mut omitted = 0;
+        let mut last_turn = None;
+        for item in &old {
+            if item.kind == ContextKind::Summary {
+                for line in item.text.lines().skip(1) {
+                    match line
+                        .strip_prefix("- (")
+                        .and_then(|rest| rest.split_once(' '))
+                        .and_then(|(n, _)| n.parse::<usize>().ok())
+                    {
+                        Some(n) => omitted += n,
+                        None => lines.push(line.to_string()),
+                    }
+                }
+                continue;
+            }
+            if last_turn != Some(item.turn) {
+                last_turn = Some(item.turn);
+                turns += 1;
+                lines.extend(summarize_turn(&old, item.turn));
+            }
+        }
+
+        if lines.len() > MAX_SUMMARY_LINES {
+            // Cut on a turn boundary
+            let mut cut = lines.len() - MAX_SUMMARY_LINES;
+            while cut < lines.len() && lines[cut].starts_with("  ") {
+                cut += 1;
+            }
+            omitted += lines[..cut].iter().filter(|l| l.starts_with("- ")).count();
+            lines.drain(..cut);
+        }
+        let mut text = String::from(SUMMARY_HEADER);
+        if omitted > 0 {
+            text.push_str(&format!("\n- ({omitted} earlier turns omitted)"));
+        }
+        for line in &lines {
+            text.push('\n');
+            text.push_str(line);
+        }
+
+        let turn = recent_from.saturating_sub(1);
+        self.items.insert(
+            0,
+            ContextItem::new(ContextKind::Summary, turn, "summary", text),
+        );
+        turns
+    }
+}
+
+/// Summary lines for one turn: what the user asked, with what attached, and
+/// how the agent answered, with which tools.
+fn summarize_turn(items: &[ContextItem], turn: usize) -> Vec<String> {
+    let items: Vec<&ContextItem> = items.iter().filter(|item| item.turn == turn).collect();
+    let of_kind = |kind: ContextKind| items.iter().filter(move |item| item.kind == kind);
+
+    let prompt: String = of_kind(ContextKind::Prompt)
+        .map(|item| item.text())
+        .collect();
+    let mut user = format!(
+        "- User: {}",
+        excerpt(first_line(&prompt), SUMMARY_EXCERPT_CHARS)
+    );
+    let attached: Vec<&str> = of_kind(ContextKind::Attachment)
+        .map(|item| item.label.as_str())
+        .collect();
+    if !attached.is_empty() {
+        user.push_str(&format!(" (attached {})", attached.join(", ")));
+    }
+
+    let reply: String = of_kind(ContextKind::Reply)
+        .map(|item| item.text())
+        .collect();
+    let tools: Vec<&str> = of_kind(ContextKind::ToolOutput)
+        .map(|item| item.label.split('(').next().unwrap_or(&item.label))
+        .collect();
+    let mut lines = vec![user];
+    if !reply.trim().is_empty() || !tools.is_empty() {
+        let mut agent = format!(
+            "  Agent: {}",
+            excerpt(first_line(&reply), SUMMARY_EXCERPT_CHARS)
+        );
+        if !tools.is_empty() {
+            agent.push_str(&format!(" [tools: {}]", tools.join(", ")));
+        }
+        lines.push(agent);
+    }
+    lines
+}
+
+/// First line with content that isn't a `[Section]` header.
+fn first_line(text: &str) -> &str {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !is_section_header(line))
+        .unwrap_or("")
+}
+
+/// `[Sibling Panes]`, `[User Message]` and the like.
+fn is_section_header(line: &str) -> bool {
+    line.starts_with('[') && line.ends_with(']')
+}
+
+/// At most `max` characters of `text` on one line, with `…` when cut.
+fn excerpt(text: &str, max: usize) -> String {
+    let flat = text.replace(['\n', '\r'], " ");
+    if flat.chars().count() <= max {
+        flat
+    } else {
+        let mut cut: String = flat.chars().take(max).collect();
+        cut.push('\u{2026}');
+        cut
+    }
+}
+
+/// Split a user message into its prompt text and the tagged blocks injected
+/// into it. The pieces concatenate back to `content`.
+fn split_message(content: &str) -> Vec<(ContextKind, String, &str)> {
+    let mut parts = Vec::new();
+    let mut prompt_start = 0;
+    let mut pos = 0;
+    while pos < content.len() {
+        let line_end = line_end(content, pos);
+        if let Some((tag, kind, label)) = block_open(&content[pos..line_end]) {
+            if let Some(end) = block_end(content, line_end, tag) {
+                if prompt_start < pos {
+                    parts.push((
+                        ContextKind::Prompt,
+                        String::new(),
+                        &content[prompt_start..pos],
+                    ));
+                }
+                parts.push((kind, label, &content[pos..end]));
+                prompt_start = end;
+                pos = end;
+                continue;
+            }
+        }
+        pos = line_end;
+    }
+    if prompt_start < content.len() {
+        parts.push((ContextKind::Prompt, String::new(), &content[prompt_start..]));
+    }
+    parts
+}
+
+/// Index just past the line starting at `pos`, newline included.
+fn line_end(content: &str, pos: usize) -> usize {
+    content[pos..]
+        .find('\n')
+        .map_or(content.len(), |i| pos + i + 1)
+}
+
+/// `<file path="src/main.rs">` → ("file", Attachment, "src/main.rs"). Pane
+/// tags with `dims` come from the observer and are snapshots.
+fn block_open(line: &str) -> Option<(&'static str, ContextKind, String)> {
+    let line = line.trim_end();
+    let inner = line.strip_prefix('<')?.strip_suffix('>')?;
+    let tag = BLOCK_TAGS.into_iter().find(|tag| {
+        inner
+            .strip_prefix(tag)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    })?;
+    let attrs = &inner[tag.len()..];
+    let value = attrs.split('"').nth(1).unwrap_or_default();
+    let kind = if tag == "pane" && attrs.contains(" dims=\"") {
+        ContextKind::PaneSnapshot
+    } else {
+        ContextKind::Attachment
+    };
+    let label = match tag {
+        "file" => value.to_string(),
+        "diff" if value == "true" => "@diff:staged".to_string(),
+        "diff" => "@diff".to_string(),
+        _ => format!("@{tag}:{value}"),
+    };
+    Some((tag, kind, label))
+}
+
+/// Index just past the `</tag>` line closing a block whose content starts at
+/// `from`.
+fn block_end(content: &str, from: usize, tag: &str) -> Option<usize> {
+    let close = format!("</{tag}>");
+    let mut pos = from;
+    while pos < content.len() {
+        let end = line_end(content, pos);
+        if content[pos..end].trim_end() == close {
+            return Some(end);
+        }
+        pos = end;
+    }
+    None
+}
+
+/// `1234` → `1.2K`.
+fn format_tokens(tokens: usize) -> String {
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    } else if tokens >= 1_000 {
+        format!("{:.1}K", tokens as f64 / 1_000.0)
+    } else {
+        tokens.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(n: usize) -> String {
+        "word ".repeat(n)
+    }
+
+    /// Turn `n` with an attachment, a pane snapshot, a tool call and a reply.
+    fn push_turn(window: &mut ContextWindow, n: usize) {
+        window.push_user(&format!(
+            "[Sibling Panes]\n<pane id=\"2\" title=\"zsh\" dims=\"80x24\">\n{}\n</pane>\n\n\
+             <file path=\"src/lib{n}.rs\">\n{}\n</file>\n\nquestion {n}",
+            big(200),
+            big(400),
+        ));
+        window.tool_started(&format!("t{n}"), "Bash", "cargo test");
+        window.tool_finished(&format!("t{n}"), &big(300));
+        window.push_reply(&format!("answer {n}"));
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+        assert_eq!(estimate_tokens("日本語"), 3);
+    }
+
+    #[test]
+    fn test_split_message() {
+        let content = "[Sibling Panes]\n<pane id=\"3\" title=\"t\" dims=\"80x24\">\n$ ls\n</pane>\n\n\
+                       <file path=\"a.rs\">\nfn a() {}\n</file>\n\n<pane id=\"4\">\nlog\n</pane>\n\nfix it";
+        let parts = split_message(content);
+        let kinds: Vec<ContextKind> = parts.iter().map(|(kind, _, _)| *kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ContextKind::Prompt,
+                ContextKind::PaneSnapshot,
+                ContextKind::Prompt,
+                ContextKind::Attachment,
+                ContextKind::Prompt,
+                ContextKind::Attachment,
+                ContextKind::Prompt,
+            ]
+        );
+        assert_eq!(parts[1].1, "@pane:3");
+        assert_eq!(parts[3].1, "a.rs");
+        assert_eq!(parts[5].1, "@pane:4");
+        let joined: String = parts.iter().map(|(_, _, text)| *text).collect();
+        assert_eq!(joined, content);
+    }
+
+    #[test]
+    fn test_unclosed_tag_stays_in_prompt() {
+        let parts = split_message("<file path=\"x\">\nno close");
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].0, ContextKind::Prompt);
+    }
+
+    #[test]
+    fn test_messages_alternate() {
+        let mut window = ContextWindow::new();
+        window.push_user("first");
+        window.push_reply("Look");
+        window.tool_started("t1", "ReadFile", "a.rs");
+        window.tool_finished("t1", "contents");
+        window.push_reply("ing good.");
+        window.push_user("second");
+        window.push_user("third");
+
+        let messages = window.messages();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].content, "first");
+        assert_eq!(
+            messages[1].content,
+            "Look\n[Tool: ReadFile(a.rs)]\ncontents\ning good."
+        );
+        assert_eq!(messages[2].role, Role::User);
+        assert_eq!(messages[2].content, "second\n\nthird");
+    }
+
+    #[test]
+    fn test_compact_drops_in_rank_order() {
+        let mut window = ContextWindow::new();
+        for n in 1..=3 {
+            push_turn(&mut window, n);
+        }
+        let total = window.tokens();
+
+        // Just enough to need the first pane snapshot gone
+        let report = window.compact(total - 100, KEEP_RECENT_TURNS);
+        assert_eq!(report.elided, 1);
+        assert_eq!(report.summarized_turns, 0);
+        let snapshot = &window.items()[1];
+        assert_eq!(snapshot.kind, ContextKind::PaneSnapshot);
+        assert!(snapshot.elided);
+        assert!(snapshot.text().starts_with("[@pane:2 snapshot elided"));
+
+        // Then tool output before attachments
+        let report = window.compact(report.after - 150, KEEP_RECENT_TURNS);
+        assert_eq!(report.elided, 1);
+        let tool = window
+            .items()
+            .iter()
+            .find(|item| item.kind == ContextKind::ToolOutput)
+            .unwrap();
+        assert!(tool.elided);
+        assert!(tool.text().contains("[Tool: Bash(cargo test)]"));
+        assert!(window
+            .items()
+            .iter()
+            .filter(|item| item.kind == ContextKind::Attachment)
+            .all(|item| !item.elided));
+    }
+
+    #[test]
+    fn test_compact_summarizes_old_turns() {
+        let mut window = ContextWindow::new();
+        for n in 1..=4 {
+            push_turn(&mut window, n);
+        }
+        let report = window.compact(0, KEEP_RECENT_TURNS);
+        assert_eq!(report.summarized_turns, 2);
+        assert!(report.after < report.before);
+
+        let summary = &window.items()[0];
+        assert_eq!(summary.kind, ContextKind::Summary);
+        assert!(summary.text().starts_with(SUMMARY_HEADER));
+        assert!(summary
+            .text()
+            .contains("- User: question 1 (attached src/lib1.rs)"));
+        assert!(summary.text().contains("  Agent: answer 2 [tools: Bash]"));
+        // Recent turns are untouched
+        assert!(window
+            .items()
+            .iter()
+            .filter(|item| item.turn >= 3)
+            .all(|item| !item.elided));
+
+        // The summary leads the first kept user message
+        let messages = window.messages();
+        assert_eq!(messages[0].role, Role::User);
+        assert!(messages[0].content.starts_with(SUMMARY_HEADER));
+        assert!(messages[0].content.ends_with("question 3"));
+
+        // A second compaction folds the summary into the new one
+        push_turn(&mut window, 5);
+        let report = window.compact(0, KEEP_RECENT_TURNS);
+        assert_eq!(report.summarized_turns, 1);
+        let summary = window.items()[0].text();
+        assert!(summary.contains("question 1"));
+        assert!(summary.contains("question 3"));
+        assert_eq!(summary.matches(SUMMARY_HEADER).count(), 1);
+    }
+
+    #[test]
+    fn test_elide_recent_keeps_prompts() {
+        let mut window = ContextWindow::new();
+        push_turn(&mut window, 1);
+        assert_eq!(window.compact(0, KEEP_RECENT_TURNS).elided, 0);
+        assert_eq!(window.elide_recent(0), 3);
+        let messages = window.messages();
+        assert!(messages[0].content.ends_with("question 1"));
+        assert!(messages[0].content.contains("[src/lib1.rs elided"));
+    }
+
+    #[test]
+    fn test_restore() {
+        let mut window = ContextWindow::new();
+        window.restore(&[
+            AgentMessage {
+                role: Role::User,
+                content: "<file path=\"a.rs\">\nfn a() {}\n</file>\n\nhi".into(),
+            },
+            AgentMessage {
+                role: Role::Assistant,
+                content: "hello".into(),
+            },
+        ]);
+        assert_eq!(window.items().len(), 3);
+        assert_eq!(window.items()[0].kind, ContextKind::Attachment);
+        assert_eq!(window.messages()[1].content, "hello");
+    }
+
+    #[test]
+    fn test_describe_report() {
+        let report = CompactReport {
+            before: 150_200,
+            after: 61_000,
+            elided: 1,
+            summarized_turns: 9,
+        };
+        assert_eq!(
+            report.describe(),
+            "Context compacted: 150.2K \u{2192} 61.0K tokens (1 item elided, 9 turns summarized)"
+        );
+        assert!(CompactReport::default()
+            .describe()
+            .starts_with("Nothing to compact"));
+    }
+}
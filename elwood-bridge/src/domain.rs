@@ -4,6 +4,7 @@
 //! `ElwoodDomain` is a new domain type that spawns `ElwoodPane` instances — panes
 //! that run the Elwood AI agent instead of a shell process.
 
+use crate::context_window::{ContextKind, ContextWindow};
 use crate::pane::ElwoodPane;
 use crate::runtime::{AgentRequest, AgentResponse, RuntimeBridge};
 use crate::semantic_bridge::SemanticBridge;
@@ -11,7 +12,7 @@ use crate::semantic_bridge::SemanticBridge;
 use anyhow::Context;
 use async_trait::async_trait;
 use mux::domain::{alloc_domain_id, DomainId, DomainState};
-use mux::pane::{alloc_pane_id, Pane};
+use mux::pane::{alloc_pane_id, Pane, PaneId};
 use mux::window::WindowId;
 use mux::Mux;
 use parking_lot::Mutex;
@@ -66,11 +67,10 @@ impl ElwoodDomain {
         self.bridge.lock().clone()
     }
 
-    /// Start the tokio runtime thread and agent loop.
-    fn start_runtime(&self) -> Arc<RuntimeBridge> {
-        let bridge = Arc::new(RuntimeBridge::new_async(|request_rx, response_tx| {
-            agent_runtime_loop(request_rx, response_tx)
-        }));
+    /// Start the shared tokio runtime thread and agent loop, working in the
+    /// process's directory on behalf of `pane_id` (the pane that needed it).
+    fn start_runtime(&self, pane_id: Option<PaneId>) -> Arc<RuntimeBridge> {
+        let bridge = new_runtime_bridge(process_cwd(), pane_id);
 
         *self.bridge.lock() = Some(Arc::clone(&bridge));
         *self.state.lock() = InternalState::Attached;
@@ -78,6 +78,19 @@ impl ElwoodDomain {
     }
 }
 
+/// Start a tokio runtime thread running the agent loop in `cwd` for the
+/// agent pane `pane_id`.
+fn new_runtime_bridge(cwd: std::path::PathBuf, pane_id: Option<PaneId>) -> Arc<RuntimeBridge> {
+    Arc::new(RuntimeBridge::new_async(move |request_rx, response_tx| {
+        agent_runtime_loop(request_rx, response_tx, cwd, pane_id)
+    }))
+}
+
+/// The process's working directory (`.` if it can't be read).
+fn process_cwd() -> std::path::PathBuf {
+    std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."))
+}
+
 /// Create the LLM provider for a given provider name.
 ///
 /// Tries in order:
@@ -110,9 +123,17 @@ fn create_provider_for(
 ///
 /// Runs on the dedicated tokio thread inside the RuntimeBridge. Processes
 /// `AgentRequest` messages and translates `AgentEvent`s into `AgentResponse`s.
+///
+/// `cwd` is this runtime's working directory: commands without their own
+/// `working_dir` run there, and `Start { working_dir }` moves it. The
+/// process-wide directory is never changed, since runtimes share the process.
+///
+/// `pane_id` is the agent's own pane, which visible commands split off.
 async fn agent_runtime_loop(
     request_rx: flume::Receiver<AgentRequest>,
     response_tx: flume::Sender<AgentResponse>,
+    mut cwd: std::path::PathBuf,
+    pane_id: Option<PaneId>,
 ) {
     use elwood_core::config::PermissionConfig;
     use elwood_core::provider::Message;
@@ -150,9 +171,11 @@ async fn agent_runtime_loop(
                         AgentRequest::RunCommand {
                             command,
                             working_dir,
+                            env,
                         } => {
                             // Commands still work even without an LLM provider
                             let tx = response_tx.clone();
+                            let dir = working_dir.map_or_else(|| cwd.clone(), Into::into);
                             tokio::spawn(async move {
                                 let shell = std::env::var("SHELL")
                                     .unwrap_or_else(|_| "bash".to_string());
@@ -160,42 +183,41 @@ async fn agent_runtime_loop(
                                 cmd.arg("-c").arg(&command);
                                 cmd.stdout(std::process::Stdio::piped());
                                 cmd.stderr(std::process::Stdio::piped());
-                                if let Some(ref dir) = working_dir {
-                                    cmd.current_dir(dir);
-                                }
+                                cmd.current_dir(&dir);
+                                cmd.envs(env);
                                 let result = tokio::time::timeout(
                                     std::time::Duration::from_secs(300),
                                     cmd.output(),
                                 )
                                 .await;
-                                let response = match result {
-                                    Ok(Ok(output)) => AgentResponse::CommandOutput {
-                                        command: command.clone(),
-                                        stdout: String::from_utf8_lossy(&output.stdout)
-                                            .to_string(),
-                                        stderr: String::from_utf8_lossy(&output.stderr)
-                                            .to_string(),
-                                        exit_code: output.status.code(),
-                                    },
-                                    Ok(Err(e)) => AgentResponse::CommandOutput {
-                                        command: command.clone(),
-                                        stdout: String::new(),
-                                        stderr: format!("Failed to execute: {e}"),
-                                        exit_code: Some(-1),
-                                    },
-                                    Err(_) => AgentResponse::CommandOutput {
-                                        command: command.clone(),
-                                        stdout: String::new(),
-                                        stderr: "Command timed out (5 minute limit)".to_string(),
-                                        exit_code: Some(-1),
-                                    },
+                                let (stdout, stderr, exit_code) = match result {
+                                    Ok(Ok(output)) => (
+                                        String::from_utf8_lossy(&output.stdout).to_string(),
+                                        String::from_utf8_lossy(&output.stderr).to_string(),
+                                        output.status.code(),
+                                    ),
+                                    Ok(Err(e)) => {
+                                        (String::new(), format!("Failed to execute: {e}"), Some(-1))
+                                    }
+                                    Err(_) => (
+                                        String::new(),
+                                        "Command timed out (5 minute limit)".to_string(),
+                                        Some(-1),
+                                    ),
                                 };
-                                let _ = tx.send(response);
+                                let _ = tx.send(AgentResponse::CommandOutput {
+                                    command,
+                                    working_dir: dir,
+                                    stdout,
+                                    stderr,
+                                    exit_code,
+                                });
                             });
                         }
                         AgentRequest::WorkflowRun { name, steps } => {
                             // Workflows work without an LLM provider
                             let tx = response_tx.clone();
+                            let cwd = cwd.clone();
                             tokio::spawn(async move {
                                 let shell = std::env::var("SHELL")
                                     .unwrap_or_else(|_| "bash".to_string());
@@ -207,6 +229,7 @@ async fn agent_runtime_loop(
                                     cmd.arg("-c").arg(command);
                                     cmd.stdout(std::process::Stdio::piped());
                                     cmd.stderr(std::process::Stdio::piped());
+                                    cmd.current_dir(&cwd);
 
                                     let timeout = std::time::Duration::from_secs(300);
                                     let result =
@@ -259,8 +282,20 @@ async fn agent_runtime_loop(
             }
         };
 
+    // Background jobs started by `/bg`, keyed by the pane-assigned job ID;
+    // the agent waits on them with `job_wait`
+    let job_handles = crate::jobs::SharedJobHandles::default();
+
     // Create tool registry with default permissions
     let mut tools_inner = ToolRegistry::new(PermissionConfig::default());
+    tools_inner.register(Arc::new(crate::tools::NativeBashTool::new(
+        pane_id,
+        cwd.clone(),
+        response_tx.clone(),
+    )));
+    tools_inner.register(Arc::new(crate::tools::JobWaitTool::new(Arc::clone(
+        &job_handles,
+    ))));
 
     // Initialize MCP client manager and register discovered tools
     let mut mcp_manager = crate::mcp::McpClientManager::new();
@@ -298,13 +333,16 @@ async fn agent_runtime_loop(
     // Conversation history persists across turns within a session
     let mut messages: Vec<Message> = Vec::new();
 
+    // The same conversation split into droppable pieces, for measuring it
+    // against the model's context budget and compacting it
+    let mut context = ContextWindow::new();
+
     // Cancellation token — recreated for each agent turn
     let mut cancel = CancellationToken::new();
 
     // Initialize semantic bridge for code-aware context enrichment
     let semantic_bridge = {
-        let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
-        let mut bridge = SemanticBridge::new(cwd);
+        let mut bridge = SemanticBridge::new(cwd.clone());
         // Initialize in a blocking spawn to avoid blocking the event loop
         tokio::task::spawn_blocking(move || {
             bridge.initialize();
@@ -333,15 +371,24 @@ async fn agent_runtime_loop(
 
                 // Reset conversation for new session
                 messages.clear();
+                context.clear();
                 cancel = CancellationToken::new();
 
-                // Set working directory if provided
-                if let Some(ref dir) = working_dir {
-                    let _ = std::env::set_current_dir(dir);
+                // Move this runtime (not the process) to the session's directory
+                if let Some(dir) = working_dir {
+                    cwd = dir.into();
                 }
 
                 // Add user message
+                context.push_user(&prompt);
                 messages.push(Message::user(&prompt));
+                manage_context(
+                    &mut context,
+                    &mut messages,
+                    model_router.active_model().context_budget(),
+                    false,
+                    &response_tx,
+                );
 
                 run_agent_turn(
                     &mut model_router,
@@ -349,6 +396,7 @@ async fn agent_runtime_loop(
                     &tools,
                     &cancel,
                     &mut messages,
+                    &mut context,
                     &response_tx,
                 )
                 .await;
@@ -359,17 +407,15 @@ async fn agent_runtime_loop(
                 cancel = CancellationToken::new();
 
                 // Collect git context and prepend to first message of each turn
-                let enriched = {
-                    let cwd = std::env::current_dir()
-                        .unwrap_or_else(|_| std::path::PathBuf::from("."));
+                let enrichment = {
                     let git_ctx = crate::git_info::get_git_context(&cwd);
 
                     let mut parts = Vec::new();
 
                     if !git_ctx.branch.is_empty() {
-                        parts.push(format!(
-                            "[Git Context]\n{}",
-                            git_ctx.format_context()
+                        parts.push((
+                            "git context",
+                            format!("[Git Context]\n{}", git_ctx.format_context()),
                         ));
                     }
 
@@ -384,16 +430,32 @@ async fn agent_runtime_loop(
                                     snippet.id, snippet.score, snippet.text,
                                 ));
                             }
-                            parts.push(ctx);
+                            parts.push(("relevant code", ctx));
                         }
                     }
 
-                    parts.push(format!("[User Message]\n{content}"));
-                    parts.join("\n")
+                    parts
                 };
 
                 // Append user message to ongoing conversation
+                context.begin_turn();
+                let mut enriched = String::new();
+                for (label, part) in &enrichment {
+                    let part = format!("{part}\n");
+                    context.push(ContextKind::Enrichment, label, &part);
+                    enriched.push_str(&part);
+                }
+                let message = format!("[User Message]\n{content}");
+                context.push_prompt(&message);
+                enriched.push_str(&message);
                 messages.push(Message::user(&enriched));
+                manage_context(
+                    &mut context,
+                    &mut messages,
+                    model_router.active_model().context_budget(),
+                    false,
+                    &response_tx,
+                );
 
                 run_agent_turn(
                     &mut model_router,
@@ -401,17 +463,44 @@ async fn agent_runtime_loop(
                     &tools,
                     &cancel,
                     &mut messages,
+                    &mut context,
                     &response_tx,
                 )
                 .await;
             }
 
+            AgentRequest::Restore { messages: restored } => {
+                tracing::info!("Restoring {} messages from a saved session", restored.len());
+                cancel = CancellationToken::new();
+                context.restore(&restored);
+                messages = core_messages(&restored);
+                manage_context(
+                    &mut context,
+                    &mut messages,
+                    model_router.active_model().context_budget(),
+                    false,
+                    &response_tx,
+                );
+            }
+
+            AgentRequest::Compact => {
+                manage_context(
+                    &mut context,
+                    &mut messages,
+                    model_router.active_model().context_budget(),
+                    true,
+                    &response_tx,
+                );
+            }
+
             AgentRequest::RunCommand {
                 command,
                 working_dir,
+                env,
             } => {
                 tracing::info!("Running shell command: {command}");
                 let tx = response_tx.clone();
+                let dir = working_dir.map_or_else(|| cwd.clone(), Into::into);
                 tokio::spawn(async move {
                     let shell = std::env::var("SHELL").unwrap_or_else(|_| "bash".to_string());
 
@@ -419,41 +508,39 @@ async fn agent_runtime_loop(
                     cmd.arg("-c").arg(&command);
                     cmd.stdout(std::process::Stdio::piped());
                     cmd.stderr(std::process::Stdio::piped());
-
-                    if let Some(ref dir) = working_dir {
-                        cmd.current_dir(dir);
-                    }
+                    cmd.current_dir(&dir);
+                    cmd.envs(env);
 
                     let timeout_duration = std::time::Duration::from_secs(300); // 5 minutes
                     let result = tokio::time::timeout(timeout_duration, cmd.output()).await;
 
-                    let response = match result {
-                        Ok(Ok(output)) => AgentResponse::CommandOutput {
-                            command: command.clone(),
-                            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-                            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-                            exit_code: output.status.code(),
-                        },
-                        Ok(Err(e)) => AgentResponse::CommandOutput {
-                            command: command.clone(),
-                            stdout: String::new(),
-                            stderr: format!("Failed to execute: {e}"),
-                            exit_code: Some(-1),
-                        },
-                        Err(_) => AgentResponse::CommandOutput {
-                            command: command.clone(),
-                            stdout: String::new(),
-                            stderr: "Command timed out (5 minute limit)".to_string(),
-                            exit_code: Some(-1),
-                        },
+                    let (stdout, stderr, exit_code) = match result {
+                        Ok(Ok(output)) => (
+                            String::from_utf8_lossy(&output.stdout).to_string(),
+                            String::from_utf8_lossy(&output.stderr).to_string(),
+                            output.status.code(),
+                        ),
+                        Ok(Err(e)) => (String::new(), format!("Failed to execute: {e}"), Some(-1)),
+                        Err(_) => (
+                            String::new(),
+                            "Command timed out (5 minute limit)".to_string(),
+                            Some(-1),
+                        ),
                     };
-                    let _ = tx.send(response);
+                    let _ = tx.send(AgentResponse::CommandOutput {
+                        command,
+                        working_dir: dir,
+                        stdout,
+                        stderr,
+                        exit_code,
+                    });
                 });
             }
 
             AgentRequest::Cancel => {
                 tracing::info!("Agent cancellation requested");
                 cancel.cancel();
+                crate::tools::cancel_native_bash();
             }
 
             AgentRequest::PermissionResponse { .. } => {
@@ -487,7 +574,15 @@ async fn agent_runtime_loop(
                      - Brief description of what to do\n\
                      - Files to modify (if applicable)"
                 );
+                context.push_user(&plan_prompt);
                 messages.push(Message::user(&plan_prompt));
+                manage_context(
+                    &mut context,
+                    &mut messages,
+                    model_router.active_model().context_budget(),
+                    false,
+                    &response_tx,
+                );
 
                 run_agent_turn(
                     &mut model_router,
@@ -495,6 +590,7 @@ async fn agent_runtime_loop(
                     &tools,
                     &cancel,
                     &mut messages,
+                    &mut context,
                     &response_tx,
                 )
                 .await;
@@ -516,6 +612,15 @@ async fn agent_runtime_loop(
                             let _ = response_tx.send(AgentResponse::ModelSwitched {
                                 model_name: active.name.clone(),
                             });
+                            // A smaller window may need the history compacted
+                            let budget = active.context_budget();
+                            manage_context(
+                                &mut context,
+                                &mut messages,
+                                budget,
+                                false,
+                                &response_tx,
+                            );
                         }
                         Err(e) => {
                             tracing::error!("Failed to create provider for {}: {e}", active.provider);
@@ -542,6 +647,7 @@ async fn agent_runtime_loop(
                     cmd.arg("-c").arg(command);
                     cmd.stdout(std::process::Stdio::piped());
                     cmd.stderr(std::process::Stdio::piped());
+                    cmd.current_dir(&cwd);
 
                     let timeout = std::time::Duration::from_secs(300);
                     let result = tokio::time::timeout(timeout, cmd.output()).await;
@@ -594,10 +700,54 @@ async fn agent_runtime_loop(
                 break;
             }
 
-            AgentRequest::RunBackgroundCommand { .. } | AgentRequest::KillJob { .. } => {
-                tracing::warn!("Background job requests not yet implemented in domain runtime");
+            AgentRequest::RunBackgroundCommand {
+                job_id,
+                command,
+                working_dir,
+                log_path,
+            } => {
+                tracing::info!("Starting background job #{job_id}: {command}");
+                let tx = response_tx.clone();
+                let dir = working_dir.map_or_else(|| cwd.clone(), Into::into);
+                let result = crate::jobs::spawn_job(
+                    &command,
+                    Some(dir.as_path()),
+                    log_path.as_deref(),
+                    move |event| {
+                        let _ = tx.send(job_update(job_id, event));
+                    },
+                );
+                match result {
+                    Ok(handle) => {
+                        // Finished jobs stay around for `job_wait`, up to the
+                        // pane's retention limit
+                        let mut handles = job_handles.lock();
+                        let retain = crate::jobs::MAX_RETAINED as u32;
+                        handles.retain(|&id, h| !h.is_finished() || id + retain > job_id);
+                        handles.insert(job_id, handle);
+                    }
+                    Err(e) => {
+                        let _ = response_tx.send(AgentResponse::JobUpdate {
+                            job_id,
+                            status: "failed".into(),
+                            output_chunk: Some(format!("Failed to start: {e}")),
+                            is_stderr: true,
+                            exit_code: Some(-1),
+                            pid: None,
+                        });
+                    }
+                }
             }
 
+            AgentRequest::KillJob { job_id } => match job_handles.lock().get(&job_id).cloned() {
+                Some(handle) if !handle.is_finished() => handle.kill(),
+                _ => {
+                    let _ = response_tx.send(AgentResponse::Error(format!(
+                        "No running job #{job_id}"
+                    )));
+                }
+            },
+
             AgentRequest::SpawnAgent { .. } | AgentRequest::MessageAgent { .. } => {
                 tracing::warn!("Multi-agent requests not yet implemented in domain runtime");
             }
@@ -612,6 +762,7 @@ async fn run_agent_turn(
     tools: &Arc<elwood_core::tools::ToolRegistry>,
     cancel: &tokio_util::sync::CancellationToken,
     messages: &mut Vec<elwood_core::provider::Message>,
+    context: &mut ContextWindow,
     response_tx: &flume::Sender<AgentResponse>,
 ) {
     use elwood_core::agent::{AgentDef, CoreAgent};
@@ -638,10 +789,13 @@ async fn run_agent_turn(
         cancel.clone(),
     );
 
-    // Spawn a task to drain AgentEvents and translate them to AgentResponses
+    // Spawn a task to drain AgentEvents and translate them to AgentResponses,
+    // recording the turn in a copy of the context window on the way
     let drain_tx = response_tx.clone();
+    let mut turn_context = context.clone();
     let drain_handle = tokio::spawn(async move {
         while let Some(event) = event_rx.recv().await {
+            record_event(&mut turn_context, &event);
             let response = translate_event(event);
             if let Some(resp) = response {
                 if drain_tx.send(resp).is_err() {
@@ -649,6 +803,7 @@ async fn run_agent_turn(
                 }
             }
         }
+        turn_context
     });
 
     // Run the agent
@@ -664,7 +819,7 @@ async fn run_agent_turn(
             drop(agent);
 
             // Wait for event drain to finish
-            let _ = drain_handle.await;
+            finish_drain(context, drain_handle).await;
 
             let _ = response_tx.send(AgentResponse::TurnComplete {
                 summary: Some(summary),
@@ -673,11 +828,132 @@ async fn run_agent_turn(
         Err(e) => {
             tracing::error!("Agent execution failed: {e}");
             drop(agent);
-            let _ = drain_handle.await;
+            finish_drain(context, drain_handle).await;
             let _ = response_tx.send(AgentResponse::Error(format!("Agent error: {e}")));
             let _ = response_tx.send(AgentResponse::TurnComplete { summary: None });
         }
     }
+
+    let _ = response_tx.send(AgentResponse::ContextUsage {
+        used_tokens: context.tokens(),
+        budget_tokens: model_router.active_model().context_budget(),
+        compacted: None,
+    });
+}
+
+/// Take the context window recorded by a turn's drain task. If the task
+/// panicked, the previous window is kept rather than losing the conversation.
+async fn finish_drain(
+    context: &mut ContextWindow,
+    drain_handle: tokio::task::JoinHandle<ContextWindow>,
+) {
+    match drain_handle.await {
+        Ok(turn_context) => *context = turn_context,
+        Err(e) => tracing::error!("Event drain task failed, keeping the previous context: {e}"),
+    }
+}
+
+/// Compact the conversation once it passes
+/// [`AUTO_COMPACT_AT`](crate::context_window::AUTO_COMPACT_AT) of `budget`,
+/// or unconditionally for `/compact`, rebuilding `messages` from the
+/// compacted window. Reports usage to the pane either way.
+fn manage_context(
+    context: &mut ContextWindow,
+    messages: &mut Vec<elwood_core::provider::Message>,
+    budget: usize,
+    force: bool,
+    response_tx: &flume::Sender<AgentResponse>,
+) {
+    use crate::context_window::{AUTO_COMPACT_AT, COMPACT_TARGET, KEEP_RECENT_TURNS};
+
+    let limit = (budget as f64 * AUTO_COMPACT_AT) as usize;
+    let target = (budget as f64 * COMPACT_TARGET) as usize;
+    let mut compacted = None;
+    if force || context.tokens() > limit {
+        let mut report = context.compact(if force { 0 } else { target }, KEEP_RECENT_TURNS);
+        if context.tokens() > limit {
+            // The recent turns alone are too big: drop their attachments too
+            report.elided += context.elide_recent(target);
+            report.after = context.tokens();
+        }
+        if report.changed() {
+            tracing::info!("{}", report.describe());
+            *messages = core_messages(&context.messages());
+        }
+        if force || report.changed() {
+            compacted = Some(report.describe());
+        }
+    }
+    let _ = response_tx.send(AgentResponse::ContextUsage {
+        used_tokens: context.tokens(),
+        budget_tokens: budget,
+        compacted,
+    });
+}
+
+/// Core messages for a conversation held on the bridge side.
+fn core_messages(
+    messages: &[crate::session_resume::AgentMessage],
+) -> Vec<elwood_core::provider::Message> {
+    use crate::session_resume::Role;
+    use elwood_core::provider::Message;
+
+    messages
+        .iter()
+        .map(|m| match m.role {
+            Role::User => Message::user(&m.content),
+            Role::Assistant => Message::assistant(&m.content),
+        })
+        .collect()
+}
+
+/// Record what the model sees of an event: reply text and full tool output.
+fn record_event(context: &mut ContextWindow, event: &elwood_core::output::AgentEvent) {
+    use elwood_core::output::AgentEvent;
+
+    match event {
+        AgentEvent::ContentDelta { delta, .. } => context.push_reply(delta),
+        AgentEvent::ToolCallStarted {
+            tool_name,
+            tool_call_id,
+            arguments,
+            ..
+        } => context.tool_started(tool_call_id, tool_name, arguments),
+        AgentEvent::ToolCallCompleted {
+            tool_call_id,
+            result,
+            ..
+        } => context.tool_finished(tool_call_id, result),
+        AgentEvent::ToolCallFailed {
+            tool_call_id,
+            error,
+            ..
+        } => context.tool_finished(tool_call_id, error),
+        _ => {}
+    }
+}
+
+/// Translate a background job event into a `JobUpdate` for the pane.
+fn job_update(job_id: u32, event: crate::jobs::JobEvent) -> AgentResponse {
+    use crate::jobs::JobEvent;
+
+    let (status, output_chunk, is_stderr, exit_code, pid) = match event {
+        JobEvent::Started { pid } => ("running", None, false, None, Some(pid)),
+        JobEvent::Output { line, is_stderr } => ("running", Some(line), is_stderr, None, None),
+        JobEvent::Finished { cancelled: true, .. } => ("cancelled", None, false, None, None),
+        JobEvent::Finished { exit_code, .. } => {
+            let status = if exit_code == Some(0) { "completed" } else { "failed" };
+            (status, None, false, exit_code, None)
+        }
+    };
+    AgentResponse::JobUpdate {
+        job_id,
+        status: status.to_string(),
+        output_chunk,
+        is_stderr,
+        exit_code,
+        pid,
+    }
 }
 
 /// Translate an elwood-core AgentEvent into an AgentResponse for the bridge.
@@ -711,11 +987,16 @@ fn translate_event(event: elwood_core::output::AgentEvent) -> Option<AgentRespon
             result,
             success,
             ..
-        } => Some(AgentResponse::ToolEnd {
-            tool_id: tool_call_id,
-            success,
-            output_preview: truncate_preview(&result, 200),
-        }),
+        } => {
+            // Base64 images would fill the preview; they're drawn instead.
+            let (result, images) = crate::inline_image::split_images(&result);
+            Some(AgentResponse::ToolEnd {
+                tool_id: tool_call_id,
+                success,
+                output_preview: truncate_preview(&result, 200),
+                images,
+            })
+        }
         AgentEvent::ToolCallFailed {
             tool_call_id,
             error,
@@ -724,6 +1005,7 @@ fn translate_event(event: elwood_core::output::AgentEvent) -> Option<AgentRespon
             tool_id: tool_call_id,
             success: false,
             output_preview: truncate_preview(&error, 200),
+            images: Vec::new(),
         }),
 
         // Permission requests
@@ -800,21 +1082,48 @@ impl mux::domain::Domain for ElwoodDomain {
     async fn spawn_pane(
         &self,
         size: TerminalSize,
-        _command: Option<CommandBuilder>,
-        _command_dir: Option<String>,
+        command: Option<CommandBuilder>,
+        command_dir: Option<String>,
     ) -> anyhow::Result<Arc<dyn Pane>> {
-        let bridge = self
-            .get_bridge()
-            .or_else(|| Some(self.start_runtime()))
-            .context("failed to get runtime bridge")?;
-
+        // Fan-out agents (see `multi_agent`) are tagged with ELWOOD_AGENT_ID
+        // and get a runtime of their own so they can work in parallel.
+        let agent_id = command
+            .as_ref()
+            .and_then(|cmd| cmd.get_env("ELWOOD_AGENT_ID"))
+            .and_then(|v| v.to_str())
+            .and_then(|v| v.parse::<u32>().ok());
+
+        // `/resume` panes (ELWOOD_RESUME) restore a saved conversation, which
+        // would clobber the shared runtime's history, so they get one too.
+        let resume_path = command
+            .as_ref()
+            .and_then(|cmd| cmd.get_env("ELWOOD_RESUME"))
+            .map(std::path::PathBuf::from);
+
+        // The pane and its runtime work in `command_dir` (e.g. an agent's
+        // worktree); the process directory is left alone.
+        let cwd = command_dir
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(process_cwd);
+
+        // A pane working elsewhere than the shared runtime needs its own, too
         let pane_id = alloc_pane_id();
-        let pane: Arc<dyn Pane> = Arc::new(ElwoodPane::new(
-            pane_id,
-            self.domain_id,
-            size,
-            bridge,
-        ));
+        let bridge = match (agent_id, &resume_path) {
+            (None, None) if cwd == process_cwd() => self
+                .get_bridge()
+                .or_else(|| Some(self.start_runtime(Some(pane_id))))
+                .context("failed to get runtime bridge")?,
+            _ => new_runtime_bridge(cwd.clone(), Some(pane_id)),
+        };
+
+        let elwood_pane = ElwoodPane::new(pane_id, self.domain_id, size, bridge, cwd);
+        if let Some(agent_id) = agent_id {
+            elwood_pane.attach_agent(agent_id);
+        }
+        if let Some(path) = resume_path {
+            elwood_pane.resume_session(&path);
+        }
+        let pane: Arc<dyn Pane> = Arc::new(elwood_pane);
 
         let mux = Mux::get();
         mux.add_pane(&pane)?;
@@ -844,7 +1153,7 @@ impl mux::domain::Domain for ElwoodDomain {
 
     async fn attach(&self, _window_id: Option<WindowId>) -> anyhow::Result<()> {
         if self.get_bridge().is_none() {
-            self.start_runtime();
+            self.start_runtime(None);
         }
         Ok(())
     }
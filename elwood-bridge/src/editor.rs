@@ -14,7 +14,7 @@
 //! `cursor_col` is 0-based byte column within the line (always clamped to char boundaries).
 
 use crate::runtime::InputMode;
-use crate::vim_mode::{VimMode, VimState};
+use crate::vim_mode::{VimAction, VimMode, VimState};
 
 /// Maximum number of lines in a single input (hard cap for rendering).
 pub const MAX_INPUT_LINES: usize = 8;
@@ -22,6 +22,35 @@ pub const MAX_INPUT_LINES: usize = 8;
 /// Maximum history entries stored per mode.
 const MAX_HISTORY: usize = 1000;
 
+/// Maximum keys a single vim key may expand to through macro replay.
+pub const MAX_REPLAY_KEYS: usize = 10_000;
+
+/// Maximum nesting of macros that replay other macros (`@a` inside `qa`).
+pub const MAX_REPLAY_DEPTH: usize = 16;
+
+/// Effect of a vim key that the host must carry out (the editor applies
+/// everything else to its own buffer).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VimEffect {
+    /// Submit the input, as if Enter was pressed.
+    Submit,
+    /// Place text on the system clipboard.
+    SetClipboard(String),
+    /// Show a message (`:` command output, or why a macro was stopped).
+    Message(String),
+}
+
+/// Bookkeeping while applying one vim key, so a macro that replays itself
+/// can't loop forever.
+struct VimReplay {
+    /// Keys still allowed to be replayed.
+    budget: usize,
+    /// Set once replay was cut off, so it is reported only once.
+    stopped: bool,
+    /// Effects collected for the host.
+    effects: Vec<VimEffect>,
+}
+
 /// The editor's operational state, controlling key event routing.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EditorState {
@@ -156,6 +185,91 @@ impl InputEditor {
         self.vim_mode.as_mut()
     }
 
+    /// Route a key through vim mode and apply the result to the buffer.
+    ///
+    /// Macro replays are fed back through vim one key at a time, up to
+    /// [`MAX_REPLAY_KEYS`] keys and [`MAX_REPLAY_DEPTH`] levels of nesting.
+    /// Returns the effects the host has to carry out; empty if vim is off.
+    pub fn handle_vim_key(&mut self, key: char, ctrl: bool) -> Vec<VimEffect> {
+        let mut replay = VimReplay {
+            budget: MAX_REPLAY_KEYS,
+            stopped: false,
+            effects: Vec::new(),
+        };
+        self.vim_key(key, ctrl, 0, &mut replay);
+        replay.effects
+    }
+
+    fn vim_key(&mut self, key: char, ctrl: bool, depth: usize, replay: &mut VimReplay) {
+        let Some(vim) = self.vim_mode.as_mut() else {
+            return;
+        };
+        let action = vim.handle_key(key, ctrl, &self.lines, self.cursor_row, self.cursor_col);
+        self.apply_vim_action(action, depth, replay);
+    }
+
+    fn apply_vim_action(&mut self, action: VimAction, depth: usize, replay: &mut VimReplay) {
+        match action {
+            VimAction::NoOp | VimAction::ChangeMode(_) => {}
+            VimAction::InsertChar(c) => self.insert_char(c),
+            VimAction::InsertNewline => self.insert_newline(),
+            VimAction::DeleteRange {
+                start_row,
+                start_col,
+                end_row,
+                end_col,
+            } => self.delete_range(start_row, start_col, end_row, end_col),
+            VimAction::DeleteLine(row) => self.delete_line(row),
+            VimAction::MoveCursor { row, col } => self.set_cursor(row, col),
+            VimAction::ReplaceChar { row, col, ch } => self.replace_char(row, col, ch),
+            VimAction::PasteAfter(text) => {
+                if let Some(line) = text.strip_suffix('\n') {
+                    self.move_to_line_end();
+                    self.insert_newline();
+                    self.insert_text(line);
+                } else {
+                    self.move_right();
+                    self.insert_text(&text);
+                }
+            }
+            VimAction::PasteBefore(text) => {
+                if text.ends_with('\n') {
+                    self.move_to_line_start();
+                }
+                self.insert_text(&text);
+            }
+            VimAction::Submit => replay.effects.push(VimEffect::Submit),
+            VimAction::ClearInput => self.clear(),
+            // The input editor keeps no undo stack
+            VimAction::Undo | VimAction::Redo => {}
+            VimAction::Backspace => self.backspace(),
+            VimAction::CommandOutput(msg) => replay.effects.push(VimEffect::Message(msg)),
+            VimAction::SetClipboard(text) => replay.effects.push(VimEffect::SetClipboard(text)),
+            VimAction::ReplayKeys(keys) => {
+                if replay.stopped {
+                    return;
+                }
+                if depth >= MAX_REPLAY_DEPTH || keys.len() > replay.budget {
+                    replay.stopped = true;
+                    replay.effects.push(VimEffect::Message(format!(
+                        "Macro stopped: replay is limited to {MAX_REPLAY_KEYS} keys \
+                         and {MAX_REPLAY_DEPTH} nested macros"
+                    )));
+                    return;
+                }
+                replay.budget -= keys.len();
+                for (key, ctrl) in keys {
+                    self.vim_key(key, ctrl, depth + 1, replay);
+                }
+            }
+            VimAction::Batch(actions) => {
+                for action in actions {
+                    self.apply_vim_action(action, depth, replay);
+                }
+            }
+        }
+    }
+
     // ─── Edit Operations ────────────────────────────────────────────────
 
     /// Insert a single character at the current cursor position.
@@ -310,6 +424,14 @@ impl InputEditor {
         }
     }
 
+    /// Replace the whole buffer with `text`, cursor at the end.
+    ///
+    /// Used when reading back a prompt composed in an external editor.
+    pub fn set_content(&mut self, text: &str) {
+        self.reset_history_state();
+        self.load_from_string(text);
+    }
+
     /// Insert text at the current cursor position (used for paste).
     pub fn insert_text(&mut self, text: &str) {
         self.reset_history_state();
@@ -1105,6 +1227,78 @@ mod tests {
         assert!(!e.at_end_of_line());
     }
 
+    #[test]
+    fn test_set_content_replaces_buffer() {
+        let mut e = editor();
+        for c in "old".chars() { e.insert_char(c); }
+        e.set_content("first\nsecond");
+        assert_eq!(e.content(), "first\nsecond");
+        assert_eq!(e.cursor_row, 1);
+        assert_eq!(e.cursor_col, 6);
+    }
+
+    // ─── Vim ───────────────────────────────────────────────────────
+
+    fn vim_keys(e: &mut InputEditor, keys: &str) -> Vec<VimEffect> {
+        keys.chars()
+            .flat_map(|c| e.handle_vim_key(c, false))
+            .collect()
+    }
+
+    #[test]
+    fn test_vim_keys_edit_buffer() {
+        let mut e = editor();
+        assert!(e.handle_vim_key('i', false).is_empty());
+        e.vim_enable();
+        vim_keys(&mut e, "ihello\x1b0x");
+        assert_eq!(e.content(), "ello");
+    }
+
+    #[test]
+    fn test_vim_clipboard_register_yank() {
+        let mut e = editor();
+        e.set_content("copy me");
+        e.vim_enable();
+        let effects = vim_keys(&mut e, "\"+yy");
+        assert_eq!(
+            effects,
+            vec![VimEffect::SetClipboard("copy me\n".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_vim_macro_replay() {
+        let mut e = editor();
+        e.set_content("abcdef");
+        e.vim_enable();
+        vim_keys(&mut e, "0qaxq2@a");
+        assert_eq!(e.content(), "def");
+    }
+
+    #[test]
+    fn test_vim_recursive_macro_is_capped() {
+        let mut e = editor();
+        e.set_content(&"x".repeat(100));
+        e.vim_enable();
+        // `@a` recorded inside `qa` makes the macro replay itself forever
+        vim_keys(&mut e, "0qax@aq");
+        let effects = vim_keys(&mut e, "@a");
+        assert!(matches!(&effects[..], [VimEffect::Message(m)] if m.starts_with("Macro stopped")));
+        // One `x` while recording, then one per level of nesting
+        assert_eq!(e.content().len(), 100 - 1 - MAX_REPLAY_DEPTH);
+    }
+
+    #[test]
+    fn test_vim_long_replay_is_capped() {
+        let mut e = editor();
+        e.set_content("abc");
+        e.vim_enable();
+        vim_keys(&mut e, "qalq");
+        let effects = vim_keys(&mut e, &format!("{}@a", MAX_REPLAY_KEYS + 1));
+        assert_eq!(effects.len(), 1);
+        assert!(matches!(&effects[0], VimEffect::Message(m) if m.starts_with("Macro stopped")));
+    }
+
     // ─── EditorState enum ──────────────────────────────────────────
 
     #[test]
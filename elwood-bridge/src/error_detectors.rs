@@ -0,0 +1,646 @@
+//! Pluggable, config-driven error detectors.
+//!
+//! [`ContentDetector`](crate::observer::ContentDetector) hard-codes patterns
+//! for the languages Elwood itself is built around. Everything else is
+//! described declaratively here: a detector is a line pattern plus rules for
+//! pulling out the file/line/column and for absorbing the lines that follow
+//! (source snippets, stack frames, boxed diagnostics).
+//!
+//! Built-in detectors (Java/Kotlin, .NET, Elixir, Terraform, ESLint, ruff)
+//! ship in `detectors/builtin.toml`. Users add or override detectors with
+//! `~/.elwood/detectors/*.toml` or `*.lua`; a user detector with the same
+//! `name` as a built-in replaces it.
+//!
+//! ## TOML format
+//!
+//! ```toml
+//! [[detector]]
+//! name = "dotnet-build"
+//! # Matched against each line (trailing whitespace trimmed)
+//! pattern = '^(?P<file>\S+\.cs)\((?P<line>\d+),(?P<column>\d+)\): (?P<level>error|warning) '
+//! error_type = "compile"        # compile | runtime | test | permission | not_found | git | general
+//! severity = "error"            # info | error | fatal
+//! suggested_fix = "dotnet build {file}"   # `{group}` expands captures
+//! auto_fixable = false
+//!
+//! # Multi-line continuation: following lines matching this are part of the error
+//! continuation = '^\s+'
+//! max_continuation = 20
+//! require_continuation = false  # only report when at least one line followed
+//! # Searched across the whole block when the pattern lacks file/line/column
+//! location = 'in (?P<file>\S+):line (?P<line>\d+)'
+//!
+//! # Optional: map roles to other group names or indexes
+//! [detector.captures]
+//! file = "path"
+//! line = 2
+//! ```
+//!
+//! A `level` group, when present, overrides `severity` per match
+//! (`w`/`warning` → info, `fatal` → fatal, anything else → error).
+//!
+//! ## Lua format
+//!
+//! A `.lua` file returns a table shaped like the TOML document, which lets
+//! patterns be assembled programmatically:
+//!
+//! ```lua
+//! local ext = "\\.(?:ex|exs)"
+//! return {
+//!   detector = {
+//!     { name = "credo", pattern = "^┃ \\[[FRCDW]\\] .+ (?P<file>\\S+" .. ext .. "):(?P<line>\\d+)" },
+//!   },
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use regex::{Captures as RegexCaptures, Regex};
+use serde::Deserialize;
+
+use crate::observer::{ErrorDetection, ErrorType, Severity};
+
+/// Detector definitions compiled into the binary.
+const BUILTIN_DETECTORS: &str = include_str!("../detectors/builtin.toml");
+
+/// Continuation lines absorbed per match when `max_continuation` is unset.
+const DEFAULT_MAX_CONTINUATION: usize = 20;
+
+/// Fix shown when a detector doesn't define one.
+const DEFAULT_FIX: &str = "Press Ctrl+F to ask Elwood to fix this error";
+
+/// A detector definition file: `[[detector]]` tables.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DetectorFile {
+    #[serde(default)]
+    pub detector: Vec<DetectorDef>,
+}
+
+/// A single detector as written in TOML or returned from Lua.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DetectorDef {
+    /// Unique name; user detectors replace built-ins with the same name.
+    pub name: String,
+    /// Regex matched against the first line of an error.
+    pub pattern: String,
+    #[serde(default = "default_error_type")]
+    pub error_type: ErrorType,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    /// Fix template; `{group}` placeholders expand to captured text.
+    #[serde(default)]
+    pub suggested_fix: Option<String>,
+    #[serde(default)]
+    pub auto_fixable: bool,
+    /// Which groups hold the file, line and column.
+    #[serde(default)]
+    pub captures: CaptureMap,
+    /// Regex for lines that continue the error after the first line.
+    #[serde(default)]
+    pub continuation: Option<String>,
+    #[serde(default)]
+    pub max_continuation: Option<usize>,
+    /// Drop matches that have no continuation lines.
+    #[serde(default)]
+    pub require_continuation: bool,
+    /// Regex searched across the block for a location the pattern lacks.
+    #[serde(default)]
+    pub location: Option<String>,
+}
+
+fn default_error_type() -> ErrorType {
+    ErrorType::General
+}
+
+fn default_severity() -> Severity {
+    Severity::Error
+}
+
+/// Capture group for each location role, shared by `pattern` and `location`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct CaptureMap {
+    pub file: GroupRef,
+    pub line: GroupRef,
+    pub column: GroupRef,
+}
+
+impl Default for CaptureMap {
+    fn default() -> Self {
+        Self {
+            file: GroupRef::Name("file".into()),
+            line: GroupRef::Name("line".into()),
+            column: GroupRef::Name("column".into()),
+        }
+    }
+}
+
+/// A regex capture group, by index or by name.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum GroupRef {
+    Index(usize),
+    Name(String),
+}
+
+impl GroupRef {
+    fn get<'t>(&self, caps: &RegexCaptures<'t>) -> Option<&'t str> {
+        let m = match self {
+            Self::Index(i) => caps.get(*i),
+            Self::Name(name) => caps.name(name),
+        }?;
+        Some(m.as_str()).filter(|s| !s.is_empty())
+    }
+}
+
+/// A compiled detector.
+#[derive(Debug, Clone)]
+pub struct Detector {
+    def: DetectorDef,
+    pattern: Regex,
+    continuation: Option<Regex>,
+    location: Option<Regex>,
+}
+
+/// A detector hit: the structured error and the lines it covers.
+#[derive(Debug, Clone)]
+pub struct DetectorMatch {
+    /// Name of the detector that matched.
+    pub detector: String,
+    pub detection: ErrorDetection,
+    /// Indexes into the scanned lines, first line through last continuation.
+    pub lines: Range<usize>,
+}
+
+impl Detector {
+    /// Compile a definition, reporting which regex is invalid.
+    pub fn compile(def: DetectorDef) -> Result<Self> {
+        let compile = |field: &str, src: &str| {
+            Regex::new(src).with_context(|| format!("detector '{}': invalid {field}", def.name))
+        };
+        let pattern = compile("pattern", &def.pattern)?;
+        let continuation = def
+            .continuation
+            .as_deref()
+            .map(|s| compile("continuation", s))
+            .transpose()?;
+        let location = def
+            .location
+            .as_deref()
+            .map(|s| compile("location", s))
+            .transpose()?;
+        Ok(Self {
+            def,
+            pattern,
+            continuation,
+            location,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.def.name
+    }
+
+    /// Try to match an error starting at `lines[start]`.
+    pub fn match_at(&self, lines: &[String], start: usize) -> Option<DetectorMatch> {
+        let first = lines.get(start)?.trim_end();
+        let caps = self.pattern.captures(first)?;
+
+        let mut end = start + 1;
+        if let Some(cont) = &self.continuation {
+            let max = self.def.max_continuation.unwrap_or(DEFAULT_MAX_CONTINUATION);
+            while end < lines.len() && end - start <= max && cont.is_match(lines[end].trim_end()) {
+                end += 1;
+            }
+            // Blank lines may continue a block but never end one
+            while end > start + 1 && lines[end - 1].trim().is_empty() {
+                end -= 1;
+            }
+        }
+        if self.def.require_continuation && end == start + 1 {
+            return None;
+        }
+
+        let mut vars: HashMap<String, String> = named_groups(&self.pattern, &caps).into_iter().collect();
+        let roles = &self.def.captures;
+        let mut file = roles.file.get(&caps).map(str::to_string);
+        let mut line: Option<u32> = roles.line.get(&caps).and_then(|s| s.parse().ok());
+        let mut column: Option<u32> = roles.column.get(&caps).and_then(|s| s.parse().ok());
+
+        if let Some(loc) = &self.location {
+            if file.is_none() || line.is_none() {
+                let found = lines[start..end]
+                    .iter()
+                    .find_map(|l| loc.captures(l.trim_end()));
+                if let Some(lc) = found {
+                    for (name, value) in named_groups(loc, &lc) {
+                        vars.entry(name).or_insert(value);
+                    }
+                    file = file.or_else(|| roles.file.get(&lc).map(str::to_string));
+                    line = line.or_else(|| roles.line.get(&lc).and_then(|s| s.parse().ok()));
+                    column = column.or_else(|| roles.column.get(&lc).and_then(|s| s.parse().ok()));
+                }
+            }
+        }
+
+        if let Some(f) = &file {
+            vars.insert("file".into(), f.clone());
+        }
+        if let Some(l) = line {
+            vars.insert("line".into(), l.to_string());
+        }
+        if let Some(c) = column {
+            vars.insert("column".into(), c.to_string());
+        }
+
+        let severity = match vars.get("level").map(|s| s.to_ascii_lowercase()) {
+            Some(level) if level == "w" || level.starts_with("warn") => Severity::Info,
+            Some(level) if level == "fatal" => Severity::Fatal,
+            Some(_) => Severity::Error,
+            None => self.def.severity,
+        };
+
+        let mut message = first.trim().to_string();
+        for l in &lines[start + 1..end] {
+            message.push('\n');
+            message.push_str(l.trim_end());
+        }
+
+        let suggested_fix = match &self.def.suggested_fix {
+            Some(template) => expand(template, &vars),
+            None => DEFAULT_FIX.to_string(),
+        };
+
+        Some(DetectorMatch {
+            detector: self.def.name.clone(),
+            detection: ErrorDetection {
+                error_type: self.def.error_type,
+                severity,
+                message,
+                suggested_fix,
+                source_file: file,
+                line,
+                column,
+                auto_fixable: self.def.auto_fixable,
+            },
+            lines: start..end,
+        })
+    }
+}
+
+fn named_groups(re: &Regex, caps: &RegexCaptures) -> Vec<(String, String)> {
+    re.capture_names()
+        .flatten()
+        .filter_map(|name| Some((name.to_string(), caps.name(name)?.as_str().to_string())))
+        .collect()
+}
+
+/// Expand `{name}` placeholders; unknown names are left as written.
+fn expand(template: &str, vars: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after.find('}') {
+            Some(close) if vars.contains_key(&after[..close]) => {
+                out.push_str(&vars[&after[..close]]);
+                rest = &after[close + 1..];
+            }
+            _ => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// An ordered set of detectors; the first detector to match a line wins.
+#[derive(Debug, Clone, Default)]
+pub struct DetectorSet {
+    detectors: Vec<Detector>,
+}
+
+impl DetectorSet {
+    /// An empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The detectors shipped with Elwood.
+    pub fn builtin() -> Self {
+        Self::from_toml(BUILTIN_DETECTORS).expect("built-in detectors are valid")
+    }
+
+    /// Parse and compile a TOML detector file.
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let file: DetectorFile = toml::from_str(content)?;
+        Self::from_defs(file.detector)
+    }
+
+    /// Evaluate a Lua detector file and compile what it returns.
+    pub fn from_lua(source: &str, chunk_name: &str) -> Result<Self> {
+        let file: DetectorFile = crate::lua_api::eval_table(source, chunk_name)
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        Self::from_defs(file.detector)
+    }
+
+    pub fn from_defs(defs: Vec<DetectorDef>) -> Result<Self> {
+        let detectors = defs
+            .into_iter()
+            .map(Detector::compile)
+            .collect::<Result<_>>()?;
+        Ok(Self { detectors })
+    }
+
+    /// Load every `*.toml` and `*.lua` file in `dir`, in file name order.
+    ///
+    /// Files that fail to load are skipped and reported in the second value.
+    pub fn load_dir(dir: &Path) -> (Self, Vec<String>) {
+        let mut set = Self::new();
+        let mut errors = Vec::new();
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return (set, errors);
+        };
+        let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        paths.sort();
+
+        for path in paths {
+            let loaded = match path.extension().and_then(|e| e.to_str()) {
+                Some("toml") => std::fs::read_to_string(&path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|s| Self::from_toml(&s)),
+                Some("lua") => std::fs::read_to_string(&path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|s| Self::from_lua(&s, &path.to_string_lossy())),
+                _ => continue,
+            };
+            match loaded {
+                Ok(more) => set.extend(more),
+                Err(e) => errors.push(format!("{}: {e:#}", path.display())),
+            }
+        }
+        (set, errors)
+    }
+
+    /// User detectors from `~/.elwood/detectors`, followed by the built-ins
+    /// they don't override.
+    pub fn load_default() -> (Self, Vec<String>) {
+        let (mut set, errors) = Self::load_dir(&detectors_dir());
+        set.extend(Self::builtin());
+        (set, errors)
+    }
+
+    /// Append detectors whose names aren't already present.
+    pub fn extend(&mut self, other: DetectorSet) {
+        for detector in other.detectors {
+            if !self.detectors.iter().any(|d| d.name() == detector.name()) {
+                self.detectors.push(detector);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.detectors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.detectors.is_empty()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.detectors.iter().map(Detector::name)
+    }
+
+    /// Scan `lines` top to bottom. Lines absorbed by a match are not
+    /// rescanned, so each error is reported once.
+    pub fn detect(&self, lines: &[String]) -> Vec<DetectorMatch> {
+        let mut matches = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            match self.detectors.iter().find_map(|d| d.match_at(lines, i)) {
+                Some(m) => {
+                    i = m.lines.end;
+                    matches.push(m);
+                }
+                None => i += 1,
+            }
+        }
+        matches
+    }
+}
+
+/// Directory holding user detector files: `~/.elwood/detectors`.
+pub fn detectors_dir() -> PathBuf {
+    dirs_next::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".elwood")
+        .join("detectors")
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(s: &str) -> Vec<String> {
+        s.lines().map(String::from).collect()
+    }
+
+    fn fixtures_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data/detectors")
+    }
+
+    fn opt<T: ToString>(v: &Option<T>) -> String {
+        v.as_ref().map_or("-".into(), |v| v.to_string())
+    }
+
+    /// One line per match: `detector type severity file:line:column`.
+    fn summarize(matches: &[DetectorMatch]) -> String {
+        matches
+            .iter()
+            .map(|m| {
+                let d = &m.detection;
+                format!(
+                    "{} {} {} {}:{}:{}\n",
+                    m.detector,
+                    d.error_type,
+                    d.severity,
+                    opt(&d.source_file),
+                    opt(&d.line),
+                    opt(&d.column)
+                )
+            })
+            .collect()
+    }
+
+    /// Runs every `test-data/detectors/*.log` through the built-in set and
+    /// compares against the sibling `.expected` file.
+    #[test]
+    fn test_fixture_logs() {
+        let set = DetectorSet::builtin();
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(fixtures_dir())
+            .unwrap()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "log"))
+            .collect();
+        paths.sort();
+        assert!(!paths.is_empty(), "no fixture logs found");
+
+        let mut failures = Vec::new();
+        for log in &paths {
+            let input = std::fs::read_to_string(log).unwrap();
+            let expected = std::fs::read_to_string(log.with_extension("expected"))
+                .unwrap_or_else(|_| panic!("missing .expected for {}", log.display()));
+            let actual = summarize(&set.detect(&lines(&input)));
+            if actual != expected {
+                failures.push(format!(
+                    "{}:\n--- expected\n{expected}--- actual\n{actual}",
+                    log.display()
+                ));
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_builtin_names_unique() {
+        let set = DetectorSet::builtin();
+        let mut names: Vec<_> = set.names().collect();
+        let total = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), total);
+    }
+
+    #[test]
+    fn test_continuation_and_template() {
+        let set = DetectorSet::from_toml(
+            r#"
+            [[detector]]
+            name = "demo"
+            pattern = '^FAIL (?P<test>\w+)$'
+            error_type = "test"
+            suggested_fix = "run {test} at {file}:{line} {missing}"
+            continuation = '^\s+'
+            location = 'at (?P<file>\S+):(?P<line>\d+)'
+            "#,
+        )
+        .unwrap();
+        let input = lines("ok\nFAIL adds\n  expected 2\n  at src/math.ex:7\n\nnext");
+        let matches = set.detect(&input);
+        assert_eq!(matches.len(), 1);
+        let m = &matches[0];
+        assert_eq!(m.lines, 1..4);
+        assert_eq!(m.detection.error_type, ErrorType::Test);
+        assert_eq!(m.detection.message, "FAIL adds\n  expected 2\n  at src/math.ex:7");
+        assert_eq!(m.detection.source_file.as_deref(), Some("src/math.ex"));
+        assert_eq!(m.detection.line, Some(7));
+        assert_eq!(m.detection.suggested_fix, "run adds at src/math.ex:7 {missing}");
+    }
+
+    #[test]
+    fn test_indexed_captures_and_level() {
+        let set = DetectorSet::from_toml(
+            r#"
+            [[detector]]
+            name = "indexed"
+            pattern = '^(\S+)@(\d+) (?P<level>\w+):'
+            captures = { file = 1, line = 2, column = "col" }
+            "#,
+        )
+        .unwrap();
+        let m = &set.detect(&lines("a.rb@3 warning: shadowed"))[0];
+        assert_eq!(m.detection.source_file.as_deref(), Some("a.rb"));
+        assert_eq!(m.detection.line, Some(3));
+        assert_eq!(m.detection.column, None);
+        assert_eq!(m.detection.severity, Severity::Info);
+        assert_eq!(m.detection.error_type, ErrorType::General);
+    }
+
+    #[test]
+    fn test_require_continuation() {
+        let set = DetectorSet::from_toml(
+            r#"
+            [[detector]]
+            name = "boxed"
+            pattern = '^error: '
+            continuation = '^\s*│'
+            require_continuation = true
+            "#,
+        )
+        .unwrap();
+        assert!(set.detect(&lines("error: plain\n\n")).is_empty());
+        assert_eq!(set.detect(&lines("error: boxed\n  │ here")).len(), 1);
+    }
+
+    #[test]
+    fn test_max_continuation() {
+        let set = DetectorSet::from_toml(
+            r#"
+            [[detector]]
+            name = "short"
+            pattern = '^E '
+            continuation = '^ '
+            max_continuation = 2
+            "#,
+        )
+        .unwrap();
+        let m = &set.detect(&lines("E x\n a\n b\n c"))[0];
+        assert_eq!(m.lines, 0..3);
+    }
+
+    #[test]
+    fn test_invalid_regex_names_field() {
+        let err = DetectorSet::from_toml(
+            r#"
+            [[detector]]
+            name = "bad"
+            pattern = 'ok'
+            location = '('
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("detector 'bad': invalid location"));
+    }
+
+    #[test]
+    fn test_unknown_field_rejected() {
+        assert!(DetectorSet::from_toml("[[detector]]\nname = 'x'\npattern = 'y'\nseverty = 'info'").is_err());
+    }
+
+    #[test]
+    fn test_load_dir_overrides_builtin() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("ruff.toml"),
+            "[[detector]]\nname = 'ruff'\npattern = '^never$'\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("broken.toml"), "[[detector]\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let (mut set, errors) = DetectorSet::load_dir(dir.path());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("broken.toml"));
+        set.extend(DetectorSet::builtin());
+        assert_eq!(set.len(), DetectorSet::builtin().len());
+        assert!(set.detect(&lines("app.py:1:8: F401 `os` imported but unused")).is_empty());
+    }
+
+    #[test]
+    fn test_expand_placeholders() {
+        let vars = HashMap::from([("file".to_string(), "a.ts".to_string())]);
+        assert_eq!(expand("fix {file} {", &vars), "fix a.ts {");
+        assert_eq!(expand("{x}{file}", &vars), "{x}a.ts");
+    }
+}
@@ -0,0 +1,167 @@
+//! Compose the input buffer in an external editor (Ctrl+X Ctrl+E).
+//!
+//! Mirrors bash's `edit-and-execute-command`: the current `InputEditor`
+//! buffer is written to a temp file, opened in the user's editor (in a split
+//! pane, so terminal editors work), and read back into the input box once
+//! that pane exits.
+//!
+//! ## Editor resolution
+//!
+//! 1. `$VISUAL`, then `$EDITOR` (may include arguments, e.g. `code -w`;
+//!    split with shell quoting rules, so quoted paths may contain spaces)
+//! 2. The best editor found by [`EditorDetector`], honoring the
+//!    `[editor] preferred` setting from `~/.elwood/elwood.toml`
+//!
+//! GUI editors are launched with their "wait" flag so the pane stays open
+//! until the file is closed.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::ide_bridge::EditorDetector;
+
+/// Prefix for temp files created by this module.
+const TEMP_PREFIX: &str = "elwood-prompt";
+
+/// Resolve the argv (without the file path) used to edit the buffer.
+///
+/// Returns `None` when no editor is configured or detected.
+pub fn editor_argv(
+    visual: Option<&str>,
+    editor: Option<&str>,
+    detector: &EditorDetector,
+    preferred: Option<&str>,
+) -> Option<Vec<String>> {
+    let from_env = [visual, editor]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|s| !s.is_empty());
+    if let Some(cmd) = from_env {
+        // An unbalanced quote is more likely a bare path than shell syntax
+        let argv = shell_words::split(cmd)
+            .unwrap_or_else(|_| cmd.split_whitespace().map(String::from).collect());
+        return Some(argv);
+    }
+
+    let kind = detector.best_editor(preferred)?;
+    let mut argv = vec![kind.binary_name().to_string()];
+    argv.extend(kind.wait_args().iter().map(|s| s.to_string()));
+    Some(argv)
+}
+
+/// Write `content` to a fresh temp file in `dir` and return its path.
+///
+/// A trailing newline is added so editors don't complain about a missing EOL.
+pub fn write_buffer(dir: &Path, content: &str) -> std::io::Result<PathBuf> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = dir.join(format!("{TEMP_PREFIX}-{}-{n}.md", std::process::id()));
+    let mut text = content.to_string();
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// Read the edited buffer back and delete the temp file.
+///
+/// Trailing newlines added by editors are stripped; other whitespace is kept.
+pub fn read_buffer(path: &Path) -> std::io::Result<String> {
+    let text = std::fs::read_to_string(path)?;
+    let _ = std::fs::remove_file(path);
+    Ok(text.trim_end_matches(['\n', '\r']).to_string())
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ide_bridge::WhichProvider;
+
+    struct Available(&'static [&'static str]);
+
+    impl WhichProvider for Available {
+        fn is_available(&self, binary: &str) -> bool {
+            self.0.contains(&binary)
+        }
+    }
+
+    #[test]
+    fn test_visual_takes_precedence() {
+        let detector = EditorDetector::detect_with(&Available(&["code"]));
+        let argv = editor_argv(Some("nvim"), Some("nano"), &detector, None).unwrap();
+        assert_eq!(argv, vec!["nvim"]);
+    }
+
+    #[test]
+    fn test_editor_env_with_args() {
+        let detector = EditorDetector::detect_with(&Available(&[]));
+        let argv = editor_argv(None, Some("code -w"), &detector, None).unwrap();
+        assert_eq!(argv, vec!["code", "-w"]);
+    }
+
+    #[test]
+    fn test_editor_env_quoted_path() {
+        let detector = EditorDetector::detect_with(&Available(&[]));
+        let subl = "/Applications/Sublime Text.app/Contents/SharedSupport/bin/subl";
+        let argv = editor_argv(None, Some(&format!("\"{subl}\" -w")), &detector, None).unwrap();
+        assert_eq!(argv, vec![subl, "-w"]);
+
+        let escaped = r"/opt/my\ editor/bin/ed --wait";
+        let argv = editor_argv(None, Some(escaped), &detector, None).unwrap();
+        assert_eq!(argv, vec!["/opt/my editor/bin/ed", "--wait"]);
+    }
+
+    #[test]
+    fn test_editor_env_unbalanced_quote() {
+        let detector = EditorDetector::detect_with(&Available(&[]));
+        let argv = editor_argv(None, Some("vim -c 'set tw=72"), &detector, None).unwrap();
+        assert_eq!(argv, vec!["vim", "-c", "'set", "tw=72"]);
+    }
+
+    #[test]
+    fn test_blank_env_falls_back_to_detector() {
+        let detector = EditorDetector::detect_with(&Available(&["code", "nvim"]));
+        let argv = editor_argv(Some("  "), None, &detector, None).unwrap();
+        assert_eq!(argv, vec!["code", "--wait"]);
+    }
+
+    #[test]
+    fn test_detector_respects_preference() {
+        let detector = EditorDetector::detect_with(&Available(&["code", "nvim"]));
+        let argv = editor_argv(None, None, &detector, Some("nvim")).unwrap();
+        assert_eq!(argv, vec!["nvim"]);
+    }
+
+    #[test]
+    fn test_no_editor() {
+        let detector = EditorDetector::detect_with(&Available(&[]));
+        assert!(editor_argv(None, None, &detector, None).is_none());
+    }
+
+    #[test]
+    fn test_write_and_read_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_buffer(dir.path(), "line one\nline two").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "line one\nline two\n"
+        );
+
+        std::fs::write(&path, "edited\n  indented\n\n").unwrap();
+        let text = read_buffer(&path).unwrap();
+        assert_eq!(text, "edited\n  indented");
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_write_buffer_unique_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write_buffer(dir.path(), "").unwrap();
+        let b = write_buffer(dir.path(), "").unwrap();
+        assert_ne!(a, b);
+    }
+}
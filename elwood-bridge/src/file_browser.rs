@@ -18,13 +18,33 @@
 //! |   Cargo.toml             320 B             |
 //! |   README.md              2.1 KB            |
 //! |                                            |
-//! | [Enter] Open  [Space] Preview  [@] Attach  |
-//! | [/] Filter  [Esc] Close                    |
+//! | [Enter] Open  [Space] Mark  [@] Attach     |
+//! | [a] New file  [r] Rename  [d] Delete  ...  |
 //! +--------------------------------------------+
 //! ```
+//!
+//! ## File Operations
+//!
+//! | Key       | Action                                              |
+//! |-----------|-----------------------------------------------------|
+//! | `Space`   | Mark/unmark the selected file (multi-select)        |
+//! | `@`       | Attach the marked files (or the selected one)       |
+//! | `a` / `A` | Create a file / directory next to the selection     |
+//! | `r`       | Rename the selected entry                           |
+//! | `d`       | Move the marked (or selected) entries to the trash  |
+//! | `m`       | Move the marked (or selected) entries to a directory |
+//! | `i`       | Toggle showing `.gitignore`d files                  |
+//! | `p`       | Toggle the preview panel                            |
+//!
+//! Destructive operations ask for confirmation in the prompt row. Entries
+//! are decorated with their status from `git status` (see
+//! [`git_ui::get_file_statuses`]).
 
+use crate::git_ui::{self, FileStatus};
+use crate::theme;
+use anyhow::{bail, Context};
 use ignore::WalkBuilder;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 /// Maximum directory depth to walk during a scan.
@@ -59,6 +79,41 @@ pub struct FileEntry {
     pub depth: usize,
 }
 
+/// What the prompt row is asking for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptKind {
+    /// Name of a new file, created inside `parent`.
+    NewFile { parent: PathBuf },
+    /// Name of a new directory, created inside `parent`.
+    NewDir { parent: PathBuf },
+    /// New name for `path`.
+    Rename { path: PathBuf },
+    /// Destination directory for `sources`.
+    MoveTo { sources: Vec<PathBuf> },
+    /// Yes/no confirmation of a move.
+    ConfirmMove {
+        sources: Vec<PathBuf>,
+        dest: PathBuf,
+    },
+    /// Yes/no confirmation of moving `targets` to the trash.
+    ConfirmDelete { targets: Vec<PathBuf> },
+}
+
+impl PromptKind {
+    /// Whether this prompt is answered with `y`/`n` rather than text.
+    pub fn is_confirmation(&self) -> bool {
+        matches!(self, Self::ConfirmMove { .. } | Self::ConfirmDelete { .. })
+    }
+}
+
+/// An in-progress file operation waiting for user input.
+#[derive(Debug, Clone)]
+pub struct FilePrompt {
+    pub kind: PromptKind,
+    /// Text typed so far (unused for confirmations).
+    pub input: String,
+}
+
 /// Interactive file tree browser state.
 pub struct FileTree {
     /// Root directory being browsed.
@@ -79,6 +134,14 @@ pub struct FileTree {
     preview_cache: Option<(PathBuf, Vec<String>)>,
     /// Whether the preview panel is visible.
     pub show_preview: bool,
+    /// Files marked for a multi-file attach, move or delete.
+    pub marked: BTreeSet<PathBuf>,
+    /// Whether `.gitignore`d files are listed.
+    pub show_ignored: bool,
+    /// Active file operation prompt, if any.
+    pub prompt: Option<FilePrompt>,
+    /// Git status of changed paths (absolute), refreshed with the tree.
+    git_status: HashMap<PathBuf, FileStatus>,
 }
 
 impl FileTree {
@@ -96,25 +159,46 @@ impl FileTree {
             scroll_offset: 0,
             preview_cache: None,
             show_preview: false,
+            marked: BTreeSet::new(),
+            show_ignored: false,
+            prompt: None,
+            git_status: HashMap::new(),
         };
-        tree.rebuild_entries();
+        tree.refresh();
         tree
     }
 
-    /// Scan a directory for its immediate children, respecting `.gitignore`.
+    /// Re-read the directory tree and git status from disk.
+    ///
+    /// Marks on paths that no longer exist are dropped.
+    pub fn refresh(&mut self) {
+        self.git_status = load_git_status(&self.root);
+        self.marked.retain(|p| p.exists());
+        self.invalidate_preview();
+        self.rebuild_entries();
+    }
+
+    /// Scan a directory with ignored files hidden.
+    #[cfg(test)]
+    fn scan_directory(path: &Path) -> Vec<FileEntry> {
+        Self::scan_directory_with(path, false)
+    }
+
+    /// Scan a directory for its immediate children, respecting `.gitignore`
+    /// unless `show_ignored` is set.
     ///
     /// Returns entries sorted: directories first (alphabetical), then files
     /// (alphabetical).
-    fn scan_directory(path: &Path) -> Vec<FileEntry> {
+    fn scan_directory_with(path: &Path, show_ignored: bool) -> Vec<FileEntry> {
         let depth_offset = path.components().count();
         let mut dirs = Vec::new();
         let mut files = Vec::new();
 
         let walker = WalkBuilder::new(path)
             .hidden(true)
-            .git_ignore(true)
-            .git_global(true)
-            .git_exclude(true)
+            .git_ignore(!show_ignored)
+            .git_global(!show_ignored)
+            .git_exclude(!show_ignored)
             .max_depth(Some(1)) // immediate children only
             .sort_by_file_name(|a, b| a.cmp(b))
             .build();
@@ -210,7 +294,7 @@ impl FileTree {
             return;
         }
 
-        let children = Self::scan_directory(dir);
+        let children = Self::scan_directory_with(dir, self.show_ignored);
         for mut child in children {
             child.depth = depth;
             let is_expanded = child.entry_type == EntryType::Directory
@@ -239,6 +323,232 @@ impl FileTree {
         }
     }
 
+    /// Select the entry for `path`, if it is visible.
+    pub fn select_path(&mut self, path: &Path) {
+        if let Some(idx) = self.entries.iter().position(|e| e.path == path) {
+            self.selected_index = idx;
+            self.invalidate_preview();
+        }
+    }
+
+    /// Mark or unmark the selected file and move to the next entry.
+    ///
+    /// Directories cannot be marked.
+    pub fn toggle_mark(&mut self) {
+        let Some(entry) = self.entries.get(self.selected_index) else {
+            return;
+        };
+        if entry.entry_type == EntryType::Directory {
+            return;
+        }
+        let path = entry.path.clone();
+        if !self.marked.remove(&path) {
+            self.marked.insert(path);
+        }
+        self.move_down();
+    }
+
+    /// Whether `path` is marked.
+    pub fn is_marked(&self, path: &Path) -> bool {
+        self.marked.contains(path)
+    }
+
+    /// Files to attach: the marked files, or the selected file if none are marked.
+    pub fn attach_targets(&self) -> Vec<PathBuf> {
+        if !self.marked.is_empty() {
+            return self.marked.iter().cloned().collect();
+        }
+        self.selected_entry()
+            .filter(|e| e.entry_type != EntryType::Directory)
+            .map(|e| vec![e.path.clone()])
+            .unwrap_or_default()
+    }
+
+    /// Entries to move or delete: the marked files, or the selected entry.
+    fn operation_targets(&self) -> Vec<PathBuf> {
+        if !self.marked.is_empty() {
+            return self.marked.iter().cloned().collect();
+        }
+        self.selected_path()
+            .map(|p| vec![p.to_path_buf()])
+            .unwrap_or_default()
+    }
+
+    /// Show or hide `.gitignore`d files.
+    pub fn toggle_show_ignored(&mut self) {
+        self.show_ignored = !self.show_ignored;
+        self.rebuild_entries();
+    }
+
+    /// Git status of `path`, if it has uncommitted changes.
+    pub fn git_status_of(&self, path: &Path) -> Option<FileStatus> {
+        self.git_status.get(path).copied()
+    }
+
+    /// Whether anything below directory `dir` has uncommitted changes.
+    fn dir_has_changes(&self, dir: &Path) -> bool {
+        self.git_status.keys().any(|p| p.starts_with(dir))
+    }
+
+    // ── File operations ─────────────────────────────────────────────
+
+    /// Directory that new entries are created in: the selected directory,
+    /// or the parent of the selected file.
+    fn target_dir(&self) -> PathBuf {
+        match self.selected_entry() {
+            Some(e) if e.entry_type == EntryType::Directory => e.path.clone(),
+            Some(e) => e
+                .path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| self.root.clone()),
+            None => self.root.clone(),
+        }
+    }
+
+    fn open_prompt(&mut self, kind: PromptKind, input: String) {
+        self.filter_active = false;
+        self.prompt = Some(FilePrompt { kind, input });
+    }
+
+    /// Prompt for the name of a new file.
+    pub fn begin_new_file(&mut self) {
+        let parent = self.target_dir();
+        self.open_prompt(PromptKind::NewFile { parent }, String::new());
+    }
+
+    /// Prompt for the name of a new directory.
+    pub fn begin_new_dir(&mut self) {
+        let parent = self.target_dir();
+        self.open_prompt(PromptKind::NewDir { parent }, String::new());
+    }
+
+    /// Prompt for a new name for the selected entry.
+    pub fn begin_rename(&mut self) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let (path, name) = (entry.path.clone(), entry.name.clone());
+        self.open_prompt(PromptKind::Rename { path }, name);
+    }
+
+    /// Ask to confirm moving the marked (or selected) entries to the trash.
+    pub fn begin_delete(&mut self) {
+        let targets = self.operation_targets();
+        if !targets.is_empty() {
+            self.open_prompt(PromptKind::ConfirmDelete { targets }, String::new());
+        }
+    }
+
+    /// Prompt for the destination of the marked (or selected) entries.
+    pub fn begin_move(&mut self) {
+        let sources = self.operation_targets();
+        if sources.is_empty() {
+            return;
+        }
+        let dest = self.target_dir();
+        let input = dest
+            .strip_prefix(&self.root)
+            .unwrap_or(&dest)
+            .to_string_lossy()
+            .to_string();
+        self.open_prompt(PromptKind::MoveTo { sources }, input);
+    }
+
+    /// Insert a character into the prompt input.
+    pub fn prompt_insert_char(&mut self, c: char) {
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.input.push(c);
+        }
+    }
+
+    /// Delete the last character of the prompt input.
+    pub fn prompt_backspace(&mut self) {
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.input.pop();
+        }
+    }
+
+    /// Abandon the active prompt.
+    pub fn cancel_prompt(&mut self) {
+        self.prompt = None;
+    }
+
+    /// Submit (or, for confirmations, accept) the active prompt.
+    ///
+    /// Returns `None` if the operation needs another step (a move asks for
+    /// confirmation after the destination is entered) or there is no prompt;
+    /// otherwise the result message of the completed operation.
+    pub fn submit_prompt(&mut self) -> Option<anyhow::Result<String>> {
+        let prompt = self.prompt.take()?;
+        let input = prompt.input.trim().to_string();
+        let result = match prompt.kind {
+            PromptKind::NewFile { parent } => create_file(&parent, &input).map(|path| {
+                self.expanded_dirs.insert(parent);
+                self.finish_op(Some(&path));
+                format!("Created {}", self.display_path(&path))
+            }),
+            PromptKind::NewDir { parent } => create_dir(&parent, &input).map(|path| {
+                self.expanded_dirs.insert(parent);
+                self.finish_op(Some(&path));
+                format!("Created {}/", self.display_path(&path))
+            }),
+            PromptKind::Rename { path } => rename_entry(&path, &input).map(|new_path| {
+                if self.marked.remove(&path) {
+                    self.marked.insert(new_path.clone());
+                }
+                self.finish_op(Some(&new_path));
+                format!("Renamed to {}", self.display_path(&new_path))
+            }),
+            PromptKind::MoveTo { sources } => {
+                if input.is_empty() {
+                    return Some(Err(anyhow::anyhow!("no destination given")));
+                }
+                let dest = self.root.join(&input);
+                self.open_prompt(PromptKind::ConfirmMove { sources, dest }, String::new());
+                return None;
+            }
+            PromptKind::ConfirmMove { sources, dest } => {
+                move_entries(&sources, &dest).map(|moved| {
+                    self.marked.clear();
+                    self.expanded_dirs.insert(dest.clone());
+                    self.finish_op(moved.first().map(PathBuf::as_path));
+                    format!(
+                        "Moved {} to {}",
+                        plural(moved.len()),
+                        self.display_path(&dest)
+                    )
+                })
+            }
+            PromptKind::ConfirmDelete { targets } => {
+                let trashed = targets
+                    .iter()
+                    .map(|t| move_to_trash(t))
+                    .collect::<anyhow::Result<Vec<_>>>();
+                self.marked.clear();
+                self.finish_op(None);
+                trashed.map(|t| format!("Moved {} to the trash", plural(t.len())))
+            }
+        };
+        Some(result)
+    }
+
+    /// Refresh after a file operation and select `path` if given.
+    fn finish_op(&mut self, path: Option<&Path>) {
+        self.refresh();
+        if let Some(path) = path {
+            self.select_path(path);
+        }
+    }
+
+    /// `path` relative to the browser root, for messages.
+    fn display_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+
     /// Move selection up by one.
     pub fn move_up(&mut self) {
         if self.selected_index > 0 {
@@ -361,15 +671,16 @@ impl FileTree {
         let bold = "\x1b[1m";
         let dim = "\x1b[2m";
 
-        // Colors (Tokyo Night)
-        let accent = "\x1b[38;2;122;162;247m";
-        let fg = "\x1b[38;2;192;202;245m";
-        let muted = "\x1b[38;2;86;95;137m";
-        let success = "\x1b[38;2;158;206;106m";
-        let warning = "\x1b[38;2;224;175;104m";
-        let info = "\x1b[38;2;125;207;255m";
-        let border = "\x1b[38;2;59;66;97m";
-        let sel_bg = "\x1b[48;2;40;44;66m";
+        // Colors (active theme)
+        let theme = theme::active();
+        let accent = &theme.ansi_fg(theme.accent);
+        let fg = &theme.ansi_fg(theme.fg_primary);
+        let muted = &theme.ansi_fg(theme.fg_muted);
+        let success = &theme.ansi_fg(theme.success);
+        let warning = &theme.ansi_fg(theme.warning);
+        let info = &theme.ansi_fg(theme.info);
+        let border = &theme.ansi_fg(theme.block_border);
+        let sel_bg = &theme.ansi_bg(theme.bg_tertiary);
 
         // Overlay dimensions
         let overlay_w = (width as usize).min(72).max(40);
@@ -377,8 +688,8 @@ impl FileTree {
         let start_col = ((width as usize).saturating_sub(overlay_w)) / 2 + 1;
         let start_row = ((height as usize).saturating_sub(overlay_h)) / 2 + 1;
 
-        // Calculate content area (minus borders, header, filter, footer)
-        let content_h = overlay_h.saturating_sub(5); // top border + title + filter + footer + bottom border
+        // Calculate content area (minus borders, header, filter, two footer rows)
+        let content_h = overlay_h.saturating_sub(6);
 
         // Handle preview split
         let (tree_w, _preview_w) = if self.show_preview {
@@ -416,7 +727,14 @@ impl FileTree {
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_else(|| "~".to_string());
         let count = self.entries.len();
-        let title = format!(" Files \u{2500}\u{2500} {root_display} \u{2500}\u{2500} {count} items ");
+        let mut title =
+            format!(" Files \u{2500}\u{2500} {root_display} \u{2500}\u{2500} {count} items ");
+        if !self.marked.is_empty() {
+            title.push_str(&format!("\u{2500}\u{2500} {} marked ", self.marked.len()));
+        }
+        if self.show_ignored {
+            title.push_str("\u{2500}\u{2500} +ignored ");
+        }
         let title_len = title.chars().count();
         let fill = overlay_w.saturating_sub(title_len + 2);
 
@@ -432,7 +750,23 @@ impl FileTree {
         let inner_w = overlay_w.saturating_sub(4); // "| " + content + " |"
         out.push_str(&goto(row, start_col));
 
-        if self.filter_active {
+        if let Some(prompt) = &self.prompt {
+            let label = self.prompt_label(&prompt.kind);
+            let (text, cursor) = if prompt.kind.is_confirmation() {
+                (String::new(), "")
+            } else {
+                (prompt.input.clone(), "\u{2588}")
+            };
+            let avail = inner_w.saturating_sub(label.chars().count() + 2);
+            // Keep the end of long input visible.
+            let skip = text.chars().count().saturating_sub(avail);
+            let shown: String = text.chars().skip(skip).collect();
+            let pad = inner_w.saturating_sub(label.chars().count() + shown.chars().count() + 1);
+            out.push_str(&format!(
+                "{accent}\u{2502}{r} {warning}{bold}{label}{r}{fg}{shown}{cursor}{r}{}{accent}\u{2502}{r}",
+                " ".repeat(pad),
+            ));
+        } else if self.filter_active {
             let filter_display: String = self.filter.chars().take(inner_w.saturating_sub(4)).collect();
             let filter_pad = inner_w.saturating_sub(filter_display.chars().count() + 4);
             out.push_str(&format!(
@@ -501,12 +835,33 @@ impl FileTree {
             };
             let size_len = size_str.len();
 
+            // Git status decoration (before the size)
+            let (git_mark, git_color) = match self.git_status_of(&entry.path) {
+                Some(status) => {
+                    let (cr, cg, cb) = status.color().rgb();
+                    (status.code(), format!("\x1b[38;2;{cr};{cg};{cb}m"))
+                }
+                None if entry.entry_type == EntryType::Directory
+                    && self.dir_has_changes(&entry.path) =>
+                {
+                    ('\u{2022}', warning.to_string())
+                }
+                None => (' ', String::new()),
+            };
+
+            // Multi-select marker (in the left padding column)
+            let mark = if self.is_marked(&entry.path) {
+                format!("{info}\u{25CF}{r}")
+            } else {
+                " ".to_string()
+            };
+
             // Available width for name
             let name_avail = tree_w
                 .saturating_sub(4) // borders + padding
                 .saturating_sub(indent_len)
                 .saturating_sub(2) // icon
-                .saturating_sub(size_len + 1); // size + gap
+                .saturating_sub(size_len + 3); // git mark + size + gaps
 
             let name_display: String = entry.name.chars().take(name_avail).collect();
             let name_len = name_display.chars().count();
@@ -517,7 +872,7 @@ impl FileTree {
 
             out.push_str(&goto(row + display_row, start_col));
             out.push_str(&format!(
-                "{accent}\u{2502}{r}{sel_start} {indent}{icon_color}{icon}{r}{sel_start}{name_color}{name_display}{r}{sel_start}{}{muted}{size_str}{r}{sel_start} {sel_end}{accent}\u{2502}{r}",
+                "{accent}\u{2502}{r}{sel_start}{mark}{sel_start}{indent}{icon_color}{icon}{r}{sel_start}{name_color}{name_display}{r}{sel_start}{}{git_color}{git_mark}{r}{sel_start} {muted}{size_str}{r}{sel_start} {sel_end}{accent}\u{2502}{r}",
                 " ".repeat(name_pad),
             ));
         }
@@ -533,22 +888,40 @@ impl FileTree {
         }
         row += content_h;
 
-        // ── Footer with keybindings ──────────────────────────────────
-        let key_bg = "\x1b[48;2;40;44;66m";
-        let key_fg = "\x1b[38;2;192;202;245m";
-
-        out.push_str(&goto(row, start_col));
-        let footer = format!(
-            " {key_bg}{key_fg} Enter {r} {muted}Open{r}  {key_bg}{key_fg} Space {r} {muted}Preview{r}  {key_bg}{key_fg} @ {r} {muted}Attach{r}  {key_bg}{key_fg} Esc {r} {muted}Close{r} ",
-        );
-        // Pad footer to fill width
-        let footer_visible_len = 4 + 5 + 1 + 5 + 2 + 7 + 2 + 1 + 6 + 2 + 3 + 2 + 5; // approx visible chars
-        let footer_pad = overlay_w.saturating_sub(footer_visible_len + 2);
-        out.push_str(&format!(
-            "{accent}\u{2502}{r}{footer}{}{accent}\u{2502}{r}",
-            " ".repeat(footer_pad),
-        ));
-        row += 1;
+        // ── Footer with keybindings (two rows) ───────────────────────
+        let confirming = self.prompt.as_ref().is_some_and(|p| p.kind.is_confirmation());
+        let footer_rows: [&[(&str, &str)]; 2] = if confirming {
+            [&[("y", "Confirm"), ("n", "Cancel")], &[]]
+        } else if self.prompt.is_some() {
+            [&[("Enter", "Submit"), ("Esc", "Cancel")], &[]]
+        } else {
+            [
+                &[
+                    ("Enter", "Open"),
+                    ("Space", "Mark"),
+                    ("@", "Attach"),
+                    ("p", "Preview"),
+                    ("Esc", "Close"),
+                ],
+                &[
+                    ("a/A", "New"),
+                    ("r", "Rename"),
+                    ("d", "Delete"),
+                    ("m", "Move"),
+                    ("i", "Ignored"),
+                    ("/", "Filter"),
+                ],
+            ]
+        };
+        for keys in footer_rows {
+            let (footer, footer_len) = render_key_hints(keys, overlay_w.saturating_sub(2));
+            out.push_str(&goto(row, start_col));
+            out.push_str(&format!(
+                "{accent}\u{2502}{r}{footer}{}{accent}\u{2502}{r}",
+                " ".repeat(overlay_w.saturating_sub(footer_len + 2)),
+            ));
+            row += 1;
+        }
 
         // ── Bottom border ────────────────────────────────────────────
         out.push_str(&goto(row, start_col));
@@ -583,6 +956,49 @@ impl FileTree {
         out
     }
 
+    /// Label shown before the input in the prompt row.
+    fn prompt_label(&self, kind: &PromptKind) -> String {
+        match kind {
+            PromptKind::NewFile { parent } => {
+                format!("New file in {}/: ", self.display_path(parent))
+            }
+            PromptKind::NewDir { parent } => format!("New dir in {}/: ", self.display_path(parent)),
+            PromptKind::Rename { .. } => "Rename to: ".to_string(),
+            PromptKind::MoveTo { sources } => format!("Move {} to: ", plural(sources.len())),
+            PromptKind::ConfirmMove { sources, dest } => format!(
+                "Move {} to {}/? [y/N]",
+                plural(sources.len()),
+                self.display_path(dest)
+            ),
+            PromptKind::ConfirmDelete { targets } => match targets.as_slice() {
+                [one] => format!("Move {} to the trash? [y/N]", self.display_path(one)),
+                many => format!("Move {} to the trash? [y/N]", plural(many.len())),
+            },
+        }
+    }
+}
+
+/// Render `[key] label` hints that fit in `width`, returning the ANSI string
+/// and its visible length.
+fn render_key_hints(keys: &[(&str, &str)], width: usize) -> (String, usize) {
+    let r = "\x1b[0m";
+    let theme = theme::active();
+    let muted = theme.ansi_fg(theme.fg_muted);
+    let key_bg = theme.ansi_bg(theme.bg_tertiary);
+    let key_fg = theme.ansi_fg(theme.fg_primary);
+
+    let mut out = String::from(" ");
+    let mut len = 1;
+    for (key, label) in keys {
+        // " key " + " " + label + "  "
+        let hint_len = key.chars().count() + 2 + 1 + label.chars().count() + 2;
+        if len + hint_len > width {
+            break;
+        }
+        out.push_str(&format!("{key_bg}{key_fg} {key} {r} {muted}{label}{r}  "));
+        len += hint_len;
+    }
+    (out, len)
 }
 
 /// Render the preview panel for the selected file (standalone to avoid borrow conflict).
@@ -596,9 +1012,10 @@ fn render_preview_panel(
 ) -> String {
     let r = "\x1b[0m";
     let dim = "\x1b[2m";
-    let fg = "\x1b[38;2;192;202;245m";
-    let muted = "\x1b[38;2;86;95;137m";
-    let accent = "\x1b[38;2;122;162;247m";
+    let theme = theme::active();
+    let fg = theme.ansi_fg(theme.fg_primary);
+    let muted = theme.ansi_fg(theme.fg_muted);
+    let accent = theme.ansi_fg(theme.accent);
 
     let goto = |row: usize, col: usize| -> String {
         format!("\x1b[{};{}H", row, col)
@@ -675,6 +1092,199 @@ fn format_file_size(bytes: u64) -> String {
     }
 }
 
+/// "1 item" / "3 items".
+fn plural(n: usize) -> String {
+    if n == 1 {
+        "1 item".to_string()
+    } else {
+        format!("{n} items")
+    }
+}
+
+/// Git status of every changed path under the repository containing `root`,
+/// keyed by absolute path (spelled relative to `root` as given, so keys
+/// match [`FileEntry::path`] even if `root` is not canonical). Empty
+/// outside a repository.
+fn load_git_status(root: &Path) -> HashMap<PathBuf, FileStatus> {
+    let (Ok(repo), Ok(files)) = (git_ui::repo_root(root), git_ui::get_file_statuses(root)) else {
+        return HashMap::new();
+    };
+    let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    files
+        .into_iter()
+        .map(|f| {
+            // Renames are reported as `old -> new`; decorate the new path.
+            let path = f.path.rsplit(" -> ").next().unwrap_or(&f.path);
+            let path = repo.join(path.trim_matches('"').trim_end_matches('/'));
+            let path = match path.strip_prefix(&canonical_root) {
+                Ok(rel) => root.join(rel),
+                Err(_) => path,
+            };
+            (path, f.status)
+        })
+        .collect()
+}
+
+/// Reject names that are empty or would escape their parent directory.
+fn check_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty() {
+        bail!("name is empty");
+    }
+    if Path::new(name).is_absolute() || name.split('/').any(|c| c == "..") {
+        bail!("{name:?} must be a relative path inside the directory");
+    }
+    Ok(())
+}
+
+/// Create an empty file `name` (which may contain `/`) inside `parent`.
+fn create_file(parent: &Path, name: &str) -> anyhow::Result<PathBuf> {
+    check_name(name)?;
+    let path = parent.join(name);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .with_context(|| format!("creating {}", path.display()))?;
+    Ok(path)
+}
+
+/// Create directory `name` (which may contain `/`) inside `parent`.
+fn create_dir(parent: &Path, name: &str) -> anyhow::Result<PathBuf> {
+    check_name(name.trim_end_matches('/'))?;
+    let path = parent.join(name.trim_end_matches('/'));
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+    std::fs::create_dir_all(&path).with_context(|| format!("creating {}", path.display()))?;
+    Ok(path)
+}
+
+/// Rename `path` to `new_name` within the same directory.
+fn rename_entry(path: &Path, new_name: &str) -> anyhow::Result<PathBuf> {
+    if new_name.is_empty() || new_name.contains('/') || new_name == "." || new_name == ".." {
+        bail!("invalid name {new_name:?} (use move to change directory)");
+    }
+    let new_path = path.with_file_name(new_name);
+    if new_path == path {
+        return Ok(new_path);
+    }
+    if new_path.exists() {
+        bail!("{} already exists", new_path.display());
+    }
+    std::fs::rename(path, &new_path).with_context(|| format!("renaming {}", path.display()))?;
+    Ok(new_path)
+}
+
+/// Move each of `sources` into directory `dest`, creating it if needed.
+///
+/// Nothing is moved if any destination path already exists or a directory
+/// would be moved into itself.
+fn move_entries(sources: &[PathBuf], dest: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut plan = Vec::with_capacity(sources.len());
+    for src in sources {
+        let name = src
+            .file_name()
+            .with_context(|| format!("cannot move {}", src.display()))?;
+        if dest.starts_with(src) {
+            bail!("cannot move {} into itself", src.display());
+        }
+        let target = dest.join(name);
+        if target.exists() {
+            bail!("{} already exists", target.display());
+        }
+        plan.push((src, target));
+    }
+    std::fs::create_dir_all(dest).with_context(|| format!("creating {}", dest.display()))?;
+    let mut moved = Vec::with_capacity(plan.len());
+    for (src, target) in plan {
+        std::fs::rename(src, &target).with_context(|| format!("moving {}", src.display()))?;
+        moved.push(target);
+    }
+    Ok(moved)
+}
+
+/// Move `path` to the user's trash and return its location there.
+///
+/// Uses `~/.Trash` on macOS and the freedesktop.org trash
+/// (`$XDG_DATA_HOME/Trash`) elsewhere.
+fn move_to_trash(path: &Path) -> anyhow::Result<PathBuf> {
+    let home = dirs_next::home_dir().context("no home directory")?;
+    if cfg!(target_os = "macos") {
+        move_to_trash_in(path, &home.join(".Trash"), false)
+    } else {
+        let data = dirs_next::data_dir().unwrap_or_else(|| home.join(".local/share"));
+        move_to_trash_in(path, &data.join("Trash"), true)
+    }
+}
+
+/// Move `path` into `trash`, either as a flat directory (macOS) or with the
+/// freedesktop `files/` + `info/*.trashinfo` layout.
+fn move_to_trash_in(path: &Path, trash: &Path, freedesktop: bool) -> anyhow::Result<PathBuf> {
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("resolving {}", path.display()))?;
+    let name = path
+        .file_name()
+        .context("cannot trash the filesystem root")?
+        .to_string_lossy()
+        .to_string();
+    let files = if freedesktop {
+        trash.join("files")
+    } else {
+        trash.to_path_buf()
+    };
+    std::fs::create_dir_all(&files).with_context(|| format!("creating {}", files.display()))?;
+
+    // Pick a name that is free in the trash: `name`, `name.2`, `name.3`, ...
+    let mut unique = name.clone();
+    let mut n = 1;
+    while files.join(&unique).exists() {
+        n += 1;
+        unique = format!("{name}.{n}");
+    }
+    let target = files.join(&unique);
+
+    // The spec writes the info file first so the name is reserved.
+    let info_file = freedesktop.then(|| trash.join("info").join(format!("{unique}.trashinfo")));
+    if let Some(info_file) = &info_file {
+        std::fs::create_dir_all(trash.join("info"))?;
+        let stamp = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S");
+        let encoded = percent_encode_path(&path);
+        std::fs::write(
+            info_file,
+            format!("[Trash Info]\nPath={encoded}\nDeletionDate={stamp}\n"),
+        )?;
+    }
+    if let Err(err) = std::fs::rename(&path, &target) {
+        if let Some(info_file) = &info_file {
+            let _ = std::fs::remove_file(info_file);
+        }
+        return Err(err).with_context(|| {
+            format!(
+                "moving {} to the trash (is it on another filesystem?)",
+                path.display()
+            )
+        });
+    }
+    Ok(target)
+}
+
+/// Percent-encode a path for a `.trashinfo` `Path=` line.
+fn percent_encode_path(path: &Path) -> String {
+    let mut out = String::new();
+    for b in path.to_string_lossy().bytes() {
+        if b.is_ascii_alphanumeric() || b"/-_.~".contains(&b) {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -920,4 +1530,227 @@ mod tests {
         assert!(!output.is_empty());
         assert!(output.contains("0 items"));
     }
+
+    fn select(tree: &mut FileTree, name: &str) {
+        let idx = tree.entries.iter().position(|e| e.name == name).unwrap();
+        tree.selected_index = idx;
+    }
+
+    fn git(dir: &Path, args: &[&str]) -> bool {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .is_ok_and(|o| o.status.success())
+    }
+
+    #[test]
+    fn test_toggle_mark_and_attach_targets() {
+        let dir = setup_test_dir();
+        let mut tree = FileTree::new(dir.path().to_path_buf());
+
+        // Directories are not markable; the selected file is the default target.
+        select(&mut tree, "src");
+        tree.toggle_mark();
+        assert!(tree.marked.is_empty());
+        select(&mut tree, "README.md");
+        assert_eq!(tree.attach_targets(), vec![dir.path().join("README.md")]);
+
+        tree.toggle_mark();
+        select(&mut tree, "Cargo.toml");
+        tree.toggle_mark();
+        assert_eq!(tree.marked.len(), 2);
+        assert_eq!(
+            tree.attach_targets(),
+            vec![dir.path().join("Cargo.toml"), dir.path().join("README.md")]
+        );
+
+        select(&mut tree, "Cargo.toml");
+        tree.toggle_mark();
+        assert_eq!(tree.attach_targets(), vec![dir.path().join("README.md")]);
+        assert!(tree.render(80, 24).contains("1 marked"));
+    }
+
+    #[test]
+    fn test_new_file_and_dir() {
+        let dir = setup_test_dir();
+        let mut tree = FileTree::new(dir.path().to_path_buf());
+
+        select(&mut tree, "src");
+        tree.begin_new_file();
+        for c in "util/helpers.rs".chars() {
+            tree.prompt_insert_char(c);
+        }
+        let msg = tree.submit_prompt().unwrap().unwrap();
+        assert_eq!(msg, "Created src/util/helpers.rs");
+        assert!(dir.path().join("src/util/helpers.rs").is_file());
+        assert!(tree.prompt.is_none());
+
+        // Creating it again fails without clobbering.
+        select(&mut tree, "src");
+        tree.begin_new_file();
+        "util/helpers.rs".chars().for_each(|c| tree.prompt_insert_char(c));
+        assert!(tree.submit_prompt().unwrap().is_err());
+
+        select(&mut tree, "README.md");
+        tree.begin_new_dir();
+        "docs".chars().for_each(|c| tree.prompt_insert_char(c));
+        tree.submit_prompt().unwrap().unwrap();
+        assert!(dir.path().join("docs").is_dir());
+        assert_eq!(tree.selected_entry().unwrap().name, "docs");
+
+        tree.begin_new_file();
+        "../escape".chars().for_each(|c| tree.prompt_insert_char(c));
+        assert!(tree.submit_prompt().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_rename() {
+        let dir = setup_test_dir();
+        let mut tree = FileTree::new(dir.path().to_path_buf());
+        select(&mut tree, "README.md");
+        tree.toggle_mark();
+        select(&mut tree, "README.md");
+        tree.begin_rename();
+        assert_eq!(tree.prompt.as_ref().unwrap().input, "README.md");
+        for _ in 0.."md".len() {
+            tree.prompt_backspace();
+        }
+        "txt".chars().for_each(|c| tree.prompt_insert_char(c));
+        tree.submit_prompt().unwrap().unwrap();
+
+        assert!(!dir.path().join("README.md").exists());
+        assert!(dir.path().join("README.txt").is_file());
+        assert_eq!(tree.selected_entry().unwrap().name, "README.txt");
+        assert!(tree.is_marked(&dir.path().join("README.txt")));
+
+        select(&mut tree, "README.txt");
+        tree.begin_rename();
+        tree.prompt.as_mut().unwrap().input = "Cargo.toml".to_string();
+        assert!(tree.submit_prompt().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_move_requires_confirmation() {
+        let dir = setup_test_dir();
+        let mut tree = FileTree::new(dir.path().to_path_buf());
+        select(&mut tree, "README.md");
+        tree.toggle_mark();
+        select(&mut tree, "Cargo.toml");
+        tree.toggle_mark();
+
+        tree.begin_move();
+        tree.prompt.as_mut().unwrap().input = "archive".to_string();
+        assert!(tree.submit_prompt().is_none());
+        assert!(matches!(
+            tree.prompt.as_ref().unwrap().kind,
+            PromptKind::ConfirmMove { .. }
+        ));
+        assert!(tree.render(80, 24).contains("Move 2 items to archive/?"));
+
+        // Declining leaves everything in place.
+        tree.cancel_prompt();
+        assert!(dir.path().join("README.md").exists());
+
+        tree.begin_move();
+        tree.prompt.as_mut().unwrap().input = "archive".to_string();
+        assert!(tree.submit_prompt().is_none());
+        let msg = tree.submit_prompt().unwrap().unwrap();
+        assert_eq!(msg, "Moved 2 items to archive");
+        assert!(dir.path().join("archive/README.md").is_file());
+        assert!(dir.path().join("archive/Cargo.toml").is_file());
+        assert!(tree.marked.is_empty());
+    }
+
+    #[test]
+    fn test_move_into_itself_rejected() {
+        let dir = setup_test_dir();
+        let src = dir.path().join("src");
+        assert!(move_entries(std::slice::from_ref(&src), &src.join("api")).is_err());
+        assert!(src.join("main.rs").exists());
+    }
+
+    #[test]
+    fn test_move_to_trash_freedesktop() {
+        let dir = setup_test_dir();
+        let trash = tempfile::tempdir().unwrap();
+        let file = dir.path().join("README.md");
+
+        let first = move_to_trash_in(&file, trash.path(), true).unwrap();
+        assert_eq!(first, trash.path().join("files/README.md"));
+        assert!(!file.exists());
+        let info = fs::read_to_string(trash.path().join("info/README.md.trashinfo")).unwrap();
+        assert!(info.starts_with("[Trash Info]\nPath=/"));
+        assert!(info.contains("README.md\nDeletionDate="));
+
+        // A second file with the same name gets a unique trash name.
+        fs::write(&file, "again").unwrap();
+        let second = move_to_trash_in(&file, trash.path(), true).unwrap();
+        assert_eq!(second, trash.path().join("files/README.md.2"));
+        assert!(trash.path().join("info/README.md.2.trashinfo").exists());
+
+        let dir_target = move_to_trash_in(&dir.path().join("src"), trash.path(), false).unwrap();
+        assert_eq!(dir_target, trash.path().join("src"));
+        assert!(dir_target.join("main.rs").exists());
+    }
+
+    #[test]
+    fn test_percent_encode_path() {
+        assert_eq!(
+            percent_encode_path(Path::new("/tmp/a b/ü.txt")),
+            "/tmp/a%20b/%C3%BC.txt"
+        );
+    }
+
+    #[test]
+    fn test_delete_prompt_targets() {
+        let dir = setup_test_dir();
+        let mut tree = FileTree::new(dir.path().to_path_buf());
+        select(&mut tree, "README.md");
+        tree.begin_delete();
+        let prompt = tree.prompt.clone().unwrap();
+        assert!(prompt.kind.is_confirmation());
+        assert_eq!(
+            prompt.kind,
+            PromptKind::ConfirmDelete {
+                targets: vec![dir.path().join("README.md")]
+            }
+        );
+        assert!(tree.render(80, 24).contains("Move README.md to the trash? [y/N]"));
+        tree.cancel_prompt();
+        assert!(dir.path().join("README.md").exists());
+    }
+
+    #[test]
+    fn test_git_status_and_ignored_toggle() {
+        let dir = setup_test_dir();
+        let base = dir.path();
+        fs::write(base.join(".gitignore"), "target/\n").unwrap();
+        fs::create_dir_all(base.join("target")).unwrap();
+        fs::write(base.join("target/out.bin"), "bin").unwrap();
+        if !git(base, &["init", "-q"])
+            || !git(base, &["add", "."])
+            || !git(
+                base,
+                &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-qm", "init"],
+            )
+        {
+            return; // git unavailable
+        }
+        fs::write(base.join("README.md"), "# Changed\n").unwrap();
+        fs::write(base.join("src/new.rs"), "").unwrap();
+
+        let mut tree = FileTree::new(base.to_path_buf());
+        let status = |tree: &FileTree, rel: &str| tree.git_status_of(&base.join(rel));
+        assert_eq!(status(&tree, "README.md"), Some(FileStatus::Modified));
+        assert_eq!(status(&tree, "src/new.rs"), Some(FileStatus::Untracked));
+        assert_eq!(status(&tree, "Cargo.toml"), None);
+
+        assert!(!tree.entries.iter().any(|e| e.name == "target"));
+        tree.toggle_show_ignored();
+        assert!(tree.entries.iter().any(|e| e.name == "target"));
+        assert!(tree.render(80, 24).contains("+ignored"));
+        tree.toggle_show_ignored();
+        assert!(!tree.entries.iter().any(|e| e.name == "target"));
+    }
 }
@@ -99,6 +99,7 @@ pub fn format_response(response: &AgentResponse) -> String {
             tool_id: _,
             success,
             output_preview,
+            images: _,
         } => format_tool_end(*success, output_preview),
 
         AgentResponse::PermissionRequest {
@@ -114,6 +115,7 @@ pub fn format_response(response: &AgentResponse) -> String {
             stdout,
             stderr,
             exit_code,
+            ..
         } => crate::screen::format_command_output(command, stdout, stderr, *exit_code),
 
         AgentResponse::Error(msg) => format_error(msg),
@@ -472,6 +474,7 @@ mod tests {
             tool_id: "t1".into(),
             success: true,
             output_preview: "200 lines".into(),
+            images: Vec::new(),
         };
         let output = format_response(&resp);
         assert!(output.contains("✔"));
@@ -485,6 +488,7 @@ mod tests {
             tool_id: "t1".into(),
             success: false,
             output_preview: "not found".into(),
+            images: Vec::new(),
         };
         let output = format_response(&resp);
         assert!(output.contains("✗"));
@@ -3,30 +3,34 @@
 //! Provides interactive overlays for common git workflows (stage, commit, push, log)
 //! rendered as ANSI text in the ElwoodPane's chat scroll area.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-// ─── Color Palette (TokyoNight, matching screen.rs / diff_viewer.rs) ─────
+use crate::theme::Role;
+
+// ─── Color Palette (active theme, matching screen.rs) ───────────────────
 
 const RESET: &str = "\x1b[0m";
 const BOLD: &str = "\x1b[1m";
 const DIM: &str = "\x1b[2m";
 
-const FG: (u8, u8, u8) = (192, 202, 245);
-const SUCCESS: (u8, u8, u8) = (158, 206, 106);
-const ERROR: (u8, u8, u8) = (247, 118, 142);
-const ACCENT: (u8, u8, u8) = (122, 162, 247);
-const MUTED: (u8, u8, u8) = (86, 95, 137);
-const BORDER: (u8, u8, u8) = (59, 66, 97);
-const SELECTION: (u8, u8, u8) = (40, 44, 66);
-const WARNING: (u8, u8, u8) = (224, 175, 104);
-const INFO: (u8, u8, u8) = (125, 207, 255);
-
-fn fgc(c: (u8, u8, u8)) -> String {
-    format!("\x1b[38;2;{};{};{}m", c.0, c.1, c.2)
+const FG: Role = Role::FgPrimary;
+const SUCCESS: Role = Role::Success;
+const ERROR: Role = Role::Error;
+const ACCENT: Role = Role::Accent;
+const MUTED: Role = Role::FgMuted;
+const BORDER: Role = Role::BlockBorder;
+const SELECTION: Role = Role::BgTertiary;
+const WARNING: Role = Role::Warning;
+const INFO: Role = Role::Info;
+
+fn fgc(c: Role) -> String {
+    let (r, g, b) = c.rgb();
+    format!("\x1b[38;2;{r};{g};{b}m")
 }
-fn bgc(c: (u8, u8, u8)) -> String {
-    format!("\x1b[48;2;{};{};{}m", c.0, c.1, c.2)
+fn bgc(c: Role) -> String {
+    let (r, g, b) = c.rgb();
+    format!("\x1b[48;2;{r};{g};{b}m")
 }
 
 // Box drawing
@@ -65,8 +69,20 @@ impl FileStatus {
         }
     }
 
-    /// Color tuple for this status.
-    fn color(&self) -> (u8, u8, u8) {
+    /// Single-letter code, as shown by `git status --short`.
+    pub fn code(&self) -> char {
+        match self {
+            Self::Modified => 'M',
+            Self::Added => 'A',
+            Self::Deleted => 'D',
+            Self::Renamed => 'R',
+            Self::Untracked => '?',
+            Self::Copied => 'C',
+        }
+    }
+
+    /// Theme role used to color this status.
+    pub fn color(&self) -> Role {
         match self {
             Self::Modified => WARNING,
             Self::Added => SUCCESS,
@@ -110,7 +126,13 @@ fn run_git(cwd: &Path, args: &[&str]) -> Result<String, String> {
         });
     }
 
-    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    // Only trim the end: porcelain output starts with a significant space.
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+/// Absolute path of the repository containing `cwd`.
+pub fn repo_root(cwd: &Path) -> Result<PathBuf, String> {
+    run_git(cwd, &["rev-parse", "--show-toplevel"]).map(PathBuf::from)
 }
 
 /// Parse `git status --porcelain=v1` output into structured file statuses.
@@ -92,6 +92,20 @@ impl EditorKind {
         matches!(self, Self::Neovim | Self::Vim | Self::Emacs)
     }
 
+    /// Flags that make the editor block until the file is closed.
+    ///
+    /// Needed when the caller waits for the editor to exit (e.g. composing a
+    /// prompt in `$EDITOR`).  Terminal editors block already; Emacs is kept
+    /// in the terminal with `-nw`.
+    #[must_use]
+    pub fn wait_args(self) -> &'static [&'static str] {
+        match self {
+            Self::Cursor | Self::Zed | Self::VSCode | Self::Sublime | Self::IntelliJ => &["--wait"],
+            Self::Emacs => &["-nw"],
+            Self::Neovim | Self::Vim => &[],
+        }
+    }
+
     /// Default preference order (higher = more preferred).
     #[must_use]
     pub fn default_priority(self) -> u8 {
@@ -877,6 +891,14 @@ mod tests {
         assert!(!EditorKind::Sublime.supports_diff());
     }
 
+    #[test]
+    fn test_editor_kind_wait_args() {
+        assert_eq!(EditorKind::VSCode.wait_args(), &["--wait"]);
+        assert_eq!(EditorKind::Zed.wait_args(), &["--wait"]);
+        assert_eq!(EditorKind::Emacs.wait_args(), &["-nw"]);
+        assert!(EditorKind::Neovim.wait_args().is_empty());
+    }
+
     #[test]
     fn test_editor_kind_is_terminal() {
         assert!(EditorKind::Neovim.is_terminal_editor());
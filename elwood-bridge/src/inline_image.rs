@@ -0,0 +1,558 @@
+//! Inline images in the chat area.
+//!
+//! The pane renders into a `wezterm_term::Terminal`, which understands both
+//! the iTerm2 (`OSC 1337 ; File=`) and kitty (`APC G`) image protocols. This
+//! module turns image bytes into one of those escape sequences, or into a
+//! one-line text placeholder when images are turned off, the data isn't an
+//! image we recognize, or it is too large. Images come from:
+//!
+//! - markdown images in agent replies that reference local files or data
+//!   URIs (`![plot](out/plot.png)`), see [`render_source`];
+//! - base64 image blocks in tool output, which the runtime splits out of the
+//!   result with [`split_images`] before the preview is truncated.
+//!
+//! ```toml
+//! [images]
+//! protocol = "iterm2"   # "kitty", or "off" for text placeholders only
+//! max_cols = 80
+//! max_rows = 20
+//! max_bytes = 8388608
+//! ```
+
+use base64::Engine;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::theme;
+
+/// Assumed cell size in pixels, for sizing an image in cells.
+const CELL_WIDTH_PX: u32 = 8;
+const CELL_HEIGHT_PX: u32 = 16;
+
+/// Kitty payloads are sent in chunks of at most this many base64 bytes.
+const KITTY_CHUNK: usize = 4096;
+
+/// Bare base64 runs shorter than this are never treated as images.
+const MIN_BASE64_BLOCK: usize = 64;
+
+/// Escape sequence family used to draw images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageProtocol {
+    #[default]
+    Iterm2,
+    Kitty,
+    /// Text placeholders only.
+    Off,
+}
+
+/// `[images]` section of `elwood.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageConfig {
+    pub protocol: ImageProtocol,
+    /// Widest an image may be drawn, in cells.
+    pub max_cols: u16,
+    /// Tallest an image may be drawn, in cells.
+    pub max_rows: u16,
+    /// Larger images are shown as a placeholder.
+    pub max_bytes: usize,
+}
+
+impl Default for ImageConfig {
+    fn default() -> Self {
+        Self {
+            protocol: ImageProtocol::default(),
+            max_cols: 80,
+            max_rows: 20,
+            max_bytes: 8 * 1024 * 1024,
+        }
+    }
+}
+
+fn config_slot() -> &'static RwLock<ImageConfig> {
+    static CONFIG: OnceLock<RwLock<ImageConfig>> = OnceLock::new();
+    CONFIG.get_or_init(|| RwLock::new(ImageConfig::default()))
+}
+
+/// The image settings in effect.
+pub fn config() -> ImageConfig {
+    config_slot().read().clone()
+}
+
+/// Replace the image settings (from `elwood.toml`).
+pub fn set_config(config: ImageConfig) {
+    *config_slot().write() = config;
+}
+
+/// Image formats recognized by their magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Webp,
+    Bmp,
+}
+
+impl ImageFormat {
+    /// Identify the format from the first bytes of `data`.
+    pub fn sniff(data: &[u8]) -> Option<Self> {
+        if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(Self::Jpeg)
+        } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+            Some(Self::Gif)
+        } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+            Some(Self::Webp)
+        } else if data.starts_with(b"BM") && data.len() >= 26 {
+            Some(Self::Bmp)
+        } else {
+            None
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Png => "PNG",
+            Self::Jpeg => "JPEG",
+            Self::Gif => "GIF",
+            Self::Webp => "WebP",
+            Self::Bmp => "BMP",
+        }
+    }
+
+    /// Pixel size from the image header, where it is cheap to find.
+    pub fn dimensions(self, data: &[u8]) -> Option<(u32, u32)> {
+        let be16 =
+            |at: usize| Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?) as u32);
+        let le16 =
+            |at: usize| Some(u16::from_le_bytes(data.get(at..at + 2)?.try_into().ok()?) as u32);
+        let be32 = |at: usize| Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?));
+        let le32 = |at: usize| Some(i32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?));
+        match self {
+            // IHDR is always the first chunk
+            Self::Png => Some((be32(16)?, be32(20)?)),
+            Self::Gif => Some((le16(6)?, le16(8)?)),
+            // Height is negative for top-down bitmaps
+            Self::Bmp => Some((le32(18)?.unsigned_abs(), le32(22)?.unsigned_abs())),
+            Self::Jpeg => {
+                // Walk the segments up to the first start-of-frame marker
+                let mut at = 2;
+                while at + 9 < data.len() {
+                    if data[at] != 0xFF {
+                        return None;
+                    }
+                    let marker = data[at + 1];
+                    let is_sof =
+                        matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+                    if is_sof {
+                        return Some((be16(at + 7)?, be16(at + 5)?));
+                    }
+                    at += 2 + be16(at + 2)? as usize;
+                }
+                None
+            }
+            Self::Webp => {
+                // Only the extended (VP8X) header stores the canvas size plainly
+                if data.get(12..16)? != b"VP8X" {
+                    return None;
+                }
+                let le24 = |at: usize| {
+                    let b = data.get(at..at + 3)?;
+                    Some(u32::from_le_bytes([b[0], b[1], b[2], 0]) + 1)
+                };
+                Some((le24(24)?, le24(27)?))
+            }
+        }
+    }
+}
+
+/// An image taken out of tool output.
+#[derive(Clone, PartialEq)]
+pub struct EmbeddedImage {
+    pub format: ImageFormat,
+    pub data: Vec<u8>,
+}
+
+impl EmbeddedImage {
+    /// `PNG 640×480, 12 KB`.
+    pub fn describe(&self) -> String {
+        describe(self.format, &self.data)
+    }
+}
+
+// The bytes would swamp any log line the image shows up in.
+impl std::fmt::Debug for EmbeddedImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmbeddedImage")
+            .field("format", &self.format)
+            .field("bytes", &self.data.len())
+            .finish()
+    }
+}
+
+fn describe(format: ImageFormat, data: &[u8]) -> String {
+    let size = format_size(data.len());
+    match format.dimensions(data) {
+        Some((w, h)) => format!("{} {w}\u{00D7}{h}, {size}", format.label()),
+        None => format!("{} {size}", format.label()),
+    }
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{} KB", bytes / 1024)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+/// Size in cells to draw an image at: its natural size, scaled down to fit
+/// the configured maximum. Images of unknown size get the whole box.
+fn cell_box(dimensions: Option<(u32, u32)>, config: &ImageConfig) -> (u32, u32) {
+    let max_cols = u32::from(config.max_cols.max(1));
+    let max_rows = u32::from(config.max_rows.max(1));
+    let Some((w, h)) = dimensions else {
+        return (max_cols, max_rows);
+    };
+    let mut cols = w.div_ceil(CELL_WIDTH_PX).max(1);
+    let mut rows = h.div_ceil(CELL_HEIGHT_PX).max(1);
+    if cols > max_cols {
+        rows = (rows * max_cols / cols).max(1);
+        cols = max_cols;
+    }
+    if rows > max_rows {
+        cols = (cols * max_rows / rows).max(1);
+        rows = max_rows;
+    }
+    (cols, rows)
+}
+
+/// Draw `data` inline, or a placeholder naming `label` if it can't be.
+pub fn render_image(data: &[u8], label: &str) -> String {
+    let Some(format) = ImageFormat::sniff(data) else {
+        return placeholder(label, "not a supported image");
+    };
+    let config = config();
+    if config.protocol == ImageProtocol::Off {
+        return placeholder(label, &describe(format, data));
+    }
+    if data.len() > config.max_bytes {
+        return placeholder(
+            label,
+            &format!("{}, too large to show", describe(format, data)),
+        );
+    }
+    let (cols, rows) = cell_box(format.dimensions(data), &config);
+    let b64 = base64::engine::general_purpose::STANDARD.encode(data);
+    // Kitty's f=100 means PNG; the terminal takes any format over iTerm2.
+    let sequence = if config.protocol == ImageProtocol::Kitty && format == ImageFormat::Png {
+        kitty_sequence(&b64, cols, rows)
+    } else {
+        iterm2_sequence(&b64, data.len(), cols, rows)
+    };
+    format!("{sequence}\r\n")
+}
+
+fn iterm2_sequence(b64: &str, size: usize, cols: u32, rows: u32) -> String {
+    format!(
+        "\x1b]1337;File=inline=1;size={size};width={cols};height={rows};preserveAspectRatio=1:{b64}\x07"
+    )
+}
+
+fn kitty_sequence(b64: &str, cols: u32, rows: u32) -> String {
+    let chunks: Vec<&[u8]> = b64.as_bytes().chunks(KITTY_CHUNK).collect();
+    let mut out = String::with_capacity(b64.len() + chunks.len() * 16);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        // Base64 is ASCII, so every chunk is valid UTF-8
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            // q=2: no OK/error replies into the pane's input
+            out.push_str(&format!(
+                "\x1b_Ga=T,f=100,q=2,c={cols},r={rows},m={more};{chunk}\x1b\\"
+            ));
+        } else {
+            out.push_str(&format!("\x1b_Gm={more};{chunk}\x1b\\"));
+        }
+    }
+    out
+}
+
+/// The text shown instead of an image.
+pub fn placeholder(label: &str, detail: &str) -> String {
+    let t = theme::active();
+    let muted = t.ansi_fg(t.fg_muted);
+    let r = t.reset();
+    if label.is_empty() {
+        format!("{muted}[image: {detail}]{r}\r\n")
+    } else {
+        format!("{muted}[image: {label} \u{2014} {detail}]{r}\r\n")
+    }
+}
+
+/// Draw the image a markdown `![alt](src)` refers to: a local path
+/// (relative to `base_dir`, `~/` and `file://` accepted) or a base64 data
+/// URI. Remote images are not fetched.
+pub fn render_source(src: &str, alt: &str, base_dir: &Path) -> String {
+    if src.starts_with("data:") {
+        return match decode_data_uri(src) {
+            Some(data) => render_image(&data, alt),
+            None => placeholder(alt, "invalid data URI"),
+        };
+    }
+    if src.starts_with("http://") || src.starts_with("https://") {
+        return placeholder(alt, src);
+    }
+
+    let path = src.strip_prefix("file://").unwrap_or(src);
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => dirs_next::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(rest),
+        None => base_dir.join(path),
+    };
+    let label = if alt.is_empty() { src } else { alt };
+    let max_bytes = config().max_bytes;
+    // Devices and FIFOs could block or never end; only read regular files.
+    match std::fs::metadata(&path) {
+        Ok(meta) if !meta.is_file() => {
+            placeholder(label, &format!("{}: not a file", path.display()))
+        }
+        Ok(meta) if meta.len() > max_bytes as u64 => placeholder(
+            label,
+            &format!("{}, too large to show", format_size(meta.len() as usize)),
+        ),
+        Ok(_) => match read_at_most(&path, max_bytes) {
+            Ok(data) if data.len() > max_bytes => placeholder(
+                label,
+                &format!("over {}, too large to show", format_size(max_bytes)),
+            ),
+            Ok(data) => render_image(&data, label),
+            Err(e) => placeholder(label, &format!("{}: {e}", path.display())),
+        },
+        Err(e) => placeholder(label, &format!("{}: {e}", path.display())),
+    }
+}
+
+/// Read up to `max_bytes + 1` bytes of `path`, so a file that grew past the
+/// limit since it was checked is still caught.
+fn read_at_most(path: &Path, max_bytes: usize) -> std::io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    std::fs::File::open(path)?
+        .take(max_bytes as u64 + 1)
+        .read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Bytes of a `data:image/...;base64,...` URI.
+pub fn decode_data_uri(uri: &str) -> Option<Vec<u8>> {
+    let rest = uri.strip_prefix("data:")?;
+    let (header, payload) = rest.split_once(',')?;
+    if !header.ends_with(";base64") {
+        return None;
+    }
+    let payload: String = payload.chars().filter(|c| !c.is_whitespace()).collect();
+    base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .ok()
+}
+
+fn is_base64_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=')
+}
+
+/// Decode `b64` if it is an image.
+fn decode_image(b64: &str) -> Option<EmbeddedImage> {
+    let data = base64::engine::general_purpose::STANDARD.decode(b64).ok()?;
+    let format = ImageFormat::sniff(&data)?;
+    Some(EmbeddedImage { format, data })
+}
+
+/// Take base64 images out of tool output.
+///
+/// Finds `data:image/*;base64,` URIs and runs of lines that are nothing but
+/// base64 (as `base64 plot.png` prints them) which decode to a known image
+/// format. Each is replaced in the returned text by a short description, so
+/// previews and logs stay readable.
+pub fn split_images(text: &str) -> (String, Vec<EmbeddedImage>) {
+    let mut images = Vec::new();
+
+    // Data URIs, anywhere in a line
+    let mut rest = text;
+    let mut without_uris = String::with_capacity(text.len());
+    while let Some(start) = rest.find("data:image/") {
+        without_uris.push_str(&rest[..start]);
+        let uri = &rest[start..];
+        let payload_at = uri
+            .find(";base64,")
+            .filter(|&at| uri[..at].chars().all(|c| !c.is_whitespace()))
+            .map(|at| at + ";base64,".len());
+        let image = payload_at.and_then(|at| {
+            let len = uri[at..]
+                .find(|c: char| !is_base64_char(c))
+                .unwrap_or(uri.len() - at);
+            decode_image(&uri[at..at + len]).map(|image| (image, at + len))
+        });
+        match image {
+            Some((image, end)) => {
+                without_uris.push_str(&format!("[image: {}]", image.describe()));
+                images.push(image);
+                rest = &uri[end..];
+            }
+            None => {
+                without_uris.push_str("data:image/");
+                rest = &uri["data:image/".len()..];
+            }
+        }
+    }
+    without_uris.push_str(rest);
+
+    // Bare base64 blocks, one or more whole lines
+    let lines: Vec<&str> = without_uris.split('\n').collect();
+    let mut out: Vec<String> = Vec::with_capacity(lines.len());
+    let mut i = 0;
+    while i < lines.len() {
+        let is_b64_line = |line: &str| {
+            let line = line.trim();
+            !line.is_empty() && line.chars().all(is_base64_char)
+        };
+        let start = i;
+        while i < lines.len() && is_b64_line(lines[i]) {
+            i += 1;
+        }
+        if i > start {
+            let block: String = lines[start..i].iter().map(|l| l.trim()).collect();
+            match decode_image(&block).filter(|_| block.len() >= MIN_BASE64_BLOCK) {
+                Some(image) => {
+                    out.push(format!("[image: {}]", image.describe()));
+                    images.push(image);
+                }
+                None => out.extend(lines[start..i].iter().map(|l| l.to_string())),
+            }
+        } else {
+            out.push(lines[i].to_string());
+            i += 1;
+        }
+    }
+
+    (out.join("\n"), images)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 1×1 PNG.
+    const PNG_1X1: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+    fn png() -> Vec<u8> {
+        base64::engine::general_purpose::STANDARD
+            .decode(PNG_1X1)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sniff_and_dimensions() {
+        let data = png();
+        assert_eq!(ImageFormat::sniff(&data), Some(ImageFormat::Png));
+        assert_eq!(ImageFormat::Png.dimensions(&data), Some((1, 1)));
+        assert_eq!(ImageFormat::sniff(b"plain text"), None);
+
+        let mut gif = b"GIF89a".to_vec();
+        gif.extend_from_slice(&[0x40, 0x01, 0xF0, 0x00]);
+        assert_eq!(ImageFormat::Gif.dimensions(&gif), Some((320, 240)));
+
+        // SOI, APP0 (length 4), SOF0 with height 480 and width 640
+        let jpeg = [
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01,
+            0xE0, 0x02, 0x80, 0x03,
+        ];
+        assert_eq!(ImageFormat::sniff(&jpeg), Some(ImageFormat::Jpeg));
+        assert_eq!(ImageFormat::Jpeg.dimensions(&jpeg), Some((640, 480)));
+    }
+
+    #[test]
+    fn test_cell_box_scales_to_fit() {
+        let config = ImageConfig::default();
+        assert_eq!(cell_box(Some((80, 32)), &config), (10, 2));
+        // 1600×320 px is 200×20 cells; halved to fit 80 columns
+        assert_eq!(cell_box(Some((1600, 320)), &config), (80, 8));
+        assert_eq!(cell_box(Some((160, 1600)), &config), (4, 20));
+        assert_eq!(cell_box(None, &config), (80, 20));
+    }
+
+    #[test]
+    fn test_render_sequences() {
+        let b64 = "A".repeat(KITTY_CHUNK + 10);
+        let kitty = kitty_sequence(&b64, 4, 2);
+        assert!(kitty.starts_with("\x1b_Ga=T,f=100,q=2,c=4,r=2,m=1;"));
+        assert!(kitty.contains("\x1b\\\x1b_Gm=0;AAAAAAAAAA\x1b\\"));
+
+        let iterm = iterm2_sequence(PNG_1X1, 70, 1, 1);
+        assert!(iterm.starts_with("\x1b]1337;File=inline=1;size=70;width=1;height=1;"));
+        assert!(iterm.ends_with(&format!(":{PNG_1X1}\x07")));
+    }
+
+    #[test]
+    fn test_render_image_fallbacks() {
+        assert!(render_image(&png(), "dot").contains("\x1b]1337;File="));
+        let text = render_image(b"not an image", "notes.txt");
+        assert!(text.contains("[image: notes.txt \u{2014} not a supported image]"));
+        let base = Path::new("/");
+        assert!(render_source("https://example.com/a.png", "logo", base)
+            .contains("https://example.com/a.png"));
+        assert!(render_source("/nonexistent/plot.png", "plot", base).contains("[image: plot"));
+        let dir = tempfile::tempdir().unwrap();
+        let text = render_source(dir.path().to_str().unwrap(), "plot", base);
+        assert!(text.contains("not a file"));
+    }
+
+    #[test]
+    fn test_render_source_file_and_data_uri() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dot.png");
+        std::fs::write(&path, png()).unwrap();
+        assert!(render_source(path.to_str().unwrap(), "dot", Path::new("/"))
+            .contains("\x1b]1337;File="));
+        // Relative paths are taken from the base directory
+        assert!(render_source("dot.png", "dot", dir.path()).contains("\x1b]1337;File="));
+        let uri = format!("data:image/png;base64,{PNG_1X1}");
+        assert_eq!(decode_data_uri(&uri), Some(png()));
+        assert!(render_source(&uri, "", Path::new("/")).contains("\x1b]1337;File="));
+    }
+
+    #[test]
+    fn test_split_images_data_uri() {
+        let text = format!("saved chart: data:image/png;base64,{PNG_1X1} (done)");
+        let (rest, images) = split_images(&text);
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].format, ImageFormat::Png);
+        assert_eq!(rest, "saved chart: [image: PNG 1\u{00D7}1, 70 B] (done)");
+    }
+
+    #[test]
+    fn test_split_images_base64_block() {
+        let (first, second) = PNG_1X1.split_at(40);
+        let text = format!("$ base64 dot.png\n{first}\n{second}\n$ echo ok");
+        let (rest, images) = split_images(&text);
+        assert_eq!(images.len(), 1);
+        assert_eq!(
+            rest,
+            "$ base64 dot.png\n[image: PNG 1\u{00D7}1, 70 B]\n$ echo ok"
+        );
+
+        // Base64 that isn't an image is left alone
+        let text = "token: c2VjcmV0\nYWJjZGVmZ2hpamtsbW5vcHFyc3R1dnd4eXphYmNkZWZnaGlqa2xtbm9wcXJzdHV2d3h5eg==";
+        let (rest, images) = split_images(text);
+        assert!(images.is_empty());
+        assert_eq!(rest, text);
+    }
+}
@@ -11,13 +11,27 @@
 //! - Job lifecycle: Running -> Completed/Failed/Cancelled
 //! - Kill with SIGTERM, escalating to SIGKILL after 5 seconds
 //! - Retention limit of 50 completed jobs (oldest evicted first)
-//! - Toast notifications on job completion/failure
-
+//! - Toast notifications on job completion/failure, with duration
+//! - Full output written to a per-job log file under `~/.elwood/jobs/`, which
+//!   outlives the pane and can be followed live in a split pane (`/jobs fg`);
+//!   logs are deleted with their evicted job, and [`prune_logs`] trims the
+//!   directory at startup, sparing logs of processes that are still running
+//! - The agent can block on a job with the `job_wait` tool
+//!   ([`JobWaitTool`](crate::tools::JobWaitTool)), which reads the outcome
+//!   from its [`JobHandle`]
+
+use parking_lot::Mutex;
 use std::collections::HashMap;
-use std::time::Instant;
+use std::fs::File;
+use std::io::{BufRead, BufReader, LineWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-/// Maximum number of completed/failed/cancelled jobs to retain.
-const MAX_RETAINED: usize = 50;
+/// Maximum number of completed/failed/cancelled jobs (and job logs) to retain.
+pub const MAX_RETAINED: usize = 50;
 
 /// Maximum stdout lines to keep per job.
 const MAX_STDOUT_LINES: usize = 1000;
@@ -25,6 +39,12 @@ const MAX_STDOUT_LINES: usize = 1000;
 /// Maximum stderr lines to keep per job.
 const MAX_STDERR_LINES: usize = 100;
 
+/// Output lines included when reporting a finished job to the agent.
+const REPORT_TAIL_LINES: usize = 40;
+
+/// Time between SIGTERM and SIGKILL when killing a job.
+const KILL_ESCALATION: Duration = Duration::from_secs(5);
+
 /// Unique job identifier.
 pub type JobId = u32;
 
@@ -73,6 +93,8 @@ pub struct Job {
     pub stderr: Vec<String>,
     /// OS process ID (for kill operations).
     pub pid: Option<u32>,
+    /// Log file holding the job's full output.
+    pub log_path: Option<PathBuf>,
 }
 
 impl Job {
@@ -88,9 +110,15 @@ impl Job {
             stdout: Vec::new(),
             stderr: Vec::new(),
             pid,
+            log_path: None,
         }
     }
 
+    /// Whether the job has finished (completed, failed, or cancelled).
+    pub fn is_finished(&self) -> bool {
+        self.status != JobStatus::Running
+    }
+
     /// Duration of the job (elapsed if running, total if finished).
     pub fn duration_secs(&self) -> f64 {
         match self.end_time {
@@ -155,6 +183,46 @@ impl Job {
         self.end_time = Some(Instant::now());
         self.status = JobStatus::Cancelled;
     }
+
+    /// One-line outcome, e.g. "Job #3 completed in 12.4s (exit 0): cargo build".
+    pub fn summary(&self) -> String {
+        let exit_str = self.exit_code
+            .map(|c| format!(" (exit {c})"))
+            .unwrap_or_default();
+        format!(
+            "Job #{} {} in {}{}: {}",
+            self.id,
+            self.status,
+            self.duration_display(),
+            exit_str,
+            self.command,
+        )
+    }
+
+    /// Report sent to the agent when a `/jobs wait` finishes: the summary,
+    /// the log path, and the tail of stdout and stderr.
+    pub fn wait_report(&self) -> String {
+        let mut out = format!("[Background Job]\n{}\n", self.summary());
+        if let Some(ref path) = self.log_path {
+            out.push_str(&format!("Full log: {}\n", path.display()));
+        }
+        for (label, lines) in [("stdout", &self.stdout), ("stderr", &self.stderr)] {
+            if lines.is_empty() {
+                continue;
+            }
+            let start = lines.len().saturating_sub(REPORT_TAIL_LINES);
+            let omitted = if start > 0 {
+                format!(", last {REPORT_TAIL_LINES} lines")
+            } else {
+                String::new()
+            };
+            out.push_str(&format!(
+                "\n{label}{omitted}:\n```\n{}\n```\n",
+                lines[start..].join("\n")
+            ));
+        }
+        out
+    }
 }
 
 /// Manages background jobs: creation, tracking, output capture, and retention.
@@ -220,7 +288,8 @@ impl JobManager {
         self.jobs.values().filter(|j| j.status == JobStatus::Running).collect()
     }
 
-    /// Evict old completed/failed/cancelled jobs beyond the retention limit.
+    /// Evict old completed/failed/cancelled jobs beyond the retention limit,
+    /// deleting their log files.
     ///
     /// Running jobs are never evicted.
     pub fn enforce_retention(&mut self) {
@@ -237,7 +306,9 @@ impl JobManager {
         if finished.len() > MAX_RETAINED {
             let to_remove = finished.len() - MAX_RETAINED;
             for id in finished.into_iter().take(to_remove) {
-                self.jobs.remove(&id);
+                if let Some(path) = self.jobs.remove(&id).and_then(|job| job.log_path) {
+                    let _ = std::fs::remove_file(path);
+                }
             }
         }
     }
@@ -309,6 +380,385 @@ impl Default for JobManager {
     }
 }
 
+// ─── Log files ──────────────────────────────────────────────────────────
+
+/// Default directory for per-job log files (`~/.elwood/jobs/`).
+pub fn jobs_dir() -> PathBuf {
+    dirs_next::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".elwood")
+        .join("jobs")
+}
+
+/// Log file path for a new job.
+///
+/// Job IDs restart with every pane, so the name also carries a timestamp and
+/// the process ID: `20260118-142501-4242-job3.log`.
+pub fn log_path_for(dir: &Path, id: JobId) -> PathBuf {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    dir.join(format!("{stamp}-{}-job{id}.log", std::process::id()))
+}
+
+/// Delete all but the `keep` most recently modified `.log` files in `dir`,
+/// returning how many were removed.
+///
+/// Logs written by a process that is still running (the PID in the name, see
+/// [`log_path_for`]) are left alone however old they are: a quiet job may
+/// still be writing to one, and that process evicts its own logs.
+pub fn prune_logs(dir: &Path, keep: usize) -> std::io::Result<usize> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    let mut logs: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .collect();
+    if logs.len() <= keep {
+        return Ok(0);
+    }
+    logs.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    let mut removed = 0;
+    for (_, path) in logs.drain(keep..) {
+        if log_owner(&path).is_some_and(process_alive) {
+            continue;
+        }
+        std::fs::remove_file(path)?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+/// PID of the process that created a log named by [`log_path_for`].
+fn log_owner(path: &Path) -> Option<u32> {
+    let stem = path.file_stem()?.to_str()?;
+    let (rest, _job) = stem.rsplit_once("-job")?;
+    rest.rsplit_once('-')?.1.parse().ok()
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    // 0 and negative PIDs would signal whole process groups
+    let Some(pid) = libc::pid_t::try_from(pid).ok().filter(|&p| p > 0) else {
+        return false;
+    };
+    // Signal 0 only checks that the process exists; EPERM means it does
+    // but belongs to someone else
+    let exists = unsafe { libc::kill(pid, 0) == 0 };
+    exists || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn process_alive(pid: u32) -> bool {
+    pid == std::process::id()
+}
+
+/// The last `lines` lines of a job log, read from its end so a huge log
+/// costs no more than a small one.
+pub fn read_log_tail(path: &Path, lines: usize) -> std::io::Result<String> {
+    const TAIL_BYTES: u64 = 64 * 1024;
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(TAIL_BYTES)))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+    let text = String::from_utf8_lossy(&buf);
+    let all: Vec<&str> = text.lines().collect();
+    // The first line may have been cut in half by the seek
+    let first = if len > TAIL_BYTES { 1 } else { 0 };
+    let start = all.len().saturating_sub(lines).max(first.min(all.len()));
+    Ok(all[start..].join("\n"))
+}
+
+/// Line-buffered job log, so `tail -f` sees output as soon as it's printed.
+pub struct JobLog {
+    file: LineWriter<File>,
+}
+
+impl JobLog {
+    /// Create the log (and its directory) and write a `$ command` header.
+    pub fn create(path: &Path, command: &str) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = LineWriter::new(File::create(path)?);
+        writeln!(file, "$ {command}")?;
+        Ok(Self { file })
+    }
+
+    /// Append one line of output.
+    pub fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        writeln!(self.file, "{line}")
+    }
+
+    /// Append the footer recording how the job ended.
+    pub fn finish(
+        &mut self,
+        exit_code: Option<i32>,
+        cancelled: bool,
+        duration: Duration,
+    ) -> std::io::Result<()> {
+        let outcome = JobOutcome {
+            exit_code,
+            cancelled,
+            duration,
+        };
+        writeln!(self.file, "\n[{outcome}]")?;
+        self.file.flush()
+    }
+}
+
+/// Command that follows a job's log in a pane (used by `/jobs fg`).
+pub fn follow_log_argv(path: &Path) -> Vec<String> {
+    vec![
+        "tail".to_string(),
+        "-n".to_string(),
+        "+1".to_string(),
+        "-f".to_string(),
+        path.display().to_string(),
+    ]
+}
+
+// ─── Process execution ──────────────────────────────────────────────────
+
+/// Lifecycle events emitted by a job started with [`spawn_job`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobEvent {
+    /// The process was spawned.
+    Started { pid: u32 },
+    /// One line of output.
+    Output { line: String, is_stderr: bool },
+    /// The process exited (or was killed) and all output was delivered.
+    Finished { exit_code: Option<i32>, cancelled: bool },
+}
+
+/// How a job started with [`spawn_job`] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobOutcome {
+    /// Exit code (`None` if terminated by a signal).
+    pub exit_code: Option<i32>,
+    /// Whether the job was killed with [`JobHandle::kill`].
+    pub cancelled: bool,
+    /// How long the job ran.
+    pub duration: Duration,
+}
+
+impl std::fmt::Display for JobOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.cancelled, self.exit_code) {
+            (true, _) => write!(f, "killed")?,
+            (false, Some(code)) => write!(f, "exit {code}")?,
+            (false, None) => write!(f, "terminated by signal")?,
+        }
+        write!(f, " after {:.1}s", self.duration.as_secs_f64())
+    }
+}
+
+/// Handle to a job started with [`spawn_job`].
+#[derive(Debug, Clone)]
+pub struct JobHandle {
+    pid: u32,
+    command: String,
+    log_path: Option<PathBuf>,
+    killed: Arc<AtomicBool>,
+    finished: Arc<AtomicBool>,
+    outcome: Arc<Mutex<Option<JobOutcome>>>,
+}
+
+/// Jobs started by a runtime, keyed by the pane-assigned job ID and shared
+/// with the `job_wait` tool.
+pub type SharedJobHandles = Arc<Mutex<HashMap<JobId, JobHandle>>>;
+
+impl JobHandle {
+    /// OS process ID of the job's shell.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// The shell command the job runs.
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Log file holding the job's full output, if it has one.
+    pub fn log_path(&self) -> Option<&Path> {
+        self.log_path.as_deref()
+    }
+
+    /// Whether the process has exited.
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Acquire)
+    }
+
+    /// How the job ended, once all its output has been delivered and logged.
+    pub fn outcome(&self) -> Option<JobOutcome> {
+        *self.outcome.lock()
+    }
+
+    /// Report a finished job to the agent: how it ended and the tail of its
+    /// log. `None` while the job is running.
+    pub fn wait_report(&self, id: JobId) -> Option<String> {
+        let outcome = self.outcome()?;
+        let mut out = format!("[Background Job]\n#{id} {}: {outcome}\n", self.command);
+        if let Some(ref path) = self.log_path {
+            out.push_str(&format!("Full log: {}\n", path.display()));
+            match read_log_tail(path, REPORT_TAIL_LINES) {
+                Ok(tail) => out.push_str(&format!(
+                    "\nlog, last {REPORT_TAIL_LINES} lines:\n```\n{tail}\n```\n"
+                )),
+                Err(e) => out.push_str(&format!("Log unreadable: {e}\n")),
+            }
+        }
+        Some(out)
+    }
+
+    /// Send SIGTERM to the job's process group, then SIGKILL if it is still
+    /// running five seconds later. Does nothing once the job has exited, so a
+    /// recycled PID is never signalled.
+    pub fn kill(&self) {
+        if self.is_finished() {
+            return;
+        }
+        self.killed.store(true, Ordering::Release);
+        signal_group(self.pid, false);
+
+        let handle = self.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(KILL_ESCALATION);
+            if !handle.is_finished() {
+                signal_group(handle.pid, true);
+            }
+        });
+    }
+}
+
+#[cfg(unix)]
+fn signal_group(pid: u32, force: bool) {
+    let sig = if force { libc::SIGKILL } else { libc::SIGTERM };
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), sig);
+    }
+}
+
+#[cfg(not(unix))]
+fn signal_group(_pid: u32, _force: bool) {}
+
+/// Run `command` via `$SHELL -c` in its own process group.
+///
+/// Every output line is written to `log_path` (when given) and reported via
+/// `on_event`, which is called from background threads. `Finished` is always
+/// the last event.
+pub fn spawn_job<F>(
+    command: &str,
+    cwd: Option<&Path>,
+    log_path: Option<&Path>,
+    on_event: F,
+) -> std::io::Result<JobHandle>
+where
+    F: Fn(JobEvent) + Send + Sync + 'static,
+{
+    let log = match log_path {
+        Some(path) => Some(JobLog::create(path, command)?),
+        None => None,
+    };
+
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
+    let mut cmd = Command::new(shell);
+    cmd.arg("-c")
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = cwd {
+        cmd.current_dir(dir);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    let mut child = cmd.spawn()?;
+
+    let started = Instant::now();
+    let handle = JobHandle {
+        pid: child.id(),
+        command: command.to_string(),
+        log_path: log_path.map(Path::to_path_buf),
+        killed: Arc::new(AtomicBool::new(false)),
+        finished: Arc::new(AtomicBool::new(false)),
+        outcome: Arc::new(Mutex::new(None)),
+    };
+    let on_event = Arc::new(on_event);
+    let log = Arc::new(Mutex::new(log));
+    on_event(JobEvent::Started { pid: handle.pid });
+
+    let mut readers = Vec::new();
+    if let Some(out) = child.stdout.take() {
+        readers.push(spawn_line_reader(out, false, Arc::clone(&log), Arc::clone(&on_event)));
+    }
+    if let Some(err) = child.stderr.take() {
+        readers.push(spawn_line_reader(err, true, Arc::clone(&log), Arc::clone(&on_event)));
+    }
+
+    let waiter = handle.clone();
+    std::thread::spawn(move || {
+        let exit_code = child.wait().ok().and_then(|s| s.code());
+        waiter.finished.store(true, Ordering::Release);
+        for reader in readers {
+            let _ = reader.join();
+        }
+        let cancelled = waiter.killed.load(Ordering::Acquire);
+        let duration = started.elapsed();
+        if let Some(log) = log.lock().as_mut() {
+            let _ = log.finish(exit_code, cancelled, duration);
+        }
+        *waiter.outcome.lock() = Some(JobOutcome {
+            exit_code,
+            cancelled,
+            duration,
+        });
+        on_event(JobEvent::Finished { exit_code, cancelled });
+    });
+
+    Ok(handle)
+}
+
+fn spawn_line_reader<R, F>(
+    source: R,
+    is_stderr: bool,
+    log: Arc<Mutex<Option<JobLog>>>,
+    on_event: Arc<F>,
+) -> std::thread::JoinHandle<()>
+where
+    R: std::io::Read + Send + 'static,
+    F: Fn(JobEvent) + Send + Sync + 'static,
+{
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(source);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let text = String::from_utf8_lossy(&buf);
+                    let line = text.trim_end_matches(['\n', '\r']).to_string();
+                    if let Some(log) = log.lock().as_mut() {
+                        let _ = log.write_line(&line);
+                    }
+                    on_event(JobEvent::Output { line, is_stderr });
+                }
+            }
+        }
+    })
+}
+
 // ─── Tests ──────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -426,6 +876,88 @@ mod tests {
         assert_eq!(mgr.jobs.len(), MAX_RETAINED);
     }
 
+    #[test]
+    fn test_retention_deletes_evicted_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut mgr = JobManager::new();
+        let mut paths = Vec::new();
+        for i in 0..MAX_RETAINED + 2 {
+            let id = mgr.create_job(format!("cmd {i}"), None);
+            let path = log_path_for(dir.path(), id);
+            JobLog::create(&path, "cmd").unwrap();
+            let job = mgr.get_mut(id).unwrap();
+            job.log_path = Some(path.clone());
+            job.complete(0);
+            paths.push(path);
+        }
+
+        mgr.enforce_retention();
+        assert!(!paths[0].exists());
+        assert!(!paths[1].exists());
+        assert!(paths[2..].iter().all(|p| p.exists()));
+    }
+
+    #[test]
+    fn test_prune_logs_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let start = std::time::SystemTime::now() - Duration::from_secs(100);
+        for i in 0..5u64 {
+            let path = dir.path().join(format!("job{i}.log"));
+            let file = File::create(&path).unwrap();
+            file.set_modified(start + Duration::from_secs(i)).unwrap();
+        }
+        std::fs::write(dir.path().join("notes.txt"), "keep me").unwrap();
+
+        assert_eq!(prune_logs(dir.path(), 2).unwrap(), 3);
+        let mut left: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        assert_eq!(left, ["job3.log", "job4.log", "notes.txt"]);
+
+        assert_eq!(prune_logs(&dir.path().join("missing"), 2).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_prune_logs_spares_live_processes() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = std::time::SystemTime::now() - Duration::from_secs(3600);
+        let ours = log_path_for(dir.path(), 1);
+        // Above any kernel's PID limit, so no such process exists
+        let gone = dir.path().join("20260101-000000-2147483647-job1.log");
+        for path in [&ours, &gone] {
+            File::create(path).unwrap().set_modified(old).unwrap();
+        }
+        File::create(dir.path().join("newest.log")).unwrap();
+
+        assert_eq!(log_owner(&ours), Some(std::process::id()));
+        assert_eq!(log_owner(&gone), Some(2_147_483_647));
+        assert_eq!(log_owner(Path::new("job3.log")), None);
+
+        assert_eq!(prune_logs(dir.path(), 1).unwrap(), 1);
+        assert!(ours.exists());
+        assert!(!gone.exists());
+    }
+
+    #[test]
+    fn test_read_log_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("job.log");
+        let text: Vec<String> = (0..10_000).map(|i| format!("line {i}")).collect();
+        std::fs::write(&path, text.join("\n")).unwrap();
+
+        assert_eq!(read_log_tail(&path, 2).unwrap(), "line 9998\nline 9999");
+        let tail = read_log_tail(&path, 100_000).unwrap();
+        assert!(tail.ends_with("line 9999"));
+        // The seek lands mid-file; the partial first line is dropped
+        assert!(tail.lines().all(|l| l.starts_with("line ")));
+        assert!(!tail.starts_with("line 0\n"));
+
+        std::fs::write(&path, "$ make\nok\n").unwrap();
+        assert_eq!(read_log_tail(&path, 40).unwrap(), "$ make\nok");
+    }
+
     #[test]
     fn test_retention_preserves_running() {
         let mut mgr = JobManager::new();
@@ -526,4 +1058,109 @@ mod tests {
         assert_eq!(format!("{}", JobStatus::Failed), "failed");
         assert_eq!(format!("{}", JobStatus::Cancelled), "cancelled");
     }
+
+    #[test]
+    fn test_summary_and_wait_report() {
+        let mut job = Job::new(3, "cargo build".into(), None);
+        job.log_path = Some(PathBuf::from("/tmp/job3.log"));
+        for i in 0..50 {
+            job.append_stdout(format!("out {i}"));
+        }
+        job.append_stderr("warning: unused".into());
+        job.complete(101);
+
+        let summary = job.summary();
+        assert!(summary.starts_with("Job #3 failed in "));
+        assert!(summary.ends_with("(exit 101): cargo build"));
+
+        let report = job.wait_report();
+        assert!(report.contains("Full log: /tmp/job3.log"));
+        assert!(report.contains("stdout, last 40 lines:"));
+        assert!(!report.contains("out 9\n"));
+        assert!(report.contains("out 49"));
+        assert!(report.contains("stderr:\n```\nwarning: unused"));
+    }
+
+    #[test]
+    fn test_log_path_for() {
+        let path = log_path_for(Path::new("/logs"), 7);
+        assert_eq!(path.parent(), Some(Path::new("/logs")));
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        assert!(name.ends_with(&format!("-{}-job7.log", std::process::id())));
+    }
+
+    #[test]
+    fn test_job_log_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("job.log");
+        let mut log = JobLog::create(&path, "make").unwrap();
+        log.write_line("building").unwrap();
+        log.finish(Some(2), false, Duration::from_millis(1500)).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text, "$ make\nbuilding\n\n[exit 2 after 1.5s]\n");
+    }
+
+    #[test]
+    fn test_follow_log_argv() {
+        let argv = follow_log_argv(Path::new("/tmp/a.log"));
+        assert_eq!(argv, vec!["tail", "-n", "+1", "-f", "/tmp/a.log"]);
+    }
+
+    #[cfg(unix)]
+    fn run_collecting(command: &str, log_path: Option<&Path>, kill: bool) -> Vec<JobEvent> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let tx = Mutex::new(tx);
+        let handle = spawn_job(command, None, log_path, move |event| {
+            let _ = tx.lock().send(event);
+        })
+        .unwrap();
+        if kill {
+            handle.kill();
+        }
+
+        let mut events = Vec::new();
+        while let Ok(event) = rx.recv_timeout(Duration::from_secs(20)) {
+            let done = matches!(event, JobEvent::Finished { .. });
+            events.push(event);
+            if done {
+                break;
+            }
+        }
+        assert!(handle.is_finished());
+        let report = handle.wait_report(1).unwrap();
+        assert!(report.starts_with(&format!("[Background Job]\n#1 {command}: ")));
+        events
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_spawn_job_streams_and_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("job.log");
+        let events = run_collecting("echo one; echo two >&2; exit 4", Some(&log), false);
+
+        assert!(matches!(events[0], JobEvent::Started { .. }));
+        assert!(events.contains(&JobEvent::Output { line: "one".into(), is_stderr: false }));
+        assert!(events.contains(&JobEvent::Output { line: "two".into(), is_stderr: true }));
+        assert_eq!(
+            events.last(),
+            Some(&JobEvent::Finished { exit_code: Some(4), cancelled: false })
+        );
+
+        let text = std::fs::read_to_string(&log).unwrap();
+        assert!(text.starts_with("$ echo one"));
+        assert!(text.contains("\none\n"));
+        assert!(text.contains("\ntwo\n"));
+        assert!(text.contains("[exit 4 after "));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_spawn_job_kill() {
+        let events = run_collecting("sleep 30", None, true);
+        assert!(matches!(
+            events.last(),
+            Some(JobEvent::Finished { cancelled: true, .. })
+        ));
+    }
 }
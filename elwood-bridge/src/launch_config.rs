@@ -29,6 +29,49 @@
 //! [[config.startup_commands]]
 //! command = "cargo check --workspace"
 //! ```
+//!
+//! ## Layouts
+//!
+//! A config can also describe a whole window: `[[config.tabs]]` entries each
+//! hold a split tree under `layout`. A node with `split` divides its space
+//! between `children` (`horizontal` = side by side, `vertical` = stacked,
+//! matching WezTerm's split naming); any other node is a pane. `size` is a
+//! percentage of the parent split, and children without one share what's
+//! left. Relative `cwd`s resolve against `working_dir`.
+//!
+//! The pane marked `agent = true` is where the current Elwood pane moves to;
+//! every other pane is a new shell (in `domain` if given) that has its
+//! `commands` typed in. The top-level `startup_commands`, `model` and
+//! `agent_prompt` still apply to the Elwood pane.
+//!
+//! ```toml
+//! [[config.tabs]]
+//! title = "dev"
+//!
+//! [config.tabs.layout]
+//! split = "horizontal"
+//!
+//! [[config.tabs.layout.children]]
+//! agent = true
+//! size = 40
+//!
+//! [[config.tabs.layout.children]]
+//! split = "vertical"
+//!
+//! [[config.tabs.layout.children.children]]
+//! commands = ["cargo watch -x check"]
+//!
+//! [[config.tabs.layout.children.children]]
+//! cwd = "docs"
+//! size = 30
+//!
+//! [[config.tabs]]
+//! title = "server"
+//!
+//! [config.tabs.layout]
+//! domain = "devbox"
+//! commands = ["cd /srv/app", "tail -f log/production.log"]
+//! ```
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -69,6 +112,261 @@ pub struct LaunchConfig {
     /// Tags for categorization and search.
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Window layout: one entry per tab. Empty means "this pane only".
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tabs: Vec<TabLayout>,
+}
+
+/// How a split arranges its children (WezTerm's naming).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitAxis {
+    /// Children side by side, left to right.
+    Horizontal,
+    /// Children stacked, top to bottom.
+    Vertical,
+}
+
+/// A node in a tab's split tree: a split when `split` is set, otherwise a pane.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LayoutNode {
+    /// Percentage of the parent split (1–99).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u8>,
+    /// Direction to lay out `children` in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split: Option<SplitAxis>,
+    /// Child nodes of a split.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<LayoutNode>,
+    /// Pane working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// Commands typed into the pane's shell once it starts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<String>,
+    /// Mux domain to spawn the pane in (defaults to the default domain).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    /// Extra environment for this pane, on top of the config's `env_vars`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+    /// Put the Elwood agent pane here.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub agent: bool,
+}
+
+impl LayoutNode {
+    fn is_split(&self) -> bool {
+        self.split.is_some()
+    }
+
+    fn count_panes(&self) -> usize {
+        if self.is_split() {
+            self.children.iter().map(Self::count_panes).sum()
+        } else {
+            1
+        }
+    }
+
+    fn count_agents(&self) -> usize {
+        if self.is_split() {
+            self.children.iter().map(Self::count_agents).sum()
+        } else {
+            usize::from(self.agent)
+        }
+    }
+}
+
+/// One tab of a window layout.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TabLayout {
+    /// Tab title.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Split tree for the tab (a single default shell when omitted).
+    #[serde(default)]
+    pub layout: LayoutNode,
+}
+
+/// A pane to create, with its directory and environment resolved.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlannedPane {
+    pub cwd: Option<String>,
+    pub commands: Vec<String>,
+    pub domain: Option<String>,
+    pub env: HashMap<String, String>,
+    pub agent: bool,
+}
+
+/// Split `panes[target]`, creating the next pane in the plan on its
+/// right/bottom side with `percent` of the space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlannedSplit {
+    pub target: usize,
+    pub axis: SplitAxis,
+    pub percent: u8,
+}
+
+/// The mux operations that build one tab: `panes[0]` is spawned with the
+/// tab, then each split in order creates `panes[1..]`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TabPlan {
+    pub title: Option<String>,
+    pub panes: Vec<PlannedPane>,
+    pub splits: Vec<PlannedSplit>,
+}
+
+impl LaunchConfig {
+    /// Check the layout for structural errors before anything is spawned.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        fn check(node: &LayoutNode, path: &str) -> anyhow::Result<()> {
+            if let Some(size) = node.size {
+                if !(1..=99).contains(&size) {
+                    anyhow::bail!("{path}: size must be between 1 and 99, got {size}");
+                }
+            }
+            if !node.is_split() {
+                if !node.children.is_empty() {
+                    anyhow::bail!("{path}: children need a `split` direction");
+                }
+                return Ok(());
+            }
+            if node.children.len() < 2 {
+                anyhow::bail!("{path}: a split needs at least two children");
+            }
+            // Every child without a size still needs at least 1%
+            let (explicit, implicit) = size_totals(&node.children);
+            if explicit + implicit > 100 {
+                anyhow::bail!("{path}: child sizes add up to more than 100%");
+            }
+            for (i, child) in node.children.iter().enumerate() {
+                check(child, &format!("{path}.children[{i}]"))?;
+            }
+            Ok(())
+        }
+
+        for (i, tab) in self.tabs.iter().enumerate() {
+            check(&tab.layout, &format!("tabs[{i}]"))?;
+        }
+        let agents: usize = self.tabs.iter().map(|t| t.layout.count_agents()).sum();
+        if agents > 1 {
+            anyhow::bail!("Only one pane can have `agent = true` (found {agents})");
+        }
+        Ok(())
+    }
+
+    /// Turn `tabs` into mux operations.
+    pub fn plan_tabs(&self) -> Vec<TabPlan> {
+        self.tabs
+            .iter()
+            .map(|tab| {
+                let mut plan = TabPlan {
+                    title: tab.title.clone(),
+                    panes: vec![PlannedPane::default()],
+                    splits: Vec::new(),
+                };
+                self.plan_node(&tab.layout, 0, &mut plan);
+                plan
+            })
+            .collect()
+    }
+
+    /// Lay out `node` in `plan.panes[slot]`. A split first carves every
+    /// child's space out of the slot, then recurses into each child.
+    fn plan_node(&self, node: &LayoutNode, slot: usize, plan: &mut TabPlan) {
+        if !node.is_split() {
+            plan.panes[slot] = self.plan_pane(node);
+            return;
+        }
+        let axis = node.split.unwrap_or(SplitAxis::Horizontal);
+        let sizes = split_sizes(&node.children);
+
+        let mut slots = vec![slot];
+        for i in 1..sizes.len() {
+            // The pane being split holds children i-1.. ; the new pane gets i..
+            let remaining: u32 = sizes[i..].iter().sum();
+            let total = remaining + sizes[i - 1];
+            let percent = ((remaining * 100 + total / 2) / total.max(1)).clamp(1, 99) as u8;
+            plan.splits.push(PlannedSplit {
+                target: slots[i - 1],
+                axis,
+                percent,
+            });
+            plan.panes.push(PlannedPane::default());
+            slots.push(plan.panes.len() - 1);
+        }
+        for (child, child_slot) in node.children.iter().zip(slots) {
+            self.plan_node(child, child_slot, plan);
+        }
+    }
+
+    fn plan_pane(&self, node: &LayoutNode) -> PlannedPane {
+        let mut env = self.env_vars.clone();
+        env.extend(node.env.clone());
+        PlannedPane {
+            cwd: resolve_dir(self.working_dir.as_deref(), node.cwd.as_deref()),
+            commands: node.commands.clone(),
+            domain: node.domain.clone(),
+            env,
+            agent: node.agent,
+        }
+    }
+}
+
+/// Sum of the explicit child sizes, and how many children have none.
+fn size_totals(children: &[LayoutNode]) -> (u32, u32) {
+    children.iter().fold((0, 0), |(sum, open), c| match c.size {
+        Some(size) => (sum + u32::from(size), open),
+        None => (sum, open + 1),
+    })
+}
+
+/// Percent of a split each child gets: explicit sizes as given, the rest
+/// shared evenly. Sums to 100 when the config is valid.
+fn split_sizes(children: &[LayoutNode]) -> Vec<u32> {
+    let (explicit, implicit) = size_totals(children);
+    let share = 100u32
+        .saturating_sub(explicit)
+        .checked_div(implicit)
+        .unwrap_or(0);
+    let mut sizes: Vec<u32> = children
+        .iter()
+        .map(|c| c.size.map(u32::from).unwrap_or(share).max(1))
+        .collect();
+    // Give rounding leftovers to the last child
+    let sum: u32 = sizes.iter().sum();
+    if sum < 100 {
+        if let Some(last) = sizes.last_mut() {
+            *last += 100 - sum;
+        }
+    }
+    sizes
+}
+
+/// Expand `~` and resolve a relative `dir` against `base`.
+fn resolve_dir(base: Option<&str>, dir: Option<&str>) -> Option<String> {
+    let expand = |d: &str| -> PathBuf {
+        let rest = if d == "~" {
+            Some("")
+        } else {
+            d.strip_prefix("~/")
+        };
+        match rest {
+            Some(rest) => dirs_next::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(rest),
+            None => PathBuf::from(d),
+        }
+    };
+    let resolved = match (base, dir) {
+        (_, Some(d)) if d.starts_with('~') || Path::new(d).is_absolute() => expand(d),
+        (Some(b), Some(d)) => expand(b).join(d),
+        (None, Some(d)) => PathBuf::from(d),
+        (Some(b), None) => expand(b),
+        (None, None) => return None,
+    };
+    Some(resolved.to_string_lossy().into_owned())
 }
 
 /// Wrapper for TOML serialization — `[config]` table.
@@ -90,13 +388,13 @@ pub struct PaneState {
 
 /// Structured data returned when applying a launch config.
 ///
-/// The pane uses this to set working directory, apply env vars, and run
-/// startup commands sequentially.
+/// The pane runs the startup commands in `working_dir` with `env_vars`
+/// added to their environment; the process environment is left alone.
 #[derive(Debug, Clone)]
 pub struct ApplyResult {
-    /// Directory to change to (if any).
+    /// Directory to run the startup commands in (if any).
     pub working_dir: Option<String>,
-    /// Environment variables to set.
+    /// Environment variables for the startup commands.
     pub env_vars: HashMap<String, String>,
     /// Commands to run sequentially.
     pub commands: Vec<String>,
@@ -104,6 +402,8 @@ pub struct ApplyResult {
     pub model: Option<String>,
     /// Agent prompt to set (if any).
     pub agent_prompt: Option<String>,
+    /// Tabs to build (empty when the config only affects the current pane).
+    pub tabs: Vec<TabPlan>,
 }
 
 // ─── Launch Config Manager ──────────────────────────────────────────────────
@@ -234,11 +534,12 @@ impl LaunchConfigManager {
             .map(|sc| sc.command.clone())
             .collect();
         ApplyResult {
-            working_dir: config.working_dir.clone(),
+            working_dir: resolve_dir(config.working_dir.as_deref(), None),
             env_vars: config.env_vars.clone(),
             commands,
             model: config.model.clone(),
             agent_prompt: config.agent_prompt.clone(),
+            tabs: config.plan_tabs(),
         }
     }
 
@@ -253,6 +554,7 @@ impl LaunchConfigManager {
             model: pane_state.model.clone(),
             agent_prompt: None,
             tags: Vec::new(),
+            tabs: Vec::new(),
         }
     }
 }
@@ -319,7 +621,8 @@ fn launch_help() -> String {
 Launch configuration commands:
 
   /launch list               List saved configurations
-  /launch apply <name>       Apply a configuration (cd, env, run commands)
+  /launch apply <name>       Apply a configuration (cd, env, run commands,
+                             and build its tabs and splits; alias: load)
   /launch save <name>        Save current state as a configuration
   /launch show <name>        Show configuration details
   /launch edit <name>        Show TOML file path for manual editing
@@ -377,7 +680,11 @@ fn execute_apply(args: &str) -> LaunchCommandResult {
     }
 
     let mgr = LaunchConfigManager::new();
-    match mgr.load(name) {
+    let loaded = mgr.load(name).and_then(|config| {
+        config.validate()?;
+        Ok(config)
+    });
+    match loaded {
         Ok(config) => {
             let result = mgr.apply(&config);
             LaunchCommandResult::Apply(result)
@@ -484,12 +791,56 @@ fn execute_show(args: &str) -> LaunchCommandResult {
                     msg.push_str(&format!("  {}. {}\n", i + 1, cmd.command));
                 }
             }
+            if !c.tabs.is_empty() {
+                msg.push_str(&format!("\nLayout ({} tabs):\n", c.tabs.len()));
+                for (i, tab) in c.tabs.iter().enumerate() {
+                    let title = tab.title.as_deref().unwrap_or("(untitled)");
+                    msg.push_str(&format!(
+                        "  Tab {}: {title} ({} panes)\n",
+                        i + 1,
+                        tab.layout.count_panes()
+                    ));
+                    describe_node(&tab.layout, 2, &mut msg);
+                }
+            }
             LaunchCommandResult::ChatMessage(msg)
         }
         Err(e) => LaunchCommandResult::ChatMessage(format!("Error: {e}")),
     }
 }
 
+/// Append an indented outline of a split tree to `out`.
+fn describe_node(node: &LayoutNode, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    let size = node.size.map(|s| format!("[{s}%] ")).unwrap_or_default();
+    let label = match node.split {
+        Some(SplitAxis::Horizontal) => "horizontal split".to_string(),
+        Some(SplitAxis::Vertical) => "vertical split".to_string(),
+        None if node.agent => "Elwood agent".to_string(),
+        None => {
+            let mut parts = Vec::new();
+            if let Some(ref domain) = node.domain {
+                parts.push(format!("{domain}:"));
+            }
+            if let Some(ref cwd) = node.cwd {
+                parts.push(cwd.clone());
+            }
+            if !node.commands.is_empty() {
+                parts.push(format!("$ {}", node.commands.join(" && ")));
+            }
+            if parts.is_empty() {
+                "shell".to_string()
+            } else {
+                parts.join(" ")
+            }
+        }
+    };
+    out.push_str(&format!("{indent}{size}{label}\n"));
+    for child in &node.children {
+        describe_node(child, depth + 1, out);
+    }
+}
+
 fn execute_edit(args: &str) -> LaunchCommandResult {
     let name = args.trim();
     if name.is_empty() {
@@ -541,6 +892,7 @@ mod tests {
             model: Some("gemini-2.5-pro".to_string()),
             agent_prompt: None,
             tags: vec!["dev".to_string(), "rust".to_string()],
+            tabs: Vec::new(),
         }
     }
 
@@ -793,6 +1145,7 @@ command = "ls -la"
             model: None,
             agent_prompt: None,
             tags: Vec::new(),
+            tabs: Vec::new(),
         };
 
         let result = mgr.apply(&config);
@@ -801,6 +1154,7 @@ command = "ls -la"
         assert!(result.commands.is_empty());
         assert!(result.model.is_none());
         assert!(result.agent_prompt.is_none());
+        assert!(result.tabs.is_empty());
     }
 
     // ── create_from_current ─────────────────────────────────────────────
@@ -973,4 +1327,180 @@ command = "ls -la"
             _ => panic!("Expected ChatMessage"),
         }
     }
+
+    // ── layouts ─────────────────────────────────────────────────────────
+
+    const LAYOUT_TOML: &str = r#"
+[config]
+name = "layout"
+working_dir = "/work/app"
+
+[config.env_vars]
+RUST_LOG = "debug"
+
+[[config.tabs]]
+title = "dev"
+
+[config.tabs.layout]
+split = "horizontal"
+
+[[config.tabs.layout.children]]
+agent = true
+size = 40
+
+[[config.tabs.layout.children]]
+split = "vertical"
+
+[[config.tabs.layout.children.children]]
+commands = ["cargo watch -x check"]
+
+[[config.tabs.layout.children.children]]
+cwd = "docs"
+size = 30
+env = { RUST_LOG = "info" }
+
+[[config.tabs]]
+title = "server"
+
+[config.tabs.layout]
+domain = "devbox"
+cwd = "/srv"
+"#;
+
+    fn layout_config() -> LaunchConfig {
+        toml::from_str::<LaunchConfigFile>(LAYOUT_TOML)
+            .unwrap()
+            .config
+    }
+
+    #[test]
+    fn test_layout_toml_parse_and_roundtrip() {
+        let config = layout_config();
+        assert_eq!(config.tabs.len(), 2);
+        let root = &config.tabs[0].layout;
+        assert_eq!(root.split, Some(SplitAxis::Horizontal));
+        assert_eq!(root.count_panes(), 3);
+        assert!(root.children[0].agent);
+        assert_eq!(config.tabs[1].layout.domain.as_deref(), Some("devbox"));
+
+        let file = LaunchConfigFile {
+            config: config.clone(),
+        };
+        let toml_str = toml::to_string_pretty(&file).unwrap();
+        let parsed: LaunchConfigFile = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed.config, config);
+    }
+
+    #[test]
+    fn test_plan_tabs() {
+        let plans = layout_config().plan_tabs();
+        assert_eq!(plans.len(), 2);
+
+        let dev = &plans[0];
+        assert_eq!(dev.title.as_deref(), Some("dev"));
+        assert_eq!(dev.panes.len(), 3);
+        assert_eq!(
+            dev.splits,
+            vec![
+                // Agent keeps 40%, the right column takes 60%
+                PlannedSplit {
+                    target: 0,
+                    axis: SplitAxis::Horizontal,
+                    percent: 60,
+                },
+                // Right column: top 70%, docs pane 30%
+                PlannedSplit {
+                    target: 1,
+                    axis: SplitAxis::Vertical,
+                    percent: 30,
+                },
+            ]
+        );
+        assert!(dev.panes[0].agent);
+        assert_eq!(dev.panes[1].commands, vec!["cargo watch -x check"]);
+        assert_eq!(dev.panes[1].cwd.as_deref(), Some("/work/app"));
+        assert_eq!(dev.panes[1].env.get("RUST_LOG").unwrap(), "debug");
+        assert_eq!(dev.panes[2].cwd.as_deref(), Some("/work/app/docs"));
+        assert_eq!(dev.panes[2].env.get("RUST_LOG").unwrap(), "info");
+
+        let server = &plans[1];
+        assert!(server.splits.is_empty());
+        assert_eq!(server.panes[0].cwd.as_deref(), Some("/srv"));
+        assert_eq!(server.panes[0].domain.as_deref(), Some("devbox"));
+    }
+
+    #[test]
+    fn test_plan_three_way_split() {
+        let pane = LayoutNode::default;
+        let config = LaunchConfig {
+            tabs: vec![TabLayout {
+                title: None,
+                layout: LayoutNode {
+                    split: Some(SplitAxis::Vertical),
+                    children: vec![pane(), pane(), pane()],
+                    ..LayoutNode::default()
+                },
+            }],
+            ..sample_config()
+        };
+        let plan = &config.plan_tabs()[0];
+        let splits: Vec<(usize, u8)> = plan.splits.iter().map(|s| (s.target, s.percent)).collect();
+        // 33/33/34: first split leaves 33% behind, second halves the rest
+        assert_eq!(splits, vec![(0, 67), (1, 51)]);
+        assert_eq!(plan.panes.len(), 3);
+    }
+
+    #[test]
+    fn test_validate_layout() {
+        assert!(layout_config().validate().is_ok());
+
+        let mut config = layout_config();
+        config.tabs[1].layout.agent = true;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("agent"));
+
+        let mut config = layout_config();
+        config.tabs[0].layout.children.truncate(1);
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("two children"));
+
+        let mut config = layout_config();
+        config.tabs[0].layout.children[0].size = Some(100);
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("more than 100%"));
+
+        let mut config = layout_config();
+        config.tabs[0].layout.children[1].children[0].size = Some(0);
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("tabs[0].children[1].children[0]"));
+
+        let mut config = layout_config();
+        config.tabs[0].layout.split = None;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("split"));
+    }
+
+    #[test]
+    fn test_resolve_dir() {
+        assert_eq!(resolve_dir(None, None), None);
+        assert_eq!(resolve_dir(Some("/a"), None).as_deref(), Some("/a"));
+        assert_eq!(resolve_dir(Some("/a"), Some("b")).as_deref(), Some("/a/b"));
+        assert_eq!(resolve_dir(Some("/a"), Some("/c")).as_deref(), Some("/c"));
+        let home = dirs_next::home_dir().unwrap();
+        assert_eq!(
+            resolve_dir(Some("/a"), Some("~/x")),
+            Some(home.join("x").to_string_lossy().into_owned())
+        );
+    }
+
+    #[test]
+    fn test_describe_layout() {
+        let mut out = String::new();
+        describe_node(&layout_config().tabs[0].layout, 0, &mut out);
+        assert_eq!(
+            out,
+            "horizontal split\n  [40%] Elwood agent\n  vertical split\n    \
+             $ cargo watch -x check\n    [30%] docs\n"
+        );
+    }
 }
@@ -19,20 +19,28 @@
 //! - **ANSI Formatter**: Converts `AgentEvent` to styled terminal output
 
 pub mod autocorrect;
+pub mod bash_stream;
 pub mod block;
+pub mod block_store;
+pub mod chart;
 pub mod commands;
+pub mod completion_spec;
 pub mod completions;
 pub mod config;
 pub mod context;
+pub mod context_window;
 pub mod diff;
 pub mod diff_viewer;
 pub mod domain;
 pub mod editor;
+pub mod error_detectors;
+pub mod external_editor;
 pub mod file_browser;
 pub mod fuzzy_finder;
 pub mod git_info;
 pub mod git_ui;
 pub mod ide_bridge;
+pub mod inline_image;
 pub mod jobs;
 pub mod history_search;
 pub mod keybindings;
@@ -51,12 +59,17 @@ pub mod plan_mode;
 pub mod plan_viewer;
 pub mod prediction_engine;
 pub mod pty_inner;
+pub mod quickfix;
 pub mod recording;
+pub mod recording_export;
 pub mod redaction;
+pub mod replay;
 pub mod runtime;
 pub mod semantic_bridge;
 pub mod session_export;
+pub mod session_index;
 pub mod session_log;
+pub mod session_resume;
 pub mod shared_writer;
 pub mod suggestion_overlay;
 pub mod tools;
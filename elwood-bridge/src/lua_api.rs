@@ -460,6 +460,18 @@ pub fn default_lua_config() -> &'static str {
     keybindings::default_keybindings_lua()
 }
 
+/// Evaluate a Lua chunk that returns a table and deserialize it into `T`.
+///
+/// Used for declarative config written in Lua (e.g. error detectors), where
+/// the script only builds data and registers no callbacks.
+pub fn eval_table<T: serde::de::DeserializeOwned>(source: &str, chunk_name: &str) -> LuaResult<T> {
+    use mlua::LuaSerdeExt;
+
+    let lua = Lua::new();
+    let value: Value = lua.load(source).set_name(chunk_name).eval()?;
+    lua.from_value(value)
+}
+
 // ── Event name constants ────────────────────────────────────────────────────
 
 /// Event fired when the agent produces content.
@@ -481,6 +493,23 @@ pub const EVENT_PERMISSION_REQUEST: &str = "permission_request";
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_eval_table() {
+        #[derive(serde::Deserialize)]
+        struct Doc {
+            name: String,
+            items: Vec<u32>,
+        }
+        let doc: Doc = eval_table(
+            r#"local n = 2; return { name = "x" .. n, items = { 1, n } }"#,
+            "test",
+        )
+        .unwrap();
+        assert_eq!(doc.name, "x2");
+        assert_eq!(doc.items, vec![1, 2]);
+        assert!(eval_table::<Doc>("return 42", "test").is_err());
+    }
+
     #[test]
     fn test_create_lua_events() {
         let events = ElwoodLuaEvents::new().expect("Failed to create Lua events");
@@ -2,11 +2,18 @@
 //!
 //! Converts markdown text to ANSI escape sequences for display in the terminal.
 //! Supports code blocks, headers, lists, tables, bold/italic, links, and blockquotes.
+//! Images are drawn inline (see [`crate::inline_image`]) and `chart` code
+//! blocks as text charts (see [`crate::chart`]).
 //!
 //! Uses the TokyoNight color palette consistent with the rest of the Elwood TUI.
 
+use std::path::{Path, PathBuf};
+
 use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd, CodeBlockKind};
 
+use crate::chart::{self, Chart};
+use crate::inline_image;
+
 // ─── ANSI Constants (TokyoNight palette) ────────────────────────────────
 
 const RESET: &str = "\x1b[0m";
@@ -42,13 +49,20 @@ fn bgc(c: (u8, u8, u8)) -> String { bg(c.0, c.1, c.2) }
 ///
 /// Parses `text` as CommonMark and converts each element to styled ANSI
 /// escape sequences suitable for display in a VT100/xterm terminal.
+/// Relative image paths are taken from the process working directory.
 pub fn render_markdown(text: &str) -> String {
+    render_markdown_in(text, Path::new(""))
+}
+
+/// Like [`render_markdown`], with relative image paths taken from `base_dir`
+/// (the pane's working directory).
+pub fn render_markdown_in(text: &str, base_dir: &Path) -> String {
     let mut opts = Options::empty();
     opts.insert(Options::ENABLE_TABLES);
     opts.insert(Options::ENABLE_STRIKETHROUGH);
 
     let parser = Parser::new_ext(text, opts);
-    let mut renderer = AnsiRenderer::new();
+    let mut renderer = AnsiRenderer::new(base_dir);
     renderer.render(parser);
     renderer.output
 }
@@ -84,12 +98,71 @@ pub fn is_markdown(text: &str) -> bool {
         }
     }
     // Inline: check for **bold** or `code`
-    if text.contains("**") || text.contains("``") {
+    if text.contains("**") || text.contains("``") || text.contains("![") {
         return true;
     }
     false
 }
 
+/// Streamed text held back while an image or chart is incomplete is
+/// released anyway past this size.
+const MAX_HELD_BACK: usize = 64 * 1024;
+
+/// How much of streamed `text` can be rendered now.
+///
+/// Replies arrive in chunks that are rendered one by one, so an image link
+/// or a chart block split across chunks would be drawn as broken text.
+/// Returns the byte length before an unfinished `![alt](src)` or an
+/// unclosed chart fence; the rest should wait for the next chunk.
+pub fn renderable_len(text: &str) -> usize {
+    let mut cut = text.len();
+
+    // An open chart fence holds everything from its opening line
+    let mut offset = 0;
+    let mut open_fence: Option<(usize, bool)> = None;
+    for line in text.split_inclusive('\n') {
+        if let Some(lang) = line.trim().strip_prefix("```") {
+            open_fence = match open_fence {
+                Some(_) => None,
+                None => Some((offset, chart::is_chart_lang(lang))),
+            };
+        }
+        offset += line.len();
+    }
+    if let Some((start, true)) = open_fence {
+        cut = start;
+    }
+
+    // An image whose link isn't closed yet
+    if let Some(start) = text[..cut].rfind("![") {
+        if may_become_image(&text[start + 2..cut]) {
+            cut = start;
+        }
+    }
+
+    if text.len() - cut > MAX_HELD_BACK {
+        text.len()
+    } else {
+        cut
+    }
+}
+
+/// Whether `rest`, the text after a `![`, can still grow into a complete
+/// `![alt](src)`: no line break yet, and either the alt text is open, it
+/// ended right at the chunk boundary, or a `(` link is still unclosed.
+fn may_become_image(rest: &str) -> bool {
+    if rest.contains('\n') {
+        return false;
+    }
+    match rest.find(']') {
+        None => true,
+        Some(close) => match rest[close + 1..].strip_prefix('(') {
+            Some(link) => !link.contains(')'),
+            None => rest.len() == close + 1,
+        },
+    }
+}
+
 // ─── Renderer State Machine ─────────────────────────────────────────────
 
 struct AnsiRenderer {
@@ -120,6 +193,10 @@ struct AnsiRenderer {
     needs_newline: bool,
     /// Link URL accumulator.
     link_url: Option<String>,
+    /// Image source and alt text accumulator.
+    image: Option<(String, String)>,
+    /// Directory relative image paths are resolved against.
+    base_dir: PathBuf,
     /// Table state.
     in_table: bool,
     table_head: bool,
@@ -129,7 +206,7 @@ struct AnsiRenderer {
 }
 
 impl AnsiRenderer {
-    fn new() -> Self {
+    fn new(base_dir: &Path) -> Self {
         Self {
             output: String::with_capacity(4096),
             bold_depth: 0,
@@ -145,6 +222,8 @@ impl AnsiRenderer {
             heading_level: None,
             needs_newline: false,
             link_url: None,
+            image: None,
+            base_dir: base_dir.to_path_buf(),
             in_table: false,
             table_head: false,
             table_rows: Vec::new(),
@@ -262,6 +341,11 @@ impl AnsiRenderer {
                 self.output.push_str(UNDERLINE);
             }
 
+            // Images in tables and quotes keep showing their alt text
+            Tag::Image { dest_url, .. } if !self.in_table && !self.in_blockquote => {
+                self.image = Some((dest_url.to_string(), String::new()));
+            }
+
             Tag::Table(_) => {
                 self.ensure_newline();
                 self.in_table = true;
@@ -333,7 +417,16 @@ impl AnsiRenderer {
 
             TagEnd::CodeBlock => {
                 self.in_code_block = false;
-                self.render_code_block();
+                let chart = if chart::is_chart_lang(&self.code_lang) {
+                    Chart::parse(&self.code_lang, &self.code_buffer)
+                } else {
+                    None
+                };
+                match chart {
+                    Some(chart) => self.output.push_str(&chart.render()),
+                    // Not chart data after all: show it as code
+                    None => self.render_code_block(),
+                }
                 self.needs_newline = true;
             }
 
@@ -369,6 +462,16 @@ impl AnsiRenderer {
                 }
             }
 
+            TagEnd::Image => {
+                if let Some((src, alt)) = self.image.take() {
+                    if !self.output.is_empty() && !self.output.ends_with('\n') {
+                        self.output.push_str("\r\n");
+                    }
+                    self.output
+                        .push_str(&inline_image::render_source(&src, &alt, &self.base_dir));
+                }
+            }
+
             TagEnd::Table => {
                 self.in_table = false;
                 self.render_table();
@@ -398,6 +501,11 @@ impl AnsiRenderer {
             return;
         }
 
+        if let Some((_, alt)) = self.image.as_mut() {
+            alt.push_str(text);
+            return;
+        }
+
         if self.in_blockquote {
             self.quote_buffer.push_str(text);
             return;
@@ -790,6 +898,50 @@ mod tests {
         assert!(output.contains("\u{2500}")); // ─
     }
 
+    #[test]
+    fn test_render_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plot.png");
+        std::fs::write(&path, b"\x89PNG\r\n\x1a\n").unwrap();
+        let output = render_markdown(&format!("Here it is:\n\n![the plot]({})", path.display()));
+        assert!(output.contains("\x1b]1337;File=inline=1;"));
+        assert!(!strip_ansi(&output).contains("the plot"));
+        let output = render_markdown_in("![the plot](plot.png)", dir.path());
+        assert!(output.contains("\x1b]1337;File=inline=1;"));
+
+        // Missing files and remote images fall back to text
+        let plain = strip_ansi(&render_markdown("![logo](https://example.com/logo.png)"));
+        assert!(plain.contains("[image: logo \u{2014} https://example.com/logo.png]"));
+        assert!(is_markdown("![logo](logo.png)"));
+    }
+
+    #[test]
+    fn test_render_chart_block() {
+        let output = render_markdown("```sparkline\n[1, 8, 4]\n```");
+        let plain = strip_ansi(&output);
+        assert!(plain.contains("\u{2581}\u{2588}"));
+        assert!(!plain.contains("[1, 8, 4]"));
+
+        // Not chart data: rendered as code
+        let plain = strip_ansi(&render_markdown("```chart\nnot json\n```"));
+        assert!(plain.contains("not json"));
+    }
+
+    #[test]
+    fn test_renderable_len() {
+        assert_eq!(renderable_len("plain text"), 10);
+        assert_eq!(renderable_len("see ![plot](out/pl"), 4);
+        assert_eq!(renderable_len("see ![plot](out/plot.png) ok"), 28);
+        assert_eq!(renderable_len("see ![plot]"), 4);
+        assert_eq!(renderable_len("see ![plot][ref] ok"), 19);
+        assert_eq!(renderable_len("a ![b\nc"), 7);
+        assert_eq!(renderable_len("![x] and more"), 13);
+        assert_eq!(renderable_len("Data:\n```chart\n[1, 2"), 6);
+        assert_eq!(renderable_len("Data:\n```chart\n[1, 2]\n```\n"), 26);
+        // Other code blocks stream as before
+        assert_eq!(renderable_len("```rust\nfn main"), 15);
+    }
+
     #[test]
     fn test_is_markdown_positive() {
         assert!(is_markdown("# Hello"));
@@ -5,6 +5,9 @@
 
 use std::collections::HashMap;
 
+/// Context budget for models the family table doesn't know.
+const DEFAULT_CONTEXT_BUDGET: usize = 128_000;
+
 /// Configuration for a single model entry.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ModelConfig {
@@ -24,6 +27,10 @@ pub struct ModelConfig {
     /// Cost per 1K output tokens in USD (optional override).
     #[serde(default)]
     pub cost_per_1k_output: f64,
+    /// Context window in tokens (optional override; see
+    /// [`ModelConfig::context_budget`]).
+    #[serde(default)]
+    pub context_window: Option<usize>,
 }
 
 impl ModelConfig {
@@ -35,6 +42,26 @@ impl ModelConfig {
             &self.display_name
         }
     }
+
+    /// Tokens the conversation may take up: the configured `context_window`,
+    /// or a default for the model family.
+    pub fn context_budget(&self) -> usize {
+        if let Some(tokens) = self.context_window.filter(|&t| t > 0) {
+            return tokens;
+        }
+        let name = self.name.to_lowercase();
+        if name.starts_with("gemini") {
+            1_000_000
+        } else if name.starts_with("claude") {
+            200_000
+        } else if name.starts_with("gpt-4.1") {
+            1_000_000
+        } else if name.starts_with("o1") || name.starts_with("o3") || name.starts_with("o4") {
+            200_000
+        } else {
+            DEFAULT_CONTEXT_BUDGET
+        }
+    }
 }
 
 /// Tracks cumulative token usage and cost.
@@ -119,6 +146,7 @@ impl ModelRouter {
             default: true,
             cost_per_1k_input: 0.0,
             cost_per_1k_output: 0.0,
+            context_window: None,
         };
         Self::new(vec![config])
     }
@@ -219,6 +247,7 @@ mod tests {
                 default: true,
                 cost_per_1k_input: 0.00125,
                 cost_per_1k_output: 0.005,
+                context_window: None,
             },
             ModelConfig {
                 name: "gemini-2.5-flash".into(),
@@ -227,6 +256,7 @@ mod tests {
                 default: false,
                 cost_per_1k_input: 0.000075,
                 cost_per_1k_output: 0.0003,
+                context_window: None,
             },
             ModelConfig {
                 name: "claude-sonnet-4-6".into(),
@@ -235,6 +265,7 @@ mod tests {
                 default: false,
                 cost_per_1k_input: 0.003,
                 cost_per_1k_output: 0.015,
+                context_window: None,
             },
         ]
     }
@@ -255,6 +286,7 @@ mod tests {
                 default: false,
                 cost_per_1k_input: 0.0,
                 cost_per_1k_output: 0.0,
+                context_window: None,
             },
             ModelConfig {
                 name: "b".into(),
@@ -263,6 +295,7 @@ mod tests {
                 default: false,
                 cost_per_1k_input: 0.0,
                 cost_per_1k_output: 0.0,
+                context_window: None,
             },
         ];
         let router = ModelRouter::new(models);
@@ -350,6 +383,7 @@ mod tests {
             default: false,
             cost_per_1k_input: 0.0,
             cost_per_1k_output: 0.0,
+            context_window: None,
         };
         assert_eq!(m.label(), "test-model");
 
@@ -360,10 +394,25 @@ mod tests {
             default: false,
             cost_per_1k_input: 0.0,
             cost_per_1k_output: 0.0,
+            context_window: None,
         };
         assert_eq!(m2.label(), "Pretty Name");
     }
 
+    #[test]
+    fn test_context_budget() {
+        let models = sample_models();
+        assert_eq!(models[0].context_budget(), 1_000_000);
+        assert_eq!(models[2].context_budget(), 200_000);
+
+        let mut m = ModelRouter::from_single("ollama", "llama3")
+            .active_model()
+            .clone();
+        assert_eq!(m.context_budget(), DEFAULT_CONTEXT_BUDGET);
+        m.context_window = Some(8_192);
+        assert_eq!(m.context_budget(), 8_192);
+    }
+
     #[test]
     fn test_format_model_list() {
         let router = ModelRouter::new(sample_models());
@@ -13,16 +13,42 @@
 //! | `/agent kill <name\|id>` | Terminate an agent |
 //! | `/agent focus <name\|id>` | Switch to agent's pane |
 //! | `/tell <name> <message>` | Send message to agent |
+//! | `/agent fanout [--no-worktree] <a>; <b>; ...` | Split a task across agents |
+//! | `/agent results <task-id>` | Show aggregated results of a fan-out task |
+//! | `/agent merge <task-id>[.<n>]` | Merge subtask branches into the checkout |
+//! | `/agent discard <task-id>[.<n>]` | Delete subtask worktrees and branches |
+//!
+//! ## Supervisor Mode
+//!
+//! `/agent fanout` breaks a task into subtasks and starts one agent pane per
+//! subtask. In a git repository each agent gets its own worktree on an
+//! `elwood/task-<id>-<n>` branch so their edits don't collide; otherwise they
+//! share the working directory. Each agent pane and its runtime run in that
+//! workspace. The [`FanOutTask`] tracks progress (shown in
+//! [`render_dashboard`]) and, once every subtask has finished, the summaries
+//! and diffs are combined by [`aggregate_results`] and reported back to the
+//! pane that started the task. A follow-up message to an agent reopens its
+//! subtask, and the task is reported again when it finishes.
+//!
+//! Worktrees and branches are kept until the user decides: `/agent merge`
+//! commits a subtask's work and merges its branch into the current checkout
+//! ([`merge_workspace`]), `/agent discard` deletes them ([`remove_workspace`]).
+//! Either way the agent's pane is closed.
 //!
 //! ## Architecture
 //!
 //! The registry is designed to be wrapped in `Arc<Mutex<AgentRegistry>>` and
 //! shared between the domain (which spawns panes) and the command layer (which
-//! parses user input). Actual pane creation via WezTerm's Mux will be wired
-//! in the domain layer later.
+//! parses user input). [`shared_registry`] provides the process-wide instance
+//! used by agent panes.
 
 use std::collections::HashMap;
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use parking_lot::Mutex;
 
 use chrono::{DateTime, Local, Utc};
 
@@ -146,6 +172,8 @@ impl AgentInstance {
 pub struct AgentRegistry {
     agents: HashMap<u32, AgentInstance>,
     next_id: u32,
+    tasks: Vec<FanOutTask>,
+    next_task_id: u32,
 }
 
 impl AgentRegistry {
@@ -154,6 +182,8 @@ impl AgentRegistry {
         Self {
             agents: HashMap::new(),
             next_id: 1,
+            tasks: Vec::new(),
+            next_task_id: 1,
         }
     }
 
@@ -313,6 +343,235 @@ impl AgentRegistry {
             .values()
             .any(|a| a.name.to_lowercase() == lower)
     }
+
+    // ── Fan-out tasks ───────────────────────────────────────────────────
+
+    /// Start a fan-out task, returning its ID.
+    ///
+    /// `origin_pane` is the pane that receives the aggregated results.
+    pub fn start_fanout(
+        &mut self,
+        prompt: &str,
+        subtasks: Vec<String>,
+        origin_pane: Option<usize>,
+    ) -> u32 {
+        let id = self.next_task_id;
+        self.next_task_id += 1;
+
+        let subtasks = subtasks
+            .into_iter()
+            .enumerate()
+            .map(|(index, description)| Subtask {
+                index,
+                description,
+                agent_id: None,
+                workspace: None,
+                status: SubtaskStatus::Pending,
+                summary: None,
+                diff: None,
+                turn: 0,
+                resolution: None,
+            })
+            .collect();
+
+        self.tasks.push(FanOutTask {
+            id,
+            prompt: prompt.to_string(),
+            origin_pane,
+            subtasks,
+            created_at: Utc::now(),
+            warnings: Vec::new(),
+            reported: false,
+        });
+        id
+    }
+
+    /// Get a fan-out task by ID.
+    pub fn task(&self, id: u32) -> Option<&FanOutTask> {
+        self.tasks.iter().find(|t| t.id == id)
+    }
+
+    /// All fan-out tasks, oldest first.
+    pub fn tasks(&self) -> &[FanOutTask] {
+        &self.tasks
+    }
+
+    /// Assign an agent and workspace to a subtask and mark it running.
+    ///
+    /// Returns `true` if the subtask exists.
+    pub fn assign_subtask(
+        &mut self,
+        task_id: u32,
+        index: usize,
+        agent_id: u32,
+        workspace: Workspace,
+    ) -> bool {
+        let Some(subtask) = self.subtask_mut(task_id, index) else {
+            return false;
+        };
+        subtask.agent_id = Some(agent_id);
+        subtask.workspace = Some(workspace);
+        subtask.status = SubtaskStatus::Running;
+        let description = subtask.description.clone();
+        self.set_task(agent_id, Some(description));
+        self.update_status(agent_id, AgentStatus::Working);
+        true
+    }
+
+    /// Record a problem hit while setting up a task (reported with its results).
+    pub fn add_task_warning(&mut self, task_id: u32, warning: String) -> bool {
+        let Some(task) = self.tasks.iter_mut().find(|t| t.id == task_id) else {
+            return false;
+        };
+        task.warnings.push(warning);
+        true
+    }
+
+    /// Find the `(task_id, index)` of the subtask assigned to an agent.
+    pub fn subtask_for_agent(&self, agent_id: u32) -> Option<(u32, usize)> {
+        self.tasks.iter().find_map(|t| {
+            t.subtasks
+                .iter()
+                .find(|s| s.agent_id == Some(agent_id))
+                .map(|s| (t.id, s.index))
+        })
+    }
+
+    /// Workspace of the subtask assigned to an agent.
+    pub fn workspace_for_agent(&self, agent_id: u32) -> Option<&Workspace> {
+        let (task_id, index) = self.subtask_for_agent(agent_id)?;
+        self.task(task_id)?.subtasks.get(index)?.workspace.as_ref()
+    }
+
+    /// Record a finished subtask for `agent_id`.
+    ///
+    /// Returns the task ID if this completed the whole task.
+    pub fn complete_subtask(
+        &mut self,
+        agent_id: u32,
+        summary: &str,
+        diff: Option<String>,
+    ) -> Option<u32> {
+        self.finish_subtask(agent_id, SubtaskStatus::Done, summary, diff)
+    }
+
+    /// Record a finished subtask for `agent_id`, unless a follow-up has
+    /// started another `turn` since (its result would be stale).
+    ///
+    /// Returns the task ID if this completed the whole task.
+    pub fn complete_turn(
+        &mut self,
+        agent_id: u32,
+        turn: u32,
+        summary: &str,
+        diff: Option<String>,
+    ) -> Option<u32> {
+        if self.subtask_turn(agent_id)? != turn {
+            return None;
+        }
+        self.complete_subtask(agent_id, summary, diff)
+    }
+
+    /// Record a failed subtask for `agent_id`.
+    ///
+    /// Returns the task ID if this completed the whole task.
+    pub fn fail_subtask(&mut self, agent_id: u32, error: &str) -> Option<u32> {
+        self.finish_subtask(agent_id, SubtaskStatus::Failed, error, None)
+    }
+
+    /// Current turn of the subtask assigned to `agent_id`.
+    pub fn subtask_turn(&self, agent_id: u32) -> Option<u32> {
+        let (task_id, index) = self.subtask_for_agent(agent_id)?;
+        Some(self.task(task_id)?.subtasks.get(index)?.turn)
+    }
+
+    /// Mark `agent_id`'s subtask running again because the agent was sent a
+    /// follow-up; its task is reported again once it finishes.
+    ///
+    /// Subtasks whose workspace was merged or discarded stay closed.
+    pub fn reopen_subtask(&mut self, agent_id: u32) -> bool {
+        let Some((task_id, index)) = self.subtask_for_agent(agent_id) else {
+            return false;
+        };
+        let Some(task) = self.tasks.iter_mut().find(|t| t.id == task_id) else {
+            return false;
+        };
+        let subtask = &mut task.subtasks[index];
+        if subtask.resolution.is_some() {
+            return false;
+        }
+        subtask.turn += 1;
+        subtask.status = SubtaskStatus::Running;
+        let description = subtask.description.clone();
+        task.reported = false;
+        self.set_task(agent_id, Some(description));
+        self.update_status(agent_id, AgentStatus::Working);
+        true
+    }
+
+    /// Record that a subtask's workspace was merged or discarded.
+    pub fn resolve_subtask(&mut self, task_id: u32, index: usize, resolution: Resolution) -> bool {
+        let Some(subtask) = self.subtask_mut(task_id, index) else {
+            return false;
+        };
+        subtask.resolution = Some(resolution);
+        true
+    }
+
+    /// Take finished tasks started from `pane_id` that haven't been reported yet.
+    pub fn take_finished(&mut self, pane_id: usize) -> Vec<FanOutTask> {
+        let mut finished = Vec::new();
+        for task in &mut self.tasks {
+            if !task.reported && task.origin_pane == Some(pane_id) && task.is_finished() {
+                task.reported = true;
+                finished.push(task.clone());
+            }
+        }
+        finished
+    }
+
+    fn subtask_mut(&mut self, task_id: u32, index: usize) -> Option<&mut Subtask> {
+        self.tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)?
+            .subtasks
+            .get_mut(index)
+    }
+
+    fn finish_subtask(
+        &mut self,
+        agent_id: u32,
+        status: SubtaskStatus,
+        summary: &str,
+        diff: Option<String>,
+    ) -> Option<u32> {
+        let (task_id, index) = self.subtask_for_agent(agent_id)?;
+        let subtask = self.subtask_mut(task_id, index)?;
+        if subtask.status != SubtaskStatus::Running {
+            return None;
+        }
+        subtask.status = status;
+        subtask.summary = Some(summary.to_string());
+        subtask.diff = diff.filter(|d| !d.trim().is_empty());
+
+        let agent_status = if status == SubtaskStatus::Done {
+            AgentStatus::Idle
+        } else {
+            AgentStatus::Error
+        };
+        self.update_status(agent_id, agent_status);
+        self.set_task(agent_id, None);
+
+        self.task(task_id)
+            .filter(|t| t.is_finished())
+            .map(|t| t.id)
+    }
+}
+
+/// The process-wide registry shared by all agent panes.
+pub fn shared_registry() -> &'static Mutex<AgentRegistry> {
+    static REGISTRY: OnceLock<Mutex<AgentRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(AgentRegistry::new()))
 }
 
 impl Default for AgentRegistry {
@@ -321,6 +580,465 @@ impl Default for AgentRegistry {
     }
 }
 
+// ─── Fan-out Tasks ──────────────────────────────────────────────────────────
+
+/// Maximum bytes of diff kept per subtask.
+const MAX_DIFF_BYTES: usize = 32 * 1024;
+
+/// Progress of a single subtask.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtaskStatus {
+    /// Waiting for an agent.
+    Pending,
+    /// An agent is working on it.
+    Running,
+    /// Finished successfully.
+    Done,
+    /// The agent reported an error.
+    Failed,
+}
+
+impl SubtaskStatus {
+    /// ANSI-colored indicator for dashboard rendering.
+    pub fn indicator(&self) -> &'static str {
+        match self {
+            Self::Pending => "\x1b[2m○\x1b[0m",
+            Self::Running => "\x1b[33m◉\x1b[0m",
+            Self::Done => "\x1b[32m✓\x1b[0m",
+            Self::Failed => "\x1b[31m✗\x1b[0m",
+        }
+    }
+
+    /// Whether the subtask has reached a final state.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Done | Self::Failed)
+    }
+}
+
+/// Where a subtask's agent does its work.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Workspace {
+    /// A dedicated git worktree on its own branch, created from `base`.
+    Worktree {
+        path: PathBuf,
+        branch: String,
+        base: String,
+    },
+    /// A plain working directory (possibly shared with other agents).
+    Dir(PathBuf),
+}
+
+impl Workspace {
+    /// Directory the agent runs in.
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Worktree { path, .. } | Self::Dir(path) => path,
+        }
+    }
+
+    /// Short label for display (branch name or directory).
+    pub fn label(&self) -> String {
+        match self {
+            Self::Worktree { branch, .. } => branch.clone(),
+            Self::Dir(path) => path.display().to_string(),
+        }
+    }
+}
+
+/// What the user did with a finished subtask's workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// The branch was merged into the checkout (`/agent merge`).
+    Merged,
+    /// The worktree and branch were deleted (`/agent discard`).
+    Discarded,
+}
+
+impl Resolution {
+    /// Plain-text label.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Merged => "merged",
+            Self::Discarded => "discarded",
+        }
+    }
+}
+
+/// One piece of a fan-out task, handled by a single agent.
+#[derive(Debug, Clone)]
+pub struct Subtask {
+    /// Position within the parent task (0-based).
+    pub index: usize,
+    /// What the agent is asked to do.
+    pub description: String,
+    /// Agent working on this subtask, once spawned.
+    pub agent_id: Option<u32>,
+    /// Worktree or directory the agent runs in.
+    pub workspace: Option<Workspace>,
+    /// Current progress.
+    pub status: SubtaskStatus,
+    /// Agent's final summary (or error message on failure).
+    pub summary: Option<String>,
+    /// Changes made in the workspace, as a unified diff.
+    pub diff: Option<String>,
+    /// Turns started on this subtask; follow-up messages start new ones.
+    pub turn: u32,
+    /// Whether the workspace was merged or discarded, once it is.
+    pub resolution: Option<Resolution>,
+}
+
+/// A task split across several agents by `/agent fanout`.
+#[derive(Debug, Clone)]
+pub struct FanOutTask {
+    /// Unique numeric identifier.
+    pub id: u32,
+    /// The original request, as typed by the user.
+    pub prompt: String,
+    /// Pane that started the task and receives the results.
+    pub origin_pane: Option<usize>,
+    /// The subtasks, in the order given.
+    pub subtasks: Vec<Subtask>,
+    /// When the task was started.
+    pub created_at: DateTime<Utc>,
+    /// Problems hit while setting up workspaces.
+    pub warnings: Vec<String>,
+    /// Whether the aggregated results were delivered to the origin pane.
+    reported: bool,
+}
+
+impl FanOutTask {
+    /// Number of finished subtasks and the total.
+    pub fn progress(&self) -> (usize, usize) {
+        let done = self
+            .subtasks
+            .iter()
+            .filter(|s| s.status.is_finished())
+            .count();
+        (done, self.subtasks.len())
+    }
+
+    /// Whether every subtask has finished (successfully or not).
+    pub fn is_finished(&self) -> bool {
+        self.subtasks.iter().all(|s| s.status.is_finished())
+    }
+
+    /// Prompt sent to the agent handling subtask `index`.
+    pub fn subtask_prompt(&self, index: usize) -> Option<String> {
+        let subtask = self.subtasks.get(index)?;
+        let mut prompt = format!(
+            "You are one of {} agents working on a shared task.\n\n\
+             Overall task: {}\n\n\
+             Your part ({}/{}): {}\n",
+            self.subtasks.len(),
+            self.prompt,
+            index + 1,
+            self.subtasks.len(),
+            subtask.description,
+        );
+        match &subtask.workspace {
+            Some(Workspace::Worktree { path, branch, .. }) => prompt.push_str(&format!(
+                "\nWork only inside {} and use absolute paths under it. \
+                 Your changes stay on branch `{branch}` until the user merges \
+                 or discards them.\n",
+                path.display()
+            )),
+            Some(Workspace::Dir(path)) => prompt.push_str(&format!(
+                "\nWork only inside {} and use absolute paths under it.\n",
+                path.display()
+            )),
+            None => {}
+        }
+        prompt.push_str("\nWhen finished, reply with a short summary of what you changed.");
+        Some(prompt)
+    }
+}
+
+/// Split fan-out input into subtasks.
+///
+/// Accepts one subtask per line (bullets and `1.` numbering are stripped) or,
+/// on a single line, subtasks separated by `;`.
+pub fn split_subtasks(input: &str) -> Vec<String> {
+    let lines: Vec<&str> = input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let parts: Vec<&str> = if lines.len() > 1 {
+        lines
+    } else {
+        input.split(';').collect()
+    };
+
+    parts
+        .into_iter()
+        .map(strip_list_marker)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Strip a leading `-`, `*`, `•` or `1.` / `1)` list marker.
+fn strip_list_marker(s: &str) -> &str {
+    let s = s.trim();
+    if let Some(rest) = s
+        .strip_prefix("- ")
+        .or_else(|| s.strip_prefix("* "))
+        .or_else(|| s.strip_prefix("• "))
+    {
+        return rest.trim();
+    }
+    let digits = s.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        let rest = &s[digits..];
+        if let Some(rest) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return rest.trim();
+        }
+    }
+    s
+}
+
+/// Branch name used for a subtask's worktree.
+pub fn worktree_branch(task_id: u32, index: usize) -> String {
+    format!("elwood/task-{task_id}-{}", index + 1)
+}
+
+/// Worktree location for a subtask: a sibling `<repo>.elwood/` directory so
+/// worktrees never land inside the repository itself.
+pub fn worktree_path(repo_root: &Path, task_id: u32, index: usize) -> PathBuf {
+    let name = repo_root
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "repo".to_string());
+    let parent = repo_root.parent().unwrap_or(repo_root);
+    parent
+        .join(format!("{name}.elwood"))
+        .join(format!("task-{task_id}-{}", index + 1))
+}
+
+/// Prepare the workspace for a subtask.
+///
+/// With `use_worktree`, creates a git worktree on a fresh branch off `HEAD`.
+/// Falls back to `cwd` when `cwd` isn't inside a git repository.
+pub fn prepare_workspace(
+    cwd: &Path,
+    task_id: u32,
+    index: usize,
+    use_worktree: bool,
+) -> anyhow::Result<Workspace> {
+    if !use_worktree {
+        return Ok(Workspace::Dir(cwd.to_path_buf()));
+    }
+    let Some(root) = git_output(cwd, &["rev-parse", "--show-toplevel"]) else {
+        return Ok(Workspace::Dir(cwd.to_path_buf()));
+    };
+    let root = PathBuf::from(root.trim());
+    let path = worktree_path(&root, task_id, index);
+    let branch = worktree_branch(task_id, index);
+    let base = git_output(&root, &["rev-parse", "HEAD"])
+        .ok_or_else(|| anyhow::anyhow!("{} has no commits yet", root.display()))?
+        .trim()
+        .to_string();
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(&root)
+        .args(["worktree", "add", "-b", &branch])
+        .arg(&path)
+        .arg("HEAD")
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git worktree add failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(Workspace::Worktree { path, branch, base })
+}
+
+/// Commit everything left in a subtask's worktree to its branch, merge the
+/// branch into the checkout at `target`, then remove the worktree.
+///
+/// On a merge conflict git leaves the merge in progress in `target` for the
+/// user to resolve, and the worktree and branch are kept. Plain directories
+/// already hold their changes, so there is nothing to merge.
+pub fn merge_workspace(workspace: &Workspace, target: &Path, message: &str) -> anyhow::Result<()> {
+    let Workspace::Worktree { path, branch, .. } = workspace else {
+        return Ok(());
+    };
+    git_run(path, &["add", "--all"])?;
+    let pending = git_output(path, &["status", "--porcelain"]).unwrap_or_default();
+    if !pending.trim().is_empty() {
+        git_run(path, &["commit", "--quiet", "-m", message])?;
+    }
+    git_run(target, &["merge", "--no-edit", branch])?;
+    remove_workspace(workspace)
+}
+
+/// Delete a subtask's worktree and its branch (`/agent discard`, or after
+/// `/agent merge`).
+///
+/// Plain directories are left alone.
+pub fn remove_workspace(workspace: &Workspace) -> anyhow::Result<()> {
+    let Workspace::Worktree { path, branch, .. } = workspace else {
+        return Ok(());
+    };
+    let common_dir = git_output(
+        path,
+        &["rev-parse", "--path-format=absolute", "--git-common-dir"],
+    )
+    .ok_or_else(|| anyhow::anyhow!("{} is not a git worktree", path.display()))?;
+    let common_dir = PathBuf::from(common_dir.trim());
+
+    let remove = Command::new("git")
+        .arg("--git-dir")
+        .arg(&common_dir)
+        .args(["worktree", "remove", "--force"])
+        .arg(path)
+        .output()?;
+    if !remove.status.success() {
+        anyhow::bail!(
+            "git worktree remove failed: {}",
+            String::from_utf8_lossy(&remove.stderr).trim()
+        );
+    }
+    let delete = Command::new("git")
+        .arg("--git-dir")
+        .arg(&common_dir)
+        .args(["branch", "-D", branch])
+        .output()?;
+    if !delete.status.success() {
+        anyhow::bail!(
+            "git branch -D failed: {}",
+            String::from_utf8_lossy(&delete.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Collect the changes made in a workspace as a unified diff: against the
+/// commit a worktree was created from (so the agent's commits are included),
+/// or against `HEAD` for a plain directory.
+///
+/// Untracked files are listed after the diff. Returns `None` when there are
+/// no changes or the directory is not a git checkout.
+pub fn collect_diff(workspace: &Workspace) -> Option<String> {
+    let dir = workspace.path();
+    let base = match workspace {
+        Workspace::Worktree { base, .. } => base.as_str(),
+        Workspace::Dir(_) => "HEAD",
+    };
+    let mut diff = git_output(dir, &["diff", base])?;
+    let untracked = git_output(dir, &["ls-files", "--others", "--exclude-standard"])
+        .unwrap_or_default();
+    if !untracked.trim().is_empty() {
+        diff.push_str("\n# Untracked files:\n");
+        for file in untracked.lines() {
+            diff.push_str(&format!("#   {file}\n"));
+        }
+    }
+    if diff.trim().is_empty() {
+        return None;
+    }
+    if diff.len() > MAX_DIFF_BYTES {
+        let mut end = MAX_DIFF_BYTES;
+        while !diff.is_char_boundary(end) {
+            end -= 1;
+        }
+        diff.truncate(end);
+        diff.push_str("\n... (diff truncated)\n");
+    }
+    Some(diff)
+}
+
+/// Run a git command in `dir`, failing with its stderr.
+fn git_run(dir: &Path, args: &[&str]) -> anyhow::Result<()> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Run a git command in `dir`, returning stdout on success.
+fn git_output(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Combine the results of a finished fan-out task into a Markdown report.
+pub fn aggregate_results(task: &FanOutTask) -> String {
+    let (done, total) = task.progress();
+    let failed = task
+        .subtasks
+        .iter()
+        .filter(|s| s.status == SubtaskStatus::Failed)
+        .count();
+
+    let mut out = format!("## Task #{}: {}\n\n", task.id, task.prompt);
+    out.push_str(&format!("{done}/{total} subtasks finished"));
+    if failed > 0 {
+        out.push_str(&format!(", {failed} failed"));
+    }
+    out.push('\n');
+    for warning in &task.warnings {
+        out.push_str(&format!("\n> {warning}\n"));
+    }
+
+    for subtask in &task.subtasks {
+        let state = match subtask.status {
+            SubtaskStatus::Done => "done",
+            SubtaskStatus::Failed => "failed",
+            SubtaskStatus::Running => "running",
+            SubtaskStatus::Pending => "pending",
+        };
+        out.push_str(&format!(
+            "\n### {}. {} ({state})\n",
+            subtask.index + 1,
+            subtask.description
+        ));
+        if let Some(ws) = &subtask.workspace {
+            match subtask.resolution {
+                Some(resolution) => out.push_str(&format!(
+                    "Workspace: `{}` ({})\n",
+                    ws.label(),
+                    resolution.label()
+                )),
+                None => out.push_str(&format!("Workspace: `{}`\n", ws.label())),
+            }
+        }
+        if let Some(summary) = &subtask.summary {
+            out.push_str(&format!("\n{}\n", summary.trim()));
+        }
+        if let Some(diff) = &subtask.diff {
+            out.push_str(&format!("\n```diff\n{}\n```\n", diff.trim_end()));
+        }
+    }
+    let kept = task
+        .subtasks
+        .iter()
+        .any(|s| s.resolution.is_none() && matches!(s.workspace, Some(Workspace::Worktree { .. })));
+    if kept {
+        out.push_str(&format!(
+            "\nWorktrees are kept until `/agent merge {id}` or `/agent discard {id}` \
+             (add `.<n>` for one subtask).\n",
+            id = task.id
+        ));
+    }
+    out
+}
+
 // ─── Dashboard Rendering ────────────────────────────────────────────────────
 
 /// Maximum characters for task description in dashboard display.
@@ -333,7 +1051,7 @@ const TASK_TRUNCATE_LEN: usize = 40;
 /// and uptime.
 pub fn render_dashboard(registry: &AgentRegistry) -> String {
     let agents = registry.list();
-    if agents.is_empty() {
+    if agents.is_empty() && registry.tasks().is_empty() {
         return "\x1b[2mNo agents running. Use /agent spawn to create one.\x1b[0m\n".to_string();
     }
 
@@ -400,6 +1118,33 @@ pub fn render_dashboard(registry: &AgentRegistry) -> String {
         cost_str,
     ));
 
+    // Fan-out tasks: progress bar plus one line per subtask
+    for task in registry.tasks() {
+        let (done, total) = task.progress();
+        let width = 20;
+        let filled = (done * width).checked_div(total).unwrap_or(0);
+        out.push_str(&format!(
+            "\n  \x1b[1mTask #{}\x1b[0m {}\n  \x1b[32m{}\x1b[2m{}\x1b[0m {done}/{total}\n",
+            task.id,
+            truncate_str(&task.prompt, TASK_TRUNCATE_LEN),
+            "█".repeat(filled),
+            "░".repeat(width - filled),
+        ));
+        for subtask in &task.subtasks {
+            let agent = subtask
+                .agent_id
+                .and_then(|id| registry.get(id))
+                .map(|a| a.name.as_str())
+                .unwrap_or("-");
+            out.push_str(&format!(
+                "    {} {:<14} {}\n",
+                subtask.status.indicator(),
+                agent,
+                truncate_str(&subtask.description, TASK_TRUNCATE_LEN),
+            ));
+        }
+    }
+
     out
 }
 
@@ -433,6 +1178,24 @@ pub enum AgentCommand {
     Focus { target: String },
     /// Send a message to an agent: `/tell <name> <message>`.
     Tell { target: String, message: String },
+    /// Split a task across agents: `/agent fanout [--no-worktree] <subtasks>`.
+    FanOut {
+        prompt: String,
+        subtasks: Vec<String>,
+        worktrees: bool,
+    },
+    /// Show aggregated results: `/agent results <task-id>`.
+    Results { task_id: u32 },
+    /// Merge subtask branches into the checkout: `/agent merge <task-id>[.<n>]`.
+    Merge {
+        task_id: u32,
+        subtask: Option<usize>,
+    },
+    /// Delete subtask worktrees and branches: `/agent discard <task-id>[.<n>]`.
+    Discard {
+        task_id: u32,
+        subtask: Option<usize>,
+    },
     /// Show help for agent commands.
     Help,
 }
@@ -472,11 +1235,56 @@ pub fn parse_agent_command(args: &str) -> Option<AgentCommand> {
                 })
             }
         }
+        "fanout" | "fan-out" | "split" => Some(parse_fanout_args(rest)),
+        "results" | "collect" => match rest.trim_start_matches('#').parse() {
+            Ok(task_id) => Some(AgentCommand::Results { task_id }),
+            Err(_) => Some(AgentCommand::Help),
+        },
+        "merge" => Some(match parse_subtask_ref(rest) {
+            Some((task_id, subtask)) => AgentCommand::Merge { task_id, subtask },
+            None => AgentCommand::Help,
+        }),
+        "discard" => Some(match parse_subtask_ref(rest) {
+            Some((task_id, subtask)) => AgentCommand::Discard { task_id, subtask },
+            None => AgentCommand::Help,
+        }),
         "help" => Some(AgentCommand::Help),
         _ => None,
     }
 }
 
+/// Parse `<task-id>` or `<task-id>.<n>` (1-based subtask) into the task ID
+/// and 0-based subtask index.
+fn parse_subtask_ref(args: &str) -> Option<(u32, Option<usize>)> {
+    let args = args.trim().trim_start_matches('#');
+    match args.split_once('.') {
+        Some((task, n)) => {
+            let n: usize = n.parse().ok()?;
+            Some((task.parse().ok()?, Some(n.checked_sub(1)?)))
+        }
+        None => Some((args.parse().ok()?, None)),
+    }
+}
+
+/// Parse fan-out arguments: `[--no-worktree] <subtasks>`.
+///
+/// Needs at least two subtasks; otherwise returns `Help`.
+fn parse_fanout_args(args: &str) -> AgentCommand {
+    let (worktrees, rest) = match args.strip_prefix("--no-worktree") {
+        Some(rest) => (false, rest.trim()),
+        None => (true, args),
+    };
+    let subtasks = split_subtasks(rest);
+    if subtasks.len() < 2 {
+        return AgentCommand::Help;
+    }
+    AgentCommand::FanOut {
+        prompt: rest.lines().next().unwrap_or(rest).trim().to_string(),
+        subtasks,
+        worktrees,
+    }
+}
+
 /// Parse `/tell <target> <message>`.
 ///
 /// Returns `None` if the input doesn't have both a target and a message.
@@ -530,11 +1338,16 @@ Agent commands:\n\
   /agent kill <name|id>               Terminate an agent\n\
   /agent focus <name|id>              Switch to agent's pane\n\
   /tell <name> <message>              Send message to an agent\n\
+  /agent fanout <a>; <b>              Split a task across agents (--no-worktree)\n\
+  /agent results <task-id>            Show aggregated fan-out results\n\
+  /agent merge <task-id>[.<n>]        Merge subtask branches into this checkout\n\
+  /agent discard <task-id>[.<n>]      Delete subtask worktrees and branches\n\
 \n\
 Examples:\n\
   /agent spawn backend --model gemini-2.5-flash\n\
   /agent spawn                        (auto-named, default model)\n\
   /tell backend write tests for auth\n\
+  /agent fanout add login endpoint; write auth tests\n\
   /agent kill backend"
         .to_string()
 }
@@ -1249,6 +2062,264 @@ mod tests {
         assert!(help.contains("/tell"));
     }
 
+    // ── Fan-out ─────────────────────────────────────────────────────────
+
+    fn fanout_registry() -> (AgentRegistry, u32, u32, u32) {
+        let mut reg = AgentRegistry::new();
+        let task = reg.start_fanout(
+            "auth",
+            vec!["add endpoint".into(), "write tests".into()],
+            Some(7),
+        );
+        let a = reg.register(None, "m");
+        let b = reg.register(None, "m");
+        reg.assign_subtask(task, 0, a, Workspace::Dir(PathBuf::from("/tmp")));
+        reg.assign_subtask(task, 1, b, Workspace::Dir(PathBuf::from("/tmp")));
+        (reg, task, a, b)
+    }
+
+    #[test]
+    fn test_split_subtasks_semicolons() {
+        assert_eq!(
+            split_subtasks("add endpoint; write tests ;; docs"),
+            vec!["add endpoint", "write tests", "docs"]
+        );
+    }
+
+    #[test]
+    fn test_split_subtasks_list_lines() {
+        let input = "- add endpoint\n2. write tests\n\n* update docs; changelog";
+        assert_eq!(
+            split_subtasks(input),
+            vec!["add endpoint", "write tests", "update docs; changelog"]
+        );
+    }
+
+    #[test]
+    fn test_parse_fanout() {
+        assert_eq!(
+            parse_agent_command("fanout --no-worktree lint; test"),
+            Some(AgentCommand::FanOut {
+                prompt: "lint; test".to_string(),
+                subtasks: vec!["lint".to_string(), "test".to_string()],
+                worktrees: false,
+            })
+        );
+        // A single subtask isn't worth fanning out
+        assert_eq!(parse_agent_command("fanout lint"), Some(AgentCommand::Help));
+    }
+
+    #[test]
+    fn test_parse_results() {
+        assert_eq!(
+            parse_agent_command("results #3"),
+            Some(AgentCommand::Results { task_id: 3 })
+        );
+        assert_eq!(parse_agent_command("results x"), Some(AgentCommand::Help));
+    }
+
+    #[test]
+    fn test_assign_subtask_updates_agent() {
+        let (reg, task, a, _) = fanout_registry();
+        let agent = reg.get(a).unwrap();
+        assert_eq!(agent.status, AgentStatus::Working);
+        assert_eq!(agent.current_task.as_deref(), Some("add endpoint"));
+        assert_eq!(reg.subtask_for_agent(a), Some((task, 0)));
+        assert_eq!(
+            reg.workspace_for_agent(a),
+            Some(&Workspace::Dir(PathBuf::from("/tmp")))
+        );
+        assert!(reg.workspace_for_agent(99).is_none());
+        assert_eq!(reg.task(task).unwrap().progress(), (0, 2));
+    }
+
+    #[test]
+    fn test_complete_subtasks_finishes_task() {
+        let (mut reg, task, a, b) = fanout_registry();
+        assert_eq!(reg.complete_subtask(a, "added", Some("+x".into())), None);
+        assert!(reg.take_finished(7).is_empty());
+
+        assert_eq!(reg.fail_subtask(b, "boom"), Some(task));
+        assert_eq!(reg.get(b).unwrap().status, AgentStatus::Error);
+
+        // Completing twice is ignored
+        assert_eq!(reg.complete_subtask(a, "again", None), None);
+
+        assert!(reg.take_finished(8).is_empty());
+        let finished = reg.take_finished(7);
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].progress(), (2, 2));
+        assert!(reg.take_finished(7).is_empty(), "reported only once");
+    }
+
+    #[test]
+    fn test_empty_diff_is_dropped() {
+        let (mut reg, task, a, _) = fanout_registry();
+        reg.complete_subtask(a, "nothing to do", Some("  \n".into()));
+        assert!(reg.task(task).unwrap().subtasks[0].diff.is_none());
+    }
+
+    #[test]
+    fn test_subtask_prompt() {
+        let (reg, task, _, _) = fanout_registry();
+        let prompt = reg.task(task).unwrap().subtask_prompt(1).unwrap();
+        assert!(prompt.contains("Overall task: auth"));
+        assert!(prompt.contains("(2/2): write tests"));
+        assert!(prompt.contains("Work only inside /tmp"));
+        assert!(reg.task(task).unwrap().subtask_prompt(5).is_none());
+    }
+
+    #[test]
+    fn test_aggregate_results() {
+        let (mut reg, task, a, b) = fanout_registry();
+        reg.complete_subtask(a, "Added POST /login", Some("+fn login() {}\n".into()));
+        reg.fail_subtask(b, "tests did not compile");
+        let report = aggregate_results(reg.task(task).unwrap());
+        assert!(report.contains("## Task #1: auth"));
+        assert!(report.contains("2/2 subtasks finished, 1 failed"));
+        assert!(report.contains("### 1. add endpoint (done)"));
+        assert!(report.contains("```diff\n+fn login() {}\n```"));
+        assert!(report.contains("### 2. write tests (failed)"));
+        assert!(report.contains("tests did not compile"));
+    }
+
+    #[test]
+    fn test_dashboard_shows_task_progress() {
+        let (mut reg, _, a, _) = fanout_registry();
+        reg.complete_subtask(a, "done", None);
+        let out = render_dashboard(&reg);
+        assert!(out.contains("Task #1"));
+        assert!(out.contains("1/2"));
+        assert!(out.contains("write tests"));
+    }
+
+    #[test]
+    fn test_worktree_naming() {
+        let root = Path::new("/src/project");
+        assert_eq!(
+            worktree_path(root, 3, 0),
+            PathBuf::from("/src/project.elwood/task-3-1")
+        );
+        assert_eq!(worktree_branch(3, 0), "elwood/task-3-1");
+    }
+
+    #[test]
+    fn test_prepare_workspace_outside_git() {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = prepare_workspace(dir.path(), 1, 0, true).unwrap();
+        assert_eq!(ws, Workspace::Dir(dir.path().to_path_buf()));
+        assert!(collect_diff(&ws).is_none());
+        remove_workspace(&ws).unwrap();
+        assert!(dir.path().exists());
+    }
+
+    #[test]
+    fn test_worktree_lifecycle() {
+        let parent = tempfile::tempdir().unwrap();
+        let repo = parent.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {args:?} failed");
+        };
+        git(&["init", "-q"]);
+        std::fs::write(repo.join("a.txt"), "one\n").unwrap();
+        git(&["add", "a.txt"]);
+        git(&["commit", "-q", "-m", "init"]);
+
+        let ws = prepare_workspace(&repo, 7, 0, true).unwrap();
+        let Workspace::Worktree { path, branch, .. } = &ws else {
+            panic!("expected a worktree, got {ws:?}");
+        };
+        assert_eq!(branch, "elwood/task-7-1");
+
+        // Commits the agent makes are part of the diff too
+        std::fs::write(path.join("a.txt"), "two\n").unwrap();
+        let wt = |args: &[&str]| {
+            let mut cmd = Command::new("git");
+            cmd.arg("-C")
+                .arg(path)
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"]);
+            assert!(cmd.args(args).output().unwrap().status.success());
+        };
+        wt(&["commit", "-q", "-am", "two"]);
+        std::fs::write(path.join("b.txt"), "new\n").unwrap();
+        let diff = collect_diff(&ws).unwrap();
+        assert!(diff.contains("+two"));
+        assert!(diff.contains("#   b.txt"));
+
+        let ws2 = prepare_workspace(&repo, 7, 1, true).unwrap();
+        git(&["config", "user.name", "t"]);
+        git(&["config", "user.email", "t@t"]);
+        merge_workspace(&ws, &repo, "task 7.1").unwrap();
+        assert!(!path.exists());
+        assert_eq!(
+            std::fs::read_to_string(repo.join("a.txt")).unwrap(),
+            "two\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo.join("b.txt")).unwrap(),
+            "new\n"
+        );
+        assert!(git_output(&repo, &["branch", "--list", branch])
+            .unwrap()
+            .trim()
+            .is_empty());
+
+        remove_workspace(&ws2).unwrap();
+        assert!(!ws2.path().exists());
+    }
+
+    #[test]
+    fn test_parse_merge_and_discard() {
+        assert_eq!(
+            parse_agent_command("merge 3"),
+            Some(AgentCommand::Merge {
+                task_id: 3,
+                subtask: None
+            })
+        );
+        assert_eq!(
+            parse_agent_command("discard #3.2"),
+            Some(AgentCommand::Discard {
+                task_id: 3,
+                subtask: Some(1)
+            })
+        );
+        assert_eq!(parse_agent_command("merge 3.0"), Some(AgentCommand::Help));
+        assert_eq!(parse_agent_command("discard"), Some(AgentCommand::Help));
+    }
+
+    #[test]
+    fn test_follow_up_reopens_subtask() {
+        let (mut reg, task, a, b) = fanout_registry();
+        let turn = reg.subtask_turn(a).unwrap();
+        reg.complete_subtask(a, "first", None);
+        reg.complete_subtask(b, "done", None);
+        assert_eq!(reg.take_finished(7).len(), 1);
+
+        // A follow-up while the first turn's result is pending makes it stale
+        assert!(reg.reopen_subtask(a));
+        assert_eq!(reg.task(task).unwrap().progress(), (1, 2));
+        assert_eq!(reg.complete_turn(a, turn, "stale", None), None);
+        assert_eq!(reg.complete_turn(a, turn + 1, "second", None), Some(task));
+        let finished = reg.take_finished(7);
+        assert_eq!(finished[0].subtasks[0].summary.as_deref(), Some("second"));
+
+        // Resolved subtasks stay closed
+        assert!(reg.resolve_subtask(task, 0, Resolution::Merged));
+        assert!(!reg.reopen_subtask(a));
+        let report = aggregate_results(reg.task(task).unwrap());
+        assert!(!report.contains("Worktrees are kept"));
+    }
+
     // ── Default trait ───────────────────────────────────────────────────
 
     #[test]
@@ -14,9 +14,27 @@
 //! - English word ratio heuristics
 //!
 //! Performance target: <100 microseconds per classification.
-
+//!
+//! ## Learning from corrections
+//!
+//! When the user re-routes an input (forces `!command`, submits with
+//! Ctrl+Enter, or `/reroute`s the last input), [`NlClassifier::learn`] nudges
+//! per-user weights so the same input lands on the corrected side with
+//! auto-route confidence. Updates go mostly to a learned weight for the
+//! input's first word (an unknown tool sent to the shell) or its first two
+//! words (a known command's name used in prose), with a small share for the
+//! generic feature weights. The learned model lives in
+//! `~/.elwood/classifier.json`; corrected inputs pass through the
+//! [`Redactor`] before they are kept there, and words holding a secret are
+//! never learned. [`NlClassifier::explain`] reports every feature's
+//! contribution for the `/routing` debug view.
+
+use crate::redaction::Redactor;
 use crate::runtime::InputMode;
-use std::collections::HashSet;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 
 /// Result of classifying an input string.
 #[derive(Debug, Clone, PartialEq)]
@@ -30,6 +48,27 @@ pub struct Classification {
 /// Confidence threshold below which auto-detection is uncertain.
 const AUTO_DETECT_THRESHOLD: f32 = 0.3;
 
+/// Score magnitude that maps to confidence 1.0.
+const SCORE_SCALE: f32 = 8.0;
+
+/// Score a correction aims for on the corrected side: confidence 0.55,
+/// comfortably above the 0.5 the pane needs to auto-route to the shell.
+const LEARN_MARGIN: f32 = 4.4;
+
+/// Share of an update applied to each generic feature, relative to the
+/// word weight that takes the bulk of it.
+const FEATURE_LEARN_SHARE: f32 = 0.05;
+
+/// Share of an update applied to the first two words when the first word
+/// takes the bulk of it.
+const MINOR_TOKEN_SHARE: f32 = 0.25;
+
+/// Learned weights are clamped to this magnitude.
+const MAX_LEARNED_WEIGHT: f32 = 8.0;
+
+/// Corrections kept in the model for the debug view.
+const MAX_CORRECTIONS: usize = 200;
+
 /// Extracted features from an input string for classification.
 #[derive(Debug)]
 struct NlFeatures {
@@ -40,16 +79,168 @@ struct NlFeatures {
     has_prose_markers: bool,
     english_word_ratio: f32,
     word_count: usize,
+    /// Lowercased first word.
+    first_word: String,
+    /// Lowercased first two words, when there are at least two.
+    first_words: Option<String>,
+}
+
+/// Generic features with their built-in weights (positive = terminal).
+const FEATURES: &[(&str, f32)] = &[
+    ("known_command", 3.0),
+    ("shell_operators", 4.0),
+    ("flags", 3.0),
+    ("question_word", -4.0),
+    ("prose_markers", -3.0),
+    ("mostly_words", -2.0),
+    ("long_input", -1.5),
+];
+
+impl NlFeatures {
+    /// Names of the generic features present in this input.
+    fn active(&self) -> Vec<&'static str> {
+        let mut active = Vec::new();
+        if self.starts_with_command {
+            active.push("known_command");
+        }
+        if self.has_shell_operators {
+            active.push("shell_operators");
+        }
+        if self.has_flags {
+            active.push("flags");
+        }
+        if self.starts_with_question_word {
+            active.push("question_word");
+        }
+        // Only apply prose markers when input does NOT start with a known command,
+        // since commands like "cargo build" contain the word "build" which is a
+        // false-positive prose marker.
+        if self.has_prose_markers && !self.starts_with_command {
+            active.push("prose_markers");
+        }
+        if self.english_word_ratio > 0.7 {
+            active.push("mostly_words");
+        }
+        if self.word_count > 6 && !self.has_shell_operators {
+            active.push("long_input");
+        }
+        active
+    }
+}
+
+/// One feature's share of a classification score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureContribution {
+    /// Feature name (`first_word:<word>` / `first_words:<a b>` for learned
+    /// word weights).
+    pub name: String,
+    /// Built-in weight (positive = terminal).
+    pub base: f32,
+    /// Per-user adjustment learned from corrections.
+    pub learned: f32,
+}
+
+impl FeatureContribution {
+    pub fn total(&self) -> f32 {
+        self.base + self.learned
+    }
+}
+
+/// Why an input was classified the way it was.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    pub classification: Classification,
+    /// Raw score (positive = terminal).
+    pub score: f32,
+    /// Contributing features, largest magnitude first.
+    pub contributions: Vec<FeatureContribution>,
+    /// Set when a list or prefix override decided without scoring.
+    pub override_reason: Option<&'static str>,
+}
+
+impl Explanation {
+    fn overridden(mode: InputMode, confidence: f32, reason: &'static str) -> Self {
+        Self {
+            classification: Classification { mode, confidence },
+            score: 0.0,
+            contributions: Vec::new(),
+            override_reason: Some(reason),
+        }
+    }
+}
+
+/// A routing correction made by the user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Correction {
+    /// The input, with secrets redacted.
+    pub input: String,
+    /// True when the input was re-routed to the shell, false for the agent.
+    pub to_shell: bool,
+    /// Unix seconds.
+    pub timestamp: u64,
+}
+
+/// Per-user weights learned from corrections.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LearnedModel {
+    /// Adjustments to the generic feature weights, by feature name.
+    #[serde(default)]
+    pub feature_weights: BTreeMap<String, f32>,
+    /// Weights for the input's first word or first two words (lowercased).
+    #[serde(default)]
+    pub token_weights: BTreeMap<String, f32>,
+    /// Most recent corrections, oldest first.
+    #[serde(default)]
+    pub corrections: Vec<Correction>,
+}
+
+impl LearnedModel {
+    /// Read a model file; a missing file is an empty model.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => {
+                serde_json::from_str(&json).with_context(|| format!("parsing {}", path.display()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("reading {}", path.display())),
+        }
+    }
+
+    /// Write the model atomically (temp file, then rename).
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("writing {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("replacing {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// Default location of the learned model: `~/.elwood/classifier.json`.
+pub fn default_model_path() -> PathBuf {
+    dirs_next::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".elwood")
+        .join("classifier.json")
 }
 
 /// Heuristic natural language classifier.
 ///
 /// Pre-populated with ~200 common command prefixes for fast lookup.
-/// Supports user-maintained denylist (always Terminal) and allowlist (always Agent).
+/// Supports user-maintained denylist (always Terminal) and allowlist (always Agent),
+/// plus weights learned from the user's routing corrections.
 pub struct NlClassifier {
     command_prefixes: HashSet<&'static str>,
     denylist: HashSet<String>,
     allowlist: HashSet<String>,
+    model: LearnedModel,
+    /// Where [`learn`](Self::learn) persists the model, if anywhere.
+    model_path: Option<PathBuf>,
+    /// Keeps secrets in corrected inputs out of the model.
+    redactor: Redactor,
 }
 
 /// Question words that typically start natural language queries.
@@ -133,18 +324,39 @@ impl NlClassifier {
             command_prefixes: COMMAND_PREFIXES.iter().copied().collect(),
             denylist: HashSet::new(),
             allowlist: HashSet::new(),
+            model: LearnedModel::default(),
+            model_path: None,
+            redactor: Redactor::new(),
         }
     }
 
     /// Create a classifier with custom deny/allow lists.
     pub fn with_lists(denylist: HashSet<String>, allowlist: HashSet<String>) -> Self {
         Self {
-            command_prefixes: COMMAND_PREFIXES.iter().copied().collect(),
             denylist,
             allowlist,
+            ..Self::new()
         }
     }
 
+    /// Load learned weights from `path` and persist future corrections there,
+    /// redacted with the user's patterns from `~/.elwood/redaction.toml`.
+    ///
+    /// An unreadable model is reported and replaced by an empty one on the
+    /// next correction.
+    pub fn with_model_path(mut self, path: PathBuf) -> (Self, Option<String>) {
+        self.redactor = Redactor::from_default_config();
+        let error = match LearnedModel::load(&path) {
+            Ok(model) => {
+                self.model = model;
+                None
+            }
+            Err(e) => Some(format!("{e:#}")),
+        };
+        self.model_path = Some(path);
+        (self, error)
+    }
+
     /// Add an entry to the denylist (always classified as Terminal).
     pub fn add_to_denylist(&mut self, entry: String) {
         self.denylist.insert(entry);
@@ -160,77 +372,153 @@ impl NlClassifier {
     /// Returns a [`Classification`] with the detected mode and confidence.
     /// When confidence is below `AUTO_DETECT_THRESHOLD`, the result is uncertain.
     pub fn classify(&self, input: &str) -> Classification {
+        self.explain(input).classification
+    }
+
+    /// Classify `input` and report which features decided it.
+    pub fn explain(&self, input: &str) -> Explanation {
         let trimmed = input.trim();
         if trimmed.is_empty() {
-            return Classification {
-                mode: InputMode::Agent,
-                confidence: 0.0,
-            };
+            return Explanation::overridden(InputMode::Agent, 0.0, "empty input");
         }
 
         // 1. Check denylist/allowlist overrides (exact match)
         if self.denylist.contains(trimmed) {
-            return Classification {
-                mode: InputMode::Terminal,
-                confidence: 1.0,
-            };
+            return Explanation::overridden(InputMode::Terminal, 1.0, "denylist");
         }
         if self.allowlist.contains(trimmed) {
-            return Classification {
-                mode: InputMode::Agent,
-                confidence: 1.0,
-            };
+            return Explanation::overridden(InputMode::Agent, 1.0, "allowlist");
         }
 
         // 2. Prefix overrides
         if trimmed.starts_with('!') {
-            return Classification {
-                mode: InputMode::Terminal,
-                confidence: 1.0,
-            };
+            return Explanation::overridden(InputMode::Terminal, 1.0, "`!` prefix");
         }
 
         // 3. Extract features
         let features = extract_features(trimmed, &self.command_prefixes);
 
         // 4. Weighted scoring (positive = terminal, negative = agent)
-        let mut score: f32 = 0.0;
-
-        if features.starts_with_command {
-            score += 3.0;
-        }
-        if features.has_shell_operators {
-            score += 4.0;
-        }
-        if features.has_flags {
-            score += 3.0;
-        }
-
-        if features.starts_with_question_word {
-            score -= 4.0;
-        }
-        // Only apply prose markers when input does NOT start with a known command,
-        // since commands like "cargo build" contain the word "build" which is a
-        // false-positive prose marker.
-        if features.has_prose_markers && !features.starts_with_command {
-            score -= 3.0;
-        }
-        if features.english_word_ratio > 0.7 {
-            score -= 2.0;
-        }
-        if features.word_count > 6 && !features.has_shell_operators {
-            score -= 1.5;
+        let mut contributions: Vec<FeatureContribution> = features
+            .active()
+            .into_iter()
+            .map(|name| FeatureContribution {
+                name: name.to_string(),
+                base: base_weight(name),
+                learned: self.model.feature_weights.get(name).copied().unwrap_or(0.0),
+            })
+            .collect();
+        let tokens = [
+            ("first_word", Some(&features.first_word)),
+            ("first_words", features.first_words.as_ref()),
+        ];
+        for (kind, token) in tokens {
+            if let Some(&learned) = token.and_then(|t| self.model.token_weights.get(t)) {
+                contributions.push(FeatureContribution {
+                    name: format!("{kind}:{}", token.expect("weight found for token")),
+                    base: 0.0,
+                    learned,
+                });
+            }
         }
+        let score: f32 = contributions.iter().map(FeatureContribution::total).sum();
+        contributions.sort_by(|a, b| b.total().abs().total_cmp(&a.total().abs()));
 
         // 5. Convert score to confidence and mode
-        let confidence = (score.abs() / 8.0).min(1.0);
+        let confidence = (score.abs() / SCORE_SCALE).min(1.0);
         let mode = if score >= 0.0 {
             InputMode::Terminal
         } else {
             InputMode::Agent
         };
 
-        Classification { mode, confidence }
+        Explanation {
+            classification: Classification { mode, confidence },
+            score,
+            contributions,
+            override_reason: None,
+        }
+    }
+
+    /// Learn from the user routing `input` to `mode` against (or without)
+    /// the classifier's advice, then persist the model.
+    ///
+    /// Updates only when the input doesn't already score past the margin on
+    /// the corrected side. Returns `true` when the weights changed.
+    pub fn learn(&mut self, input: &str, mode: InputMode) -> anyhow::Result<bool> {
+        let trimmed = input.trim().trim_start_matches('!').trim();
+        if trimmed.is_empty() {
+            return Ok(false);
+        }
+        let to_shell = mode == InputMode::Terminal;
+        self.model.corrections.push(Correction {
+            input: self.redactor.redact(trimmed).redacted,
+            to_shell,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        });
+        let excess = self.model.corrections.len().saturating_sub(MAX_CORRECTIONS);
+        self.model.corrections.drain(..excess);
+
+        let sign = if to_shell { 1.0 } else { -1.0 };
+        let score = self.explain(trimmed).score;
+        let changed = sign * score < LEARN_MARGIN;
+        if changed {
+            // An unknown first word sent to the shell is most likely a tool
+            // name, so it takes the bulk of the update; otherwise the first
+            // two words do and the first word is left alone ("make sense of
+            // this" is prose, "make build" still isn't).
+            let features = extract_features(trimmed, &self.command_prefixes);
+            let active = features.active();
+            let (word_share, pair_share) = match &features.first_words {
+                Some(_) if !to_shell || features.starts_with_command => (0.0, 1.0),
+                Some(_) => (1.0, MINOR_TOKEN_SHARE),
+                None => (1.0, 0.0),
+            };
+            let mut updates: Vec<(String, f32)> = vec![(features.first_word, word_share)];
+            updates.extend(features.first_words.map(|pair| (pair, pair_share)));
+            updates.retain(|(token, share)| {
+                *share > 0.0 && !self.redactor.redact(token).has_secrets()
+            });
+
+            // Passive-aggressive step: move just far enough to reach the margin
+            let shares = word_share + pair_share + FEATURE_LEARN_SHARE * active.len() as f32;
+            let step = sign * (LEARN_MARGIN - sign * score) / shares;
+            for (token, share) in updates {
+                let weight = self.model.token_weights.entry(token).or_insert(0.0);
+                *weight = (*weight + step * share).clamp(-MAX_LEARNED_WEIGHT, MAX_LEARNED_WEIGHT);
+            }
+            for name in active {
+                let weight = self
+                    .model
+                    .feature_weights
+                    .entry(name.to_string())
+                    .or_insert(0.0);
+                *weight = (*weight + step * FEATURE_LEARN_SHARE)
+                    .clamp(-MAX_LEARNED_WEIGHT, MAX_LEARNED_WEIGHT);
+            }
+        }
+
+        if let Some(path) = &self.model_path {
+            self.model.save(path)?;
+        }
+        Ok(changed)
+    }
+
+    /// Forget everything learned from corrections.
+    pub fn reset_learning(&mut self) -> anyhow::Result<()> {
+        self.model = LearnedModel::default();
+        if let Some(path) = &self.model_path {
+            self.model.save(path)?;
+        }
+        Ok(())
+    }
+
+    /// The learned per-user model.
+    pub fn model(&self) -> &LearnedModel {
+        &self.model
     }
 
     /// Returns the auto-detect threshold.
@@ -245,6 +533,68 @@ impl Default for NlClassifier {
     }
 }
 
+fn base_weight(feature: &str) -> f32 {
+    FEATURES
+        .iter()
+        .find(|(name, _)| *name == feature)
+        .map_or(0.0, |(_, w)| *w)
+}
+
+/// Render an [`Explanation`] and the learned model summary for `/routing`.
+pub fn format_explanation(input: &str, explanation: &Explanation, model: &LearnedModel) -> String {
+    let c = &explanation.classification;
+    let route = match c.mode {
+        InputMode::Terminal => "shell",
+        InputMode::Agent => "agent",
+    };
+    let mut out = format!(
+        "Input: {input}\nRoute: {route} (confidence {:.2}, score {:+.2})\n",
+        c.confidence, explanation.score,
+    );
+    if let Some(reason) = explanation.override_reason {
+        out.push_str(&format!("Decided by: {reason}\n"));
+    } else if explanation.contributions.is_empty() {
+        out.push_str("No features matched\n");
+    } else {
+        out.push_str("\nFeatures (+ shell / - agent):\n");
+        for f in &explanation.contributions {
+            let learned = if f.learned != 0.0 {
+                format!("  (built-in {:+.2}, learned {:+.2})", f.base, f.learned)
+            } else {
+                String::new()
+            };
+            out.push_str(&format!("  {:<24} {:+6.2}{learned}\n", f.name, f.total()));
+        }
+    }
+
+    let shell = model.corrections.iter().filter(|c| c.to_shell).count();
+    out.push_str(&format!(
+        "\nLearned: {} word weight{}, {} correction{} ({shell} to shell, {} to agent)\n",
+        model.token_weights.len(),
+        if model.token_weights.len() == 1 {
+            ""
+        } else {
+            "s"
+        },
+        model.corrections.len(),
+        if model.corrections.len() == 1 {
+            ""
+        } else {
+            "s"
+        },
+        model.corrections.len() - shell,
+    ));
+    for c in model.corrections.iter().rev().take(5) {
+        let arrow = if c.to_shell {
+            "\u{2192} shell"
+        } else {
+            "\u{2192} agent"
+        };
+        out.push_str(&format!("  {arrow}  {}\n", c.input));
+    }
+    out
+}
+
 /// Extract classification features from input text.
 fn extract_features(input: &str, commands: &HashSet<&str>) -> NlFeatures {
     let words: Vec<&str> = input.split_whitespace().collect();
@@ -305,6 +655,9 @@ fn extract_features(input: &str, commands: &HashSet<&str>) -> NlFeatures {
         has_prose_markers,
         english_word_ratio,
         word_count,
+        first_word: first_word_lower,
+        first_words: (word_count >= 2)
+            .then(|| format!("{} {}", words[0], words[1]).to_ascii_lowercase()),
     }
 }
 
@@ -570,4 +923,123 @@ mod tests {
         assert_eq!(r.mode, InputMode::Agent);
         assert!(r.confidence > 0.3);
     }
+
+    // ── Learning from corrections ────────────────────────────────────
+
+    #[test]
+    fn learns_internal_tool_name() {
+        let mut c = classifier();
+        assert_eq!(
+            c.classify("deployctl rollout staging").mode,
+            InputMode::Agent
+        );
+
+        assert!(c
+            .learn("deployctl rollout staging", InputMode::Terminal)
+            .unwrap());
+        let r = c.classify("deployctl rollout staging");
+        assert_eq!(r.mode, InputMode::Terminal);
+        assert!(r.confidence >= 0.5);
+
+        // The tool name now leans shell in other invocations too
+        assert_eq!(c.classify("deployctl status").mode, InputMode::Terminal);
+        // Unrelated prose is unaffected
+        assert_eq!(
+            c.classify("what files are in this directory?").mode,
+            InputMode::Agent
+        );
+    }
+
+    #[test]
+    fn learns_prose_starting_with_command_name() {
+        let mut c = classifier();
+        let before = c.classify("make sense of these logs").mode;
+        assert_eq!(before, InputMode::Terminal);
+
+        c.learn("make sense of these logs", InputMode::Agent)
+            .unwrap();
+        let r = c.classify("make sense of these logs");
+        assert_eq!(r.mode, InputMode::Agent);
+        assert!(r.confidence >= 0.5);
+        assert_eq!(c.classify("make build").mode, InputMode::Terminal);
+        assert_eq!(c.classify("make -j8 test").mode, InputMode::Terminal);
+    }
+
+    #[test]
+    fn confident_input_is_not_retrained() {
+        let mut c = classifier();
+        assert!(!c.learn("ls -la | grep foo", InputMode::Terminal).unwrap());
+        assert!(c.model().token_weights.is_empty());
+        assert_eq!(c.model().corrections.len(), 1);
+    }
+
+    #[test]
+    fn corrections_keep_no_secrets() {
+        let mut c = classifier();
+        let token = format!("ghp_{}", "a".repeat(36));
+        c.learn(
+            &format!("gh auth login --with-token {token}"),
+            InputMode::Terminal,
+        )
+        .unwrap();
+        // A secret as the first word would otherwise become a learned token
+        assert!(c
+            .learn(&format!("{token} rollout staging"), InputMode::Terminal)
+            .unwrap());
+
+        let model = c.model();
+        assert!(model.corrections.iter().all(|c| !c.input.contains(&token)));
+        assert!(model.corrections[0]
+            .input
+            .contains("[REDACTED:github_token]"));
+        assert!(model.token_weights.keys().all(|t| !t.contains("ghp_")));
+    }
+
+    #[test]
+    fn explain_reports_deciding_features() {
+        let mut c = classifier();
+        let e = c.explain("cat file.txt | grep error");
+        assert_eq!(e.contributions[0].name, "shell_operators");
+        assert_eq!(e.score, 7.0);
+        assert!(e.override_reason.is_none());
+        assert_eq!(c.explain("!ls").override_reason, Some("`!` prefix"));
+
+        c.learn("deployctl rollout staging", InputMode::Terminal)
+            .unwrap();
+        let e = c.explain("deployctl rollout staging");
+        assert_eq!(e.contributions[0].name, "first_word:deployctl");
+        assert!(e.contributions[0].learned > 0.0);
+
+        let text = format_explanation("deployctl rollout staging", &e, c.model());
+        assert!(text.contains("Route: shell"));
+        assert!(text.contains("first_word:deployctl"));
+        assert!(text.contains("1 correction (1 to shell, 0 to agent)"));
+    }
+
+    #[test]
+    fn model_persists_and_resets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("classifier.json");
+
+        let (mut c, error) = classifier().with_model_path(path.clone());
+        assert!(error.is_none());
+        c.learn("deployctl rollout staging", InputMode::Terminal)
+            .unwrap();
+
+        let (reloaded, _) = classifier().with_model_path(path.clone());
+        assert_eq!(reloaded.model(), c.model());
+        assert_eq!(
+            reloaded.classify("deployctl rollout staging").mode,
+            InputMode::Terminal
+        );
+
+        c.reset_learning().unwrap();
+        let (reloaded, _) = classifier().with_model_path(path.clone());
+        assert!(reloaded.model().corrections.is_empty());
+
+        std::fs::write(&path, "not json").unwrap();
+        let (broken, error) = classifier().with_model_path(path);
+        assert!(error.unwrap().contains("classifier.json"));
+        assert!(broken.model().token_weights.is_empty());
+    }
 }
@@ -18,14 +18,39 @@
 //! ```
 //!
 //! Color by level: Info=blue, Success=green, Warning=yellow, Error=red.
+//!
+//! ## Desktop notifications
+//!
+//! When enabled, toasts at or above `desktop_level` are also sent to the OS
+//! through `wezterm-toast-notification` while the Elwood pane is unfocused.
+//! The pane only sees pane focus, so this also fires while the window is
+//! focused but another pane or tab is active; it is off by default. Desktop
+//! notifications stay up until dismissed rather than expiring with the toast:
+//!
+//! ```toml
+//! [notifications]
+//! desktop_when_pane_unfocused = true  # default: false
+//! desktop_level = "warning"   # info | success | warning | error
+//! ```
+//!
+//! ## History
+//!
+//! Every toast is kept in a [`NotificationHistory`] (newest first) that
+//! outlives its auto-dismiss, so its action can still be run from the
+//! history overlay (Alt+N, `/notifications`).
 
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
+use serde::{Deserialize, Serialize};
+
+use crate::theme;
+
 // ─── Toast Level ────────────────────────────────────────────────────────
 
-/// Severity/type of a toast notification.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Severity/type of a toast notification, ordered from least to most urgent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ToastLevel {
     /// Informational (blue).
     Info,
@@ -48,6 +73,16 @@ impl ToastLevel {
         }
     }
 
+    /// Display name for this level.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Info => "Info",
+            Self::Success => "Success",
+            Self::Warning => "Warning",
+            Self::Error => "Error",
+        }
+    }
+
     /// Single-character icon for this level.
     fn icon(self) -> &'static str {
         match self {
@@ -112,6 +147,56 @@ impl Toast {
     }
 }
 
+// ─── Configuration ──────────────────────────────────────────────────────
+
+/// `[notifications]` settings in `elwood.toml`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Send toasts to the OS while the pane is unfocused (default: off).
+    pub desktop_when_pane_unfocused: bool,
+    /// Lowest level sent to the OS.
+    pub desktop_level: ToastLevel,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            desktop_when_pane_unfocused: false,
+            desktop_level: ToastLevel::Warning,
+        }
+    }
+}
+
+/// Hands a toast to the OS notification center.
+pub type DesktopSink = Box<dyn Fn(&Toast) + Send>;
+
+/// Show `toast` as an OS notification. Runs on its own thread because some
+/// backends (D-Bus) block until the notification server answers.
+fn show_desktop_notification(toast: &Toast) {
+    let message = match &toast.detail {
+        Some(detail) => format!("{}\n{detail}", toast.message),
+        None => toast.message.clone(),
+    };
+    let notification = wezterm_toast_notification::ToastNotification {
+        title: format!("Elwood \u{2014} {}", toast.level.label()),
+        message,
+        url: match &toast.action {
+            Some(ToastAction::OpenUrl(url)) => Some(url.clone()),
+            _ => None,
+        },
+        // The in-pane toast lasts a few seconds; the desktop one is for a
+        // user who is looking elsewhere, so leave it until dismissed.
+        timeout: None,
+    };
+    let spawned = std::thread::Builder::new()
+        .name("elwood-notify".into())
+        .spawn(move || notification.show());
+    if let Err(e) = spawned {
+        log::warn!("Failed to show desktop notification: {e}");
+    }
+}
+
 // ─── Toast Manager ──────────────────────────────────────────────────────
 
 /// Maximum number of toasts visible at once.
@@ -121,10 +206,16 @@ const MAX_VISIBLE: usize = 3;
 ///
 /// Toasts are added to a queue and rendered from the front. Expired toasts
 /// are automatically removed on each `tick()`. At most `MAX_VISIBLE`
-/// toasts are shown at any time; the rest are queued.
+/// toasts are shown at any time; the rest are queued. Every toast is also
+/// recorded in the [`NotificationHistory`], and sent to the desktop when
+/// the pane is unfocused and the level is high enough.
 pub struct ToastManager {
     toasts: VecDeque<Toast>,
     next_id: usize,
+    history: NotificationHistory,
+    config: NotificationConfig,
+    pane_focused: bool,
+    desktop: DesktopSink,
 }
 
 impl ToastManager {
@@ -133,7 +224,58 @@ impl ToastManager {
         Self {
             toasts: VecDeque::new(),
             next_id: 0,
+            history: NotificationHistory::default(),
+            config: NotificationConfig::default(),
+            pane_focused: true,
+            desktop: Box::new(show_desktop_notification),
+        }
+    }
+
+    /// Use `config` for desktop notification routing.
+    pub fn with_config(mut self, config: NotificationConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Replace how desktop notifications are shown.
+    pub fn with_desktop_sink(mut self, sink: DesktopSink) -> Self {
+        self.desktop = sink;
+        self
+    }
+
+    /// Record whether the pane has focus; unfocused toasts go to the desktop.
+    ///
+    /// This is pane focus: the GUI unfocuses the active pane when its window
+    /// loses focus, and a pane when another one in the window is activated.
+    pub fn set_pane_focused(&mut self, focused: bool) {
+        self.pane_focused = focused;
+    }
+
+    /// Whether a toast of `level` pushed now would go to the desktop.
+    pub fn notifies_desktop(&self, level: ToastLevel) -> bool {
+        self.config.desktop_when_pane_unfocused
+            && !self.pane_focused
+            && level >= self.config.desktop_level
+    }
+
+    /// Every toast shown so far, newest first.
+    pub fn history(&self) -> &NotificationHistory {
+        &self.history
+    }
+
+    /// Mutable access to the history (for overlay navigation).
+    pub fn history_mut(&mut self) -> &mut NotificationHistory {
+        &mut self.history
+    }
+
+    fn enqueue(&mut self, toast: Toast) -> usize {
+        let id = toast.id;
+        if self.notifies_desktop(toast.level) {
+            (self.desktop)(&toast);
         }
+        self.history.record(toast.clone());
+        self.toasts.push_back(toast);
+        id
     }
 
     /// Push a new toast notification.
@@ -148,7 +290,7 @@ impl ToastManager {
     ) -> usize {
         let id = self.next_id;
         self.next_id += 1;
-        self.toasts.push_back(Toast {
+        self.enqueue(Toast {
             id,
             message: message.into(),
             detail: None,
@@ -156,8 +298,7 @@ impl ToastManager {
             action,
             created_at: Instant::now(),
             duration: duration.unwrap_or_else(|| level.default_duration()),
-        });
-        id
+        })
     }
 
     /// Push a toast with a detail line.
@@ -171,7 +312,7 @@ impl ToastManager {
     ) -> usize {
         let id = self.next_id;
         self.next_id += 1;
-        self.toasts.push_back(Toast {
+        self.enqueue(Toast {
             id,
             message: message.into(),
             detail: Some(detail.into()),
@@ -179,8 +320,7 @@ impl ToastManager {
             action,
             created_at: Instant::now(),
             duration: duration.unwrap_or_else(|| level.default_duration()),
-        });
-        id
+        })
     }
 
     /// Dismiss a specific toast by ID.
@@ -251,6 +391,190 @@ impl Default for ToastManager {
     }
 }
 
+// ─── Notification History ───────────────────────────────────────────────
+
+/// Toasts kept in the history; older ones are dropped.
+const HISTORY_LIMIT: usize = 100;
+/// Rows shown in the history overlay.
+const HISTORY_MAX_ROWS: usize = 14;
+const HISTORY_WIDTH: usize = 80;
+const HISTORY_TOP_OFFSET: usize = 3;
+
+/// Past toasts and the state of the history overlay.
+#[derive(Debug, Default)]
+pub struct NotificationHistory {
+    /// Newest first.
+    entries: VecDeque<Toast>,
+    open: bool,
+    selected: usize,
+}
+
+impl NotificationHistory {
+    fn record(&mut self, toast: Toast) {
+        self.entries.push_front(toast);
+        self.entries.truncate(HISTORY_LIMIT);
+        if self.open && self.selected + 1 < self.entries.len() {
+            // Keep the same toast selected as new ones arrive above it
+            self.selected += 1;
+        }
+    }
+
+    /// Past toasts, newest first.
+    pub fn entries(&self) -> impl Iterator<Item = &Toast> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Open the overlay with the newest toast selected.
+    pub fn open(&mut self) {
+        self.open = true;
+        self.selected = 0;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// The highlighted toast.
+    pub fn selected(&self) -> Option<&Toast> {
+        self.entries.get(self.selected)
+    }
+
+    /// Forget every past toast.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.selected = 0;
+    }
+
+    /// Render the history overlay (empty string when closed).
+    pub fn render(&self, screen_width: u16) -> String {
+        if !self.open {
+            return String::new();
+        }
+        let t = theme::active();
+        let r = t.reset();
+        let border = t.ansi_fg(t.accent);
+        let text_fg = t.ansi_fg(t.fg_primary);
+        let muted = t.ansi_fg(t.fg_muted);
+        let sel_bg = t.ansi_bg(t.bg_tertiary);
+        let bold = t.bold();
+
+        let width = HISTORY_WIDTH
+            .min((screen_width as usize).saturating_sub(4))
+            .max(20);
+        let left = (screen_width as usize).saturating_sub(width) / 2 + 1;
+        let inner = width.saturating_sub(2);
+        let mut row = HISTORY_TOP_OFFSET;
+
+        let mut out = String::with_capacity(4096);
+        out.push_str("\x1b[s\x1b[?25l");
+
+        let title = format!(" Notifications ({}) ", self.entries.len());
+        let fill = "\u{2500}".repeat(inner.saturating_sub(title.chars().count() + 1));
+        out.push_str(&format!(
+            "\x1b[{row};{left}H{border}\u{256D}\u{2500}{bold}{title}{r}{border}{fill}\u{256E}{r}"
+        ));
+        row += 1;
+
+        if self.entries.is_empty() {
+            let text = " No notifications yet";
+            let pad = inner.saturating_sub(text.len());
+            out.push_str(&format!(
+                "\x1b[{row};{left}H{border}\u{2502}{r}{muted}{text}{}{r}{border}\u{2502}{r}",
+                " ".repeat(pad)
+            ));
+            row += 1;
+        } else {
+            let start = self
+                .selected
+                .saturating_sub(HISTORY_MAX_ROWS / 2)
+                .min(self.entries.len().saturating_sub(HISTORY_MAX_ROWS));
+            for (i, toast) in self
+                .entries
+                .iter()
+                .enumerate()
+                .skip(start)
+                .take(HISTORY_MAX_ROWS)
+            {
+                let (cr, cg, cb) = toast.level.color();
+                let icon = format!("{}{}{r}", fg(cr, cg, cb), toast.level.icon());
+                let detail = toast
+                    .detail
+                    .as_deref()
+                    .map(|d| format!(" \u{2014} {d}"))
+                    .unwrap_or_default();
+                let act = if toast.action.is_some() {
+                    " [\u{23CE}]"
+                } else {
+                    ""
+                };
+                let text = format!(
+                    " {:>4}  {}{detail}{act}",
+                    format_age(toast.created_at.elapsed()),
+                    toast.message
+                );
+                // The icon is styled separately so truncation can't split its escape
+                let selected = i == self.selected;
+                let bg = if selected { sel_bg.as_str() } else { "" };
+                out.push_str(&format!(
+                    "\x1b[{row};{left}H{border}\u{2502}{r}{bg}{icon}{bg}"
+                ));
+                let shown: String = text.chars().take(inner.saturating_sub(2)).collect();
+                let pad = inner.saturating_sub(shown.chars().count() + 1);
+                out.push_str(&format!(
+                    "{text_fg}{shown}{}{r}{border}\u{2502}{r}",
+                    " ".repeat(pad)
+                ));
+                row += 1;
+            }
+        }
+
+        let bottom = "\u{2500}".repeat(inner);
+        out.push_str(&format!(
+            "\x1b[{row};{left}H{border}\u{2570}{bottom}\u{256F}{r}"
+        ));
+        row += 1;
+        out.push_str(&format!(
+            "\x1b[{row};{left}H{muted}  \u{2191}\u{2193} select  \u{23CE} run action  c clear  Esc close{r}"
+        ));
+
+        out.push_str("\x1b[?25h\x1b[u");
+        out
+    }
+}
+
+/// Compact age for the history list: `12s`, `5m`, `3h`, `2d`.
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86_399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86_400),
+    }
+}
+
 // ─── ANSI Rendering ─────────────────────────────────────────────────────
 
 /// True-color foreground escape.
@@ -402,6 +726,7 @@ fn visible_len(s: &str) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn test_push_and_visible() {
@@ -650,4 +975,138 @@ mod tests {
         assert_eq!(id1, 1);
         assert_eq!(id2, 2);
     }
+
+    // ── Desktop routing and history ─────────────────────────────────────
+
+    fn recording_manager(config: NotificationConfig) -> (ToastManager, Arc<Mutex<Vec<String>>>) {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&sent);
+        let mgr = ToastManager::new()
+            .with_config(config)
+            .with_desktop_sink(Box::new(move |t: &Toast| {
+                sink.lock().unwrap().push(t.message.clone())
+            }));
+        (mgr, sent)
+    }
+
+    #[test]
+    fn test_level_ordering() {
+        assert!(ToastLevel::Info < ToastLevel::Success);
+        assert!(ToastLevel::Success < ToastLevel::Warning);
+        assert!(ToastLevel::Warning < ToastLevel::Error);
+    }
+
+    #[test]
+    fn test_desktop_only_when_pane_unfocused() {
+        let (mut mgr, sent) = recording_manager(NotificationConfig {
+            desktop_when_pane_unfocused: true,
+            ..Default::default()
+        });
+        mgr.push("Focused", ToastLevel::Error, None, None);
+        assert!(sent.lock().unwrap().is_empty());
+
+        mgr.set_pane_focused(false);
+        mgr.push("Too quiet", ToastLevel::Info, None, None);
+        mgr.push_with_detail("Build failed", "3 errors", ToastLevel::Error, None, None);
+        assert_eq!(*sent.lock().unwrap(), vec!["Build failed"]);
+    }
+
+    #[test]
+    fn test_desktop_disabled_and_level_threshold() {
+        let (mut mgr, sent) = recording_manager(NotificationConfig {
+            desktop_when_pane_unfocused: true,
+            desktop_level: ToastLevel::Info,
+        });
+        mgr.set_pane_focused(false);
+        assert!(mgr.notifies_desktop(ToastLevel::Info));
+        mgr.push("Done", ToastLevel::Success, None, None);
+        assert_eq!(sent.lock().unwrap().len(), 1);
+
+        let (mut off, sent) = recording_manager(NotificationConfig {
+            desktop_when_pane_unfocused: false,
+            ..Default::default()
+        });
+        off.set_pane_focused(false);
+        off.push("Error", ToastLevel::Error, None, None);
+        assert!(sent.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_config_from_toml() {
+        let config: NotificationConfig = toml::from_str("desktop_level = \"error\"").unwrap();
+        assert!(!config.desktop_when_pane_unfocused);
+        assert_eq!(config.desktop_level, ToastLevel::Error);
+    }
+
+    #[test]
+    fn test_history_outlives_toasts() {
+        let mut mgr = ToastManager::new();
+        mgr.push(
+            "Fix it",
+            ToastLevel::Error,
+            Some(Duration::ZERO),
+            Some(ToastAction::RunCommand("cargo fix".into())),
+        );
+        mgr.push("Later", ToastLevel::Info, None, None);
+        std::thread::sleep(Duration::from_millis(1));
+        mgr.tick();
+        mgr.clear();
+
+        let history = mgr.history_mut();
+        assert_eq!(history.len(), 2);
+        history.open();
+        assert_eq!(history.selected().unwrap().message, "Later");
+        history.select_next();
+        history.select_next();
+        let selected = history.selected().unwrap();
+        assert!(matches!(&selected.action, Some(ToastAction::RunCommand(c)) if c == "cargo fix"));
+        history.select_prev();
+        assert_eq!(history.selected().unwrap().message, "Later");
+    }
+
+    #[test]
+    fn test_history_capped_and_keeps_selection() {
+        let mut mgr = ToastManager::new();
+        for i in 0..HISTORY_LIMIT + 5 {
+            mgr.push(format!("Toast {i}"), ToastLevel::Info, None, None);
+        }
+        assert_eq!(mgr.history().len(), HISTORY_LIMIT);
+        assert_eq!(mgr.history().entries().next().unwrap().message, "Toast 104");
+
+        mgr.history_mut().open();
+        mgr.history_mut().select_next();
+        mgr.push("Newest", ToastLevel::Info, None, None);
+        assert_eq!(mgr.history().selected().unwrap().message, "Toast 103");
+    }
+
+    #[test]
+    fn test_history_render() {
+        let mut history = NotificationHistory::default();
+        assert!(history.render(100).is_empty());
+        history.open();
+        assert!(history.render(100).contains("No notifications yet"));
+
+        let mut mgr = ToastManager::new();
+        mgr.push_with_detail(
+            "Error in `cargo build`",
+            "expected `;`",
+            ToastLevel::Error,
+            None,
+            Some(ToastAction::SendToAgent("fix".into())),
+        );
+        mgr.history_mut().open();
+        let out = mgr.history().render(100);
+        assert!(out.contains("Notifications (1)"));
+        assert!(out.contains("Error in `cargo build`"));
+        assert!(out.contains("expected `;`"));
+        assert!(out.contains("run action"));
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(5)), "5s");
+        assert_eq!(format_age(Duration::from_secs(150)), "2m");
+        assert_eq!(format_age(Duration::from_secs(7200)), "2h");
+        assert_eq!(format_age(Duration::from_secs(200_000)), "2d");
+    }
 }
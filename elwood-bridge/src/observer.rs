@@ -9,21 +9,28 @@
 //! (compiler errors, test failures, stack traces) for agent prompt injection.
 //! See [`ContentDetector`] for the pattern matching engine and
 //! [`PaneObserver::get_contextual_content`] for the high-level API.
+//! Languages beyond the hard-coded set are handled by pluggable detectors,
+//! see [`crate::error_detectors`].
 
 use mux::pane::PaneId;
 use mux::Mux;
 use parking_lot::RwLock;
 use regex::Regex;
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::fmt;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 use wezterm_term::StableRowIndex;
 
+use crate::error_detectors::DetectorSet;
+
 // ─── Error Detection Types ──────────────────────────────────────────────────
 
 /// The broad category of an error detected in terminal output.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ErrorType {
     /// Compile-time error (Rust, C/C++, TypeScript, Go).
     Compile,
@@ -56,7 +63,8 @@ impl fmt::Display for ErrorType {
 }
 
 /// Severity level for a detected error.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Severity {
     /// Informational (warnings, hints).
     Info,
@@ -92,6 +100,10 @@ pub struct ErrorDetection {
     pub suggested_fix: String,
     /// Source file extracted from the error, if any.
     pub source_file: Option<String>,
+    /// 1-based line in `source_file`, when the detector captured one.
+    pub line: Option<u32>,
+    /// 1-based column in `source_file`, when the detector captured one.
+    pub column: Option<u32>,
     /// Whether this fix can be applied automatically (without LLM).
     pub auto_fixable: bool,
 }
@@ -111,6 +123,8 @@ pub struct PaneSnapshot {
     pub cursor_row: i64,
     /// Terminal dimensions (cols, rows).
     pub dimensions: (usize, usize),
+    /// The pane's working directory, when known.
+    pub cwd: Option<PathBuf>,
 }
 
 /// The type of actionable content detected in a pane.
@@ -158,6 +172,10 @@ pub struct ContextualContent {
     pub timestamp: Instant,
 }
 
+/// Lines after an error searched for its source location before falling
+/// back to the whole output.
+const LOCATION_LOOKAHEAD: usize = 6;
+
 /// Pattern-matching engine for detecting actionable content in terminal output.
 ///
 /// Pre-compiles regexes on construction for efficient repeated use.
@@ -239,11 +257,22 @@ pub struct ContentDetector {
     // ── Exit codes ─────────────────────────────────────────────────────
     // Non-zero exit code from shell prompt
     exit_code: Regex,
+
+    // ── Pluggable detectors ────────────────────────────────────────────
+    // Config-driven detectors for other toolchains, tried before the above
+    plugins: DetectorSet,
 }
 
 impl ContentDetector {
-    /// Create a new detector with pre-compiled patterns.
+    /// Create a new detector with pre-compiled patterns and the built-in
+    /// pluggable detectors.
     pub fn new() -> Self {
+        Self::with_detectors(DetectorSet::builtin())
+    }
+
+    /// Create a detector that consults `plugins` in addition to the
+    /// hard-coded patterns (see [`DetectorSet::load_default`]).
+    pub fn with_detectors(plugins: DetectorSet) -> Self {
         Self {
             // ── Rust ────────────────────────────────────────────────
             rust_error: Regex::new(r"^error(\[E\d+\])?:").expect("valid regex"),
@@ -298,6 +327,8 @@ impl ContentDetector {
 
             // ── Exit codes ──────────────────────────────────────────
             exit_code: Regex::new(r"exit (?:code|status)[:\s]+(\d+)").expect("valid regex"),
+
+            plugins,
         }
     }
 
@@ -320,10 +351,42 @@ impl ContentDetector {
         if let Some(cc) = self.detect_exit_codes(pane_id, lines, timestamp) {
             results.push(cc);
         }
+        self.detect_plugin_content(pane_id, lines, timestamp, &mut results);
 
         results
     }
 
+    /// Map pluggable detector hits onto content types the hard-coded
+    /// patterns didn't already report.
+    fn detect_plugin_content(
+        &self,
+        pane_id: PaneId,
+        lines: &[String],
+        timestamp: Instant,
+        results: &mut Vec<ContextualContent>,
+    ) {
+        for m in self.plugins.detect(lines) {
+            let content_type = match m.detection.error_type {
+                ErrorType::Compile => ContentType::CompilerError,
+                ErrorType::Test => ContentType::TestFailure,
+                ErrorType::Runtime => ContentType::StackTrace,
+                _ => ContentType::CommandOutput,
+            };
+            if m.detection.severity == Severity::Info
+                || results.iter().any(|cc| cc.content_type == content_type)
+            {
+                continue;
+            }
+            results.push(ContextualContent {
+                pane_id,
+                content_type,
+                text: m.detection.message,
+                source_file: m.detection.source_file,
+                timestamp,
+            });
+        }
+    }
+
     /// Detect Rust/C/C++/TypeScript compiler errors.
     fn detect_compiler_errors(
         &self,
@@ -533,24 +596,44 @@ impl ContentDetector {
     pub fn detect_errors(&self, lines: &[String]) -> Vec<ErrorDetection> {
         let mut results = Vec::new();
 
-        // Helper to extract source file from nearby lines
-        let extract_source_file = |lines: &[String]| -> Option<String> {
+        // Pluggable detectors claim their lines (including continuations)
+        // first so the generic patterns below don't report them twice.
+        let mut plugin_matches = self.plugins.detect(lines).into_iter().peekable();
+
+        // Helper to extract the source location nearest an error: the lines
+        // right after it first (rustc's `-->`), then the whole output.
+        let find_location = |lines: &[String]| -> Option<(String, Option<u32>, Option<u32>)> {
             lines.iter().find_map(|line| {
                 let trimmed = line.trim();
                 if let Some(caps) = self.rust_location.captures(trimmed) {
-                    return Some(caps[1].to_string());
+                    return Some((caps[1].to_string(), caps[2].parse().ok(), caps[3].parse().ok()));
                 }
                 if let Some(caps) = self.file_line_col.captures(trimmed) {
                     let path = &caps[1];
                     if path.contains('/') || path.contains('\\') || path.contains('.') {
-                        return Some(path.to_string());
+                        return Some((path.to_string(), caps[2].parse().ok(), caps[3].parse().ok()));
                     }
                 }
                 None
             })
         };
+        let locate = |idx: usize| -> (Option<String>, Option<u32>, Option<u32>) {
+            let nearby = &lines[idx..lines.len().min(idx + LOCATION_LOOKAHEAD)];
+            match find_location(nearby).or_else(|| find_location(lines)) {
+                Some((file, line, column)) => (Some(file), line, column),
+                None => (None, None, None),
+            }
+        };
 
-        for line in lines {
+        let mut claimed_until = 0;
+        for (idx, line) in lines.iter().enumerate() {
+            if let Some(m) = plugin_matches.next_if(|m| m.lines.start == idx) {
+                claimed_until = m.lines.end;
+                results.push(m.detection);
+            }
+            if idx < claimed_until {
+                continue;
+            }
             let trimmed = line.trim();
 
             // ── Rust compiler errors ────────────────────────────────
@@ -568,12 +651,15 @@ impl ContentDetector {
                     "Press Ctrl+F to ask Elwood to fix this error".to_string()
                 };
 
+                let (source_file, line_no, column) = locate(idx);
                 results.push(ErrorDetection {
                     error_type: ErrorType::Compile,
                     severity: Severity::Error,
                     message: trimmed.to_string(),
                     suggested_fix: fix,
-                    source_file: extract_source_file(lines),
+                    source_file,
+                    line: line_no,
+                    column,
                     auto_fixable: is_cannot_find,
                 });
                 continue;
@@ -581,12 +667,15 @@ impl ContentDetector {
 
             // ── Rust warnings ───────────────────────────────────────
             if self.rust_warning.is_match(trimmed) {
+                let (source_file, line_no, column) = locate(idx);
                 results.push(ErrorDetection {
                     error_type: ErrorType::Compile,
                     severity: Severity::Info,
                     message: trimmed.to_string(),
                     suggested_fix: "cargo clippy --fix".to_string(),
-                    source_file: extract_source_file(lines),
+                    source_file,
+                    line: line_no,
+                    column,
                     auto_fixable: true,
                 });
                 continue;
@@ -594,46 +683,58 @@ impl ContentDetector {
 
             // ── Python errors ──────────────────────────────────────
             if self.python_syntax_error.is_match(trimmed) {
+                let (source_file, line_no, column) = locate(idx);
                 results.push(ErrorDetection {
                     error_type: ErrorType::Compile,
                     severity: Severity::Error,
                     message: trimmed.to_string(),
                     suggested_fix: "Fix the syntax error at the indicated line".to_string(),
-                    source_file: extract_source_file(lines),
+                    source_file,
+                    line: line_no,
+                    column,
                     auto_fixable: false,
                 });
                 continue;
             }
             if let Some(caps) = self.python_import_error.captures(trimmed) {
                 let module = caps.get(1).map(|m| m.as_str()).unwrap_or("unknown");
+                let (source_file, line_no, column) = locate(idx);
                 results.push(ErrorDetection {
                     error_type: ErrorType::NotFound,
                     severity: Severity::Error,
                     message: trimmed.to_string(),
                     suggested_fix: format!("pip install {module}"),
-                    source_file: extract_source_file(lines),
+                    source_file,
+                    line: line_no,
+                    column,
                     auto_fixable: true,
                 });
                 continue;
             }
             if self.python_runtime_error.is_match(trimmed) {
+                let (source_file, line_no, column) = locate(idx);
                 results.push(ErrorDetection {
                     error_type: ErrorType::Runtime,
                     severity: Severity::Error,
                     message: trimmed.to_string(),
                     suggested_fix: "Press Ctrl+F to ask Elwood to fix this error".to_string(),
-                    source_file: extract_source_file(lines),
+                    source_file,
+                    line: line_no,
+                    column,
                     auto_fixable: false,
                 });
                 continue;
             }
             if self.python_traceback.is_match(trimmed) {
+                let (source_file, line_no, column) = locate(idx);
                 results.push(ErrorDetection {
                     error_type: ErrorType::Runtime,
                     severity: Severity::Error,
                     message: trimmed.to_string(),
                     suggested_fix: "Press Ctrl+F to ask Elwood to fix this error".to_string(),
-                    source_file: extract_source_file(lines),
+                    source_file,
+                    line: line_no,
+                    column,
                     auto_fixable: false,
                 });
                 continue;
@@ -641,24 +742,30 @@ impl ContentDetector {
 
             // ── JavaScript/TypeScript errors ───────────────────────
             if self.js_error.is_match(trimmed) {
+                let (source_file, line_no, column) = locate(idx);
                 results.push(ErrorDetection {
                     error_type: ErrorType::Runtime,
                     severity: Severity::Error,
                     message: trimmed.to_string(),
                     suggested_fix: "Press Ctrl+F to ask Elwood to fix this error".to_string(),
-                    source_file: extract_source_file(lines),
+                    source_file,
+                    line: line_no,
+                    column,
                     auto_fixable: false,
                 });
                 continue;
             }
             if let Some(caps) = self.js_cannot_find_module.captures(trimmed) {
                 let module = caps.get(1).map(|m| m.as_str()).unwrap_or("unknown");
+                let (source_file, line_no, column) = locate(idx);
                 results.push(ErrorDetection {
                     error_type: ErrorType::NotFound,
                     severity: Severity::Error,
                     message: trimmed.to_string(),
                     suggested_fix: format!("npm install {module}"),
-                    source_file: extract_source_file(lines),
+                    source_file,
+                    line: line_no,
+                    column,
                     auto_fixable: true,
                 });
                 continue;
@@ -666,12 +773,15 @@ impl ContentDetector {
 
             // ── Go errors ──────────────────────────────────────────
             if self.go_error.is_match(trimmed) || self.go_syntax_error.is_match(trimmed) {
+                let (source_file, line_no, column) = locate(idx);
                 results.push(ErrorDetection {
                     error_type: ErrorType::Compile,
                     severity: Severity::Error,
                     message: trimmed.to_string(),
                     suggested_fix: "Press Ctrl+F to ask Elwood to fix this error".to_string(),
-                    source_file: extract_source_file(lines),
+                    source_file,
+                    line: line_no,
+                    column,
                     auto_fixable: false,
                 });
                 continue;
@@ -685,6 +795,8 @@ impl ContentDetector {
                     message: trimmed.to_string(),
                     suggested_fix: "Resolve merge conflicts, then git add and git commit".to_string(),
                     source_file: None,
+                    line: None,
+                    column: None,
                     auto_fixable: false,
                 });
                 continue;
@@ -696,6 +808,8 @@ impl ContentDetector {
                     message: trimmed.to_string(),
                     suggested_fix: "Press Ctrl+F to ask Elwood to diagnose the git error".to_string(),
                     source_file: None,
+                    line: None,
+                    column: None,
                     auto_fixable: false,
                 });
                 continue;
@@ -710,6 +824,8 @@ impl ContentDetector {
                         message: trimmed.to_string(),
                         suggested_fix: "Check file permissions or run with appropriate privileges".to_string(),
                         source_file: None,
+                        line: None,
+                        column: None,
                         auto_fixable: false,
                     });
                     continue;
@@ -724,6 +840,8 @@ impl ContentDetector {
                     message: trimmed.to_string(),
                     suggested_fix: "Check the file path exists".to_string(),
                     source_file: None,
+                    line: None,
+                    column: None,
                     auto_fixable: false,
                 });
                 continue;
@@ -735,6 +853,8 @@ impl ContentDetector {
                     message: trimmed.to_string(),
                     suggested_fix: "Install the missing command or check your PATH".to_string(),
                     source_file: None,
+                    line: None,
+                    column: None,
                     auto_fixable: false,
                 });
                 continue;
@@ -746,6 +866,8 @@ impl ContentDetector {
                     message: trimmed.to_string(),
                     suggested_fix: "Check the file path exists".to_string(),
                     source_file: None,
+                    line: None,
+                    column: None,
                     auto_fixable: false,
                 });
                 continue;
@@ -756,24 +878,30 @@ impl ContentDetector {
                 || self.test_fail_prefix.is_match(trimmed)
                 || self.assertion_failed.is_match(trimmed)
             {
+                let (source_file, line_no, column) = locate(idx);
                 results.push(ErrorDetection {
                     error_type: ErrorType::Test,
                     severity: Severity::Error,
                     message: trimmed.to_string(),
                     suggested_fix: "Press Ctrl+F to ask Elwood to fix the failing tests".to_string(),
-                    source_file: extract_source_file(lines),
+                    source_file,
+                    line: line_no,
+                    column,
                     auto_fixable: false,
                 });
                 continue;
             }
             // "FAILED" keyword (but not "0 failed")
             if self.test_failed.is_match(trimmed) && !self.zero_failed.is_match(trimmed) {
+                let (source_file, line_no, column) = locate(idx);
                 results.push(ErrorDetection {
                     error_type: ErrorType::Test,
                     severity: Severity::Error,
                     message: trimmed.to_string(),
                     suggested_fix: "Press Ctrl+F to ask Elwood to fix the failing tests".to_string(),
-                    source_file: extract_source_file(lines),
+                    source_file,
+                    line: line_no,
+                    column,
                     auto_fixable: false,
                 });
                 continue;
@@ -781,12 +909,15 @@ impl ContentDetector {
 
             // ── Rust panic ─────────────────────────────────────────
             if self.rust_panic.is_match(trimmed) {
+                let (source_file, line_no, column) = locate(idx);
                 results.push(ErrorDetection {
                     error_type: ErrorType::Runtime,
                     severity: Severity::Fatal,
                     message: trimmed.to_string(),
                     suggested_fix: "Press Ctrl+F to ask Elwood to fix the panic".to_string(),
-                    source_file: extract_source_file(lines),
+                    source_file,
+                    line: line_no,
+                    column,
                     auto_fixable: false,
                 });
                 continue;
@@ -794,12 +925,15 @@ impl ContentDetector {
 
             // ── file:line:col error (gcc, clang, TypeScript) ───────
             if self.file_line_col_error.is_match(trimmed) {
+                let (source_file, line_no, column) = locate(idx);
                 results.push(ErrorDetection {
                     error_type: ErrorType::Compile,
                     severity: Severity::Error,
                     message: trimmed.to_string(),
                     suggested_fix: "Press Ctrl+F to ask Elwood to fix this error".to_string(),
-                    source_file: extract_source_file(lines),
+                    source_file,
+                    line: line_no,
+                    column,
                     auto_fixable: false,
                 });
             }
@@ -885,6 +1019,7 @@ impl PaneObserver {
                                 title: pane.get_title(),
                                 cursor_row: cursor.y as i64,
                                 dimensions: (dims.cols, dims.viewport_rows),
+                                cwd: pane_cwd(&*pane),
                             };
 
                             cache.write().insert(pane_id, snapshot);
@@ -965,6 +1100,7 @@ impl PaneObserver {
             title: pane.get_title(),
             cursor_row: cursor.y as i64,
             dimensions: (dims.cols, dims.viewport_rows),
+            cwd: pane_cwd(&*pane),
         })
     }
 
@@ -1002,6 +1138,7 @@ impl PaneObserver {
                 title: pane.get_title(),
                 cursor_row: cursor.y as i64,
                 dimensions: (dims.cols, dims.viewport_rows),
+                cwd: pane_cwd(&*pane),
             });
         }
 
@@ -1137,6 +1274,12 @@ impl PaneObserver {
     }
 }
 
+/// Working directory of a pane as a local path.
+fn pane_cwd(pane: &dyn mux::pane::Pane) -> Option<PathBuf> {
+    pane.get_current_working_dir(mux::pane::CachePolicy::AllowStale)
+        .and_then(|url| url.to_file_path().ok())
+}
+
 /// Summary information about a pane.
 #[derive(Debug, Clone)]
 pub struct PaneInfo {
@@ -1807,6 +1950,48 @@ exit code: 1"#;
         assert_eq!(results[0].error_type, ErrorType::Compile);
     }
 
+    #[test]
+    fn detect_errors_plugin_detector_location() {
+        let results = detect_errors("Program.cs(10,5): error CS1002: ; expected [App.csproj]");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].error_type, ErrorType::Compile);
+        assert_eq!(results[0].source_file.as_deref(), Some("Program.cs"));
+        assert_eq!(results[0].line, Some(10));
+        assert_eq!(results[0].column, Some(5));
+    }
+
+    #[test]
+    fn detect_errors_plugin_claims_continuation_lines() {
+        // The boxed lines would otherwise trip the permission-denied pattern
+        let input = "╷\n│ Error: reading S3 bucket\n│ \n│   on main.tf line 4:\n│ AccessDenied: Permission denied\n╵\nerror: linker failed";
+        let results = detect_errors(input);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].error_type, ErrorType::General);
+        assert_eq!(results[0].source_file.as_deref(), Some("main.tf"));
+        assert_eq!(results[0].line, Some(4));
+        assert!(results[0].message.ends_with("Permission denied"));
+        assert_eq!(results[1].message, "error: linker failed");
+    }
+
+    #[test]
+    fn detects_plugin_compiler_error() {
+        let results = detect("** (CompileError) lib/app.ex:3: undefined function bar/0");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content_type, ContentType::CompilerError);
+        assert_eq!(results[0].source_file.as_deref(), Some("lib/app.ex"));
+    }
+
+    #[test]
+    fn detect_errors_location_nearest_error() {
+        let input = "error[E0308]: mismatched types\n  --> src/a.rs:3:5\nerror[E0425]: cannot find value `x`\n  --> src/b.rs:9:1";
+        let results = detect_errors(input);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].source_file.as_deref(), Some("src/a.rs"));
+        assert_eq!((results[0].line, results[0].column), (Some(3), Some(5)));
+        assert_eq!(results[1].source_file.as_deref(), Some("src/b.rs"));
+        assert_eq!(results[1].line, Some(9));
+    }
+
     #[test]
     fn detect_errors_clean_output_empty() {
         let results = detect_errors("   Compiling my-crate v0.1.0\n    Finished dev in 0.52s");
@@ -437,6 +437,27 @@ fn default_entries() -> Vec<PaletteEntry> {
             category: ActionCategory::Tool,
             command: "quick_fix".to_string(),
         },
+        PaletteEntry {
+            name: "Quickfix List".to_string(),
+            description: "Errors from all panes, by file".to_string(),
+            shortcut: Some("Alt+Q".to_string()),
+            category: ActionCategory::Navigation,
+            command: "quickfix".to_string(),
+        },
+        PaletteEntry {
+            name: "Notification History".to_string(),
+            description: "Past toasts and their actions".to_string(),
+            shortcut: Some("Alt+N".to_string()),
+            category: ActionCategory::Navigation,
+            command: "notifications".to_string(),
+        },
+        PaletteEntry {
+            name: "Search Sessions".to_string(),
+            description: "Find, resume or re-export past sessions".to_string(),
+            shortcut: None,
+            category: ActionCategory::Navigation,
+            command: "sessions".to_string(),
+        },
         PaletteEntry {
             name: "Previous Block".to_string(),
             description: "Navigate to previous block".to_string(),
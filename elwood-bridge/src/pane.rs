@@ -33,36 +33,62 @@
 //! naturally within the bounded area. Chrome updates (header, input, status)
 //! use cursor save/restore to avoid disturbing the scroll position.
 
-use crate::block::BlockManager;
-use crate::commands::{self, CommandResult};
-use crate::completions::CompletionEngine;
+use crate::autocorrect::{self, CommandCorrector, Correction};
+use crate::block::{self, BlockAction, BlockManager};
+use crate::block_store::{BlockStore, PersistedBlock, MAX_SESSIONS_PER_DIR};
+use crate::commands::{
+    self, AutocorrectAction, ClassifierAction, CommandResult, JobAction, NotificationsAction,
+    PredictionsAction, QuickfixAction, SessionsAction, VimToggleAction,
+};
+use crate::completion_spec::SpecSet;
+use crate::completions::{CompletionEngine, CompletionMenu};
 use crate::context;
 use crate::diff;
 use crate::lua_api::{self, LuaEventArg, LuaEventDispatcher};
+use crate::markdown;
+use crate::multi_agent::{self, AgentCommand, AgentStatus, Resolution, Workspace};
 use crate::file_browser::FileTree;
 use crate::fuzzy_finder::{self, FuzzyFinder, FileSource, SlashCommandSource, HistorySource, FuzzyAction};
-use crate::semantic_bridge::SemanticBridge;
+use crate::semantic_bridge::{BuildStatus, IndexWorker, SemanticBridge};
 use crate::diff_viewer::{DiffViewer, ReviewAction};
-use crate::editor::InputEditor;
+use crate::editor::{InputEditor, VimEffect};
+use crate::error_detectors::DetectorSet;
+use crate::external_editor;
 use crate::git_info;
 use crate::git_ui::{self, CommitView, StagingView};
 use crate::history_search::{HistoryRecord, HistorySearch};
-use crate::nl_classifier::NlClassifier;
+use crate::ide_bridge;
+use crate::inline_image::{self, ImageProtocol};
+use crate::jobs::{self, JobId, JobManager, JobStatus};
+use crate::launch_config::{
+    ApplyResult, LaunchCommandResult, PlannedPane, SplitAxis, TabPlan,
+};
+use crate::nl_classifier::{self, NlClassifier};
 use crate::notification::{self, ToastAction, ToastLevel, ToastManager};
 use crate::observer::{ContentDetector, ContentType, NextCommandSuggester, PaneObserver};
 use crate::palette::CommandPalette;
 use crate::plan_mode;
 use crate::plan_viewer::PlanViewer;
 use crate::suggestion_overlay::SuggestionManager;
-use crate::prediction_engine::{PredictionContext, PredictionEngine};
+use crate::prediction_engine::{self, PredictionContext, PredictionEngine};
+use crate::quickfix::{self, PaneErrors, QuickfixEntry, QuickfixList};
+use crate::recording_export::{self, ExportOptions, ExportPalette, ExportSummary};
+use crate::redaction::Redactor;
+use crate::replay::{self, ReplayFrame, ReplayPlayer};
 use crate::pty_inner::InnerPty;
 use crate::runtime::{AgentRequest, AgentResponse, InputMode, RuntimeBridge};
 use crate::screen::{self, ScreenState};
-use crate::session_log::SessionLog;
+use crate::session_export;
+use crate::session_index::{self, SessionIndex, SessionSearch};
+use crate::session_log::{self, SessionLog};
+use crate::session_resume::{AgentState, Role};
 use crate::shared_writer::SharedWriter;
+use crate::theme;
+use crate::vim_mode::VimState;
 
 use async_trait::async_trait;
-use mux::domain::DomainId;
+use config::keyassignment::SpawnTabDomain;
+use mux::domain::{DomainId, SplitSource};
 use mux::pane::{
     CachePolicy, CloseReason, ForEachPaneLogicalLine, LogicalLine, PaneId,
     PerformAssignmentResult, WithPaneLines,
@@ -74,34 +100,52 @@ use mux::renderable::{
     StableCursorPosition,
 };
 use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
-use portable_pty::PtySize;
+use mux::tab::{SplitDirection, SplitRequest, SplitSize};
+use mux::Mux;
+use portable_pty::{CommandBuilder, PtySize};
 use rangeset::RangeSet;
 use std::collections::HashMap;
 use std::io::Write;
 use std::ops::Range;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
 use std::time::Instant;
 use termwiz::surface::{Line, SequenceNo};
 use url::Url;
 use wezterm_term::color::ColorPalette;
 use wezterm_term::{
-    KeyCode, KeyModifiers, MouseEvent, StableRowIndex, Terminal, TerminalConfiguration,
-    TerminalSize,
+    Clipboard, ClipboardSelection, KeyCode, KeyModifiers, MouseEvent, StableRowIndex, Terminal,
+    TerminalConfiguration, TerminalSize,
 };
 
 /// Minimal terminal configuration for the virtual terminal.
-#[derive(Debug)]
-struct ElwoodTermConfig;
+///
+/// Colors come from the window's configuration, including its overrides,
+/// once the GUI hands one over through `set_config`.
+#[derive(Debug, Default)]
+struct ElwoodTermConfig {
+    window: Option<Arc<dyn TerminalConfiguration>>,
+}
 
 impl TerminalConfiguration for ElwoodTermConfig {
+    fn generation(&self) -> usize {
+        self.window.as_ref().map_or(0, |w| w.generation())
+    }
+
     fn scrollback_size(&self) -> usize {
         10_000
     }
 
     fn color_palette(&self) -> ColorPalette {
-        ColorPalette::default()
+        match &self.window {
+            Some(window) => window.color_palette(),
+            None => config::configuration().resolved_palette.clone().into(),
+        }
+    }
+
+    fn enable_kitty_graphics(&self) -> bool {
+        inline_image::config().protocol == ImageProtocol::Kitty
     }
 }
 
@@ -129,6 +173,32 @@ struct LastDetection {
     source_file: Option<String>,
 }
 
+/// An in-flight Ctrl+X Ctrl+E edit of the input buffer in an external editor.
+struct ExternalEdit {
+    /// Temp file holding the buffer while it is being edited.
+    path: PathBuf,
+    /// Progress of the editor pane, updated by the spawn future.
+    status: Arc<Mutex<ExternalEditStatus>>,
+}
+
+#[derive(Debug, Clone)]
+enum ExternalEditStatus {
+    /// The split pane is being spawned.
+    Spawning,
+    /// The editor is running in this pane.
+    Running(PaneId),
+    /// Spawning the editor pane failed.
+    Failed(String),
+}
+
+/// A `/replay` session: the player plus the wall-clock time of its last tick.
+struct ActiveReplay {
+    player: ReplayPlayer,
+    last_tick: Instant,
+    /// Chat output that arrived during playback, written out on stop.
+    backlog: String,
+}
+
 /// WezTerm Pane implementation for Elwood agent output.
 ///
 /// Wraps a virtual terminal (`wezterm_term::Terminal`) and renders a
@@ -138,6 +208,9 @@ struct LastDetection {
 pub struct ElwoodPane {
     pane_id: PaneId,
     domain_id: DomainId,
+    /// Directory this pane works in. Every pane has its own; the process
+    /// directory is shared by all panes and never changed.
+    cwd: PathBuf,
     terminal: Arc<Mutex<Terminal>>,
     /// Swappable writer: points to sink in agent mode, PTY stdin in terminal mode.
     /// The Terminal's internal writer is a clone of this; swapping the destination
@@ -157,8 +230,12 @@ pub struct ElwoodPane {
     pending_permission: Mutex<Option<PendingPermission>>,
     /// Full-screen layout state (dimensions, model, tokens, etc.).
     screen: Mutex<ScreenState>,
+    /// Streamed reply text held back until an image or chart in it is complete.
+    pending_content: Mutex<String>,
     /// Block manager — tracks agent response / command blocks for navigation.
     block_manager: Mutex<BlockManager>,
+    /// On-disk store for this pane's blocks (keyed by working directory and session).
+    block_store: Mutex<BlockStore>,
     /// Last Active AI detection — populated when errors are found in command output.
     /// Consumed by Ctrl+F to build a quick-fix prompt.
     last_detection: Mutex<Option<LastDetection>>,
@@ -172,18 +249,49 @@ pub struct ElwoodPane {
     inner_pty: Mutex<Option<InnerPty>>,
     /// Active diff viewer for code review. When `Some`, key events are routed here.
     diff_viewer: Mutex<Option<DiffViewer>>,
-    /// NL classifier for auto-detecting input mode on submit.
-    nl_classifier: NlClassifier,
+    /// NL classifier for auto-detecting input mode on submit. Learns from
+    /// `!command`, Ctrl+Enter and `/reroute` corrections.
+    nl_classifier: Mutex<NlClassifier>,
+    /// Last input auto-routed in Agent mode and where it went (for `/reroute`).
+    last_routed: Mutex<Option<(String, InputMode)>>,
     /// Completion engine for ghost text suggestions.
     completion_engine: Mutex<CompletionEngine>,
+    /// Completion dropdown (Ctrl+Space), open while `Some`.
+    completion_menu: Mutex<Option<CompletionMenu>>,
     /// Command palette overlay (Ctrl+P).
     palette: Mutex<CommandPalette>,
     /// Fuzzy history search overlay (Ctrl+R).
     history_search: Mutex<HistorySearch>,
+    /// Errors with file locations from all observed panes (Alt+Q).
+    quickfix: Mutex<QuickfixList>,
+    /// Saved-session search results (`/sessions`).
+    session_search: Mutex<SessionSearch>,
+    /// `/sessions` index refresh running in the background.
+    session_index_job:
+        Mutex<Option<(SessionsAction, mpsc::Receiver<anyhow::Result<SessionIndex>>)>>,
+    /// Conversation with the agent, saved with the session for `/resume`.
+    agent_state: Mutex<AgentState>,
+    /// Plan being worked through, saved with the session for `/resume`.
+    active_plan: Mutex<Option<plan_mode::PlanDocument>>,
+    /// Background jobs started with `/bg`.
+    jobs: Mutex<JobManager>,
+    /// Job the agent turn is held for by `/jobs wait`.
+    job_wait: Mutex<Option<JobId>>,
     /// Semantic bridge for code-aware completions and context.
     semantic_bridge: Mutex<Option<SemanticBridge>>,
+    /// Index thread: builds the symbol index, then sends updated copies of
+    /// it into `semantic_bridge` as files change.
+    semantic_worker: Mutex<Option<IndexWorker>>,
     /// Next-command prediction engine (rules + history bigrams + LLM).
     prediction_engine: Mutex<PredictionEngine>,
+    /// Fixes for failed shell commands; learns from the ones the user runs.
+    corrector: Mutex<CommandCorrector>,
+    /// Correction offered for the last failed command, settled on the next submit.
+    pending_correction: Mutex<Option<Correction>>,
+    /// Alias/function probe of the user's shell, while it runs.
+    shell_probe: Mutex<Option<mpsc::Receiver<Vec<String>>>>,
+    /// `/record export` running in the background.
+    svg_export: Mutex<Option<mpsc::Receiver<anyhow::Result<ExportSummary>>>>,
     /// Cross-pane observer for terminal awareness (reads sibling pane content).
     pane_observer: PaneObserver,
     /// File browser overlay (F2).
@@ -204,6 +312,20 @@ pub struct ElwoodPane {
     fuzzy_finder: Mutex<Option<FuzzyFinder>>,
     /// Terminal session recorder (asciinema v2 format).
     recorder: Mutex<crate::recording::SessionRecorder>,
+    /// Recording being replayed (`/replay`). When `Some`, key events are routed here.
+    replay: Mutex<Option<ActiveReplay>>,
+    /// Clipboard handed to us by the GUI via `Pane::set_clipboard`.
+    clipboard: Mutex<Option<Arc<dyn Clipboard>>>,
+    /// Set after Ctrl+X, waiting for the second key of a chord (Ctrl+E).
+    ctrl_x_pending: Mutex<bool>,
+    /// External editor session started with Ctrl+X Ctrl+E.
+    external_edit: Mutex<Option<ExternalEdit>>,
+    /// Registry ID when this pane runs a fan-out subtask.
+    agent_id: Mutex<Option<u32>>,
+    /// Agent text streamed for the current subtask (summary fallback).
+    agent_output: Mutex<String>,
+    /// `theme::generation()` at the last chrome redraw.
+    theme_generation: AtomicUsize,
 }
 
 /// A pending permission request waiting for user approval.
@@ -241,18 +363,31 @@ fn generate_simple_commit_message(files: &[git_ui::GitFileStatus]) -> String {
 }
 
 impl ElwoodPane {
-    /// Create a new ElwoodPane with a virtual terminal and full-screen TUI.
+    /// Create a new ElwoodPane with a virtual terminal and full-screen TUI,
+    /// working in `cwd`.
     pub fn new(
         pane_id: PaneId,
         domain_id: DomainId,
         size: TerminalSize,
         bridge: Arc<RuntimeBridge>,
+        cwd: PathBuf,
     ) -> Self {
         let shared_writer = SharedWriter::new();
+        let (plugin_detectors, detector_errors) = DetectorSet::load_default();
+        let (completion_specs, spec_errors) = SpecSet::load_default();
+        let (nl_classifier, classifier_error) =
+            NlClassifier::new().with_model_path(nl_classifier::default_model_path());
+        let (prediction_engine, prediction_error) =
+            PredictionEngine::new().with_model_path(prediction_engine::default_model_path());
+        let (corrector, corrector_error) =
+            CommandCorrector::new().with_memory_path(autocorrect::default_memory_path());
+        let elwood_config = crate::config::ElwoodConfig::load();
+        inline_image::set_config(elwood_config.images);
+        let notification_config = elwood_config.notifications;
 
         let terminal = Terminal::new(
             size,
-            Arc::new(ElwoodTermConfig),
+            Arc::new(ElwoodTermConfig::default()),
             "Elwood",
             "0.1.0",
             // Terminal's internal writer goes through SharedWriter so that
@@ -260,7 +395,9 @@ impl ElwoodPane {
             Box::new(shared_writer.clone()),
         );
 
-        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let session_log = SessionLog::new(cwd.clone());
+        let block_session = format!("{}-{pane_id}", session_log.file_stem());
+        let block_store = BlockStore::for_session(&cwd, &block_session);
 
         let mut screen_state = ScreenState::default();
         screen_state.width = size.cols as u16;
@@ -273,6 +410,7 @@ impl ElwoodPane {
         let pane = Self {
             pane_id,
             domain_id,
+            cwd: cwd.clone(),
             terminal: Arc::new(Mutex::new(terminal)),
             shared_writer,
             pane_writer: Mutex::new(Box::new(pane_writer_clone)),
@@ -284,23 +422,35 @@ impl ElwoodPane {
             state: Mutex::new(PaneState::Idle),
             pending_permission: Mutex::new(None),
             screen: Mutex::new(screen_state),
+            pending_content: Mutex::new(String::new()),
             block_manager: Mutex::new(BlockManager::new()),
+            block_store: Mutex::new(block_store),
             last_detection: Mutex::new(None),
-            detector: ContentDetector::new(),
+            detector: ContentDetector::with_detectors(plugin_detectors),
             suggester: NextCommandSuggester::new(),
-            session_log: Mutex::new(SessionLog::new(cwd.clone())),
+            session_log: Mutex::new(session_log),
             inner_pty: Mutex::new(None),
             diff_viewer: Mutex::new(None),
-            nl_classifier: NlClassifier::new(),
-            completion_engine: Mutex::new(CompletionEngine::new()),
+            nl_classifier: Mutex::new(nl_classifier),
+            last_routed: Mutex::new(None),
+            completion_engine: Mutex::new(CompletionEngine::new().with_specs(completion_specs)),
+            completion_menu: Mutex::new(None),
             palette: Mutex::new(CommandPalette::new()),
             history_search: Mutex::new(HistorySearch::new()),
-            semantic_bridge: {
-                let mut bridge = SemanticBridge::new(cwd);
-                bridge.initialize();
-                Mutex::new(Some(bridge))
-            },
-            prediction_engine: Mutex::new(PredictionEngine::new()),
+            quickfix: Mutex::new(QuickfixList::new()),
+            session_search: Mutex::new(SessionSearch::new()),
+            session_index_job: Mutex::new(None),
+            agent_state: Mutex::new(AgentState::new(&cwd.display().to_string())),
+            active_plan: Mutex::new(None),
+            jobs: Mutex::new(JobManager::new()),
+            job_wait: Mutex::new(None),
+            semantic_bridge: Mutex::new(None),
+            semantic_worker: Mutex::new(Some(SemanticBridge::build_in_background(cwd))),
+            prediction_engine: Mutex::new(prediction_engine),
+            corrector: Mutex::new(corrector),
+            pending_correction: Mutex::new(None),
+            shell_probe: Mutex::new(None),
+            svg_export: Mutex::new(None),
             pane_observer: PaneObserver::new(pane_id),
             file_browser: Mutex::new(None),
             lua_events: Mutex::new(LuaEventDispatcher::try_new()),
@@ -308,33 +458,126 @@ impl ElwoodPane {
             staging_view: Mutex::new(None),
             commit_view: Mutex::new(None),
             plan_viewer: Mutex::new(None),
-            toast_manager: Mutex::new(ToastManager::new()),
+            toast_manager: Mutex::new(ToastManager::new().with_config(notification_config)),
             fuzzy_finder: Mutex::new(None),
             recorder: Mutex::new(crate::recording::SessionRecorder::new()),
+            replay: Mutex::new(None),
+            clipboard: Mutex::new(None),
+            ctrl_x_pending: Mutex::new(false),
+            external_edit: Mutex::new(None),
+            agent_id: Mutex::new(None),
+            agent_output: Mutex::new(String::new()),
+            theme_generation: AtomicUsize::new(0),
         };
 
+        // Pick up the configured TUI theme before the first render
+        let theme_error = apply_configured_theme(&pane.terminal.lock().palette()).err();
+        pane.theme_generation.store(theme::generation(), Ordering::Relaxed);
+
         // Start observing sibling panes for cross-pane awareness.
         // subscribe_all() watches all panes (empty subscription = observe everything).
         pane.pane_observer.subscribe_all();
         pane.pane_observer.start_observing();
 
+        // Learn the user's aliases and functions for typo correction
+        pane.start_shell_probe();
+
+        // Drop job logs left behind by earlier sessions
+        if let Err(e) = jobs::prune_logs(&jobs::jobs_dir(), jobs::MAX_RETAINED) {
+            log::warn!("Failed to prune job logs: {e}");
+        }
+
         // Render the full-screen TUI layout (includes welcome message)
         {
             let ss = pane.screen.lock();
-            pane.write_ansi(&screen::render_full_screen(&ss));
+            pane.write_chrome(&screen::render_full_screen(&ss));
+        }
+
+        if let Some(e) = theme_error {
+            pane.write_ansi(&screen::format_error(&format!("Theme: {e}")));
+        }
+        for e in detector_errors {
+            pane.write_ansi(&screen::format_error(&format!("Detector: {e}")));
+        }
+        for e in spec_errors {
+            pane.write_ansi(&screen::format_error(&format!("Completion spec: {e}")));
+        }
+        if let Some(e) = classifier_error {
+            pane.write_ansi(&screen::format_error(&format!("Classifier: {e}")));
+        }
+        if let Some(e) = prediction_error {
+            pane.write_ansi(&screen::format_error(&format!("Predictions: {e}")));
+        }
+        if let Some(e) = corrector_error {
+            pane.write_ansi(&screen::format_error(&format!("Autocorrect: {e}")));
         }
 
+        // Replay blocks saved by the previous session in this directory
+        pane.restore_blocks();
+
         pane
     }
 
-    /// Write ANSI-escaped text to the virtual terminal.
+    /// Write chat content to the virtual terminal.
+    ///
+    /// Content is recorded when a recording is running. While a replay
+    /// owns the screen it is buffered and written out when the replay stops.
     fn write_ansi(&self, text: &str) {
+        if let Some(active) = self.replay.lock().as_mut() {
+            active.backlog.push_str(text);
+            return;
+        }
+        {
+            let mut rec = self.recorder.lock();
+            if rec.is_recording() {
+                rec.record_output(text);
+            }
+        }
+        self.write_terminal(text);
+    }
+
+    /// Write chrome (header, input box, status bar, overlays) to the
+    /// virtual terminal. Chrome is never recorded and is dropped while a
+    /// replay owns the screen; [`Self::stop_replay`] redraws it.
+    fn write_chrome(&self, text: &str) {
+        if self.replay.lock().is_some() {
+            return;
+        }
+        self.write_terminal(text);
+    }
+
+    /// Parse ANSI-escaped text into the virtual terminal.
+    fn write_terminal(&self, text: &str) {
         let mut terminal = self.terminal.lock();
         let actions = termwiz::escape::parser::Parser::new().parse_as_vec(text.as_bytes());
         terminal.perform_actions(actions);
         self.seqno.fetch_add(1, Ordering::Release);
     }
 
+    /// Format a streamed reply chunk, holding back an image link or chart
+    /// block that hasn't fully arrived yet.
+    fn stream_content(&self, delta: &str) -> String {
+        let mut pending = self.pending_content.lock();
+        pending.push_str(delta);
+        let ready = markdown::renderable_len(&pending);
+        if ready == 0 {
+            return String::new();
+        }
+        let rest = pending.split_off(ready);
+        let text = std::mem::replace(&mut *pending, rest);
+        screen::format_content(&text, &self.cwd)
+    }
+
+    /// Format whatever reply text was held back by [`Self::stream_content`].
+    fn flush_pending_content(&self) -> String {
+        let text = std::mem::take(&mut *self.pending_content.lock());
+        if text.is_empty() {
+            String::new()
+        } else {
+            screen::format_content(&text, &self.cwd)
+        }
+    }
+
     /// Render the toast notification overlay if any toasts are visible.
     fn render_toasts(&self) {
         let tm = self.toast_manager.lock();
@@ -346,12 +589,350 @@ impl ElwoodPane {
         let overlay = notification::render_toast_overlay(toasts, ss.width, ss.chat_top());
         drop(ss);
         drop(tm);
-        self.write_ansi(&overlay);
+        self.write_chrome(&overlay);
+    }
+
+    /// Put a toast's command or agent message into the input box.
+    fn run_toast_action(&self, action: ToastAction) {
+        let text = match action {
+            ToastAction::RunCommand(text) | ToastAction::SendToAgent(text) => text,
+            ToastAction::OpenUrl(_) | ToastAction::Dismiss => return,
+        };
+        let mut editor = self.input_editor.lock();
+        editor.clear();
+        for ch in text.chars() {
+            editor.insert_char(ch);
+        }
+        drop(editor);
+        self.sync_editor_to_screen();
+        self.refresh_input_box();
+    }
+
+    /// `/notifications` — open or clear the notification history.
+    fn handle_notifications_action(&self, action: NotificationsAction) {
+        match action {
+            NotificationsAction::Open => {
+                self.toast_manager.lock().history_mut().open();
+                self.render_notification_history();
+            }
+            NotificationsAction::Clear => {
+                self.toast_manager.lock().history_mut().clear();
+                self.write_ansi(&screen::format_command_response(
+                    "Notification history cleared.",
+                ));
+            }
+        }
+    }
+
+    /// Render the notification history overlay into the virtual terminal.
+    fn render_notification_history(&self) {
+        let width = self.screen.lock().width;
+        let rendered = self.toast_manager.lock().history().render(width);
+        if !rendered.is_empty() {
+            self.write_chrome(&rendered);
+        }
+    }
+
+    /// `/sessions` — search saved sessions, or rebuild the index first.
+    /// The index is refreshed on a background thread;
+    /// [`Self::poll_session_index`] shows the results.
+    fn handle_sessions_action(&self, action: SessionsAction) {
+        let mut job = self.session_index_job.lock();
+        if job.is_some() {
+            drop(job);
+            self.write_ansi(&screen::format_error("Sessions are already being indexed"));
+            return;
+        }
+        let reindex = action == SessionsAction::Reindex;
+        let (tx, rx) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("elwood-session-index".into())
+            .spawn(move || {
+                let _ = tx.send(refresh_session_index(reindex));
+            });
+        match spawned {
+            Ok(_) => *job = Some((action, rx)),
+            Err(e) => {
+                drop(job);
+                self.write_ansi(&screen::format_error(&format!("Sessions: {e}")));
+            }
+        }
+    }
+
+    /// Show the result of a finished `/sessions` index refresh.
+    fn poll_session_index(&self) {
+        let (action, result) = {
+            let mut job = self.session_index_job.lock();
+            let Some((_, rx)) = job.as_ref() else {
+                return;
+            };
+            let result = match rx.try_recv() {
+                Ok(result) => result,
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    Err(anyhow::anyhow!("index thread exited"))
+                }
+            };
+            match job.take() {
+                Some((action, _)) => (action, result),
+                None => return,
+            }
+        };
+        let index = match result {
+            Ok(index) => index,
+            Err(e) => {
+                self.write_ansi(&screen::format_error(&format!("Sessions: {e:#}")));
+                return;
+            }
+        };
+
+        match action {
+            SessionsAction::Reindex => {
+                let n = index.len();
+                self.write_ansi(&screen::format_command_response(&format!(
+                    "Indexed {n} session{}.",
+                    if n == 1 { "" } else { "s" },
+                )));
+            }
+            SessionsAction::Search(query) => {
+                let hits = index.search(&query, session_index::SEARCH_LIMIT);
+                self.session_search.lock().show(&query, hits);
+                self.render_session_search();
+            }
+        }
+    }
+
+    /// Render the session search overlay into the virtual terminal.
+    fn render_session_search(&self) {
+        let width = self.screen.lock().width;
+        let rendered = self.session_search.lock().render(width);
+        if !rendered.is_empty() {
+            self.write_chrome(&rendered);
+        }
+    }
+
+    /// Open a saved session file in the user's editor.
+    fn open_session_file(&self, path: &Path) {
+        let config = ide_bridge::EditorConfig::load();
+        let editor = ide_bridge::EditorDetector::cached().best_editor(config.preferred.as_deref());
+        let result = match editor {
+            Some(editor) => {
+                ide_bridge::EditorBridge::open_file(editor, &path.display().to_string(), None, None)
+            }
+            None => Err("No editor detected. Install one of: cursor, zed, code, subl, nvim".into()),
+        };
+        let (msg, level) = match result {
+            Ok(_) => (format!("Opened {}", path.display()), ToastLevel::Info),
+            Err(e) => (e, ToastLevel::Error),
+        };
+        self.toast_manager.lock().push(msg, level, None, None);
+        self.render_toasts();
+    }
+
+    /// `/resume <path>` — check the file, then continue it in a new pane.
+    fn handle_resume_command(&self, path: &str) {
+        let path = match path.strip_prefix("~/") {
+            Some(rest) => dirs_next::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(rest),
+            None => PathBuf::from(path),
+        };
+        match std::fs::canonicalize(&path) {
+            Ok(path) => match session_export::import_from_file(&path, None) {
+                Ok(log) => self.spawn_resume_pane(&path, &log),
+                Err(e) => self.write_ansi(&screen::format_error(&format!(
+                    "Cannot resume {}: {e}",
+                    path.display()
+                ))),
+            },
+            Err(e) => self.write_ansi(&screen::format_error(&format!(
+                "Cannot resume {}: {e}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Spawn a fresh Elwood pane next to this one that resumes `path`.
+    ///
+    /// The `ELWOOD_RESUME` env var tells the domain to give the pane its own
+    /// runtime and call [`ElwoodPane::resume_session`]. The pane works in the
+    /// session's directory, or in this pane's if that no longer exists.
+    fn spawn_resume_pane(&self, path: &Path, log: &SessionLog) {
+        let mut command = CommandBuilder::new_default_prog();
+        command.env("ELWOOD_RESUME", path.as_os_str());
+        let working_dir = match &log.agent_state {
+            Some(state) => PathBuf::from(&state.working_dir),
+            None => log.working_dir.clone(),
+        };
+        let dir = if working_dir.is_dir() {
+            working_dir
+        } else {
+            self.cwd.clone()
+        };
+        let dir = Some(dir.display().to_string());
+        self.spawn_elwood_split(command, dir, |e| {
+            log::error!("Failed to spawn resume pane: {e:#}");
+        });
+    }
+
+    /// Restore a saved session into this (fresh) pane: the transcript is
+    /// replayed, and the conversation, model and plan handed back to the
+    /// agent so the next message continues it.
+    ///
+    /// Called by the domain right after the pane is created.
+    pub fn resume_session(&self, path: &Path) {
+        let mut log = match session_export::import_from_file(path, None) {
+            Ok(log) => log,
+            Err(e) => {
+                self.write_ansi(&screen::format_error(&format!(
+                    "Cannot resume {}: {e}",
+                    path.display()
+                )));
+                return;
+            }
+        };
+        let state = log
+            .agent_state
+            .take()
+            .unwrap_or_else(|| AgentState::from_log(&log));
+
+        for (i, message) in state.messages.iter().enumerate() {
+            match message.role {
+                Role::User => self.write_ansi(&screen::format_user_prompt(&message.content)),
+                Role::Assistant => {
+                    self.write_ansi(&screen::format_assistant_prefix());
+                    for call in state.tool_calls_for(i) {
+                        self.write_ansi(&screen::format_tool_start(
+                            &call.tool_name,
+                            &call.input_preview,
+                        ));
+                    }
+                    self.write_ansi(&screen::format_content(&message.content, &self.cwd));
+                    self.write_ansi("\r\n");
+                }
+            }
+        }
+
+        let _ = self.bridge.send_request(AgentRequest::Restore {
+            messages: state.restored_messages(),
+        });
+        if let Some(ref model_name) = state.model {
+            let _ = self.bridge.send_request(AgentRequest::SwitchModel {
+                model_name: model_name.clone(),
+            });
+        }
+
+        let mut summary = format!(
+            "Resumed session from {} ({}): {} messages, {} tool calls",
+            log.started_at,
+            path.display(),
+            state.messages.len(),
+            state.tool_calls.len(),
+        );
+        if let Some(ref model_name) = state.model {
+            summary.push_str(&format!(", model {model_name}"));
+        }
+        if !state.working_dir.is_empty() {
+            if Path::new(&state.working_dir).is_dir() {
+                summary.push_str(&format!(".\nWorking directory: {}", state.working_dir));
+            } else {
+                summary.push_str(&format!(
+                    ".\nWorking directory {} no longer exists; working in {}",
+                    state.working_dir,
+                    self.cwd.display()
+                ));
+            }
+        }
+        self.write_ansi(&screen::format_command_response(&summary));
+
+        let plan = state.unfinished_plan();
+        *self.session_log.lock() = log;
+        *self.agent_state.lock() = state;
+        if let Some(plan) = plan {
+            *self.active_plan.lock() = Some(plan.clone());
+            let viewer = PlanViewer::new(plan);
+            let rendered = viewer.render(self.screen.lock().width);
+            *self.plan_viewer.lock() = Some(viewer);
+            self.write_ansi(&rendered);
+        }
+    }
+
+    /// Send a request to the agent, recording conversation turns for
+    /// `/resume`.
+    fn send_agent_request(&self, request: AgentRequest) {
+        if let (AgentRequest::SendMessage { .. }, Some(agent_id)) =
+            (&request, *self.agent_id.lock())
+        {
+            // A follow-up to a fan-out agent reopens its subtask
+            let mut registry = multi_agent::shared_registry().lock();
+            if registry.subtask_for_agent(agent_id).is_some() {
+                registry.reopen_subtask(agent_id);
+            } else {
+                registry.update_status(agent_id, AgentStatus::Working);
+            }
+        }
+        {
+            let mut state = self.agent_state.lock();
+            match &request {
+                AgentRequest::Start { prompt, .. } => {
+                    state.reset();
+                    state.record_user(prompt);
+                }
+                AgentRequest::SendMessage { content } => state.record_user(content),
+                AgentRequest::GeneratePlan { description } => {
+                    state.record_user(&format!("Create an implementation plan for: {description}"));
+                }
+                _ => {}
+            }
+        }
+        let _ = self.bridge.send_request(request);
+    }
+
+    /// This session's log with the agent state attached, for saving.
+    fn session_snapshot(&self) -> SessionLog {
+        let mut state = self.agent_state.lock().clone();
+        state.model = Some(self.screen.lock().model_name.clone()).filter(|m| !m.is_empty());
+        let plan = self
+            .plan_viewer
+            .lock()
+            .as_ref()
+            .map(|v| v.plan.clone())
+            .or_else(|| self.active_plan.lock().clone());
+        state.set_plan(plan.as_ref());
+
+        let mut log = self.session_log.lock().clone();
+        log.agent_state = Some(state);
+        log
+    }
+
+    /// `/export html|json|share [path]` — write the session in that format.
+    fn handle_export_formatted(&self, path: &str, format: &str) {
+        let result =
+            session_export::export_to_file(&self.session_snapshot(), Path::new(path), None);
+        match result {
+            Ok(path) => self.write_ansi(&screen::format_command_response(&format!(
+                "Session exported as {format} to: {}",
+                path.display()
+            ))),
+            Err(e) => self.write_ansi(&screen::format_error(&format!("Export failed: {e}"))),
+        }
+    }
+
+    /// Export a saved session again, as HTML next to the original file.
+    fn reexport_session(&self, path: &Path) {
+        let result = session_index::load_session(path).and_then(|log| {
+            session_export::export_to_file(&log, &path.with_extension("html"), None)
+        });
+        let (msg, level) = match result {
+            Ok(out) => (format!("Exported to {}", out.display()), ToastLevel::Info),
+            Err(e) => (format!("Export failed: {e}"), ToastLevel::Error),
+        };
+        self.toast_manager.lock().push(msg, level, None, None);
+        self.render_toasts();
     }
 
     /// Redraw the fixed chrome (header, input box, status bar) without
     /// disturbing the chat scroll position.
-    #[allow(dead_code)]
     fn redraw_chrome(&self) {
         let ss = self.screen.lock();
         let mut out = String::with_capacity(2048);
@@ -361,7 +942,154 @@ impl ElwoodPane {
         out.push_str(&screen::render_status_bar(&ss));
         out.push_str("\x1b[u"); // restore cursor
         drop(ss);
-        self.write_ansi(&out);
+        self.write_chrome(&out);
+    }
+
+    /// Reload the theme if its files changed and redraw the chrome when the
+    /// active theme differs from the one last drawn.
+    fn poll_theme(&self) {
+        let config_path = crate::config::default_config_path();
+        if let Err(e) = theme::reload_if_changed(&config_path, || self.terminal.lock().palette()) {
+            self.toast_manager.lock().push(
+                format!("Theme: {e}"),
+                ToastLevel::Error,
+                None,
+                None,
+            );
+            self.render_toasts();
+        }
+
+        let generation = theme::generation();
+        if self.theme_generation.swap(generation, Ordering::Relaxed) != generation {
+            self.redraw_chrome();
+            self.seqno.fetch_add(1, Ordering::Release);
+        }
+    }
+
+    /// Merge the commands recorded since the last save into the prediction
+    /// model file on a background thread, once the batch is due.
+    fn poll_prediction_save(&self) {
+        let Some(update) = self.prediction_engine.lock().take_unsaved(false) else {
+            return;
+        };
+        let spawned = std::thread::Builder::new()
+            .name("elwood-predictions-save".into())
+            .spawn(move || {
+                if let Err(e) = update.save() {
+                    log::warn!("Failed to save prediction model: {e:#}");
+                }
+            });
+        if let Err(e) = spawned {
+            log::warn!("Failed to start prediction model save: {e}");
+        }
+    }
+
+    /// Track the background symbol index build and pick up the updated
+    /// index whenever the index thread has reindexed changed files.
+    fn poll_semantic_index(&self) {
+        let mut worker = self.semantic_worker.lock();
+        let Some(pending) = worker.as_mut() else {
+            return;
+        };
+        let progress = match pending.poll() {
+            BuildStatus::Running(done, total) => Some((done, total)),
+            BuildStatus::Ready(bridge) => {
+                *self.semantic_bridge.lock() = Some(*bridge);
+                None
+            }
+            BuildStatus::Idle => return,
+            BuildStatus::Failed => {
+                log::warn!("semantic index build failed");
+                *worker = None;
+                None
+            }
+        };
+        drop(worker);
+        let changed = {
+            let mut ss = self.screen.lock();
+            std::mem::replace(&mut ss.index_progress, progress) != progress
+        };
+        if changed {
+            self.refresh_status_bar();
+        }
+    }
+
+    /// Probe the user's shell for aliases and functions on a background
+    /// thread; [`Self::poll_shell_probe`] hands the result to the corrector.
+    fn start_shell_probe(&self) {
+        let (tx, rx) = mpsc::channel();
+        let cwd = self.cwd.clone();
+        let spawned = std::thread::Builder::new()
+            .name("elwood-shell-probe".into())
+            .spawn(move || {
+                let shell = InnerPty::shell_program();
+                let _ = tx.send(InnerPty::probe_definitions(&shell, &cwd));
+            });
+        match spawned {
+            Ok(_) => *self.shell_probe.lock() = Some(rx),
+            Err(e) => log::warn!("Failed to start shell probe: {e}"),
+        }
+    }
+
+    /// Pass finished shell probe results to the corrector.
+    fn poll_shell_probe(&self) {
+        let mut probe = self.shell_probe.lock();
+        let Some(rx) = probe.as_ref() else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(names) => {
+                log::debug!("Shell probe found {} aliases/functions", names.len());
+                self.corrector.lock().set_shell_definitions(names);
+                *probe = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => *probe = None,
+        }
+    }
+
+    /// Offer a fix for a failed shell command as ghost text (Tab accepts it).
+    fn offer_correction(&self, command: &str, output: &str, code: i32) {
+        let Some(fix) = self
+            .corrector
+            .lock()
+            .suggest_correction(command, output, code)
+        else {
+            return;
+        };
+        self.write_ansi(&screen::format_correction_suggestion(
+            &fix.suggested,
+            &fix.explanation,
+        ));
+        if self.input_editor.lock().mode() == InputMode::Terminal {
+            self.input_editor
+                .lock()
+                .set_ghost_text(Some(fix.suggested.clone()));
+            self.sync_editor_to_screen();
+            self.refresh_input_box();
+        }
+        *self.pending_correction.lock() = Some(fix);
+    }
+
+    /// Record whether the user ran the pending correction, then apply any
+    /// correction learned well enough to fix `command` without asking.
+    fn resolve_correction(&self, command: String) -> String {
+        let corrector = self.corrector.lock();
+        if let Some(fix) = self.pending_correction.lock().take() {
+            let accepted = command.trim() == fix.suggested;
+            if let Err(e) = corrector.record_outcome(&fix, accepted) {
+                log::warn!("Failed to save autocorrect memory: {e:#}");
+            }
+        }
+        let fix = corrector.auto_fix(&command);
+        drop(corrector);
+        match fix {
+            Some(fix) => {
+                self.write_ansi(&screen::format_autocorrected(&command));
+                fix.suggested
+            }
+            None => command,
+        }
     }
 
     /// Update just the status bar (lightweight refresh for timer/state changes).
@@ -372,7 +1100,7 @@ impl ElwoodPane {
         out.push_str(&screen::render_status_bar(&ss));
         out.push_str("\x1b[u"); // restore cursor
         drop(ss);
-        self.write_ansi(&out);
+        self.write_chrome(&out);
     }
 
     /// Update the input box (after keystroke or clear).
@@ -383,7 +1111,7 @@ impl ElwoodPane {
         out.push_str(&screen::render_input_box(&ss));
         out.push_str("\x1b[u"); // restore cursor
         drop(ss);
-        self.write_ansi(&out);
+        self.write_chrome(&out);
     }
 
     /// Poll the RuntimeBridge for new responses and render them.
@@ -391,6 +1119,15 @@ impl ElwoodPane {
     /// Content is written into the scroll region. Chrome is updated via
     /// cursor save/restore so the scroll position is preserved.
     pub fn poll_responses(&self) {
+        self.poll_replay();
+        self.poll_external_edit();
+        self.poll_fanout_results();
+        self.poll_theme();
+        self.poll_prediction_save();
+        self.poll_semantic_index();
+        self.poll_shell_probe();
+        self.poll_svg_export();
+        self.poll_session_index();
         let mut any_update = false;
 
         loop {
@@ -413,6 +1150,7 @@ impl ElwoodPane {
                             tool_id: _,
                             input_preview: _,
                         } => {
+                            self.record_marker(&format!("tool: {tool_name}"));
                             *self.state.lock() = PaneState::Running;
                             let mut ss = self.screen.lock();
                             ss.is_running = true;
@@ -444,6 +1182,7 @@ impl ElwoodPane {
                             ss.tool_start = None;
                         }
                         AgentResponse::TurnComplete { .. } => {
+                            self.record_marker("turn complete");
                             *self.state.lock() = PaneState::Idle;
                             let mut ss = self.screen.lock();
                             ss.is_running = false;
@@ -471,6 +1210,7 @@ impl ElwoodPane {
                         }
                         AgentResponse::CommandOutput {
                             command,
+                            working_dir,
                             stdout,
                             stderr,
                             exit_code,
@@ -491,8 +1231,12 @@ impl ElwoodPane {
                                 combined.lines().map(|l| l.to_string()).collect();
                             let detections = self.detector.detect(self.pane_id, &lines, Instant::now());
 
+                            // The command may have committed, fetched or switched branches
+                            let git = git_info::get_git_info(&self.cwd);
+
                             *self.state.lock() = PaneState::Idle;
                             let mut ss = self.screen.lock();
+                            ss.git_info = git;
                             ss.is_running = false;
                             ss.active_tool = None;
                             ss.tool_start = None;
@@ -523,7 +1267,10 @@ impl ElwoodPane {
                             });
 
                             // Record command in prediction engine for bigram tracking
-                            self.prediction_engine.lock().record_command(command, code);
+                            let cwd = working_dir.clone();
+                            self.prediction_engine
+                                .lock()
+                                .record_command_in(command, code, &cwd);
 
                             // Populate suggestion overlay with structured detections
                             let error_detections = self.detector.detect_errors(&lines);
@@ -586,7 +1333,7 @@ impl ElwoodPane {
                                     );
                                     drop(ss);
                                     drop(sm);
-                                    self.write_ansi(&overlay);
+                                    self.write_chrome(&overlay);
                                 }
                             } else if let Some(suggestion) = self.suggester.suggest(command, success) {
                                 // No errors but there is a next-command suggestion
@@ -595,11 +1342,8 @@ impl ElwoodPane {
 
                             // Set prediction as ghost text in Terminal mode
                             if self.input_editor.lock().mode() == InputMode::Terminal {
-                                let cwd = std::env::current_dir()
-                                    .unwrap_or_else(|_| PathBuf::from("."));
-                                let git_branch = self.screen.lock().git_info
-                                    .as_ref()
-                                    .map(|gi| gi.branch.clone());
+                                let git = self.screen.lock().git_info.clone();
+                                let git_branch = git.as_ref().map(|gi| gi.branch.clone());
                                 let recent: Vec<String> = self.completion_engine.lock()
                                     .ghost_text("", &cwd)
                                     .into_iter()
@@ -609,6 +1353,7 @@ impl ElwoodPane {
                                     last_exit_code: code,
                                     working_dir: cwd,
                                     git_branch,
+                                    git,
                                     recent_commands: recent,
                                 };
                                 if let Some(prediction) = self.prediction_engine.lock().predict(&pred_ctx) {
@@ -619,6 +1364,11 @@ impl ElwoodPane {
                                     self.refresh_input_box();
                                 }
                             }
+
+                            // A likely typo fix replaces the prediction as ghost text
+                            if let Some(code) = code.filter(|&c| c != 0) {
+                                self.offer_correction(command, &combined, code);
+                            }
                         }
                         AgentResponse::FileEdit {
                             file_path,
@@ -710,6 +1460,7 @@ impl ElwoodPane {
                             self.write_ansi(&inline);
 
                             // Open the plan viewer overlay for approval
+                            *self.active_plan.lock() = Some(plan.clone());
                             let viewer = PlanViewer::new(plan);
                             let rendered = viewer.render(self.screen.lock().width);
                             *self.plan_viewer.lock() = Some(viewer);
@@ -726,6 +1477,26 @@ impl ElwoodPane {
                             );
                             ss.cost += cost_usd;
                         }
+                        AgentResponse::ContextUsage {
+                            used_tokens,
+                            budget_tokens,
+                            ref compacted,
+                        } => {
+                            {
+                                let mut ss = self.screen.lock();
+                                ss.context_used = *used_tokens;
+                                ss.context_max = *budget_tokens;
+                            }
+                            if let Some(msg) = compacted {
+                                self.toast_manager.lock().push(
+                                    msg.clone(),
+                                    ToastLevel::Info,
+                                    None,
+                                    None,
+                                );
+                                self.render_toasts();
+                            }
+                        }
                         AgentResponse::WorkflowStepResult {
                             ref exit_code, is_last, ..
                         } => {
@@ -760,8 +1531,22 @@ impl ElwoodPane {
                         AgentResponse::Shutdown => {
                             *self.dead.lock() = true;
                         }
-                        AgentResponse::JobUpdate { .. } => {
-                            // Handled by jobs panel (not yet wired)
+                        AgentResponse::JobUpdate {
+                            job_id,
+                            status,
+                            output_chunk,
+                            is_stderr,
+                            exit_code,
+                            pid,
+                        } => {
+                            self.handle_job_update(
+                                *job_id,
+                                status,
+                                output_chunk.clone(),
+                                *is_stderr,
+                                *exit_code,
+                                *pid,
+                            );
                         }
                         AgentResponse::AgentSpawned { .. }
                         | AgentResponse::AgentMessage { .. } => {
@@ -783,40 +1568,61 @@ impl ElwoodPane {
                     match &response {
                         AgentResponse::ContentDelta(text) => {
                             self.session_log.lock().log_agent(text);
+                            self.agent_state.lock().record_reply(text);
                         }
-                        AgentResponse::ToolStart { tool_name, input_preview, .. } => {
+                        AgentResponse::ToolStart { tool_name, tool_id, input_preview } => {
                             self.session_log.lock().log_tool(tool_name, &format!("started: {input_preview}"));
+                            self.agent_state.lock().record_tool_start(tool_id, tool_name, input_preview);
                         }
-                        AgentResponse::ToolEnd { success, output_preview, .. } => {
+                        AgentResponse::ToolEnd { tool_id, success, output_preview, .. } => {
                             let status = if *success { "OK" } else { "FAIL" };
                             self.session_log.lock().log_tool("ToolEnd", &format!("{status}: {output_preview}"));
+                            self.agent_state.lock().record_tool_end(tool_id, *success, output_preview);
                         }
                         AgentResponse::CommandOutput { stdout, stderr, exit_code, .. } => {
                             self.session_log.lock().log_command_output(stdout, stderr, *exit_code);
-                            // Refresh git info after commands (may have changed branch/state)
-                            if let Some(cwd) = std::env::current_dir().ok() {
-                                let mut ss = self.screen.lock();
-                                ss.git_info = git_info::get_git_info(&cwd);
-                            }
-                            // Refresh semantic index after commands (may have changed files)
-                            if let Some(ref mut bridge) = *self.semantic_bridge.lock() {
-                                bridge.refresh();
+                            // Refresh semantic index after commands (may have changed
+                            // files); the watcher keeps it current when available.
+                            if let Some(ref worker) = *self.semantic_worker.lock() {
+                                worker.request_refresh();
                             }
                         }
                         AgentResponse::Error(msg) => {
                             self.session_log.lock().log_system(&format!("Error: {msg}"));
                         }
+                        AgentResponse::ContextUsage { compacted: Some(msg), .. } => {
+                            self.session_log.lock().log_system(msg);
+                        }
                         _ => {}
                     }
 
+                    // ── Fan-out subtask bookkeeping ───────────────────
+                    self.track_subtask(&response);
+
                     // ── Lua plugin hooks ──────────────────────────────
                     self.dispatch_lua_event(&response);
 
                     // Render content into the scroll region
-                    let text = format_response_for_chat(&response);
+                    let text = match &response {
+                        AgentResponse::ContentDelta(delta) => self.stream_content(delta),
+                        _ => {
+                            self.flush_pending_content()
+                                + &format_response_for_chat(&response, &self.cwd)
+                        }
+                    };
                     if !text.is_empty() {
                         self.write_ansi(&text);
                     }
+
+                    // Close the output zone of the block that just finished
+                    if matches!(
+                        response,
+                        AgentResponse::TurnComplete { .. } | AgentResponse::CommandOutput { .. }
+                    ) {
+                        let row = self.current_row();
+                        self.block_manager.lock().extend_output(row);
+                        self.persist_last_block();
+                    }
                 }
                 Ok(None) => break,
                 Err(_) => {
@@ -1039,8 +1845,7 @@ impl ElwoodPane {
                     };
                     drop(ss);
 
-                    let cwd = std::env::current_dir()
-                        .unwrap_or_else(|_| PathBuf::from("."));
+                    let cwd = self.cwd.clone();
 
                     match InnerPty::spawn(size, &cwd, &self.terminal, &self.shared_writer) {
                         Ok(inner) => {
@@ -1105,6 +1910,7 @@ impl ElwoodPane {
             Some(c) => c,
             None => return,
         };
+        let command = self.resolve_correction(command);
 
         // Clear input box screen state
         self.sync_editor_to_screen();
@@ -1116,8 +1922,15 @@ impl ElwoodPane {
         // Log to session
         self.session_log.lock().log_command(&command);
 
-        // Write "$ command" prompt into chat area
+        // Open a block for this command, then write "$ command" into the chat area
+        let cwd = Some(self.cwd.clone());
+        let start_row = self.current_row();
+        self.block_manager
+            .lock()
+            .push_command_block(start_row, &command, cwd.clone());
         self.write_ansi(&screen::format_command_prompt(&command));
+        let output_row = self.current_row();
+        self.block_manager.lock().extend_output(output_row);
 
         // Update state — mark as running
         {
@@ -1130,15 +1943,65 @@ impl ElwoodPane {
         self.refresh_status_bar();
 
         // Send RunCommand to the bridge
-        let working_dir = std::env::current_dir()
-            .ok()
-            .map(|p| p.to_string_lossy().to_string());
+        let working_dir = cwd.map(|p| p.to_string_lossy().to_string());
         let _ = self.bridge.send_request(AgentRequest::RunCommand {
             command,
             working_dir,
+            env: HashMap::new(),
         });
     }
 
+    /// Submit the input buffer on Enter: `!`-prefixed input runs as a
+    /// command, anything else is routed by auto-detection.
+    fn submit_editor(&self) {
+        let mode = self.input_editor.lock().mode();
+        match mode {
+            InputMode::Agent => {
+                // Check for `!` prefix — run as command
+                let starts_with_bang = self
+                    .input_editor
+                    .lock()
+                    .lines()
+                    .first()
+                    .map(|l| l.starts_with('!'))
+                    .unwrap_or(false);
+                if starts_with_bang {
+                    // Strip the leading `!` from the first line
+                    let first = {
+                        let mut ed = self.input_editor.lock();
+                        let first = ed.lines()[0].trim_start_matches('!').to_string();
+                        ed.clear();
+                        for c in first.chars() {
+                            ed.insert_char(c);
+                        }
+                        first
+                    };
+                    // Forcing the shell is a correction when auto-detect
+                    // would have sent this to the agent
+                    if self.auto_route(&first) == InputMode::Agent {
+                        self.learn_route(&first, InputMode::Terminal);
+                    }
+                    self.submit_command();
+                } else {
+                    // NL auto-detection: classify input and auto-route
+                    // high-confidence terminal commands to shell
+                    let content = self.input_editor.lock().content();
+                    let route = self.auto_route(&content);
+                    if commands::parse_command(&content).is_none() {
+                        *self.last_routed.lock() = Some((content, route));
+                    }
+                    match route {
+                        InputMode::Terminal => self.submit_command(),
+                        InputMode::Agent => self.submit_input(),
+                    }
+                }
+            }
+            InputMode::Terminal => {
+                self.submit_command();
+            }
+        }
+    }
+
     /// Submit the current input buffer as a message to the agent.
     fn submit_input(&self) {
         let content = match self.input_editor.lock().submit() {
@@ -1161,15 +2024,23 @@ impl ElwoodPane {
             return;
         }
 
-        // ── @ context attachment (with @symbol: support) ─────────────
-        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        // ── @ context attachment (files, symbols, git, panes, blocks) ─
+        let cwd = self.cwd.clone();
+        let redactor = Redactor::from_default_config();
         let bridge_guard = self.semantic_bridge.lock();
-        let (attachments, augmented_content) =
-            context::resolve_and_build_prompt_with_symbols(&content, &cwd, bridge_guard.as_ref());
+        let (attachments, augmented_content) = context::resolve_and_build_prompt_with_context(
+            &content,
+            &cwd,
+            bridge_guard.as_ref(),
+            Some(self),
+            &redactor,
+        );
         drop(bridge_guard);
 
         // Log to session
         self.session_log.lock().log_user(&content);
+        let first_line: String = content.lines().next().unwrap_or("").chars().take(60).collect();
+        self.record_marker(&format!("prompt: {first_line}"));
 
         // Write user prompt into chat area (show original, not augmented)
         let display_content = if attachments.is_empty() {
@@ -1178,10 +2049,14 @@ impl ElwoodPane {
             let labels: Vec<&str> = attachments.iter().map(|a| a.label.as_str()).collect();
             format!("{content}\n  [attached: {}]", labels.join(", "))
         };
+        let start_row = self.current_row();
+        self.block_manager.lock().push_agent_block(start_row);
         self.write_ansi(&screen::format_user_prompt(&display_content));
 
         // Write the "Elwood" prefix before streaming starts
         self.write_ansi(&screen::format_assistant_prefix());
+        let output_row = self.current_row();
+        self.block_manager.lock().extend_output(output_row);
 
         // Update state
         {
@@ -1204,7 +2079,7 @@ impl ElwoodPane {
         };
 
         // Send to agent via bridge (use augmented content with file + pane context)
-        let _ = self.bridge.send_request(AgentRequest::SendMessage {
+        self.send_agent_request(AgentRequest::SendMessage {
             content: final_content,
         });
     }
@@ -1228,7 +2103,7 @@ impl ElwoodPane {
                 }
                 *self.state.lock() = PaneState::Running;
                 self.refresh_status_bar();
-                let _ = self.bridge.send_request(request);
+                self.send_agent_request(request);
             }
             CommandResult::ClearChat => {
                 self.write_ansi("\x1b[2J");
@@ -1272,14 +2147,24 @@ impl ElwoodPane {
                     model_name,
                 });
             }
-            CommandResult::ExportFormatted { path, format } => { self.write_ansi(&screen::format_command_response(&format!("Export as {format} to: {path}"))); }
+            CommandResult::ExportFormatted { path, format } => {
+                self.handle_export_formatted(&path, &format)
+            }
             CommandResult::ImportSession { path } => { self.write_ansi(&screen::format_command_response(&format!("Import session from: {path}"))); }
+            CommandResult::ResumeSession { path } => self.handle_resume_command(&path),
+            CommandResult::CompactContext => {
+                // The runtime answers with a `ContextUsage` toast
+                self.send_agent_request(AgentRequest::Compact);
+            }
             CommandResult::ListBookmarks => {
                 self.handle_list_bookmarks();
             }
             CommandResult::ExportBlock { index } => {
                 self.handle_export_block(index);
             }
+            CommandResult::BlockAction { action, index } => {
+                self.handle_block_action(action, index);
+            }
             CommandResult::RecordStart { filename } => {
                 self.handle_record_start(filename.as_deref());
             }
@@ -1292,6 +2177,24 @@ impl ElwoodPane {
             CommandResult::RecordResume => {
                 self.handle_record_resume();
             }
+            CommandResult::RecordExport {
+                source,
+                output,
+                from_marker,
+                to_marker,
+                max_idle,
+            } => {
+                self.handle_record_export(
+                    &source,
+                    output.as_deref(),
+                    from_marker,
+                    to_marker,
+                    max_idle,
+                );
+            }
+            CommandResult::Replay { path } => {
+                self.start_replay(&path);
+            }
             CommandResult::WorkflowResult(wf_result) => {
                 use crate::workflow::WorkflowCommandResult;
                 match wf_result {
@@ -1329,32 +2232,632 @@ impl ElwoodPane {
             CommandResult::RedactCommand(msg) => {
                 self.write_ansi(&screen::format_command_response(&msg));
             }
-            CommandResult::LaunchCommand(_) => {
-                // Handled by launch config integration
-            }
-            CommandResult::AutocorrectCommand(msg) => {
-                self.write_ansi(&screen::format_command_response(&msg));
-            }
+            CommandResult::LaunchCommand(result) => match result {
+                LaunchCommandResult::ChatMessage(msg) => {
+                    self.write_ansi(&screen::format_command_response(&msg));
+                }
+                LaunchCommandResult::Apply(apply) => self.apply_launch_config(apply),
+            },
+            CommandResult::AutocorrectCommand(action) => self.handle_autocorrect_action(action),
             CommandResult::RehashCommands => {
-                // Handled by autocorrect integration
+                self.corrector.lock().rehash();
+                self.start_shell_probe();
+                self.write_ansi(&screen::format_command_response(
+                    "Refreshing known commands and shell aliases/functions.",
+                ));
             }
-            CommandResult::OpenJobsPanel
-            | CommandResult::RunBackground { .. }
-            | CommandResult::KillJob { .. } => {
-                // Handled by jobs panel integration (not yet wired)
+            CommandResult::OpenJobsPanel => {
+                let panel = self.jobs.lock().render_panel();
+                self.write_ansi(&format!("\r\n{panel}"));
             }
+            CommandResult::RunBackground { command } => self.start_background_job(command),
+            CommandResult::KillJob { job_id } => {
+                let running = self.jobs.lock().get(job_id).is_some_and(|j| !j.is_finished());
+                if running {
+                    let _ = self.bridge.send_request(AgentRequest::KillJob { job_id });
+                } else {
+                    self.write_ansi(&screen::format_command_response(&format!(
+                        "No running job #{job_id}. Use /jobs to see job IDs."
+                    )));
+                }
+            }
+            CommandResult::Job(action) => self.handle_job_action(action),
             CommandResult::OpenInIde(msg) | CommandResult::EditorCommand(msg) => {
                 self.write_ansi(&screen::format_command_response(&msg));
             }
-            CommandResult::AgentCommand(_) => {
-                // Handled by multi-agent integration
-            }
+            CommandResult::AgentCommand(cmd) => self.handle_agent_command(cmd),
             CommandResult::NotebookCommand(_) => {
                 // Handled by notebook integration
             }
-            CommandResult::VimToggle(_) => {
-                // Handled by vim mode integration
+            CommandResult::VimToggle(action) => self.handle_vim_toggle(action),
+            CommandResult::Quickfix(action) => {
+                self.handle_quickfix_action(action);
+            }
+            CommandResult::Classifier(action) => self.handle_classifier_action(action),
+            CommandResult::Reroute => self.reroute_last_input(),
+            CommandResult::Predictions(action) => self.handle_predictions_action(action),
+            CommandResult::Notifications(action) => self.handle_notifications_action(action),
+            CommandResult::Sessions(action) => self.handle_sessions_action(action),
+        }
+    }
+
+    /// `/autocorrect` — toggle suggestions and manage what was learned.
+    fn handle_autocorrect_action(&self, action: AutocorrectAction) {
+        let mut corrector = self.corrector.lock();
+        let result = match action {
+            AutocorrectAction::On | AutocorrectAction::Off => {
+                let on = action == AutocorrectAction::On;
+                corrector.set_enabled(on);
+                Ok(format!("Autocorrect {}.", if on { "on" } else { "off" }))
+            }
+            AutocorrectAction::Status => Ok(corrector.format_status()),
+            AutocorrectAction::Never(cmd) => corrector.never_correct(&cmd).map(|()| {
+                format!("`{cmd}` will not be corrected. Undo with /autocorrect allow {cmd}")
+            }),
+            AutocorrectAction::Allow(cmd) => corrector.allow_correct(&cmd).map(|removed| {
+                if removed {
+                    format!("`{cmd}` can be corrected again.")
+                } else {
+                    format!("`{cmd}` is not on the never-correct list.")
+                }
+            }),
+            AutocorrectAction::Forget => corrector
+                .forget_corrections()
+                .map(|()| "Forgot all learned corrections.".to_string()),
+        };
+        drop(corrector);
+        match result {
+            Ok(msg) => self.write_ansi(&screen::format_command_response(&msg)),
+            Err(e) => self.write_ansi(&screen::format_error(&format!("Autocorrect: {e:#}"))),
+        }
+    }
+
+    /// `/predictions` — show accuracy stats or forget the learned model.
+    fn handle_predictions_action(&self, action: PredictionsAction) {
+        match action {
+            PredictionsAction::Stats => {
+                let report = prediction_engine::format_stats(self.prediction_engine.lock().stats());
+                self.write_ansi(&screen::format_command_response(&report));
+            }
+            PredictionsAction::Reset => match self.prediction_engine.lock().reset() {
+                Ok(()) => self.write_ansi(&screen::format_command_response(
+                    "Predictions reset: learned command history and stats cleared.",
+                )),
+                Err(e) => self.write_ansi(&screen::format_error(&format!("Predictions: {e:#}"))),
+            },
+        }
+    }
+
+    // ── NL classifier corrections ──────────────────────────────────────────
+
+    /// Where Agent-mode Enter sends `content` without any correction.
+    fn auto_route(&self, content: &str) -> InputMode {
+        let classification = self.nl_classifier.lock().classify(content);
+        if classification.mode == InputMode::Terminal && classification.confidence >= 0.5 {
+            InputMode::Terminal
+        } else {
+            InputMode::Agent
+        }
+    }
+
+    /// Teach the classifier that `content` belongs to `mode`.
+    fn learn_route(&self, content: &str, mode: InputMode) {
+        if let Err(e) = self.nl_classifier.lock().learn(content, mode) {
+            log::warn!("Failed to save classifier model: {e:#}");
+        }
+    }
+
+    /// `/reroute` — re-send the last auto-routed input the other way.
+    fn reroute_last_input(&self) {
+        let Some((content, routed)) = self.last_routed.lock().take() else {
+            self.write_ansi(&screen::format_command_response(
+                "Nothing to reroute. /reroute re-sends the last auto-detected input the other way.",
+            ));
+            return;
+        };
+        let route = opposite_route(routed);
+        self.learn_route(&content, route);
+        self.input_editor.lock().set_content(&content);
+        match route {
+            InputMode::Terminal => self.submit_command(),
+            InputMode::Agent => self.submit_input(),
+        }
+    }
+
+    fn handle_classifier_action(&self, action: ClassifierAction) {
+        match action {
+            ClassifierAction::Explain(text) => {
+                let text =
+                    text.or_else(|| self.last_routed.lock().as_ref().map(|(t, _)| t.clone()));
+                let Some(text) = text else {
+                    self.write_ansi(&screen::format_command_response(
+                        "Nothing routed yet. Use /routing <text> to see how it would be routed.",
+                    ));
+                    return;
+                };
+                let classifier = self.nl_classifier.lock();
+                let explanation = classifier.explain(&text);
+                let report =
+                    nl_classifier::format_explanation(&text, &explanation, classifier.model());
+                drop(classifier);
+                self.write_ansi(&screen::format_command_response(&report));
+            }
+            ClassifierAction::Reset => match self.nl_classifier.lock().reset_learning() {
+                Ok(()) => self.write_ansi(&screen::format_command_response(
+                    "Classifier reset: learned weights and corrections cleared.",
+                )),
+                Err(e) => self.write_ansi(&screen::format_error(&format!("Classifier: {e:#}"))),
+            },
+        }
+    }
+
+    /// Handle `/agent` subcommands.
+    fn handle_agent_command(&self, cmd: AgentCommand) {
+        match cmd {
+            AgentCommand::List => {
+                let dashboard = multi_agent::render_dashboard(&multi_agent::shared_registry().lock());
+                self.write_ansi(&screen::format_command_response(&dashboard));
+            }
+            AgentCommand::FanOut { prompt, subtasks, worktrees } => {
+                self.start_fanout(&prompt, subtasks, worktrees);
+            }
+            AgentCommand::Results { task_id } => {
+                let report = multi_agent::shared_registry()
+                    .lock()
+                    .task(task_id)
+                    .map(multi_agent::aggregate_results);
+                match report {
+                    Some(report) => {
+                        self.write_ansi("\r\n");
+                        self.write_ansi(&screen::format_content(&report, &self.cwd));
+                    }
+                    None => self.write_ansi(&screen::format_error(&format!(
+                        "No fan-out task #{task_id}"
+                    ))),
+                }
+            }
+            AgentCommand::Spawn { name, model } => self.spawn_agent(name, model),
+            AgentCommand::Kill { target } => self.kill_agent(&target),
+            AgentCommand::Focus { target } => {
+                let Some((_, pane_id)) = self.agent_pane(&target) else {
+                    return;
+                };
+                if let Err(e) = Mux::get().focus_pane_and_containing_tab(pane_id) {
+                    self.write_ansi(&screen::format_error(&format!(
+                        "Cannot focus {target}: {e}"
+                    )));
+                }
+            }
+            AgentCommand::Tell { target, message } => self.tell_agent(&target, &message),
+            AgentCommand::Merge { task_id, subtask } => {
+                self.resolve_fanout(task_id, subtask, Resolution::Merged);
+            }
+            AgentCommand::Discard { task_id, subtask } => {
+                self.resolve_fanout(task_id, subtask, Resolution::Discarded);
+            }
+            AgentCommand::Help => {
+                self.write_ansi(&screen::format_command_response(
+                    &multi_agent::agent_help_text(),
+                ));
+            }
+        }
+    }
+
+    /// `/agent spawn`: start an idle agent pane next to this one, with its
+    /// own runtime in this pane's directory.
+    fn spawn_agent(&self, name: Option<String>, model: Option<String>) {
+        let model = model.unwrap_or_else(|| self.screen.lock().model_name.clone());
+        let agent_id = {
+            let mut registry = multi_agent::shared_registry().lock();
+            if let Some(name) = name.as_deref().filter(|n| registry.name_exists(n)) {
+                drop(registry);
+                self.write_ansi(&screen::format_error(&format!(
+                    "An agent named {name} already exists"
+                )));
+                return;
+            }
+            registry.register(name.as_deref(), &model)
+        };
+        let dir = self.cwd.display().to_string();
+        spawn_agent_pane(self.pane_id, self.domain_id, agent_id, dir);
+    }
+
+    /// The ID and pane of the agent named or numbered `target`, reporting
+    /// why there is none.
+    fn agent_pane(&self, target: &str) -> Option<(u32, PaneId)> {
+        let found = {
+            let registry = multi_agent::shared_registry().lock();
+            registry
+                .resolve(target)
+                .and_then(|id| registry.get(id))
+                .map(|a| (a.id, a.pane_id, a.status))
+        };
+        match found {
+            Some((id, Some(pane_id), status)) if status != AgentStatus::Stopped => {
+                Some((id, pane_id))
+            }
+            Some((_, _, AgentStatus::Stopped)) => {
+                self.write_ansi(&screen::format_error(&format!(
+                    "Agent {target} has stopped"
+                )));
+                None
+            }
+            Some(_) => {
+                self.write_ansi(&screen::format_error(&format!(
+                    "Agent {target} is still starting"
+                )));
+                None
+            }
+            None => {
+                self.write_ansi(&screen::format_error(&format!("No agent {target}")));
+                None
+            }
+        }
+    }
+
+    /// `/agent kill`: close an agent's pane (which stops its runtime and
+    /// fails its subtask, see `kill`).
+    fn kill_agent(&self, target: &str) {
+        let Some((agent_id, pane_id)) = self.agent_pane(target) else {
+            return;
+        };
+        if pane_id == self.pane_id {
+            self.write_ansi(&screen::format_error(
+                "This is the agent's own pane; close it instead",
+            ));
+            return;
+        }
+        Mux::get().remove_pane(pane_id);
+        self.write_ansi(&screen::format_command_response(&format!(
+            "Stopped agent {target} (#{agent_id})"
+        )));
+    }
+
+    /// `/tell`: send `message` to an agent as its next prompt.
+    fn tell_agent(&self, target: &str, message: &str) {
+        let Some((to_id, pane_id)) = self.agent_pane(target) else {
+            return;
+        };
+        let from = self.agent_id.lock().and_then(|id| {
+            multi_agent::shared_registry()
+                .lock()
+                .get(id)
+                .map(|a| (id, a.name.clone()))
+        });
+        let (from_id, from_name) = from.unwrap_or((0, "user".to_string()));
+        let msg = multi_agent::InterAgentMessage::new(from_id, &from_name, to_id, message);
+
+        let Some(pane) = Mux::get().get_pane(pane_id) else {
+            self.write_ansi(&screen::format_error(&format!(
+                "Agent {target} has no pane"
+            )));
+            return;
+        };
+        let Some(agent) = pane.downcast_ref::<ElwoodPane>() else {
+            return;
+        };
+        agent.send_to_agent(&format!("[{from_name}] {message}"), msg.as_system_prompt());
+        self.write_ansi(&screen::format_command_response(&format!(
+            "Sent to {target}"
+        )));
+    }
+
+    /// `/agent merge` / `/agent discard`: merge or delete the worktrees of a
+    /// finished task's subtasks (all, or just `subtask`) on a background
+    /// thread, then close their agent panes.
+    fn resolve_fanout(&self, task_id: u32, subtask: Option<usize>, resolution: Resolution) {
+        let targets: Result<Vec<_>, String> = {
+            let registry = multi_agent::shared_registry().lock();
+            match registry.task(task_id) {
+                None => Err(format!("No fan-out task #{task_id}")),
+                Some(task) => task
+                    .subtasks
+                    .iter()
+                    .filter(|s| subtask.is_none_or(|n| s.index == n))
+                    .filter(|s| s.resolution.is_none())
+                    .filter_map(|s| {
+                        let ws = s.workspace.clone()?;
+                        let pane = s.agent_id.and_then(|id| registry.get(id)?.pane_id);
+                        Some((s, ws, pane))
+                    })
+                    .map(|(s, ws, pane)| {
+                        if s.status.is_finished() {
+                            Ok((s.index, s.description.clone(), ws, pane))
+                        } else {
+                            Err(format!(
+                                "Subtask {task_id}.{} is still running",
+                                s.index + 1
+                            ))
+                        }
+                    })
+                    .collect(),
+            }
+        };
+        let targets = match targets {
+            Ok(targets) if targets.is_empty() => Err(format!(
+                "Task #{task_id} has nothing left to {}",
+                if resolution == Resolution::Merged {
+                    "merge"
+                } else {
+                    "discard"
+                }
+            )),
+            other => other,
+        };
+        let targets = match targets {
+            Ok(targets) => targets,
+            Err(msg) => {
+                self.write_ansi(&screen::format_error(&msg));
+                return;
+            }
+        };
+
+        let target_dir = self.cwd.clone();
+        let pane_id = self.pane_id;
+        let spawned = std::thread::Builder::new()
+            .name("elwood-agent-merge".into())
+            .spawn(move || {
+                let mut lines = Vec::new();
+                let mut close = Vec::new();
+                for (index, description, workspace, agent_pane) in targets {
+                    let result = match resolution {
+                        Resolution::Merged => multi_agent::merge_workspace(
+                            &workspace,
+                            &target_dir,
+                            &format!("Task #{task_id}.{}: {description}", index + 1),
+                        ),
+                        Resolution::Discarded => multi_agent::remove_workspace(&workspace),
+                    };
+                    match result {
+                        Ok(()) => {
+                            multi_agent::shared_registry()
+                                .lock()
+                                .resolve_subtask(task_id, index, resolution);
+                            close.extend(agent_pane);
+                            lines.push(format!(
+                                "{task_id}.{} {}: {}",
+                                index + 1,
+                                resolution.label(),
+                                workspace.label()
+                            ));
+                        }
+                        Err(e) => lines.push(format!(
+                            "{task_id}.{} not {}: {e:#}",
+                            index + 1,
+                            resolution.label()
+                        )),
+                    }
+                }
+                promise::spawn::spawn_into_main_thread(async move {
+                    let mux = Mux::get();
+                    for agent_pane in close {
+                        mux.remove_pane(agent_pane);
+                    }
+                    let Some(pane) = mux.get_pane(pane_id) else {
+                        return;
+                    };
+                    let Some(elwood) = pane.downcast_ref::<ElwoodPane>() else {
+                        return;
+                    };
+                    elwood.write_ansi(&screen::format_command_response(&lines.join("\n")));
+                })
+                .detach();
+            });
+        if let Err(e) = spawned {
+            self.write_ansi(&screen::format_error(&format!(
+                "Failed to resolve task #{task_id}: {e}"
+            )));
+        }
+    }
+
+    /// Split a task across agents: register an agent for each subtask, then
+    /// prepare the workspaces and spawn the agent panes on a background thread.
+    fn start_fanout(&self, prompt: &str, subtasks: Vec<String>, worktrees: bool) {
+        let model = self.screen.lock().model_name.clone();
+        let count = subtasks.len();
+
+        let (task_id, agents) = {
+            let mut registry = multi_agent::shared_registry().lock();
+            let task_id = registry.start_fanout(prompt, subtasks, Some(self.pane_id));
+            let agents: Vec<u32> = (0..count)
+                .map(|index| {
+                    let name = format!("task{task_id}-{}", index + 1);
+                    registry.register(Some(&name), &model)
+                })
+                .collect();
+            (task_id, agents)
+        };
+
+        let cwd = self.cwd.clone();
+        let pane_id = self.pane_id;
+        let domain_id = self.domain_id;
+        let spawned = std::thread::Builder::new()
+            .name("elwood-fanout".into())
+            .spawn(move || {
+                for (index, agent_id) in agents.into_iter().enumerate() {
+                    // Creating a worktree runs git: keep it off the GUI thread
+                    // and outside the registry lock.
+                    let workspace =
+                        match multi_agent::prepare_workspace(&cwd, task_id, index, worktrees) {
+                            Ok(ws) => ws,
+                            Err(e) => {
+                                let warning =
+                                    format!("Subtask {}: {e}; using {}", index + 1, cwd.display());
+                                multi_agent::shared_registry()
+                                    .lock()
+                                    .add_task_warning(task_id, warning);
+                                Workspace::Dir(cwd.clone())
+                            }
+                        };
+                    let dir = workspace.path().display().to_string();
+                    multi_agent::shared_registry()
+                        .lock()
+                        .assign_subtask(task_id, index, agent_id, workspace);
+                    promise::spawn::spawn_into_main_thread(async move {
+                        spawn_agent_pane(pane_id, domain_id, agent_id, dir);
+                    })
+                    .detach();
+                }
+            });
+        if let Err(e) = spawned {
+            self.write_ansi(&screen::format_error(&format!(
+                "Failed to start task #{task_id}: {e}"
+            )));
+            return;
+        }
+
+        let dashboard = multi_agent::render_dashboard(&multi_agent::shared_registry().lock());
+        self.write_ansi(&screen::format_command_response(&format!(
+            "Started task #{task_id} with {count} agents; results will be collected here.\n\n{dashboard}"
+        )));
+    }
+
+    /// Split this pane and spawn an Elwood pane running `command` in the
+    /// new half. `on_error` runs if the split fails.
+    fn spawn_elwood_split(
+        &self,
+        command: CommandBuilder,
+        dir: Option<String>,
+        on_error: impl FnOnce(anyhow::Error) + 'static,
+    ) {
+        split_elwood_pane(self.pane_id, self.domain_id, command, dir, on_error);
+    }
+
+    /// Bind this pane to a registered agent and, for a fan-out agent, start
+    /// its subtask. Agents from `/agent spawn` wait for `/tell`.
+    ///
+    /// Called by the domain right after the pane is created.
+    pub fn attach_agent(&self, agent_id: u32) {
+        let prompt = {
+            let mut registry = multi_agent::shared_registry().lock();
+            if !registry.set_pane_id(agent_id, self.pane_id) {
+                return;
+            }
+            *self.agent_id.lock() = Some(agent_id);
+            match registry.subtask_for_agent(agent_id) {
+                Some((task_id, index)) => {
+                    registry.task(task_id).and_then(|t| t.subtask_prompt(index))
+                }
+                None => {
+                    registry.update_status(agent_id, AgentStatus::Idle);
+                    None
+                }
+            }
+        };
+        let Some(prompt) = prompt else {
+            self.greet_spawned_agent(agent_id);
+            return;
+        };
+
+        self.session_log.lock().log_user(&prompt);
+        let start_row = self.current_row();
+        self.block_manager.lock().push_agent_block(start_row);
+        self.write_ansi(&screen::format_user_prompt(&prompt));
+        self.write_ansi(&screen::format_assistant_prefix());
+        let output_row = self.current_row();
+        self.block_manager.lock().extend_output(output_row);
+
+        {
+            let mut ss = self.screen.lock();
+            ss.is_running = true;
+            ss.task_start = Some(Instant::now());
+            ss.task_elapsed_frozen = None;
+        }
+        *self.state.lock() = PaneState::Running;
+        self.refresh_status_bar();
+
+        // The domain started this pane's runtime in the agent's workspace;
+        // name it again so the runtime's cwd and the pane's never drift.
+        self.send_agent_request(AgentRequest::Start {
+            prompt,
+            session_id: format!("agent-{agent_id}"),
+            working_dir: Some(self.cwd.display().to_string()),
+        });
+    }
+
+    /// Name the model of an agent started by `/agent spawn` and switch to it.
+    fn greet_spawned_agent(&self, agent_id: u32) {
+        let agent = multi_agent::shared_registry()
+            .lock()
+            .get(agent_id)
+            .map(|a| (a.name.clone(), a.model.clone()));
+        let Some((name, model)) = agent else { return };
+        if !model.is_empty() && model != self.screen.lock().model_name {
+            let _ = self.bridge.send_request(AgentRequest::SwitchModel {
+                model_name: model.clone(),
+            });
+        }
+        self.write_ansi(&screen::format_command_response(&format!(
+            "Agent {name} (#{agent_id}) is ready. Send it work with /tell {name} <message>."
+        )));
+    }
+
+    /// Record the outcome of this pane's fan-out subtask in the shared registry.
+    fn track_subtask(&self, response: &AgentResponse) {
+        let Some(agent_id) = *self.agent_id.lock() else { return };
+        match response {
+            AgentResponse::ContentDelta(text) => self.agent_output.lock().push_str(text),
+            AgentResponse::TurnComplete { summary } => {
+                let output = std::mem::take(&mut *self.agent_output.lock());
+                let summary = summary
+                    .clone()
+                    .filter(|s| !s.trim().is_empty())
+                    .unwrap_or_else(|| tail_text(&output, 2000));
+
+                let (turn, workspace) = {
+                    let mut registry = multi_agent::shared_registry().lock();
+                    let Some(turn) = registry.subtask_turn(agent_id) else {
+                        // An `/agent spawn` agent just goes back to waiting
+                        registry.update_status(agent_id, AgentStatus::Idle);
+                        return;
+                    };
+                    (turn, registry.workspace_for_agent(agent_id).cloned())
+                };
+                // Diffs are only attributable to one agent in its own worktree;
+                // collecting one runs git, so keep it off the GUI thread. A
+                // follow-up sent meanwhile starts a new turn and makes this
+                // result stale.
+                let spawned = std::thread::Builder::new()
+                    .name("elwood-agent-diff".into())
+                    .spawn(move || {
+                        let diff = workspace
+                            .filter(|ws| matches!(ws, Workspace::Worktree { .. }))
+                            .and_then(|ws| multi_agent::collect_diff(&ws));
+                        multi_agent::shared_registry()
+                            .lock()
+                            .complete_turn(agent_id, turn, &summary, diff);
+                    });
+                if let Err(e) = spawned {
+                    log::warn!("Failed to collect agent diff: {e}");
+                }
+            }
+            AgentResponse::Error(msg) => {
+                self.agent_output.lock().clear();
+                multi_agent::shared_registry().lock().fail_subtask(agent_id, msg);
             }
+            _ => {}
+        }
+    }
+
+    /// Report fan-out tasks started from this pane once all their agents finish.
+    fn poll_fanout_results(&self) {
+        let finished = multi_agent::shared_registry().lock().take_finished(self.pane_id);
+        for task in finished {
+            let report = multi_agent::aggregate_results(&task);
+            self.session_log.lock().log_system(&report);
+            self.write_ansi("\r\n");
+            self.write_ansi(&screen::format_content(&report, &self.cwd));
+            self.write_ansi("\r\n");
+
+            let (done, total) = task.progress();
+            self.toast_manager.lock().push(
+                format!("Task #{} finished ({done}/{total})", task.id),
+                ToastLevel::Success,
+                None,
+                None,
+            );
+            self.render_toasts();
+            self.seqno.fetch_add(1, Ordering::Release);
         }
     }
 
@@ -1562,6 +3065,234 @@ impl ElwoodPane {
         self.write_ansi(&screen::format_command_response("Recording resumed"));
     }
 
+    /// `/record export` — render a recording to an animated SVG on a
+    /// background thread; [`Self::poll_svg_export`] reports the result.
+    fn handle_record_export(
+        &self,
+        source: &str,
+        output: Option<&str>,
+        from_marker: Option<String>,
+        to_marker: Option<String>,
+        max_idle: Option<f64>,
+    ) {
+        if self.svg_export.lock().is_some() {
+            self.write_ansi(&screen::format_error("An export is already running"));
+            return;
+        }
+        let path = replay::resolve_recording_path(source);
+        let out = output
+            .map(PathBuf::from)
+            .unwrap_or_else(|| recording_export::default_output_path(&path));
+
+        let mut config = crate::config::ElwoodConfig::load().recording_export;
+        if let Some(secs) = max_idle {
+            config.max_idle = secs;
+        }
+        let options = ExportOptions {
+            config,
+            palette: ExportPalette::from_theme(&theme::active(), &self.terminal.lock().palette()),
+            from_marker,
+            to_marker,
+        };
+
+        let (tx, rx) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("elwood-svg-export".into())
+            .spawn(move || {
+                let result = crate::recording::load_recording(&path)
+                    .map_err(|e| anyhow::anyhow!("Failed to load {}: {e}", path.display()))
+                    .and_then(|recording| {
+                        recording_export::export_to_file(&recording, &out, &options)
+                    });
+                let _ = tx.send(result);
+            });
+        match spawned {
+            Ok(_) => {
+                *self.svg_export.lock() = Some(rx);
+                self.write_ansi(&screen::format_command_response(&format!(
+                    "Exporting {source}..."
+                )));
+            }
+            Err(e) => self.write_ansi(&screen::format_error(&format!("Export failed: {e}"))),
+        }
+    }
+
+    /// Report a finished `/record export` with a toast.
+    fn poll_svg_export(&self) {
+        let result = {
+            let mut export = self.svg_export.lock();
+            let Some(rx) = export.as_ref() else {
+                return;
+            };
+            match rx.try_recv() {
+                Ok(result) => {
+                    *export = None;
+                    result
+                }
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    *export = None;
+                    Err(anyhow::anyhow!("export thread exited"))
+                }
+            }
+        };
+        let (msg, level) = match result {
+            Ok(summary) => (
+                format!(
+                    "Exported {} ({} frames, {:.1}s, {} KB)",
+                    summary.path.display(),
+                    summary.frames,
+                    summary.duration,
+                    summary.bytes.div_ceil(1024)
+                ),
+                ToastLevel::Success,
+            ),
+            Err(e) => (format!("Export failed: {e:#}"), ToastLevel::Error),
+        };
+        self.toast_manager.lock().push(msg, level, None, None);
+        self.render_toasts();
+    }
+
+    /// Add a chapter marker to the active recording, if any.
+    fn record_marker(&self, label: &str) {
+        let mut rec = self.recorder.lock();
+        if rec.is_recording() {
+            rec.record_marker(label);
+        }
+    }
+
+    /// `/replay <file>` — load a recording and play it on the alternate screen.
+    fn start_replay(&self, arg: &str) {
+        if self.replay.lock().is_some() {
+            return;
+        }
+        let path = replay::resolve_recording_path(arg);
+        let recording = match crate::recording::load_recording(&path) {
+            Ok(r) => r,
+            Err(e) => {
+                self.write_ansi(&screen::format_error(&format!(
+                    "Failed to load {}: {e}",
+                    path.display()
+                )));
+                return;
+            }
+        };
+
+        let (width, height) = {
+            let ss = self.screen.lock();
+            (ss.width as u32, ss.height as u32)
+        };
+        let header = &recording.header;
+        if header.width > width || header.height > height {
+            self.toast_manager.lock().push(
+                format!(
+                    "Recording is {}x{}, pane is {width}x{height}; output may wrap",
+                    header.width, header.height
+                ),
+                ToastLevel::Warning,
+                None,
+                None,
+            );
+        }
+
+        // The alternate screen keeps the chat intact underneath
+        self.write_terminal(&format!("\x1b[?1049h{}", replay::RESET_SEQUENCE));
+        *self.replay.lock() = Some(ActiveReplay {
+            player: ReplayPlayer::new(recording),
+            last_tick: Instant::now(),
+            backlog: String::new(),
+        });
+        self.render_replay_status();
+    }
+
+    /// Advance a playing replay by the wall time since the last poll.
+    fn poll_replay(&self) {
+        let frame = {
+            let mut guard = self.replay.lock();
+            let Some(active) = guard.as_mut() else { return };
+            let now = Instant::now();
+            let elapsed = now.duration_since(active.last_tick);
+            active.last_tick = now;
+            if !active.player.is_playing() {
+                return;
+            }
+            active.player.tick(elapsed)
+        };
+        self.apply_replay_frame(frame);
+    }
+
+    fn handle_replay_key(&self, key: KeyCode, mods: KeyModifiers) {
+        let frame = {
+            let mut guard = self.replay.lock();
+            let Some(active) = guard.as_mut() else { return };
+            let player = &mut active.player;
+            match (key, mods) {
+                (KeyCode::Char('q'), _) | (KeyCode::Escape, _) => None,
+                (KeyCode::Char(' '), _) => Some(player.toggle_pause()),
+                (KeyCode::RightArrow, _) | (KeyCode::Char('l'), _) => {
+                    Some(player.seek_by(replay::SEEK_STEP))
+                }
+                (KeyCode::LeftArrow, _) | (KeyCode::Char('h'), _) => {
+                    Some(player.seek_by(-replay::SEEK_STEP))
+                }
+                (KeyCode::Char(']'), _) => Some(player.next_marker().unwrap_or_default()),
+                (KeyCode::Char('['), _) => Some(player.prev_marker().unwrap_or_default()),
+                (KeyCode::Char('+'), _) | (KeyCode::Char('='), _) => {
+                    player.faster();
+                    Some(ReplayFrame::default())
+                }
+                (KeyCode::Char('-'), _) => {
+                    player.slower();
+                    Some(ReplayFrame::default())
+                }
+                (KeyCode::Char('0'), _) | (KeyCode::Home, _) => Some(player.seek(0.0)),
+                _ => Some(ReplayFrame::default()),
+            }
+        };
+
+        match frame {
+            Some(frame) => {
+                self.apply_replay_frame(frame);
+                self.render_replay_status();
+            }
+            None => self.stop_replay(),
+        }
+    }
+
+    /// Write a replay frame (if any) and redraw the transport bar.
+    fn apply_replay_frame(&self, frame: ReplayFrame) {
+        if frame.is_empty() {
+            return;
+        }
+        self.write_terminal(&frame.to_ansi());
+        self.render_replay_status();
+    }
+
+    fn render_replay_status(&self) {
+        let (width, height) = {
+            let ss = self.screen.lock();
+            (ss.width, ss.height)
+        };
+        let status = match self.replay.lock().as_ref() {
+            Some(active) => active.player.render_status(width, height),
+            None => return,
+        };
+        self.write_terminal(&status);
+    }
+
+    /// Leave replay mode and return to the chat.
+    fn stop_replay(&self) {
+        let Some(active) = self.replay.lock().take() else {
+            return;
+        };
+        self.write_terminal("\x1b[0m\x1b[?1049l");
+        if !active.backlog.is_empty() {
+            self.write_ansi(&active.backlog);
+        }
+        self.redraw_chrome();
+        self.render_toasts();
+    }
+
     /// `/bookmarks` — list all bookmarked blocks with their header summaries.
     fn handle_list_bookmarks(&self) {
         let mgr = self.block_manager.lock();
@@ -1590,29 +3321,315 @@ impl ElwoodPane {
                 block.id,
             ));
         }
-        msg.push_str("\nUse /export block <index> to export a block as markdown.");
+        msg.push_str("\nUse /export block <index> to export a block as markdown,");
+        msg.push_str("\nor /block <action> <index> to copy, rerun or share it.");
         drop(mgr);
         self.write_ansi(&screen::format_command_response(&msg));
     }
 
-    /// `/export block [index]` — export a block as markdown.
-    fn handle_export_block(&self, index: Option<usize>) {
-        let mgr = self.block_manager.lock();
+    /// `/export block [index]` — export a block as markdown.
+    fn handle_export_block(&self, index: Option<usize>) {
+        let mgr = self.block_manager.lock();
+
+        // Determine which block to export: explicit index, selected, or last
+        let target_index = index
+            .or_else(|| mgr.selected_index())
+            .unwrap_or_else(|| mgr.len().saturating_sub(1));
+
+        match mgr.export_block_markdown(target_index) {
+            Some(markdown) => {
+                drop(mgr);
+                self.write_ansi(&screen::format_command_response(&markdown));
+            }
+            None => {
+                drop(mgr);
+                self.write_ansi(&screen::format_command_response(&format!(
+                    "No block at index {target_index}.\nUse /bookmarks to see available blocks."
+                )));
+            }
+        }
+    }
+
+    /// `/block <action> [index]` — act on a block's command or output.
+    ///
+    /// Targets the explicit index, else the selected block, else the last one.
+    fn handle_block_action(&self, action: BlockAction, index: Option<usize>) {
+        let target = {
+            let mgr = self.block_manager.lock();
+            let idx = index
+                .or_else(|| mgr.selected_index())
+                .unwrap_or_else(|| mgr.len().saturating_sub(1));
+            mgr.blocks().get(idx).cloned()
+        };
+        let Some(target) = target else {
+            self.write_ansi(&screen::format_command_response(
+                "No block to act on.\nUse /bookmarks to see available blocks.",
+            ));
+            return;
+        };
+
+        let command = self.block_command_text(&target);
+        let output = self.block_output_text(&target);
+
+        match action {
+            BlockAction::CopyCommand => match command {
+                Some(cmd) => self.copy_to_clipboard(&cmd, "command"),
+                None => self.write_ansi(&screen::format_error(&format!(
+                    "Block #{} has no command",
+                    target.id
+                ))),
+            },
+            BlockAction::CopyOutput => self.copy_to_clipboard(&output, "output"),
+            BlockAction::Rerun => {
+                let Some(command) = command else {
+                    self.write_ansi(&screen::format_error(&format!(
+                        "Block #{} has no command to rerun",
+                        target.id
+                    )));
+                    return;
+                };
+                let start_row = self.current_row();
+                self.block_manager
+                    .lock()
+                    .push_command_block(start_row, &command, target.cwd.clone());
+                self.write_ansi(&screen::format_command_prompt(&command));
+                let output_row = self.current_row();
+                self.block_manager.lock().extend_output(output_row);
+                self.session_log.lock().log_command(&command);
+                {
+                    let mut ss = self.screen.lock();
+                    ss.is_running = true;
+                    ss.task_start = Some(Instant::now());
+                    ss.task_elapsed_frozen = None;
+                }
+                *self.state.lock() = PaneState::Running;
+                self.refresh_status_bar();
+                let working_dir = target
+                    .cwd
+                    .as_ref()
+                    .map(|p| p.to_string_lossy().to_string());
+                let _ = self.bridge.send_request(AgentRequest::RunCommand {
+                    command,
+                    working_dir,
+                    env: HashMap::new(),
+                });
+            }
+            BlockAction::SendToAgent => {
+                let content = block::format_block_for_agent(&target, command.as_deref(), &output);
+                self.session_log.lock().log_user(&content);
+                self.write_ansi(&screen::format_user_prompt(&format!(
+                    "[Block #{} sent as context]",
+                    target.id
+                )));
+                self.write_ansi(&screen::format_assistant_prefix());
+                {
+                    let mut ss = self.screen.lock();
+                    ss.is_running = true;
+                    ss.task_start = Some(Instant::now());
+                    ss.task_elapsed_frozen = None;
+                }
+                *self.state.lock() = PaneState::Running;
+                self.refresh_status_bar();
+                self.send_agent_request(AgentRequest::SendMessage { content });
+            }
+            BlockAction::SaveOutput(path) => {
+                let path = path
+                    .unwrap_or_else(|| PathBuf::from(block::default_block_filename(&target, "txt")));
+                self.write_block_file(&path, &output);
+            }
+            BlockAction::ExportHtml(path) => {
+                let path = path
+                    .unwrap_or_else(|| PathBuf::from(block::default_block_filename(&target, "html")));
+                let html = crate::session_export::export_block_html(
+                    &target,
+                    command.as_deref(),
+                    &output,
+                );
+                self.write_block_file(&path, &html);
+            }
+        }
+    }
+
+    /// Write `contents` to `path` and report the outcome in the chat area.
+    fn write_block_file(&self, path: &std::path::Path, contents: &str) {
+        match std::fs::write(path, contents) {
+            Ok(()) => self.write_ansi(&screen::format_command_response(&format!(
+                "Saved {} bytes to: {}",
+                contents.len(),
+                path.display()
+            ))),
+            Err(e) => self.write_ansi(&screen::format_error(&format!(
+                "Failed to write {}: {e}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// The command text of a block.
+    ///
+    /// Prefers the command recorded when the block was created, falling back
+    /// to the text of the input zone (shell blocks from OSC 133 zones).
+    fn block_command_text(&self, block: &block::Block) -> Option<String> {
+        if let Some(cmd) = &block.command {
+            return Some(cmd.clone());
+        }
+        let zone = block.input_zone?;
+        let text = self.zone_text(zone.start_y..zone.end_y + 1);
+        let text = text.trim();
+        if text.is_empty() {
+            None
+        } else {
+            Some(text.to_string())
+        }
+    }
+
+    /// The text of a block's output zone, with trailing blank lines removed.
+    fn block_output_text(&self, block: &block::Block) -> String {
+        block
+            .output_zone
+            .map(|z| self.zone_text(z.start_y..z.end_y + 1))
+            .map(|t| t.trim_end().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Read the plain text of a row range from the virtual terminal.
+    fn zone_text(&self, rows: Range<StableRowIndex>) -> String {
+        let mut terminal = self.terminal.lock();
+        let (_, lines) = terminal_get_lines(&mut terminal, rows);
+        lines
+            .iter()
+            .map(|l| l.as_str().trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Place `text` on the system clipboard and confirm in the chat area.
+    fn copy_to_clipboard(&self, text: &str, what: &str) {
+        let clipboard = self.clipboard.lock().clone();
+        let Some(clipboard) = clipboard else {
+            self.write_ansi(&screen::format_error("Clipboard is not available in this pane"));
+            return;
+        };
+        match clipboard.set_contents(ClipboardSelection::Clipboard, Some(text.to_string())) {
+            Ok(()) => {
+                let msg = format!(
+                    "\r\n\x1b[2m[Copied {} bytes of block {what}]\x1b[0m\r\n",
+                    text.len(),
+                );
+                self.write_ansi(&msg);
+            }
+            Err(e) => self.write_ansi(&screen::format_error(&format!("Copy failed: {e}"))),
+        }
+    }
+
+    /// Write the input buffer to a temp file and open it in the user's editor
+    /// in a split pane. The result is read back by `poll_external_edit`.
+    fn open_input_in_editor(&self) {
+        if self.external_edit.lock().is_some() {
+            self.write_ansi(&screen::format_error("An external editor is already open"));
+            return;
+        }
+
+        let config = ide_bridge::EditorConfig::load();
+        let argv = external_editor::editor_argv(
+            std::env::var("VISUAL").ok().as_deref(),
+            std::env::var("EDITOR").ok().as_deref(),
+            ide_bridge::EditorDetector::cached(),
+            config.preferred.as_deref(),
+        );
+        let Some(mut argv) = argv else {
+            self.write_ansi(&screen::format_error(
+                "No editor found: set $VISUAL or $EDITOR, or install one of: nvim, vim, code, zed",
+            ));
+            return;
+        };
+
+        let content = self.input_editor.lock().content();
+        let path = match external_editor::write_buffer(&std::env::temp_dir(), &content) {
+            Ok(p) => p,
+            Err(e) => {
+                self.write_ansi(&screen::format_error(&format!(
+                    "Failed to write temp file: {e}"
+                )));
+                return;
+            }
+        };
+
+        let editor_name = argv[0].clone();
+        argv.push(path.display().to_string());
+        let cwd = self.cwd.to_string_lossy().to_string();
+        let mut command = CommandBuilder::from_argv(argv.into_iter().map(Into::into).collect());
+        command.cwd(&cwd);
+
+        let status = Arc::new(Mutex::new(ExternalEditStatus::Spawning));
+        *self.external_edit.lock() = Some(ExternalEdit {
+            path,
+            status: Arc::clone(&status),
+        });
+
+        let pane_id = self.pane_id;
+        promise::spawn::spawn(async move {
+            let request = SplitRequest {
+                direction: SplitDirection::Vertical,
+                target_is_second: true,
+                top_level: false,
+                size: SplitSize::Percent(50),
+            };
+            let source = SplitSource::Spawn {
+                command: Some(command),
+                command_dir: Some(cwd),
+            };
+            let result = Mux::get()
+                .split_pane(pane_id, request, source, SpawnTabDomain::DefaultDomain)
+                .await;
+            *status.lock() = match result {
+                Ok((pane, _size)) => ExternalEditStatus::Running(pane.pane_id()),
+                Err(e) => ExternalEditStatus::Failed(e.to_string()),
+            };
+        })
+        .detach();
+
+        self.write_ansi(&screen::format_command_response(&format!(
+            "Editing input in {editor_name} — save and quit to return"
+        )));
+    }
 
-        // Determine which block to export: explicit index, selected, or last
-        let target_index = index
-            .or_else(|| mgr.selected_index())
-            .unwrap_or_else(|| mgr.len().saturating_sub(1));
+    /// Check on the Ctrl+X Ctrl+E editor pane; once it has exited, load the
+    /// edited text back into the input box.
+    fn poll_external_edit(&self) {
+        let status = match self.external_edit.lock().as_ref() {
+            Some(edit) => edit.status.lock().clone(),
+            None => return,
+        };
 
-        match mgr.export_block_markdown(target_index) {
-            Some(markdown) => {
-                drop(mgr);
-                self.write_ansi(&screen::format_command_response(&markdown));
+        match status {
+            ExternalEditStatus::Spawning => {}
+            ExternalEditStatus::Running(id) => {
+                let alive = Mux::try_get()
+                    .and_then(|mux| mux.get_pane(id))
+                    .is_some_and(|pane| !pane.is_dead());
+                if alive {
+                    return;
+                }
+                let Some(edit) = self.external_edit.lock().take() else { return };
+                match external_editor::read_buffer(&edit.path) {
+                    Ok(text) => {
+                        self.input_editor.lock().set_content(&text);
+                        self.sync_editor_to_screen();
+                        self.refresh_input_box();
+                        self.seqno.fetch_add(1, Ordering::Release);
+                    }
+                    Err(e) => self.write_ansi(&screen::format_error(&format!(
+                        "Failed to read edited input: {e}"
+                    ))),
+                }
             }
-            None => {
-                drop(mgr);
-                self.write_ansi(&screen::format_command_response(&format!(
-                    "No block at index {target_index}.\nUse /bookmarks to see available blocks."
+            ExternalEditStatus::Failed(err) => {
+                if let Some(edit) = self.external_edit.lock().take() {
+                    let _ = std::fs::remove_file(&edit.path);
+                }
+                self.write_ansi(&screen::format_error(&format!(
+                    "Failed to open editor pane: {err}"
                 )));
             }
         }
@@ -1628,11 +3645,91 @@ impl ElwoodPane {
         ss.ghost_text = editor.ghost_text().map(String::from);
         // Keep legacy field in sync for single-line fallback
         ss.input_text = editor.lines().first().cloned().unwrap_or_default();
+        ss.vim_state = editor.vim_state();
+        ss.vim_command_buffer = match editor.vim() {
+            Some(vim) if vim.state() == VimState::Search => vim.command_line(),
+            Some(vim) => vim.command_buffer().to_string(),
+            None => String::new(),
+        };
+    }
+
+    /// The `(key, ctrl)` pair vim mode should handle for this key, if vim
+    /// is on and wants it.
+    ///
+    /// Enter still submits and Escape still cancels from Normal mode; in
+    /// Insert mode the editor's Ctrl bindings keep working.
+    fn vim_key_for(&self, key: KeyCode, mods: KeyModifiers) -> Option<(char, bool)> {
+        let state = self.input_editor.lock().vim_state()?;
+        let in_line = matches!(state, VimState::Command | VimState::Search);
+        match key {
+            KeyCode::Char(c) if mods.is_empty() || mods == KeyModifiers::SHIFT => Some((c, false)),
+            KeyCode::Char(c) if mods == KeyModifiers::CTRL => {
+                (state != VimState::Insert || c == '[').then_some((c, true))
+            }
+            KeyCode::Escape if state != VimState::Normal => Some(('\x1b', false)),
+            KeyCode::Enter if mods.is_empty() && in_line => Some(('\r', false)),
+            KeyCode::Backspace if mods.is_empty() && (in_line || state == VimState::Insert) => {
+                Some(('\x7f', false))
+            }
+            _ => None,
+        }
+    }
+
+    /// Carry out what a vim key asked of the pane.
+    fn apply_vim_effects(&self, effects: Vec<VimEffect>) {
+        for effect in effects {
+            match effect {
+                VimEffect::Submit => self.submit_editor(),
+                VimEffect::SetClipboard(text) => {
+                    let clipboard = self.clipboard.lock().clone();
+                    let Some(clipboard) = clipboard else {
+                        self.write_ansi(&screen::format_error(
+                            "Clipboard is not available in this pane",
+                        ));
+                        continue;
+                    };
+                    if let Err(e) =
+                        clipboard.set_contents(ClipboardSelection::Clipboard, Some(text))
+                    {
+                        self.write_ansi(&screen::format_error(&format!("Copy failed: {e}")));
+                    }
+                }
+                VimEffect::Message(msg) => {
+                    self.write_ansi(&screen::format_command_response(&msg));
+                }
+            }
+        }
+    }
+
+    /// Handle `/vim on|off|status`.
+    fn handle_vim_toggle(&self, action: VimToggleAction) {
+        let msg = {
+            let mut editor = self.input_editor.lock();
+            match action {
+                VimToggleAction::On => {
+                    editor.vim_enable();
+                    if let Some(vim) = editor.vim_mut() {
+                        vim.set_clipboard_reader(read_system_clipboard);
+                    }
+                }
+                VimToggleAction::Off => editor.vim_disable(),
+                VimToggleAction::Status => {}
+            }
+            if editor.vim_enabled() {
+                "Vim mode is on (Esc for Normal mode, /vim off to disable)"
+            } else {
+                "Vim mode is off (/vim on to enable)"
+            }
+        };
+        self.sync_editor_to_screen();
+        self.refresh_input_box();
+        self.refresh_status_bar();
+        self.write_ansi(&screen::format_command_response(msg));
     }
 
     /// Update ghost text suggestion based on current editor content.
     fn update_ghost_text(&self) {
-        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let cwd = self.cwd.clone();
         let input = self.input_editor.lock().content();
 
         // Try completions with symbol index for richer suggestions
@@ -1649,6 +3746,91 @@ impl ElwoodPane {
         self.input_editor.lock().set_ghost_text(ghost);
     }
 
+    /// Open the completion dropdown for the current input.
+    fn open_completion_menu(&self) {
+        let cwd = self.cwd.clone();
+        let input = self.input_editor.lock().content();
+        let bridge_guard = self.semantic_bridge.lock();
+        let completions = self
+            .completion_engine
+            .lock()
+            .get_completions_with_symbols(&input, &cwd, bridge_guard.as_ref());
+        drop(bridge_guard);
+
+        let menu = CompletionMenu::new(&input, completions);
+        if menu.is_none() {
+            self.toast_manager
+                .lock()
+                .push("No completions".into(), ToastLevel::Info, None, None);
+            self.render_toasts();
+        }
+        *self.completion_menu.lock() = menu;
+        self.render_completion_menu();
+    }
+
+    fn render_completion_menu(&self) {
+        let menu = self.completion_menu.lock();
+        if let Some(ref menu) = *menu {
+            let ss = self.screen.lock();
+            let rendered = menu.render(ss.width, ss.input_top());
+            drop(ss);
+            self.write_chrome(&rendered);
+        }
+    }
+
+    fn close_completion_menu(&self) {
+        if let Some(menu) = self.completion_menu.lock().take() {
+            let ss = self.screen.lock();
+            let erased = menu.erase(ss.width, ss.input_top());
+            drop(ss);
+            self.write_ansi(&erased);
+        }
+    }
+
+    /// Handle a key while the completion dropdown is open. Returns `false`
+    /// for keys that should close it and fall through to the editor.
+    fn handle_completion_menu_key(&self, key: KeyCode, mods: KeyModifiers) -> bool {
+        match key {
+            KeyCode::UpArrow => {
+                if let Some(ref mut menu) = *self.completion_menu.lock() {
+                    menu.select_prev();
+                }
+                self.render_completion_menu();
+            }
+            KeyCode::DownArrow => {
+                if let Some(ref mut menu) = *self.completion_menu.lock() {
+                    menu.select_next();
+                }
+                self.render_completion_menu();
+            }
+            KeyCode::Tab | KeyCode::Enter if mods.is_empty() => {
+                let text = self
+                    .completion_menu
+                    .lock()
+                    .as_ref()
+                    .map(|m| m.selected().text.clone());
+                self.close_completion_menu();
+                if let Some(text) = text {
+                    let mut editor = self.input_editor.lock();
+                    editor.clear();
+                    for c in text.chars() {
+                        editor.insert_char(c);
+                    }
+                    drop(editor);
+                    self.update_ghost_text();
+                    self.sync_editor_to_screen();
+                    self.refresh_input_box();
+                }
+            }
+            KeyCode::Escape => self.close_completion_menu(),
+            _ => {
+                self.close_completion_menu();
+                return false;
+            }
+        }
+        true
+    }
+
     /// Record submitted text to the completion engine and history search.
     fn record_submission(&self, text: &str, mode: InputMode) {
         self.completion_engine.lock().add_history(text.to_string());
@@ -1657,7 +3839,7 @@ impl ElwoodPane {
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
-        let cwd = std::env::current_dir().ok().map(|p| p.to_string_lossy().to_string());
+        let cwd = Some(self.cwd.to_string_lossy().to_string());
         self.history_search.lock().add_entry(HistoryRecord {
             text: text.to_string(),
             timestamp,
@@ -1681,6 +3863,9 @@ impl ElwoodPane {
             "fuzzy_finder" => {
                 self.open_fuzzy_finder();
             }
+            "quickfix" => self.handle_quickfix_action(QuickfixAction::Open),
+            "notifications" => self.handle_notifications_action(NotificationsAction::Open),
+            "sessions" => self.handle_sessions_action(SessionsAction::Search(String::new())),
             cmd if cmd.starts_with('/') => {
                 // Route slash commands through the normal command handler
                 if let Some((cmd_name, args)) = commands::parse_command(cmd) {
@@ -1700,7 +3885,7 @@ impl ElwoodPane {
         let rendered = palette.render(width);
         drop(palette);
         if !rendered.is_empty() {
-            self.write_ansi(&rendered);
+            self.write_chrome(&rendered);
         }
     }
 
@@ -1711,7 +3896,7 @@ impl ElwoodPane {
         let rendered = hs.render(width);
         drop(hs);
         if !rendered.is_empty() {
-            self.write_ansi(&rendered);
+            self.write_chrome(&rendered);
         }
     }
 
@@ -1719,7 +3904,7 @@ impl ElwoodPane {
 
     /// Open the fuzzy finder overlay with all sources.
     fn open_fuzzy_finder(&self) {
-        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let cwd = self.cwd.clone();
 
         // Collect history texts for the HistorySource
         let history_texts: Vec<String> = self
@@ -1750,7 +3935,7 @@ impl ElwoodPane {
             drop(ss);
             drop(ff);
             if !rendered.is_empty() {
-                self.write_ansi(&rendered);
+                self.write_chrome(&rendered);
             }
         }
     }
@@ -1852,7 +4037,7 @@ impl ElwoodPane {
     fn toggle_file_browser(&self) {
         let mut fb = self.file_browser.lock();
         if fb.is_some() { *fb = None; } else {
-            let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+            let cwd = self.cwd.clone();
             *fb = Some(FileTree::new(cwd));
             drop(fb); self.render_file_browser_overlay();
         }
@@ -1862,10 +4047,15 @@ impl ElwoodPane {
         if let Some(ref mut tree) = *fb {
             let ss = self.screen.lock();
             let rendered = tree.render(ss.width, ss.height);
-            drop(ss); drop(fb); self.write_ansi(&rendered);
+            drop(ss); drop(fb); self.write_chrome(&rendered);
         }
     }
     fn handle_file_browser_key(&self, key: KeyCode, mods: KeyModifiers) {
+        let prompt = self.file_browser.lock().as_ref().and_then(|t| t.prompt.as_ref().map(|p| p.kind.is_confirmation()));
+        if let Some(confirming) = prompt {
+            self.handle_file_browser_prompt_key(key, mods, confirming);
+            return;
+        }
         let filter_active = self.file_browser.lock().as_ref().map(|t| t.filter_active).unwrap_or(false);
         if filter_active {
             match key {
@@ -1885,15 +4075,52 @@ impl ElwoodPane {
             KeyCode::Char(' ') if mods.is_empty() => {
                 if let Some(ref mut t) = *self.file_browser.lock() {
                     let is_dir = t.selected_entry().map(|e| e.entry_type == crate::file_browser::EntryType::Directory).unwrap_or(false);
-                    if is_dir { t.toggle_expand(); } else { t.show_preview = !t.show_preview; }
+                    if is_dir { t.toggle_expand(); } else { t.toggle_mark(); }
                 }
                 self.render_file_browser_overlay();
             }
             KeyCode::Char('@') if mods.is_empty() || mods == KeyModifiers::SHIFT => { self.file_browser_action_attach(); }
             KeyCode::Char('/') if mods.is_empty() => { if let Some(ref mut t) = *self.file_browser.lock() { t.filter_active = true; } self.render_file_browser_overlay(); }
+            KeyCode::Char(c @ ('p' | 'a' | 'A' | 'r' | 'd' | 'm' | 'i')) if mods.is_empty() || mods == KeyModifiers::SHIFT => {
+                if let Some(ref mut t) = *self.file_browser.lock() {
+                    match c {
+                        'p' => t.show_preview = !t.show_preview,
+                        'a' => t.begin_new_file(),
+                        'A' => t.begin_new_dir(),
+                        'r' => t.begin_rename(),
+                        'd' => t.begin_delete(),
+                        'm' => t.begin_move(),
+                        _ => t.toggle_show_ignored(),
+                    }
+                }
+                self.render_file_browser_overlay();
+            }
             _ => {}
         }
     }
+    /// Keys while the file browser's prompt row is active (a name, a move
+    /// destination, or a y/n confirmation).
+    fn handle_file_browser_prompt_key(&self, key: KeyCode, mods: KeyModifiers, confirming: bool) {
+        let submit = match key {
+            KeyCode::Escape => { if let Some(ref mut t) = *self.file_browser.lock() { t.cancel_prompt(); } false }
+            KeyCode::Char('y' | 'Y') if confirming => true,
+            KeyCode::Char(_) | KeyCode::Enter if confirming => { if let Some(ref mut t) = *self.file_browser.lock() { t.cancel_prompt(); } false }
+            KeyCode::Enter => true,
+            KeyCode::Backspace => { if let Some(ref mut t) = *self.file_browser.lock() { t.prompt_backspace(); } false }
+            KeyCode::Char(c) if mods.is_empty() || mods == KeyModifiers::SHIFT => { if let Some(ref mut t) = *self.file_browser.lock() { t.prompt_insert_char(c); } false }
+            _ => false,
+        };
+        if submit {
+            let result = self.file_browser.lock().as_mut().and_then(|t| t.submit_prompt());
+            match result {
+                Some(Ok(msg)) => { self.toast_manager.lock().push(msg, ToastLevel::Success, None, None); }
+                Some(Err(e)) => { self.toast_manager.lock().push(format!("{e:#}"), ToastLevel::Error, None, None); }
+                None => {}
+            }
+            self.render_toasts();
+        }
+        self.render_file_browser_overlay();
+    }
     fn file_browser_action_open(&self) {
         let info = { let mut fb = self.file_browser.lock(); fb.as_mut().and_then(|tree| { let entry = tree.selected_entry()?; let et = entry.entry_type; let p = entry.path.clone(); if et == crate::file_browser::EntryType::Directory { tree.toggle_expand(); None } else { Some(p) } }) };
         if info.is_none() { self.render_file_browser_overlay(); return; }
@@ -1902,21 +4129,436 @@ impl ElwoodPane {
         let editor_cmd = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
         let command = format!("{editor_cmd} {}", path.display());
         self.write_ansi(&screen::format_command_prompt(&command));
-        let working_dir = std::env::current_dir().ok().map(|p| p.to_string_lossy().to_string());
-        let _ = self.bridge.send_request(AgentRequest::RunCommand { command, working_dir });
+        let working_dir = Some(self.cwd.to_string_lossy().to_string());
+        let _ = self.bridge.send_request(AgentRequest::RunCommand {
+            command,
+            working_dir,
+            env: HashMap::new(),
+        });
+    }
+    fn file_browser_action_attach(&self) {
+        let paths = self.file_browser.lock().as_ref().map(|tree| tree.attach_targets()).unwrap_or_default();
+        if paths.is_empty() { return; }
+        *self.file_browser.lock() = None;
+        let cwd = self.cwd.clone();
+        let refs: Vec<String> = paths.iter().map(|p| format!("@{}", p.strip_prefix(&cwd).unwrap_or(p).to_string_lossy())).collect();
+        { let mut ed = self.input_editor.lock(); for c in refs.iter().flat_map(|r| r.chars().chain(std::iter::once(' '))) { ed.insert_char(c); } }
+        self.sync_editor_to_screen(); self.refresh_input_box();
+        self.write_ansi(&format!("\n\x1b[38;2;86;95;137m\x1b[2m[Attached: {}]\x1b[0m\n", refs.join(", ")));
+    }
+
+    // ── Quickfix list (Alt+Q) ──────────────────────────────────────────────
+
+    /// Rebuild the quickfix list from the errors visible in sibling panes.
+    fn refresh_quickfix(&self) {
+        let panes = self
+            .pane_observer
+            .scan_sibling_panes()
+            .into_iter()
+            .map(|snap| PaneErrors {
+                pane_id: snap.pane_id,
+                detections: self.detector.detect_errors(&snap.lines),
+                title: snap.title,
+                cwd: snap.cwd,
+            })
+            .collect();
+        self.quickfix.lock().rebuild(panes, Some(self.cwd.clone()));
+    }
+
+    fn handle_quickfix_action(&self, action: QuickfixAction) {
+        match action {
+            QuickfixAction::Open => {
+                self.refresh_quickfix();
+                self.quickfix.lock().open();
+                self.render_quickfix_overlay();
+            }
+            QuickfixAction::Next | QuickfixAction::Prev => {
+                self.refresh_quickfix();
+                let entry = {
+                    let mut qf = self.quickfix.lock();
+                    let entry = if action == QuickfixAction::Next {
+                        qf.select_next()
+                    } else {
+                        qf.select_prev()
+                    };
+                    entry.cloned()
+                };
+                match entry {
+                    Some(entry) => self.open_quickfix_entry(&entry),
+                    None => self.write_ansi(&screen::format_command_response(
+                        "No errors with file locations in observed panes.",
+                    )),
+                }
+                if self.quickfix.lock().is_open() {
+                    self.render_quickfix_overlay();
+                }
+            }
+            QuickfixAction::FixFile => {
+                let group = self.quickfix.lock().selected_group().cloned();
+                match group {
+                    Some(group) => self.send_quickfix_group(&group),
+                    None => self.write_ansi(&screen::format_command_response(
+                        "Quickfix list is empty. Open it with /quickfix first.",
+                    )),
+                }
+            }
+        }
+    }
+
+    /// Open an entry's location in the preferred editor.
+    fn open_quickfix_entry(&self, entry: &QuickfixEntry) {
+        let config = ide_bridge::EditorConfig::load();
+        let editor = ide_bridge::EditorDetector::cached().best_editor(config.preferred.as_deref());
+        let result = match editor {
+            Some(editor) => {
+                ide_bridge::EditorBridge::open_file(editor, &entry.path, entry.line, entry.column)
+            }
+            None => Err("No editor detected. Install one of: cursor, zed, code, subl, nvim".into()),
+        };
+        let (msg, level) = match result {
+            Ok(_) => {
+                let qf = self.quickfix.lock();
+                let pos = entry.position();
+                let at = if pos.is_empty() { String::new() } else { format!(":{pos}") };
+                (
+                    format!(
+                        "[{}/{}] {}{at}",
+                        qf.selected_index() + 1,
+                        qf.len(),
+                        qf.display_path(&entry.path)
+                    ),
+                    ToastLevel::Info,
+                )
+            }
+            Err(e) => (e, ToastLevel::Error),
+        };
+        self.toast_manager.lock().push(msg, level, None, None);
+        self.render_toasts();
+    }
+
+    /// Ask the agent to fix every error in one file.
+    fn send_quickfix_group(&self, group: &quickfix::QuickfixGroup) {
+        let prompt = quickfix::fix_file_prompt(group);
+        let label = self.quickfix.lock().display_path(&group.path).to_string();
+        self.write_ansi(&screen::format_user_prompt(&format!(
+            "[Quickfix] Fix {} error{} in {label}",
+            group.entries.len(),
+            if group.entries.len() == 1 { "" } else { "s" },
+        )));
+        self.write_ansi(&screen::format_assistant_prefix());
+        {
+            let mut ss = self.screen.lock();
+            ss.is_running = true;
+            ss.task_start = Some(Instant::now());
+            ss.task_elapsed_frozen = None;
+        }
+        *self.state.lock() = PaneState::Running;
+        self.send_agent_request(AgentRequest::SendMessage { content: prompt });
+        self.refresh_status_bar();
+    }
+
+    /// Start `/bg <command>`: register the job and hand it to the runtime.
+    fn start_background_job(&self, command: String) {
+        let (job_id, log_path) = {
+            let mut jm = self.jobs.lock();
+            jm.enforce_retention();
+            let id = jm.create_job(command.clone(), None);
+            let path = jobs::log_path_for(&jobs::jobs_dir(), id);
+            if let Some(job) = jm.get_mut(id) {
+                job.log_path = Some(path.clone());
+            }
+            (id, path)
+        };
+        let working_dir = Some(self.cwd.to_string_lossy().to_string());
+
+        let sent = self.bridge.send_request(AgentRequest::RunBackgroundCommand {
+            job_id,
+            command: command.clone(),
+            working_dir,
+            log_path: Some(log_path.clone()),
+        });
+        if sent.is_err() {
+            if let Some(job) = self.jobs.lock().get_mut(job_id) {
+                job.fail("agent runtime is not running");
+            }
+            self.write_ansi(&screen::format_error("Failed to start job: agent runtime is not running"));
+            return;
+        }
+
+        self.screen.lock().running_jobs = self.jobs.lock().running_count();
+        self.write_ansi(&screen::format_command_response(&format!(
+            "[{job_id}] {command}\nLog: {}",
+            log_path.display()
+        )));
+        self.refresh_status_bar();
+    }
+
+    /// Apply a `JobUpdate` from the runtime; toast when the job finishes and
+    /// resume a `/jobs wait` turn.
+    fn handle_job_update(
+        &self,
+        job_id: JobId,
+        status: &str,
+        output_chunk: Option<String>,
+        is_stderr: bool,
+        exit_code: Option<i32>,
+        pid: Option<u32>,
+    ) {
+        let finished = {
+            let mut jm = self.jobs.lock();
+            let Some(job) = jm.get_mut(job_id) else { return };
+            if pid.is_some() {
+                job.pid = pid;
+            }
+            if let Some(line) = output_chunk {
+                if is_stderr {
+                    job.append_stderr(line);
+                } else {
+                    job.append_stdout(line);
+                }
+            }
+            match (status, exit_code) {
+                ("completed" | "failed", Some(code)) => job.complete(code),
+                ("completed" | "failed", None) => job.fail("terminated by signal"),
+                ("cancelled", _) => job.cancel(),
+                _ => {}
+            }
+            (status != "running").then(|| job.clone())
+        };
+
+        let Some(job) = finished else { return };
+        self.screen.lock().running_jobs = self.jobs.lock().running_count();
+        self.refresh_status_bar();
+
+        let level = match job.status {
+            JobStatus::Completed => ToastLevel::Success,
+            JobStatus::Failed => ToastLevel::Error,
+            JobStatus::Cancelled | JobStatus::Running => ToastLevel::Warning,
+        };
+        self.toast_manager.lock().push(job.summary(), level, None, None);
+        self.render_toasts();
+
+        let waiting = {
+            let mut wait = self.job_wait.lock();
+            if *wait == Some(job_id) {
+                wait.take()
+            } else {
+                None
+            }
+        };
+        if waiting.is_some() {
+            self.send_job_report(&job);
+        }
+    }
+
+    fn handle_job_action(&self, action: JobAction) {
+        let id = match action {
+            JobAction::Foreground(id) | JobAction::Wait(id) | JobAction::Log(id) => id,
+        };
+        let Some(job) = self.jobs.lock().get(id).cloned() else {
+            self.write_ansi(&screen::format_command_response(&format!(
+                "No job #{id}. Use /jobs to see job IDs."
+            )));
+            return;
+        };
+
+        match action {
+            JobAction::Foreground(_) => self.foreground_job(&job),
+            JobAction::Log(_) => {
+                let msg = match job.log_path {
+                    Some(ref path) => format!("Job #{id} log: {}", path.display()),
+                    None => format!("Job #{id} has no log file"),
+                };
+                self.write_ansi(&screen::format_command_response(&msg));
+            }
+            JobAction::Wait(_) if job.is_finished() => self.send_job_report(&job),
+            JobAction::Wait(_) => {
+                *self.job_wait.lock() = Some(id);
+                {
+                    let mut ss = self.screen.lock();
+                    ss.is_running = true;
+                    ss.task_start = Some(Instant::now());
+                    ss.task_elapsed_frozen = None;
+                    ss.active_tool = Some(format!("waiting for job #{id}"));
+                }
+                *self.state.lock() = PaneState::Running;
+                self.write_ansi(&screen::format_command_response(&format!(
+                    "Waiting for job #{id}: {} — the agent continues when it finishes (Esc to stop waiting)",
+                    job.command
+                )));
+                self.refresh_status_bar();
+            }
+        }
+    }
+
+    /// Reset the running state set by `/jobs wait`.
+    fn end_job_wait(&self) {
+        *self.state.lock() = PaneState::Idle;
+        let mut ss = self.screen.lock();
+        ss.is_running = false;
+        ss.active_tool = None;
+        ss.task_elapsed_frozen = ss.task_start.map(|s| s.elapsed().as_secs());
+        ss.task_start = None;
+        drop(ss);
+        self.refresh_status_bar();
+    }
+
+    /// Hand a finished job's result to the agent as the next message.
+    fn send_job_report(&self, job: &jobs::Job) {
+        self.send_to_agent(&format!("[Jobs] {}", job.summary()), job.wait_report());
+    }
+
+    /// Show `display` as the user prompt and send `content` to the agent.
+    fn send_to_agent(&self, display: &str, content: String) {
+        self.write_ansi(&screen::format_user_prompt(display));
+        self.write_ansi(&screen::format_assistant_prefix());
+        {
+            let mut ss = self.screen.lock();
+            ss.is_running = true;
+            ss.active_tool = None;
+            if ss.task_start.is_none() {
+                ss.task_start = Some(Instant::now());
+            }
+            ss.task_elapsed_frozen = None;
+        }
+        *self.state.lock() = PaneState::Running;
+        self.send_agent_request(AgentRequest::SendMessage { content });
+        self.refresh_status_bar();
+    }
+
+    /// `/launch apply` — set up this pane from a launch config, then build
+    /// the config's tabs around it.
+    fn apply_launch_config(&self, apply: ApplyResult) {
+        // The directory and env vars apply to the startup commands only;
+        // layout panes get their own through `layout_command`
+        let mut notes = Vec::new();
+        let working_dir = match apply.working_dir {
+            Some(dir) if Path::new(&dir).is_dir() => {
+                notes.push(format!("working dir {dir}"));
+                dir
+            }
+            Some(dir) => {
+                notes.push(format!("{dir} not found, using {}", self.cwd.display()));
+                self.cwd.to_string_lossy().to_string()
+            }
+            None => self.cwd.to_string_lossy().to_string(),
+        };
+        if !apply.env_vars.is_empty() {
+            notes.push(format!("{} env vars", apply.env_vars.len()));
+        }
+        if let Some(model_name) = apply.model {
+            notes.push(format!("model {model_name}"));
+            let _ = self.bridge.send_request(AgentRequest::SwitchModel { model_name });
+        }
+        if !apply.tabs.is_empty() {
+            notes.push(format!("building {} tabs", apply.tabs.len()));
+            self.spawn_layout(apply.tabs);
+        }
+        let summary = if notes.is_empty() {
+            "nothing to change".to_string()
+        } else {
+            notes.join(", ")
+        };
+        self.write_ansi(&screen::format_command_response(&format!(
+            "Launch config applied: {summary}"
+        )));
+
+        if !apply.commands.is_empty() {
+            let command = apply.commands.join(" && ");
+            self.write_ansi(&screen::format_command_prompt(&command));
+            let _ = self.bridge.send_request(AgentRequest::RunCommand {
+                command,
+                working_dir: Some(working_dir),
+                env: apply.env_vars,
+            });
+        }
+        if let Some(prompt) = apply.agent_prompt {
+            self.session_log.lock().log_user(&prompt);
+            self.send_to_agent(&prompt, prompt.clone());
+        }
+    }
+
+    /// Build a launch config's tabs in this pane's window. Runs on the mux;
+    /// the outcome is reported back into this pane when it finishes.
+    fn spawn_layout(&self, tabs: Vec<TabPlan>) {
+        let agent_pane = self.pane_id;
+        let mux = Mux::get();
+        let Some((_, window_id, tab_id)) = mux.resolve_pane_id(agent_pane) else {
+            self.write_ansi(&screen::format_error(
+                "Launch layout: pane is not in a window",
+            ));
+            return;
+        };
+        let size = match mux.get_tab(tab_id) {
+            Some(tab) => tab.get_size(),
+            None => self.terminal.lock().get_size(),
+        };
+
+        promise::spawn::spawn(async move {
+            let result = build_layout(agent_pane, window_id, size, tabs).await;
+            let Some(pane) = Mux::get().get_pane(agent_pane) else { return };
+            let Some(elwood) = pane.downcast_ref::<ElwoodPane>() else { return };
+            match result {
+                Ok(count) => elwood.write_ansi(&screen::format_command_response(&format!(
+                    "Launch layout ready ({count} panes)"
+                ))),
+                Err(e) => elwood.write_ansi(&screen::format_error(&format!(
+                    "Launch layout failed: {e:#}"
+                ))),
+            }
+        })
+        .detach();
+    }
+
+    /// Follow a job's log in a split pane (`/jobs fg`).
+    fn foreground_job(&self, job: &jobs::Job) {
+        let Some(log_path) = job.log_path.clone() else {
+            self.write_ansi(&screen::format_command_response(&format!(
+                "Job #{} has no log file to follow",
+                job.id
+            )));
+            return;
+        };
+
+        let argv = jobs::follow_log_argv(&log_path);
+        let command = CommandBuilder::from_argv(argv.into_iter().map(Into::into).collect());
+        let pane_id = self.pane_id;
+        let job_id = job.id;
+        promise::spawn::spawn(async move {
+            let request = SplitRequest {
+                direction: SplitDirection::Vertical,
+                target_is_second: true,
+                top_level: false,
+                size: SplitSize::Percent(40),
+            };
+            let source = SplitSource::Spawn {
+                command: Some(command),
+                command_dir: None,
+            };
+            if let Err(e) = Mux::get()
+                .split_pane(pane_id, request, source, SpawnTabDomain::DefaultDomain)
+                .await
+            {
+                tracing::error!("Failed to open pane for job #{job_id}: {e:#}");
+            }
+        })
+        .detach();
+
+        self.toast_manager.lock().push(
+            format!("Following job #{} in a new pane (Ctrl+C to close)", job.id),
+            ToastLevel::Info,
+            None,
+            None,
+        );
+        self.render_toasts();
     }
-    fn file_browser_action_attach(&self) {
-        let path = self.file_browser.lock().as_ref().and_then(|tree| { tree.selected_entry().filter(|e| e.entry_type == crate::file_browser::EntryType::File).map(|e| e.path.clone()) });
-        if let Some(path) = path {
-            *self.file_browser.lock() = None;
-            let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-            let rel = path.strip_prefix(&cwd).unwrap_or(&path).to_string_lossy().to_string();
-            let at_ref = format!("@{rel} ");
-            { let mut ed = self.input_editor.lock(); for c in at_ref.chars() { ed.insert_char(c); } }
-            self.sync_editor_to_screen(); self.refresh_input_box();
-            self.write_ansi(&format!("
-[38;2;86;95;137m[2m[Attached: @{rel}][0m
-"));
+
+    /// Render the quickfix overlay into the virtual terminal.
+    fn render_quickfix_overlay(&self) {
+        let width = self.screen.lock().width;
+        let rendered = self.quickfix.lock().render(width);
+        if !rendered.is_empty() {
+            self.write_chrome(&rendered);
         }
     }
 
@@ -1973,7 +4615,7 @@ impl ElwoodPane {
                 }
                 *self.state.lock() = PaneState::Running;
 
-                let _ = self.bridge.send_request(AgentRequest::SendMessage { content: prompt });
+                self.send_agent_request(AgentRequest::SendMessage { content: prompt });
             }
         }
         self.refresh_status_bar();
@@ -2122,7 +4764,7 @@ impl ElwoodPane {
 
     /// Open the diff viewer for git diff output (from `/diff` command).
     fn open_git_diff_viewer(&self, staged: bool) {
-        let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+        let cwd = self.cwd.clone();
         match diff::git_diff(&cwd, staged) {
             Ok(diffs) => {
                 if diffs.is_empty() {
@@ -2154,7 +4796,7 @@ impl ElwoodPane {
     // ── Git UI overlay handlers (staging view, commit view) ────────────
 
     fn handle_git_status(&self) {
-        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let cwd = self.cwd.clone();
         match git_ui::format_git_status(&cwd) {
             Ok(o) => self.write_ansi(&format!("\r\n{o}")),
             Err(e) => self.write_ansi(&screen::format_error(&format!("git status failed: {e}"))),
@@ -2162,7 +4804,7 @@ impl ElwoodPane {
     }
 
     fn handle_git_log(&self, count: usize) {
-        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let cwd = self.cwd.clone();
         match git_ui::format_git_log(&cwd, count) {
             Ok(o) => self.write_ansi(&format!("\r\n{o}")),
             Err(e) => self.write_ansi(&screen::format_error(&format!("git log failed: {e}"))),
@@ -2170,7 +4812,7 @@ impl ElwoodPane {
     }
 
     fn handle_git_push(&self) {
-        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let cwd = self.cwd.clone();
         let r = git_ui::git_push(&cwd);
         self.write_ansi(&git_ui::format_git_push_result(r));
         self.screen.lock().git_info = git_info::get_git_info(&cwd);
@@ -2178,7 +4820,7 @@ impl ElwoodPane {
     }
 
     fn open_staging_view(&self) {
-        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let cwd = self.cwd.clone();
         match StagingView::new(&cwd) {
             Ok(v) => { let w = self.screen.lock().width as usize; let rendered = v.render(w); *self.staging_view.lock() = Some(v); self.write_ansi(&rendered); }
             Err(e) => self.write_ansi(&screen::format_command_response(&e)),
@@ -2186,7 +4828,7 @@ impl ElwoodPane {
     }
 
     fn open_commit_flow(&self) {
-        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let cwd = self.cwd.clone();
         let files = match git_ui::get_file_statuses(&cwd) { Ok(f) => f, Err(e) => { self.write_ansi(&screen::format_error(&format!("git status: {e}"))); return; } };
         let sc = files.iter().filter(|f| f.staged).count();
         if sc == 0 { self.write_ansi(&screen::format_command_response("No files staged. Opening staging view...")); self.open_staging_view(); return; }
@@ -2200,13 +4842,13 @@ impl ElwoodPane {
 
     fn handle_staging_view_key(&self, key: KeyCode, mods: KeyModifiers) {
         let act = { let mut g = self.staging_view.lock(); let v = match g.as_mut() { Some(v) => v, None => return }; match key { KeyCode::Char(' ') if mods.is_empty() => { v.toggle_current(); None } KeyCode::Char('a') | KeyCode::Char('A') if mods.is_empty() => { v.toggle_all(); None } KeyCode::Char('j') | KeyCode::DownArrow if mods.is_empty() => { v.move_down(); None } KeyCode::Char('k') | KeyCode::UpArrow if mods.is_empty() => { v.move_up(); None } KeyCode::Enter if mods.is_empty() => Some(("ok", v.staged_paths().len())), KeyCode::Escape => Some(("esc", 0)), _ => None, } };
-        if let Some((a, n)) = act { *self.staging_view.lock() = None; if a == "ok" { self.write_ansi(&format!("\r\n\x1b[38;2;158;206;106m\x1b[1m{n} file{} staged\x1b[0m\r\n", if n == 1 { "" } else { "s" })); let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")); self.screen.lock().git_info = git_info::get_git_info(&cwd); self.refresh_status_bar(); } else { self.write_ansi("\r\n\x1b[38;2;86;95;137m\x1b[2m[Staging cancelled]\x1b[0m\r\n"); } }
+        if let Some((a, n)) = act { *self.staging_view.lock() = None; if a == "ok" { self.write_ansi(&format!("\r\n\x1b[38;2;158;206;106m\x1b[1m{n} file{} staged\x1b[0m\r\n", if n == 1 { "" } else { "s" })); let cwd = self.cwd.clone(); self.screen.lock().git_info = git_info::get_git_info(&cwd); self.refresh_status_bar(); } else { self.write_ansi("\r\n\x1b[38;2;86;95;137m\x1b[2m[Staging cancelled]\x1b[0m\r\n"); } }
         else { let g = self.staging_view.lock(); if let Some(ref v) = *g { let w = self.screen.lock().width as usize; let rendered = v.render(w); drop(g); self.write_ansi(&rendered); } }
     }
 
     fn handle_commit_view_key(&self, key: KeyCode, mods: KeyModifiers) {
         let act = { let mut g = self.commit_view.lock(); let v = match g.as_mut() { Some(v) => v, None => return }; if v.editing { match key { KeyCode::Escape => { v.editing = false; None } KeyCode::Enter if mods == KeyModifiers::SHIFT => { v.insert_newline(); None } KeyCode::Enter if mods.is_empty() => Some("commit"), KeyCode::Backspace => { v.backspace(); None } KeyCode::Char(c) if mods.is_empty() || mods == KeyModifiers::SHIFT => { v.insert_char(c); None } _ => None, } } else { match key { KeyCode::Enter if mods.is_empty() => Some("commit"), KeyCode::Char('e') | KeyCode::Char('E') if mods.is_empty() => { v.start_edit(); None } KeyCode::Escape => Some("cancel"), _ => None, } } };
-        if let Some(a) = act { if a == "commit" { let res = { self.commit_view.lock().as_ref().map(|v| v.commit()) }; *self.commit_view.lock() = None; match res { Some(Ok(out)) => { self.write_ansi(&format!("\r\n\x1b[38;2;158;206;106m\x1b[1mCommit successful\x1b[0m\r\n\x1b[38;2;192;202;245m{out}\x1b[0m\r\n")); let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")); self.screen.lock().git_info = git_info::get_git_info(&cwd); self.refresh_status_bar(); } Some(Err(e)) => self.write_ansi(&screen::format_error(&format!("Commit failed: {e}"))), None => {} } } else { *self.commit_view.lock() = None; self.write_ansi("\r\n\x1b[38;2;86;95;137m\x1b[2m[Commit cancelled]\x1b[0m\r\n"); } }
+        if let Some(a) = act { if a == "commit" { let res = { self.commit_view.lock().as_ref().map(|v| v.commit()) }; *self.commit_view.lock() = None; match res { Some(Ok(out)) => { self.write_ansi(&format!("\r\n\x1b[38;2;158;206;106m\x1b[1mCommit successful\x1b[0m\r\n\x1b[38;2;192;202;245m{out}\x1b[0m\r\n")); let cwd = self.cwd.clone(); self.screen.lock().git_info = git_info::get_git_info(&cwd); self.refresh_status_bar(); } Some(Err(e)) => self.write_ansi(&screen::format_error(&format!("Commit failed: {e}"))), None => {} } } else { *self.commit_view.lock() = None; self.write_ansi("\r\n\x1b[38;2;86;95;137m\x1b[2m[Commit cancelled]\x1b[0m\r\n"); } }
         else { let g = self.commit_view.lock(); if let Some(ref v) = *g { let w = self.screen.lock().width as usize; let rendered = v.render(w); drop(g); self.write_ansi(&rendered); } }
     }
 
@@ -2237,6 +4879,7 @@ impl ElwoodPane {
                 let (path, _, _) = matching[0];
                 match plan_mode::load_plan(path) {
                     Ok(plan) => {
+                        *self.active_plan.lock() = Some(plan.clone());
                         let viewer = PlanViewer::new(plan);
                         let rendered = viewer.render(self.screen.lock().width);
                         *self.plan_viewer.lock() = Some(viewer);
@@ -2296,13 +4939,14 @@ impl ElwoodPane {
                     if let Some(mut plan) = plan {
                         plan.status = plan_mode::PlanStatus::InProgress;
                         let _ = plan_mode::save_plan(&plan);
+                        *self.active_plan.lock() = Some(plan.clone());
                         if let Some(idx) = plan.next_step_index() {
                             let prompt = format!("Execute step {} of the plan: {}", idx + 1, plan.steps[idx].description);
                             self.write_ansi(&screen::format_assistant_prefix());
                             { let mut ss = self.screen.lock(); ss.is_running = true; ss.task_start = Some(Instant::now()); ss.task_elapsed_frozen = None; }
                             *self.state.lock() = PaneState::Running;
                             self.refresh_status_bar();
-                            let _ = self.bridge.send_request(AgentRequest::SendMessage { content: prompt });
+                            self.send_agent_request(AgentRequest::SendMessage { content: prompt });
                         }
                     }
                 }
@@ -2368,6 +5012,104 @@ impl ElwoodPane {
         terminal_get_cursor_position(&mut terminal).y
     }
 
+    /// Replay blocks persisted by a previous session into the scrollback.
+    ///
+    /// The restored blocks are written to this pane's own session, so they
+    /// survive even once the older session files are pruned.
+    fn restore_blocks(&self) {
+        let persisted = match self.block_store.lock().load_previous() {
+            Ok(blocks) => blocks,
+            Err(e) => {
+                log::warn!("Failed to load saved blocks: {e}");
+                return;
+            }
+        };
+        if persisted.is_empty() {
+            return;
+        }
+
+        let bookmarked = persisted.iter().filter(|b| b.bookmarked).count();
+        self.write_ansi(&screen::format_restored_banner(persisted.len(), bookmarked));
+        let mut restored = Vec::with_capacity(persisted.len());
+        for block in &persisted {
+            let start_row = self.current_row();
+            if let Some(ref command) = block.command {
+                self.write_ansi(&screen::format_command_prompt(command));
+            }
+            let output_row = self.current_row();
+            self.write_ansi(&screen::format_restored_output(&block.output));
+            let end_row = self.current_row() - 1;
+            let id = self
+                .block_manager
+                .lock()
+                .restore_block(block, start_row, output_row, end_row);
+            restored.push((id, block.clone()));
+        }
+
+        let mut store = self.block_store.lock();
+        if let Err(e) = store.upsert_all(restored) {
+            log::warn!("Failed to save restored blocks: {e}");
+        }
+        if let Err(e) = store.prune_sessions(MAX_SESSIONS_PER_DIR) {
+            log::warn!("Failed to prune old block sessions: {e}");
+        }
+    }
+
+    /// Write the current state of block `id` to the session's block store.
+    ///
+    /// Once the block's rows have left the scrollback its text can no longer
+    /// be read back, so the output saved earlier is kept.
+    fn persist_block(&self, id: block::BlockId) {
+        let Some(block) = self.block_manager.lock().get_block_by_id(id).cloned() else {
+            return;
+        };
+        let scrollback_top = {
+            let mut terminal = self.terminal.lock();
+            terminal_get_dimensions(&mut terminal).scrollback_top
+        };
+        let live_text = block
+            .first_row()
+            .filter(|&first| first >= scrollback_top)
+            .map(|_| self.block_persist_text(&block));
+        let mut store = self.block_store.lock();
+        let output = live_text
+            .or_else(|| store.get(id).map(|saved| saved.output.clone()))
+            .unwrap_or_default();
+        if let Err(e) = store.upsert(id, PersistedBlock::from_block(&block, &output)) {
+            log::warn!("Failed to save block: {e}");
+        }
+    }
+
+    /// Write the most recent block to the session's block store.
+    fn persist_last_block(&self) {
+        let id = self.block_manager.lock().blocks().last().map(|b| b.id);
+        if let Some(id) = id {
+            self.persist_block(id);
+        }
+    }
+
+    /// Write the block at `index` to the session's block store.
+    fn persist_block_at(&self, index: usize) {
+        let id = self.block_manager.lock().blocks().get(index).map(|b| b.id);
+        if let Some(id) = id {
+            self.persist_block(id);
+        }
+    }
+
+    /// Text to persist for a block.
+    ///
+    /// Command blocks store their output only (the command is replayed from
+    /// `Block::command`); agent blocks store everything from the user prompt on.
+    fn block_persist_text(&self, block: &block::Block) -> String {
+        if block.command.is_some() {
+            return self.block_output_text(block);
+        }
+        match (block.first_row(), block.last_row()) {
+            (Some(first), Some(last)) => self.zone_text(first..last + 1).trim_end().to_string(),
+            _ => String::new(),
+        }
+    }
+
     /// Navigate to the previous block (Ctrl+Up).
     fn navigate_block_prev(&self) {
         let current = self.current_row();
@@ -2405,55 +5147,43 @@ impl ElwoodPane {
     }
 
     /// Copy the output zone of the block at the current cursor row.
-    ///
-    /// Writes a brief confirmation message into the chat area.  Full clipboard
-    /// integration requires access to the WezTerm clipboard API (wezterm-gui);
-    /// that is left as a comment for future wiring.
     fn copy_current_block_output(&self) {
         let current = self.current_row();
-        let output_text = {
-            let mgr = self.block_manager.lock();
-            mgr.get_block_at_row(current)
-                .and_then(|b| b.output_zone)
-                .map(|z| {
-                    let mut terminal = self.terminal.lock();
-                    let (_, lines) = terminal_get_lines(&mut terminal, z.start_y..z.end_y + 1);
-                    lines
-                        .iter()
-                        .map(|l| l.as_str().to_string())
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                })
-        };
-
-        if let Some(text) = output_text {
-            let msg = format!(
-                "\r\n\x1b[2m[Copied {} bytes from block]\x1b[0m\r\n",
-                text.len(),
-            );
-            self.write_ansi(&msg);
-            // Future: pass `text` to the clipboard via a callback registered
-            // at ElwoodDomain construction time.
-            let _ = text;
+        let block = self.block_manager.lock().get_block_at_row(current).cloned();
+        if let Some(block) = block {
+            let text = self.block_output_text(&block);
+            self.copy_to_clipboard(&text, "output");
         }
     }
 }
 
 /// Format an `AgentResponse` for the chat scroll region.
 /// Uses the screen module's formatting functions for rich ANSI output.
-fn format_response_for_chat(response: &AgentResponse) -> String {
+fn format_response_for_chat(response: &AgentResponse, cwd: &Path) -> String {
     match response {
-        AgentResponse::ContentDelta(text) => screen::format_content(text),
+        AgentResponse::ContentDelta(text) => screen::format_content(text, cwd),
         AgentResponse::ToolStart {
             tool_name,
             tool_id: _,
             input_preview,
         } => screen::format_tool_start(tool_name, input_preview),
+        AgentResponse::ToolOutput {
+            tool_name: _,
+            text,
+            is_stderr,
+        } => screen::format_tool_output(text, *is_stderr),
         AgentResponse::ToolEnd {
             tool_id: _,
             success,
             output_preview,
-        } => screen::format_tool_end(*success, output_preview),
+            images,
+        } => {
+            let mut out = screen::format_tool_end(*success, output_preview);
+            for image in images {
+                out.push_str(&inline_image::render_image(&image.data, &image.describe()));
+            }
+            out
+        }
         AgentResponse::PermissionRequest {
             request_id: _,
             tool_name,
@@ -2464,6 +5194,7 @@ fn format_response_for_chat(response: &AgentResponse) -> String {
             stdout,
             stderr,
             exit_code,
+            ..
         } => screen::format_command_output(command, stdout, stderr, *exit_code),
         AgentResponse::TurnComplete { summary } => {
             screen::format_turn_complete(summary.as_deref())
@@ -2506,6 +5237,229 @@ fn format_response_for_chat(response: &AgentResponse) -> String {
 }
 
 
+/// Spawn every tab in `tabs`, moving `agent_pane` into the slot marked as the
+/// agent. Returns the number of panes in the new layout.
+async fn build_layout(
+    agent_pane: PaneId,
+    window_id: mux::window::WindowId,
+    size: TerminalSize,
+    tabs: Vec<TabPlan>,
+) -> anyhow::Result<usize> {
+    let mux = Mux::get();
+    let workspace = mux.active_workspace();
+    let mut total = 0;
+
+    for plan in tabs {
+        let Some(root) = plan.panes.first() else { continue };
+        let (tab, root_pane) = if root.agent {
+            let (tab, _) = mux
+                .move_pane_to_new_tab(agent_pane, Some(window_id), None)
+                .await?;
+            let pane = mux
+                .get_pane(agent_pane)
+                .ok_or_else(|| anyhow::anyhow!("agent pane {agent_pane} went away"))?;
+            (tab, pane)
+        } else {
+            let (tab, pane, _) = mux
+                .spawn_tab_or_window(
+                    Some(window_id),
+                    layout_domain(root),
+                    Some(layout_command(root)),
+                    root.cwd.clone(),
+                    size,
+                    Some(agent_pane),
+                    workspace.clone(),
+                    None,
+                )
+                .await?;
+            (tab, pane)
+        };
+        if let Some(ref title) = plan.title {
+            tab.set_title(title);
+        }
+
+        let mut panes = vec![root_pane];
+        for split in &plan.splits {
+            let spec = &plan.panes[panes.len()];
+            let request = SplitRequest {
+                direction: match split.axis {
+                    SplitAxis::Horizontal => SplitDirection::Horizontal,
+                    SplitAxis::Vertical => SplitDirection::Vertical,
+                },
+                target_is_second: true,
+                top_level: false,
+                size: SplitSize::Percent(split.percent),
+            };
+            let (source, domain) = if spec.agent {
+                (
+                    SplitSource::MovePane(agent_pane),
+                    SpawnTabDomain::CurrentPaneDomain,
+                )
+            } else {
+                (
+                    SplitSource::Spawn {
+                        command: Some(layout_command(spec)),
+                        command_dir: spec.cwd.clone(),
+                    },
+                    layout_domain(spec),
+                )
+            };
+            let target = panes[split.target].pane_id();
+            let (pane, _) = mux.split_pane(target, request, source, domain).await?;
+            panes.push(pane);
+        }
+
+        // Type startup commands into the shells so they stay open afterwards
+        for (pane, spec) in panes.iter().zip(&plan.panes) {
+            if spec.agent || spec.commands.is_empty() {
+                continue;
+            }
+            let mut writer = pane.writer();
+            for command in &spec.commands {
+                writer.write_all(format!("{command}\r").as_bytes())?;
+            }
+        }
+        total += panes.len();
+    }
+    Ok(total)
+}
+
+/// Spawn an Elwood pane for a registered agent in a split next to `pane_id`.
+///
+/// The `ELWOOD_AGENT_ID` env var tells the domain to give the pane its own
+/// runtime in `dir` and start the agent's subtask (see
+/// [`ElwoodPane::attach_agent`]).
+fn spawn_agent_pane(pane_id: PaneId, domain_id: DomainId, agent_id: u32, dir: String) {
+    let mut command = CommandBuilder::new_default_prog();
+    command.env("ELWOOD_AGENT_ID", agent_id.to_string());
+
+    split_elwood_pane(pane_id, domain_id, command, Some(dir), move |e| {
+        let mut registry = multi_agent::shared_registry().lock();
+        registry.fail_subtask(agent_id, &format!("Failed to spawn agent pane: {e}"));
+        registry.update_status(agent_id, AgentStatus::Error);
+    });
+}
+
+/// Split `pane_id` and spawn an Elwood pane running `command` in the new
+/// half. Must be called on the main thread; `on_error` runs if the split fails.
+fn split_elwood_pane(
+    pane_id: PaneId,
+    domain_id: DomainId,
+    command: CommandBuilder,
+    dir: Option<String>,
+    on_error: impl FnOnce(anyhow::Error) + 'static,
+) {
+    promise::spawn::spawn(async move {
+        let request = SplitRequest {
+            direction: SplitDirection::Horizontal,
+            target_is_second: true,
+            top_level: false,
+            size: SplitSize::Percent(50),
+        };
+        let source = SplitSource::Spawn {
+            command: Some(command),
+            command_dir: dir,
+        };
+        let result = Mux::get()
+            .split_pane(
+                pane_id,
+                request,
+                source,
+                SpawnTabDomain::DomainId(domain_id),
+            )
+            .await;
+        if let Err(e) = result {
+            on_error(e);
+        }
+    })
+    .detach();
+}
+
+fn layout_domain(spec: &PlannedPane) -> SpawnTabDomain {
+    match spec.domain {
+        Some(ref name) => SpawnTabDomain::DomainName(name.clone()),
+        None => SpawnTabDomain::DefaultDomain,
+    }
+}
+
+/// The default shell with the pane's environment and directory.
+fn layout_command(spec: &PlannedPane) -> CommandBuilder {
+    let mut cmd = CommandBuilder::new_default_prog();
+    for (key, value) in &spec.env {
+        cmd.env(key, value);
+    }
+    if let Some(ref cwd) = spec.cwd {
+        cmd.cwd(cwd);
+    }
+    cmd
+}
+
+/// Text on the OS clipboard, for vim puts from `"+`.
+///
+/// The GUI only hands panes a clipboard to write to, so this asks the
+/// platform's clipboard tool instead.
+fn read_system_clipboard() -> Option<String> {
+    let tools: &[&[&str]] = if cfg!(target_os = "macos") {
+        &[&["pbpaste"]]
+    } else if cfg!(windows) {
+        &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard -Raw"]]
+    } else {
+        &[
+            &["wl-paste", "--no-newline"],
+            &["xclip", "-selection", "clipboard", "-o"],
+            &["xsel", "--clipboard", "--output"],
+        ]
+    };
+    tools.iter().find_map(|argv| {
+        let output = std::process::Command::new(argv[0])
+            .args(&argv[1..])
+            .stdin(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    })
+}
+
+/// Resolve the `theme` setting from `elwood.toml` against the pane's
+/// `palette` and make it active.
+fn apply_configured_theme(palette: &ColorPalette) -> anyhow::Result<()> {
+    let name = crate::config::ElwoodConfig::load().theme;
+    theme::apply(&name, palette)
+}
+
+/// Bring the saved-session index up to date with the export directory,
+/// starting from scratch when `reindex` is set. Saves it when it changed.
+fn refresh_session_index(reindex: bool) -> anyhow::Result<SessionIndex> {
+    let index_path = session_index::default_index_path();
+    let mut index = if reindex {
+        SessionIndex::default()
+    } else {
+        SessionIndex::load(&index_path).unwrap_or_else(|e| {
+            log::warn!("Failed to load session index: {e:#}");
+            SessionIndex::default()
+        })
+    };
+    let changed = index.refresh(&session_log::session_export_dir(None))?;
+    if changed > 0 || reindex {
+        if let Err(e) = index.save(&index_path) {
+            log::warn!("Failed to save session index: {e:#}");
+        }
+    }
+    Ok(index)
+}
+
+/// The route a correction sends input to.
+fn opposite_route(mode: InputMode) -> InputMode {
+    match mode {
+        InputMode::Agent => InputMode::Terminal,
+        InputMode::Terminal => InputMode::Agent,
+    }
+}
+
 /// Truncate a command string for display in toast messages.
 fn truncate_cmd(cmd: &str, max: usize) -> String {
     if cmd.len() <= max {
@@ -2519,6 +5473,38 @@ fn truncate_cmd(cmd: &str, max: usize) -> String {
     }
 }
 
+/// Keep the last `max` bytes of `text` (on a char boundary), trimmed.
+fn tail_text(text: &str, max: usize) -> String {
+    let mut start = text.len().saturating_sub(max);
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    text[start..].trim().to_string()
+}
+
+impl context::TerminalContext for ElwoodPane {
+    fn pane_text(&self, pane_id: PaneId, max_lines: usize) -> Option<String> {
+        let pane = Mux::try_get()?.get_pane(pane_id)?;
+        let dims = pane.get_dimensions();
+        let end = dims.physical_top + dims.viewport_rows as StableRowIndex;
+        let start = end
+            .saturating_sub(max_lines as StableRowIndex)
+            .max(dims.scrollback_top);
+        let (_, lines) = pane.get_lines(start..end);
+        let text = lines
+            .iter()
+            .map(|l| l.as_str().trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(text.trim_end().to_string())
+    }
+
+    fn block_text(&self, index: usize) -> Option<(Option<String>, String)> {
+        let target = self.block_manager.lock().blocks().get(index).cloned()?;
+        Some((self.block_command_text(&target), self.block_output_text(&target)))
+    }
+}
+
 #[async_trait(?Send)]
 impl mux::pane::Pane for ElwoodPane {
     fn pane_id(&self) -> PaneId {
@@ -2678,6 +5664,12 @@ impl mux::pane::Pane for ElwoodPane {
             }
         }
 
+        // ── Replay mode: route all keys to the player ──────────────
+        if self.replay.lock().is_some() {
+            self.handle_replay_key(key, mods);
+            return Ok(());
+        }
+
         // ── Diff viewer mode: route keys to the diff viewer ────────────
         if self.diff_viewer.lock().is_some() {
             return self.handle_diff_viewer_key(key, mods);
@@ -2740,7 +5732,7 @@ impl mux::pane::Pane for ElwoodPane {
                         );
                         drop(ss);
                         drop(sm);
-                        self.write_ansi(&overlay);
+                        self.write_chrome(&overlay);
                     }
                     return Ok(());
                 }
@@ -2760,22 +5752,8 @@ impl mux::pane::Pane for ElwoodPane {
                 }
                 KeyCode::Enter => {
                     let action = self.toast_manager.lock().accept_top();
-                    if let Some(ToastAction::RunCommand(cmd)) = action {
-                        let mut editor = self.input_editor.lock();
-                        editor.clear();
-                        for ch in cmd.chars() {
-                            editor.insert_char(ch);
-                        }
-                        drop(editor);
-                        self.sync_editor_to_screen();
-                    } else if let Some(ToastAction::SendToAgent(msg)) = action {
-                        let mut editor = self.input_editor.lock();
-                        editor.clear();
-                        for ch in msg.chars() {
-                            editor.insert_char(ch);
-                        }
-                        drop(editor);
-                        self.sync_editor_to_screen();
+                    if let Some(action) = action {
+                        self.run_toast_action(action);
                     }
                     return Ok(());
                 }
@@ -2870,12 +5848,146 @@ impl mux::pane::Pane for ElwoodPane {
             }
         }
 
+        // ── Notification history: route all keys to the history overlay ──
+        {
+            let history_open = self.toast_manager.lock().history().is_open();
+            if history_open {
+                match key {
+                    KeyCode::Escape => {
+                        self.toast_manager.lock().history_mut().close();
+                    }
+                    KeyCode::UpArrow => {
+                        self.toast_manager.lock().history_mut().select_prev();
+                        self.render_notification_history();
+                    }
+                    KeyCode::DownArrow => {
+                        self.toast_manager.lock().history_mut().select_next();
+                        self.render_notification_history();
+                    }
+                    KeyCode::Enter => {
+                        let action = {
+                            let mut tm = self.toast_manager.lock();
+                            let history = tm.history_mut();
+                            let action = history.selected().and_then(|t| t.action.clone());
+                            if action.is_some() {
+                                history.close();
+                            }
+                            action
+                        };
+                        if let Some(action) = action {
+                            self.run_toast_action(action);
+                        }
+                    }
+                    KeyCode::Char('c') => {
+                        self.toast_manager.lock().history_mut().clear();
+                        self.render_notification_history();
+                    }
+                    _ => {}
+                }
+                return Ok(());
+            }
+        }
+
+        // ── Session search: route all keys to the results overlay ──────
+        {
+            let search_open = self.session_search.lock().is_open();
+            if search_open {
+                let path = self
+                    .session_search
+                    .lock()
+                    .selected()
+                    .map(|h| h.path.clone());
+                match key {
+                    KeyCode::Escape => {
+                        self.session_search.lock().close();
+                    }
+                    KeyCode::UpArrow => {
+                        self.session_search.lock().select_prev();
+                        self.render_session_search();
+                    }
+                    KeyCode::DownArrow => {
+                        self.session_search.lock().select_next();
+                        self.render_session_search();
+                    }
+                    KeyCode::Enter => {
+                        if let Some(path) = path {
+                            self.open_session_file(&path);
+                        }
+                    }
+                    KeyCode::Char('r') => {
+                        if let Some(path) = path {
+                            self.session_search.lock().close();
+                            self.spawn_resume_pane(&path);
+                        }
+                    }
+                    KeyCode::Char('e') => {
+                        if let Some(path) = path {
+                            self.reexport_session(&path);
+                        }
+                    }
+                    _ => {}
+                }
+                return Ok(());
+            }
+        }
+
+        // ── Quickfix overlay: route all keys to the quickfix list ──────
+        {
+            let qf_open = self.quickfix.lock().is_open();
+            if qf_open {
+                match key {
+                    KeyCode::Escape => {
+                        self.quickfix.lock().close();
+                    }
+                    KeyCode::UpArrow => {
+                        self.quickfix.lock().select_prev();
+                        self.render_quickfix_overlay();
+                    }
+                    KeyCode::DownArrow => {
+                        self.quickfix.lock().select_next();
+                        self.render_quickfix_overlay();
+                    }
+                    KeyCode::Tab if mods.is_empty() => {
+                        self.quickfix.lock().select_next_file();
+                        self.render_quickfix_overlay();
+                    }
+                    KeyCode::Tab if mods == KeyModifiers::SHIFT => {
+                        self.quickfix.lock().select_prev_file();
+                        self.render_quickfix_overlay();
+                    }
+                    KeyCode::Enter => {
+                        let entry = self.quickfix.lock().selected_entry().cloned();
+                        if let Some(entry) = entry {
+                            self.open_quickfix_entry(&entry);
+                        }
+                    }
+                    KeyCode::Char('n') => self.handle_quickfix_action(QuickfixAction::Next),
+                    KeyCode::Char('p') => self.handle_quickfix_action(QuickfixAction::Prev),
+                    KeyCode::Char('r') => {
+                        self.refresh_quickfix();
+                        self.render_quickfix_overlay();
+                    }
+                    KeyCode::Char('f') => {
+                        self.quickfix.lock().close();
+                        self.handle_quickfix_action(QuickfixAction::FixFile);
+                    }
+                    _ => {}
+                }
+                return Ok(());
+            }
+        }
+
         // ── Fuzzy finder mode: route all keys to fuzzy finder ──────
         if self.fuzzy_finder.lock().is_some() {
             self.handle_fuzzy_finder_key(key, mods);
             return Ok(());
         }
 
+        // ── Completion dropdown: navigation keys; anything else closes it ──
+        if self.completion_menu.lock().is_some() && self.handle_completion_menu_key(key, mods) {
+            return Ok(());
+        }
+
         // F2 toggles file browser
         if key == KeyCode::Function(2) && mods.is_empty() {
             self.toggle_file_browser();
@@ -2896,6 +6008,32 @@ impl mux::pane::Pane for ElwoodPane {
             return Ok(());
         }
 
+        // Ctrl+Space opens the completion dropdown
+        if key == KeyCode::Char(' ') && mods == KeyModifiers::CTRL {
+            self.open_completion_menu();
+            return Ok(());
+        }
+
+        // Alt+N opens the notification history
+        if key == KeyCode::Char('n') && mods == KeyModifiers::ALT {
+            self.handle_notifications_action(NotificationsAction::Open);
+            return Ok(());
+        }
+
+        // Alt+Q opens the quickfix list; Alt+] / Alt+[ jump between its errors
+        if mods == KeyModifiers::ALT {
+            let action = match key {
+                KeyCode::Char('q') => Some(QuickfixAction::Open),
+                KeyCode::Char(']') => Some(QuickfixAction::Next),
+                KeyCode::Char('[') => Some(QuickfixAction::Prev),
+                _ => None,
+            };
+            if let Some(action) = action {
+                self.handle_quickfix_action(action);
+                return Ok(());
+            }
+        }
+
         // Ctrl+T toggles input mode (always intercepted, never forwarded to PTY)
         if key == KeyCode::Char('t') && mods == KeyModifiers::CTRL {
             self.toggle_input_mode();
@@ -2927,6 +6065,19 @@ impl mux::pane::Pane for ElwoodPane {
 
         // ── Agent mode: route keys to InputEditor ────────────────────────────
 
+        // Ctrl+X Ctrl+E: compose the input in $EDITOR (bash's edit-and-execute)
+        let chord_pending = std::mem::take(&mut *self.ctrl_x_pending.lock());
+        if mods == KeyModifiers::CTRL {
+            if key == KeyCode::Char('x') {
+                *self.ctrl_x_pending.lock() = true;
+                return Ok(());
+            }
+            if chord_pending && key == KeyCode::Char('e') {
+                self.open_input_in_editor();
+                return Ok(());
+            }
+        }
+
         // Ctrl+F: open fuzzy finder overlay
         if key == KeyCode::Char('f') && mods == KeyModifiers::CTRL {
             self.open_fuzzy_finder();
@@ -2968,52 +6119,43 @@ impl mux::pane::Pane for ElwoodPane {
             }
         }
 
+        // Vim mode gets the keys it understands before the editor bindings
+        if let Some((c, ctrl)) = self.vim_key_for(key, mods) {
+            let effects = self.input_editor.lock().handle_vim_key(c, ctrl);
+            self.apply_vim_effects(effects);
+            self.sync_editor_to_screen();
+            self.refresh_input_box();
+            self.refresh_status_bar();
+            return Ok(());
+        }
+
         let mut editor_changed = true;
 
         match key {
             // ── Submit ───────────────────────────────────────────────
             KeyCode::Enter if mods.is_empty() => {
-                let mode = self.input_editor.lock().mode();
-                match mode {
-                    InputMode::Agent => {
-                        // Check for `!` prefix — run as command
-                        let starts_with_bang = self
-                            .input_editor
-                            .lock()
-                            .lines()
-                            .first()
-                            .map(|l| l.starts_with('!'))
-                            .unwrap_or(false);
-                        if starts_with_bang {
-                            // Strip the leading `!` from the first line
-                            {
-                                let mut ed = self.input_editor.lock();
-                                let first = ed.lines()[0].trim_start_matches('!').to_string();
-                                ed.clear();
-                                for c in first.chars() {
-                                    ed.insert_char(c);
-                                }
-                            }
-                            self.submit_command();
-                        } else {
-                            // NL auto-detection: classify input and auto-route
-                            // high-confidence terminal commands to shell
-                            let content = self.input_editor.lock().content();
-                            let classification = self.nl_classifier.classify(&content);
-                            if classification.mode == InputMode::Terminal
-                                && classification.confidence >= 0.5
-                            {
-                                self.submit_command();
-                            } else {
-                                self.submit_input();
-                            }
-                        }
-                    }
-                    InputMode::Terminal => {
-                        self.submit_command();
+                self.submit_editor();
+                editor_changed = false; // sync already done in submit_*
+            }
+
+            // ── Submit the other way than auto-detect (Ctrl+Enter) ───
+            KeyCode::Enter
+                if mods == KeyModifiers::CTRL
+                    && self.input_editor.lock().mode() == InputMode::Agent =>
+            {
+                let content = self.input_editor.lock().content();
+                if commands::parse_command(&content).is_some() {
+                    self.submit_input();
+                } else {
+                    let route = opposite_route(self.auto_route(&content));
+                    self.learn_route(&content, route);
+                    *self.last_routed.lock() = None;
+                    match route {
+                        InputMode::Terminal => self.submit_command(),
+                        InputMode::Agent => self.submit_input(),
                     }
                 }
-                editor_changed = false; // sync already done in submit_*
+                editor_changed = false;
             }
 
             // ── Multi-line newline (Shift+Enter) ─────────────────────
@@ -3024,6 +6166,13 @@ impl mux::pane::Pane for ElwoodPane {
             // ── Cancel ───────────────────────────────────────────────
             KeyCode::Escape => {
                 let _ = self.bridge.send_request(AgentRequest::Cancel);
+                let waited = self.job_wait.lock().take();
+                if let Some(job_id) = waited {
+                    self.end_job_wait();
+                    self.write_ansi(&screen::format_command_response(&format!(
+                        "Stopped waiting for job #{job_id} (it keeps running)"
+                    )));
+                }
                 editor_changed = false;
             }
 
@@ -3125,15 +6274,38 @@ impl mux::pane::Pane for ElwoodPane {
                 let mut mgr = self.block_manager.lock();
                 if let Some(idx) = mgr.selected_index() {
                     mgr.toggle_collapse_at(idx);
+                    drop(mgr);
+                    self.persist_block_at(idx);
                 }
                 editor_changed = false;
             }
 
+            // ── Block actions on selected block (y/o/r/a when input empty) ──
+            // Rerun and send-to-agent start new work, so only when idle;
+            // otherwise the letters fall through to the input editor.
+            KeyCode::Char(c @ ('y' | 'o' | 'r' | 'a'))
+                if mods.is_empty()
+                    && self.input_editor.lock().is_empty()
+                    && self.block_manager.lock().selected_index().is_some()
+                    && (matches!(c, 'y' | 'o') || *self.state.lock() == PaneState::Idle) =>
+            {
+                let action = match c {
+                    'y' => BlockAction::CopyCommand,
+                    'o' => BlockAction::CopyOutput,
+                    'r' => BlockAction::Rerun,
+                    _ => BlockAction::SendToAgent,
+                };
+                self.handle_block_action(action, None);
+                editor_changed = false;
+            }
+
             // ── Toggle bookmark on selected block (b when input empty) ──
             KeyCode::Char('b') if mods.is_empty() && self.input_editor.lock().is_empty() => {
                 let mut mgr = self.block_manager.lock();
                 if let Some(idx) = mgr.selected_index() {
                     mgr.toggle_bookmark_at(idx);
+                    drop(mgr);
+                    self.persist_block_at(idx);
                 }
                 editor_changed = false;
             }
@@ -3173,6 +6345,12 @@ impl mux::pane::Pane for ElwoodPane {
         Ok(())
     }
 
+    fn focus_changed(&self, focused: bool) {
+        // Toasts go to the desktop while this pane is unfocused, whether
+        // another pane is active or the window is in the background
+        self.toast_manager.lock().set_pane_focused(focused);
+    }
+
     fn is_dead(&self) -> bool {
         if *self.dead.lock() {
             return true;
@@ -3189,6 +6367,24 @@ impl mux::pane::Pane for ElwoodPane {
     }
 
     fn kill(&self) {
+        if let Some(agent_id) = *self.agent_id.lock() {
+            let mut registry = multi_agent::shared_registry().lock();
+            registry.fail_subtask(agent_id, "Agent pane was closed");
+            registry.update_status(agent_id, AgentStatus::Stopped);
+            registry.set_task(agent_id, None);
+        }
+        self.persist_last_block();
+        let predictions = self.prediction_engine.lock().take_unsaved(true);
+        if let Some(Err(e)) = predictions.map(|update| update.save()) {
+            log::warn!("Failed to save prediction model: {e:#}");
+        }
+        let autosave = self
+            .session_snapshot()
+            .autosave(&session_log::session_export_dir(None));
+        if let Err(e) = autosave {
+            log::warn!("Failed to auto-save session: {e}");
+        }
+
         // Kill the inner PTY if present
         {
             let mut pty_guard = self.inner_pty.lock();
@@ -3203,6 +6399,26 @@ impl mux::pane::Pane for ElwoodPane {
         *self.dead.lock() = true;
     }
 
+    fn set_clipboard(&self, clipboard: &Arc<dyn Clipboard>) {
+        *self.clipboard.lock() = Some(Arc::clone(clipboard));
+        self.terminal.lock().set_clipboard(clipboard);
+    }
+
+    fn set_config(&self, config: Arc<dyn TerminalConfiguration>) {
+        let palette = {
+            let mut terminal = self.terminal.lock();
+            terminal.set_config(Arc::new(ElwoodTermConfig {
+                window: Some(config),
+            }));
+            terminal.palette()
+        };
+        // The GUI calls this on every config reload and override change, so
+        // `auto` follows the window's color scheme
+        if let Err(e) = apply_configured_theme(&palette) {
+            log::warn!("Failed to reload theme: {e}");
+        }
+    }
+
     fn palette(&self) -> ColorPalette {
         self.terminal.lock().palette()
     }
@@ -3228,9 +6444,7 @@ impl mux::pane::Pane for ElwoodPane {
     }
 
     fn get_current_working_dir(&self, _policy: CachePolicy) -> Option<Url> {
-        std::env::current_dir()
-            .ok()
-            .and_then(|p| Url::from_directory_path(p).ok())
+        Url::from_directory_path(&self.cwd).ok()
     }
 
     fn can_close_without_prompting(&self, _reason: CloseReason) -> bool {
@@ -447,7 +447,7 @@ pub fn save_plan(plan: &PlanDocument) -> std::io::Result<PathBuf> {
 /// Load a plan from a markdown file on disk.
 pub fn load_plan(path: &Path) -> std::io::Result<PlanDocument> {
     let content = std::fs::read_to_string(path)?;
-    let mut plan = parse_llm_plan(&content);
+    let mut plan = parse_saved_plan(&content);
 
     // Try to extract the ID from the filename (timestamp prefix)
     if let Some(filename) = path.file_stem().and_then(|n| n.to_str()) {
@@ -456,6 +456,20 @@ pub fn load_plan(path: &Path) -> std::io::Result<PlanDocument> {
         }
     }
 
+    Ok(plan)
+}
+
+/// Rebuild a plan with the given ID from [`PlanDocument::to_markdown`] output.
+pub fn plan_from_markdown(id: &str, content: &str) -> PlanDocument {
+    let mut plan = parse_saved_plan(content);
+    plan.id = id.to_string();
+    plan
+}
+
+/// Parse a saved plan, including its status line.
+fn parse_saved_plan(content: &str) -> PlanDocument {
+    let mut plan = parse_llm_plan(content);
+
     // Check for status line in the content
     for line in content.lines() {
         let trimmed = line.trim();
@@ -470,7 +484,7 @@ pub fn load_plan(path: &Path) -> std::io::Result<PlanDocument> {
         }
     }
 
-    Ok(plan)
+    plan
 }
 
 /// List all saved plans.
@@ -9,9 +9,36 @@
 //!
 //! The engine is designed to be non-blocking: rule and history predictions return
 //! immediately, while LLM predictions arrive asynchronously via a spawned task.
-
+//!
+//! ## Context
+//!
+//! Predictions condition on more than the previous command:
+//!
+//! - **Exit status** — failure rules suggest the usual fix (`git pull --rebase`
+//!   after a rejected push, `chmod +x` after exit 126), and history keeps
+//!   separate counts for what follows a success and what follows a failure.
+//! - **Project kind** — detected from marker files (`Cargo.toml`,
+//!   `package.json`, ...), so `git pull` leads to `cargo build` in a Rust
+//!   project and `npm install` in a Node one.
+//! - **Git state** — [`GitInfo`] ahead/behind/dirty counts, so `git commit`
+//!   suggests `git push` only once there is something to push.
+//!
+//! The history model and accuracy stats persist in `~/.elwood/predictions.json`.
+//! The model is capped at [`MAX_HISTORY_KEYS`] contexts of
+//! [`MAX_FOLLOWERS`] commands each; the least-seen ones are dropped first.
+//! Counts recorded since the last save are batched for up to
+//! [`SAVE_INTERVAL`] and then added to what is on disk ([`ModelUpdate`]), so
+//! panes sharing the file don't overwrite each other's history.
+
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+
+use crate::git_info::GitInfo;
 
 /// Context for making a prediction.
 #[derive(Debug, Clone)]
@@ -24,6 +51,8 @@ pub struct PredictionContext {
     pub working_dir: PathBuf,
     /// Current git branch, if in a git repo.
     pub git_branch: Option<String>,
+    /// Git repository state (ahead/behind/dirty), if in a git repo.
+    pub git: Option<GitInfo>,
     /// The last N commands executed this session (most recent last).
     pub recent_commands: Vec<String>,
 }
@@ -50,6 +79,74 @@ pub enum PredictionSource {
     Llm,
 }
 
+/// Kind of project a directory belongs to, from its marker files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectKind {
+    /// `Cargo.toml`
+    Rust,
+    /// `package.json`
+    Node,
+    /// `pyproject.toml`, `setup.py` or `requirements.txt`
+    Python,
+    /// `go.mod`
+    Go,
+    /// `Makefile`
+    Make,
+    /// No marker found.
+    Unknown,
+}
+
+/// Marker files in priority order: a Rust crate with a Makefile is a Rust project.
+const PROJECT_MARKERS: &[(&str, ProjectKind)] = &[
+    ("Cargo.toml", ProjectKind::Rust),
+    ("package.json", ProjectKind::Node),
+    ("pyproject.toml", ProjectKind::Python),
+    ("setup.py", ProjectKind::Python),
+    ("requirements.txt", ProjectKind::Python),
+    ("go.mod", ProjectKind::Go),
+    ("Makefile", ProjectKind::Make),
+];
+
+impl ProjectKind {
+    /// Detect the project kind of `dir` from the nearest ancestor with a
+    /// marker file, without looking past the repository root.
+    pub fn detect(dir: &Path) -> Self {
+        for ancestor in dir.ancestors() {
+            for (marker, kind) in PROJECT_MARKERS {
+                if ancestor.join(marker).is_file() {
+                    return *kind;
+                }
+            }
+            if ancestor.join(".git").exists() {
+                break;
+            }
+        }
+        ProjectKind::Unknown
+    }
+
+    /// Short name used in history keys and stats.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProjectKind::Rust => "rust",
+            ProjectKind::Node => "node",
+            ProjectKind::Python => "python",
+            ProjectKind::Go => "go",
+            ProjectKind::Make => "make",
+            ProjectKind::Unknown => "unknown",
+        }
+    }
+}
+
+/// Whether a rule's success/failure filter accepts the last exit status.
+fn outcome_matches(on_success: Option<bool>, success: Option<bool>) -> bool {
+    match (on_success, success) {
+        (Some(required), Some(s)) => s == required,
+        // Unknown exit code: skip success-only rules
+        (Some(required), None) => !required,
+        (None, _) => true,
+    }
+}
+
 // ─── Rule Predictor ─────────────────────────────────────────────────────────
 
 /// A rule mapping a command pattern to a predicted next command.
@@ -64,14 +161,116 @@ struct Rule {
     confidence: f32,
 }
 
+/// A rule that also depends on git state or project kind. Context rules are
+/// checked before plain [`Rule`]s so the more specific prediction wins.
+struct ContextRule {
+    /// Prefix the last command must match (lowercase).
+    prefix: &'static str,
+    /// Whether this rule applies on success, failure, or both.
+    on_success: Option<bool>,
+    /// Git state the rule requires; the rule never applies outside a repo.
+    git: Option<fn(&GitInfo) -> bool>,
+    /// Project kind the rule requires.
+    project: Option<ProjectKind>,
+    /// The predicted command. May contain the same placeholders as [`Rule`].
+    prediction: &'static str,
+    /// Confidence score for this rule.
+    confidence: f32,
+}
+
 /// Instant, rule-based predictions for common command sequences.
 struct RulePredictor {
+    context_rules: Vec<ContextRule>,
     rules: Vec<Rule>,
 }
 
 impl RulePredictor {
     fn new() -> Self {
         Self {
+            context_rules: vec![
+                // ── Git state ───────────────────────────────────
+                ContextRule {
+                    prefix: "git commit",
+                    on_success: Some(true),
+                    git: Some(|g| g.ahead > 0),
+                    project: None,
+                    prediction: "git push",
+                    confidence: 0.92,
+                },
+                ContextRule {
+                    prefix: "git pull",
+                    on_success: Some(true),
+                    git: Some(|g| g.ahead > 0),
+                    project: None,
+                    prediction: "git push",
+                    confidence: 0.80,
+                },
+                ContextRule {
+                    prefix: "git fetch",
+                    on_success: Some(true),
+                    git: Some(|g| g.behind > 0),
+                    project: None,
+                    prediction: "git pull --rebase",
+                    confidence: 0.85,
+                },
+                ContextRule {
+                    prefix: "git push",
+                    on_success: Some(false),
+                    git: Some(|g| g.behind > 0),
+                    project: None,
+                    prediction: "git pull --rebase",
+                    confidence: 0.92,
+                },
+                ContextRule {
+                    prefix: "git status",
+                    on_success: Some(true),
+                    git: Some(|g| g.is_dirty),
+                    project: None,
+                    prediction: "git add .",
+                    confidence: 0.60,
+                },
+                // ── Project kind ────────────────────────────────
+                ContextRule {
+                    prefix: "git pull",
+                    on_success: Some(true),
+                    git: None,
+                    project: Some(ProjectKind::Rust),
+                    prediction: "cargo build",
+                    confidence: 0.70,
+                },
+                ContextRule {
+                    prefix: "git pull",
+                    on_success: Some(true),
+                    git: None,
+                    project: Some(ProjectKind::Node),
+                    prediction: "npm install",
+                    confidence: 0.70,
+                },
+                ContextRule {
+                    prefix: "git pull",
+                    on_success: Some(true),
+                    git: None,
+                    project: Some(ProjectKind::Go),
+                    prediction: "go build ./...",
+                    confidence: 0.65,
+                },
+                ContextRule {
+                    prefix: "npm start",
+                    on_success: Some(false),
+                    git: None,
+                    project: Some(ProjectKind::Node),
+                    prediction: "npm install",
+                    confidence: 0.70,
+                },
+                ContextRule {
+                    prefix: "npm run dev",
+                    on_success: Some(false),
+                    git: None,
+                    project: Some(ProjectKind::Node),
+                    prediction: "npm install",
+                    confidence: 0.65,
+                },
+            ],
             rules: vec![
                 // ── Git workflow ──────────────────────────────────
                 Rule {
@@ -122,6 +321,18 @@ impl RulePredictor {
                     prediction: "git push --force-with-lease",
                     confidence: 0.60,
                 },
+                Rule {
+                    prefix: "git push",
+                    on_success: Some(false),
+                    prediction: "git pull --rebase",
+                    confidence: 0.75,
+                },
+                Rule {
+                    prefix: "git commit",
+                    on_success: Some(false),
+                    prediction: "git status",
+                    confidence: 0.60,
+                },
                 // ── Directory navigation ─────────────────────────
                 Rule {
                     prefix: "cd ",
@@ -184,6 +395,12 @@ impl RulePredictor {
                     prediction: "cargo clippy -- -D warnings",
                     confidence: 0.70,
                 },
+                Rule {
+                    prefix: "cargo fmt",
+                    on_success: Some(false),
+                    prediction: "cargo fmt",
+                    confidence: 0.85,
+                },
                 Rule {
                     prefix: "cargo check",
                     on_success: Some(true),
@@ -264,6 +481,19 @@ impl RulePredictor {
                     prediction: "pytest",
                     confidence: 0.85,
                 },
+                // ── Go ──────────────────────────────────────────
+                Rule {
+                    prefix: "go build",
+                    on_success: Some(false),
+                    prediction: "go mod tidy",
+                    confidence: 0.60,
+                },
+                Rule {
+                    prefix: "go mod tidy",
+                    on_success: Some(true),
+                    prediction: "go build ./...",
+                    confidence: 0.75,
+                },
                 // ── Docker ──────────────────────────────────────
                 Rule {
                     prefix: "docker build",
@@ -304,32 +534,37 @@ impl RulePredictor {
     }
 
     /// Predict the next command given context.
-    fn predict(&self, ctx: &PredictionContext) -> Option<Prediction> {
+    fn predict(&self, ctx: &PredictionContext, project: ProjectKind) -> Option<Prediction> {
         let lower = ctx.last_command.to_lowercase();
         let success = ctx.last_exit_code.map(|c| c == 0);
 
-        for rule in &self.rules {
-            if !lower.starts_with(rule.prefix) {
-                continue;
-            }
-            // Check success/failure filter
-            if let Some(requires_success) = rule.on_success {
-                match success {
-                    Some(s) if s != requires_success => continue,
-                    None if requires_success => continue, // unknown exit code, skip success-only rules
-                    _ => {}
-                }
-            }
-
-            let command = self.expand_placeholders(rule.prediction, ctx);
-            return Some(Prediction {
-                command,
-                confidence: rule.confidence,
-                source: PredictionSource::Rule,
-            });
+        if let Some(p) = permission_fix(ctx) {
+            return Some(p);
         }
 
-        None
+        let context_match = self.context_rules.iter().find(|rule| {
+            lower.starts_with(rule.prefix)
+                && outcome_matches(rule.on_success, success)
+                && rule
+                    .git
+                    .is_none_or(|applies| ctx.git.as_ref().is_some_and(applies))
+                && rule.project.is_none_or(|kind| kind == project)
+        });
+        let (prediction, confidence) = match context_match {
+            Some(rule) => (rule.prediction, rule.confidence),
+            None => {
+                let rule = self.rules.iter().find(|rule| {
+                    lower.starts_with(rule.prefix) && outcome_matches(rule.on_success, success)
+                })?;
+                (rule.prediction, rule.confidence)
+            }
+        };
+
+        Some(Prediction {
+            command: self.expand_placeholders(prediction, ctx),
+            confidence,
+            source: PredictionSource::Rule,
+        })
     }
 
     /// Expand `{dir}`, `{branch}`, `{pkg}` placeholders in a prediction template.
@@ -346,6 +581,7 @@ impl RulePredictor {
         if result.contains("{branch}") {
             let branch = extract_branch_arg(&ctx.last_command)
                 .or_else(|| ctx.git_branch.clone())
+                .or_else(|| ctx.git.as_ref().map(|g| g.branch.clone()))
                 .unwrap_or_else(|| "branch".to_string());
             result = result.replace("{branch}", &branch);
         }
@@ -354,6 +590,20 @@ impl RulePredictor {
     }
 }
 
+/// Exit status 126 means the file exists but isn't executable: suggest making
+/// the script executable.
+fn permission_fix(ctx: &PredictionContext) -> Option<Prediction> {
+    if ctx.last_exit_code != Some(126) {
+        return None;
+    }
+    let program = ctx.last_command.split_whitespace().next()?;
+    program.contains('/').then(|| Prediction {
+        command: format!("chmod +x {program}"),
+        confidence: 0.85,
+        source: PredictionSource::Rule,
+    })
+}
+
 /// Extract a directory-like argument from a command string.
 ///
 /// For commands like `mkdir foo`, `cd foo`, `cargo new myapp`, extracts `foo`/`myapp`.
@@ -381,47 +631,121 @@ fn extract_branch_arg(command: &str) -> Option<String> {
 // ─── History Predictor ──────────────────────────────────────────────────────
 
 /// History-based bigram predictor — tracks what command typically follows what.
+///
+/// Each transition is counted under three keys, from least to most specific:
+/// the previous command alone, plus its exit outcome, plus the project kind.
+/// Prediction uses the most specific key with enough observations.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 struct HistoryPredictor {
-    /// Bigram counts: `bigrams[prev_command] = { next_command: count }`.
+    /// Bigram counts: `bigrams[context_key] = { next_command: count }`.
     bigrams: HashMap<String, HashMap<String, u32>>,
-    /// Total occurrences of each command as "previous command".
+    /// Total occurrences of each context key.
     totals: HashMap<String, u32>,
 }
 
+/// Confidence multiplier per context key, least to most specific. More
+/// specific history is trusted more but still ranks below most rules.
+const HISTORY_SCALE: [f32; 3] = [0.8, 0.85, 0.9];
+
+/// Most context keys the history model keeps.
+pub const MAX_HISTORY_KEYS: usize = 2_000;
+
+/// Most follower commands kept per context key.
+pub const MAX_FOLLOWERS: usize = 16;
+
 impl HistoryPredictor {
     fn new() -> Self {
-        Self {
-            bigrams: HashMap::new(),
-            totals: HashMap::new(),
-        }
+        Self::default()
     }
 
-    /// Record that `next` was executed after `prev`.
-    fn record(&mut self, prev: &str, next: &str) {
-        let prev_key = normalize_command(prev);
+    /// Record that `next` was executed after `prev`, which exited with
+    /// `prev_exit` in a `project` directory.
+    fn record(&mut self, prev: &str, prev_exit: Option<i32>, project: ProjectKind, next: &str) {
         let next_key = normalize_command(next);
+        for key in history_keys(prev, prev_exit, project) {
+            *self
+                .bigrams
+                .entry(key.clone())
+                .or_default()
+                .entry(next_key.clone())
+                .or_insert(0) += 1;
+            *self.totals.entry(key).or_insert(0) += 1;
+        }
+        self.prune();
+    }
 
-        *self
+    /// Add the counts of `other` to this model.
+    fn merge(&mut self, other: &HistoryPredictor) {
+        for (key, followers) in &other.bigrams {
+            let ours = self.bigrams.entry(key.clone()).or_default();
+            for (next, count) in followers {
+                *ours.entry(next.clone()).or_insert(0) += count;
+            }
+        }
+        for (key, total) in &other.totals {
+            *self.totals.entry(key.clone()).or_insert(0) += total;
+        }
+        self.prune();
+    }
+
+    /// Enforce [`MAX_FOLLOWERS`] and [`MAX_HISTORY_KEYS`], dropping the
+    /// least frequent followers and the least seen context keys.
+    fn prune(&mut self) {
+        for (key, followers) in &mut self.bigrams {
+            while followers.len() > MAX_FOLLOWERS {
+                let Some((rarest, count)) = followers
+                    .iter()
+                    .min_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+                    .map(|(cmd, count)| (cmd.clone(), *count))
+                else {
+                    break;
+                };
+                followers.remove(&rarest);
+                if let Some(total) = self.totals.get_mut(key) {
+                    *total = total.saturating_sub(count);
+                }
+            }
+        }
+
+        if self.bigrams.len() <= MAX_HISTORY_KEYS {
+            return;
+        }
+        // Trim to 90% so pruning doesn't run on every new key
+        let keep = MAX_HISTORY_KEYS * 9 / 10;
+        let mut keys: Vec<(String, u32)> = self
             .bigrams
-            .entry(prev_key.clone())
-            .or_default()
-            .entry(next_key)
-            .or_insert(0) += 1;
-        *self.totals.entry(prev_key).or_insert(0) += 1;
+            .keys()
+            .map(|k| (k.clone(), self.totals.get(k).copied().unwrap_or(0)))
+            .collect();
+        keys.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        for (key, _) in keys.drain(keep..) {
+            self.bigrams.remove(&key);
+            self.totals.remove(&key);
+        }
     }
 
     /// Predict the next command based on bigram frequencies.
-    fn predict(&self, ctx: &PredictionContext) -> Option<Prediction> {
-        let key = normalize_command(&ctx.last_command);
-        let followers = self.bigrams.get(&key)?;
-        let total = *self.totals.get(&key)?;
+    fn predict(&self, ctx: &PredictionContext, project: ProjectKind) -> Option<Prediction> {
+        let keys = history_keys(&ctx.last_command, ctx.last_exit_code, project);
+        keys.iter()
+            .zip(HISTORY_SCALE)
+            .rev()
+            .find_map(|(key, scale)| self.predict_key(key, scale))
+    }
+
+    fn predict_key(&self, key: &str, scale: f32) -> Option<Prediction> {
+        let followers = self.bigrams.get(key)?;
+        let total = *self.totals.get(key)?;
 
         if total < 2 {
             return None; // Need at least 2 observations for a meaningful prediction
         }
 
-        // Find the most frequent follower
-        let (best_cmd, best_count) = followers.iter().max_by_key(|(_, count)| *count)?;
+        // Find the most frequent follower (ties broken by name for stability)
+        let (best_cmd, best_count) = followers
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))?;
 
         let confidence = (*best_count as f32) / (total as f32);
 
@@ -432,12 +756,28 @@ impl HistoryPredictor {
 
         Some(Prediction {
             command: best_cmd.clone(),
-            confidence: confidence * 0.8, // Scale down vs rules
+            confidence: confidence * scale, // Scale down vs rules
             source: PredictionSource::History,
         })
     }
 }
 
+/// Context keys for a transition out of `command`, least specific first.
+fn history_keys(command: &str, exit_code: Option<i32>, project: ProjectKind) -> [String; 3] {
+    let prev = normalize_command(command);
+    let outcome = match exit_code {
+        Some(0) => "ok",
+        Some(_) => "fail",
+        None => "?",
+    };
+    // Normalized commands never contain tabs, so they make a safe separator.
+    [
+        prev.clone(),
+        format!("{prev}\t{outcome}"),
+        format!("{prev}\t{outcome}\t{}", project.as_str()),
+    ]
+}
+
 /// Normalize a command for bigram matching.
 ///
 /// Strips arguments from common commands to group similar invocations.
@@ -459,6 +799,188 @@ fn normalize_command(cmd: &str) -> String {
     }
 }
 
+// ─── Accuracy Stats ────────────────────────────────────────────────────────
+
+/// How often predictions from one source matched the command run next.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SourceStats {
+    /// Predictions shown and followed by another command.
+    pub shown: u32,
+    /// The next command was exactly the prediction.
+    pub exact: u32,
+    /// The next command matched after normalization (`cargo test -p foo`
+    /// for a `cargo test` prediction). Includes exact matches.
+    pub matched: u32,
+}
+
+impl SourceStats {
+    /// Fraction of shown predictions that matched, if any were shown.
+    pub fn accuracy(&self) -> Option<f32> {
+        (self.shown > 0).then(|| self.matched as f32 / self.shown as f32)
+    }
+
+    fn add(&mut self, other: &SourceStats) {
+        self.shown += other.shown;
+        self.exact += other.exact;
+        self.matched += other.matched;
+    }
+}
+
+/// Prediction accuracy per source, persisted with the history model.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PredictionStats {
+    pub rule: SourceStats,
+    pub history: SourceStats,
+    pub llm: SourceStats,
+}
+
+impl PredictionStats {
+    /// Stats for one source.
+    pub fn source(&self, source: PredictionSource) -> &SourceStats {
+        match source {
+            PredictionSource::Rule => &self.rule,
+            PredictionSource::History => &self.history,
+            PredictionSource::Llm => &self.llm,
+        }
+    }
+
+    /// Add the counts of `other` to these stats.
+    fn merge(&mut self, other: &PredictionStats) {
+        self.rule.add(&other.rule);
+        self.history.add(&other.history);
+        self.llm.add(&other.llm);
+    }
+
+    /// Stats across all sources.
+    pub fn total(&self) -> SourceStats {
+        let mut total = self.rule;
+        total.add(&self.history);
+        total.add(&self.llm);
+        total
+    }
+
+    /// Score a shown prediction against the command that was actually run.
+    fn score(&mut self, prediction: &Prediction, command: &str) {
+        let stats = match prediction.source {
+            PredictionSource::Rule => &mut self.rule,
+            PredictionSource::History => &mut self.history,
+            PredictionSource::Llm => &mut self.llm,
+        };
+        stats.shown += 1;
+        if prediction.command.trim() == command.trim() {
+            stats.exact += 1;
+        }
+        if normalize_command(&prediction.command) == normalize_command(command) {
+            stats.matched += 1;
+        }
+    }
+}
+
+/// Render accuracy stats for `/predictions`.
+pub fn format_stats(stats: &PredictionStats) -> String {
+    let total = stats.total();
+    if total.shown == 0 {
+        return "No predictions scored yet. Accuracy is tracked once a predicted \
+                command is followed by the next one you run."
+            .to_string();
+    }
+    let mut out = String::from("Prediction accuracy:\n\n");
+    out.push_str(&format!(
+        "  {:<9} {:>6} {:>8} {:>6} {:>9}\n",
+        "Source", "Shown", "Matched", "Exact", "Accuracy"
+    ));
+    let rows = [
+        ("Rules", stats.rule),
+        ("History", stats.history),
+        ("LLM", stats.llm),
+        ("Total", total),
+    ];
+    for (name, s) in rows {
+        let accuracy = s
+            .accuracy()
+            .map(|a| format!("{:.0}%", a * 100.0))
+            .unwrap_or_else(|| "-".to_string());
+        out.push_str(&format!(
+            "  {name:<9} {:>6} {:>8} {:>6} {accuracy:>9}\n",
+            s.shown, s.matched, s.exact
+        ));
+    }
+    out
+}
+
+// ─── Persistence ───────────────────────────────────────────────────────────
+
+/// On-disk form of the learned model: history counts plus accuracy stats.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedModel<'a> {
+    history: Cow<'a, HistoryPredictor>,
+    stats: Cow<'a, PredictionStats>,
+}
+
+/// Counts recorded since the last save, to be added to the model file by
+/// [`save`](Self::save). Taken with [`PredictionEngine::take_unsaved`].
+pub struct ModelUpdate {
+    path: PathBuf,
+    history: HistoryPredictor,
+    stats: PredictionStats,
+}
+
+impl ModelUpdate {
+    /// Read the model file, add these counts and write it back.
+    ///
+    /// Does file I/O, so keep it off the GUI thread. An unreadable file is
+    /// replaced.
+    pub fn save(self) -> anyhow::Result<()> {
+        // Panes of one process take turns; other processes can still race
+        // between the read and the rename, losing only that batch
+        static SAVING: parking_lot::Mutex<()> = parking_lot::Mutex::new(());
+        let _saving = SAVING.lock();
+
+        let (mut history, mut stats) = match PredictionEngine::load(&self.path) {
+            Ok(model) => model,
+            Err(e) => {
+                log::warn!("Replacing unreadable prediction model: {e:#}");
+                Default::default()
+            }
+        };
+        history.merge(&self.history);
+        stats.merge(&self.stats);
+        write_model(&self.path, &history, &stats)
+    }
+}
+
+/// Write the model atomically (temp file, then rename).
+fn write_model(
+    path: &Path,
+    history: &HistoryPredictor,
+    stats: &PredictionStats,
+) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("creating {}", parent.display()))?;
+    }
+    let model = SavedModel {
+        history: Cow::Borrowed(history),
+        stats: Cow::Borrowed(stats),
+    };
+    let json = serde_json::to_string_pretty(&model)?;
+    let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    std::fs::write(&tmp, json).with_context(|| format!("writing {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("renaming to {}", path.display()))?;
+    Ok(())
+}
+
+/// Default location of the persisted model: `~/.elwood/predictions.json`.
+pub fn default_model_path() -> PathBuf {
+    dirs_next::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".elwood")
+        .join("predictions.json")
+}
+
 // ─── Prediction Engine ─────────────────────────────────────────────────────
 
 /// Next-command prediction engine combining rule, history, and LLM sources.
@@ -472,8 +994,24 @@ pub struct PredictionEngine {
     llm_prediction: Option<Prediction>,
     /// Previous command for bigram tracking.
     prev_command: Option<String>,
+    /// Exit code and project kind of `prev_command`.
+    prev_context: (Option<i32>, ProjectKind),
+    /// Last prediction returned, scored against the next recorded command.
+    pending: Option<Prediction>,
+    /// Accuracy of the predictions made so far.
+    stats: PredictionStats,
+    /// Where the model is saved, if anywhere.
+    model_path: Option<PathBuf>,
+    /// History and stats recorded since the last save.
+    unsaved: (HistoryPredictor, PredictionStats),
+    /// When the oldest unsaved command was recorded.
+    unsaved_since: Option<Instant>,
 }
 
+/// Longest a recorded command waits before [`PredictionEngine::take_unsaved`]
+/// hands it out for saving.
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
 impl PredictionEngine {
     /// Create a new prediction engine.
     pub fn new() -> Self {
@@ -482,23 +1020,80 @@ impl PredictionEngine {
             history_predictor: HistoryPredictor::new(),
             llm_prediction: None,
             prev_command: None,
+            prev_context: (None, ProjectKind::Unknown),
+            pending: None,
+            stats: PredictionStats::default(),
+            model_path: None,
+            unsaved: Default::default(),
+            unsaved_since: None,
         }
     }
 
+    /// Load the persisted model from `path` and save recorded commands back
+    /// to it (see [`take_unsaved`](Self::take_unsaved)). Returns the load
+    /// error, if any, alongside an engine that starts empty but still saves
+    /// to `path`.
+    pub fn with_model_path(mut self, path: PathBuf) -> (Self, Option<String>) {
+        let error = match Self::load(&path) {
+            Ok((history, stats)) => {
+                self.history_predictor = history;
+                self.stats = stats;
+                None
+            }
+            Err(e) => Some(format!("{e:#}")),
+        };
+        self.model_path = Some(path);
+        (self, error)
+    }
+
+    fn load(path: &Path) -> anyhow::Result<(HistoryPredictor, PredictionStats)> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Default::default()),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+        };
+        let model: SavedModel =
+            serde_json::from_str(&json).with_context(|| format!("parsing {}", path.display()))?;
+        // Models saved before the caps existed may be larger
+        let mut history = model.history.into_owned();
+        history.prune();
+        Ok((history, model.stats.into_owned()))
+    }
+
+    /// The commands recorded since the last save, once the oldest of them
+    /// is [`SAVE_INTERVAL`] old (or right away with `force`, e.g. when the
+    /// pane closes). `None` when there is nothing to save yet or no model
+    /// path.
+    pub fn take_unsaved(&mut self, force: bool) -> Option<ModelUpdate> {
+        let since = self.unsaved_since?;
+        if !force && since.elapsed() < SAVE_INTERVAL {
+            return None;
+        }
+        self.unsaved_since = None;
+        let (history, stats) = std::mem::take(&mut self.unsaved);
+        Some(ModelUpdate {
+            path: self.model_path.clone()?,
+            history,
+            stats,
+        })
+    }
+
     /// Predict the next command given context.
     ///
     /// Returns the highest-confidence prediction from rule or history sources.
-    /// LLM predictions (if available) are also considered.
+    /// LLM predictions (if available) are also considered. The returned
+    /// prediction is scored against the next recorded command.
     pub fn predict(&mut self, ctx: &PredictionContext) -> Option<Prediction> {
+        let project = ProjectKind::detect(&ctx.working_dir);
         let mut candidates: Vec<Prediction> = Vec::new();
 
         // 1. Rule-based prediction (instant, highest priority)
-        if let Some(p) = self.rule_predictor.predict(ctx) {
+        if let Some(p) = self.rule_predictor.predict(ctx, project) {
             candidates.push(p);
         }
 
         // 2. History-based prediction (fast, medium priority)
-        if let Some(p) = self.history_predictor.predict(ctx) {
+        if let Some(p) = self.history_predictor.predict(ctx, project) {
             candidates.push(p);
         }
 
@@ -513,19 +1108,68 @@ impl PredictionEngine {
                 .partial_cmp(&a.confidence)
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
-        candidates.into_iter().next()
+        let best = candidates.into_iter().next();
+        self.pending = best.clone();
+        best
+    }
+
+    /// Record that a command was executed (for bigram tracking), without
+    /// project context. Prefer [`Self::record_command_in`].
+    pub fn record_command(&mut self, command: &str, exit_code: Option<i32>) {
+        self.record(command, exit_code, ProjectKind::Unknown);
     }
 
-    /// Record that a command was executed (for bigram tracking).
+    /// Record that a command finished in `working_dir` with `exit_code`.
     ///
-    /// Call this after each command completes. The `exit_code` is stored for
-    /// context but the bigram model currently ignores it.
-    pub fn record_command(&mut self, command: &str, _exit_code: Option<i32>) {
+    /// Call this after each command completes. The exit code and project
+    /// kind condition the transition to whatever command runs next.
+    pub fn record_command_in(&mut self, command: &str, exit_code: Option<i32>, working_dir: &Path) {
+        self.record(command, exit_code, ProjectKind::detect(working_dir));
+    }
+
+    fn record(&mut self, command: &str, exit_code: Option<i32>, project: ProjectKind) {
+        let saving = self.model_path.is_some();
+        if let Some(prediction) = self.pending.take() {
+            self.stats.score(&prediction, command);
+            if saving {
+                self.unsaved.1.score(&prediction, command);
+            }
+        }
         // Update bigram model
         if let Some(ref prev) = self.prev_command {
-            self.history_predictor.record(prev, command);
+            let (prev_exit, prev_project) = self.prev_context;
+            self.history_predictor
+                .record(prev, prev_exit, prev_project, command);
+            if saving {
+                self.unsaved
+                    .0
+                    .record(prev, prev_exit, prev_project, command);
+            }
         }
         self.prev_command = Some(command.to_string());
+        self.prev_context = (exit_code, project);
+
+        if saving && self.unsaved_since.is_none() {
+            self.unsaved_since = Some(Instant::now());
+        }
+    }
+
+    /// Accuracy of the predictions scored so far.
+    pub fn stats(&self) -> &PredictionStats {
+        &self.stats
+    }
+
+    /// Forget the learned history and accuracy stats.
+    pub fn reset(&mut self) -> anyhow::Result<()> {
+        self.history_predictor = HistoryPredictor::new();
+        self.stats = PredictionStats::default();
+        self.pending = None;
+        self.unsaved = Default::default();
+        self.unsaved_since = None;
+        match &self.model_path {
+            Some(path) => write_model(path, &self.history_predictor, &self.stats),
+            None => Ok(()),
+        }
     }
 
     /// Set an LLM prediction (called from async context).
@@ -557,6 +1201,7 @@ mod tests {
             last_exit_code: Some(exit_code),
             working_dir: PathBuf::from("/tmp/project"),
             git_branch: Some("main".to_string()),
+            git: None,
             recent_commands: vec![cmd.to_string()],
         }
     }
@@ -829,6 +1474,7 @@ mod tests {
             last_exit_code: Some(0),
             working_dir: PathBuf::from("/tmp"),
             git_branch: None,
+            git: None,
             // No rule match for `cargo check` success + `cargo test` follow-up
             // at higher confidence than history, so history wins only if rules
             // don't fire first. In this case, the rule predictor has a rule for
@@ -847,13 +1493,17 @@ mod tests {
         engine.record_command("bar", Some(0));
 
         // The history predictor needs at least 2 observations of the same prev command
-        let p = engine.history_predictor.predict(&PredictionContext {
-            last_command: "foo".to_string(),
-            last_exit_code: Some(0),
-            working_dir: PathBuf::from("/tmp"),
-            git_branch: None,
-            recent_commands: vec!["foo".to_string()],
-        });
+        let p = engine.history_predictor.predict(
+            &PredictionContext {
+                last_command: "foo".to_string(),
+                last_exit_code: Some(0),
+                working_dir: PathBuf::from("/tmp"),
+                git_branch: None,
+                git: None,
+                recent_commands: vec!["foo".to_string()],
+            },
+            ProjectKind::Unknown,
+        );
         assert!(p.is_none());
     }
 
@@ -866,13 +1516,17 @@ mod tests {
             engine.record_command("bar", Some(0));
         }
 
-        let p = engine.history_predictor.predict(&PredictionContext {
-            last_command: "foo".to_string(),
-            last_exit_code: Some(0),
-            working_dir: PathBuf::from("/tmp"),
-            git_branch: None,
-            recent_commands: vec!["foo".to_string()],
-        });
+        let p = engine.history_predictor.predict(
+            &PredictionContext {
+                last_command: "foo".to_string(),
+                last_exit_code: Some(0),
+                working_dir: PathBuf::from("/tmp"),
+                git_branch: None,
+                git: None,
+                recent_commands: vec!["foo".to_string()],
+            },
+            ProjectKind::Unknown,
+        );
         assert!(p.is_some());
         assert_eq!(p.unwrap().command, "bar");
     }
@@ -1025,6 +1679,253 @@ mod tests {
         assert_eq!(extract_branch_arg("git checkout main"), None);
     }
 
+    // ── Exit status, project kind and git state ─────────────────────────
+
+    fn git(ahead: u32, behind: u32, is_dirty: bool) -> Option<GitInfo> {
+        Some(GitInfo {
+            branch: "main".to_string(),
+            is_dirty,
+            ahead,
+            behind,
+        })
+    }
+
+    fn ctx_in(cmd: &str, exit_code: i32, dir: &Path) -> PredictionContext {
+        PredictionContext {
+            working_dir: dir.to_path_buf(),
+            ..ctx(cmd, exit_code)
+        }
+    }
+
+    #[test]
+    fn failure_suggests_fix() {
+        let mut engine = PredictionEngine::new();
+        let p = engine.predict(&ctx_failure("git push")).unwrap();
+        assert_eq!(p.command, "git pull --rebase");
+
+        let p = engine
+            .predict(&ctx_failure("cargo fmt -- --check"))
+            .unwrap();
+        assert_eq!(p.command, "cargo fmt");
+
+        let p = engine.predict(&ctx("./deploy.sh --prod", 126)).unwrap();
+        assert_eq!(p.command, "chmod +x ./deploy.sh");
+    }
+
+    #[test]
+    fn git_state_conditions_rules() {
+        let mut engine = PredictionEngine::new();
+
+        let mut c = ctx_success("git commit -m \"fix\"");
+        c.git = git(1, 0, false);
+        let p = engine.predict(&c).unwrap();
+        assert_eq!(p.command, "git push");
+        assert!(p.confidence > 0.9);
+
+        let mut c = ctx_success("git fetch");
+        c.git = git(0, 3, false);
+        assert_eq!(engine.predict(&c).unwrap().command, "git pull --rebase");
+        // Nothing to pull: no git-state rule applies
+        c.git = git(0, 0, false);
+        assert!(engine.predict(&c).is_none());
+
+        let mut c = ctx_success("git status");
+        c.git = git(0, 0, true);
+        assert_eq!(engine.predict(&c).unwrap().command, "git add .");
+        c.git = git(0, 0, false);
+        assert!(engine.predict(&c).is_none());
+    }
+
+    #[test]
+    fn project_kind_detection() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        assert_eq!(ProjectKind::detect(root), ProjectKind::Unknown);
+
+        std::fs::write(root.join("Makefile"), "all:\n").unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[package]\n").unwrap();
+        std::fs::create_dir_all(root.join("src/bin")).unwrap();
+        assert_eq!(ProjectKind::detect(root), ProjectKind::Rust);
+        assert_eq!(
+            ProjectKind::detect(&root.join("src/bin")),
+            ProjectKind::Rust
+        );
+
+        // A nested repository stops the search at its root
+        let nested = root.join("vendor/web");
+        std::fs::create_dir_all(nested.join(".git")).unwrap();
+        assert_eq!(ProjectKind::detect(&nested), ProjectKind::Unknown);
+        std::fs::write(nested.join("package.json"), "{}").unwrap();
+        assert_eq!(ProjectKind::detect(&nested), ProjectKind::Node);
+    }
+
+    #[test]
+    fn project_kind_conditions_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]\n").unwrap();
+
+        let mut engine = PredictionEngine::new();
+        let p = engine.predict(&ctx_in("git pull", 0, dir.path())).unwrap();
+        assert_eq!(p.command, "cargo build");
+
+        std::fs::remove_file(dir.path().join("Cargo.toml")).unwrap();
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        let p = engine.predict(&ctx_in("git pull", 0, dir.path())).unwrap();
+        assert_eq!(p.command, "npm install");
+    }
+
+    #[test]
+    fn history_conditions_on_exit_status() {
+        let mut engine = PredictionEngine::new();
+        for _ in 0..3 {
+            engine.record_command("deploy staging", Some(1));
+            engine.record_command("vault login", Some(0));
+            engine.record_command("deploy staging", Some(0));
+            engine.record_command("notify-team", Some(0));
+        }
+
+        let after_failure = engine.predict(&ctx_failure("deploy staging")).unwrap();
+        assert_eq!(after_failure.source, PredictionSource::History);
+        assert_eq!(after_failure.command, "vault");
+        let after_success = engine.predict(&ctx_success("deploy staging")).unwrap();
+        assert_eq!(after_success.command, "notify-team");
+    }
+
+    #[test]
+    fn history_conditions_on_project() {
+        let rust = tempfile::tempdir().unwrap();
+        std::fs::write(rust.path().join("Cargo.toml"), "[package]\n").unwrap();
+        let node = tempfile::tempdir().unwrap();
+        std::fs::write(node.path().join("package.json"), "{}").unwrap();
+
+        let mut engine = PredictionEngine::new();
+        for _ in 0..3 {
+            engine.record_command_in("vim notes.md", Some(0), rust.path());
+            engine.record_command_in("bacon", Some(0), rust.path());
+            engine.record_command_in("vim notes.md", Some(0), node.path());
+            engine.record_command_in("vite", Some(0), node.path());
+        }
+
+        let p = engine
+            .predict(&ctx_in("vim notes.md", 0, rust.path()))
+            .unwrap();
+        assert_eq!(p.command, "bacon");
+        let p = engine
+            .predict(&ctx_in("vim notes.md", 0, node.path()))
+            .unwrap();
+        assert_eq!(p.command, "vite");
+    }
+
+    #[test]
+    fn stats_score_predictions_against_next_command() {
+        let mut engine = PredictionEngine::new();
+        engine.predict(&ctx_success("git add ."));
+        engine.record_command("git commit -m \"wip\"", Some(0));
+        engine.predict(&ctx_success("cd src"));
+        engine.record_command("ls", Some(0));
+        engine.predict(&ctx_success("cargo build"));
+        engine.record_command("cargo run", Some(0));
+        // No prediction shown: nothing to score
+        engine.predict(&ctx_success("echo hi"));
+        engine.record_command("ls", Some(0));
+
+        let rule = engine.stats().source(PredictionSource::Rule);
+        assert_eq!(rule.shown, 3);
+        assert_eq!(rule.matched, 2);
+        assert_eq!(rule.exact, 1);
+        assert_eq!(engine.stats().total().accuracy(), Some(2.0 / 3.0));
+        assert!(format_stats(engine.stats()).contains("67%"));
+    }
+
+    #[test]
+    fn model_persists_and_resets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("predictions.json");
+
+        let (mut engine, error) = PredictionEngine::new().with_model_path(path.clone());
+        assert!(error.is_none());
+        engine.predict(&ctx_success("cd src"));
+        for _ in 0..3 {
+            engine.record_command("foo", Some(0));
+            engine.record_command("bar", Some(0));
+        }
+        // Saves are batched
+        assert!(engine.take_unsaved(false).is_none());
+        assert!(!path.exists());
+        engine.take_unsaved(true).unwrap().save().unwrap();
+        assert!(engine.take_unsaved(true).is_none());
+
+        let (mut reloaded, error) = PredictionEngine::new().with_model_path(path.clone());
+        assert!(error.is_none());
+        assert_eq!(reloaded.stats(), engine.stats());
+        assert_eq!(
+            reloaded.predict(&ctx_success("foo")).unwrap().command,
+            "bar"
+        );
+
+        reloaded.reset().unwrap();
+        let (mut reloaded, _) = PredictionEngine::new().with_model_path(path.clone());
+        assert_eq!(reloaded.stats().total().shown, 0);
+        assert!(reloaded.predict(&ctx_success("foo")).is_none());
+
+        std::fs::write(&path, "not json").unwrap();
+        let (_, error) = PredictionEngine::new().with_model_path(path);
+        assert!(error.unwrap().contains("parsing"));
+    }
+
+    #[test]
+    fn saves_merge_with_other_panes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("predictions.json");
+
+        // Two panes start from the same (empty) model and save in turn
+        let (mut first, _) = PredictionEngine::new().with_model_path(path.clone());
+        let (mut second, _) = PredictionEngine::new().with_model_path(path.clone());
+        for _ in 0..2 {
+            first.record_command("foo", Some(0));
+            first.record_command("bar", Some(0));
+            second.record_command("make", Some(0));
+            second.record_command("make test", Some(0));
+        }
+        first.take_unsaved(true).unwrap().save().unwrap();
+        second.take_unsaved(true).unwrap().save().unwrap();
+
+        let (mut reloaded, _) = PredictionEngine::new().with_model_path(path);
+        assert_eq!(
+            reloaded.predict(&ctx_success("foo")).unwrap().command,
+            "bar"
+        );
+        assert_eq!(
+            reloaded.predict(&ctx_success("make")).unwrap().command,
+            "make test"
+        );
+    }
+
+    #[test]
+    fn history_model_is_capped() {
+        let mut history = HistoryPredictor::new();
+        for _ in 0..3 {
+            history.record("make", Some(0), ProjectKind::Make, "make test");
+        }
+        for i in 0..MAX_FOLLOWERS + 5 {
+            history.record("make", Some(0), ProjectKind::Make, &format!("tool{i}"));
+        }
+        let followers = &history.bigrams["make"];
+        assert_eq!(followers.len(), MAX_FOLLOWERS);
+        assert!(followers.contains_key("make test"));
+        let kept: u32 = followers.values().sum();
+        assert_eq!(history.totals["make"], kept);
+
+        for i in 0..MAX_HISTORY_KEYS {
+            history.record(&format!("cmd{i}"), Some(0), ProjectKind::Make, "ls");
+        }
+        assert!(history.bigrams.len() <= MAX_HISTORY_KEYS);
+        assert_eq!(history.totals.len(), history.bigrams.len());
+        // The most used contexts survive
+        assert!(history.bigrams.contains_key("make"));
+    }
+
     // ── Default trait ───────────────────────────────────────────────────
 
     #[test]
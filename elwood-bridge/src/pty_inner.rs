@@ -7,6 +7,10 @@
 //! The reader runs on a dedicated `std::thread` (not tokio) because PTY
 //! file descriptor reads are blocking I/O that should not occupy an async
 //! executor thread.
+//!
+//! [`InnerPty::probe_definitions`] asks a separate interactive instance of
+//! the same shell for the user's aliases and functions, so the live session
+//! is never disturbed.
 
 use crate::shared_writer::SharedWriter;
 
@@ -16,8 +20,12 @@ use std::io::Read;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 use wezterm_term::Terminal;
 
+/// How long the alias/function probe may take (rc files can be slow).
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
 /// Manages the embedded PTY lifecycle.
 ///
 /// Created lazily on first terminal-mode activation (Ctrl+T). Owns the PTY
@@ -61,7 +69,7 @@ impl InnerPty {
         let pair = pty_system.openpty(size)?;
 
         // Build shell command
-        let shell = std::env::var("SHELL").unwrap_or_else(|_| "bash".to_string());
+        let shell = Self::shell_program();
         let mut cmd = CommandBuilder::new(&shell);
         cmd.cwd(cwd);
 
@@ -89,6 +97,29 @@ impl InnerPty {
         })
     }
 
+    /// The shell the embedded PTY runs: `$SHELL`, falling back to `bash`.
+    pub fn shell_program() -> String {
+        std::env::var("SHELL").unwrap_or_else(|_| "bash".to_string())
+    }
+
+    /// List the alias and function names defined by an interactive `shell`
+    /// started in `cwd` (so its rc files are read).
+    ///
+    /// Blocks for up to a few seconds; call it off the UI thread. Returns
+    /// nothing for unsupported shells or if the probe fails.
+    pub fn probe_definitions(shell: &str, cwd: &std::path::Path) -> Vec<String> {
+        let Some(script) = crate::autocorrect::definitions_probe_script(shell) else {
+            return Vec::new();
+        };
+        let lines = crate::completion_spec::command_lines_with_timeout(
+            shell,
+            &["-i", "-c", &script],
+            cwd,
+            PROBE_TIMEOUT,
+        );
+        crate::autocorrect::parse_definitions_probe(&lines)
+    }
+
     /// Returns `true` if the PTY child process has exited (EOF detected by reader).
     pub fn is_dead(&self) -> bool {
         self.dead.load(Ordering::Acquire)
@@ -236,6 +267,12 @@ mod tests {
         assert!(inner.is_dead());
     }
 
+    #[test]
+    fn test_probe_definitions_unsupported_shell() {
+        let cwd = std::env::current_dir().unwrap();
+        assert!(InnerPty::probe_definitions("/bin/tcsh", &cwd).is_empty());
+    }
+
     #[test]
     fn test_resize() {
         let shared_writer = SharedWriter::new();
@@ -0,0 +1,487 @@
+//! Quickfix list: errors from every observed pane, grouped by file.
+//!
+//! Built from [`ErrorDetection`]s that carry a source file. Entries are
+//! de-duplicated across panes (the same build error often shows up in a
+//! watcher and in a manual run), grouped by resolved path and sorted by
+//! line. The pane drives navigation and opens the selected location through
+//! [`EditorBridge::open_file`](crate::ide_bridge::EditorBridge::open_file).
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::observer::{ErrorDetection, ErrorType, Severity};
+use crate::theme;
+
+/// Maximum list rows (headers + entries) shown in the overlay.
+const QUICKFIX_MAX_ROWS: usize = 16;
+const QUICKFIX_WIDTH: usize = 90;
+const QUICKFIX_TOP_OFFSET: usize = 3;
+
+/// Errors detected in one pane, as input to [`QuickfixList::rebuild`].
+#[derive(Debug, Clone)]
+pub struct PaneErrors {
+    pub pane_id: usize,
+    pub title: String,
+    /// Working directory of the pane, used to resolve relative paths.
+    pub cwd: Option<PathBuf>,
+    pub detections: Vec<ErrorDetection>,
+}
+
+/// A single navigable error location.
+#[derive(Debug, Clone)]
+pub struct QuickfixEntry {
+    /// Pane the error was first seen in.
+    pub pane_id: usize,
+    pub pane_title: String,
+    /// Resolved path (absolute when the pane's cwd is known).
+    pub path: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub error_type: ErrorType,
+    pub severity: Severity,
+    /// Full error text, including continuation lines.
+    pub message: String,
+}
+
+impl QuickfixEntry {
+    /// First line of the message, for list display.
+    pub fn summary(&self) -> &str {
+        self.message.lines().next().unwrap_or("")
+    }
+
+    /// `line:col`, `line`, or empty.
+    pub fn position(&self) -> String {
+        match (self.line, self.column) {
+            (Some(l), Some(c)) => format!("{l}:{c}"),
+            (Some(l), None) => l.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn key(&self) -> (String, Option<u32>, Option<u32>, String) {
+        (
+            self.path.clone(),
+            self.line,
+            self.column,
+            self.summary().to_string(),
+        )
+    }
+}
+
+/// All entries for one file.
+#[derive(Debug, Clone)]
+pub struct QuickfixGroup {
+    pub path: String,
+    pub entries: Vec<QuickfixEntry>,
+}
+
+/// The quickfix list and its overlay state.
+#[derive(Debug, Default)]
+pub struct QuickfixList {
+    groups: Vec<QuickfixGroup>,
+    /// Flat index across all groups.
+    selected: usize,
+    open: bool,
+    /// Paths are displayed relative to this directory.
+    root: Option<PathBuf>,
+}
+
+impl QuickfixList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the list with errors from `panes`, keeping the selection on
+    /// the same entry when it still exists.
+    pub fn rebuild(&mut self, panes: Vec<PaneErrors>, root: Option<PathBuf>) {
+        let previous = self.selected_entry().map(QuickfixEntry::key);
+
+        let mut seen = HashSet::new();
+        let mut by_file: BTreeMap<String, Vec<QuickfixEntry>> = BTreeMap::new();
+        for pane in panes {
+            for det in pane.detections {
+                let Some(file) = det.source_file else {
+                    continue;
+                };
+                let entry = QuickfixEntry {
+                    pane_id: pane.pane_id,
+                    pane_title: pane.title.clone(),
+                    path: resolve_path(&file, pane.cwd.as_deref()),
+                    line: det.line,
+                    column: det.column,
+                    error_type: det.error_type,
+                    severity: det.severity,
+                    message: det.message,
+                };
+                if seen.insert(entry.key()) {
+                    by_file.entry(entry.path.clone()).or_default().push(entry);
+                }
+            }
+        }
+
+        self.groups = by_file
+            .into_iter()
+            .map(|(path, mut entries)| {
+                entries.sort_by_key(|e| (e.line, e.column));
+                QuickfixGroup { path, entries }
+            })
+            .collect();
+        self.root = root;
+        self.selected = previous
+            .and_then(|key| self.entries().position(|e| e.key() == key))
+            .unwrap_or(0);
+    }
+
+    pub fn groups(&self) -> &[QuickfixGroup] {
+        &self.groups
+    }
+
+    fn entries(&self) -> impl Iterator<Item = &QuickfixEntry> {
+        self.groups.iter().flat_map(|g| g.entries.iter())
+    }
+
+    pub fn len(&self) -> usize {
+        self.groups.iter().map(|g| g.entries.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    pub fn open(&mut self) {
+        self.open = true;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected_entry(&self) -> Option<&QuickfixEntry> {
+        self.entries().nth(self.selected)
+    }
+
+    /// Group containing the selected entry.
+    pub fn selected_group(&self) -> Option<&QuickfixGroup> {
+        let mut start = 0;
+        self.groups.iter().find(|g| {
+            start += g.entries.len();
+            self.selected < start
+        })
+    }
+
+    /// Move to the next entry, wrapping at the end.
+    pub fn select_next(&mut self) -> Option<&QuickfixEntry> {
+        let len = self.len();
+        if len > 0 {
+            self.selected = (self.selected + 1) % len;
+        }
+        self.selected_entry()
+    }
+
+    /// Move to the previous entry, wrapping at the start.
+    pub fn select_prev(&mut self) -> Option<&QuickfixEntry> {
+        let len = self.len();
+        if len > 0 {
+            self.selected = (self.selected + len - 1) % len;
+        }
+        self.selected_entry()
+    }
+
+    /// Jump to the first entry of the next file, wrapping.
+    pub fn select_next_file(&mut self) {
+        let starts = self.group_starts();
+        if starts.is_empty() {
+            return;
+        }
+        self.selected = starts
+            .iter()
+            .copied()
+            .find(|&s| s > self.selected)
+            .unwrap_or(starts[0]);
+    }
+
+    /// Jump to the first entry of the previous file, wrapping.
+    pub fn select_prev_file(&mut self) {
+        let starts = self.group_starts();
+        let current = starts.iter().rposition(|&s| s <= self.selected).unwrap_or(0);
+        let target = if current == 0 { starts.len().saturating_sub(1) } else { current - 1 };
+        self.selected = starts.get(target).copied().unwrap_or(0);
+    }
+
+    fn group_starts(&self) -> Vec<usize> {
+        let mut start = 0;
+        self.groups
+            .iter()
+            .map(|g| {
+                let s = start;
+                start += g.entries.len();
+                s
+            })
+            .collect()
+    }
+
+    /// Path for display: relative to the root when beneath it.
+    pub fn display_path<'a>(&self, path: &'a str) -> &'a str {
+        self.root
+            .as_deref()
+            .and_then(|root| Path::new(path).strip_prefix(root).ok())
+            .and_then(|p| p.to_str())
+            .unwrap_or(path)
+    }
+
+    /// Render the overlay as ANSI escape sequences.
+    pub fn render(&self, screen_width: u16) -> String {
+        if !self.open {
+            return String::new();
+        }
+        let t = theme::active();
+        let r = t.reset();
+        let border = t.ansi_fg(t.accent);
+        let fg = t.ansi_fg(t.fg_primary);
+        let muted = t.ansi_fg(t.fg_muted);
+        let header = t.ansi_bold_fg(t.fg_bright);
+        let sel_bg = t.ansi_bg(t.bg_tertiary);
+        let bold = t.bold();
+
+        let width = QUICKFIX_WIDTH.min((screen_width as usize).saturating_sub(4)).max(20);
+        let left = (screen_width as usize).saturating_sub(width) / 2 + 1;
+        let inner = width.saturating_sub(2);
+        let mut row = QUICKFIX_TOP_OFFSET;
+
+        let mut out = String::with_capacity(4096);
+        out.push_str("\x1b[s\x1b[?25l");
+
+        let files = self.groups.len();
+        let title = format!(
+            " Quickfix: {} error{} in {files} file{} ",
+            self.len(),
+            if self.len() == 1 { "" } else { "s" },
+            if files == 1 { "" } else { "s" },
+        );
+        let fill = "\u{2500}".repeat(inner.saturating_sub(title.chars().count() + 1));
+        out.push_str(&format!(
+            "\x1b[{row};{left}H{border}\u{256D}\u{2500}{bold}{title}{r}{border}{fill}\u{256E}{r}"
+        ));
+        row += 1;
+
+        let line = |out: &mut String, row: &mut usize, text: &str, style: &str, selected: bool| {
+            let shown: String = text.chars().take(inner.saturating_sub(2)).collect();
+            let pad = inner.saturating_sub(shown.chars().count() + 1);
+            let (bg, marker) = if selected { (sel_bg.as_str(), "\u{25B8}") } else { ("", " ") };
+            out.push_str(&format!(
+                "\x1b[{row};{left}H{border}\u{2502}{r}{bg}{marker}{style}{shown}{}{r}{border}\u{2502}{r}",
+                " ".repeat(pad),
+            ));
+            *row += 1;
+        };
+
+        // Flatten into rows, then window around the selection
+        let mut rows: Vec<(String, bool, bool)> = Vec::new(); // (text, is_header, selected)
+        let mut flat = 0;
+        for group in &self.groups {
+            rows.push((
+                format!("{} ({})", self.display_path(&group.path), group.entries.len()),
+                true,
+                false,
+            ));
+            for entry in &group.entries {
+                let sev = match entry.severity {
+                    Severity::Info => "W",
+                    Severity::Error => "E",
+                    Severity::Fatal => "F",
+                };
+                rows.push((
+                    format!("  {:>8}  {sev}  {}", entry.position(), entry.summary()),
+                    false,
+                    flat == self.selected,
+                ));
+                flat += 1;
+            }
+        }
+
+        if rows.is_empty() {
+            line(&mut out, &mut row, "No errors with file locations in observed panes", &muted, false);
+        } else {
+            let sel_row = rows.iter().position(|(_, _, s)| *s).unwrap_or(0);
+            let start = sel_row
+                .saturating_sub(QUICKFIX_MAX_ROWS / 2)
+                .min(rows.len().saturating_sub(QUICKFIX_MAX_ROWS));
+            for (text, is_header, selected) in rows.iter().skip(start).take(QUICKFIX_MAX_ROWS) {
+                let style = if *is_header { &header } else { &fg };
+                line(&mut out, &mut row, text, style, *selected);
+            }
+        }
+
+        let bottom = "\u{2500}".repeat(inner);
+        out.push_str(&format!("\x1b[{row};{left}H{border}\u{2570}{bottom}\u{256F}{r}"));
+        row += 1;
+        out.push_str(&format!(
+            "\x1b[{row};{left}H{muted}  \u{2191}\u{2193} select  \u{23CE} open  n/p next/prev  Tab file  f fix file  r refresh  Esc close{r}"
+        ));
+
+        out.push_str("\x1b[?25h\x1b[u");
+        out
+    }
+}
+
+/// Resolve `file` against the pane's working directory.
+pub fn resolve_path(file: &str, cwd: Option<&Path>) -> String {
+    let path = Path::new(file);
+    match cwd {
+        Some(dir) if path.is_relative() => dir.join(path).to_string_lossy().into_owned(),
+        _ => file.to_string(),
+    }
+}
+
+/// Prompt asking the agent to fix every error in `group`.
+pub fn fix_file_prompt(group: &QuickfixGroup) -> String {
+    let mut prompt = format!(
+        "Fix all {} error{} in `{}`:\n",
+        group.entries.len(),
+        if group.entries.len() == 1 { "" } else { "s" },
+        group.path
+    );
+    for entry in &group.entries {
+        let pos = entry.position();
+        let at = if pos.is_empty() { String::new() } else { format!("line {pos}, ") };
+        prompt.push_str(&format!(
+            "\n- {at}{} ({}):\n```\n{}\n```\n",
+            entry.error_type, entry.pane_title, entry.message
+        ));
+    }
+    prompt.push_str("\nFix them together, then summarize the changes.");
+    prompt
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn det(file: &str, line: u32, msg: &str) -> ErrorDetection {
+        ErrorDetection {
+            error_type: ErrorType::Compile,
+            severity: Severity::Error,
+            message: msg.to_string(),
+            suggested_fix: String::new(),
+            source_file: Some(file.to_string()),
+            line: Some(line),
+            column: None,
+            auto_fixable: false,
+        }
+    }
+
+    fn pane(id: usize, cwd: &str, detections: Vec<ErrorDetection>) -> PaneErrors {
+        PaneErrors {
+            pane_id: id,
+            title: format!("pane {id}"),
+            cwd: Some(PathBuf::from(cwd)),
+            detections,
+        }
+    }
+
+    fn sample() -> QuickfixList {
+        let mut qf = QuickfixList::new();
+        qf.rebuild(
+            vec![
+                pane(1, "/p", vec![det("src/b.rs", 9, "b9"), det("src/a.rs", 20, "a20")]),
+                pane(2, "/p", vec![det("/p/src/a.rs", 3, "a3"), det("src/b.rs", 9, "b9")]),
+            ],
+            Some(PathBuf::from("/p")),
+        );
+        qf
+    }
+
+    #[test]
+    fn test_groups_sorted_and_deduplicated() {
+        let qf = sample();
+        assert_eq!(qf.len(), 3);
+        let groups = qf.groups();
+        assert_eq!(groups[0].path, "/p/src/a.rs");
+        assert_eq!(groups[0].entries[0].line, Some(3));
+        assert_eq!(groups[0].entries[1].line, Some(20));
+        assert_eq!(groups[1].entries.len(), 1);
+        assert_eq!(groups[1].entries[0].pane_id, 1);
+        assert_eq!(qf.display_path(&groups[1].path), "src/b.rs");
+    }
+
+    #[test]
+    fn test_skips_detections_without_file() {
+        let mut no_file = det("x", 1, "m");
+        no_file.source_file = None;
+        let mut qf = QuickfixList::new();
+        qf.rebuild(vec![pane(1, "/p", vec![no_file])], None);
+        assert!(qf.is_empty());
+        assert!(qf.selected_entry().is_none());
+        assert!(qf.select_next().is_none());
+    }
+
+    #[test]
+    fn test_next_prev_wrap() {
+        let mut qf = sample();
+        assert_eq!(qf.selected_entry().unwrap().summary(), "a3");
+        assert_eq!(qf.select_next().unwrap().summary(), "a20");
+        assert_eq!(qf.select_next().unwrap().summary(), "b9");
+        assert_eq!(qf.select_next().unwrap().summary(), "a3");
+        assert_eq!(qf.select_prev().unwrap().summary(), "b9");
+    }
+
+    #[test]
+    fn test_file_navigation() {
+        let mut qf = sample();
+        qf.select_next_file();
+        assert_eq!(qf.selected_group().unwrap().path, "/p/src/b.rs");
+        qf.select_next_file();
+        assert_eq!(qf.selected_index(), 0);
+        qf.select_next();
+        qf.select_prev_file();
+        assert_eq!(qf.selected_group().unwrap().path, "/p/src/b.rs");
+    }
+
+    #[test]
+    fn test_rebuild_keeps_selection() {
+        let mut qf = sample();
+        qf.select_next();
+        qf.rebuild(
+            vec![pane(1, "/p", vec![det("src/a.rs", 1, "a1"), det("src/a.rs", 20, "a20")])],
+            None,
+        );
+        assert_eq!(qf.selected_entry().unwrap().summary(), "a20");
+    }
+
+    #[test]
+    fn test_fix_file_prompt() {
+        let qf = sample();
+        let prompt = fix_file_prompt(&qf.groups()[0]);
+        assert!(prompt.starts_with("Fix all 2 errors in `/p/src/a.rs`"));
+        assert!(prompt.contains("line 3, compile (pane 2)"));
+        assert!(prompt.contains("```\na20\n```"));
+    }
+
+    #[test]
+    fn test_resolve_path() {
+        assert_eq!(resolve_path("a.rs", Some(Path::new("/w"))), "/w/a.rs");
+        assert_eq!(resolve_path("/abs/a.rs", Some(Path::new("/w"))), "/abs/a.rs");
+        assert_eq!(resolve_path("a.rs", None), "a.rs");
+    }
+
+    #[test]
+    fn test_render_closed_and_open() {
+        let mut qf = sample();
+        assert!(qf.render(120).is_empty());
+        qf.open();
+        let out = qf.render(120);
+        assert!(out.contains("Quickfix: 3 errors in 2 files"));
+        assert!(out.contains("src/a.rs (2)"));
+        assert!(out.contains("\u{25B8}"));
+    }
+}
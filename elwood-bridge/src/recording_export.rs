@@ -0,0 +1,964 @@
+//! Export asciinema v2 recordings to animated SVG (`/record export`).
+//!
+//! The recording's output events are fed through a headless
+//! [`wezterm_term::Terminal`] — the same emulator the pane uses, so cursor
+//! movement, scroll regions and the alternate screen render exactly as they
+//! did live. After each batch of output the visible screen is captured as a
+//! [`Frame`] of styled text runs.
+//!
+//! Frames become `<g>` groups in a single self-contained SVG whose visibility
+//! is switched by SMIL `<animate>` timing, so the file plays in any browser
+//! (and in code review tools that inline images) with no scripts, fonts or
+//! external tools.
+//!
+//! - **Idle compression**: gaps between events longer than `max_idle` seconds
+//!   are shortened to `max_idle`
+//! - **Chapter trimming**: `--from` / `--to` name marker events (matched
+//!   case-insensitively by substring); output before `--from` is still fed to
+//!   the terminal so the first frame shows the correct screen
+//! - **Palette**: default foreground/background come from the active Elwood
+//!   theme, indexed colors from the WezTerm color scheme
+//!
+//! Font metrics are configurable under `[recording_export]` in `elwood.toml`:
+//!
+//! ```toml
+//! [recording_export]
+//! font_family = "JetBrains Mono, monospace"
+//! font_size = 14.0
+//! line_height = 1.3   # multiple of font_size
+//! char_width = 0.6    # multiple of font_size
+//! max_idle = 2.0      # seconds; 0 disables compression
+//! ```
+//!
+//! GIF output would need a glyph rasterizer, which this crate doesn't have,
+//! so only SVG is supported.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use termwiz::surface::CursorVisibility;
+use wezterm_term::color::{ColorAttribute, ColorPalette};
+use wezterm_term::{
+    CellAttributes, Intensity, Line, Terminal, TerminalConfiguration, TerminalSize, Underline,
+    VisibleRowIndex,
+};
+
+use crate::recording::{EventType, Recording};
+use crate::replay::marker_label;
+use crate::theme::{Color, ElwoodTheme};
+
+/// Seconds the last frame stays on screen before the animation loops.
+const END_HOLD: f64 = 2.0;
+
+/// Font metrics and timing for SVG export (`[recording_export]` in `elwood.toml`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportConfig {
+    /// CSS font-family list for the terminal text.
+    pub font_family: String,
+    /// Font size in pixels.
+    pub font_size: f64,
+    /// Row height as a multiple of `font_size`.
+    pub line_height: f64,
+    /// Cell width as a multiple of `font_size`.
+    pub char_width: f64,
+    /// Padding around the terminal grid, in pixels.
+    pub padding: f64,
+    /// Longest pause kept between events, in seconds (0 keeps real timing).
+    pub max_idle: f64,
+    /// Output events closer together than this (seconds) share one frame.
+    pub frame_interval: f64,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            font_family: "'SF Mono', Menlo, Consolas, 'DejaVu Sans Mono', monospace".into(),
+            font_size: 14.0,
+            line_height: 1.3,
+            char_width: 0.6,
+            padding: 12.0,
+            max_idle: 2.0,
+            frame_interval: 1.0 / 30.0,
+        }
+    }
+}
+
+/// Everything an export needs besides the recording itself.
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub config: ExportConfig,
+    pub palette: ExportPalette,
+    /// Start at the first marker whose label contains this text.
+    pub from_marker: Option<String>,
+    /// Stop at the first marker after the start whose label contains this text.
+    pub to_marker: Option<String>,
+}
+
+/// Colors used to resolve cell attributes.
+#[derive(Debug, Clone)]
+pub struct ExportPalette {
+    pub foreground: Color,
+    pub background: Color,
+    /// The 256 indexed colors.
+    pub colors: Vec<Color>,
+}
+
+impl ExportPalette {
+    /// Default colors from `theme`, indexed colors from the WezTerm `palette`.
+    pub fn from_theme(theme: &ElwoodTheme, palette: &ColorPalette) -> Self {
+        let colors = palette
+            .colors
+            .0
+            .iter()
+            .map(|c| {
+                let (r, g, b, _) = c.to_srgb_u8();
+                Color::new(r, g, b)
+            })
+            .collect();
+        Self {
+            foreground: theme.fg_primary,
+            background: theme.bg_primary,
+            colors,
+        }
+    }
+
+    fn indexed(&self, idx: u8) -> Color {
+        self.colors
+            .get(idx as usize)
+            .copied()
+            .unwrap_or(self.foreground)
+    }
+
+    fn resolve(&self, attr: ColorAttribute) -> Option<Color> {
+        match attr {
+            ColorAttribute::TrueColorWithPaletteFallback(c, _)
+            | ColorAttribute::TrueColorWithDefaultFallback(c) => {
+                let (r, g, b, _) = c.to_srgb_u8();
+                Some(Color::new(r, g, b))
+            }
+            ColorAttribute::PaletteIndex(idx) => Some(self.indexed(idx)),
+            ColorAttribute::Default => None,
+        }
+    }
+
+    /// Resolve a cell's attributes to concrete colors and text decorations.
+    fn style_for(&self, attrs: &CellAttributes) -> SpanStyle {
+        let bold = attrs.intensity() == Intensity::Bold;
+        let mut fg = match attrs.foreground() {
+            // Bold brightens the eight basic colors, like most terminals
+            ColorAttribute::PaletteIndex(idx) if bold && idx < 8 => self.indexed(idx + 8),
+            other => self.resolve(other).unwrap_or(self.foreground),
+        };
+        let mut bg = self.resolve(attrs.background());
+
+        if attrs.reverse() {
+            let old_fg = fg;
+            fg = bg.unwrap_or(self.background);
+            bg = Some(old_fg);
+        }
+        if attrs.intensity() == Intensity::Half {
+            fg = fg.blend(bg.unwrap_or(self.background), 0.4);
+        }
+        if attrs.invisible() {
+            fg = bg.unwrap_or(self.background);
+        }
+
+        SpanStyle {
+            fg,
+            bg,
+            bold,
+            italic: attrs.italic(),
+            underline: attrs.underline() != Underline::None,
+            strikethrough: attrs.strikethrough(),
+        }
+    }
+}
+
+/// Resolved appearance of a run of cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpanStyle {
+    pub fg: Color,
+    /// `None` for the default background (not drawn).
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+}
+
+/// A run of adjacent cells with the same style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    /// Column of the first cell.
+    pub col: usize,
+    /// Number of cells covered (wide characters count twice).
+    pub width: usize,
+    pub text: String,
+    pub style: SpanStyle,
+}
+
+impl Span {
+    /// Blank runs on the default background draw nothing.
+    fn is_invisible(&self) -> bool {
+        self.style.bg.is_none()
+            && !self.style.underline
+            && !self.style.strikethrough
+            && self.text.trim().is_empty()
+    }
+}
+
+/// One captured screen: styled runs per row plus the cursor cell.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Frame {
+    pub rows: Vec<Vec<Span>>,
+    /// `(col, row)` when the cursor is visible.
+    pub cursor: Option<(usize, usize)>,
+}
+
+/// A frame and the (compressed) time it appears.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedFrame {
+    pub start: f64,
+    pub frame: Frame,
+}
+
+/// Output to feed the terminal, on the compressed export clock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineStep {
+    pub time: f64,
+    pub data: String,
+}
+
+/// The slice of a recording that gets exported.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Timeline {
+    /// Output before the `--from` marker: fed to the terminal, never shown.
+    pub preroll: String,
+    pub steps: Vec<TimelineStep>,
+    /// Length of the animation, including the final hold.
+    pub duration: f64,
+}
+
+/// A rendered SVG and what went into it.
+#[derive(Debug, Clone)]
+pub struct SvgDocument {
+    pub svg: String,
+    pub frames: usize,
+    pub duration: f64,
+}
+
+/// Result of a successful export.
+#[derive(Debug, Clone)]
+pub struct ExportSummary {
+    pub path: PathBuf,
+    pub frames: usize,
+    pub duration: f64,
+    pub bytes: usize,
+}
+
+fn find_marker(recording: &Recording, after: usize, needle: &str) -> anyhow::Result<usize> {
+    let needle = needle.to_lowercase();
+    recording
+        .events
+        .iter()
+        .enumerate()
+        .skip(after)
+        .find(|(_, e)| {
+            e.event_type == EventType::Marker
+                && marker_label(&e.data).to_lowercase().contains(&needle)
+        })
+        .map(|(i, _)| i)
+        .ok_or_else(|| anyhow::anyhow!("No marker matching '{needle}'"))
+}
+
+/// Select, trim and time-compress the output events to export.
+pub fn build_timeline(
+    recording: &Recording,
+    from_marker: Option<&str>,
+    to_marker: Option<&str>,
+    config: &ExportConfig,
+) -> anyhow::Result<Timeline> {
+    let start = match from_marker {
+        Some(m) => find_marker(recording, 0, m)?,
+        None => 0,
+    };
+    let end = match to_marker {
+        Some(m) => find_marker(recording, start + 1, m)?,
+        None => recording.events.len().saturating_sub(1),
+    };
+
+    let mut timeline = Timeline::default();
+    for event in &recording.events[..start] {
+        if event.event_type == EventType::Output {
+            timeline.preroll.push_str(&event.data);
+        }
+    }
+
+    let mut prev = recording.events.get(start).map(|e| e.time).unwrap_or(0.0);
+    let mut clock = 0.0;
+    for event in recording.events.iter().take(end + 1).skip(start) {
+        let gap = (event.time - prev).max(0.0);
+        prev = event.time;
+        clock += if config.max_idle > 0.0 {
+            gap.min(config.max_idle)
+        } else {
+            gap
+        };
+
+        if event.event_type != EventType::Output {
+            continue;
+        }
+        match timeline.steps.last_mut() {
+            Some(last) if clock - last.time < config.frame_interval => {
+                last.data.push_str(&event.data);
+            }
+            _ => timeline.steps.push(TimelineStep {
+                time: clock,
+                data: event.data.clone(),
+            }),
+        }
+    }
+    timeline.duration = clock + END_HOLD;
+    Ok(timeline)
+}
+
+#[derive(Debug)]
+struct ExportTermConfig;
+
+impl TerminalConfiguration for ExportTermConfig {
+    fn scrollback_size(&self) -> usize {
+        0
+    }
+
+    fn color_palette(&self) -> ColorPalette {
+        ColorPalette::default()
+    }
+}
+
+fn spans_for_line(line: &Line, palette: &ExportPalette) -> Vec<Span> {
+    let mut spans: Vec<Span> = Vec::new();
+    for cell in line.visible_cells() {
+        let style = palette.style_for(cell.attrs());
+        let col = cell.cell_index();
+        match spans.last_mut() {
+            Some(span) if span.style == style && span.col + span.width == col => {
+                span.text.push_str(cell.str());
+                span.width += cell.width();
+            }
+            _ => spans.push(Span {
+                col,
+                width: cell.width(),
+                text: cell.str().to_string(),
+                style,
+            }),
+        }
+    }
+    spans.retain(|s| !s.is_invisible());
+    spans
+}
+
+fn capture(terminal: &Terminal, palette: &ExportPalette) -> Frame {
+    let screen = terminal.screen();
+    let visible = 0..screen.physical_rows as VisibleRowIndex;
+    let rows = screen
+        .lines_in_phys_range(screen.phys_range(&visible))
+        .iter()
+        .map(|line| spans_for_line(line, palette))
+        .collect();
+    let cursor = terminal.cursor_pos();
+    Frame {
+        rows,
+        cursor: (cursor.visibility == CursorVisibility::Visible && cursor.y >= 0)
+            .then_some((cursor.x, cursor.y as usize)),
+    }
+}
+
+/// Play `timeline` through a headless terminal, capturing a frame per step.
+/// Steps that leave the screen unchanged don't produce a frame.
+pub fn capture_frames(
+    timeline: &Timeline,
+    cols: usize,
+    rows: usize,
+    palette: &ExportPalette,
+) -> Vec<TimedFrame> {
+    let mut terminal = Terminal::new(
+        TerminalSize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+            dpi: 0,
+        },
+        Arc::new(ExportTermConfig),
+        "Elwood",
+        "0.1.0",
+        // Replies to terminal queries have nowhere to go
+        Box::new(std::io::sink()),
+    );
+    terminal.advance_bytes(timeline.preroll.as_bytes());
+
+    let mut frames = vec![TimedFrame {
+        start: 0.0,
+        frame: capture(&terminal, palette),
+    }];
+    for step in &timeline.steps {
+        terminal.advance_bytes(step.data.as_bytes());
+        let frame = capture(&terminal, palette);
+        let Some(last) = frames.last_mut() else {
+            continue;
+        };
+        if last.frame == frame {
+            continue;
+        }
+        if last.start >= step.time {
+            last.frame = frame;
+        } else {
+            frames.push(TimedFrame {
+                start: step.time,
+                frame,
+            });
+        }
+    }
+    frames
+}
+
+/// Render `recording` to an animated SVG document.
+pub fn export_svg(recording: &Recording, options: &ExportOptions) -> anyhow::Result<SvgDocument> {
+    let timeline = build_timeline(
+        recording,
+        options.from_marker.as_deref(),
+        options.to_marker.as_deref(),
+        &options.config,
+    )?;
+    let cols = recording.header.width.max(1) as usize;
+    let rows = recording.header.height.max(1) as usize;
+    let frames = capture_frames(&timeline, cols, rows, &options.palette);
+    let svg = render_svg(
+        &frames,
+        cols,
+        rows,
+        timeline.duration,
+        &options.config,
+        &options.palette,
+    );
+    Ok(SvgDocument {
+        svg,
+        frames: frames.len(),
+        duration: timeline.duration,
+    })
+}
+
+/// Export `recording` and write the SVG to `path`.
+pub fn export_to_file(
+    recording: &Recording,
+    path: &Path,
+    options: &ExportOptions,
+) -> anyhow::Result<ExportSummary> {
+    if path.extension().and_then(|e| e.to_str()) == Some("gif") {
+        anyhow::bail!("GIF export is not supported; use an .svg output path");
+    }
+    let doc = export_svg(recording, options)?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, &doc.svg)?;
+    Ok(ExportSummary {
+        path: path.to_path_buf(),
+        frames: doc.frames,
+        duration: doc.duration,
+        bytes: doc.svg.len(),
+    })
+}
+
+/// `session.cast` → `session.svg`.
+pub fn default_output_path(source: &Path) -> PathBuf {
+    source.with_extension("svg")
+}
+
+/// Format a coordinate or time with at most two decimals.
+fn num(v: f64) -> String {
+    let s = format!("{v:.2}");
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".into()
+    } else {
+        s.to_string()
+    }
+}
+
+fn hex(c: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", c.r, c.g, c.b)
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Control characters are not allowed in XML 1.0
+            c if (c as u32) < 0x20 && c != '\t' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The `<animate>` element that shows a frame between `start` and `end`
+/// (fractions of the loop). `None` when the frame is always visible.
+fn visibility_animation(start: f64, end: f64, duration: f64) -> Option<String> {
+    let (values, key_times) = match (start <= 0.0, end >= 1.0) {
+        (true, true) => return None,
+        (true, false) => ("visible;hidden".to_string(), format!("0;{}", num_frac(end))),
+        (false, true) => (
+            "hidden;visible".to_string(),
+            format!("0;{}", num_frac(start)),
+        ),
+        (false, false) => (
+            "hidden;visible;hidden".to_string(),
+            format!("0;{};{}", num_frac(start), num_frac(end)),
+        ),
+    };
+    Some(format!(
+        "<animate attributeName=\"visibility\" calcMode=\"discrete\" dur=\"{}s\" \
+         repeatCount=\"indefinite\" values=\"{values}\" keyTimes=\"{key_times}\"/>",
+        num(duration)
+    ))
+}
+
+/// keyTimes need more precision than pixel coordinates.
+fn num_frac(v: f64) -> String {
+    let s = format!("{:.5}", v.clamp(0.0, 1.0));
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn render_frame(out: &mut String, frame: &Frame, config: &ExportConfig, palette: &ExportPalette) {
+    let cw = config.font_size * config.char_width;
+    let lh = config.font_size * config.line_height;
+    let pad = config.padding;
+    // Center the glyphs vertically in the row
+    let baseline = (lh + config.font_size * 0.7) / 2.0;
+
+    for (row, spans) in frame.rows.iter().enumerate() {
+        let y = pad + row as f64 * lh;
+        for span in spans {
+            if let Some(bg) = span.style.bg {
+                out.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    num(pad + span.col as f64 * cw),
+                    num(y),
+                    num(span.width as f64 * cw),
+                    num(lh),
+                    hex(bg)
+                ));
+            }
+        }
+        for span in spans {
+            if span.text.trim().is_empty() && !span.style.underline && !span.style.strikethrough {
+                continue;
+            }
+            let mut attrs = String::new();
+            if span.style.fg != palette.foreground {
+                attrs.push_str(&format!(" fill=\"{}\"", hex(span.style.fg)));
+            }
+            if span.style.bold {
+                attrs.push_str(" font-weight=\"bold\"");
+            }
+            if span.style.italic {
+                attrs.push_str(" font-style=\"italic\"");
+            }
+            let decoration = match (span.style.underline, span.style.strikethrough) {
+                (true, true) => Some("underline line-through"),
+                (true, false) => Some("underline"),
+                (false, true) => Some("line-through"),
+                (false, false) => None,
+            };
+            if let Some(d) = decoration {
+                attrs.push_str(&format!(" text-decoration=\"{d}\""));
+            }
+            out.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\"{attrs}>{}</text>",
+                num(pad + span.col as f64 * cw),
+                num(y + baseline),
+                num(span.width as f64 * cw),
+                escape_xml(&span.text)
+            ));
+        }
+    }
+
+    if let Some((col, row)) = frame.cursor {
+        out.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" fill-opacity=\"0.6\"/>",
+            num(pad + col as f64 * cw),
+            num(pad + row as f64 * lh),
+            num(cw),
+            num(lh),
+            hex(palette.foreground)
+        ));
+    }
+}
+
+/// Build the SVG document: a background plus one group per frame, each
+/// visible from its start until the next frame's start.
+pub fn render_svg(
+    frames: &[TimedFrame],
+    cols: usize,
+    rows: usize,
+    duration: f64,
+    config: &ExportConfig,
+    palette: &ExportPalette,
+) -> String {
+    let width = cols as f64 * config.font_size * config.char_width + config.padding * 2.0;
+    let height = rows as f64 * config.font_size * config.line_height + config.padding * 2.0;
+    let duration = duration.max(0.01);
+
+    let mut out = String::with_capacity(frames.len() * 4096 + 512);
+    out.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"0 0 {w} {h}\" font-family=\"{}\" font-size=\"{}\" fill=\"{}\" \
+         xml:space=\"preserve\">",
+        escape_xml(&config.font_family),
+        num(config.font_size),
+        hex(palette.foreground),
+        w = num(width),
+        h = num(height),
+    ));
+    out.push_str("<style>text{white-space:pre}</style>");
+    out.push_str(&format!(
+        "<rect width=\"100%\" height=\"100%\" rx=\"6\" fill=\"{}\"/>",
+        hex(palette.background)
+    ));
+
+    for (i, timed) in frames.iter().enumerate() {
+        let start = timed.start / duration;
+        let end = frames
+            .get(i + 1)
+            .map(|next| next.start / duration)
+            .unwrap_or(1.0);
+        match visibility_animation(start, end, duration) {
+            Some(animate) => {
+                out.push_str("<g visibility=\"hidden\">");
+                out.push_str(&animate);
+            }
+            None => out.push_str("<g>"),
+        }
+        render_frame(&mut out, &timed.frame, config, palette);
+        out.push_str("</g>");
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording::{RecordingEnv, RecordingEvent, RecordingHeader};
+
+    fn event(time: f64, event_type: EventType, data: &str) -> RecordingEvent {
+        RecordingEvent {
+            time,
+            event_type,
+            data: data.into(),
+        }
+    }
+
+    fn recording(events: Vec<RecordingEvent>) -> Recording {
+        Recording {
+            header: RecordingHeader {
+                version: 2,
+                width: 20,
+                height: 3,
+                timestamp: None,
+                env: RecordingEnv {
+                    shell: "/bin/sh".into(),
+                    term: "xterm-256color".into(),
+                },
+            },
+            events,
+        }
+    }
+
+    fn palette() -> ExportPalette {
+        ExportPalette {
+            foreground: Color::new(200, 200, 200),
+            background: Color::new(10, 10, 10),
+            colors: vec![Color::new(0, 0, 0); 256],
+        }
+    }
+
+    fn span(col: usize, text: &str, style: SpanStyle) -> Span {
+        Span {
+            col,
+            width: text.chars().count(),
+            text: text.into(),
+            style,
+        }
+    }
+
+    fn plain() -> SpanStyle {
+        SpanStyle {
+            fg: palette().foreground,
+            bg: None,
+            bold: false,
+            italic: false,
+            underline: false,
+            strikethrough: false,
+        }
+    }
+
+    #[test]
+    fn test_timeline_compresses_idle_time() {
+        let rec = recording(vec![
+            event(0.0, EventType::Output, "a"),
+            event(10.0, EventType::Output, "b"),
+            event(10.5, EventType::Output, "c"),
+        ]);
+        let config = ExportConfig::default();
+        let tl = build_timeline(&rec, None, None, &config).unwrap();
+        let times: Vec<f64> = tl.steps.iter().map(|s| s.time).collect();
+        assert_eq!(times, vec![0.0, 2.0, 2.5]);
+        assert_eq!(tl.duration, 2.5 + END_HOLD);
+
+        let real = ExportConfig {
+            max_idle: 0.0,
+            ..ExportConfig::default()
+        };
+        let tl = build_timeline(&rec, None, None, &real).unwrap();
+        assert_eq!(tl.steps[1].time, 10.0);
+    }
+
+    #[test]
+    fn test_timeline_coalesces_close_events() {
+        let rec = recording(vec![
+            event(0.0, EventType::Output, "a"),
+            event(0.01, EventType::Output, "b"),
+            event(1.0, EventType::Output, "c"),
+        ]);
+        let tl = build_timeline(&rec, None, None, &ExportConfig::default()).unwrap();
+        assert_eq!(tl.steps.len(), 2);
+        assert_eq!(tl.steps[0].data, "ab");
+        assert_eq!(tl.steps[1].data, "c");
+    }
+
+    #[test]
+    fn test_timeline_trims_to_markers() {
+        let rec = recording(vec![
+            event(0.0, EventType::Output, "setup"),
+            event(1.0, EventType::Marker, "prompt: build it"),
+            event(1.5, EventType::Output, "building"),
+            event(2.0, EventType::Marker, r#"{"label":"tool: bash"}"#),
+            event(3.0, EventType::Output, "done"),
+            event(4.0, EventType::Marker, "turn complete"),
+            event(5.0, EventType::Output, "after"),
+        ]);
+        let tl =
+            build_timeline(&rec, Some("PROMPT"), Some("turn"), &ExportConfig::default()).unwrap();
+        assert_eq!(tl.preroll, "setup");
+        let data: Vec<&str> = tl.steps.iter().map(|s| s.data.as_str()).collect();
+        assert_eq!(data, vec!["building", "done"]);
+        assert_eq!(tl.steps[0].time, 0.5);
+        assert_eq!(tl.duration, 3.0 + END_HOLD);
+
+        // Trimming by a JSON marker's label
+        let tl = build_timeline(&rec, Some("tool: bash"), None, &ExportConfig::default()).unwrap();
+        assert_eq!(tl.preroll, "setupbuilding");
+    }
+
+    #[test]
+    fn test_timeline_missing_marker() {
+        let rec = recording(vec![event(0.0, EventType::Output, "x")]);
+        let err = build_timeline(&rec, Some("nope"), None, &ExportConfig::default()).unwrap_err();
+        assert!(err.to_string().contains("nope"));
+
+        // `--to` must come after `--from`
+        let rec = recording(vec![
+            event(0.0, EventType::Marker, "end"),
+            event(1.0, EventType::Marker, "start"),
+        ]);
+        assert!(
+            build_timeline(&rec, Some("start"), Some("end"), &ExportConfig::default()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_visibility_animation() {
+        assert_eq!(visibility_animation(0.0, 1.0, 5.0), None);
+        let first = visibility_animation(0.0, 0.5, 4.0).unwrap();
+        assert!(first.contains("values=\"visible;hidden\""));
+        assert!(first.contains("keyTimes=\"0;0.5\""));
+        assert!(first.contains("dur=\"4s\""));
+        let mid = visibility_animation(0.25, 0.5, 4.0).unwrap();
+        assert!(mid.contains("values=\"hidden;visible;hidden\""));
+        assert!(mid.contains("keyTimes=\"0;0.25;0.5\""));
+        let last = visibility_animation(0.75, 1.0, 4.0).unwrap();
+        assert!(last.contains("values=\"hidden;visible\""));
+    }
+
+    #[test]
+    fn test_render_svg_frames_and_styles() {
+        let config = ExportConfig::default();
+        let pal = palette();
+        let red = SpanStyle {
+            fg: Color::new(255, 0, 0),
+            bold: true,
+            ..plain()
+        };
+        let highlighted = SpanStyle {
+            bg: Some(Color::new(0, 0, 255)),
+            underline: true,
+            ..plain()
+        };
+        let frames = vec![
+            TimedFrame {
+                start: 0.0,
+                frame: Frame {
+                    rows: vec![vec![span(0, "$ ls", plain())]],
+                    cursor: Some((4, 0)),
+                },
+            },
+            TimedFrame {
+                start: 1.0,
+                frame: Frame {
+                    rows: vec![vec![span(0, "err", red), span(4, "a<b", highlighted)]],
+                    cursor: None,
+                },
+            },
+        ];
+        let svg = render_svg(&frames, 20, 3, 2.0, &config, &pal);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<animate ").count(), 2);
+        assert!(svg.contains("keyTimes=\"0;0.5\""));
+        assert!(svg.contains("fill=\"#ff0000\" font-weight=\"bold\">err</text>"));
+        assert!(svg.contains(">a&lt;b</text>"));
+        assert!(svg.contains("text-decoration=\"underline\""));
+        assert!(svg.contains("fill=\"#0000ff\""));
+        // Background uses the theme color
+        assert!(svg.contains("fill=\"#0a0a0a\""));
+        // Width: 20 cols * 8.4px + 2 * 12px padding
+        assert!(svg.contains("width=\"192\""));
+        // Cursor only in the first frame
+        assert_eq!(svg.matches("fill-opacity").count(), 1);
+    }
+
+    #[test]
+    fn test_export_cast_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let cast = dir.path().join("session.cast");
+        std::fs::write(
+            &cast,
+            concat!(
+                r#"{"version":2,"width":20,"height":3,"env":{"SHELL":"/bin/sh","TERM":"xterm-256color"}}"#,
+                "\n",
+                r#"[0.0,"o","$ ls\r\n"]"#,
+                "\n",
+                r#"[1.0,"o","\u001b[1;31merr\u001b[0m"]"#,
+                "\n",
+                r#"[2.0,"o","\u001b[H\u001b[2Jclear"]"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        let rec = crate::recording::load_recording(&cast).unwrap();
+
+        let config = ExportConfig::default();
+        let tl = build_timeline(&rec, None, None, &config).unwrap();
+        let frames = capture_frames(&tl, 20, 3, &palette());
+        let text = |frame: &Frame, row: usize| -> String {
+            frame.rows[row]
+                .iter()
+                .map(|s| s.text.as_str())
+                .collect::<String>()
+        };
+        // The first event lands at 0s and replaces the blank initial frame
+        let starts: Vec<f64> = frames.iter().map(|f| f.start).collect();
+        assert_eq!(starts, vec![0.0, 1.0, 2.0]);
+        assert_eq!(text(&frames[0].frame, 0).trim_end(), "$ ls");
+        assert_eq!(text(&frames[1].frame, 0).trim_end(), "$ ls");
+        assert_eq!(text(&frames[1].frame, 1).trim_end(), "err");
+        let err = frames[1].frame.rows[1]
+            .iter()
+            .find(|s| s.text.starts_with("err"))
+            .unwrap();
+        assert!(err.style.bold);
+        assert_eq!(text(&frames[2].frame, 0).trim_end(), "clear");
+        assert_eq!(text(&frames[2].frame, 1).trim(), "");
+        assert_eq!(frames[2].frame.cursor, Some((5, 0)));
+
+        let options = ExportOptions {
+            config,
+            palette: palette(),
+            from_marker: None,
+            to_marker: None,
+        };
+        let out = default_output_path(&cast);
+        let summary = export_to_file(&rec, &out, &options).unwrap();
+        assert_eq!(summary.frames, 3);
+        let svg = std::fs::read_to_string(&out).unwrap();
+        assert_eq!(summary.bytes, svg.len());
+        assert!(svg.contains(">$ ls"));
+        assert!(svg.contains("font-weight=\"bold\">err</text>"));
+        assert!(svg.contains(">clear"));
+    }
+
+    #[test]
+    fn test_single_frame_is_static() {
+        let frames = vec![TimedFrame {
+            start: 0.0,
+            frame: Frame::default(),
+        }];
+        let svg = render_svg(&frames, 10, 2, 1.0, &ExportConfig::default(), &palette());
+        assert!(!svg.contains("<animate"));
+        assert!(svg.contains("<g></g>"));
+    }
+
+    #[test]
+    fn test_escape_and_number_helpers() {
+        assert_eq!(escape_xml("a & \"b\"\x07"), "a &amp; &quot;b&quot; ");
+        assert_eq!(num(8.4), "8.4");
+        assert_eq!(num(12.0), "12");
+        assert_eq!(num(-0.001), "0");
+        assert_eq!(num_frac(1.0 / 3.0), "0.33333");
+    }
+
+    #[test]
+    fn test_invisible_spans() {
+        assert!(span(0, "   ", plain()).is_invisible());
+        assert!(!span(0, "x", plain()).is_invisible());
+        let bg = SpanStyle {
+            bg: Some(Color::new(1, 2, 3)),
+            ..plain()
+        };
+        assert!(!span(0, "   ", bg).is_invisible());
+    }
+
+    #[test]
+    fn test_export_config_from_toml() {
+        let config: ExportConfig = toml::from_str("font_size = 16.0\nmax_idle = 0.5").unwrap();
+        assert_eq!(config.font_size, 16.0);
+        assert_eq!(config.max_idle, 0.5);
+        assert_eq!(config.char_width, ExportConfig::default().char_width);
+    }
+
+    #[test]
+    fn test_default_output_path_and_gif() {
+        assert_eq!(
+            default_output_path(Path::new("/tmp/session.cast")),
+            PathBuf::from("/tmp/session.svg")
+        );
+        let options = ExportOptions {
+            config: ExportConfig::default(),
+            palette: palette(),
+            from_marker: None,
+            to_marker: None,
+        };
+        let err =
+            export_to_file(&recording(vec![]), Path::new("/tmp/out.gif"), &options).unwrap_err();
+        assert!(err.to_string().contains("GIF"));
+    }
+}
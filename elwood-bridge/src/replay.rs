@@ -0,0 +1,550 @@
+//! Replay of asciinema v2 recordings inside the Elwood pane (`/replay`).
+//!
+//! [`ReplayPlayer`] walks a [`Recording`]'s output events on a virtual clock
+//! and hands back the bytes to feed into the pane's terminal. The pane owns
+//! the terminal; the player only decides *what* to write:
+//!
+//! - Playing forward emits the output events between the old and new clock
+//! - Seeking backwards can't un-print anything, so it returns a frame with
+//!   `reset` set and every output event from the start up to the target
+//! - Marker events (`"m"`, written by the recorder for agent annotations)
+//!   become chapter points for `[` / `]` navigation
+//!
+//! ## Keys
+//!
+//! | Key | Action |
+//! |-----|--------|
+//! | `Space` | Play / pause |
+//! | `←` / `→` | Seek 5 seconds |
+//! | `[` / `]` | Previous / next marker |
+//! | `-` / `+` | Slower / faster |
+//! | `0` | Restart |
+//! | `q` / `Esc` | Quit |
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::recording::{EventType, Recording};
+use crate::theme;
+
+/// Playback speeds cycled by `-` / `+`.
+pub const SPEEDS: [f64; 8] = [0.25, 0.5, 1.0, 1.5, 2.0, 4.0, 8.0, 16.0];
+
+/// Index of 1.0x in [`SPEEDS`].
+const DEFAULT_SPEED: usize = 2;
+
+/// Seconds moved by a single `←` / `→` press.
+pub const SEEK_STEP: f64 = 5.0;
+
+/// `[` within this many seconds after a marker goes to the one before it,
+/// so repeated presses keep walking backwards.
+const PREV_MARKER_GRACE: f64 = 1.0;
+
+/// Clears the screen and resets scroll region and attributes before a
+/// replay restarts from the beginning.
+pub const RESET_SEQUENCE: &str = "\x1b[r\x1b[0m\x1b[2J\x1b[H";
+
+/// A chapter point from a marker event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayMarker {
+    /// Seconds since recording start.
+    pub time: f64,
+    /// Display label.
+    pub label: String,
+}
+
+/// Output to write into the terminal after a player operation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayFrame {
+    /// Clear the terminal (see [`RESET_SEQUENCE`]) before writing `output`.
+    pub reset: bool,
+    /// Concatenated output event data.
+    pub output: String,
+}
+
+impl ReplayFrame {
+    /// Whether there is nothing to write.
+    pub fn is_empty(&self) -> bool {
+        !self.reset && self.output.is_empty()
+    }
+
+    /// The bytes to feed to the terminal, including the reset if needed.
+    pub fn to_ansi(&self) -> String {
+        if self.reset {
+            format!("{RESET_SEQUENCE}{}", self.output)
+        } else {
+            self.output.clone()
+        }
+    }
+}
+
+/// Playback state for one recording.
+#[derive(Debug)]
+pub struct ReplayPlayer {
+    recording: Recording,
+    markers: Vec<ReplayMarker>,
+    /// Current clock position in seconds.
+    position: f64,
+    /// Index of the first event not yet emitted.
+    next_event: usize,
+    playing: bool,
+    speed_index: usize,
+}
+
+impl ReplayPlayer {
+    /// Create a player positioned at the start, already playing.
+    pub fn new(recording: Recording) -> Self {
+        let markers = recording
+            .events
+            .iter()
+            .filter(|e| e.event_type == EventType::Marker)
+            .map(|e| ReplayMarker {
+                time: e.time,
+                label: marker_label(&e.data),
+            })
+            .collect();
+        Self {
+            recording,
+            markers,
+            position: 0.0,
+            next_event: 0,
+            playing: true,
+            speed_index: DEFAULT_SPEED,
+        }
+    }
+
+    /// The recording being played.
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Chapter markers in time order.
+    pub fn markers(&self) -> &[ReplayMarker] {
+        &self.markers
+    }
+
+    /// Current position in seconds.
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    /// Total length in seconds.
+    pub fn duration(&self) -> f64 {
+        self.recording.duration()
+    }
+
+    /// Whether the clock is running.
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Whether every event has been emitted.
+    pub fn is_finished(&self) -> bool {
+        self.next_event >= self.recording.events.len()
+    }
+
+    /// Current playback speed multiplier.
+    pub fn speed(&self) -> f64 {
+        SPEEDS[self.speed_index]
+    }
+
+    /// Play / pause. Pressing play at the end restarts from the beginning.
+    pub fn toggle_pause(&mut self) -> ReplayFrame {
+        if self.playing {
+            self.playing = false;
+            return ReplayFrame::default();
+        }
+        self.playing = true;
+        if self.is_finished() {
+            self.seek(0.0)
+        } else {
+            ReplayFrame::default()
+        }
+    }
+
+    /// Step up to the next speed.
+    pub fn faster(&mut self) {
+        self.speed_index = (self.speed_index + 1).min(SPEEDS.len() - 1);
+    }
+
+    /// Step down to the previous speed.
+    pub fn slower(&mut self) {
+        self.speed_index = self.speed_index.saturating_sub(1);
+    }
+
+    /// Advance the clock by `elapsed` wall time (scaled by the speed).
+    ///
+    /// Playback pauses itself once the last event has been emitted.
+    pub fn tick(&mut self, elapsed: Duration) -> ReplayFrame {
+        if !self.playing {
+            return ReplayFrame::default();
+        }
+        let target = (self.position + elapsed.as_secs_f64() * self.speed()).min(self.duration());
+        let frame = self.seek(target);
+        if self.is_finished() {
+            self.playing = false;
+        }
+        frame
+    }
+
+    /// Jump to `time` (clamped to the recording).
+    pub fn seek(&mut self, time: f64) -> ReplayFrame {
+        let target = time.clamp(0.0, self.duration());
+        let reset = target < self.position;
+        if reset {
+            self.next_event = 0;
+        }
+        let mut output = String::new();
+        let events = &self.recording.events;
+        while let Some(event) = events.get(self.next_event) {
+            if event.time > target {
+                break;
+            }
+            if event.event_type == EventType::Output {
+                output.push_str(&event.data);
+            }
+            self.next_event += 1;
+        }
+        self.position = target;
+        ReplayFrame { reset, output }
+    }
+
+    /// Move the clock by `delta` seconds.
+    pub fn seek_by(&mut self, delta: f64) -> ReplayFrame {
+        self.seek(self.position + delta)
+    }
+
+    /// Jump to the first marker after the current position.
+    pub fn next_marker(&mut self) -> Option<ReplayFrame> {
+        let time = self.markers.iter().find(|m| m.time > self.position)?.time;
+        Some(self.seek(time))
+    }
+
+    /// Jump to the marker before the current position.
+    pub fn prev_marker(&mut self) -> Option<ReplayFrame> {
+        let limit = self.position - PREV_MARKER_GRACE;
+        let time = self.markers.iter().rev().find(|m| m.time < limit)?.time;
+        Some(self.seek(time))
+    }
+
+    /// The last marker at or before the current position.
+    pub fn current_marker(&self) -> Option<&ReplayMarker> {
+        self.markers.iter().rev().find(|m| m.time <= self.position)
+    }
+
+    /// Render the transport bar for the bottom row of the screen.
+    pub fn render_status(&self, screen_width: u16, screen_height: u16) -> String {
+        let t = theme::active();
+        let r = t.reset();
+        let bar_bg = t.ansi_bg(t.bg_tertiary);
+        let accent = t.ansi_bold_fg(t.accent);
+        let muted = t.ansi_fg(t.fg_muted);
+        let marker_fg = t.ansi_fg(t.warning);
+        let width = screen_width as usize;
+
+        let icon = if self.playing { "\u{25B6}" } else { "\u{23F8}" };
+        let left = format!(
+            " {icon} {:>5}x  {} / {} ",
+            format_speed(self.speed()),
+            format_clock(self.position),
+            format_clock(self.duration()),
+        );
+        let chapter = self
+            .current_marker()
+            .map(|m| format!(" {} ", m.label))
+            .unwrap_or_default();
+        let help = " Space \u{23EF}  \u{2190}/\u{2192} seek  [/] marker  -/+ speed  q quit ";
+
+        let fixed = left.chars().count() + chapter.chars().count() + help.chars().count();
+        let track_width = width.saturating_sub(fixed).max(10);
+        let track = self.render_track(track_width, &accent, &muted, &marker_fg, &bar_bg);
+
+        let mut line = format!(
+            "{bar_bg}{accent}{left}{r}{bar_bg}{track}{bar_bg}{marker_fg}{chapter}{muted}{help}"
+        );
+        let shown =
+            left.chars().count() + track_width + chapter.chars().count() + help.chars().count();
+        line.push_str(&" ".repeat(width.saturating_sub(shown)));
+        line.push_str(r);
+
+        format!("\x1b[s\x1b[{screen_height};1H\x1b[2K{line}\x1b[u")
+    }
+
+    /// Progress track with the played part highlighted and markers as ◆.
+    fn render_track(
+        &self,
+        width: usize,
+        played_style: &str,
+        rest_style: &str,
+        marker_style: &str,
+        bg: &str,
+    ) -> String {
+        let duration = self.duration();
+        let cell = |time: f64| -> usize {
+            if duration <= 0.0 {
+                0
+            } else {
+                (((time / duration) * width as f64) as usize).min(width.saturating_sub(1))
+            }
+        };
+        let head = if duration <= 0.0 {
+            0
+        } else {
+            cell(self.position)
+        };
+        let mut marks = vec![false; width];
+        for m in &self.markers {
+            marks[cell(m.time)] = true;
+        }
+
+        let mut out = String::new();
+        for (i, &is_mark) in marks.iter().enumerate() {
+            let (style, ch) = if is_mark {
+                (marker_style, "\u{25C6}")
+            } else if i < head {
+                (played_style, "\u{2501}")
+            } else if i == head {
+                (played_style, "\u{25CF}")
+            } else {
+                (rest_style, "\u{2500}")
+            };
+            out.push_str(bg);
+            out.push_str(style);
+            out.push_str(ch);
+        }
+        out
+    }
+}
+
+/// Label for a marker event: the `label` (or `text`) field when the data is
+/// a JSON object, otherwise the raw string.
+pub fn marker_label(data: &str) -> String {
+    if let Ok(serde_json::Value::Object(map)) = serde_json::from_str::<serde_json::Value>(data) {
+        for key in ["label", "text", "name"] {
+            if let Some(serde_json::Value::String(s)) = map.get(key) {
+                return s.clone();
+            }
+        }
+    }
+    data.trim().to_string()
+}
+
+/// Resolve a `/replay` argument: an existing path as given, otherwise a name
+/// in `~/.elwood/recordings/` (with or without the `.cast` extension).
+pub fn resolve_recording_path(arg: &str) -> PathBuf {
+    let given = PathBuf::from(arg);
+    if given.exists() || given.is_absolute() {
+        return given;
+    }
+    let dir = recordings_dir();
+    let candidate = dir.join(arg);
+    if candidate.exists() || Path::new(arg).extension().is_some() {
+        return candidate;
+    }
+    dir.join(format!("{arg}.cast"))
+}
+
+/// Directory the recorder writes to by default.
+pub fn recordings_dir() -> PathBuf {
+    dirs_next::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".elwood")
+        .join("recordings")
+}
+
+/// `mm:ss` (or `h:mm:ss` past an hour).
+pub fn format_clock(secs: f64) -> String {
+    let total = secs.max(0.0) as u64;
+    let (h, m, s) = (total / 3600, (total % 3600) / 60, total % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m:02}:{s:02}")
+    }
+}
+
+fn format_speed(speed: f64) -> String {
+    if speed.fract() == 0.0 {
+        format!("{speed:.0}")
+    } else {
+        format!("{speed}")
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording::{RecordingEnv, RecordingEvent, RecordingHeader};
+
+    fn event(time: f64, event_type: EventType, data: &str) -> RecordingEvent {
+        RecordingEvent {
+            time,
+            event_type,
+            data: data.to_string(),
+        }
+    }
+
+    fn sample() -> Recording {
+        Recording {
+            header: RecordingHeader {
+                version: 2,
+                width: 80,
+                height: 24,
+                timestamp: None,
+                env: RecordingEnv {
+                    shell: "/bin/sh".into(),
+                    term: "xterm-256color".into(),
+                },
+            },
+            events: vec![
+                event(0.5, EventType::Output, "a"),
+                event(1.0, EventType::Marker, "prompt: build it"),
+                event(1.5, EventType::Input, "x"),
+                event(2.0, EventType::Output, "b"),
+                event(4.0, EventType::Marker, r#"{"label":"tool: Bash"}"#),
+                event(5.0, EventType::Output, "c"),
+                event(10.0, EventType::Output, "d"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_tick_emits_output_in_order() {
+        let mut player = ReplayPlayer::new(sample());
+        assert!(player.is_playing());
+        let frame = player.tick(Duration::from_secs(2));
+        assert_eq!(
+            frame,
+            ReplayFrame {
+                reset: false,
+                output: "ab".into()
+            }
+        );
+        let frame = player.tick(Duration::from_secs(4));
+        assert_eq!(frame.output, "c");
+        assert!((player.position() - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tick_respects_speed_and_stops_at_end() {
+        let mut player = ReplayPlayer::new(sample());
+        player.faster();
+        player.faster();
+        assert_eq!(player.speed(), 2.0);
+        let frame = player.tick(Duration::from_secs(10));
+        assert_eq!(frame.output, "abcd");
+        assert!(player.is_finished());
+        assert!(!player.is_playing());
+        assert_eq!(player.position(), player.duration());
+    }
+
+    #[test]
+    fn test_pause_stops_clock() {
+        let mut player = ReplayPlayer::new(sample());
+        player.toggle_pause();
+        assert!(player.tick(Duration::from_secs(5)).is_empty());
+        assert_eq!(player.position(), 0.0);
+    }
+
+    #[test]
+    fn test_play_at_end_restarts() {
+        let mut player = ReplayPlayer::new(sample());
+        player.seek(100.0);
+        player.toggle_pause(); // pause
+        let frame = player.toggle_pause(); // play again from the top
+        assert!(frame.reset);
+        assert_eq!(frame.output, "");
+        assert_eq!(player.position(), 0.0);
+        assert!(player.is_playing());
+    }
+
+    #[test]
+    fn test_seek_backwards_replays_from_start() {
+        let mut player = ReplayPlayer::new(sample());
+        player.seek(6.0);
+        let frame = player.seek_by(-SEEK_STEP);
+        assert!(frame.reset);
+        assert_eq!(frame.output, "a");
+        assert_eq!(frame.to_ansi(), format!("{RESET_SEQUENCE}a"));
+        let frame = player.seek_by(SEEK_STEP);
+        assert!(!frame.reset);
+        assert_eq!(frame.output, "bc");
+    }
+
+    #[test]
+    fn test_marker_navigation() {
+        let mut player = ReplayPlayer::new(sample());
+        assert_eq!(player.markers().len(), 2);
+        assert_eq!(player.markers()[1].label, "tool: Bash");
+
+        let frame = player.next_marker().unwrap();
+        assert_eq!(frame.output, "a");
+        assert_eq!(player.current_marker().unwrap().label, "prompt: build it");
+        player.next_marker().unwrap();
+        assert_eq!(player.position(), 4.0);
+        assert!(player.next_marker().is_none());
+
+        // Within the grace period, [ skips back past the current marker
+        let frame = player.prev_marker().unwrap();
+        assert!(frame.reset);
+        assert_eq!(player.position(), 1.0);
+        assert!(player.prev_marker().is_none());
+    }
+
+    #[test]
+    fn test_speed_bounds() {
+        let mut player = ReplayPlayer::new(sample());
+        for _ in 0..20 {
+            player.slower();
+        }
+        assert_eq!(player.speed(), SPEEDS[0]);
+        for _ in 0..20 {
+            player.faster();
+        }
+        assert_eq!(player.speed(), SPEEDS[SPEEDS.len() - 1]);
+    }
+
+    #[test]
+    fn test_marker_label() {
+        assert_eq!(marker_label("  thinking "), "thinking");
+        assert_eq!(marker_label(r#"{"text":"hello"}"#), "hello");
+        assert_eq!(marker_label(r#"{"other":1}"#), r#"{"other":1}"#);
+    }
+
+    #[test]
+    fn test_format_clock() {
+        assert_eq!(format_clock(0.0), "00:00");
+        assert_eq!(format_clock(75.4), "01:15");
+        assert_eq!(format_clock(3725.0), "1:02:05");
+    }
+
+    #[test]
+    fn test_render_status_contains_transport() {
+        let mut player = ReplayPlayer::new(sample());
+        player.seek(4.5);
+        let out = player.render_status(120, 30);
+        assert!(out.starts_with("\x1b[s\x1b[30;1H"));
+        assert!(out.contains("00:04 / 00:10"));
+        assert!(out.contains("tool: Bash"));
+        assert!(out.contains("\u{25C6}"));
+        assert!(out.ends_with("\x1b[u"));
+    }
+
+    #[test]
+    fn test_resolve_recording_path() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let file = tmp.path().join("demo.cast");
+        std::fs::write(&file, "").unwrap();
+        assert_eq!(resolve_recording_path(file.to_str().unwrap()), file);
+        assert_eq!(
+            resolve_recording_path("missing-demo"),
+            recordings_dir().join("missing-demo.cast")
+        );
+        assert_eq!(
+            resolve_recording_path("missing.cast"),
+            recordings_dir().join("missing.cast")
+        );
+    }
+}
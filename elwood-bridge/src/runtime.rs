@@ -14,6 +14,7 @@
 //! └─────────────────┘                      └──────────────────┘
 //! ```
 
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
@@ -41,10 +42,22 @@ pub enum AgentRequest {
     /// Send a follow-up message to an active agent session.
     SendMessage { content: String },
 
+    /// Replace the conversation history with one from a saved session
+    /// (`/resume`). No turn is run until the next message.
+    Restore {
+        messages: Vec<crate::session_resume::AgentMessage>,
+    },
+
+    /// Compact the conversation history now (`/compact`) instead of waiting
+    /// for it to near the model's context budget.
+    Compact,
+
     /// Run a shell command (Terminal mode or `!` prefix).
     RunCommand {
         command: String,
         working_dir: Option<String>,
+        /// Variables added to the command's environment.
+        env: HashMap<String, String>,
     },
 
     /// User responded to a permission request.
@@ -93,8 +106,12 @@ pub enum AgentRequest {
 
     /// Run a command in the background (from `/bg` or `&` suffix).
     RunBackgroundCommand {
+        /// Job ID assigned by the pane's `JobManager`.
+        job_id: u32,
         command: String,
         working_dir: Option<String>,
+        /// File that receives the job's full output.
+        log_path: Option<std::path::PathBuf>,
     },
 
     /// Kill a running background job.
@@ -133,11 +150,21 @@ pub enum AgentResponse {
         input_preview: String,
     },
 
+    /// Output streamed by a running tool, ahead of its `ToolEnd`.
+    ToolOutput {
+        tool_name: String,
+        /// One or more whole lines.
+        text: String,
+        is_stderr: bool,
+    },
+
     /// Tool execution completed.
     ToolEnd {
         tool_id: String,
         success: bool,
         output_preview: String,
+        /// Images taken out of the full output, drawn under the tool block.
+        images: Vec<crate::inline_image::EmbeddedImage>,
     },
 
     /// Agent needs permission to perform an action.
@@ -156,6 +183,8 @@ pub enum AgentResponse {
     /// Shell command execution completed.
     CommandOutput {
         command: String,
+        /// Directory the command ran in.
+        working_dir: std::path::PathBuf,
         stdout: String,
         stderr: String,
         exit_code: Option<i32>,
@@ -205,6 +234,15 @@ pub enum AgentResponse {
         cost_usd: f64,
     },
 
+    /// Estimated size of the conversation against the active model's
+    /// context budget (sent after each turn and whenever it changes).
+    ContextUsage {
+        used_tokens: usize,
+        budget_tokens: usize,
+        /// Description of the compaction that just ran, if any.
+        compacted: Option<String>,
+    },
+
     /// A single workflow step completed.
     WorkflowStepResult {
         /// Workflow name.
@@ -26,11 +26,12 @@
 
 use crate::git_info::GitInfo;
 use crate::runtime::InputMode;
-use crate::theme;
+use crate::theme::{self, Role};
 use crate::vim_mode::VimState;
+use std::path::Path;
 use std::time::Instant;
 
-// ─── TokyoNight Color Palette ────────────────────────────────────────────
+// ─── Color Palette ──────────────────────────────────────────────────────
 
 /// True-color foreground.
 fn fg(r: u8, g: u8, b: u8) -> String {
@@ -47,36 +48,37 @@ const BOLD: &str = "\x1b[1m";
 const DIM: &str = "\x1b[2m";
 const ITALIC: &str = "\x1b[3m";
 
-// Palette tuples: (R, G, B)
-const BG: (u8, u8, u8) = (26, 27, 38);
-const FG: (u8, u8, u8) = (192, 202, 245);
-const ACCENT: (u8, u8, u8) = (122, 162, 247);
-const SUCCESS: (u8, u8, u8) = (158, 206, 106);
-const ERROR: (u8, u8, u8) = (247, 118, 142);
-const WARNING: (u8, u8, u8) = (224, 175, 104);
-const INFO: (u8, u8, u8) = (125, 207, 255);
-const MUTED: (u8, u8, u8) = (86, 95, 137);
-const BORDER: (u8, u8, u8) = (59, 66, 97);
-const HEADER_BG: (u8, u8, u8) = (26, 27, 38);
-const STATUS_BG: (u8, u8, u8) = (26, 27, 38);
-const SELECTION: (u8, u8, u8) = (40, 44, 66);
-const WHITE: (u8, u8, u8) = (220, 225, 252);
+const BG: Role = Role::BgPrimary;
+const FG: Role = Role::FgPrimary;
+const ACCENT: Role = Role::Accent;
+const SUCCESS: Role = Role::Success;
+const ERROR: Role = Role::Error;
+const WARNING: Role = Role::Warning;
+const INFO: Role = Role::Info;
+const MUTED: Role = Role::FgMuted;
+const BORDER: Role = Role::BlockBorder;
+const HEADER_BG: Role = Role::BgPrimary;
+const STATUS_BG: Role = Role::BgPrimary;
+const SELECTION: Role = Role::BgTertiary;
+const WHITE: Role = Role::FgBright;
+const TOOL_ACCENT: Role = Role::ToolAccent;
 
 // Tab colors
-const TAB_CHAT: (u8, u8, u8) = (122, 162, 247);   // blue
-const TAB_TOOLS: (u8, u8, u8) = (224, 175, 104);   // yellow
-const TAB_FILES: (u8, u8, u8) = (158, 206, 106);   // green
-const TAB_AGENTS: (u8, u8, u8) = (187, 154, 247);   // magenta
+const TAB_CHAT: Role = Role::AgentAccent;   // blue
+const TAB_TOOLS: Role = Role::Warning;      // yellow
+const TAB_FILES: Role = Role::UserAccent;   // green
+const TAB_AGENTS: Role = Role::ToolAccent;  // magenta
 
-fn fgc(c: (u8, u8, u8)) -> String { fg(c.0, c.1, c.2) }
-fn bgc(c: (u8, u8, u8)) -> String { bg(c.0, c.1, c.2) }
+fn fgc(c: Role) -> String { let (r, g, b) = c.rgb(); fg(r, g, b) }
+fn bgc(c: Role) -> String { let (r, g, b) = c.rgb(); bg(r, g, b) }
 
 /// Blend color 35% toward background.
-fn muted_tab(tab: (u8, u8, u8)) -> (u8, u8, u8) {
+fn muted_tab(tab: Role) -> (u8, u8, u8) {
+    let (tab, base) = (tab.rgb(), BG.rgb());
     (
-        ((tab.0 as u16 * 35 + BG.0 as u16 * 65) / 100) as u8,
-        ((tab.1 as u16 * 35 + BG.1 as u16 * 65) / 100) as u8,
-        ((tab.2 as u16 * 35 + BG.2 as u16 * 65) / 100) as u8,
+        ((tab.0 as u16 * 35 + base.0 as u16 * 65) / 100) as u8,
+        ((tab.1 as u16 * 35 + base.1 as u16 * 65) / 100) as u8,
+        ((tab.2 as u16 * 35 + base.2 as u16 * 65) / 100) as u8,
     )
 }
 
@@ -161,9 +163,13 @@ pub struct ScreenState {
     pub recording_paused: bool,
     /// Number of currently running background jobs.
     pub running_jobs: usize,
+    /// Symbol index build progress `(files done, files total)` while the
+    /// initial index is being built; `None` once it is ready.
+    pub index_progress: Option<(usize, usize)>,
     /// Current vim mode state (None = vim off).
     pub vim_state: Option<VimState>,
-    /// Vim command-line buffer (for `:` mode rendering).
+    /// Vim command-line buffer (for `:` mode rendering; in search mode it
+    /// includes the `/` or `?` prefix).
     pub vim_command_buffer: String,
 }
 
@@ -199,6 +205,7 @@ impl Default for ScreenState {
             recording_active: false,
             recording_paused: false,
             running_jobs: 0,
+            index_progress: None,
             vim_state: None,
             vim_command_buffer: String::new(),
         }
@@ -380,7 +387,7 @@ pub fn render_header(state: &ScreenState) -> String {
     struct Tab {
         num: u8,
         label: &'static str,
-        color: (u8, u8, u8),
+        color: Role,
         active: bool,
     }
     let tabs = [
@@ -395,13 +402,13 @@ pub fn render_header(state: &ScreenState) -> String {
         if tab.active {
             tab_str.push_str(&format!(
                 "{}{} {}:{} {RESET} ",
-                bgc(tab.color), fg(BG.0, BG.1, BG.2), tab.num, tab.label,
+                bgc(tab.color), fgc(BG), tab.num, tab.label,
             ));
         } else {
             let m = muted_tab(tab.color);
             tab_str.push_str(&format!(
                 "{}{} {}:{} {RESET}{hbg} ",
-                bgc(m), fgc(MUTED), tab.num, tab.label,
+                bg(m.0, m.1, m.2), fgc(MUTED), tab.num, tab.label,
             ));
         }
     }
@@ -589,7 +596,7 @@ pub fn render_status_bar(state: &ScreenState) -> String {
         InputMode::Terminal => (" Term ", WARNING),
     };
     let mode_bg = bgc(mode_color);
-    let mode_fg = fg(BG.0, BG.1, BG.2);
+    let mode_fg = fgc(BG);
 
     // Git branch chip
     let git_chip = if let Some(ref gi) = state.git_info {
@@ -681,6 +688,22 @@ pub fn render_status_bar(state: &ScreenState) -> String {
         String::new()
     };
 
+    // Symbol index build indicator
+    let index_chip = match state.index_progress {
+        Some((_, 0)) => {
+            let sep = format!("{}·{RESET}{sbg}", fgc(MUTED));
+            format!(" {sep} {}\u{27F3} indexing\u{2026}{RESET}{sbg}", fgc(MUTED))
+        }
+        Some((done, total)) => {
+            let sep = format!("{}·{RESET}{sbg}", fgc(MUTED));
+            format!(
+                " {sep} {}\u{27F3} indexing {done}/{total}{RESET}{sbg}",
+                fgc(MUTED)
+            )
+        }
+        None => String::new(),
+    };
+
     // Vim mode indicator
     let vim_chip = match state.vim_state {
         Some(VimState::Normal) => {
@@ -699,13 +722,18 @@ pub fn render_status_bar(state: &ScreenState) -> String {
             let sep = format!("{}·{RESET}{sbg}", fgc(MUTED));
             format!(" {sep} {}:{}{RESET}{sbg}", fgc(WARNING), state.vim_command_buffer)
         }
+        Some(VimState::Search) => {
+            // Buffer carries its own `/` or `?` prefix (see `VimMode::command_line`)
+            let sep = format!("{}·{RESET}{sbg}", fgc(MUTED));
+            format!(" {sep} {}{}{RESET}{sbg}", fgc(WARNING), state.vim_command_buffer)
+        }
         None => String::new(),
     };
 
     // Assemble left section
     out.push_str(&goto(row, 1));
     out.push_str(&format!(
-        "{sbg} {mode_bg}{mode_fg}{BOLD}{mode_label}{RESET}{sbg}{vim_chip}{rec_chip}{jobs_chip}{git_chip}{model_chip}{status_chip}",
+        "{sbg} {mode_bg}{mode_fg}{BOLD}{mode_label}{RESET}{sbg}{vim_chip}{rec_chip}{jobs_chip}{index_chip}{git_chip}{model_chip}{status_chip}",
     ));
 
     // ── Right section: tokens, cost, elapsed, keyboard hints ────────
@@ -716,14 +744,14 @@ pub fn render_status_bar(state: &ScreenState) -> String {
         right_parts.push(format!("{}{}{RESET}", fgc(MUTED), format_tokens(state.tokens_used)));
     }
 
-    // Context budget
+    // Context budget meter
     if state.context_max > 0 {
         let pct = (state.context_used as f64 / state.context_max as f64 * 100.0) as u8;
         let color = if pct >= 90 { ERROR } else if pct >= 70 { WARNING } else { MUTED };
         right_parts.push(format!(
-            "{}{}/{}({pct}%){RESET}",
+            "{}ctx {} {pct}% of {}{RESET}",
             fgc(color),
-            format_tokens(state.context_used),
+            context_meter(state.context_used, state.context_max, 8),
             format_tokens(state.context_max),
         ));
     }
@@ -806,11 +834,12 @@ pub fn format_assistant_prefix() -> String {
 
 /// Format a content delta (streaming text).
 ///
-/// If the text contains markdown formatting, renders it as rich ANSI output.
-/// Otherwise, applies plain foreground coloring.
-pub fn format_content(text: &str) -> String {
+/// If the text contains markdown formatting, renders it as rich ANSI output,
+/// with relative image paths taken from `cwd`. Otherwise, applies plain
+/// foreground coloring.
+pub fn format_content(text: &str, cwd: &Path) -> String {
     if crate::markdown::is_markdown(text) {
-        crate::markdown::render_markdown(text)
+        crate::markdown::render_markdown_in(text, cwd)
     } else {
         format!("{}{text}{RESET}", fgc(FG))
     }
@@ -824,7 +853,7 @@ pub fn format_content(text: &str) -> String {
 /// │  src/main.rs                                    │
 /// ```
 pub fn format_tool_start(tool_name: &str, preview: &str) -> String {
-    let tool_color = fgc((187, 154, 247)); // purple - tool accent
+    let tool_color = fgc(TOOL_ACCENT);
     let muted = fgc(MUTED);
 
     let title = format!(" {GEAR} {tool_name} ");
@@ -842,6 +871,20 @@ pub fn format_tool_start(tool_name: &str, preview: &str) -> String {
     out
 }
 
+/// Format output streamed by a running tool, one row per line inside the
+/// tool block.
+pub fn format_tool_output(text: &str, is_stderr: bool) -> String {
+    let tool_color = fgc(TOOL_ACCENT);
+    let color = if is_stderr { fgc(ERROR) } else { fgc(FG) };
+    let mut out = String::new();
+    for line in text.lines() {
+        out.push_str(&format!(
+            "{tool_color}{BOX_V}{RESET}  {color}{line}{RESET}\r\n"
+        ));
+    }
+    out
+}
+
 /// Format a tool end event.
 ///
 /// Closes the tool block with exit status:
@@ -850,7 +893,7 @@ pub fn format_tool_start(tool_name: &str, preview: &str) -> String {
 /// ╰────────────────────────────────────────────────╯
 /// ```
 pub fn format_tool_end(success: bool, preview: &str) -> String {
-    let tool_color = fgc((187, 154, 247)); // purple - tool accent
+    let tool_color = fgc(TOOL_ACCENT);
     let (icon, color) = if success {
         (CHECK, fgc(SUCCESS))
     } else {
@@ -917,7 +960,7 @@ pub fn format_permission_request(tool_name: &str, description: &str) -> String {
     let muted = fgc(MUTED);
     let fgv = fgc(FG);
     let key_bg = bgc(WARNING);
-    let key_fg = fg(BG.0, BG.1, BG.2);
+    let key_fg = fgc(BG);
 
     let title = " \u{26A0} Permission Required ";
     let fill_len = 54usize.saturating_sub(title.len() + 2);
@@ -1000,6 +1043,27 @@ pub fn format_command_prompt(command: &str) -> String {
     )
 }
 
+/// Format the divider shown above blocks replayed from a previous session.
+pub fn format_restored_banner(count: usize, bookmarked: usize) -> String {
+    let muted = fgc(MUTED);
+    let noun = if count == 1 { "block" } else { "blocks" };
+    let marks = if bookmarked > 0 {
+        format!(", {bookmarked} bookmarked")
+    } else {
+        String::new()
+    };
+    format!("\r\n{muted}{ITALIC}── Restored {count} {noun} from last session{marks} ──{RESET}\r\n")
+}
+
+/// Format the text of a restored block, dimmed so it reads as history.
+pub fn format_restored_output(text: &str) -> String {
+    let mut out = String::new();
+    for line in text.lines() {
+        out.push_str(&format!("{DIM}{line}{RESET}\r\n"));
+    }
+    out
+}
+
 /// Format shell command output as a boxed section with exit code.
 ///
 /// Uses a muted border for command output to distinguish from agent/tool blocks:
@@ -1104,6 +1168,36 @@ pub fn format_next_command_suggestion(suggestion: &str) -> String {
     )
 }
 
+/// Format a correction suggestion after a failed command.
+///
+/// ```text
+///   [?] Did you mean: git status  (Common typo: `gti` -> `git`) — Tab to accept
+/// ```
+pub fn format_correction_suggestion(suggested: &str, explanation: &str) -> String {
+    let warning = fgc(WARNING);
+    let muted = fgc(MUTED);
+    let fg = fgc(FG);
+    format!(
+        "{warning}  [?]{RESET} {warning}Did you mean:{RESET} {fg}{BOLD}{suggested}{RESET} \
+         {muted}({explanation}) \u{2014} Tab to accept{RESET}\r\n",
+    )
+}
+
+/// Format the note shown when a learned correction rewrote a command.
+///
+/// ```text
+///   [~] Autocorrected `gti` (/autocorrect never gti to stop)
+/// ```
+pub fn format_autocorrected(original: &str) -> String {
+    let info = fgc(INFO);
+    let muted = fgc(MUTED);
+    let name = original.split_whitespace().next().unwrap_or(original);
+    format!(
+        "{info}  [~]{RESET} {muted}Autocorrected `{original}` \
+         (/autocorrect never {name} to stop){RESET}\r\n",
+    )
+}
+
 /// Format an error message with a red accent block.
 ///
 /// ```text
@@ -1166,7 +1260,7 @@ pub fn render_shortcut_overlay(state: &ScreenState) -> String {
 
     // Center the overlay vertically in the chat area
     let chat_h = state.chat_bottom().saturating_sub(state.chat_top()) + 1;
-    let overlay_height = 16u16; // lines including borders
+    let overlay_height = 18u16; // lines including borders
     let start_row = state.chat_top() + chat_h.saturating_sub(overlay_height) / 2;
 
     // Shortcuts to display
@@ -1180,6 +1274,7 @@ pub fn render_shortcut_overlay(state: &ScreenState) -> String {
         ("Shift+Enter", "New line in input"),
         ("Enter", "Send message / run command"),
         ("Tab", "Accept ghost text completion"),
+        ("Ctrl+Space", "Completion dropdown"),
         ("!command", "Run shell command directly"),
         ("@file.rs", "Attach file as context"),
         ("F1", "Toggle this overlay"),
@@ -1422,6 +1517,13 @@ pub fn render_block_header(block: &crate::block::Block, selected: bool) -> Strin
         out.push_str(&bgc(SELECTION));
     }
 
+    // Command text (shell blocks only)
+    if let Some(ref cmd) = block.command {
+        let first_line = cmd.lines().next().unwrap_or("");
+        out.push_str(&format!(" {}$ {}{r}", fgc(FG), truncate(first_line, 60)));
+        if selected { out.push_str(&bgc(SELECTION)); }
+    }
+
     // Exit code badge
     match block.exit_code {
         Some(0) => {
@@ -1501,6 +1603,13 @@ fn format_tokens(tokens: usize) -> String {
     }
 }
 
+/// `███░░░░░` — `used / max` as a bar of `width` cells.
+fn context_meter(used: usize, max: usize, width: usize) -> String {
+    let filled = ((used as f64 / max.max(1) as f64) * width as f64).round() as usize;
+    let filled = filled.min(width);
+    "\u{2588}".repeat(filled) + &"\u{2591}".repeat(width - filled)
+}
+
 fn format_elapsed(secs: u64) -> String {
     let hours = secs / 3600;
     let mins = (secs % 3600) / 60;
@@ -1622,12 +1731,25 @@ mod tests {
         assert!(s.contains("[>]"));
     }
 
+    #[test]
+    fn test_format_correction_lines() {
+        let s = format_correction_suggestion("git status", "Common typo");
+        assert!(s.contains("Did you mean:"));
+        assert!(s.contains("git status"));
+        assert!(s.contains("Tab to accept"));
+
+        let s = format_autocorrected("gti log -n 3");
+        assert!(s.contains("`gti log -n 3`"));
+        assert!(s.contains("/autocorrect never gti"));
+    }
+
     #[test]
     fn test_muted_tab_blends() {
         let m = muted_tab(TAB_CHAT);
+        let tab = TAB_CHAT.rgb();
         // Should be darker than original
-        assert!(m.0 < TAB_CHAT.0);
-        assert!(m.1 < TAB_CHAT.1);
+        assert!(m.0 < tab.0);
+        assert!(m.1 < tab.1);
     }
 
     #[test]
@@ -1680,6 +1802,22 @@ mod tests {
         assert!(err.contains("╰"));
     }
 
+    #[test]
+    fn test_format_restored_banner() {
+        let banner = format_restored_banner(3, 1);
+        assert!(banner.contains("Restored 3 blocks"));
+        assert!(banner.contains("1 bookmarked"));
+        assert!(format_restored_banner(1, 0).contains("1 block from"));
+    }
+
+    #[test]
+    fn test_format_restored_output() {
+        let out = format_restored_output("one\ntwo");
+        assert!(out.contains("one"));
+        assert!(out.contains("two"));
+        assert_eq!(out.matches("\r\n").count(), 2);
+    }
+
     #[test]
     fn test_format_error_multiline() {
         let err = format_error("line one\nline two");
@@ -1703,6 +1841,14 @@ mod tests {
         assert!(s.contains("╰"));
     }
 
+    #[test]
+    fn test_format_tool_output_rows() {
+        let s = format_tool_output("first\nsecond\n", false);
+        assert_eq!(s.matches("│").count(), 2);
+        assert!(s.contains("first"));
+        assert!(s.contains("second"));
+    }
+
     #[test]
     fn test_format_tool_end_failure() {
         let s = format_tool_end(false, "timeout");
@@ -1806,6 +1952,20 @@ mod tests {
         assert!(bar.contains("ReadFile"));
     }
 
+    #[test]
+    fn test_status_bar_context_meter() {
+        let mut state = ScreenState { width: 160, height: 24, ..Default::default() };
+        let bar = render_status_bar(&state);
+        assert!(!bar.contains("ctx"));
+
+        state.context_used = 150_000;
+        state.context_max = 200_000;
+        let bar = render_status_bar(&state);
+        assert!(bar.contains("ctx \u{2588}\u{2588}\u{2588}\u{2588}\u{2588}\u{2588}\u{2591}\u{2591} 75% of 200.0K tok"));
+        assert_eq!(context_meter(300, 200, 4), "\u{2588}".repeat(4));
+        assert_eq!(context_meter(0, 200, 2), "\u{2591}\u{2591}");
+    }
+
     #[test]
     fn test_status_bar_terminal_mode() {
         let mut state = ScreenState { width: 80, height: 24, ..Default::default() };
@@ -1823,6 +1983,8 @@ mod tests {
             prompt_zone: None,
             input_zone: None,
             output_zone: Some(crate::block::ZoneRange { start_y: 0, end_y: 10 }),
+            command: None,
+            cwd: None,
             exit_code: Some(0),
             start_time: None,
             end_time: None,
@@ -1843,6 +2005,8 @@ mod tests {
             prompt_zone: None,
             input_zone: None,
             output_zone: Some(crate::block::ZoneRange { start_y: 0, end_y: 5 }),
+            command: None,
+            cwd: None,
             exit_code: None,
             start_time: None,
             end_time: None,
@@ -1860,6 +2024,8 @@ mod tests {
             prompt_zone: None,
             input_zone: None,
             output_zone: Some(crate::block::ZoneRange { start_y: 0, end_y: 5 }),
+            command: None,
+            cwd: None,
             exit_code: None,
             start_time: None,
             end_time: None,
@@ -1877,6 +2043,8 @@ mod tests {
             prompt_zone: None,
             input_zone: None,
             output_zone: Some(crate::block::ZoneRange { start_y: 0, end_y: 5 }),
+            command: None,
+            cwd: None,
             exit_code: Some(127),
             start_time: None,
             end_time: None,
@@ -1895,6 +2063,8 @@ mod tests {
             prompt_zone: None,
             input_zone: None,
             output_zone: Some(crate::block::ZoneRange { start_y: 0, end_y: 5 }),
+            command: None,
+            cwd: None,
             exit_code: Some(0),
             start_time: None,
             end_time: None,
@@ -1905,4 +2075,23 @@ mod tests {
         // Selected header should have SELECTION background color escape
         assert!(header.contains("\x1b[48;2;40;44;66m")); // bgc(SELECTION)
     }
+
+    #[test]
+    fn test_render_block_header_with_command() {
+        let block = crate::block::Block {
+            id: 4,
+            prompt_zone: None,
+            input_zone: None,
+            output_zone: None,
+            command: Some("cargo test --workspace".to_string()),
+            cwd: None,
+            exit_code: Some(0),
+            start_time: None,
+            end_time: None,
+            collapsed: false,
+            bookmarked: false,
+        };
+        let header = render_block_header(&block, false);
+        assert!(header.contains("$ cargo test --workspace"));
+    }
 }
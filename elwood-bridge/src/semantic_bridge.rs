@@ -11,10 +11,29 @@
 //!   source code for context attachment.
 //! - **Relevant context retrieval**: Before each agent turn, find code snippets
 //!   related to the user's message via TF-IDF cosine similarity.
+//!
+//! ## Incremental Indexing
+//!
+//! Symbols are kept per file, stamped with the file's mtime, size and
+//! SHA-256. The per-file records are cached on disk
+//! (`~/.elwood/index/<project-key>.json`), so on startup only files whose
+//! stamp changed are re-parsed. [`SemanticBridge::build_in_background`]
+//! builds the index off the GUI thread and exposes its progress. The thread
+//! then keeps the index current: a filesystem watcher (honoring nested
+//! `.gitignore` files) feeds debounced reindexing, and each batch of changes
+//! is sent to the pane as a fresh read-only [`SemanticBridge`].
 
 use elwood_core::treesitter::semantic::{SemanticIndex, TfIdfEmbedder};
 use elwood_core::treesitter::{LanguageRegistry, Symbol, SymbolIndex, SymbolKind};
+use ignore::gitignore::Gitignore;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, UNIX_EPOCH};
 
 /// Default TF-IDF embedding dimension.
 const TFIDF_DIMENSIONS: usize = 128;
@@ -28,9 +47,30 @@ const MAX_FILE_SIZE: u64 = 512 * 1024;
 /// Maximum depth when walking the project directory.
 const MAX_WALK_DEPTH: usize = 10;
 
+/// Build output and dependency directories, skipped even when not gitignored.
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules"];
+
 /// Minimum TF-IDF similarity score to include in context results.
 const MIN_RELEVANCE_SCORE: f32 = 0.1;
 
+/// File extensions handed to the tree-sitter parser.
+const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "rs", "py", "pyi", "js", "jsx", "mjs", "cjs", "ts", "tsx", "go", "c", "h", "cpp", "cc", "cxx",
+    "hpp", "hh", "hxx", "java", "rb", "sh", "bash", "zsh",
+];
+
+/// Number of files parsed between progress updates during a full build.
+const PARSE_CHUNK: usize = 64;
+
+/// Quiet period after the last filesystem event before reindexing.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// How often the index thread checks for settled watcher events.
+const WATCH_POLL: Duration = Duration::from_millis(100);
+
+/// Bumped whenever the on-disk cache format changes.
+const CACHE_VERSION: u32 = 1;
+
 /// A symbol completion candidate.
 #[derive(Debug, Clone)]
 pub struct SymbolCompletion {
@@ -76,16 +116,308 @@ pub struct ContextSnippet {
     pub score: f32,
 }
 
+/// A symbol as stored in the index and its on-disk cache.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Absolute file path.
+    pub file: PathBuf,
+    /// Start line (1-based).
+    pub start_line: usize,
+    /// End line (1-based).
+    pub end_line: usize,
+    pub signature: String,
+    /// Enclosing type or module, if any.
+    pub parent: Option<String>,
+}
+
+impl From<&Symbol> for IndexedSymbol {
+    fn from(symbol: &Symbol) -> Self {
+        Self {
+            name: symbol.name.clone(),
+            kind: symbol.kind,
+            file: symbol.file.clone(),
+            start_line: symbol.start_line,
+            end_line: symbol.end_line,
+            signature: symbol.signature.clone(),
+            parent: symbol.parent.clone(),
+        }
+    }
+}
+
+/// Change-detection stamp of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct FileStamp {
+    /// Modification time in nanoseconds since the Unix epoch.
+    mtime_ns: u128,
+    size: u64,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Option<Self> {
+        let meta = std::fs::metadata(path).ok()?;
+        let mtime_ns = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        Some(Self {
+            mtime_ns,
+            size: meta.len(),
+        })
+    }
+}
+
+/// The indexed symbols of one file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileRecord {
+    stamp: FileStamp,
+    /// Hex SHA-256 of the contents, used when only the mtime changed.
+    hash: String,
+    symbols: Vec<IndexedSymbol>,
+}
+
+/// On-disk form of the index.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexCache {
+    version: u32,
+    root: PathBuf,
+    files: HashMap<PathBuf, FileRecord>,
+}
+
+/// How the last full build was satisfied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BuildStats {
+    /// Files reused from the on-disk cache.
+    pub cached: usize,
+    /// Files parsed with tree-sitter.
+    pub parsed: usize,
+}
+
+/// Progress of a full index build, shared with the thread doing it.
+#[derive(Debug, Default)]
+pub struct IndexProgress {
+    total: AtomicUsize,
+    done: AtomicUsize,
+}
+
+impl IndexProgress {
+    /// `(files done, files total)`; the total is 0 while the project is
+    /// still being walked.
+    pub fn snapshot(&self) -> (usize, usize) {
+        (
+            self.done.load(Ordering::Relaxed),
+            self.total.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// What [`IndexWorker::poll`] found.
+pub enum BuildStatus {
+    /// Still building the initial index: `(files done, files total)`.
+    Running(usize, usize),
+    /// A new index: the initial build, or one with changed files reindexed.
+    Ready(Box<SemanticBridge>),
+    /// Nothing new since the last poll.
+    Idle,
+    /// The index thread died without producing a bridge.
+    Failed,
+}
+
+/// Handle to the index thread started by
+/// [`SemanticBridge::build_in_background`]. Dropping it stops the thread.
+pub struct IndexWorker {
+    progress: Arc<IndexProgress>,
+    rx: flume::Receiver<SemanticBridge>,
+    refresh: flume::Sender<()>,
+    built: bool,
+}
+
+impl IndexWorker {
+    /// Check on the index thread without blocking.
+    pub fn poll(&mut self) -> BuildStatus {
+        if let Some(bridge) = self.rx.try_iter().last() {
+            self.built = true;
+            return BuildStatus::Ready(Box::new(bridge));
+        }
+        if self.built {
+            BuildStatus::Idle
+        } else if self.rx.is_disconnected() {
+            BuildStatus::Failed
+        } else {
+            let (done, total) = self.progress.snapshot();
+            BuildStatus::Running(done, total)
+        }
+    }
+
+    /// Ask the thread to re-walk the project for changes. Ignored while a
+    /// watcher keeps the index current.
+    pub fn request_refresh(&self) {
+        let _ = self.refresh.send(());
+    }
+}
+
+/// Decides which watcher events to drop, matching the initial project walk:
+/// hidden paths, [`SKIPPED_DIRS`] and `.gitignore` files at any depth.
+struct IgnoreRules {
+    root: PathBuf,
+    /// Parsed `.gitignore` of each directory seen (`None` if it has none).
+    gitignores: HashMap<PathBuf, Option<Gitignore>>,
+}
+
+impl IgnoreRules {
+    fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            gitignores: HashMap::new(),
+        }
+    }
+
+    /// Whether `path` would be skipped by the initial project walk.
+    fn is_ignored(&mut self, path: &Path) -> bool {
+        let Ok(rel) = path.strip_prefix(&self.root) else {
+            return true;
+        };
+        let skipped = rel.components().any(|c| {
+            let name = c.as_os_str().to_string_lossy();
+            name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref())
+        });
+        if skipped || rel.components().count() > MAX_WALK_DEPTH {
+            return true;
+        }
+
+        // The closest .gitignore with a matching rule decides
+        let is_dir = path.is_dir();
+        for dir in path.ancestors().skip(1) {
+            if !dir.starts_with(&self.root) {
+                break;
+            }
+            let gitignore = self.gitignores.entry(dir.to_path_buf()).or_insert_with(|| {
+                let file = dir.join(".gitignore");
+                file.is_file().then(|| Gitignore::new(file).0)
+            });
+            let Some(gitignore) = gitignore else { continue };
+            match gitignore.matched_path_or_any_parents(path, is_dir) {
+                ignore::Match::Ignore(_) => return true,
+                ignore::Match::Whitelist(_) => return false,
+                ignore::Match::None => {}
+            }
+        }
+        false
+    }
+
+    /// Drop the cached rules of a directory whose `.gitignore` changed.
+    fn forget_if_gitignore(&mut self, path: &Path) {
+        if path.file_name().is_some_and(|n| n == ".gitignore") {
+            if let Some(dir) = path.parent() {
+                self.gitignores.remove(dir);
+            }
+        }
+    }
+}
+
+/// Filesystem watcher that collects changed source paths for debouncing.
+struct IndexWatcher {
+    _watcher: notify::RecommendedWatcher,
+    /// Changed paths and the time of the most recent event.
+    pending: Arc<Mutex<(HashSet<PathBuf>, Option<Instant>)>>,
+}
+
+impl IndexWatcher {
+    fn start(root: &Path) -> anyhow::Result<Self> {
+        use notify::Watcher;
+
+        let pending: Arc<Mutex<(HashSet<PathBuf>, Option<Instant>)>> = Arc::default();
+        let sink = Arc::clone(&pending);
+        let mut rules = IgnoreRules::new(root);
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                use notify::event::{EventKind, ModifyKind};
+
+                let Ok(event) = res else { return };
+                if matches!(event.kind, EventKind::Access(_)) {
+                    return;
+                }
+                let moved_in = matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_))
+                );
+                let mut paths = Vec::new();
+                for path in event.paths {
+                    rules.forget_if_gitignore(&path);
+                    let source = has_supported_extension(&path);
+                    if (!source && path.is_file()) || rules.is_ignored(&path) {
+                        continue;
+                    }
+                    if source {
+                        paths.push(path);
+                    } else if path.is_dir() {
+                        // Files in a directory moved into the project get no
+                        // events of their own
+                        if moved_in {
+                            paths.extend(collect_source_files(&path));
+                        }
+                    } else if !path.exists() {
+                        // Maybe a directory moved away or deleted; the index
+                        // knows which files were under it
+                        paths.push(path);
+                    }
+                }
+                if paths.is_empty() {
+                    return;
+                }
+                let mut pending = sink.lock();
+                pending.0.extend(paths);
+                pending.1 = Some(Instant::now());
+            })?;
+        watcher.watch(root, notify::RecursiveMode::Recursive)?;
+        Ok(Self {
+            _watcher: watcher,
+            pending,
+        })
+    }
+
+    /// Changed paths, once no event has arrived for [`WATCH_DEBOUNCE`].
+    fn take_debounced(&self) -> Vec<PathBuf> {
+        let mut pending = self.pending.lock();
+        match pending.1 {
+            Some(last) if last.elapsed() >= WATCH_DEBOUNCE => {
+                pending.1 = None;
+                pending.0.drain().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// How a file compares with its indexed record.
+enum FileCheck {
+    Unchanged,
+    /// No longer indexable; its record was dropped.
+    Removed,
+    /// New or changed contents that need parsing.
+    Stale(FileStamp, String),
+}
+
 /// Bridge between elwood-core's tree-sitter capabilities and the terminal pane.
 ///
 /// Lazily initializes by scanning the project directory and building a
-/// `SymbolIndex` + `SemanticIndex` on a background thread.
+/// per-file symbol index + `SemanticIndex`, typically on a background thread
+/// (see [`build_in_background()`](Self::build_in_background)).
 pub struct SemanticBridge {
-    symbol_index: Option<SymbolIndex>,
+    /// Indexed symbols by absolute file path.
+    files: HashMap<PathBuf, FileRecord>,
     semantic_index: SemanticIndex,
     embedder: TfIdfEmbedder,
     project_root: PathBuf,
     initialized: bool,
+    /// Where the index is cached between sessions (`None` = no cache).
+    cache_path: Option<PathBuf>,
+    progress: Arc<IndexProgress>,
+    last_build: BuildStats,
+    watcher: Option<IndexWatcher>,
 }
 
 impl SemanticBridge {
@@ -94,52 +426,166 @@ impl SemanticBridge {
     /// The bridge is not initialized until [`initialize()`](Self::initialize) is called.
     pub fn new(project_root: PathBuf) -> Self {
         Self {
-            symbol_index: None,
+            files: HashMap::new(),
             semantic_index: SemanticIndex::new(),
             embedder: TfIdfEmbedder::new(TFIDF_DIMENSIONS),
             project_root,
             initialized: false,
+            cache_path: None,
+            progress: Arc::default(),
+            last_build: BuildStats::default(),
+            watcher: None,
+        }
+    }
+
+    /// Persist the index to (and load it from) `path`.
+    pub fn with_cache(mut self, path: PathBuf) -> Self {
+        self.cache_path = Some(path);
+        self
+    }
+
+    /// Build the index for `project_root` on a background thread, using the
+    /// default on-disk cache, then keep it current from the same thread.
+    ///
+    /// Every change (watcher events, or [`IndexWorker::request_refresh`]
+    /// when watching is unavailable) is reindexed and cached there, and
+    /// the updated index is sent back through [`IndexWorker::poll`].
+    pub fn build_in_background(project_root: PathBuf) -> IndexWorker {
+        let cache = default_cache_path(&project_root);
+        let mut bridge = Self::new(project_root);
+        if let Some(cache) = cache {
+            bridge = bridge.with_cache(cache);
+        }
+        let progress = Arc::clone(&bridge.progress);
+        let (tx, rx) = flume::bounded(1);
+        let (refresh, refresh_rx) = flume::unbounded();
+        let spawned = std::thread::Builder::new()
+            .name("semantic-index".into())
+            .spawn(move || {
+                bridge.initialize();
+                if let Err(e) = bridge.start_watching() {
+                    tracing::warn!("SemanticBridge: file watching unavailable: {e:#}");
+                }
+                if tx.send(bridge.snapshot()).is_err() {
+                    return;
+                }
+                loop {
+                    let changed = match refresh_rx.recv_timeout(WATCH_POLL) {
+                        Ok(()) if !bridge.is_watching() => {
+                            while refresh_rx.try_recv().is_ok() {}
+                            bridge.refresh()
+                        }
+                        Ok(()) | Err(flume::RecvTimeoutError::Timeout) => {
+                            bridge.process_file_events()
+                        }
+                        // The pane dropped its handle
+                        Err(flume::RecvTimeoutError::Disconnected) => return,
+                    };
+                    if changed > 0 && tx.send(bridge.snapshot()).is_err() {
+                        return;
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            tracing::warn!("SemanticBridge: failed to spawn index thread: {e}");
+        }
+        IndexWorker {
+            progress,
+            rx,
+            refresh,
+            built: false,
+        }
+    }
+
+    /// A copy of the index for answering queries, handed to the pane. The
+    /// TF-IDF index moves into it; the next [`apply_changes()`](Self::apply_changes)
+    /// rebuilds this bridge's own.
+    fn snapshot(&mut self) -> Self {
+        Self {
+            files: self.files.clone(),
+            semantic_index: std::mem::replace(&mut self.semantic_index, SemanticIndex::new()),
+            embedder: TfIdfEmbedder::new(TFIDF_DIMENSIONS),
+            project_root: self.project_root.clone(),
+            initialized: self.initialized,
+            cache_path: None,
+            progress: Arc::clone(&self.progress),
+            last_build: self.last_build,
+            watcher: None,
         }
     }
 
     /// Initialize the bridge by scanning the project and building indices.
     ///
     /// This is a blocking operation that walks the project directory,
-    /// parses source files with tree-sitter, and indexes symbols for
-    /// semantic search. Should be called from a background thread.
+    /// parses changed source files with tree-sitter (reusing cached symbols
+    /// for the rest), and indexes symbols for semantic search. Should be
+    /// called from a background thread.
     pub fn initialize(&mut self) {
         if self.initialized {
             return;
         }
 
-        let registry = LanguageRegistry::new();
-        let mut index = SymbolIndex::new(registry);
-
         let paths = collect_source_files(&self.project_root);
-        if !paths.is_empty() {
-            let parsed = index.parse_files_parallel(&paths);
-            tracing::info!(
-                "SemanticBridge: indexed {} files ({} symbols) from {}",
-                parsed,
-                index.symbol_count(),
-                self.project_root.display()
-            );
-        }
-
-        // Build semantic index from all symbols
-        for symbol in index.all_symbols() {
-            let id = format!("{}:{}", symbol.file.display(), symbol.start_line);
-            let text = format!(
-                "{} {} {} {}",
-                symbol.kind,
-                symbol.name,
-                symbol.signature,
-                symbol.parent.as_deref().unwrap_or("")
-            );
-            self.semantic_index.add(&id, &text, &self.embedder);
-        }
-
-        self.symbol_index = Some(index);
+        self.progress.total.store(paths.len(), Ordering::Relaxed);
+        self.progress.done.store(0, Ordering::Relaxed);
+
+        let mut cached = self.load_cache();
+        let mut stale = Vec::new();
+        let mut restamped = false;
+        for path in paths {
+            let Some(stamp) = FileStamp::of(&path) else {
+                continue;
+            };
+            match cached.remove(&path) {
+                Some(record) if record.stamp == stamp => {
+                    self.files.insert(path, record);
+                }
+                Some(mut record) if hash_file(&path).as_deref() == Some(record.hash.as_str()) => {
+                    record.stamp = stamp;
+                    restamped = true;
+                    self.files.insert(path, record);
+                }
+                _ => stale.push(path),
+            }
+        }
+        let reused = self.files.len();
+        self.progress.done.store(reused, Ordering::Relaxed);
+
+        if !stale.is_empty() {
+            let mut symbols = parse_symbols(&stale, Some(&self.progress));
+            for path in &stale {
+                let (Some(stamp), Some(hash)) = (FileStamp::of(path), hash_file(path)) else {
+                    continue;
+                };
+                let symbols = symbols.remove(path).unwrap_or_default();
+                self.files.insert(
+                    path.clone(),
+                    FileRecord {
+                        stamp,
+                        hash,
+                        symbols,
+                    },
+                );
+            }
+        }
+
+        self.last_build = BuildStats {
+            cached: reused,
+            parsed: stale.len(),
+        };
+        tracing::info!(
+            "SemanticBridge: indexed {} files ({} symbols, {} from cache) from {}",
+            self.files.len(),
+            self.symbol_count(),
+            reused,
+            self.project_root.display()
+        );
+
+        self.rebuild_semantic_index();
+        // Whatever is left in `cached` belongs to files that no longer exist.
+        if restamped || !stale.is_empty() || !cached.is_empty() {
+            self.save_cache();
+        }
         self.initialized = true;
     }
 
@@ -148,24 +594,201 @@ impl SemanticBridge {
         self.initialized
     }
 
+    /// Shared progress counters of the full build.
+    pub fn progress(&self) -> Arc<IndexProgress> {
+        Arc::clone(&self.progress)
+    }
+
+    /// How many files the last [`initialize()`](Self::initialize) reused
+    /// from the cache versus parsed.
+    pub fn last_build_stats(&self) -> BuildStats {
+        self.last_build
+    }
+
+    /// Start watching the project root for changes to source files.
+    pub fn start_watching(&mut self) -> anyhow::Result<()> {
+        self.watcher = Some(IndexWatcher::start(&self.project_root)?);
+        Ok(())
+    }
+
+    /// Whether a filesystem watcher keeps the index up to date.
+    pub fn is_watching(&self) -> bool {
+        self.watcher.is_some()
+    }
+
+    /// Reindex files reported by the watcher once they have settled.
+    ///
+    /// Cheap to call often. Returns the number of files whose symbols
+    /// changed.
+    pub fn process_file_events(&mut self) -> usize {
+        let Some(watcher) = &self.watcher else {
+            return 0;
+        };
+        let paths = watcher.take_debounced();
+        if paths.is_empty() {
+            return 0;
+        }
+        self.apply_changes(&paths)
+    }
+
+    /// Reindex `paths` (added, modified or deleted files) and refresh the
+    /// semantic index and cache if anything changed. A path that isn't a
+    /// source file stands for the indexed files under it, e.g. a directory
+    /// that was moved away.
+    ///
+    /// Returns the number of files whose symbols changed.
+    pub fn apply_changes(&mut self, paths: &[PathBuf]) -> usize {
+        if !self.initialized {
+            return 0;
+        }
+        let mut expanded = Vec::new();
+        for path in paths {
+            if has_supported_extension(path) {
+                expanded.push(path.clone());
+            } else {
+                expanded.extend(self.files.keys().filter(|f| f.starts_with(path)).cloned());
+            }
+        }
+        let mut changed = 0;
+        let mut stale = Vec::new();
+        for path in &expanded {
+            match self.check_file(path) {
+                FileCheck::Unchanged => {}
+                FileCheck::Removed => changed += 1,
+                FileCheck::Stale(stamp, hash) => stale.push((path.clone(), stamp, hash)),
+            }
+        }
+        if !stale.is_empty() {
+            let stale_paths: Vec<PathBuf> = stale.iter().map(|(p, ..)| p.clone()).collect();
+            let mut symbols = parse_symbols(&stale_paths, None);
+            changed += stale.len();
+            for (path, stamp, hash) in stale {
+                let symbols = symbols.remove(&path).unwrap_or_default();
+                self.files.insert(
+                    path,
+                    FileRecord {
+                        stamp,
+                        hash,
+                        symbols,
+                    },
+                );
+            }
+        }
+        if changed > 0 {
+            self.rebuild_semantic_index();
+            self.save_cache();
+            tracing::debug!("SemanticBridge: reindexed {changed} changed file(s)");
+        }
+        changed
+    }
+
+    /// Compare a file with its record, dropping the record if the file is
+    /// gone or no longer indexable.
+    fn check_file(&mut self, path: &Path) -> FileCheck {
+        let indexable = has_supported_extension(path)
+            && std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.len() <= MAX_FILE_SIZE);
+        let stamp = FileStamp::of(path).filter(|_| indexable);
+        let Some(stamp) = stamp else {
+            return match self.files.remove(path) {
+                Some(_) => FileCheck::Removed,
+                None => FileCheck::Unchanged,
+            };
+        };
+        let hash = match self.files.get_mut(path) {
+            Some(record) if record.stamp == stamp => return FileCheck::Unchanged,
+            Some(record) => {
+                let hash = hash_file(path);
+                if hash.as_deref() == Some(record.hash.as_str()) {
+                    record.stamp = stamp;
+                    return FileCheck::Unchanged;
+                }
+                hash
+            }
+            None => hash_file(path),
+        };
+        match hash {
+            Some(hash) => FileCheck::Stale(stamp, hash),
+            None => FileCheck::Unchanged,
+        }
+    }
+
+    /// Iterate over every indexed symbol.
+    fn symbols(&self) -> impl Iterator<Item = &IndexedSymbol> {
+        self.files.values().flat_map(|r| r.symbols.iter())
+    }
+
+    /// Rebuild the TF-IDF index from the per-file symbols.
+    fn rebuild_semantic_index(&mut self) {
+        self.semantic_index.clear();
+        for record in self.files.values() {
+            for symbol in &record.symbols {
+                let id = format!("{}:{}", symbol.file.display(), symbol.start_line);
+                let text = format!(
+                    "{} {} {} {}",
+                    symbol.kind,
+                    symbol.name,
+                    symbol.signature,
+                    symbol.parent.as_deref().unwrap_or("")
+                );
+                self.semantic_index.add(&id, &text, &self.embedder);
+            }
+        }
+    }
+
+    /// Load cached file records for this project, if any.
+    fn load_cache(&self) -> HashMap<PathBuf, FileRecord> {
+        let Some(path) = &self.cache_path else {
+            return HashMap::new();
+        };
+        let cache = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<IndexCache>(&text).ok());
+        match cache {
+            Some(cache) if cache.version == CACHE_VERSION && cache.root == self.project_root => {
+                cache.files
+            }
+            _ => HashMap::new(),
+        }
+    }
+
+    /// Write the index to the cache file (if configured), logging failures.
+    fn save_cache(&self) {
+        let Some(path) = &self.cache_path else { return };
+        if let Err(e) = self.write_cache(path) {
+            tracing::warn!("SemanticBridge: failed to save index cache: {e:#}");
+        }
+    }
+
+    fn write_cache(&self, path: &Path) -> anyhow::Result<()> {
+        use anyhow::Context;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let cache = IndexCache {
+            version: CACHE_VERSION,
+            root: self.project_root.clone(),
+            files: self.files.clone(),
+        };
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&cache)?)
+            .with_context(|| format!("writing {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("replacing {}", path.display()))?;
+        Ok(())
+    }
+
     /// Complete a symbol name prefix.
     ///
     /// Returns up to `limit` symbols whose names match the prefix
     /// (case-insensitive), ranked by relevance.
     pub fn complete_symbol(&self, prefix: &str, limit: usize) -> Vec<SymbolCompletion> {
-        let index = match &self.symbol_index {
-            Some(idx) => idx,
-            None => return Vec::new(),
-        };
-
-        if prefix.is_empty() {
+        if !self.initialized || prefix.is_empty() {
             return Vec::new();
         }
 
         let prefix_lower = prefix.to_lowercase();
-        let mut results: Vec<SymbolCompletion> = index
-            .all_symbols()
-            .into_iter()
+        let mut results: Vec<SymbolCompletion> = self
+            .symbols()
             .filter_map(|sym| {
                 let name_lower = sym.name.to_lowercase();
                 let score = if name_lower == prefix_lower {
@@ -202,6 +825,8 @@ impl SemanticBridge {
             b.score
                 .partial_cmp(&a.score)
                 .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.file.cmp(&b.file))
+                .then_with(|| a.line.cmp(&b.line))
         });
         results.truncate(limit);
         results
@@ -209,17 +834,32 @@ impl SemanticBridge {
 
     /// Resolve a symbol name to its definition and source code.
     ///
-    /// Finds the best-matching symbol by name and reads the source lines
-    /// from disk. Returns `None` if no match is found.
+    /// Finds the best-matching symbol by name (exact, then prefix, then
+    /// substring; case-insensitive) and reads the source lines from disk.
+    /// Returns `None` if no match is found.
     pub fn resolve_symbol(&self, name: &str) -> Option<SymbolDefinition> {
-        let index = self.symbol_index.as_ref()?;
-
-        let matches = index.search_by_name(name);
-        // Prefer exact match, then take first result
-        let symbol = matches
-            .iter()
-            .find(|s| s.name.eq_ignore_ascii_case(name))
-            .or_else(|| matches.first())?;
+        let name_lower = name.to_lowercase();
+        let rank = |sym: &IndexedSymbol| {
+            let n = sym.name.to_lowercase();
+            if n == name_lower {
+                Some(0)
+            } else if n.starts_with(&name_lower) {
+                Some(1)
+            } else if n.contains(&name_lower) {
+                Some(2)
+            } else {
+                None
+            }
+        };
+        let symbol = self
+            .symbols()
+            .filter_map(|s| rank(s).map(|r| (r, s)))
+            .min_by(|(ra, a), (rb, b)| {
+                ra.cmp(rb)
+                    .then_with(|| a.file.cmp(&b.file))
+                    .then_with(|| a.start_line.cmp(&b.start_line))
+            })
+            .map(|(_, s)| s)?;
 
         let source = read_symbol_source(symbol)?;
 
@@ -238,11 +878,7 @@ impl SemanticBridge {
     ///
     /// Uses TF-IDF similarity to find the most related symbols, then reads
     /// their source code. Stops when `max_tokens` (approximate) is reached.
-    pub fn find_relevant_context(
-        &self,
-        query: &str,
-        max_tokens: usize,
-    ) -> Vec<ContextSnippet> {
+    pub fn find_relevant_context(&self, query: &str, max_tokens: usize) -> Vec<ContextSnippet> {
         if !self.initialized || query.is_empty() {
             return Vec::new();
         }
@@ -271,13 +907,9 @@ impl SemanticBridge {
 
             // Find the symbol to get end_line
             let symbol = self
-                .symbol_index
-                .as_ref()
-                .and_then(|idx| {
-                    idx.all_symbols().into_iter().find(|s| {
-                        s.file.display().to_string() == file_str && s.start_line == line
-                    })
-                });
+                .files
+                .get(Path::new(file_str))
+                .and_then(|r| r.symbols.iter().find(|s| s.start_line == line));
 
             let source = match symbol {
                 Some(sym) => match read_symbol_source(sym) {
@@ -306,63 +938,102 @@ impl SemanticBridge {
 
     /// Refresh the index for changed files.
     ///
-    /// Re-scans the project directory and reparses any files that have
-    /// changed. This is more efficient than a full rebuild.
-    pub fn refresh(&mut self) {
-        let index = match &mut self.symbol_index {
-            Some(idx) => idx,
-            None => return,
-        };
-
-        let paths = collect_source_files(&self.project_root);
-        for path in &paths {
-            if index.is_indexed(path) {
-                index.reparse_file(path);
-            } else {
-                index.parse_file(path);
-            }
+    /// Re-walks the project directory, drops deleted files and reparses only
+    /// files whose mtime/size and content hash changed. Unnecessary while a
+    /// watcher is running (see [`is_watching()`](Self::is_watching)).
+    ///
+    /// Returns the number of files whose symbols changed.
+    pub fn refresh(&mut self) -> usize {
+        if !self.initialized {
+            return 0;
         }
 
-        // Rebuild semantic index
-        self.semantic_index.clear();
-        for symbol in index.all_symbols() {
-            let id = format!("{}:{}", symbol.file.display(), symbol.start_line);
-            let text = format!(
-                "{} {} {} {}",
-                symbol.kind,
-                symbol.name,
-                symbol.signature,
-                symbol.parent.as_deref().unwrap_or("")
-            );
-            self.semantic_index.add(&id, &text, &self.embedder);
-        }
+        let current: HashSet<PathBuf> = collect_source_files(&self.project_root)
+            .into_iter()
+            .collect();
+        let mut changed: Vec<PathBuf> = self
+            .files
+            .keys()
+            .filter(|p| !current.contains(*p))
+            .cloned()
+            .collect();
+        changed.extend(current);
+        let count = self.apply_changes(&changed);
 
         tracing::debug!(
-            "SemanticBridge: refreshed — {} files, {} symbols",
-            index.file_count(),
-            index.symbol_count()
+            "SemanticBridge: refreshed — {} files, {} symbols, {count} changed",
+            self.file_count(),
+            self.symbol_count()
         );
+        count
     }
 
     /// Get the number of indexed symbols.
     pub fn symbol_count(&self) -> usize {
-        self.symbol_index
-            .as_ref()
-            .map(|idx| idx.symbol_count())
-            .unwrap_or(0)
+        self.files.values().map(|r| r.symbols.len()).sum()
     }
 
     /// Get the number of indexed files.
     pub fn file_count(&self) -> usize {
-        self.symbol_index
-            .as_ref()
-            .map(|idx| idx.file_count())
-            .unwrap_or(0)
+        self.files.len()
+    }
+}
+
+/// Default cache file for a project: `~/.elwood/index/<key>.json`.
+pub fn default_cache_path(project_root: &Path) -> Option<PathBuf> {
+    let digest = Sha256::digest(project_root.to_string_lossy().as_bytes());
+    let key: String = digest.iter().take(8).map(|b| format!("{b:02x}")).collect();
+    Some(
+        dirs_next::home_dir()?
+            .join(".elwood")
+            .join("index")
+            .join(format!("{key}.json")),
+    )
+}
+
+/// Parse `paths` with a fresh tree-sitter index and group the symbols by
+/// file, counting parsed files into `progress`.
+fn parse_symbols(
+    paths: &[PathBuf],
+    progress: Option<&IndexProgress>,
+) -> HashMap<PathBuf, Vec<IndexedSymbol>> {
+    let mut parser = SymbolIndex::new(LanguageRegistry::new());
+    for chunk in paths.chunks(PARSE_CHUNK) {
+        parser.parse_files_parallel(chunk);
+        if let Some(progress) = progress {
+            progress.done.fetch_add(chunk.len(), Ordering::Relaxed);
+        }
     }
+    let mut symbols: HashMap<PathBuf, Vec<IndexedSymbol>> = HashMap::new();
+    for symbol in parser.all_symbols() {
+        symbols
+            .entry(symbol.file.clone())
+            .or_default()
+            .push(symbol.into());
+    }
+    symbols
+}
+
+/// Hex SHA-256 of a file's contents.
+fn hash_file(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    Some(
+        Sha256::digest(&bytes)
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect(),
+    )
+}
+
+/// Whether the parser handles files with this path's extension.
+fn has_supported_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| SUPPORTED_EXTENSIONS.contains(&ext))
 }
 
 /// Read the source code lines for a symbol from disk.
-fn read_symbol_source(symbol: &Symbol) -> Option<String> {
+fn read_symbol_source(symbol: &IndexedSymbol) -> Option<String> {
     let content = std::fs::read_to_string(&symbol.file).ok()?;
     let lines: Vec<&str> = content.lines().collect();
 
@@ -386,16 +1057,9 @@ fn collect_source_files(root: &Path) -> Vec<PathBuf> {
         .git_global(true)
         .git_exclude(true)
         .max_depth(Some(MAX_WALK_DEPTH))
+        .filter_entry(|entry| !SKIPPED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()))
         .build();
 
-    // Pre-build the extension set for fast lookups
-    let supported_exts: std::collections::HashSet<&str> = [
-        "rs", "py", "pyi", "js", "jsx", "mjs", "cjs", "ts", "tsx", "go", "c", "h", "cpp", "cc",
-        "cxx", "hpp", "hh", "hxx", "java", "rb", "sh", "bash", "zsh",
-    ]
-    .into_iter()
-    .collect();
-
     for entry in walker.flatten() {
         if paths.len() >= MAX_INDEX_FILES {
             break;
@@ -406,12 +1070,7 @@ fn collect_source_files(root: &Path) -> Vec<PathBuf> {
         }
 
         // Check file extension
-        let ext = entry
-            .path()
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("");
-        if !supported_exts.contains(ext) {
+        if !has_supported_extension(entry.path()) {
             continue;
         }
 
@@ -645,7 +1304,10 @@ pub fn validate_input(input: &str) -> bool {
 
     #[test]
     fn test_extract_symbol_name() {
-        assert_eq!(extract_symbol_name("symbol:calculate_total"), Some("calculate_total"));
+        assert_eq!(
+            extract_symbol_name("symbol:calculate_total"),
+            Some("calculate_total")
+        );
         assert_eq!(extract_symbol_name("symbol:Config"), Some("Config"));
         assert_eq!(extract_symbol_name("not_a_symbol"), None);
     }
@@ -680,4 +1342,262 @@ pub fn validate_input(input: &str) -> bool {
         bridge.initialize(); // second call should be no-op
         assert_eq!(bridge.symbol_count(), count);
     }
+
+    #[test]
+    fn test_cache_reuses_unchanged_files() {
+        let dir = setup_test_project();
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache = cache_dir.path().join("index.json");
+
+        let mut first = SemanticBridge::new(dir.path().to_path_buf()).with_cache(cache.clone());
+        first.initialize();
+        assert_eq!(
+            first.last_build_stats(),
+            BuildStats {
+                cached: 0,
+                parsed: 2
+            }
+        );
+        assert!(cache.exists());
+
+        let mut second = SemanticBridge::new(dir.path().to_path_buf()).with_cache(cache.clone());
+        second.initialize();
+        assert_eq!(
+            second.last_build_stats(),
+            BuildStats {
+                cached: 2,
+                parsed: 0
+            }
+        );
+        assert_eq!(second.symbol_count(), first.symbol_count());
+        assert_eq!(
+            second.resolve_symbol("calculate_total").unwrap().kind,
+            SymbolKind::Function
+        );
+        assert_eq!(second.progress().snapshot(), (2, 2));
+
+        // Only the edited file is parsed again.
+        fs::write(
+            dir.path().join("src/lib.rs"),
+            "pub fn process_data(data: &str) -> String {\n    data.to_lowercase()\n}\n",
+        )
+        .unwrap();
+        let mut third = SemanticBridge::new(dir.path().to_path_buf()).with_cache(cache);
+        third.initialize();
+        assert_eq!(
+            third.last_build_stats(),
+            BuildStats {
+                cached: 1,
+                parsed: 1
+            }
+        );
+        assert!(third.resolve_symbol("validate_input").is_none());
+    }
+
+    #[test]
+    fn test_cache_for_other_root_ignored() {
+        let dir = setup_test_project();
+        let other = setup_test_project();
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache = cache_dir.path().join("index.json");
+
+        SemanticBridge::new(dir.path().to_path_buf())
+            .with_cache(cache.clone())
+            .initialize();
+        let mut bridge = SemanticBridge::new(other.path().to_path_buf()).with_cache(cache);
+        bridge.initialize();
+        assert_eq!(bridge.last_build_stats().cached, 0);
+    }
+
+    #[test]
+    fn test_apply_changes_add_modify_delete() {
+        let dir = setup_test_project();
+        let mut bridge = SemanticBridge::new(dir.path().to_path_buf());
+        bridge.initialize();
+
+        let new_file = dir.path().join("src/extra.rs");
+        fs::write(&new_file, "pub fn freshly_added() {\n}\n").unwrap();
+        assert_eq!(bridge.apply_changes(std::slice::from_ref(&new_file)), 1);
+        assert_eq!(
+            bridge.complete_symbol("freshly", 5)[0].name,
+            "freshly_added"
+        );
+
+        // Unchanged files are skipped.
+        assert_eq!(bridge.apply_changes(std::slice::from_ref(&new_file)), 0);
+
+        fs::remove_file(&new_file).unwrap();
+        assert_eq!(bridge.apply_changes(&[new_file]), 1);
+        assert!(bridge.complete_symbol("freshly", 5).is_empty());
+
+        // Unsupported files are never indexed.
+        let notes = dir.path().join("notes.txt");
+        fs::write(&notes, "fn not_code() {}").unwrap();
+        assert_eq!(bridge.apply_changes(&[notes]), 0);
+    }
+
+    #[test]
+    fn test_apply_changes_directory_moved_away() {
+        let dir = setup_test_project();
+        let mut bridge = SemanticBridge::new(dir.path().to_path_buf());
+        bridge.initialize();
+        assert_eq!(bridge.file_count(), 2);
+
+        // Only the directory itself is reported for a move
+        let src = dir.path().join("src");
+        fs::rename(&src, dir.path().join("moved")).unwrap();
+        assert_eq!(bridge.apply_changes(&[src]), 2);
+        assert_eq!(bridge.file_count(), 0);
+        assert!(bridge.resolve_symbol("process_data").is_none());
+    }
+
+    #[test]
+    fn test_refresh_picks_up_changes() {
+        let dir = setup_test_project();
+        let mut bridge = SemanticBridge::new(dir.path().to_path_buf());
+        bridge.initialize();
+        assert_eq!(bridge.file_count(), 2);
+
+        fs::remove_file(dir.path().join("src/lib.rs")).unwrap();
+        fs::write(
+            dir.path().join("src/util.rs"),
+            "pub fn helper_util() {\n}\n",
+        )
+        .unwrap();
+        bridge.refresh();
+
+        assert_eq!(bridge.file_count(), 2);
+        assert!(bridge.resolve_symbol("process_data").is_none());
+        assert!(bridge.resolve_symbol("helper_util").is_some());
+    }
+
+    #[test]
+    fn test_watcher_reindexes_changed_file() {
+        let dir = setup_test_project();
+        let mut bridge = SemanticBridge::new(dir.path().to_path_buf());
+        bridge.initialize();
+        if bridge.start_watching().is_err() {
+            return; // no inotify/FSEvents in this environment
+        }
+        assert!(bridge.is_watching());
+
+        fs::write(
+            dir.path().join("src/watched.rs"),
+            "pub fn seen_by_watcher() {\n}\n",
+        )
+        .unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while bridge.resolve_symbol("seen_by_watcher").is_none() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+            bridge.process_file_events();
+        }
+        assert!(bridge.resolve_symbol("seen_by_watcher").is_some());
+    }
+
+    #[test]
+    fn test_watcher_follows_moved_directory() {
+        let dir = setup_test_project();
+        let mut bridge = SemanticBridge::new(dir.path().to_path_buf());
+        bridge.initialize();
+        if bridge.start_watching().is_err() {
+            return; // no inotify/FSEvents in this environment
+        }
+
+        let moved = dir.path().join("code");
+        fs::rename(dir.path().join("src"), &moved).unwrap();
+        let in_moved = |bridge: &SemanticBridge| {
+            bridge
+                .resolve_symbol("process_data")
+                .is_some_and(|s| s.file.starts_with(&moved))
+        };
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !(in_moved(&bridge) && bridge.file_count() == 2) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+            bridge.process_file_events();
+        }
+        assert!(in_moved(&bridge));
+        assert_eq!(bridge.file_count(), 2);
+    }
+
+    /// Poll `worker` until it sends a new index.
+    fn next_index(worker: &mut IndexWorker) -> Box<SemanticBridge> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            match worker.poll() {
+                BuildStatus::Ready(bridge) => return bridge,
+                BuildStatus::Running(done, total) => assert!(done <= total || total == 0),
+                BuildStatus::Idle => {}
+                BuildStatus::Failed => panic!("index thread died"),
+            }
+            assert!(Instant::now() < deadline, "index update timed out");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_build_in_background() {
+        let dir = setup_test_project();
+        let mut worker = SemanticBridge::build_in_background(dir.path().to_path_buf());
+        let bridge = next_index(&mut worker);
+        assert!(bridge.is_initialized());
+        assert!(bridge.resolve_symbol("calculate_total").is_some());
+        assert!(matches!(worker.poll(), BuildStatus::Idle));
+
+        // Changes are reindexed on the index thread and sent as a new index,
+        // whether it is watching or has to re-walk the project
+        fs::write(
+            dir.path().join("src/later.rs"),
+            "pub fn added_later() {\n}\n",
+        )
+        .unwrap();
+        worker.request_refresh();
+        let bridge = next_index(&mut worker);
+        assert!(bridge.resolve_symbol("added_later").is_some());
+        assert!(bridge.resolve_symbol("calculate_total").is_some());
+    }
+
+    #[test]
+    fn test_ignore_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join(".gitignore"), "*.gen.rs\n").unwrap();
+        fs::write(root.join("sub/.gitignore"), "skip.rs\n!keep.gen.rs\n").unwrap();
+        let mut rules = IgnoreRules::new(root);
+
+        assert!(!rules.is_ignored(&root.join("src/main.rs")));
+        assert!(rules.is_ignored(&root.join("api.gen.rs")));
+        // Nested .gitignore files apply below their directory and override
+        // the ones above
+        assert!(rules.is_ignored(&root.join("sub/skip.rs")));
+        assert!(!rules.is_ignored(&root.join("skip.rs")));
+        assert!(!rules.is_ignored(&root.join("sub/keep.gen.rs")));
+        assert!(rules.is_ignored(&root.join("sub/other.gen.rs")));
+        // Build output, dependencies and hidden paths
+        assert!(rules.is_ignored(&root.join("target/debug/build/out/gen.rs")));
+        assert!(rules.is_ignored(&root.join("web/node_modules/pkg/index.js")));
+        assert!(rules.is_ignored(&root.join(".git/hooks/pre-commit.sh")));
+        assert!(rules.is_ignored(Path::new("/elsewhere/main.rs")));
+
+        // Edited .gitignore files are picked up again
+        fs::write(root.join("sub/.gitignore"), "").unwrap();
+        rules.forget_if_gitignore(&root.join("sub/.gitignore"));
+        assert!(!rules.is_ignored(&root.join("sub/skip.rs")));
+    }
+
+    #[test]
+    fn test_collect_source_files_skips_build_dirs() {
+        let dir = setup_test_project();
+        fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+        fs::write(dir.path().join("target/debug/gen.rs"), "fn gen() {}").unwrap();
+        fs::create_dir_all(dir.path().join("node_modules/pkg")).unwrap();
+        fs::write(
+            dir.path().join("node_modules/pkg/index.js"),
+            "function f() {}",
+        )
+        .unwrap();
+
+        let files = collect_source_files(dir.path());
+        assert_eq!(files.len(), 2);
+    }
 }
@@ -3,6 +3,7 @@
 //! Provides three export targets beyond the existing markdown export:
 //!
 //! - **HTML**: Self-contained single file with embedded Tokyo Night CSS
+//!   (plus per-block snippets for sharing a single command and its output)
 //! - **JSON**: Machine-readable structured format (version 1)
 //! - **Encrypted share**: Compressed + encrypted `.elwood-session` file
 //!
@@ -14,7 +15,9 @@ use std::path::{Path, PathBuf};
 
 use sha2::{Digest, Sha256};
 
+use crate::block::Block;
 use crate::session_log::{EntryType, SessionEntry, SessionLog};
+use crate::session_resume::AgentState;
 
 /// Magic bytes identifying an Elwood session file.
 const MAGIC: &[u8; 4] = b"ELWD";
@@ -45,7 +48,9 @@ pub fn export_html(session: &SessionLog) -> String {
         escape_html(&session.started_at)
     ));
     html.push_str("<style>\n");
+    html.push_str(&format!(":root {{{TOKYO_NIGHT_VARS}}}\n"));
     html.push_str(TOKYO_NIGHT_CSS);
+    html.push_str(ENTRY_CSS);
     html.push_str("</style>\n");
     html.push_str("</head>\n<body>\n");
 
@@ -73,6 +78,76 @@ pub fn export_html(session: &SessionLog) -> String {
     html
 }
 
+/// Generate a self-contained HTML snippet for a single terminal block.
+///
+/// The snippet carries its own `<style>` scoped to `.elwood-block`, so it can
+/// be pasted into a review comment or wiki page without the full document.
+pub fn export_block_html(block: &Block, command: Option<&str>, output: &str) -> String {
+    let mut html = String::with_capacity(4096);
+
+    html.push_str("<div class=\"elwood-block\">\n<style>\n");
+    html.push_str(&format!(".elwood-block {{{TOKYO_NIGHT_VARS}}}\n"));
+    html.push_str(BLOCK_SNIPPET_CSS);
+    html.push_str(&scope_css(ENTRY_CSS, ".elwood-block"));
+    html.push_str("</style>\n");
+
+    if let Some(cmd) = command {
+        html.push_str("<div class=\"entry command\">\n");
+        html.push_str(&format!(
+            "<pre class=\"code-block\"><code>$ {}</code></pre>\n",
+            escape_html(cmd)
+        ));
+        html.push_str("</div>\n");
+    }
+
+    html.push_str("<div class=\"entry output\">\n");
+    html.push_str(&format!(
+        "<pre class=\"code-block output-block\"><code>{}</code></pre>\n",
+        escape_html(output.trim_end())
+    ));
+    html.push_str("</div>\n");
+
+    let mut meta = Vec::new();
+    match block.exit_code {
+        Some(0) => meta.push("<span class=\"exit-ok\">exit 0</span>".to_string()),
+        Some(n) => meta.push(format!("<span class=\"exit-err\">exit {n}</span>")),
+        None => {}
+    }
+    if let Some(dur) = block.duration_secs() {
+        meta.push(format!("{dur:.1}s"));
+    }
+    if let Some(ref cwd) = block.cwd {
+        meta.push(format!("<code>{}</code>", escape_html(&cwd.display().to_string())));
+    }
+    if !meta.is_empty() {
+        html.push_str(&format!("<div class=\"meta\">{}</div>\n", meta.join(" · ")));
+    }
+
+    html.push_str("</div>\n");
+    html
+}
+
+/// Prefix every selector in `css` with `scope`, so a snippet's rules can't
+/// restyle the page it's pasted into. Expects one rule header per line, as in
+/// the stylesheets here.
+fn scope_css(css: &str, scope: &str) -> String {
+    let mut out = String::with_capacity(css.len() * 2);
+    for line in css.lines() {
+        match line.split_once('{') {
+            Some((selectors, rest)) if !selectors.trim().is_empty() => {
+                let scoped: Vec<String> = selectors
+                    .split(',')
+                    .map(|sel| format!("{scope} {}", sel.trim()))
+                    .collect();
+                out.push_str(&format!("{} {{{rest}", scoped.join(", ")));
+            }
+            _ => out.push_str(line),
+        }
+        out.push('\n');
+    }
+    out
+}
+
 /// Render a single entry as HTML.
 fn render_entry_html(html: &mut String, entry: &SessionEntry) {
     match entry.entry_type {
@@ -151,9 +226,8 @@ fn escape_html(s: &str) -> String {
         .replace('"', "&quot;")
 }
 
-/// Inline CSS using Tokyo Night colours.
-const TOKYO_NIGHT_CSS: &str = r#"
-:root {
+/// Tokyo Night colour variables shared by the page and block-snippet styles.
+const TOKYO_NIGHT_VARS: &str = r#"
   --bg: #1a1b26;
   --bg-dark: #16161e;
   --bg-highlight: #292e42;
@@ -168,8 +242,10 @@ const TOKYO_NIGHT_CSS: &str = r#"
   --yellow: #e0af68;
   --orange: #ff9e64;
   --border: #3b4261;
-}
+"#;
 
+/// Page-level CSS for a full session document.
+const TOKYO_NIGHT_CSS: &str = r#"
 * { margin: 0; padding: 0; box-sizing: border-box; }
 
 body {
@@ -196,6 +272,14 @@ hr { border: none; border-top: 1px solid var(--border); margin: 1.5rem 0; }
 
 .entries { display: flex; flex-direction: column; gap: 1rem; }
 
+@media (max-width: 600px) {
+  body { padding: 1rem; font-size: 13px; }
+  .session-header h1 { font-size: 1.2rem; }
+}
+"#;
+
+/// Entry CSS shared by the session document and exported block snippets.
+const ENTRY_CSS: &str = r#"
 .entry {
   border-left: 3px solid var(--border);
   padding: 0.75rem 1rem;
@@ -237,11 +321,27 @@ hr { border: none; border-top: 1px solid var(--border); margin: 1.5rem 0; }
 }
 
 .output-block { color: var(--fg-dark); border-radius: 0 0 6px 0; }
+"#;
 
-@media (max-width: 600px) {
-  body { padding: 1rem; font-size: 13px; }
-  .session-header h1 { font-size: 1.2rem; }
+/// Wrapper CSS for a standalone block snippet (scoped to `.elwood-block`).
+const BLOCK_SNIPPET_CSS: &str = r#"
+.elwood-block {
+  background: var(--bg);
+  color: var(--fg);
+  font-family: 'SF Mono', 'Fira Code', 'JetBrains Mono', 'Cascadia Code', monospace;
+  font-size: 14px;
+  line-height: 1.6;
+  padding: 1rem;
+  border-radius: 6px;
+  display: flex;
+  flex-direction: column;
+  gap: 0.5rem;
 }
+
+.elwood-block pre { margin: 0; }
+.elwood-block .meta { color: var(--comment); font-size: 0.8rem; }
+.elwood-block .exit-ok { color: var(--green); }
+.elwood-block .exit-err { color: var(--red); }
 "#;
 
 // ─── JSON Export ────────────────────────────────────────────────────────────
@@ -253,10 +353,16 @@ pub struct SessionJson {
     pub version: u32,
     /// Session start time (ISO 8601).
     pub started_at: String,
+    /// Session id, see [`SessionLog::id`]; absent in older exports.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
     /// All log entries.
     pub entries: Vec<EntryJson>,
     /// Session metadata.
     pub metadata: SessionMetadata,
+    /// Agent state for `/resume`, when the session was saved by a pane.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<AgentState>,
 }
 
 /// JSON representation of a single log entry.
@@ -284,6 +390,7 @@ impl SessionLog {
         let json = SessionJson {
             version: 1,
             started_at: self.started_at.clone(),
+            id: self.id.clone(),
             entries: self
                 .entries
                 .iter()
@@ -296,6 +403,7 @@ impl SessionLog {
             metadata: SessionMetadata {
                 working_dir: self.working_dir.display().to_string(),
             },
+            agent: self.agent_state.clone(),
         };
         serde_json::to_string_pretty(&json)
     }
@@ -305,6 +413,7 @@ impl SessionLog {
         let parsed: SessionJson = serde_json::from_str(json)?;
         Ok(SessionLog {
             started_at: parsed.started_at,
+            id: parsed.id,
             working_dir: PathBuf::from(&parsed.metadata.working_dir),
             entries: parsed
                 .entries
@@ -315,6 +424,7 @@ impl SessionLog {
                     content: e.content,
                 })
                 .collect(),
+            agent_state: parsed.agent,
         })
     }
 }
@@ -442,10 +552,16 @@ pub fn import_shared(data: &[u8], passphrase: &str) -> Result<SessionLog, ShareE
 ///
 /// - `.elwood-session` files are treated as encrypted (passphrase required)
 /// - `.json` files are imported as JSON
-/// - `.md` files are not supported for import
+/// - `.md` files are parsed as a markdown export
 pub fn import_from_file(path: &Path, passphrase: Option<&str>) -> Result<SessionLog, ShareError> {
     let data = std::fs::read(path)?;
 
+    if path.extension().is_some_and(|e| e == "md") {
+        let text = String::from_utf8_lossy(&data);
+        return SessionLog::from_markdown(&text)
+            .ok_or_else(|| ShareError::InvalidFormat("not an Elwood session export".into()));
+    }
+
     // Check for encrypted format by magic bytes
     if data.len() >= 4 && &data[..4] == MAGIC {
         let pass = passphrase.unwrap_or("");
@@ -612,6 +728,30 @@ mod tests {
         assert!(html.contains("&lt;script&gt;"));
     }
 
+    #[test]
+    fn test_block_html_snippet() {
+        let mut mgr = crate::block::BlockManager::new();
+        mgr.push_command_block(0, "echo <hi>", Some(PathBuf::from("/tmp")));
+        mgr.finish_block(Some(0));
+        let block = &mgr.blocks()[0];
+
+        let html = export_block_html(block, block.command.as_deref(), "<hi>\n");
+        assert!(html.starts_with("<div class=\"elwood-block\">"));
+        assert!(html.contains("<style>"));
+        assert!(html.contains("--bg: #1a1b26"));
+        assert!(html.contains("$ echo &lt;hi&gt;"));
+        assert!(html.contains("exit 0"));
+        assert!(!html.contains("<!DOCTYPE"));
+        assert!(!html.contains("<hi>"));
+
+        // Every rule is scoped to the snippet
+        let css = &html[html.find("<style>").unwrap()..html.find("</style>").unwrap()];
+        for line in css.lines().filter(|l| l.contains('{')) {
+            assert!(line.starts_with(".elwood-block"), "unscoped rule: {line}");
+        }
+        assert!(css.contains(".elwood-block .entry.user .entry-header {"));
+    }
+
     #[test]
     fn test_html_export_empty_session() {
         let log = SessionLog::new(PathBuf::from("/tmp"));
@@ -629,6 +769,7 @@ mod tests {
         let restored = SessionLog::from_json(&json).unwrap();
 
         assert_eq!(restored.entries.len(), session.entries.len());
+        assert_eq!(restored.file_stem(), session.file_stem());
         assert_eq!(
             restored.working_dir.display().to_string(),
             session.working_dir.display().to_string()
@@ -851,4 +992,35 @@ mod tests {
         let restored = import_from_file(&path, Some("mypass")).unwrap();
         assert_eq!(restored.entries.len(), session.entries.len());
     }
+
+    #[test]
+    fn test_import_from_file_markdown() {
+        let session = sample_session();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.md");
+
+        std::fs::write(&path, session.export_markdown()).unwrap();
+        let restored = import_from_file(&path, None).unwrap();
+        assert_eq!(restored.entries.len(), session.entries.len());
+
+        std::fs::write(&path, "# Notes").unwrap();
+        assert!(import_from_file(&path, None).is_err());
+    }
+
+    #[test]
+    fn test_json_agent_state_roundtrip() {
+        let mut session = sample_session();
+        let plain = session.to_json().unwrap();
+        assert!(!plain.contains("\"agent\":"));
+
+        let mut state = AgentState::new("/home/user/project");
+        state.model = Some("gemini-2.5-flash".into());
+        state.record_user("Fix the bug in main.rs");
+        state.record_reply("Done.");
+        session.agent_state = Some(state.clone());
+
+        let restored = SessionLog::from_json(&session.to_json().unwrap()).unwrap();
+        assert_eq!(restored.agent_state, Some(state));
+        assert!(SessionLog::from_json(&plain).unwrap().agent_state.is_none());
+    }
 }
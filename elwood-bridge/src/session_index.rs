@@ -0,0 +1,744 @@
+//! Full-text index over saved sessions, for `/sessions search`.
+//!
+//! Every session that was exported (`/export`, markdown) or auto-saved when
+//! its pane closed (JSON) lives in `~/.elwood/sessions/`. The index keeps, per
+//! file, a term-frequency table built from user prompts, agent replies,
+//! commands, tool names and file paths, plus enough metadata to list a hit
+//! without reopening the file. It is stored in `~/.elwood/session_index.json`
+//! and refreshed incrementally: only files whose size or mtime changed are
+//! re-read.
+//!
+//! Queries AND their terms and rank by tf·idf; the last term also matches as
+//! a prefix so results show up while a word is still being typed. Matches are
+//! shown in the [`SessionSearch`] overlay, from which the pane opens, resumes
+//! or re-exports the selected session.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::session_export;
+use crate::session_log::{EntryType, SessionLog};
+use crate::theme;
+
+/// Term weight per source field: a word the user typed says more about a
+/// session than one the agent happened to write.
+const WEIGHT_USER: u32 = 3;
+const WEIGHT_COMMAND: u32 = 2;
+const WEIGHT_TOOL: u32 = 2;
+const WEIGHT_FILE: u32 = 2;
+const WEIGHT_AGENT: u32 = 1;
+
+/// User prompts kept per session for result snippets.
+const MAX_PROMPTS: usize = 20;
+const PROMPT_CHARS: usize = 160;
+/// Distinct commands, tools and files kept per session.
+const MAX_LISTED: usize = 50;
+
+/// Results listed by `/sessions`.
+pub const SEARCH_LIMIT: usize = 50;
+
+const OVERLAY_MAX_HITS: usize = 8;
+const OVERLAY_WIDTH: usize = 100;
+const OVERLAY_TOP_OFFSET: usize = 3;
+
+/// One indexed session file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedSession {
+    pub path: PathBuf,
+    /// Modification time (seconds since the epoch) when indexed.
+    pub modified: u64,
+    /// File size in bytes when indexed.
+    pub size: u64,
+    pub started_at: String,
+    pub working_dir: String,
+    /// First line of the first user prompt.
+    pub title: String,
+    /// First lines of user prompts, for snippets.
+    pub prompts: Vec<String>,
+    pub commands: Vec<String>,
+    pub tools: Vec<String>,
+    pub files: Vec<String>,
+    /// Weighted term frequencies.
+    pub terms: BTreeMap<String, u32>,
+}
+
+impl IndexedSession {
+    /// Build the index entry for a parsed session.
+    pub fn from_log(path: PathBuf, modified: u64, size: u64, log: &SessionLog) -> Self {
+        let mut session = IndexedSession {
+            path,
+            modified,
+            size,
+            started_at: log.started_at.clone(),
+            working_dir: log.working_dir.display().to_string(),
+            title: String::new(),
+            prompts: Vec::new(),
+            commands: Vec::new(),
+            tools: Vec::new(),
+            files: Vec::new(),
+            terms: BTreeMap::new(),
+        };
+
+        // Agent replies are logged per streamed chunk; join consecutive
+        // chunks so words split across them are indexed whole.
+        let mut reply = String::new();
+        for entry in &log.entries {
+            if entry.entry_type == EntryType::Agent {
+                reply.push_str(&entry.content);
+                continue;
+            }
+            session.add_terms(&reply, WEIGHT_AGENT);
+            reply.clear();
+
+            match entry.entry_type {
+                EntryType::User => {
+                    let first = entry.content.lines().next().unwrap_or("").trim();
+                    if session.title.is_empty() {
+                        session.title = first.chars().take(PROMPT_CHARS).collect();
+                    }
+                    if session.prompts.len() < MAX_PROMPTS && !first.is_empty() {
+                        session
+                            .prompts
+                            .push(first.chars().take(PROMPT_CHARS).collect());
+                    }
+                    session.add_terms(&entry.content, WEIGHT_USER);
+                    session.add_files(&entry.content);
+                }
+                EntryType::Command => {
+                    push_unique(&mut session.commands, entry.content.trim());
+                    session.add_terms(&entry.content, WEIGHT_COMMAND);
+                    session.add_files(&entry.content);
+                }
+                EntryType::Tool => {
+                    let (name, detail) = entry
+                        .content
+                        .split_once(": ")
+                        .unwrap_or((&entry.content, ""));
+                    if name != "ToolEnd" {
+                        push_unique(&mut session.tools, name);
+                        session.add_terms(name, WEIGHT_TOOL);
+                        session.add_files(detail);
+                    }
+                }
+                EntryType::Agent | EntryType::CommandOutput | EntryType::System => {}
+            }
+        }
+        session.add_terms(&reply, WEIGHT_AGENT);
+        session.add_files(&reply);
+
+        session
+    }
+
+    fn add_terms(&mut self, text: &str, weight: u32) {
+        for term in tokenize(text) {
+            *self.terms.entry(term).or_insert(0) += weight;
+        }
+    }
+
+    fn add_files(&mut self, text: &str) {
+        for file in file_paths(text) {
+            self.add_terms(&file, WEIGHT_FILE);
+            push_unique(&mut self.files, &file);
+        }
+    }
+
+    /// The prompt (or file/command) that best shows why `terms` matched.
+    fn snippet(&self, terms: &[String]) -> String {
+        let mentions = |text: &str| {
+            let words = tokenize(text);
+            terms
+                .iter()
+                .any(|t| words.iter().any(|w| w.starts_with(t.as_str())))
+        };
+        self.prompts
+            .iter()
+            .chain(&self.commands)
+            .chain(&self.files)
+            .find(|text| mentions(text))
+            .unwrap_or(&self.title)
+            .clone()
+    }
+}
+
+/// A search result.
+#[derive(Debug, Clone)]
+pub struct SessionHit {
+    pub path: PathBuf,
+    pub started_at: String,
+    pub working_dir: String,
+    pub title: String,
+    pub snippet: String,
+    pub score: f64,
+}
+
+/// The persisted index over a sessions directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionIndex {
+    /// Indexed sessions, sorted by path.
+    pub sessions: Vec<IndexedSession>,
+}
+
+impl SessionIndex {
+    /// Read an index file; a missing file is an empty index.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => {
+                serde_json::from_str(&json).with_context(|| format!("parsing {}", path.display()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("reading {}", path.display())),
+        }
+    }
+
+    /// Write the index atomically (temp file + rename).
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("creating {}", parent.display()))?;
+        }
+        let json = serde_json::to_string(self)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).with_context(|| format!("writing {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("renaming to {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// Bring the index up to date with the session files in `dir`.
+    ///
+    /// Re-reads only new or changed files and forgets deleted ones. A
+    /// markdown export is skipped when the same session was also saved as
+    /// JSON, which is lossless. Files that aren't Elwood sessions are
+    /// ignored. Returns the number of entries added, updated or removed.
+    pub fn refresh(&mut self, dir: &Path) -> anyhow::Result<usize> {
+        let read_dir = match std::fs::read_dir(dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let removed = self.sessions.len();
+                self.sessions.clear();
+                return Ok(removed);
+            }
+            Err(e) => return Err(e).with_context(|| format!("reading {}", dir.display())),
+        };
+
+        let mut files: Vec<(PathBuf, u64, u64)> = Vec::new();
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            if ext != "json" && ext != "md" {
+                continue;
+            }
+            if ext == "md" && path.with_extension("json").exists() {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else { continue };
+            if !meta.is_file() {
+                continue;
+            }
+            let modified = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_secs());
+            files.push((path, modified, meta.len()));
+        }
+        files.sort();
+
+        let mut previous: HashMap<PathBuf, IndexedSession> = self
+            .sessions
+            .drain(..)
+            .map(|s| (s.path.clone(), s))
+            .collect();
+        let mut changed = 0;
+        for (path, modified, size) in files {
+            match previous.remove(&path) {
+                Some(s) if s.modified == modified && s.size == size => {
+                    self.sessions.push(s);
+                }
+                _ => {
+                    if let Ok(log) = load_session(&path) {
+                        self.sessions
+                            .push(IndexedSession::from_log(path, modified, size, &log));
+                        changed += 1;
+                    }
+                }
+            }
+        }
+        Ok(changed + previous.len())
+    }
+
+    /// Sessions containing every term of `query`, best match first.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SessionHit> {
+        let terms = tokenize(query);
+        if terms.is_empty() {
+            return self.recent(limit);
+        }
+        let last = terms.len() - 1;
+        let total = self.sessions.len() as f64;
+
+        // Weighted frequency of query term `i` in a session; the last term
+        // also counts words it is a prefix of.
+        let frequency = |session: &IndexedSession, i: usize| -> u32 {
+            let term = &terms[i];
+            if i == last {
+                session
+                    .terms
+                    .range(term.clone()..)
+                    .take_while(|(word, _)| word.starts_with(term.as_str()))
+                    .map(|(_, n)| *n)
+                    .sum()
+            } else {
+                session.terms.get(term).copied().unwrap_or(0)
+            }
+        };
+        let idf: Vec<f64> = (0..terms.len())
+            .map(|i| {
+                let df = self.sessions.iter().filter(|s| frequency(s, i) > 0).count() as f64;
+                (1.0 + total / df.max(1.0)).ln()
+            })
+            .collect();
+
+        let mut hits: Vec<(SessionHit, u64)> = Vec::new();
+        for session in &self.sessions {
+            let mut score = 0.0;
+            let mut matched = true;
+            for (i, weight) in idf.iter().enumerate() {
+                let n = frequency(session, i);
+                if n == 0 {
+                    matched = false;
+                    break;
+                }
+                score += (1.0 + f64::from(n).ln()) * weight;
+            }
+            if matched {
+                let mut hit = hit_for(session);
+                hit.snippet = session.snippet(&terms);
+                hit.score = score;
+                hits.push((hit, session.modified));
+            }
+        }
+        hits.sort_by(|(a, a_mod), (b, b_mod)| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b_mod.cmp(a_mod))
+        });
+        hits.into_iter().take(limit).map(|(hit, _)| hit).collect()
+    }
+
+    /// The most recently saved sessions.
+    pub fn recent(&self, limit: usize) -> Vec<SessionHit> {
+        let mut sessions: Vec<&IndexedSession> = self.sessions.iter().collect();
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.modified));
+        sessions.into_iter().take(limit).map(hit_for).collect()
+    }
+}
+
+fn hit_for(session: &IndexedSession) -> SessionHit {
+    SessionHit {
+        path: session.path.clone(),
+        started_at: session.started_at.clone(),
+        working_dir: session.working_dir.clone(),
+        title: session.title.clone(),
+        snippet: session.title.clone(),
+        score: 0.0,
+    }
+}
+
+/// Default location of the index: `~/.elwood/session_index.json`.
+pub fn default_index_path() -> PathBuf {
+    dirs_next::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".elwood")
+        .join("session_index.json")
+}
+
+/// Read a saved session, markdown or JSON.
+pub fn load_session(path: &Path) -> Result<SessionLog, session_export::ShareError> {
+    session_export::import_from_file(path, None)
+}
+
+/// Lowercased words of at least two characters.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| word.chars().count() >= 2)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Words in `text` that look like file paths: containing a `/`, or a
+/// `name.ext` with a short alphanumeric extension. URLs are skipped.
+fn file_paths(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| "\"'`()[]{}<>,;:".contains(c)))
+        .filter(|word| !word.contains("://"))
+        .filter(|word| {
+            if word.contains('/') {
+                return word.chars().any(char::is_alphanumeric);
+            }
+            match word.rsplit_once('.') {
+                Some((name, ext)) => {
+                    !name.is_empty()
+                        && (1..=5).contains(&ext.len())
+                        && ext.chars().all(|c| c.is_ascii_alphanumeric())
+                        && ext.chars().any(|c| c.is_ascii_alphabetic())
+                }
+                None => false,
+            }
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+fn push_unique(list: &mut Vec<String>, item: &str) {
+    if !item.is_empty() && list.len() < MAX_LISTED && !list.iter().any(|i| i == item) {
+        list.push(item.to_string());
+    }
+}
+
+/// Overlay listing search results.
+#[derive(Debug, Default)]
+pub struct SessionSearch {
+    open: bool,
+    query: String,
+    hits: Vec<SessionHit>,
+    selected: usize,
+}
+
+impl SessionSearch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show `hits` for `query` (empty for the recent-sessions list).
+    pub fn show(&mut self, query: &str, hits: Vec<SessionHit>) {
+        self.query = query.to_string();
+        self.hits = hits;
+        self.selected = 0;
+        self.open = true;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn hits(&self) -> &[SessionHit] {
+        &self.hits
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.hits.is_empty() {
+            self.selected = (self.selected + 1) % self.hits.len();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.hits.is_empty() {
+            self.selected = (self.selected + self.hits.len() - 1) % self.hits.len();
+        }
+    }
+
+    pub fn selected(&self) -> Option<&SessionHit> {
+        self.hits.get(self.selected)
+    }
+
+    /// Render the overlay as an ANSI string positioned over the chat area.
+    pub fn render(&self, screen_width: u16) -> String {
+        if !self.open {
+            return String::new();
+        }
+        let t = theme::active();
+        let r = t.reset();
+        let border = t.ansi_fg(t.accent);
+        let text_fg = t.ansi_fg(t.fg_primary);
+        let muted = t.ansi_fg(t.fg_muted);
+        let sel_bg = t.ansi_bg(t.bg_tertiary);
+        let bold = t.bold();
+
+        let width = OVERLAY_WIDTH
+            .min((screen_width as usize).saturating_sub(4))
+            .max(20);
+        let left = (screen_width as usize).saturating_sub(width) / 2 + 1;
+        let inner = width.saturating_sub(2);
+        let mut row = OVERLAY_TOP_OFFSET;
+
+        let mut out = String::with_capacity(4096);
+        out.push_str("\x1b[s\x1b[?25l");
+
+        let count = self.hits.len();
+        let plural = if count == 1 { "" } else { "s" };
+        let title = if self.query.is_empty() {
+            format!(" Recent sessions ({count}) ")
+        } else {
+            format!(
+                " Sessions matching \"{}\": {count} result{plural} ",
+                self.query
+            )
+        };
+        let title: String = title.chars().take(inner.saturating_sub(2)).collect();
+        let fill = "\u{2500}".repeat(inner.saturating_sub(title.chars().count() + 1));
+        out.push_str(&format!(
+            "\x1b[{row};{left}H{border}\u{256D}\u{2500}{bold}{title}{r}{border}{fill}\u{256E}{r}"
+        ));
+        row += 1;
+
+        let line = |out: &mut String, row: &mut usize, text: &str, style: &str, selected: bool| {
+            let shown: String = text.chars().take(inner.saturating_sub(2)).collect();
+            let pad = inner.saturating_sub(shown.chars().count() + 1);
+            let (bg, marker) = if selected {
+                (sel_bg.as_str(), "\u{25B8}")
+            } else {
+                ("", " ")
+            };
+            out.push_str(&format!(
+                "\x1b[{row};{left}H{border}\u{2502}{r}{bg}{marker}{style}{shown}{}{r}{border}\u{2502}{r}",
+                " ".repeat(pad),
+            ));
+            *row += 1;
+        };
+
+        if self.hits.is_empty() {
+            line(&mut out, &mut row, "No matching sessions", &muted, false);
+        } else {
+            let start = self
+                .selected
+                .saturating_sub(OVERLAY_MAX_HITS / 2)
+                .min(count.saturating_sub(OVERLAY_MAX_HITS));
+            for (i, hit) in self
+                .hits
+                .iter()
+                .enumerate()
+                .skip(start)
+                .take(OVERLAY_MAX_HITS)
+            {
+                let selected = i == self.selected;
+                let date: String = hit.started_at.replace('T', " ").chars().take(16).collect();
+                let title = if hit.title.is_empty() {
+                    "(no prompts)"
+                } else {
+                    &hit.title
+                };
+                line(
+                    &mut out,
+                    &mut row,
+                    &format!("{date}  {title}"),
+                    &text_fg,
+                    selected,
+                );
+                let detail = if hit.snippet == hit.title {
+                    format!("    {}", hit.working_dir)
+                } else {
+                    format!("    {} \u{00B7} {}", hit.working_dir, hit.snippet)
+                };
+                line(&mut out, &mut row, &detail, &muted, selected);
+            }
+        }
+
+        let bottom = "\u{2500}".repeat(inner);
+        out.push_str(&format!(
+            "\x1b[{row};{left}H{border}\u{2570}{bottom}\u{256F}{r}"
+        ));
+        row += 1;
+        out.push_str(&format!(
+            "\x1b[{row};{left}H{muted}  \u{2191}\u{2193} select  \u{23CE} open  r resume  e re-export  Esc close{r}"
+        ));
+
+        out.push_str("\x1b[?25h\x1b[u");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(prompt: &str, command: &str, reply: &[&str]) -> SessionLog {
+        let mut log = SessionLog::new(PathBuf::from("/home/user/project"));
+        log.log_user(prompt);
+        for chunk in reply {
+            log.log_agent(chunk);
+        }
+        log.log_tool("ReadFile", "started: src/parser.rs");
+        log.log_tool("ToolEnd", "OK: 120 lines");
+        log.log_command(command);
+        log
+    }
+
+    fn indexed(name: &str, log: &SessionLog, modified: u64) -> IndexedSession {
+        IndexedSession::from_log(PathBuf::from(name), modified, 0, log)
+    }
+
+    #[test]
+    fn test_index_fields() {
+        let log = session(
+            "Fix the tokenizer\nit panics",
+            "cargo test",
+            &["Look", "ing now"],
+        );
+        let s = indexed("a.json", &log, 1);
+        assert_eq!(s.title, "Fix the tokenizer");
+        assert_eq!(s.commands, vec!["cargo test"]);
+        assert_eq!(s.tools, vec!["ReadFile"]);
+        assert_eq!(s.files, vec!["src/parser.rs"]);
+        // Streamed chunks are joined before tokenizing
+        assert!(s.terms.contains_key("looking"));
+        assert!(!s.terms.contains_key("ing"));
+        assert!(!s.terms.contains_key("toolend"));
+        // User words outweigh agent words
+        assert_eq!(s.terms["tokenizer"], WEIGHT_USER);
+        assert_eq!(s.terms["looking"], WEIGHT_AGENT);
+    }
+
+    #[test]
+    fn test_search_requires_all_terms() {
+        let index = SessionIndex {
+            sessions: vec![
+                indexed("a.json", &session("fix tokenizer", "cargo test", &[]), 1),
+                indexed("b.json", &session("fix renderer", "cargo build", &[]), 2),
+            ],
+        };
+        let hits = index.search("fix tokenizer", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].path, PathBuf::from("a.json"));
+        assert_eq!(index.search("fix", 10).len(), 2);
+        assert!(index.search("missing", 10).is_empty());
+    }
+
+    #[test]
+    fn test_search_prefix_matches_last_term() {
+        let index = SessionIndex {
+            sessions: vec![indexed("a.json", &session("fix tokenizer", "ls", &[]), 1)],
+        };
+        assert_eq!(index.search("tok", 10).len(), 1);
+        // Only the last term is a prefix
+        assert!(index.search("tok fix", 10).is_empty());
+    }
+
+    #[test]
+    fn test_search_ranks_by_weight() {
+        let index = SessionIndex {
+            sessions: vec![
+                indexed("agent.json", &session("hello", "ls", &["the lexer"]), 2),
+                indexed("user.json", &session("rewrite the lexer", "ls", &[]), 1),
+            ],
+        };
+        let hits = index.search("lexer", 10);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].path, PathBuf::from("user.json"));
+    }
+
+    #[test]
+    fn test_search_matches_commands_tools_and_files() {
+        let index = SessionIndex {
+            sessions: vec![indexed(
+                "a.json",
+                &session("hello", "kubectl get pods", &[]),
+                1,
+            )],
+        };
+        assert_eq!(index.search("kubectl", 10)[0].snippet, "kubectl get pods");
+        assert_eq!(index.search("readfile", 10).len(), 1);
+        assert_eq!(index.search("parser.rs", 10).len(), 1);
+    }
+
+    #[test]
+    fn test_empty_query_lists_recent() {
+        let index = SessionIndex {
+            sessions: vec![
+                indexed("old.json", &session("a", "ls", &[]), 1),
+                indexed("new.json", &session("b", "ls", &[]), 5),
+            ],
+        };
+        let hits = index.search("  ", 1);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].path, PathBuf::from("new.json"));
+    }
+
+    #[test]
+    fn test_refresh_is_incremental() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = session("first session", "ls", &[]);
+        let json = first.autosave(dir.path()).unwrap().unwrap();
+
+        // A markdown export of the same session is shadowed by its JSON save
+        std::fs::write(json.with_extension("md"), first.export_markdown()).unwrap();
+        // Another markdown-only export is indexed
+        let mut second = session("second session", "pwd", &[]);
+        second.started_at = "2020-01-01T00:00:00".into();
+        std::fs::write(dir.path().join("other.md"), second.export_markdown()).unwrap();
+        // Unrelated files are ignored
+        std::fs::write(dir.path().join("notes.md"), "# Notes").unwrap();
+        std::fs::write(dir.path().join("data.txt"), "first").unwrap();
+
+        let mut index = SessionIndex::default();
+        assert_eq!(index.refresh(dir.path()).unwrap(), 2);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.refresh(dir.path()).unwrap(), 0);
+        assert_eq!(index.search("second", 10).len(), 1);
+
+        std::fs::remove_file(dir.path().join("other.md")).unwrap();
+        assert_eq!(index.refresh(dir.path()).unwrap(), 1);
+        assert!(index.search("second", 10).is_empty());
+    }
+
+    #[test]
+    fn test_index_save_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.json");
+        assert!(SessionIndex::load(&path).unwrap().is_empty());
+
+        let index = SessionIndex {
+            sessions: vec![indexed("a.json", &session("fix tokenizer", "ls", &[]), 1)],
+        };
+        index.save(&path).unwrap();
+        let loaded = SessionIndex::load(&path).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.search("tokenizer", 10).len(), 1);
+    }
+
+    #[test]
+    fn test_file_paths() {
+        let found = file_paths("edit `src/main.rs` and Cargo.toml, see https://x.io/a v1.2 /");
+        assert_eq!(found, vec!["src/main.rs", "Cargo.toml"]);
+    }
+
+    #[test]
+    fn test_overlay_navigation_and_render() {
+        let index = SessionIndex {
+            sessions: vec![
+                indexed("a.json", &session("fix tokenizer", "ls", &[]), 1),
+                indexed("b.json", &session("fix renderer", "ls", &[]), 2),
+            ],
+        };
+        let mut overlay = SessionSearch::new();
+        assert!(overlay.render(120).is_empty());
+        overlay.show("fix", index.search("fix", 10));
+        assert!(overlay.is_open());
+        overlay.select_prev();
+        assert_eq!(overlay.selected().unwrap().path, overlay.hits()[1].path);
+        overlay.select_next();
+        assert_eq!(overlay.selected().unwrap().path, overlay.hits()[0].path);
+
+        let out = overlay.render(120);
+        assert!(out.contains("Sessions matching \"fix\": 2 results"));
+        assert!(out.contains("fix tokenizer"));
+        assert!(out.contains("r resume"));
+        overlay.close();
+        assert!(!overlay.is_open());
+    }
+}
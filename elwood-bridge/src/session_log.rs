@@ -4,6 +4,9 @@
 //! a session, then exports them as a readable markdown document.
 
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::session_resume::AgentState;
 
 /// Type of session log entry.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -40,8 +43,14 @@ pub struct SessionLog {
     pub entries: Vec<SessionEntry>,
     /// When the session started (ISO 8601).
     pub started_at: String,
+    /// Tells apart sessions started in the same second, by this or another
+    /// process. Empty for sessions loaded from files that predate it.
+    pub id: String,
     /// Working directory at session start.
     pub working_dir: PathBuf,
+    /// Agent conversation state, attached when the pane saves the session
+    /// so it can be resumed (JSON and shared exports only).
+    pub agent_state: Option<AgentState>,
 }
 
 impl SessionLog {
@@ -50,7 +59,9 @@ impl SessionLog {
         Self {
             entries: Vec::new(),
             started_at: now_iso8601(),
+            id: next_session_id(),
             working_dir,
+            agent_state: None,
         }
     }
 
@@ -156,26 +167,196 @@ impl SessionLog {
         md
     }
 
+    /// Rebuild a session from the output of [`SessionLog::export_markdown`].
+    ///
+    /// Returns `None` if the text doesn't start with the session header.
+    /// Reply text containing lines that look like entry headings is split
+    /// at those lines, so the round trip is best effort.
+    pub fn from_markdown(markdown: &str) -> Option<Self> {
+        let mut lines = markdown.lines();
+        let started_at = lines
+            .next()?
+            .strip_prefix("# Elwood Session — ")?
+            .trim()
+            .to_string();
+        let mut log = SessionLog {
+            entries: Vec::new(),
+            started_at,
+            id: String::new(),
+            working_dir: PathBuf::new(),
+            agent_state: None,
+        };
+
+        // User/agent entry whose text is still being collected.
+        let mut current: Option<SessionEntry> = None;
+        // Timestamp of a command heading waiting for its ```bash fence.
+        let mut pending_command: Option<String> = None;
+        // Open code fence: the entry it becomes and the lines seen so far.
+        let mut fence: Option<(EntryType, String, Vec<&str>)> = None;
+
+        for line in lines {
+            if let Some((entry_type, timestamp, body)) = fence.as_mut() {
+                if line == "```" {
+                    let mut content = body.join("\n");
+                    if *entry_type == EntryType::Command {
+                        content = content.strip_prefix("$ ").unwrap_or(&content).to_string();
+                    }
+                    log.entries.push(SessionEntry {
+                        timestamp: std::mem::take(timestamp),
+                        entry_type: entry_type.clone(),
+                        content,
+                    });
+                    fence = None;
+                } else {
+                    body.push(line);
+                }
+                continue;
+            }
+
+            if let Some((entry_type, timestamp)) = parse_heading(line) {
+                flush_entry(&mut log, current.take());
+                if entry_type == EntryType::Command {
+                    pending_command = Some(timestamp);
+                } else {
+                    current = Some(SessionEntry {
+                        timestamp,
+                        entry_type,
+                        content: String::new(),
+                    });
+                }
+                continue;
+            }
+
+            if let Some(entry) = parse_quote(line) {
+                flush_entry(&mut log, current.take());
+                log.entries.push(entry);
+                continue;
+            }
+
+            match current.as_mut() {
+                Some(entry) => {
+                    if !entry.content.is_empty() || !line.is_empty() {
+                        entry.content.push_str(line);
+                        entry.content.push('\n');
+                    }
+                }
+                None if line == "```bash" && pending_command.is_some() => {
+                    fence = Some((
+                        EntryType::Command,
+                        pending_command.take().unwrap_or_default(),
+                        Vec::new(),
+                    ));
+                }
+                None if line == "```" => {
+                    // Output fences follow the command they belong to.
+                    let timestamp = log
+                        .entries
+                        .last()
+                        .filter(|e| e.entry_type == EntryType::Command)
+                        .map(|e| e.timestamp.clone());
+                    if let Some(timestamp) = timestamp {
+                        fence = Some((EntryType::CommandOutput, timestamp, Vec::new()));
+                    }
+                }
+                None => {
+                    if let Some(dir) = line
+                        .strip_prefix("**Working directory:** `")
+                        .and_then(|rest| rest.strip_suffix('`'))
+                    {
+                        log.working_dir = PathBuf::from(dir);
+                    }
+                }
+            }
+        }
+        flush_entry(&mut log, current);
+
+        Some(log)
+    }
+
+    /// File name stem shared by the markdown and JSON exports; also
+    /// identifies the session elsewhere (e.g. its block store).
+    pub fn file_stem(&self) -> String {
+        let started = self.started_at.replace(':', "-").replace(' ', "_");
+        if self.id.is_empty() {
+            format!("elwood-{started}")
+        } else {
+            format!("elwood-{started}-{}", self.id)
+        }
+    }
+
     /// Export the session to a file.
     ///
-    /// Writes to `~/.elwood/sessions/elwood-{timestamp}.md`.
+    /// Writes to `~/.elwood/sessions/{file_stem}.md`.
     /// Creates the directory if it doesn't exist.
     /// Returns the path of the written file.
     pub fn export_to_file(&self) -> std::io::Result<PathBuf> {
         let dir = default_session_dir();
         std::fs::create_dir_all(&dir)?;
 
-        let filename = format!(
-            "elwood-{}.md",
-            self.started_at.replace(':', "-").replace(' ', "_")
-        );
-        let path = dir.join(filename);
+        let path = dir.join(format!("{}.md", self.file_stem()));
 
         let markdown = self.export_markdown();
         std::fs::write(&path, markdown)?;
 
         Ok(path)
     }
+
+    /// Save the session as `{file_stem}.json` in `dir`, overwriting
+    /// any earlier autosave of the same session.
+    ///
+    /// Sessions without entries are not written and return `Ok(None)`.
+    pub fn autosave(&self, dir: &Path) -> std::io::Result<Option<PathBuf>> {
+        if self.entries.is_empty() {
+            return Ok(None);
+        }
+        std::fs::create_dir_all(dir)?;
+
+        let path = dir.join(format!("{}.json", self.file_stem()));
+        let json = self.to_json().map_err(std::io::Error::other)?;
+        std::fs::write(&path, json)?;
+
+        Ok(Some(path))
+    }
+}
+
+/// Parse a `### You (ts)`, `### Elwood (ts)` or `#### Command (ts)` heading.
+fn parse_heading(line: &str) -> Option<(EntryType, String)> {
+    let (entry_type, rest) = if let Some(rest) = line.strip_prefix("### You (") {
+        (EntryType::User, rest)
+    } else if let Some(rest) = line.strip_prefix("### Elwood (") {
+        (EntryType::Agent, rest)
+    } else if let Some(rest) = line.strip_prefix("#### Command (") {
+        (EntryType::Command, rest)
+    } else {
+        return None;
+    };
+    let timestamp = rest.strip_suffix(')')?;
+    Some((entry_type, timestamp.to_string()))
+}
+
+/// Parse a `> **Tool** (ts): …` or `> _System (ts): …_` line.
+fn parse_quote(line: &str) -> Option<SessionEntry> {
+    let (entry_type, rest) = if let Some(rest) = line.strip_prefix("> **Tool** (") {
+        (EntryType::Tool, rest)
+    } else if let Some(rest) = line.strip_prefix("> _System (") {
+        (EntryType::System, rest.strip_suffix('_')?)
+    } else {
+        return None;
+    };
+    let (timestamp, content) = rest.split_once("): ")?;
+    Some(SessionEntry {
+        timestamp: timestamp.to_string(),
+        entry_type,
+        content: content.to_string(),
+    })
+}
+
+/// Finish a user/agent entry collected by [`SessionLog::from_markdown`].
+fn flush_entry(log: &mut SessionLog, entry: Option<SessionEntry>) {
+    if let Some(mut entry) = entry {
+        entry.content.truncate(entry.content.trim_end().len());
+        log.entries.push(entry);
+    }
 }
 
 /// Default directory for session exports.
@@ -193,6 +374,13 @@ pub fn session_export_dir(override_dir: Option<&Path>) -> PathBuf {
         .unwrap_or_else(default_session_dir)
 }
 
+/// `{pid}-{n}`: unique among the sessions of all running processes.
+fn next_session_id() -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    format!("{}-{n}", std::process::id())
+}
+
 /// Current time in ISO 8601 format.
 fn now_iso8601() -> String {
     chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string()
@@ -304,4 +492,66 @@ mod tests {
         let dir = session_export_dir(Some(Path::new("/custom/path")));
         assert_eq!(dir, PathBuf::from("/custom/path"));
     }
+
+    #[test]
+    fn test_from_markdown_roundtrip() {
+        let mut log = SessionLog::new(PathBuf::from("/home/user/project"));
+        log.log_user("Fix the bug\n\nin main.rs");
+        log.log_agent("Sure.\n\n```rust\nfn main() {}\n```");
+        log.log_tool("ReadFile", "src/main.rs");
+        log.log_command("cargo test");
+        log.log_command_output("test result: ok\n", "", Some(0));
+        log.log_system("Session started");
+
+        let parsed = SessionLog::from_markdown(&log.export_markdown()).unwrap();
+        assert_eq!(parsed.started_at, log.started_at);
+        assert_eq!(parsed.working_dir, log.working_dir);
+        assert_eq!(parsed.entries.len(), log.entries.len());
+        for (a, b) in parsed.entries.iter().zip(&log.entries) {
+            assert_eq!(a.entry_type, b.entry_type);
+            assert_eq!(a.content, b.content);
+            assert_eq!(a.timestamp, b.timestamp);
+        }
+    }
+
+    #[test]
+    fn test_from_markdown_rejects_other_documents() {
+        assert!(SessionLog::from_markdown("# Notes\n\nhello").is_none());
+        assert!(SessionLog::from_markdown("").is_none());
+    }
+
+    #[test]
+    fn test_autosave_writes_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut log = SessionLog::new(PathBuf::from("/tmp"));
+        assert!(log.autosave(dir.path()).unwrap().is_none());
+
+        log.log_user("hello");
+        let path = log.autosave(dir.path()).unwrap().unwrap();
+        assert_eq!(path.extension().unwrap(), "json");
+        let text = std::fs::read_to_string(&path).unwrap();
+        let restored = SessionLog::from_json(&text).unwrap();
+        assert_eq!(restored.entries[0].content, "hello");
+
+        // Saving again overwrites the same file.
+        log.log_agent("hi");
+        assert_eq!(log.autosave(dir.path()).unwrap().unwrap(), path);
+    }
+
+    #[test]
+    fn test_autosave_sessions_started_together() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut first = SessionLog::new(PathBuf::from("/tmp"));
+        let mut second = SessionLog::new(PathBuf::from("/tmp"));
+        second.started_at = first.started_at.clone();
+        first.log_user("first");
+        second.log_user("second");
+
+        let first_path = first.autosave(dir.path()).unwrap().unwrap();
+        let second_path = second.autosave(dir.path()).unwrap().unwrap();
+        assert_ne!(first_path, second_path);
+        let text = std::fs::read_to_string(&first_path).unwrap();
+        let restored = SessionLog::from_json(&text).unwrap();
+        assert_eq!(restored.entries[0].content, "first");
+    }
 }
@@ -0,0 +1,357 @@
+//! Agent state saved with a session, for `/resume`.
+//!
+//! JSON exports and auto-saves carry an [`AgentState`] next to the log: the
+//! conversation as the pane sent and received it, the tool calls made along
+//! the way, the active model, the working directory and the plan being worked
+//! on. `/resume <file>` opens a fresh Elwood pane with a runtime of its own
+//! and hands it this state (see `ElwoodPane::resume_session`), so the agent
+//! picks up where it left off after a crash or on another machine.
+//!
+//! Sessions saved without agent state (markdown exports, older JSON files)
+//! are resumed from their log instead: user prompts and agent replies become
+//! the conversation and tool entries the tool history.
+
+use serde::{Deserialize, Serialize};
+
+use crate::plan_mode::{self, PlanDocument, PlanStatus};
+use crate::session_log::{EntryType, SessionLog};
+
+/// Who wrote a conversation message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+/// One message of the agent conversation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentMessage {
+    pub role: Role,
+    pub content: String,
+}
+
+/// A tool call the agent made.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    pub tool_id: String,
+    pub tool_name: String,
+    pub input_preview: String,
+    /// Result preview, once the call finished.
+    #[serde(default)]
+    pub output_preview: Option<String>,
+    #[serde(default)]
+    pub success: Option<bool>,
+    /// Index in [`AgentState::messages`] of the reply the call was made for.
+    pub message_index: usize,
+}
+
+/// The plan the session was working through.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedPlan {
+    pub id: String,
+    /// [`PlanDocument::to_markdown`] output, status included.
+    pub markdown: String,
+}
+
+/// Everything needed to continue an agent conversation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentState {
+    /// Active model name.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Working directory the agent worked in.
+    #[serde(default)]
+    pub working_dir: String,
+    #[serde(default)]
+    pub messages: Vec<AgentMessage>,
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCallRecord>,
+    #[serde(default)]
+    pub plan: Option<SavedPlan>,
+}
+
+impl AgentState {
+    /// Empty state for a conversation in `working_dir`.
+    pub fn new(working_dir: &str) -> Self {
+        Self {
+            working_dir: working_dir.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Rebuild the state from a session log that was saved without one.
+    pub fn from_log(log: &SessionLog) -> Self {
+        let mut state = Self::new(&log.working_dir.display().to_string());
+        for (n, entry) in log.entries.iter().enumerate() {
+            match entry.entry_type {
+                EntryType::User => state.record_user(&entry.content),
+                EntryType::Agent => state.record_reply(&entry.content),
+                EntryType::Tool => {
+                    let (name, detail) = entry
+                        .content
+                        .split_once(": ")
+                        .unwrap_or((&entry.content, ""));
+                    if name == "ToolEnd" {
+                        // "OK: preview" / "FAIL: preview", for the last open call
+                        let (status, preview) = detail.split_once(": ").unwrap_or((detail, ""));
+                        let open = state
+                            .tool_calls
+                            .iter()
+                            .rev()
+                            .find(|c| c.success.is_none())
+                            .map(|c| c.tool_id.clone());
+                        if let Some(tool_id) = open {
+                            state.record_tool_end(&tool_id, status == "OK", preview);
+                        }
+                    } else {
+                        let input = detail.strip_prefix("started: ").unwrap_or(detail);
+                        state.record_tool_start(&format!("log-{n}"), name, input);
+                    }
+                }
+                EntryType::Command | EntryType::CommandOutput | EntryType::System => {}
+            }
+        }
+        state
+    }
+
+    /// Forget the conversation: the runtime starts a new one.
+    pub fn reset(&mut self) {
+        self.messages.clear();
+        self.tool_calls.clear();
+    }
+
+    /// A message sent to the agent.
+    pub fn record_user(&mut self, content: &str) {
+        self.messages.push(AgentMessage {
+            role: Role::User,
+            content: content.to_string(),
+        });
+    }
+
+    /// Streamed reply text; consecutive chunks form one message.
+    pub fn record_reply(&mut self, delta: &str) {
+        match self.messages.last_mut() {
+            Some(last) if last.role == Role::Assistant => last.content.push_str(delta),
+            _ => self.messages.push(AgentMessage {
+                role: Role::Assistant,
+                content: delta.to_string(),
+            }),
+        }
+    }
+
+    /// A tool call started, as part of the reply in progress.
+    pub fn record_tool_start(&mut self, tool_id: &str, tool_name: &str, input_preview: &str) {
+        let message_index = match self.messages.last() {
+            Some(last) if last.role == Role::Assistant => self.messages.len() - 1,
+            _ => self.messages.len(),
+        };
+        self.tool_calls.push(ToolCallRecord {
+            tool_id: tool_id.to_string(),
+            tool_name: tool_name.to_string(),
+            input_preview: input_preview.to_string(),
+            output_preview: None,
+            success: None,
+            message_index,
+        });
+    }
+
+    /// A tool call finished.
+    pub fn record_tool_end(&mut self, tool_id: &str, success: bool, output_preview: &str) {
+        if let Some(call) = self
+            .tool_calls
+            .iter_mut()
+            .rev()
+            .find(|c| c.tool_id == tool_id && c.success.is_none())
+        {
+            call.success = Some(success);
+            call.output_preview = Some(output_preview.to_string());
+        }
+    }
+
+    /// Remember the plan being worked on (or forget it).
+    pub fn set_plan(&mut self, plan: Option<&PlanDocument>) {
+        self.plan = plan.map(|plan| SavedPlan {
+            id: plan.id.clone(),
+            markdown: plan.to_markdown(),
+        });
+    }
+
+    /// The saved plan, if it still has work left.
+    pub fn unfinished_plan(&self) -> Option<PlanDocument> {
+        let saved = self.plan.as_ref()?;
+        let plan = plan_mode::plan_from_markdown(&saved.id, &saved.markdown);
+        match plan.status {
+            PlanStatus::Completed | PlanStatus::Cancelled => None,
+            _ => Some(plan),
+        }
+    }
+
+    /// Tool calls made for the message at `index`.
+    pub fn tool_calls_for(&self, index: usize) -> impl Iterator<Item = &ToolCallRecord> {
+        self.tool_calls
+            .iter()
+            .filter(move |c| c.message_index == index)
+    }
+
+    /// The conversation to seed a runtime with.
+    ///
+    /// Replies note the tool calls made for them, since the previews are all
+    /// that was kept of the calls. The first message says where the session
+    /// ran, so the agent doesn't assume the new pane's directory.
+    pub fn restored_messages(&self) -> Vec<AgentMessage> {
+        let mut restored: Vec<AgentMessage> = Vec::with_capacity(self.messages.len());
+        for (i, message) in self.messages.iter().enumerate() {
+            let mut content = message.content.clone();
+            if message.role == Role::Assistant {
+                let calls: Vec<String> = self.tool_calls_for(i).map(describe_call).collect();
+                if !calls.is_empty() {
+                    content.push_str(&format!("\n\n[Tool calls: {}]", calls.join("; ")));
+                }
+            }
+            restored.push(AgentMessage {
+                role: message.role,
+                content,
+            });
+        }
+        if let Some(first) = restored.first_mut() {
+            if !self.working_dir.is_empty() {
+                first.content = format!(
+                    "[Resumed session; working directory: {}]\n{}",
+                    self.working_dir, first.content
+                );
+            }
+        }
+        restored
+    }
+}
+
+/// `Name(input) -> OK: output`, for a restored reply.
+fn describe_call(call: &ToolCallRecord) -> String {
+    let status = match call.success {
+        Some(true) => "OK",
+        Some(false) => "FAIL",
+        None => "interrupted",
+    };
+    match &call.output_preview {
+        Some(output) if !output.is_empty() => format!(
+            "{}({}) -> {status}: {output}",
+            call.tool_name, call.input_preview
+        ),
+        _ => format!("{}({}) -> {status}", call.tool_name, call.input_preview),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn recorded() -> AgentState {
+        let mut state = AgentState::new("/home/user/project");
+        state.record_user("fix the parser");
+        state.record_reply("Look");
+        state.record_tool_start("t1", "ReadFile", "src/parser.rs");
+        state.record_tool_end("t1", true, "120 lines");
+        state.record_reply("ing good now.");
+        state.record_user("thanks");
+        state
+    }
+
+    #[test]
+    fn test_record_conversation() {
+        let state = recorded();
+        assert_eq!(state.messages.len(), 3);
+        assert_eq!(state.messages[1].role, Role::Assistant);
+        assert_eq!(state.messages[1].content, "Looking good now.");
+        assert_eq!(state.tool_calls.len(), 1);
+        assert_eq!(state.tool_calls[0].message_index, 1);
+        assert_eq!(state.tool_calls[0].success, Some(true));
+    }
+
+    #[test]
+    fn test_tool_call_before_reply_text() {
+        let mut state = AgentState::new("/tmp");
+        state.record_user("list files");
+        state.record_tool_start("t1", "Bash", "ls");
+        state.record_reply("Here they are.");
+        assert_eq!(state.tool_calls_for(1).count(), 1);
+    }
+
+    #[test]
+    fn test_restored_messages() {
+        let restored = recorded().restored_messages();
+        assert_eq!(restored.len(), 3);
+        assert!(restored[0]
+            .content
+            .starts_with("[Resumed session; working directory: /home/user/project]\n"));
+        assert!(restored[0].content.ends_with("fix the parser"));
+        assert_eq!(
+            restored[1].content,
+            "Looking good now.\n\n[Tool calls: ReadFile(src/parser.rs) -> OK: 120 lines]"
+        );
+        assert_eq!(restored[2].content, "thanks");
+    }
+
+    #[test]
+    fn test_from_log() {
+        let mut log = SessionLog::new(PathBuf::from("/work"));
+        log.log_user("build it");
+        log.log_agent("On ");
+        log.log_agent("it.");
+        log.log_tool("Bash", "started: cargo build");
+        log.log_tool("ToolEnd", "FAIL: error[E0308]");
+        log.log_command("ls");
+
+        let state = AgentState::from_log(&log);
+        assert_eq!(state.working_dir, "/work");
+        assert_eq!(state.messages.len(), 2);
+        assert_eq!(state.messages[1].content, "On it.");
+        assert_eq!(state.tool_calls.len(), 1);
+        assert_eq!(state.tool_calls[0].tool_name, "Bash");
+        assert_eq!(state.tool_calls[0].input_preview, "cargo build");
+        assert_eq!(state.tool_calls[0].success, Some(false));
+        assert_eq!(
+            state.tool_calls[0].output_preview.as_deref(),
+            Some("error[E0308]")
+        );
+    }
+
+    #[test]
+    fn test_plan_roundtrip() {
+        let mut plan = PlanDocument::create("Refactor", "Split the module");
+        plan.steps.push(plan_mode::PlanStep {
+            description: "Move types".into(),
+            completed: true,
+            substeps: Vec::new(),
+        });
+        plan.steps.push(plan_mode::PlanStep {
+            description: "Move functions".into(),
+            completed: false,
+            substeps: Vec::new(),
+        });
+        plan.status = PlanStatus::InProgress;
+
+        let mut state = AgentState::default();
+        state.set_plan(Some(&plan));
+        let restored = state.unfinished_plan().unwrap();
+        assert_eq!(restored.id, plan.id);
+        assert_eq!(restored.status, PlanStatus::InProgress);
+        assert_eq!(restored.next_step_index(), Some(1));
+
+        plan.complete_step(1);
+        state.set_plan(Some(&plan));
+        assert!(state.unfinished_plan().is_none());
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let state = recorded();
+        let json = serde_json::to_string(&state).unwrap();
+        let parsed: AgentState = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, state);
+        // Fields added later default when missing
+        let minimal: AgentState = serde_json::from_str("{}").unwrap();
+        assert!(minimal.messages.is_empty());
+    }
+}
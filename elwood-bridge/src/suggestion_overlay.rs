@@ -192,6 +192,8 @@ mod tests {
             message: msg.to_string(),
             suggested_fix: fix.to_string(),
             source_file: None,
+            line: None,
+            column: None,
             auto_fixable: auto,
         }
     }
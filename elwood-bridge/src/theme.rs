@@ -4,6 +4,31 @@
 //! sequences. Two built-in themes ship by default: Tokyo Night (dark) and
 //! Tokyo Night Light.
 //!
+//! ## Choosing a theme
+//!
+//! `theme = "..."` in `~/.elwood/elwood.toml` selects one of:
+//!
+//! - a built-in theme (`tokyo_night`, `tokyo_night_light`)
+//! - `auto`: every role is derived from the WezTerm color scheme in use
+//!   (see [`ElwoodTheme::from_palette`])
+//! - the name of a file in `~/.elwood/themes/`, e.g. `gruvbox` for
+//!   `~/.elwood/themes/gruvbox.toml`:
+//!
+//! ```toml
+//! name = "Gruvbox"
+//! base = "auto"          # roles not listed below; defaults to tokyo_night
+//!
+//! [colors]
+//! accent = "#83a598"
+//! error = "#fb4934"
+//! ```
+//!
+//! The resolved theme is held in a process-wide slot ([`active`]) that the
+//! renderers (screen, block chrome, file browser, git views) read, mostly via
+//! [`Role`], and is re-resolved against the pane's palette when the WezTerm
+//! config reloads, the window's config overrides change, or `elwood.toml` /
+//! the theme file changes ([`reload_if_changed`]).
+//!
 //! ## Usage
 //!
 //! ```rust
@@ -13,6 +38,14 @@
 //! let header = format!("{}{}Header{}", theme.ansi_bg(theme.bg_primary), theme.ansi_bold_fg(theme.accent), theme.reset());
 //! ```
 
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use parking_lot::{Mutex, RwLock};
+use wezterm_term::color::ColorPalette;
+
 /// An RGB color triple.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Color {
@@ -37,6 +70,21 @@ impl Color {
         Self { r, g, b }
     }
 
+    /// Parse `#RRGGBB` (leading `#` optional). Returns `None` on malformed input.
+    pub fn parse_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim().trim_start_matches('#');
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Self::new(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// Relative luminance (0.0 = black, 1.0 = white), ignoring gamma.
+    pub fn luminance(self) -> f32 {
+        (0.2126 * self.r as f32 + 0.7152 * self.g as f32 + 0.0722 * self.b as f32) / 255.0
+    }
+
     /// Blend this color toward `other` by `amount` (0.0 = self, 1.0 = other).
     pub fn blend(self, other: Color, amount: f32) -> Color {
         let a = amount.clamp(0.0, 1.0);
@@ -58,7 +106,7 @@ impl Color {
 /// - **Semantic**: block-type-specific accent colors
 #[derive(Debug, Clone)]
 pub struct ElwoodTheme {
-    pub name: &'static str,
+    pub name: String,
 
     // ── Surfaces ─────────────────────────────────────────────────────
     /// Main background.
@@ -169,7 +217,7 @@ impl ElwoodTheme {
     /// roles for agent, tool, user, and error blocks.
     pub fn tokyo_night() -> Self {
         Self {
-            name: "Tokyo Night",
+            name: "Tokyo Night".into(),
 
             bg_primary: Color::new(26, 27, 38),       // #1A1B26
             bg_secondary: Color::new(36, 40, 59),      // #24283B
@@ -198,7 +246,7 @@ impl ElwoodTheme {
     /// Tokyo Night Light theme.
     pub fn tokyo_night_light() -> Self {
         Self {
-            name: "Tokyo Night Light",
+            name: "Tokyo Night Light".into(),
 
             bg_primary: Color::new(213, 214, 219),     // #D5D6DB
             bg_secondary: Color::new(224, 225, 230),    // #E0E1E6
@@ -226,12 +274,328 @@ impl ElwoodTheme {
 
     /// Get a theme by name. Falls back to tokyo_night for unknown names.
     pub fn by_name(name: &str) -> Self {
+        Self::builtin(name).unwrap_or_else(Self::tokyo_night)
+    }
+
+    /// Look up a built-in theme by name.
+    pub fn builtin(name: &str) -> Option<Self> {
         match name {
-            "tokyo_night" | "Tokyo Night" | "dark" => Self::tokyo_night(),
-            "tokyo_night_light" | "Tokyo Night Light" | "light" => Self::tokyo_night_light(),
-            _ => Self::tokyo_night(),
+            "tokyo_night" | "Tokyo Night" | "dark" => Some(Self::tokyo_night()),
+            "tokyo_night_light" | "Tokyo Night Light" | "light" => {
+                Some(Self::tokyo_night_light())
+            }
+            _ => None,
+        }
+    }
+
+    /// Derive every semantic role from a terminal color palette.
+    ///
+    /// Surfaces and text levels are blends of the palette's background and
+    /// foreground; accents come from the ANSI colors (blue, green, yellow,
+    /// red, cyan, magenta), so any WezTerm color scheme maps cleanly.
+    pub fn from_palette(palette: &ColorPalette) -> Self {
+        let rgb = |c: wezterm_term::color::SrgbaTuple| {
+            let (r, g, b, _) = c.to_srgb_u8();
+            Color::new(r, g, b)
+        };
+        let ansi = |i: usize| rgb(palette.colors.0[i]);
+
+        let bg = rgb(palette.background);
+        let fg = rgb(palette.foreground);
+        let blue = ansi(4);
+        let yellow = ansi(3);
+        let bright_fg = if bg.luminance() < 0.5 { ansi(15) } else { ansi(0) };
+
+        Self {
+            name: "Auto".into(),
+
+            bg_primary: bg,
+            bg_secondary: bg.blend(fg, 0.08),
+            bg_tertiary: bg.blend(fg, 0.14),
+
+            fg_primary: fg,
+            fg_secondary: fg.blend(bg, 0.2),
+            fg_muted: fg.blend(bg, 0.5),
+            fg_bright: bright_fg.blend(fg, 0.2),
+
+            accent: blue,
+            success: ansi(2),
+            warning: yellow,
+            error: ansi(1),
+            info: ansi(6),
+
+            agent_accent: blue,
+            user_accent: ansi(2),
+            tool_accent: ansi(5),
+            block_border: bg.blend(fg, 0.25),
+            permission_accent: yellow,
+            code_bg: bg.blend(fg, 0.05),
         }
     }
+
+    /// Parse a theme file (see the module docs for the format).
+    ///
+    /// Roles not listed under `[colors]` come from `base`; `base = "auto"`
+    /// derives them from `palette`.
+    pub fn from_toml(content: &str, palette: &ColorPalette) -> anyhow::Result<Self> {
+        let table: toml::Table = content.parse()?;
+
+        let mut theme = match table.get("base").and_then(|v| v.as_str()) {
+            None => Self::tokyo_night(),
+            Some("auto") => Self::from_palette(palette),
+            Some(base) => Self::builtin(base)
+                .ok_or_else(|| anyhow::anyhow!("unknown base theme: {base}"))?,
+        };
+        if let Some(name) = table.get("name").and_then(|v| v.as_str()) {
+            theme.name = name.to_string();
+        }
+
+        if let Some(colors) = table.get("colors") {
+            let colors = colors
+                .as_table()
+                .ok_or_else(|| anyhow::anyhow!("[colors] must be a table"))?;
+            for (role, value) in colors {
+                let hex = value
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("{role}: expected a \"#RRGGBB\" string"))?;
+                let color = Color::parse_hex(hex)
+                    .ok_or_else(|| anyhow::anyhow!("{role}: invalid color {hex:?}"))?;
+                let slot = theme
+                    .role_mut(role)
+                    .ok_or_else(|| anyhow::anyhow!("unknown color role: {role}"))?;
+                *slot = color;
+            }
+        }
+        Ok(theme)
+    }
+
+    /// Resolve a theme name from `elwood.toml`: `auto`, a built-in theme, or
+    /// a file in `themes_dir`.
+    pub fn resolve(name: &str, themes_dir: &Path, palette: &ColorPalette) -> anyhow::Result<Self> {
+        if name == "auto" {
+            return Ok(Self::from_palette(palette));
+        }
+        if let Some(theme) = Self::builtin(name) {
+            return Ok(theme);
+        }
+        let path = theme_file(themes_dir, name);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("theme {name:?} ({}): {e}", path.display()))?;
+        Self::from_toml(&content, palette)
+            .map_err(|e| anyhow::anyhow!("theme {name:?} ({}): {e}", path.display()))
+    }
+
+    /// Mutable access to a color by its role name (the field name).
+    fn role_mut(&mut self, role: &str) -> Option<&mut Color> {
+        Some(match role {
+            "bg_primary" => &mut self.bg_primary,
+            "bg_secondary" => &mut self.bg_secondary,
+            "bg_tertiary" => &mut self.bg_tertiary,
+            "fg_primary" => &mut self.fg_primary,
+            "fg_secondary" => &mut self.fg_secondary,
+            "fg_muted" => &mut self.fg_muted,
+            "fg_bright" => &mut self.fg_bright,
+            "accent" => &mut self.accent,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "info" => &mut self.info,
+            "agent_accent" => &mut self.agent_accent,
+            "user_accent" => &mut self.user_accent,
+            "tool_accent" => &mut self.tool_accent,
+            "block_border" => &mut self.block_border,
+            "permission_accent" => &mut self.permission_accent,
+            "code_bg" => &mut self.code_bg,
+            _ => return None,
+        })
+    }
+}
+
+// ── Theme files ─────────────────────────────────────────────────────────
+
+/// Directory holding user theme files: `~/.elwood/themes/`.
+pub fn themes_dir() -> PathBuf {
+    dirs_next::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".elwood")
+        .join("themes")
+}
+
+/// Path of the theme file for `name` inside `dir`.
+fn theme_file(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{name}.toml"))
+}
+
+/// Names of the theme files in `dir` (without `.toml`), sorted.
+pub fn list_theme_files(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "toml" {
+                return None;
+            }
+            Some(path.file_stem()?.to_string_lossy().into_owned())
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+// ── Active theme ────────────────────────────────────────────────────────
+
+/// How often [`reload_if_changed`] looks at file modification times.
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+fn active_slot() -> &'static RwLock<Arc<ElwoodTheme>> {
+    static ACTIVE: OnceLock<RwLock<Arc<ElwoodTheme>>> = OnceLock::new();
+    ACTIVE.get_or_init(|| RwLock::new(Arc::new(ElwoodTheme::default())))
+}
+
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// The theme used for rendering.
+pub fn active() -> Arc<ElwoodTheme> {
+    Arc::clone(&active_slot().read())
+}
+
+/// Replace the active theme and bump [`generation`].
+pub fn set_active(theme: ElwoodTheme) {
+    *active_slot().write() = Arc::new(theme);
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Counter bumped on every theme change, so panes know when to redraw.
+pub fn generation() -> usize {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+/// Semantic palette roles, resolved against the [`active`] theme at render
+/// time so theme changes apply to the next frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    BgPrimary,
+    BgTertiary,
+    FgPrimary,
+    FgMuted,
+    FgBright,
+    Accent,
+    Success,
+    Warning,
+    Error,
+    Info,
+    AgentAccent,
+    UserAccent,
+    ToolAccent,
+    BlockBorder,
+}
+
+impl Role {
+    /// The role's color in the active theme.
+    pub fn color(self) -> Color {
+        let t = active();
+        match self {
+            Self::BgPrimary => t.bg_primary,
+            Self::BgTertiary => t.bg_tertiary,
+            Self::FgPrimary => t.fg_primary,
+            Self::FgMuted => t.fg_muted,
+            Self::FgBright => t.fg_bright,
+            Self::Accent => t.accent,
+            Self::Success => t.success,
+            Self::Warning => t.warning,
+            Self::Error => t.error,
+            Self::Info => t.info,
+            Self::AgentAccent => t.agent_accent,
+            Self::UserAccent => t.user_accent,
+            Self::ToolAccent => t.tool_accent,
+            Self::BlockBorder => t.block_border,
+        }
+    }
+
+    /// The role's color in the active theme as an (R, G, B) tuple.
+    pub fn rgb(self) -> (u8, u8, u8) {
+        let c = self.color();
+        (c.r, c.g, c.b)
+    }
+}
+
+/// Resolve `name` and make it the active theme.
+///
+/// On error the active theme is left unchanged and the error returned.
+pub fn apply(name: &str, palette: &ColorPalette) -> anyhow::Result<()> {
+    let theme = ElwoodTheme::resolve(name, &themes_dir(), palette)?;
+    set_active(theme);
+    Ok(())
+}
+
+/// What [`reload_if_changed`] saw on its last check.
+#[derive(Default)]
+struct Watched {
+    last_check: Option<Instant>,
+    /// Theme name from `elwood.toml`, re-read when the file changes.
+    name: String,
+    config_mtime: Option<SystemTime>,
+    theme_mtime: Option<SystemTime>,
+}
+
+impl Watched {
+    /// Record the current state at `now`, returning the theme name to apply
+    /// if `config_path` or the theme file in `themes_dir` changed since the
+    /// last check. Checks at most once per [`RELOAD_CHECK_INTERVAL`]; the
+    /// first call only records the current state.
+    fn check(&mut self, config_path: &Path, themes_dir: &Path, now: Instant) -> Option<String> {
+        let first_check = self.last_check.is_none();
+        if self
+            .last_check
+            .is_some_and(|t| now.saturating_duration_since(t) < RELOAD_CHECK_INTERVAL)
+        {
+            return None;
+        }
+        self.last_check = Some(now);
+
+        let config_mtime = mtime(config_path);
+        let config_changed = first_check || config_mtime != self.config_mtime;
+        if config_changed {
+            self.config_mtime = config_mtime;
+            self.name = crate::config::ElwoodConfig::load_from(config_path).theme;
+        }
+        let theme_mtime = mtime(&theme_file(themes_dir, &self.name));
+        let theme_changed = theme_mtime != self.theme_mtime;
+        self.theme_mtime = theme_mtime;
+
+        if first_check || !(config_changed || theme_changed) {
+            return None;
+        }
+        Some(self.name.clone())
+    }
+}
+
+fn mtime(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Re-apply the configured theme if `elwood.toml` or the theme file changed
+/// on disk since the last call. Checks at most once per second; the first
+/// call only records the current state.
+///
+/// Returns `true` if the active theme was reloaded.
+///
+/// `palette` is only called when a reload is needed.
+pub fn reload_if_changed<F>(config_path: &Path, palette: F) -> anyhow::Result<bool>
+where
+    F: FnOnce() -> ColorPalette,
+{
+    static WATCHED: OnceLock<Mutex<Watched>> = OnceLock::new();
+    let mut watched = WATCHED.get_or_init(Mutex::default).lock();
+    let Some(name) = watched.check(config_path, &themes_dir(), Instant::now()) else {
+        return Ok(false);
+    };
+    drop(watched);
+
+    apply(&name, &palette())?;
+    Ok(true)
 }
 
 impl Default for ElwoodTheme {
@@ -422,6 +786,156 @@ mod tests {
         assert_eq!(fallback.name, "Tokyo Night");
     }
 
+    #[test]
+    fn test_builtin_unknown_is_none() {
+        assert!(ElwoodTheme::builtin("tokyo_night").is_some());
+        assert!(ElwoodTheme::builtin("unknown_theme").is_none());
+    }
+
+    #[test]
+    fn test_color_parse_hex() {
+        assert_eq!(Color::parse_hex("#7AA2F7"), Some(Color::new(122, 162, 247)));
+        assert_eq!(Color::parse_hex(" 9ece6a "), Some(Color::new(158, 206, 106)));
+        assert_eq!(Color::parse_hex("#fff"), None);
+        assert_eq!(Color::parse_hex("#zzzzzz"), None);
+        assert_eq!(Color::parse_hex("#éé"), None);
+    }
+
+    // ── Palette-derived and file themes ─────────────────────────────
+
+    fn to_color(c: wezterm_term::color::SrgbaTuple) -> Color {
+        let (r, g, b, _) = c.to_srgb_u8();
+        Color::new(r, g, b)
+    }
+
+    #[test]
+    fn test_from_palette_maps_roles() {
+        let palette = ColorPalette::default();
+        let theme = ElwoodTheme::from_palette(&palette);
+        assert_eq!(theme.name, "Auto");
+        assert_eq!(theme.bg_primary, to_color(palette.background));
+        assert_eq!(theme.fg_primary, to_color(palette.foreground));
+        assert_eq!(theme.error, to_color(palette.colors.0[1]));
+        assert_eq!(theme.success, to_color(palette.colors.0[2]));
+        assert_eq!(theme.accent, to_color(palette.colors.0[4]));
+        assert_eq!(theme.agent_accent, theme.accent);
+        // Surfaces step away from the background
+        assert_ne!(theme.bg_secondary, theme.bg_primary);
+        assert_ne!(theme.fg_muted, theme.fg_primary);
+    }
+
+    #[test]
+    fn test_from_toml_overrides_base() {
+        let toml = r##"
+            name = "Custom"
+            base = "tokyo_night_light"
+
+            [colors]
+            accent = "#112233"
+        "##;
+        let theme = ElwoodTheme::from_toml(toml, &ColorPalette::default()).unwrap();
+        let light = ElwoodTheme::tokyo_night_light();
+        assert_eq!(theme.name, "Custom");
+        assert_eq!(theme.accent, Color::new(0x11, 0x22, 0x33));
+        assert_eq!(theme.bg_primary, light.bg_primary);
+    }
+
+    #[test]
+    fn test_from_toml_auto_base() {
+        let palette = ColorPalette::default();
+        let theme = ElwoodTheme::from_toml("base = \"auto\"", &palette).unwrap();
+        assert_eq!(theme.bg_primary, to_color(palette.background));
+        // No name given: keeps the base's name
+        assert_eq!(theme.name, "Auto");
+    }
+
+    #[test]
+    fn test_from_toml_errors() {
+        let palette = ColorPalette::default();
+        let err = ElwoodTheme::from_toml("[colors]\nnope = \"#000000\"", &palette).unwrap_err();
+        assert!(err.to_string().contains("unknown color role: nope"));
+        let err = ElwoodTheme::from_toml("[colors]\naccent = \"blue\"", &palette).unwrap_err();
+        assert!(err.to_string().contains("invalid color"));
+        let err = ElwoodTheme::from_toml("base = \"solarized\"", &palette).unwrap_err();
+        assert!(err.to_string().contains("unknown base theme"));
+        assert!(ElwoodTheme::from_toml("not toml [", &palette).is_err());
+    }
+
+    #[test]
+    fn test_resolve_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("mine.toml"),
+            "name = \"Mine\"\n[colors]\nerror = \"#ff0000\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "").unwrap();
+        let palette = ColorPalette::default();
+
+        let file = ElwoodTheme::resolve("mine", dir.path(), &palette).unwrap();
+        assert_eq!(file.name, "Mine");
+        assert_eq!(file.error, Color::new(255, 0, 0));
+
+        let builtin = ElwoodTheme::resolve("light", dir.path(), &palette).unwrap();
+        assert_eq!(builtin.name, "Tokyo Night Light");
+
+        let auto = ElwoodTheme::resolve("auto", dir.path(), &palette).unwrap();
+        assert_eq!(auto.name, "Auto");
+
+        let err = ElwoodTheme::resolve("missing", dir.path(), &palette).unwrap_err();
+        assert!(err.to_string().contains("missing"));
+
+        assert_eq!(list_theme_files(dir.path()), vec!["mine"]);
+    }
+
+    #[test]
+    fn test_reload_watch_detects_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("elwood.toml");
+        let themes = dir.path().join("themes");
+        std::fs::create_dir(&themes).unwrap();
+        let theme_path = themes.join("mine.toml");
+        std::fs::write(&config, "theme = \"mine\"\n").unwrap();
+        std::fs::write(&theme_path, "[colors]\naccent = \"#112233\"\n").unwrap();
+
+        // Bump a file's mtime explicitly; writes within one tick can share it
+        let touch = |path: &Path, secs: u64| {
+            let file = std::fs::File::options().write(true).open(path).unwrap();
+            file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+                .unwrap();
+        };
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut watched = Watched::default();
+
+        // The first check only records the current state
+        assert_eq!(watched.check(&config, &themes, at(0)), None);
+        assert_eq!(watched.name, "mine");
+
+        // Changes are noticed at most once per interval
+        touch(&theme_path, 1_000);
+        assert_eq!(watched.check(&config, &themes, start), None);
+        assert_eq!(watched.check(&config, &themes, at(2)), Some("mine".into()));
+        assert_eq!(watched.check(&config, &themes, at(4)), None);
+
+        // Switching themes in elwood.toml picks up the new name
+        std::fs::write(&config, "theme = \"tokyo_night_light\"\n").unwrap();
+        touch(&config, 2_000);
+        assert_eq!(
+            watched.check(&config, &themes, at(6)),
+            Some("tokyo_night_light".into())
+        );
+        assert_eq!(watched.check(&config, &themes, at(8)), None);
+    }
+
+    #[test]
+    fn test_role_follows_active_theme() {
+        let theme = active();
+        let border = theme.block_border;
+        assert_eq!(Role::Accent.color(), theme.accent);
+        assert_eq!(Role::BlockBorder.rgb(), (border.r, border.g, border.b));
+    }
+
     #[test]
     fn test_ansi_fg() {
         let theme = ElwoodTheme::default();
@@ -1,7 +1,8 @@
 //! Terminal-native tool implementations.
 //!
 //! These tools leverage WezTerm's native pane system instead of subprocess hacks.
-//! They are registered in elwood-core's tool registry when running inside WezTerm.
+//! They are registered in elwood-core's tool registry when running inside WezTerm;
+//! so far only [`NativeBashTool`] and [`JobWaitTool`] are wired into the runtime.
 //!
 //! ## Tools
 //!
@@ -11,13 +12,28 @@
 //! | `ListPanesTool` | List all panes with titles, IDs, process info |
 //! | `WatchPaneTool` | Subscribe to pane output, get notified on changes |
 //! | `SendToPaneTool` | Send keystrokes/text to another pane |
-//! | `NativeBashTool` | Execute commands in a real terminal pane |
+//! | `NativeBashTool` | Execute commands with streamed output, optionally in a visible pane |
 //! | `ProcessTreeTool` | Read process hierarchy from WezTerm's mux |
 //! | `SplitPaneTool` | Create new terminal panes from agent |
+//! | `JobWaitTool` | Wait for a background job (`/bg`) to finish and read its result |
 
+use crate::bash_stream::{self, BashChunk, HeadTailBuffer, OutputStream, ScreenTail};
+use crate::jobs::{JobId, SharedJobHandles};
 use crate::observer::{PaneInfo, PaneObserver};
+use crate::runtime::AgentResponse;
+use async_trait::async_trait;
+use config::keyassignment::SpawnTabDomain;
+use elwood_core::tools::{RiskLevel, Tool, ToolCategory, ToolResult};
+use mux::domain::SplitSource;
 use mux::pane::{CachePolicy, PaneId};
+use mux::tab::{SplitDirection, SplitRequest, SplitSize};
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 
 /// Input for ReadPaneTool.
 #[derive(Debug, Deserialize)]
@@ -122,101 +138,418 @@ pub fn watch_pane(observer: &PaneObserver, input: &WatchPaneInput) {
 
 // ─── NativeBashTool ──────────────────────────────────────────────────
 
+/// How often a command running in a pane is re-read for new output.
+const PANE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long to wait for the mux to create a visible pane.
+const SPLIT_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Input for NativeBashTool.
 #[derive(Debug, Deserialize)]
 pub struct NativeBashInput {
     /// The command to execute.
     pub command: String,
-    /// If provided, send the command to this existing pane instead of spawning new.
+    /// If provided, run the command in this existing pane.
     pub pane_id: Option<PaneId>,
-    /// Working directory for the command (only used when spawning a new pane).
+    /// Working directory for the command (ignored when `pane_id` is set).
     pub cwd: Option<String>,
     /// Timeout in seconds. Default: 120.
     #[serde(default = "default_timeout")]
     pub timeout_seconds: u64,
+    /// Run in a new split pane next to the agent so the user can watch.
+    #[serde(default)]
+    pub visible: bool,
+    /// Lines of output returned per stream, split between head and tail.
+    /// Default: 200.
+    #[serde(default = "default_max_output_lines")]
+    pub max_output_lines: usize,
 }
 
 fn default_timeout() -> u64 {
     120
 }
 
+fn default_max_output_lines() -> usize {
+    200
+}
+
 /// Output from NativeBashTool.
 #[derive(Debug, Serialize)]
 pub struct NativeBashOutput {
-    /// The pane ID where the command is running.
-    pub pane_id: PaneId,
-    /// Initial output captured from the pane (may be empty for long-running commands).
-    pub initial_output: Vec<String>,
-    /// Whether the command appears to have completed.
+    /// The pane the command ran in (`None` for headless runs).
+    pub pane_id: Option<PaneId>,
+    /// Standard output (or the pane's text), truncated to head and tail.
+    pub output: String,
+    /// Standard error. Always empty for pane runs, where it is interleaved.
+    pub stderr: String,
+    /// Exit code, when the command finished on its own.
+    pub exit_code: Option<i32>,
+    /// Whether the command ran to completion.
     pub completed: bool,
+    /// Whether the command was stopped by `AgentRequest::Cancel`.
+    pub cancelled: bool,
+    /// Whether the command was stopped by the timeout.
+    pub timed_out: bool,
+    /// Whether output was dropped from the middle.
+    pub truncated: bool,
+}
+
+/// Token cancelled by [`cancel_native_bash`]; replaced after each cancel.
+fn cancel_slot() -> &'static Mutex<CancellationToken> {
+    static SLOT: OnceLock<Mutex<CancellationToken>> = OnceLock::new();
+    SLOT.get_or_init(|| Mutex::new(CancellationToken::new()))
 }
 
-/// Execute a command in a real terminal pane.
+/// Stop every running NativeBashTool command and [`JobWaitTool`] wait.
+/// Called for `AgentRequest::Cancel`.
+pub fn cancel_native_bash() {
+    let mut token = cancel_slot().lock();
+    token.cancel();
+    *token = CancellationToken::new();
+}
+
+/// Execute a command, streaming its output while it runs.
 ///
-/// Unlike subprocess-based bash execution, this runs the command in a real PTY
-/// with full terminal emulation. Interactive programs, REPLs, and curses UIs
-/// all work naturally. The agent can observe the output via the pane observer.
+/// By default the command runs headless via `$SHELL -c` with stdout and
+/// stderr streamed separately. With `pane_id` (or `visible`, which splits a
+/// new pane off `agent_pane_id`) it is typed into a real terminal pane
+/// instead, so interactive programs work and the user watches the same
+/// process; new screen lines are streamed as stdout.
+///
+/// `on_chunk` receives output as it arrives. The command is interrupted when
+/// `cancel` or [`cancel_native_bash`] fires, or when the timeout elapses.
+/// Blocks the calling thread; must not be called from the GUI thread.
 pub fn native_bash(
     observer: &PaneObserver,
     input: &NativeBashInput,
+    agent_pane_id: Option<PaneId>,
+    cancel: &CancellationToken,
+    on_chunk: &mut dyn FnMut(&BashChunk),
 ) -> anyhow::Result<NativeBashOutput> {
-    let pane_id = if let Some(id) = input.pane_id {
-        // Send command to existing pane
-        send_to_pane(&SendToPaneInput {
-            pane_id: id,
-            text: input.command.clone(),
-            press_enter: true,
-        })?;
-        id
-    } else {
-        // No pane specified — send to first non-elwood pane, or error
-        let panes = list_panes();
-        let shell_pane = panes
-            .iter()
-            .find(|p| !p.is_dead)
-            .or(panes.first());
-
-        match shell_pane {
-            Some(p) => {
-                send_to_pane(&SendToPaneInput {
-                    pane_id: p.pane_id,
-                    text: input.command.clone(),
-                    press_enter: true,
-                })?;
-                p.pane_id
-            }
-            None => {
-                anyhow::bail!("No pane available to run command. Use ListPanesTool to find a target pane.");
+    let global = cancel_slot().lock().clone();
+    let is_cancelled = || cancel.is_cancelled() || global.is_cancelled();
+
+    let pane_id = match input.pane_id {
+        Some(id) => Some(id),
+        None if input.visible => Some(spawn_visible_pane(agent_pane_id, input.cwd.clone())?),
+        None => None,
+    };
+
+    if let Some(pane_id) = pane_id {
+        return run_in_pane(observer, pane_id, input, &is_cancelled, on_chunk);
+    }
+
+    let result = bash_stream::run_subprocess(
+        &input.command,
+        input.cwd.as_deref().map(Path::new),
+        Duration::from_secs(input.timeout_seconds),
+        input.max_output_lines,
+        &is_cancelled,
+        on_chunk,
+    )?;
+    Ok(NativeBashOutput {
+        pane_id: None,
+        output: result.stdout,
+        stderr: result.stderr,
+        exit_code: result.exit_code,
+        completed: !result.cancelled && !result.timed_out,
+        cancelled: result.cancelled,
+        timed_out: result.timed_out,
+        truncated: result.truncated,
+    })
+}
+
+/// Name the agent calls [`NativeBashTool`] by.
+pub const NATIVE_BASH_TOOL: &str = "native_bash";
+
+/// [`native_bash`] as an elwood-core tool, registered in each runtime's
+/// `ToolRegistry`.
+///
+/// Output is forwarded as [`AgentResponse::ToolOutput`] (whole lines) while
+/// the command runs; the tool result holds the truncated final output.
+pub struct NativeBashTool {
+    observer: Arc<PaneObserver>,
+    /// The agent's pane, which `visible` runs split off (`None`, or once it
+    /// is closed: the first live pane).
+    agent_pane_id: Option<PaneId>,
+    /// Directory for headless runs that don't name a `cwd`.
+    cwd: PathBuf,
+    responses: flume::Sender<AgentResponse>,
+}
+
+impl NativeBashTool {
+    pub fn new(
+        agent_pane_id: Option<PaneId>,
+        cwd: PathBuf,
+        responses: flume::Sender<AgentResponse>,
+    ) -> Self {
+        Self {
+            // Without an agent pane there is nothing to hide from the observer
+            observer: Arc::new(PaneObserver::new(agent_pane_id.unwrap_or(PaneId::MAX))),
+            agent_pane_id,
+            cwd,
+            responses,
+        }
+    }
+
+    /// Run one tool call on a blocking thread, streaming its output.
+    ///
+    /// Dropping the future (e.g. when the turn is cancelled) interrupts the
+    /// command.
+    async fn run(&self, arguments: serde_json::Value) -> anyhow::Result<NativeBashOutput> {
+        let mut input: NativeBashInput = serde_json::from_value(arguments)?;
+        if input.pane_id.is_none() && input.cwd.is_none() {
+            input.cwd = Some(self.cwd.display().to_string());
+        }
+
+        let cancel = CancellationToken::new();
+        let _interrupt_on_drop = cancel.clone().drop_guard();
+        let observer = Arc::clone(&self.observer);
+        let agent_pane_id = self.agent_pane_id;
+        let responses = self.responses.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut forward = |text: String, is_stderr: bool| {
+                let _ = responses.send(AgentResponse::ToolOutput {
+                    tool_name: NATIVE_BASH_TOOL.to_string(),
+                    text,
+                    is_stderr,
+                });
+            };
+            let mut lines = ChunkLines::default();
+            let result = native_bash(&observer, &input, agent_pane_id, &cancel, &mut |chunk| {
+                lines.push(chunk, &mut forward)
+            });
+            lines.flush(&mut forward);
+            result
+        })
+        .await?
+    }
+}
+
+#[async_trait]
+impl Tool for NativeBashTool {
+    fn name(&self) -> &str {
+        NATIVE_BASH_TOOL
+    }
+
+    fn description(&self) -> &str {
+        "Run a shell command and stream its output. Runs headless by default; \
+         set `pane_id` to type it into an existing terminal pane, or `visible` \
+         to run it in a new split the user can watch."
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Execute
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "command": { "type": "string", "description": "The command to execute." },
+                "pane_id": { "type": "integer", "description": "Run in this existing pane." },
+                "cwd": { "type": "string", "description": "Working directory (headless runs)." },
+                "timeout_seconds": { "type": "integer", "default": default_timeout() },
+                "visible": { "type": "boolean", "default": false },
+                "max_output_lines": { "type": "integer", "default": default_max_output_lines() }
+            },
+            "required": ["command"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        arguments: serde_json::Value,
+    ) -> elwood_core::error::Result<ToolResult> {
+        let output = match self.run(arguments).await {
+            Ok(output) => output,
+            Err(e) => return Ok(ToolResult::error(format!("{NATIVE_BASH_TOOL}: {e:#}"))),
+        };
+        let report = serde_json::to_string_pretty(&output).unwrap_or_default();
+        if output.completed && output.exit_code == Some(0) {
+            Ok(ToolResult::success(report))
+        } else {
+            Ok(ToolResult::error(report))
+        }
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Dangerous
+    }
+
+    fn usage_example(&self) -> &str {
+        r#"{"command": "cargo test", "visible": true}"#
+    }
+}
+
+/// Regroups streamed chunks into whole lines per stream, so each
+/// [`AgentResponse::ToolOutput`] can be drawn on its own rows.
+#[derive(Default)]
+struct ChunkLines {
+    stdout: String,
+    stderr: String,
+}
+
+impl ChunkLines {
+    fn push(&mut self, chunk: &BashChunk, emit: &mut dyn FnMut(String, bool)) {
+        let is_stderr = chunk.is_stderr();
+        let pending = if is_stderr {
+            &mut self.stderr
+        } else {
+            &mut self.stdout
+        };
+        pending.push_str(&chunk.text);
+        if let Some(end) = pending.rfind('\n') {
+            emit(pending.drain(..=end).collect(), is_stderr);
+        }
+    }
+
+    /// Emit any trailing partial lines.
+    fn flush(&mut self, emit: &mut dyn FnMut(String, bool)) {
+        for (pending, is_stderr) in [(&mut self.stdout, false), (&mut self.stderr, true)] {
+            if !pending.is_empty() {
+                pending.push('\n');
+                emit(std::mem::take(pending), is_stderr);
             }
         }
+    }
+}
+
+/// Split a shell pane off `source` (or, once it is gone, the first live pane)
+/// and return its ID.
+fn spawn_visible_pane(source: Option<PaneId>, cwd: Option<String>) -> anyhow::Result<PaneId> {
+    let panes = list_panes();
+    let live = |id: PaneId| panes.iter().any(|p| p.pane_id == id && !p.is_dead);
+    let source = source
+        .filter(|&id| live(id))
+        .or_else(|| panes.iter().find(|p| !p.is_dead).map(|p| p.pane_id))
+        .ok_or_else(|| anyhow::anyhow!("No pane available to split for a visible command"))?;
+
+    let (tx, rx) = flume::bounded(1);
+    // The split future isn't `Send`, so hop to the main thread first and
+    // spawn it there.
+    promise::spawn::spawn_into_main_thread(async move {
+        promise::spawn::spawn(async move {
+            let request = SplitRequest {
+                direction: SplitDirection::Vertical,
+                target_is_second: true,
+                top_level: false,
+                size: SplitSize::Percent(40),
+            };
+            let split_source = SplitSource::Spawn {
+                command: None,
+                command_dir: cwd,
+            };
+            let result = mux::Mux::get()
+                .split_pane(source, request, split_source, SpawnTabDomain::DefaultDomain)
+                .await
+                .map(|(pane, _size)| pane.pane_id());
+            let _ = tx.send(result);
+        })
+        .detach();
+    })
+    .detach();
+
+    rx.recv_timeout(SPLIT_TIMEOUT)
+        .map_err(|_| anyhow::anyhow!("Timed out waiting for a new pane"))?
+}
+
+/// Type the command into `pane_id` and follow the screen until the exit-code
+/// sentinel appears, the command is cancelled, or it times out.
+fn run_in_pane(
+    observer: &PaneObserver,
+    pane_id: PaneId,
+    input: &NativeBashInput,
+    is_cancelled: &dyn Fn() -> bool,
+    on_chunk: &mut dyn FnMut(&BashChunk),
+) -> anyhow::Result<NativeBashOutput> {
+    let read_lines = || {
+        PaneObserver::read_pane_now(pane_id).map(|snap| {
+            snap.lines
+                .into_iter()
+                .map(|l| l.trim_end().to_string())
+                .collect::<Vec<_>>()
+        })
     };
 
-    // Subscribe to the pane to watch output
+    let before = read_lines()
+        .ok_or_else(|| anyhow::anyhow!("Pane {} not found or not accessible", pane_id))?;
+    let mut screen = ScreenTail::new(&before);
+    let nonce = bash_stream::new_nonce();
+    send_to_pane(&SendToPaneInput {
+        pane_id,
+        text: bash_stream::wrap_command(&input.command, &nonce),
+        press_enter: true,
+    })?;
     observer.subscribe(pane_id);
 
-    // Brief pause to let output start, then read
-    std::thread::sleep(std::time::Duration::from_millis(100));
-
-    let snapshot = PaneObserver::read_pane_now(pane_id);
-    let (lines, completed) = match snapshot {
-        Some(snap) => {
-            // Heuristic: if the last non-empty line looks like a shell prompt, command is done
-            let last_line = snap.lines.iter().rev().find(|l| !l.trim().is_empty());
-            let looks_complete = last_line
-                .map(|l| l.ends_with("$ ") || l.ends_with("% ") || l.ends_with("# "))
-                .unwrap_or(false);
-            (snap.lines, looks_complete)
+    let mut output = HeadTailBuffer::with_max_lines(input.max_output_lines);
+    let timeout = Duration::from_secs(input.timeout_seconds);
+    let started = Instant::now();
+    let mut exit_code = None;
+    let mut cancelled = false;
+    let mut timed_out = false;
+
+    while exit_code.is_none() {
+        std::thread::sleep(PANE_POLL_INTERVAL);
+        let lines = read_lines()
+            .ok_or_else(|| anyhow::anyhow!("Pane {} closed while running command", pane_id))?;
+
+        for line in screen.advance(&lines) {
+            if let Some(code) = bash_stream::parse_sentinel(&line, &nonce) {
+                exit_code = Some(code);
+                break;
+            }
+            if bash_stream::contains_sentinel(&line, &nonce) {
+                // The echoed command line
+                continue;
+            }
+            let chunk = BashChunk {
+                stream: OutputStream::Stdout,
+                text: format!("{line}\n"),
+            };
+            output.push(&chunk.text);
+            on_chunk(&chunk);
+        }
+        if exit_code.is_some() {
+            break;
         }
-        None => (vec![], false),
-    };
 
+        if is_cancelled() {
+            cancelled = true;
+        } else if started.elapsed() >= timeout {
+            timed_out = true;
+        }
+        if cancelled || timed_out {
+            interrupt_pane(pane_id)?;
+            break;
+        }
+    }
+
+    let truncated = output.is_truncated();
     Ok(NativeBashOutput {
-        pane_id,
-        initial_output: lines,
-        completed,
+        pane_id: Some(pane_id),
+        output: output.finish(),
+        stderr: String::new(),
+        exit_code,
+        completed: exit_code.is_some(),
+        cancelled,
+        timed_out,
+        truncated,
     })
 }
 
+/// Send Ctrl+C to a pane's foreground process.
+fn interrupt_pane(pane_id: PaneId) -> anyhow::Result<()> {
+    let mux = mux::Mux::try_get()
+        .ok_or_else(|| anyhow::anyhow!("Mux not available"))?;
+    let pane = mux
+        .get_pane(pane_id)
+        .ok_or_else(|| anyhow::anyhow!("Pane {} not found", pane_id))?;
+    pane.writer().write_all(b"\x03")?;
+    Ok(())
+}
+
 // ─── ProcessTreeTool ─────────────────────────────────────────────────
 
 /// Process information for a single pane.
@@ -336,3 +669,235 @@ pub fn split_pane(
          or SendToPaneTool to interact with existing panes."
     )
 }
+
+// ─── JobWaitTool ─────────────────────────────────────────────────────
+
+/// Name the agent calls [`JobWaitTool`] by.
+pub const JOB_WAIT_TOOL: &str = "job_wait";
+
+/// How often a wait checks whether its job has finished.
+const JOB_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Input for JobWaitTool.
+#[derive(Debug, Deserialize)]
+pub struct JobWaitInput {
+    /// The job to wait for, as numbered by `/bg` and `/jobs`.
+    pub job_id: JobId,
+    /// Give up after this many seconds. Default: 600.
+    #[serde(default = "default_job_wait_timeout")]
+    pub timeout_seconds: u64,
+}
+
+fn default_job_wait_timeout() -> u64 {
+    600
+}
+
+/// Block the agent's turn until a background job started in its runtime
+/// finishes, then hand it the job's outcome and the tail of its log.
+///
+/// The wait ends early on [`cancel_native_bash`] or the timeout; the job
+/// itself keeps running either way.
+pub struct JobWaitTool {
+    jobs: SharedJobHandles,
+}
+
+impl JobWaitTool {
+    pub fn new(jobs: SharedJobHandles) -> Self {
+        Self { jobs }
+    }
+
+    async fn wait(&self, arguments: serde_json::Value) -> anyhow::Result<String> {
+        let input: JobWaitInput = serde_json::from_value(arguments)?;
+        let handle = self.jobs.lock().get(&input.job_id).cloned();
+        let Some(handle) = handle else {
+            let mut known: Vec<String> = self
+                .jobs
+                .lock()
+                .iter()
+                .map(|(id, h)| format!("#{id} {}", h.command()))
+                .collect();
+            known.sort();
+            if known.is_empty() {
+                anyhow::bail!("No job #{}; no background jobs were started", input.job_id);
+            }
+            anyhow::bail!("No job #{}; known jobs: {}", input.job_id, known.join(", "));
+        };
+
+        let cancel = cancel_slot().lock().clone();
+        let deadline = Instant::now() + Duration::from_secs(input.timeout_seconds);
+        loop {
+            if let Some(report) = handle.wait_report(input.job_id) {
+                return Ok(report);
+            }
+            if cancel.is_cancelled() {
+                anyhow::bail!(
+                    "Stopped waiting for job #{}; it is still running",
+                    input.job_id
+                );
+            }
+            if Instant::now() >= deadline {
+                anyhow::bail!(
+                    "Job #{} is still running after {}s",
+                    input.job_id,
+                    input.timeout_seconds
+                );
+            }
+            tokio::time::sleep(JOB_POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for JobWaitTool {
+    fn name(&self) -> &str {
+        JOB_WAIT_TOOL
+    }
+
+    fn description(&self) -> &str {
+        "Wait for a background job started with /bg to finish, then return its \
+         exit status and the end of its output. Use it when the next step \
+         depends on the job's result."
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::ReadOnly
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "job_id": { "type": "integer", "description": "The job number shown by /bg and /jobs." },
+                "timeout_seconds": { "type": "integer", "default": default_job_wait_timeout() }
+            },
+            "required": ["job_id"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        arguments: serde_json::Value,
+    ) -> elwood_core::error::Result<ToolResult> {
+        match self.wait(arguments).await {
+            Ok(report) => Ok(ToolResult::success(report)),
+            Err(e) => Ok(ToolResult::error(format!("{JOB_WAIT_TOOL}: {e:#}"))),
+        }
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Safe
+    }
+
+    fn usage_example(&self) -> &str {
+        r#"{"job_id": 3}"#
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use elwood_core::config::PermissionConfig;
+    use elwood_core::tools::ToolRegistry;
+
+    fn chunk(stream: OutputStream, text: &str) -> BashChunk {
+        BashChunk {
+            stream,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_chunk_lines_regroup() {
+        let mut lines = ChunkLines::default();
+        let mut out = Vec::new();
+        let mut emit = |text: String, is_stderr: bool| out.push((text, is_stderr));
+        lines.push(&chunk(OutputStream::Stdout, "ab"), &mut emit);
+        lines.push(&chunk(OutputStream::Stderr, "oops\n"), &mut emit);
+        lines.push(&chunk(OutputStream::Stdout, "c\nd"), &mut emit);
+        lines.flush(&mut emit);
+        assert_eq!(
+            out,
+            vec![
+                ("oops\n".to_string(), true),
+                ("abc\n".to_string(), false),
+                ("d\n".to_string(), false),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_native_bash_tool_runs_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let (tx, rx) = flume::unbounded();
+        let tool = Arc::new(NativeBashTool::new(None, dir.path().to_path_buf(), tx));
+        let mut registry = ToolRegistry::new(PermissionConfig::default());
+        registry.register(tool.clone());
+        assert_eq!(tool.name(), NATIVE_BASH_TOOL);
+
+        let output = tool
+            .run(serde_json::json!({ "command": "pwd; echo done; echo oops >&2" }))
+            .await
+            .unwrap();
+        assert!(output.completed);
+        assert_eq!(output.exit_code, Some(0));
+        assert!(output.output.contains("done"));
+        assert!(output.stderr.contains("oops"));
+
+        // Output was streamed while the command ran, in the tool's directory
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        for response in rx.try_iter() {
+            match response {
+                AgentResponse::ToolOutput {
+                    tool_name,
+                    text,
+                    is_stderr,
+                } => {
+                    assert_eq!(tool_name, NATIVE_BASH_TOOL);
+                    if is_stderr {
+                        stderr.push_str(&text);
+                    } else {
+                        stdout.push_str(&text);
+                    }
+                }
+                other => panic!("unexpected response: {other:?}"),
+            }
+        }
+        let ran_in = stdout.lines().next().unwrap();
+        assert_eq!(
+            Path::new(ran_in).canonicalize().unwrap(),
+            dir.path().canonicalize().unwrap()
+        );
+        assert!(stdout.contains("done\n"));
+        assert_eq!(stderr, "oops\n");
+
+        // Failing commands come back as tool errors, not execution errors
+        assert!(tool
+            .execute(serde_json::json!({ "command": "exit 3" }))
+            .await
+            .is_ok());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_job_wait_tool_waits_for_job() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("job.log");
+        let command = "sleep 0.3; echo built";
+        let handle = crate::jobs::spawn_job(command, None, Some(&log), |_| {}).unwrap();
+        let jobs = SharedJobHandles::default();
+        jobs.lock().insert(4, handle);
+        let tool = JobWaitTool::new(Arc::clone(&jobs));
+        assert_eq!(tool.name(), JOB_WAIT_TOOL);
+
+        let report = tool.wait(serde_json::json!({ "job_id": 4 })).await.unwrap();
+        assert!(report.contains(&format!("#4 {command}: exit 0 after ")));
+        assert!(report.contains("\nbuilt\n"));
+
+        let err = tool
+            .wait(serde_json::json!({ "job_id": 5 }))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("known jobs: #4 sleep"));
+    }
+}
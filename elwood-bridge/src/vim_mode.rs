@@ -7,12 +7,20 @@
 //!
 //! ## Modes
 //!
-//! - **Normal**: Motions (`h/l/w/b/e/0/$`), operators (`d/c/y/x/r/p`),
-//!   count prefixes (`3w`), dot repeat (`.`), `f/F` char find.
+//! - **Normal**: Motions (`h/l/w/b/e/0/$/%`), operators (`d/c/y/x/r/p`),
+//!   count prefixes (`3w`), dot repeat (`.`), `f/F` char find, text objects
+//!   (`diw`, `ca(`, `yi"`, `dap`…), `/` and `?` search with `n`/`N`.
+//! - **Registers**: `"a`–`"z` (uppercase appends), and `"+` (or `"*`) for the
+//!   system clipboard.  Yanks into `"+` emit [`VimAction::SetClipboard`] so the
+//!   host can forward them to WezTerm's clipboard; puts from `"+` read the
+//!   clipboard through the host's [`ClipboardReader`].
+//! - **Macros**: `q{a-z}` records, `q` stops, `@{a-z}` / `@@` replay via
+//!   [`VimAction::ReplayKeys`].
 //! - **Insert**: Regular typing; `Esc` or `Ctrl+[` returns to Normal.
 //! - **Visual**: Character-wise selection; motions extend selection;
 //!   `d/y/c` operate on the selection.
 //! - **Command**: `:` line — `:w` submits, `:q` clears, `:set paste`/`:set nopaste`.
+//! - **Search**: `/` (forward) or `?` (backward) line; literal match, wraps.
 //!
 //! ## Usage
 //!
@@ -25,6 +33,7 @@
 //! // Then apply the returned VimAction to the editor buffer.
 //! ```
 
+use std::collections::HashMap;
 use std::fmt;
 
 // ─── Vim State ─────────────────────────────────────────────────────────────
@@ -40,6 +49,8 @@ pub enum VimState {
     Visual,
     /// Command-line mode (`:` prefix).
     Command,
+    /// Search prompt (`/` or `?` prefix).
+    Search,
 }
 
 impl fmt::Display for VimState {
@@ -49,6 +60,7 @@ impl fmt::Display for VimState {
             VimState::Insert => write!(f, "-- INSERT --"),
             VimState::Visual => write!(f, "-- VISUAL --"),
             VimState::Command => write!(f, ":"),
+            VimState::Search => write!(f, "/"),
         }
     }
 }
@@ -105,6 +117,11 @@ pub enum VimAction {
     Backspace,
     /// A command-line command was entered (e.g., `:set paste`).
     CommandOutput(String),
+    /// Place text on the system clipboard (yank into the `"+` register).
+    SetClipboard(String),
+    /// Feed these `(key, ctrl)` pairs back through `handle_key`, applying
+    /// each resulting action before the next (macro replay).
+    ReplayKeys(Vec<(char, bool)>),
     /// Multiple actions to execute in sequence.
     Batch(Vec<VimAction>),
 }
@@ -124,6 +141,15 @@ struct RecordedCommand {
     inserted_text: Option<String>,
 }
 
+// ─── Registers ─────────────────────────────────────────────────────────────
+
+/// Contents of a named register.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Register {
+    text: String,
+    linewise: bool,
+}
+
 // ─── VimMode ───────────────────────────────────────────────────────────────
 
 /// Vim modal editing state machine.
@@ -152,10 +178,32 @@ pub struct VimMode {
     visual_anchor: Option<(usize, usize)>,
     /// Command-line buffer (for `:` mode).
     command_buffer: String,
-    /// Waiting for a character argument (after `f`, `F`, `r`).
+    /// Waiting for a character argument (after `f`, `F`, `r`, `"`, `q`, `@`,
+    /// or `i`/`a` with a pending operator).
     awaiting_char: Option<char>,
+    /// Named registers (`a`–`z` and `+`).
+    registers: HashMap<char, Register>,
+    /// Register chosen with a `"x` prefix for the next yank/delete/paste.
+    pending_register: Option<char>,
+    /// Set when the unnamed register is written while handling a key.
+    register_written: bool,
+    /// Last search pattern and whether it searched backward (`?`).
+    last_search: Option<(String, bool)>,
+    /// Direction of the search prompt currently open.
+    search_backward: bool,
+    /// Macro being recorded: target register and keys so far.
+    recording: Option<(char, Vec<(char, bool)>)>,
+    /// Recorded macros by register.
+    macros: HashMap<char, Vec<(char, bool)>>,
+    /// Register of the last replayed macro (for `@@`).
+    last_macro: Option<char>,
+    /// Reads the system clipboard when `"+` is put.
+    clipboard_reader: Option<ClipboardReader>,
 }
 
+/// Returns the system clipboard's text, if it can be read.
+pub type ClipboardReader = fn() -> Option<String>;
+
 impl VimMode {
     /// Create a new VimMode in Normal state.
     pub fn new() -> Self {
@@ -171,9 +219,24 @@ impl VimMode {
             visual_anchor: None,
             command_buffer: String::new(),
             awaiting_char: None,
+            registers: HashMap::new(),
+            pending_register: None,
+            register_written: false,
+            last_search: None,
+            search_backward: false,
+            recording: None,
+            macros: HashMap::new(),
+            last_macro: None,
+            clipboard_reader: None,
         }
     }
 
+    /// Read the system clipboard with `reader` whenever `"+` is put, so the
+    /// register follows copies made outside the editor.
+    pub fn set_clipboard_reader(&mut self, reader: ClipboardReader) {
+        self.clipboard_reader = Some(reader);
+    }
+
     /// Current vim state.
     pub fn state(&self) -> VimState {
         self.state
@@ -189,6 +252,42 @@ impl VimMode {
         &self.register
     }
 
+    /// Content of a named register (`a`–`z` or `+`), if set.
+    pub fn named_register(&self, name: char) -> Option<&str> {
+        self.registers
+            .get(&name.to_ascii_lowercase())
+            .map(|r| r.text.as_str())
+    }
+
+    /// Set a named register from outside (e.g., seed `+` with the clipboard
+    /// contents when the host receives a paste).
+    pub fn set_register(&mut self, name: char, text: impl Into<String>) {
+        let text = text.into();
+        let linewise = text.ends_with('\n');
+        self.registers.insert(
+            name.to_ascii_lowercase(),
+            Register {
+                text: text.trim_end_matches('\n').to_string(),
+                linewise,
+            },
+        );
+    }
+
+    /// The register being recorded into, if a macro recording is active.
+    pub fn recording_register(&self) -> Option<char> {
+        self.recording.as_ref().map(|(r, _)| *r)
+    }
+
+    /// The full search/command line with its prefix (`:`, `/` or `?`).
+    pub fn command_line(&self) -> String {
+        let prefix = match self.state {
+            VimState::Search if self.search_backward => '?',
+            VimState::Search => '/',
+            _ => ':',
+        };
+        format!("{prefix}{}", self.command_buffer)
+    }
+
     /// Visual mode anchor position, if in visual mode.
     pub fn visual_anchor(&self) -> Option<(usize, usize)> {
         self.visual_anchor
@@ -207,12 +306,29 @@ impl VimMode {
         cursor_row: usize,
         cursor_col: usize,
     ) -> VimAction {
-        match self.state {
+        let was_recording = self.recording.is_some();
+        let action = match self.state {
             VimState::Normal => self.handle_normal(key, ctrl, lines, cursor_row, cursor_col),
             VimState::Insert => self.handle_insert(key, ctrl),
             VimState::Visual => self.handle_visual(key, ctrl, lines, cursor_row, cursor_col),
             VimState::Command => self.handle_command(key, ctrl),
+            VimState::Search => self.handle_search(key, ctrl, lines, cursor_row, cursor_col),
+        };
+
+        // Keys that start or stop a recording are not part of the macro
+        if was_recording {
+            if let Some((_, ref mut keys)) = self.recording {
+                keys.push((key, ctrl));
+            }
+        }
+
+        // Mirror the unnamed register into the `"x` register, if one was chosen
+        if std::mem::take(&mut self.register_written) {
+            if let Some(clip) = self.commit_register() {
+                return VimAction::Batch(vec![action, clip]);
+            }
         }
+        action
     }
 
     // ─── Normal Mode ───────────────────────────────────────────────────
@@ -275,6 +391,33 @@ impl VimMode {
                 );
             }
 
+            // Text objects: diw, ca(, yi"…
+            if key == 'i' || key == 'a' {
+                self.awaiting_char = Some(key);
+                return VimAction::NoOp;
+            }
+
+            // Search as a motion: d/foo
+            if key == '/' || key == '?' {
+                self.open_search(key == '?');
+                return VimAction::ChangeMode(VimState::Search);
+            }
+
+            // d% is inclusive of the matching bracket
+            if key == '%' {
+                if let Some((row, col)) =
+                    self.resolve_motion(key, count, lines, cursor_row, cursor_col)
+                {
+                    let (sr, sc, er, ec) = normalize_range(cursor_row, cursor_col, row, col);
+                    let end =
+                        advance_n_chars(lines.get(er).map(|s| s.as_str()).unwrap_or(""), ec, 1);
+                    return self.execute_operator_range(op, sr, sc, er, end, lines);
+                }
+                self.pending_operator = None;
+                self.current_keys.clear();
+                return VimAction::NoOp;
+            }
+
             // Motion-based operator
             if let Some((target_row, target_col)) =
                 self.resolve_motion(key, count, lines, cursor_row, cursor_col)
@@ -361,9 +504,31 @@ impl VimMode {
                 self.current_keys.clear();
                 VimAction::ChangeMode(VimState::Command)
             }
+            '/' | '?' => {
+                self.open_search(key == '?');
+                self.current_keys.clear();
+                VimAction::ChangeMode(VimState::Search)
+            }
+
+            // ── Registers and macros ──────────────────────────────
+            '"' | '@' => {
+                self.awaiting_char = Some(key);
+                self.count_prefix = (count > 1).then_some(count);
+                VimAction::NoOp
+            }
+            'q' => {
+                self.current_keys.clear();
+                if let Some((reg, keys)) = self.recording.take() {
+                    self.macros.insert(reg, keys);
+                    VimAction::NoOp
+                } else {
+                    self.awaiting_char = Some('q');
+                    VimAction::NoOp
+                }
+            }
 
             // ── Motions ───────────────────────────────────────────
-            'h' | 'l' | 'w' | 'b' | 'e' | '0' | '$' | '^' | 'j' | 'k' => {
+            'h' | 'l' | 'w' | 'b' | 'e' | '0' | '$' | '^' | 'j' | 'k' | '%' | 'n' | 'N' => {
                 if let Some((row, col)) =
                     self.resolve_motion(key, count, lines, cursor_row, cursor_col)
                 {
@@ -432,7 +597,7 @@ impl VimMode {
                     // Yank deleted text
                     if let Some(line) = lines.get(cursor_row) {
                         let end = end_col.min(line.len());
-                        self.register = line[cursor_col..end].to_string();
+                        self.write_register(line[cursor_col..end].to_string());
                         self.register_linewise = false;
                     }
                     self.current_keys.clear();
@@ -455,23 +620,19 @@ impl VimMode {
             // ── Paste ─────────────────────────────────────────────
             'p' => {
                 self.current_keys.clear();
-                if self.register.is_empty() {
+                let (text, _linewise) = self.read_register();
+                if text.is_empty() {
                     VimAction::NoOp
-                } else if self.register_linewise {
-                    // Line-wise paste: insert on next line
-                    let text = self.register.clone();
-                    VimAction::PasteAfter(text)
                 } else {
-                    let text = self.register.clone();
                     VimAction::PasteAfter(text)
                 }
             }
             'P' => {
                 self.current_keys.clear();
-                if self.register.is_empty() {
+                let (text, _linewise) = self.read_register();
+                if text.is_empty() {
                     VimAction::NoOp
                 } else {
-                    let text = self.register.clone();
                     VimAction::PasteBefore(text)
                 }
             }
@@ -582,7 +743,7 @@ impl VimMode {
 
         // Motions extend the selection (move cursor, anchor stays)
         match key {
-            'h' | 'l' | 'w' | 'b' | 'e' | '0' | '$' | '^' | 'j' | 'k' => {
+            'h' | 'l' | 'w' | 'b' | 'e' | '0' | '$' | '^' | 'j' | 'k' | '%' | 'n' | 'N' => {
                 if let Some((row, col)) =
                     self.resolve_motion(key, count, lines, cursor_row, cursor_col)
                 {
@@ -694,6 +855,100 @@ impl VimMode {
         VimAction::NoOp
     }
 
+    // ─── Search Mode ───────────────────────────────────────────────────
+
+    fn open_search(&mut self, backward: bool) {
+        self.state = VimState::Search;
+        self.search_backward = backward;
+        self.command_buffer.clear();
+    }
+
+    fn handle_search(
+        &mut self,
+        key: char,
+        ctrl: bool,
+        lines: &[String],
+        cursor_row: usize,
+        cursor_col: usize,
+    ) -> VimAction {
+        // Escape cancels (and drops any operator waiting on the search)
+        if key == '\x1b' || (ctrl && key == '[') {
+            self.state = VimState::Normal;
+            self.command_buffer.clear();
+            self.current_keys.clear();
+            self.pending_operator = None;
+            return VimAction::ChangeMode(VimState::Normal);
+        }
+
+        if key == '\r' || key == '\n' {
+            self.state = VimState::Normal;
+            let pattern = std::mem::take(&mut self.command_buffer);
+            // An empty pattern repeats the previous search in the new direction
+            let pattern = if pattern.is_empty() {
+                match &self.last_search {
+                    Some((p, _)) => p.clone(),
+                    None => {
+                        self.pending_operator = None;
+                        return VimAction::ChangeMode(VimState::Normal);
+                    }
+                }
+            } else {
+                pattern
+            };
+            self.last_search = Some((pattern.clone(), self.search_backward));
+
+            let Some((row, col)) = search(
+                lines,
+                cursor_row,
+                cursor_col,
+                &pattern,
+                self.search_backward,
+                1,
+            ) else {
+                self.pending_operator = None;
+                self.current_keys.clear();
+                return VimAction::Batch(vec![
+                    VimAction::ChangeMode(VimState::Normal),
+                    VimAction::CommandOutput(format!("Pattern not found: {pattern}")),
+                ]);
+            };
+            if let Some(op) = self.pending_operator {
+                let action =
+                    self.execute_operator_motion(op, cursor_row, cursor_col, row, col, lines);
+                let mode = if op == Operator::Change {
+                    VimState::Insert
+                } else {
+                    VimState::Normal
+                };
+                return match action {
+                    VimAction::Batch(_) => action,
+                    other => VimAction::Batch(vec![VimAction::ChangeMode(mode), other]),
+                };
+            }
+            self.current_keys.clear();
+            return VimAction::Batch(vec![
+                VimAction::ChangeMode(VimState::Normal),
+                VimAction::MoveCursor { row, col },
+            ]);
+        }
+
+        if key == '\x08' || key == '\x7f' {
+            if self.command_buffer.is_empty() {
+                self.state = VimState::Normal;
+                self.current_keys.clear();
+                self.pending_operator = None;
+                return VimAction::ChangeMode(VimState::Normal);
+            }
+            self.command_buffer.pop();
+            return VimAction::NoOp;
+        }
+
+        if !key.is_control() {
+            self.command_buffer.push(key);
+        }
+        VimAction::NoOp
+    }
+
     fn execute_ex_command(&self, cmd: &str) -> VimAction {
         let cmd = cmd.trim();
         match cmd {
@@ -767,6 +1022,12 @@ impl VimMode {
                 let new_col = clamp_to_char_boundary(target_line, new_col);
                 Some((target_row, new_col))
             }
+            '%' => match_bracket(lines, row, col),
+            'n' | 'N' => {
+                let (pattern, backward) = self.last_search.as_ref()?;
+                let backward = if key == 'N' { !backward } else { *backward };
+                search(lines, row, col, pattern, backward, count)
+            }
             _ => None,
         }
     }
@@ -781,10 +1042,65 @@ impl VimMode {
         cursor_row: usize,
         cursor_col: usize,
     ) -> VimAction {
+        // `"x` selects a register for the next command (count is kept)
+        if cmd == '"' {
+            self.current_keys.clear();
+            // `"*` is the same clipboard register as `"+`
+            let target = if target == '*' { '+' } else { target };
+            if target.is_ascii_alphabetic() || target == '+' || target == '"' {
+                self.pending_register = (target != '"').then_some(target);
+            }
+            return VimAction::NoOp;
+        }
+
         let count = self.count_prefix.take().unwrap_or(1);
         let line = lines.get(cursor_row).map(|s| s.as_str()).unwrap_or("");
 
         match cmd {
+            'q' => {
+                self.current_keys.clear();
+                if target.is_ascii_alphabetic() {
+                    self.recording = Some((target.to_ascii_lowercase(), Vec::new()));
+                }
+                VimAction::NoOp
+            }
+            '@' => {
+                self.current_keys.clear();
+                let reg = if target == '@' {
+                    self.last_macro
+                } else {
+                    Some(target.to_ascii_lowercase())
+                };
+                let Some(keys) = reg.and_then(|r| self.macros.get(&r)) else {
+                    return VimAction::NoOp;
+                };
+                let mut replay = Vec::with_capacity(keys.len() * count);
+                for _ in 0..count {
+                    replay.extend_from_slice(keys);
+                }
+                self.last_macro = reg;
+                VimAction::ReplayKeys(replay)
+            }
+            'i' | 'a' => {
+                let Some(op) = self.pending_operator.take() else {
+                    self.current_keys.clear();
+                    return VimAction::NoOp;
+                };
+                self.current_keys.push(target);
+                let inner = cmd == 'i';
+                if target == 'p' {
+                    return self.execute_paragraph_object(op, inner, lines, cursor_row);
+                }
+                match text_object(lines, cursor_row, cursor_col, inner, target) {
+                    Some((sr, sc, er, ec)) => {
+                        self.execute_operator_range(op, sr, sc, er, ec, lines)
+                    }
+                    None => {
+                        self.current_keys.clear();
+                        VimAction::NoOp
+                    }
+                }
+            }
             'f' => {
                 if let Some(pos) = find_char_forward(line, cursor_col, target, count) {
                     // If there's a pending operator, execute it
@@ -847,7 +1163,7 @@ impl VimMode {
         // Yank the line(s)
         let end_row = (cursor_row + count).min(lines.len());
         let yanked: Vec<&str> = lines[cursor_row..end_row].iter().map(|s| s.as_str()).collect();
-        self.register = yanked.join("\n");
+        self.write_register(yanked.join("\n"));
         self.register_linewise = true;
 
         self.record_command(Some(op), count, self.current_keys.clone(), None);
@@ -952,7 +1268,7 @@ impl VimMode {
                 let s = start_col.min(line.len());
                 let e = end_col.min(line.len());
                 if s <= e {
-                    self.register = line[s..e].to_string();
+                    self.write_register(line[s..e].to_string());
                 }
             }
         } else {
@@ -970,7 +1286,124 @@ impl VimMode {
                     }
                 }
             }
-            self.register = result;
+            self.write_register(result);
+        }
+    }
+
+    /// Write the unnamed register; mirrored into `"x` after the key is handled.
+    fn write_register(&mut self, text: String) {
+        self.register = text;
+        self.register_written = true;
+    }
+
+    /// Copy the unnamed register into the pending `"x` register.
+    ///
+    /// Uppercase names append (`"Ayy`).  Returns a clipboard action for `"+`.
+    fn commit_register(&mut self) -> Option<VimAction> {
+        let name = self.pending_register.take()?;
+        let reg = Register {
+            text: self.register.clone(),
+            linewise: self.register_linewise,
+        };
+        if name.is_ascii_uppercase() {
+            let entry = self.registers.entry(name.to_ascii_lowercase()).or_default();
+            if !entry.text.is_empty() {
+                entry.text.push('\n');
+            }
+            entry.text.push_str(&reg.text);
+            entry.linewise |= reg.linewise;
+            return None;
+        }
+        self.registers.insert(name, reg.clone());
+        if name == '+' {
+            let mut text = reg.text;
+            if reg.linewise {
+                text.push('\n');
+            }
+            return Some(VimAction::SetClipboard(text));
+        }
+        None
+    }
+
+    /// Text (and line-wise flag) of the pending `"x` register, or the unnamed one.
+    fn read_register(&mut self) -> (String, bool) {
+        if self.pending_register == Some('+') {
+            if let Some(text) = self.clipboard_reader.and_then(|read| read()) {
+                self.set_register('+', text);
+            }
+        }
+        match self.pending_register.take() {
+            Some(name) => self
+                .registers
+                .get(&name.to_ascii_lowercase())
+                .map(|r| (r.text.clone(), r.linewise))
+                .unwrap_or_default(),
+            None => (self.register.clone(), self.register_linewise),
+        }
+    }
+
+    // ─── Text Objects ──────────────────────────────────────────────────
+
+    /// `ip` / `ap` — operate line-wise on the paragraph around `cursor_row`.
+    fn execute_paragraph_object(
+        &mut self,
+        op: Operator,
+        inner: bool,
+        lines: &[String],
+        cursor_row: usize,
+    ) -> VimAction {
+        let (first, last) = paragraph_rows(lines, cursor_row, inner);
+        self.pending_operator = None;
+        if lines.is_empty() {
+            self.current_keys.clear();
+            return VimAction::NoOp;
+        }
+        self.write_register(lines[first..=last].join("\n"));
+        self.register_linewise = true;
+        self.record_command(Some(op), 1, self.current_keys.clone(), None);
+        self.current_keys.clear();
+
+        if op == Operator::Yank {
+            return VimAction::MoveCursor { row: first, col: 0 };
+        }
+
+        // Remove whole lines, including one line break, so no blank line remains
+        let last_len = lines.get(last).map(|l| l.len()).unwrap_or(0);
+        let delete = if op == Operator::Change {
+            VimAction::DeleteRange {
+                start_row: first,
+                start_col: 0,
+                end_row: last,
+                end_col: last_len,
+            }
+        } else if last + 1 < lines.len() {
+            VimAction::DeleteRange {
+                start_row: first,
+                start_col: 0,
+                end_row: last + 1,
+                end_col: 0,
+            }
+        } else if first > 0 {
+            let prev_len = lines.get(first - 1).map(|l| l.len()).unwrap_or(0);
+            VimAction::DeleteRange {
+                start_row: first - 1,
+                start_col: prev_len,
+                end_row: last,
+                end_col: last_len,
+            }
+        } else {
+            VimAction::DeleteRange {
+                start_row: first,
+                start_col: 0,
+                end_row: last,
+                end_col: last_len,
+            }
+        };
+        if op == Operator::Change {
+            self.enter_insert();
+            VimAction::Batch(vec![delete, VimAction::ChangeMode(VimState::Insert)])
+        } else {
+            delete
         }
     }
 
@@ -1018,6 +1451,20 @@ impl VimMode {
                 if is_line_op {
                     return self.execute_line_operator(op, cmd.count, lines, cursor_row);
                 }
+                // Text object (diw, ca(, dap…)
+                let object_kind = cmd.keys[cmd.keys.len() - 2];
+                if cmd.keys.len() >= 3 && (object_kind == 'i' || object_kind == 'a') {
+                    self.last_command = Some(cmd.clone());
+                    self.pending_operator = Some(op);
+                    self.current_keys = cmd.keys[..cmd.keys.len() - 1].to_vec();
+                    return self.handle_char_argument(
+                        object_kind,
+                        motion_key,
+                        lines,
+                        cursor_row,
+                        cursor_col,
+                    );
+                }
                 // Motion-based
                 if let Some((tr, tc)) =
                     self.resolve_motion(motion_key, cmd.count, lines, cursor_row, cursor_col)
@@ -1375,6 +1822,251 @@ fn normalize_range(
     }
 }
 
+// ─── Buffer Offsets ────────────────────────────────────────────────────────
+
+/// Byte offset of (row, col) in the buffer joined with `\n`.
+fn to_offset(lines: &[String], row: usize, col: usize) -> usize {
+    lines.iter().take(row).map(|l| l.len() + 1).sum::<usize>() + col
+}
+
+/// Inverse of [`to_offset`].
+fn from_offset(lines: &[String], offset: usize) -> (usize, usize) {
+    let mut remaining = offset;
+    for (row, line) in lines.iter().enumerate() {
+        if remaining <= line.len() {
+            return (row, remaining);
+        }
+        remaining -= line.len() + 1;
+    }
+    let last = lines.len().saturating_sub(1);
+    (last, lines.get(last).map(|l| l.len()).unwrap_or(0))
+}
+
+// ─── Search and Bracket Matching ───────────────────────────────────────────
+
+/// Find the `count`-th literal occurrence of `pattern` after (or before, when
+/// `backward`) the cursor, wrapping around the buffer.
+fn search(
+    lines: &[String],
+    row: usize,
+    col: usize,
+    pattern: &str,
+    backward: bool,
+    count: usize,
+) -> Option<(usize, usize)> {
+    if pattern.is_empty() {
+        return None;
+    }
+    let text = lines.join("\n");
+    let mut pos = to_offset(lines, row, col);
+    for _ in 0..count.max(1) {
+        pos = if backward {
+            text[..pos].rfind(pattern).or_else(|| text.rfind(pattern))?
+        } else {
+            let start = advance_n_chars(&text, pos, 1);
+            text[start..]
+                .find(pattern)
+                .map(|i| start + i)
+                .or_else(|| text.find(pattern))?
+        };
+    }
+    Some(from_offset(lines, pos))
+}
+
+/// The open/close pair for a bracket or text-object alias.
+fn bracket_pair(ch: char) -> Option<(u8, u8)> {
+    match ch {
+        '(' | ')' | 'b' => Some((b'(', b')')),
+        '[' | ']' => Some((b'[', b']')),
+        '{' | '}' | 'B' => Some((b'{', b'}')),
+        '<' | '>' => Some((b'<', b'>')),
+        _ => None,
+    }
+}
+
+/// Offset of the bracket matching the one at `offset` (`%`).
+fn matching_bracket(text: &[u8], offset: usize) -> Option<usize> {
+    let ch = *text.get(offset)? as char;
+    let (open, close) = bracket_pair(ch)?;
+    let mut depth = 0usize;
+    if text[offset] == open {
+        for (i, &b) in text.iter().enumerate().skip(offset + 1) {
+            if b == open {
+                depth += 1;
+            } else if b == close {
+                if depth == 0 {
+                    return Some(i);
+                }
+                depth -= 1;
+            }
+        }
+    } else {
+        for i in (0..offset).rev() {
+            if text[i] == close {
+                depth += 1;
+            } else if text[i] == open {
+                if depth == 0 {
+                    return Some(i);
+                }
+                depth -= 1;
+            }
+        }
+    }
+    None
+}
+
+/// `%` — jump from the first bracket at or after the cursor to its match.
+fn match_bracket(lines: &[String], row: usize, col: usize) -> Option<(usize, usize)> {
+    let line = lines.get(row)?;
+    let rel = line
+        .bytes()
+        .skip(col)
+        .position(|b| matches!(b, b'(' | b')' | b'[' | b']' | b'{' | b'}'))?;
+    let text = lines.join("\n");
+    let target = matching_bracket(text.as_bytes(), to_offset(lines, row, col + rel))?;
+    Some(from_offset(lines, target))
+}
+
+// ─── Text Object Ranges ────────────────────────────────────────────────────
+
+/// Range `(start_row, start_col, end_row, end_col)` (end exclusive) of the
+/// text object `obj` at the cursor: `w`, quotes, or brackets.
+fn text_object(
+    lines: &[String],
+    row: usize,
+    col: usize,
+    inner: bool,
+    obj: char,
+) -> Option<(usize, usize, usize, usize)> {
+    match obj {
+        'w' => {
+            let (s, e) = word_object(lines.get(row)?, col, inner)?;
+            Some((row, s, row, e))
+        }
+        '"' | '\'' | '`' => {
+            let (s, e) = quote_object(lines.get(row)?, col, obj as u8, inner)?;
+            Some((row, s, row, e))
+        }
+        _ => {
+            let (open, close) = bracket_pair(obj)?;
+            let text = lines.join("\n");
+            let bytes = text.as_bytes();
+            let offset = to_offset(lines, row, col);
+
+            // Find the enclosing opener (the cursor may sit on either bracket)
+            let start = if bytes.get(offset) == Some(&open) {
+                offset
+            } else if bytes.get(offset) == Some(&close) {
+                matching_bracket(bytes, offset)?
+            } else {
+                let mut depth = 0usize;
+                let mut found = None;
+                for i in (0..offset.min(bytes.len())).rev() {
+                    if bytes[i] == close {
+                        depth += 1;
+                    } else if bytes[i] == open {
+                        if depth == 0 {
+                            found = Some(i);
+                            break;
+                        }
+                        depth -= 1;
+                    }
+                }
+                found?
+            };
+            let end = matching_bracket(bytes, start)?;
+            let (s, e) = if inner {
+                (start + 1, end)
+            } else {
+                (start, end + 1)
+            };
+            let (sr, sc) = from_offset(lines, s);
+            let (er, ec) = from_offset(lines, e);
+            Some((sr, sc, er, ec))
+        }
+    }
+}
+
+/// `iw` / `aw` column range on a single line.
+fn word_object(line: &str, col: usize, inner: bool) -> Option<(usize, usize)> {
+    let bytes = line.as_bytes();
+    if col >= bytes.len() {
+        return None;
+    }
+    let class = |b: u8| {
+        if b == b' ' || b == b'\t' {
+            0
+        } else if b.is_ascii_alphanumeric() || b == b'_' || b >= 0x80 {
+            1
+        } else {
+            2
+        }
+    };
+    let cls = class(bytes[col]);
+    let mut start = col;
+    while start > 0 && class(bytes[start - 1]) == cls {
+        start -= 1;
+    }
+    let mut end = col;
+    while end < bytes.len() && class(bytes[end]) == cls {
+        end += 1;
+    }
+    if !inner && cls != 0 {
+        // Include trailing whitespace, or leading whitespace at end of line
+        if end < bytes.len() && class(bytes[end]) == 0 {
+            while end < bytes.len() && class(bytes[end]) == 0 {
+                end += 1;
+            }
+        } else {
+            while start > 0 && class(bytes[start - 1]) == 0 {
+                start -= 1;
+            }
+        }
+    }
+    Some((start, end))
+}
+
+/// `i"` / `a"` column range on a single line.
+fn quote_object(line: &str, col: usize, quote: u8, inner: bool) -> Option<(usize, usize)> {
+    let bytes = line.as_bytes();
+    let quotes: Vec<usize> = (0..bytes.len())
+        .filter(|&i| bytes[i] == quote && (i == 0 || bytes[i - 1] != b'\\'))
+        .collect();
+    let pair = quotes
+        .chunks_exact(2)
+        .find(|p| p[0] <= col && col <= p[1])
+        .or_else(|| quotes.chunks_exact(2).find(|p| p[0] > col))?;
+    if inner {
+        Some((pair[0] + 1, pair[1]))
+    } else {
+        Some((pair[0], pair[1] + 1))
+    }
+}
+
+/// First and last rows of the paragraph at `row` (`ap` adds trailing blanks).
+fn paragraph_rows(lines: &[String], row: usize, inner: bool) -> (usize, usize) {
+    if lines.is_empty() {
+        return (0, 0);
+    }
+    let row = row.min(lines.len() - 1);
+    let blank = |r: usize| lines[r].trim().is_empty();
+    let kind = blank(row);
+    let mut first = row;
+    while first > 0 && blank(first - 1) == kind {
+        first -= 1;
+    }
+    let mut last = row;
+    while last + 1 < lines.len() && blank(last + 1) == kind {
+        last += 1;
+    }
+    if !inner && !kind {
+        while last + 1 < lines.len() && blank(last + 1) {
+            last += 1;
+        }
+    }
+    (first, last)
+}
+
 // ─── Tests ─────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -2269,6 +2961,296 @@ mod tests {
         }
     }
 
+    // ─── Registers ─────────────────────────────────────────────────────
+
+    fn keys(v: &mut VimMode, keys: &str, buf: &[String], row: usize, col: usize) -> VimAction {
+        let mut last = VimAction::NoOp;
+        for k in keys.chars() {
+            last = v.handle_key(k, false, buf, row, col);
+        }
+        last
+    }
+
+    #[test]
+    fn test_named_register_yank_and_paste() {
+        let mut v = vim();
+        let buf = lines("hello world");
+        keys(&mut v, "\"ayw", &buf, 0, 0);
+        assert_eq!(v.named_register('a'), Some("hello "));
+
+        // Unnamed register is overwritten, "a keeps its content
+        keys(&mut v, "yw", &buf, 0, 6);
+        assert_eq!(v.register(), "world");
+        let action = keys(&mut v, "\"ap", &buf, 0, 0);
+        assert_eq!(action, VimAction::PasteAfter("hello ".to_string()));
+    }
+
+    #[test]
+    fn test_uppercase_register_appends() {
+        let mut v = vim();
+        let buf = lines("one\ntwo");
+        keys(&mut v, "\"ayy", &buf, 0, 0);
+        keys(&mut v, "\"Ayy", &buf, 1, 0);
+        assert_eq!(v.named_register('a'), Some("one\ntwo"));
+    }
+
+    #[test]
+    fn test_plus_register_sets_clipboard() {
+        let mut v = vim();
+        let buf = lines("copy me");
+        let action = keys(&mut v, "\"+yy", &buf, 0, 0);
+        match action {
+            VimAction::Batch(actions) => {
+                assert!(actions.contains(&VimAction::SetClipboard("copy me\n".to_string())));
+            }
+            other => panic!("expected Batch with SetClipboard, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_plus_register_paste_uses_seeded_clipboard() {
+        let mut v = vim();
+        let buf = lines("x");
+        v.set_register('+', "from clipboard");
+        let action = keys(&mut v, "\"+P", &buf, 0, 0);
+        assert_eq!(action, VimAction::PasteBefore("from clipboard".to_string()));
+    }
+
+    #[test]
+    fn test_clipboard_register_put_reads_clipboard() {
+        let mut v = vim();
+        let buf = lines("x");
+        v.set_register('+', "stale");
+        v.set_clipboard_reader(|| Some("copied elsewhere".to_string()));
+        let action = keys(&mut v, "\"*p", &buf, 0, 0);
+        assert_eq!(
+            action,
+            VimAction::PasteAfter("copied elsewhere".to_string())
+        );
+        // Other registers don't touch the clipboard
+        v.set_register('a', "named");
+        let action = keys(&mut v, "\"ap", &buf, 0, 0);
+        assert_eq!(action, VimAction::PasteAfter("named".to_string()));
+    }
+
+    // ─── Text Objects ──────────────────────────────────────────────────
+
+    #[test]
+    fn test_diw_deletes_inner_word() {
+        let mut v = vim();
+        let buf = lines("foo bar baz");
+        let action = keys(&mut v, "diw", &buf, 0, 5);
+        assert_eq!(
+            action,
+            VimAction::DeleteRange {
+                start_row: 0,
+                start_col: 4,
+                end_row: 0,
+                end_col: 7
+            }
+        );
+        assert_eq!(v.register(), "bar");
+    }
+
+    #[test]
+    fn test_daw_includes_trailing_space() {
+        let mut v = vim();
+        let buf = lines("foo bar baz");
+        keys(&mut v, "daw", &buf, 0, 5);
+        assert_eq!(v.register(), "bar ");
+    }
+
+    #[test]
+    fn test_ci_quote() {
+        let mut v = vim();
+        let buf = lines(r#"say "hello there" now"#);
+        let action = keys(&mut v, "ci\"", &buf, 0, 8);
+        assert_eq!(v.state(), VimState::Insert);
+        assert_eq!(v.register(), "hello there");
+        assert!(matches!(action, VimAction::Batch(_)));
+    }
+
+    #[test]
+    fn test_da_paren_multiline() {
+        let mut v = vim();
+        let buf = lines("call(a,\n  b)");
+        keys(&mut v, "da(", &buf, 1, 2);
+        assert_eq!(v.register(), "(a,\n  b)");
+    }
+
+    #[test]
+    fn test_yi_paren_nested() {
+        let mut v = vim();
+        let buf = lines("f(g(x), y)");
+        keys(&mut v, "yi(", &buf, 0, 8);
+        assert_eq!(v.register(), "g(x), y");
+        keys(&mut v, "yi(", &buf, 0, 4);
+        assert_eq!(v.register(), "x");
+    }
+
+    #[test]
+    fn test_dip_deletes_paragraph_lines() {
+        let mut v = vim();
+        let buf = lines("a\nb\n\nc");
+        let action = keys(&mut v, "dip", &buf, 1, 0);
+        assert_eq!(
+            action,
+            VimAction::DeleteRange {
+                start_row: 0,
+                start_col: 0,
+                end_row: 2,
+                end_col: 0
+            }
+        );
+        assert_eq!(v.register(), "a\nb");
+    }
+
+    #[test]
+    fn test_yap_includes_trailing_blank_lines() {
+        assert_eq!(paragraph_rows(&lines("a\nb\n\n\nc"), 0, false), (0, 3));
+        assert_eq!(paragraph_rows(&lines("a\nb\n\n\nc"), 0, true), (0, 1));
+    }
+
+    #[test]
+    fn test_dot_repeats_text_object() {
+        let mut v = vim();
+        let buf = lines("foo bar");
+        keys(&mut v, "diw", &buf, 0, 0);
+        let action = v.handle_key('.', false, &buf, 0, 4);
+        assert_eq!(
+            action,
+            VimAction::DeleteRange {
+                start_row: 0,
+                start_col: 4,
+                end_row: 0,
+                end_col: 7
+            }
+        );
+    }
+
+    // ─── Search ────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_slash_search_moves_to_match() {
+        let mut v = vim();
+        let buf = lines("alpha\nbeta gamma\ngamma");
+        let action = v.handle_key('/', false, &buf, 0, 0);
+        assert_eq!(action, VimAction::ChangeMode(VimState::Search));
+        assert_eq!(v.state(), VimState::Search);
+        keys(&mut v, "gamma", &buf, 0, 0);
+        assert_eq!(v.command_line(), "/gamma");
+        let action = v.handle_key('\r', false, &buf, 0, 0);
+        assert_eq!(
+            action,
+            VimAction::Batch(vec![
+                VimAction::ChangeMode(VimState::Normal),
+                VimAction::MoveCursor { row: 1, col: 5 },
+            ])
+        );
+        // n goes to the next match, N back
+        assert_eq!(
+            v.handle_key('n', false, &buf, 1, 5),
+            VimAction::MoveCursor { row: 2, col: 0 }
+        );
+        assert_eq!(
+            v.handle_key('N', false, &buf, 2, 0),
+            VimAction::MoveCursor { row: 1, col: 5 }
+        );
+    }
+
+    #[test]
+    fn test_question_search_backward_wraps() {
+        let mut v = vim();
+        let buf = lines("x one\ntwo\none");
+        keys(&mut v, "?one\r", &buf, 1, 0);
+        assert_eq!(
+            v.handle_key('n', false, &buf, 0, 2),
+            VimAction::MoveCursor { row: 2, col: 0 }
+        );
+    }
+
+    #[test]
+    fn test_search_not_found() {
+        let mut v = vim();
+        let buf = lines("abc");
+        let action = keys(&mut v, "/zzz\r", &buf, 0, 0);
+        match action {
+            VimAction::Batch(actions) => assert!(actions.contains(&VimAction::CommandOutput(
+                "Pattern not found: zzz".to_string()
+            ))),
+            other => panic!("expected Batch, got {other:?}"),
+        }
+        assert_eq!(v.state(), VimState::Normal);
+    }
+
+    #[test]
+    fn test_delete_to_search_match() {
+        let mut v = vim();
+        let buf = lines("keep drop this");
+        keys(&mut v, "d/this\r", &buf, 0, 5);
+        assert_eq!(v.register(), "drop ");
+    }
+
+    // ─── Bracket Matching ──────────────────────────────────────────────
+
+    #[test]
+    fn test_percent_jumps_to_match() {
+        let mut v = vim();
+        let buf = lines("if (a) {\n  b[0]\n}");
+        assert_eq!(
+            v.handle_key('%', false, &buf, 0, 0),
+            VimAction::MoveCursor { row: 0, col: 5 }
+        );
+        assert_eq!(
+            v.handle_key('%', false, &buf, 0, 7),
+            VimAction::MoveCursor { row: 2, col: 0 }
+        );
+        assert_eq!(
+            v.handle_key('%', false, &buf, 2, 0),
+            VimAction::MoveCursor { row: 0, col: 7 }
+        );
+    }
+
+    #[test]
+    fn test_d_percent_is_inclusive() {
+        let mut v = vim();
+        let buf = lines("x(ab)y");
+        keys(&mut v, "d%", &buf, 0, 1);
+        assert_eq!(v.register(), "(ab)");
+    }
+
+    // ─── Macros ────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_macro_record_and_replay() {
+        let mut v = vim();
+        let buf = lines("abc");
+        keys(&mut v, "qa", &buf, 0, 0);
+        assert_eq!(v.recording_register(), Some('a'));
+        keys(&mut v, "xl", &buf, 0, 0);
+        v.handle_key('q', false, &buf, 0, 0);
+        assert_eq!(v.recording_register(), None);
+
+        let action = keys(&mut v, "2@a", &buf, 0, 0);
+        assert_eq!(
+            action,
+            VimAction::ReplayKeys(vec![('x', false), ('l', false), ('x', false), ('l', false)])
+        );
+        // @@ repeats the last macro
+        let action = keys(&mut v, "@@", &buf, 0, 0);
+        assert_eq!(
+            action,
+            VimAction::ReplayKeys(vec![('x', false), ('l', false)])
+        );
+    }
+
+    #[test]
+    fn test_replay_unknown_macro_is_noop() {
+        let mut v = vim();
+        let buf = lines("abc");
+        assert_eq!(keys(&mut v, "@z", &buf, 0, 0), VimAction::NoOp);
+    }
+
     // ─── Default impl ──────────────────────────────────────────────────
 
     #[test]